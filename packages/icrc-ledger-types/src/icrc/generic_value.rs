@@ -133,6 +133,65 @@ impl std::fmt::Display for Value {
     }
 }
 
+/// The value type of the ICRC-3 standard.
+///
+/// Unlike [Value], it has no `Nat64` variant: 64-bit naturals are represented
+/// as `Nat`. Both encodings hash to the same bytes, so converting a [Value] into
+/// an [ICRC3Value] preserves its hash.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ICRC3Value {
+    Blob(ByteBuf),
+    Text(String),
+    Nat(Nat),
+    Int(Int),
+    Array(Vec<ICRC3Value>),
+    Map(BTreeMap<String, ICRC3Value>),
+}
+
+impl ICRC3Value {
+    /// Computes the representation-independent hash of a value.
+    pub fn hash(&self) -> Hash {
+        Value::from(self.clone()).hash()
+    }
+}
+
+impl From<Value> for ICRC3Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Blob(bytes) => Self::Blob(bytes),
+            Value::Text(text) => Self::Text(text),
+            Value::Nat(nat) => Self::Nat(nat),
+            Value::Nat64(nat64) => Self::Nat(Nat::from(nat64)),
+            Value::Int(int) => Self::Int(int),
+            Value::Array(array) => Self::Array(array.into_iter().map(Self::from).collect()),
+            Value::Map(map) => {
+                Self::Map(map.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+            }
+        }
+    }
+}
+
+impl From<ICRC3Value> for Value {
+    fn from(value: ICRC3Value) -> Self {
+        match value {
+            ICRC3Value::Blob(bytes) => Self::Blob(bytes),
+            ICRC3Value::Text(text) => Self::Text(text),
+            ICRC3Value::Nat(nat) => Self::Nat(nat),
+            ICRC3Value::Int(int) => Self::Int(int),
+            ICRC3Value::Array(array) => Self::Array(array.into_iter().map(Self::from).collect()),
+            ICRC3Value::Map(map) => {
+                Self::Map(map.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ICRC3Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Value::from(self.clone()))
+    }
+}
+
 /// Encodes a 128-bit integer using unsigned LEB-128 encoding.
 /// Returns the index of the last valid byte in the buffer.
 fn leb128(buf: &mut [u8; INT128_BUF_SIZE], v: u128) -> usize {
@@ -283,3 +342,31 @@ fn test_test_vectors() {
         );
     }
 }
+
+#[test]
+fn test_icrc3_value_preserves_hash() {
+    let value = Value::map(vec![
+        ("ts", Value::Nat64(1699218263)),
+        ("amt", Value::Nat(42_u32.into())),
+        (
+            "tx",
+            Value::Array(vec![Value::Nat64(u64::MAX), Value::text("foo")]),
+        ),
+    ]);
+    let icrc3_value = ICRC3Value::from(value.clone());
+    assert_eq!(
+        icrc3_value,
+        ICRC3Value::Map(BTreeMap::from([
+            ("ts".to_string(), ICRC3Value::Nat(1699218263_u64.into())),
+            ("amt".to_string(), ICRC3Value::Nat(42_u32.into())),
+            (
+                "tx".to_string(),
+                ICRC3Value::Array(vec![
+                    ICRC3Value::Nat(u64::MAX.into()),
+                    ICRC3Value::Text("foo".to_string())
+                ])
+            ),
+        ]))
+    );
+    assert_eq!(icrc3_value.hash(), value.hash());
+}
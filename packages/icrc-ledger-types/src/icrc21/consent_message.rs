@@ -0,0 +1,551 @@
+use super::errors::{ErrorInfo, Icrc21Error};
+use super::requests::{ConsentMessageMetadata, ConsentMessageRequest, DisplayMessageType};
+use super::responses::{ConsentInfo, ConsentMessage, LineDisplayPage};
+use crate::icrc1::account::Account;
+use crate::icrc1::transfer::{Memo, TransferArg};
+use crate::icrc2::approve::ApproveArgs;
+use crate::icrc2::transfer_from::TransferFromArgs;
+use candid::{Decode, Nat, Principal};
+use time::format_description::well_known::Rfc2822;
+use time::{OffsetDateTime, UtcOffset};
+
+/// The maximum size of the argument the ledger attempts to decode.
+pub const MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES: usize = 500;
+
+/// The only language consent messages are currently available in.
+/// Requests for other languages fall back to this one, and the returned
+/// metadata reports the language the message is actually written in, as
+/// required by ICRC-21.
+const DEFAULT_LANGUAGE: &str = "en";
+
+/// The token parameters that consent messages refer to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u8,
+    pub fee: Nat,
+}
+
+/// A consent message before it is rendered for a specific device.
+struct Message {
+    title: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Message {
+    fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            fields: vec![],
+        }
+    }
+
+    fn field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
+    fn opt_field(self, name: impl Into<String>, value: Option<String>) -> Self {
+        match value {
+            Some(value) => self.field(name, value),
+            None => self,
+        }
+    }
+
+    /// Renders the message as Markdown.
+    fn to_generic_display(&self) -> String {
+        let mut message = format!("# {}", self.title);
+        for (name, value) in &self.fields {
+            message.push_str(&format!("\n\n**{}:**\n{}", name, value));
+        }
+        message
+    }
+
+    /// Splits the message into pages of at most `lines_per_page` lines of at
+    /// most `characters_per_line` characters each.
+    fn to_line_display(
+        &self,
+        characters_per_line: u16,
+        lines_per_page: u16,
+    ) -> Vec<LineDisplayPage> {
+        let width = (characters_per_line as usize).max(1);
+        let mut lines = wrap(&self.title, width);
+        for (name, value) in &self.fields {
+            lines.extend(wrap(&format!("{}:", name), width));
+            lines.extend(wrap(value, width));
+        }
+        lines
+            .chunks((lines_per_page as usize).max(1))
+            .map(|lines| LineDisplayPage {
+                lines: lines.to_vec(),
+            })
+            .collect()
+    }
+}
+
+/// Wraps the text into lines of at most `width` characters, breaking words
+/// that do not fit on a single line.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let word: Vec<char> = word.chars().collect();
+        for chunk in word.chunks(width) {
+            if !line.is_empty() && line.chars().count() + 1 + chunk.len() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.extend(chunk);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Formats an amount of token subunits as a decimal number of tokens.
+pub fn format_amount(amount: &Nat, decimals: u8) -> String {
+    let digits = amount.0.to_string();
+    let decimals = decimals as usize;
+    let (integer, fraction) = if digits.len() > decimals {
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        (integer.to_string(), fraction.to_string())
+    } else {
+        (
+            "0".to_string(),
+            format!("{:0>width$}", digits, width = decimals),
+        )
+    };
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+/// Formats a timestamp in nanoseconds since the Unix epoch in the user's time zone.
+fn format_timestamp(timestamp: u64, utc_offset_minutes: Option<i16>) -> String {
+    let offset = utc_offset_minutes
+        .and_then(|minutes| UtcOffset::from_whole_seconds(minutes as i32 * 60).ok())
+        .unwrap_or(UtcOffset::UTC);
+    OffsetDateTime::from_unix_timestamp_nanos(timestamp as i128)
+        .ok()
+        .and_then(|time| time.to_offset(offset).format(&Rfc2822).ok())
+        .unwrap_or_else(|| format!("{} nanoseconds since the Unix epoch", timestamp))
+}
+
+fn format_memo(memo: &Memo) -> String {
+    match std::str::from_utf8(memo.0.as_slice()) {
+        Ok(text) if !text.chars().any(char::is_control) => text.to_string(),
+        _ => format!("0x{}", hex::encode(memo.0.as_slice())),
+    }
+}
+
+fn unsupported_call(description: impl Into<String>) -> Icrc21Error {
+    Icrc21Error::UnsupportedCanisterCall(ErrorInfo {
+        description: description.into(),
+    })
+}
+
+/// Builds the ICRC-21 consent message for a call to `icrc1_transfer`,
+/// `icrc2_approve` or `icrc2_transfer_from` made by `caller`.
+pub fn build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+    request: ConsentMessageRequest,
+    caller: Principal,
+    token: TokenInfo,
+) -> Result<ConsentInfo, Icrc21Error> {
+    build_icrc21_consent_info_with_account_formatter(request, caller, token, |account| {
+        account.to_string()
+    })
+}
+
+/// Same as [build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints], but
+/// renders the accounts in the message with `format_account`. This allows
+/// ledgers to also show their native representation of accounts.
+pub fn build_icrc21_consent_info_with_account_formatter(
+    request: ConsentMessageRequest,
+    caller: Principal,
+    token: TokenInfo,
+    format_account: impl Fn(&Account) -> String,
+) -> Result<ConsentInfo, Icrc21Error> {
+    if request.arg.len() > MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES {
+        return Err(unsupported_call(format!(
+            "The argument size is too large. The maximum allowed size is {} bytes.",
+            MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES
+        )));
+    }
+
+    let utc_offset_minutes = request.user_preferences.metadata.utc_offset_minutes;
+    let tokens =
+        |amount: &Nat| format!("{} {}", format_amount(amount, token.decimals), token.symbol);
+    // The ledger rejects calls whose fee differs from its current fee, so a
+    // caller-supplied fee is what the user agrees to pay.
+    let fee = |arg_fee: &Option<Nat>| tokens(arg_fee.as_ref().unwrap_or(&token.fee));
+    let created_at = |created_at_time: Option<u64>| {
+        created_at_time.map(|time| format_timestamp(time, utc_offset_minutes))
+    };
+
+    let message = match request.method.as_str() {
+        "icrc1_transfer" => {
+            let arg = Decode!(request.arg.as_slice(), TransferArg).map_err(|err| {
+                unsupported_call(format!("Failed to decode TransferArg: {}", err))
+            })?;
+            let from = Account {
+                owner: caller,
+                subaccount: arg.from_subaccount,
+            };
+            Message::new(format!("Send {}", token.symbol))
+                .field("Amount", tokens(&arg.amount))
+                .field("From", format_account(&from))
+                .field("To", format_account(&arg.to))
+                .field("Fee", fee(&arg.fee))
+                .opt_field("Memo", arg.memo.as_ref().map(format_memo))
+                .opt_field("Created at", created_at(arg.created_at_time))
+        }
+        "icrc2_approve" => {
+            let arg = Decode!(request.arg.as_slice(), ApproveArgs).map_err(|err| {
+                unsupported_call(format!("Failed to decode ApproveArgs: {}", err))
+            })?;
+            let from = Account {
+                owner: caller,
+                subaccount: arg.from_subaccount,
+            };
+            let expiration = match arg.expires_at {
+                Some(expires_at) => format_timestamp(expires_at, utc_offset_minutes),
+                None => "This approval does not expire.".to_string(),
+            };
+            Message::new(format!(
+                "Allow another account to spend your {}",
+                token.symbol
+            ))
+            .field("Your account", format_account(&from))
+            .field("Spender", format_account(&arg.spender))
+            .field(
+                "Allowance",
+                format!(
+                    "{}. The allowance replaces any previous allowance of this spender.",
+                    tokens(&arg.amount)
+                ),
+            )
+            .opt_field(
+                "Expected current allowance",
+                arg.expected_allowance.as_ref().map(tokens),
+            )
+            .field("Expiration", expiration)
+            .field("Approval fee", fee(&arg.fee))
+            .opt_field("Memo", arg.memo.as_ref().map(format_memo))
+            .opt_field("Created at", created_at(arg.created_at_time))
+        }
+        "icrc2_transfer_from" => {
+            let arg = Decode!(request.arg.as_slice(), TransferFromArgs).map_err(|err| {
+                unsupported_call(format!("Failed to decode TransferFromArgs: {}", err))
+            })?;
+            let spender = Account {
+                owner: caller,
+                subaccount: arg.spender_subaccount,
+            };
+            Message::new(format!("Spend {} from an approved account", token.symbol))
+                .field("Amount", tokens(&arg.amount))
+                .field("From", format_account(&arg.from))
+                .field("Spender", format_account(&spender))
+                .field("To", format_account(&arg.to))
+                .field("Fee paid by the sending account", fee(&arg.fee))
+                .opt_field("Memo", arg.memo.as_ref().map(format_memo))
+                .opt_field("Created at", created_at(arg.created_at_time))
+        }
+        method => {
+            return Err(unsupported_call(format!(
+                "The ledger does not provide consent messages for method {}",
+                method
+            )))
+        }
+    };
+
+    let consent_message = match request.user_preferences.device_spec {
+        None | Some(DisplayMessageType::GenericDisplay) => {
+            ConsentMessage::GenericDisplayMessage(message.to_generic_display())
+        }
+        Some(DisplayMessageType::LineDisplay {
+            characters_per_line,
+            lines_per_page,
+        }) => ConsentMessage::LineDisplayMessage {
+            pages: message.to_line_display(characters_per_line, lines_per_page),
+        },
+    };
+
+    Ok(ConsentInfo {
+        consent_message,
+        metadata: ConsentMessageMetadata {
+            language: DEFAULT_LANGUAGE.to_string(),
+            utc_offset_minutes,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icrc21::requests::ConsentMessageSpec;
+    use candid::Encode;
+    use serde_bytes::ByteBuf;
+
+    fn token() -> TokenInfo {
+        TokenInfo {
+            symbol: "XTST".to_string(),
+            decimals: 8,
+            fee: Nat::from(10_000_u64),
+        }
+    }
+
+    fn request(
+        method: &str,
+        arg: Vec<u8>,
+        device_spec: Option<DisplayMessageType>,
+    ) -> ConsentMessageRequest {
+        ConsentMessageRequest {
+            method: method.to_string(),
+            arg: ByteBuf::from(arg),
+            user_preferences: ConsentMessageSpec {
+                metadata: ConsentMessageMetadata {
+                    language: "fr".to_string(),
+                    utc_offset_minutes: Some(120),
+                },
+                device_spec,
+            },
+        }
+    }
+
+    fn account(id: u8) -> Account {
+        Account {
+            owner: Principal::from_slice(&[id; 29]),
+            subaccount: None,
+        }
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(&Nat::from(0_u8), 8), "0");
+        assert_eq!(format_amount(&Nat::from(10_000_u64), 8), "0.0001");
+        assert_eq!(format_amount(&Nat::from(150_000_000_u64), 8), "1.5");
+        assert_eq!(format_amount(&Nat::from(1_000_000_000_u64), 8), "10");
+        assert_eq!(format_amount(&Nat::from(42_u8), 0), "42");
+        assert_eq!(
+            format_amount(&Nat::from(u128::MAX), 18),
+            "340282366920938463463.374607431768211455"
+        );
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("abcdefgh ij", 3), vec!["abc", "def", "gh", "ij"]);
+        assert!(wrap("", 10).is_empty());
+    }
+
+    #[test]
+    fn test_transfer_consent_message() {
+        let arg = TransferArg {
+            from_subaccount: None,
+            to: account(2),
+            fee: None,
+            created_at_time: None,
+            memo: Some(Memo::from(ByteBuf::from(b"invoice 42".to_vec()))),
+            amount: Nat::from(150_000_000_u64),
+        };
+        let info = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request("icrc1_transfer", Encode!(&arg).unwrap(), None),
+            account(1).owner,
+            token(),
+        )
+        .unwrap();
+        assert_eq!(info.metadata.language, "en");
+        assert_eq!(info.metadata.utc_offset_minutes, Some(120));
+        let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+            panic!("expected a generic display message");
+        };
+        assert!(message.starts_with("# Send XTST"));
+        assert!(message.contains("**Amount:**\n1.5 XTST"));
+        assert!(message.contains(&format!("**From:**\n{}", account(1))));
+        assert!(message.contains(&format!("**To:**\n{}", account(2))));
+        assert!(message.contains("**Fee:**\n0.0001 XTST"));
+        assert!(message.contains("**Memo:**\ninvoice 42"));
+    }
+
+    #[test]
+    fn test_approve_consent_message() {
+        let arg = ApproveArgs {
+            from_subaccount: None,
+            spender: account(2),
+            amount: Nat::from(100_000_000_u64),
+            expected_allowance: None,
+            // 2024-01-01T00:00:00Z
+            expires_at: Some(1_704_067_200_000_000_000),
+            fee: None,
+            memo: None,
+            created_at_time: None,
+        };
+        let info = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request(
+                "icrc2_approve",
+                Encode!(&arg).unwrap(),
+                Some(DisplayMessageType::GenericDisplay),
+            ),
+            account(1).owner,
+            token(),
+        )
+        .unwrap();
+        let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+            panic!("expected a generic display message");
+        };
+        assert!(message.contains("**Expiration:**\nMon, 01 Jan 2024 02:00:00 +0200"));
+        assert!(message.contains("**Approval fee:**\n0.0001 XTST"));
+        assert!(!message.contains("Memo"));
+    }
+
+    #[test]
+    fn test_consent_message_shows_caller_supplied_fee_and_created_at_time() {
+        let arg = TransferArg {
+            from_subaccount: None,
+            to: account(2),
+            fee: Some(Nat::from(20_000_u64)),
+            // 2024-01-01T00:00:00Z
+            created_at_time: Some(1_704_067_200_000_000_000),
+            memo: None,
+            amount: Nat::from(1_u8),
+        };
+        let info = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request("icrc1_transfer", Encode!(&arg).unwrap(), None),
+            account(1).owner,
+            token(),
+        )
+        .unwrap();
+        let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+            panic!("expected a generic display message");
+        };
+        assert!(message.contains("**Fee:**\n0.0002 XTST"));
+        assert!(message.contains("**Created at:**\nMon, 01 Jan 2024 02:00:00 +0200"));
+    }
+
+    #[test]
+    fn test_unsupported_language_falls_back_to_english() {
+        let arg = TransferArg {
+            from_subaccount: None,
+            to: account(2),
+            fee: None,
+            created_at_time: None,
+            memo: None,
+            amount: Nat::from(1_u8),
+        };
+        for language in ["en", "de", "zh-Hant"] {
+            let mut request = request("icrc1_transfer", Encode!(&arg).unwrap(), None);
+            request.user_preferences.metadata.language = language.to_string();
+            let info = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+                request,
+                account(1).owner,
+                token(),
+            )
+            .unwrap();
+            assert_eq!(info.metadata.language, "en");
+            let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+                panic!("expected a generic display message");
+            };
+            assert!(message.starts_with("# Send XTST"));
+        }
+    }
+
+    #[test]
+    fn test_line_display_consent_message() {
+        let arg = TransferFromArgs {
+            spender_subaccount: None,
+            from: account(1),
+            to: account(2),
+            amount: Nat::from(1_u8),
+            fee: None,
+            memo: None,
+            created_at_time: None,
+        };
+        let info = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request(
+                "icrc2_transfer_from",
+                Encode!(&arg).unwrap(),
+                Some(DisplayMessageType::LineDisplay {
+                    characters_per_line: 20,
+                    lines_per_page: 4,
+                }),
+            ),
+            account(3).owner,
+            token(),
+        )
+        .unwrap();
+        let ConsentMessage::LineDisplayMessage { pages } = info.consent_message else {
+            panic!("expected a line display message");
+        };
+        assert!(!pages.is_empty());
+        for page in &pages {
+            assert!(page.lines.len() <= 4);
+            assert!(page.lines.iter().all(|line| line.chars().count() <= 20));
+        }
+        let lines: Vec<_> = pages.into_iter().flat_map(|page| page.lines).collect();
+        assert!(lines.contains(&"0.00000001 XTST".to_string()));
+    }
+
+    #[test]
+    fn test_account_formatter() {
+        let arg = TransferArg {
+            from_subaccount: None,
+            to: account(2),
+            fee: None,
+            created_at_time: None,
+            memo: None,
+            amount: Nat::from(1_u8),
+        };
+        let info = build_icrc21_consent_info_with_account_formatter(
+            request("icrc1_transfer", Encode!(&arg).unwrap(), None),
+            account(1).owner,
+            token(),
+            |account| format!("account #{}", account.owner.as_slice()[0]),
+        )
+        .unwrap();
+        let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+            panic!("expected a generic display message");
+        };
+        assert!(message.contains("**From:**\naccount #1"));
+        assert!(message.contains("**To:**\naccount #2"));
+    }
+
+    #[test]
+    fn test_unsupported_calls() {
+        let token = token();
+        let err = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request("icrc1_balance_of", Encode!(&account(1)).unwrap(), None),
+            account(1).owner,
+            token.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Icrc21Error::UnsupportedCanisterCall(_)));
+
+        let err = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request("icrc1_transfer", Encode!(&account(1)).unwrap(), None),
+            account(1).owner,
+            token.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Icrc21Error::UnsupportedCanisterCall(_)));
+
+        let err = build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+            request(
+                "icrc1_transfer",
+                vec![0; MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES + 1],
+                None,
+            ),
+            account(1).owner,
+            token,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Icrc21Error::UnsupportedCanisterCall(_)));
+    }
+}
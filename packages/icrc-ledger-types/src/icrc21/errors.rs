@@ -0,0 +1,21 @@
+use candid::{CandidType, Deserialize, Nat};
+use serde::Serialize;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorInfo {
+    pub description: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Icrc21Error {
+    /// The canister does not provide consent messages for the requested call,
+    /// either because it does not support the method or because it could not
+    /// decode the argument.
+    UnsupportedCanisterCall(ErrorInfo),
+    ConsentMessageUnavailable(ErrorInfo),
+    InsufficientPayment(ErrorInfo),
+    GenericError {
+        error_code: Nat,
+        description: String,
+    },
+}
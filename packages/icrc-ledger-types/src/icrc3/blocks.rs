@@ -1,6 +1,9 @@
 use crate::icrc3::archive::ArchivedRange;
-use crate::icrc3::archive::QueryBlockArchiveFn;
-use crate::{icrc::generic_value::Value, icrc1::transfer::BlockIndex};
+use crate::icrc3::archive::{QueryArchiveFn, QueryBlockArchiveFn};
+use crate::{
+    icrc::generic_value::{ICRC3Value, Value},
+    icrc1::transfer::BlockIndex,
+};
 use candid::{CandidType, Deserialize, Nat};
 use serde::Serialize;
 use serde_bytes::ByteBuf;
@@ -53,3 +56,62 @@ pub struct DataCertificate {
     pub certificate: Option<serde_bytes::ByteBuf>,
     pub hash_tree: serde_bytes::ByteBuf,
 }
+
+/// The arguments of the `icrc3_get_blocks` endpoint: a list of block ranges.
+pub type GetBlocksArgs = Vec<GetBlocksRequest>;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlockWithId {
+    pub id: Nat,
+    pub block: ICRC3Value,
+}
+
+/// A callback that the caller should use to fetch the blocks in `args`.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ArchivedBlocks {
+    pub args: GetBlocksArgs,
+    pub callback: QueryArchiveFn<GetBlocksArgs, GetBlocksResult>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetBlocksResult {
+    /// The total number of blocks in the log.
+    pub log_length: Nat,
+    pub blocks: Vec<BlockWithId>,
+    pub archived_blocks: Vec<ArchivedBlocks>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ICRC3DataCertificate {
+    /// See https://internetcomputer.org/docs/current/references/ic-interface-spec#certification
+    pub certificate: ByteBuf,
+    /// CBOR encoded hash_tree
+    pub hash_tree: ByteBuf,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SupportedBlockType {
+    pub block_type: String,
+    pub url: String,
+}
+
+/// Returns the block types of the ICRC-1 and ICRC-2 standards, i.e., the
+/// block types supported by ledgers implementing ICRC-1 and ICRC-2 and by
+/// their archives.
+pub fn icrc1_and_icrc2_supported_block_types() -> Vec<SupportedBlockType> {
+    let icrc1_url = "https://github.com/dfinity/ICRC-1/blob/main/standards/ICRC-1/README.md";
+    let icrc2_url = "https://github.com/dfinity/ICRC-1/blob/main/standards/ICRC-2/README.md";
+    [
+        ("1burn", icrc1_url),
+        ("1mint", icrc1_url),
+        ("1xfer", icrc1_url),
+        ("2approve", icrc2_url),
+        ("2xfer", icrc2_url),
+    ]
+    .into_iter()
+    .map(|(block_type, url)| SupportedBlockType {
+        block_type: block_type.to_string(),
+        url: url.to_string(),
+    })
+    .collect()
+}
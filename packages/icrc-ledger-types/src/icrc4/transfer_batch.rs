@@ -0,0 +1,16 @@
+use candid::Nat;
+
+use super::super::icrc1::transfer::{TransferArg, TransferError};
+
+/// The argument of the `icrc4_transfer_batch` endpoint. Each transfer is
+/// executed on behalf of the caller, in the order of the batch.
+pub type TransferBatchArgs = Vec<TransferArg>;
+
+/// The outcome of a single transfer in a batch.
+pub type TransferBatchResult = Result<Nat, TransferError>;
+
+/// The value returned by the `icrc4_transfer_batch` endpoint. The i-th entry
+/// is the outcome of the i-th transfer of the batch, or `None` if the ledger
+/// stopped processing the batch before that transfer, e.g., because it ran
+/// out of instructions. Such transfers were not executed and can be retried.
+pub type TransferBatchResults = Vec<Option<TransferBatchResult>>;
@@ -65,12 +65,37 @@ pub struct RawCanisterCall {
     pub payload: Vec<u8>,
 }
 
+/// Identifies an ingress message submitted with `submit_call`. The effective
+/// principal determines the subnet the message was submitted to.
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq, Eq, Hash)]
+pub struct RawMessageId {
+    pub effective_principal: RawEffectivePrincipal,
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub message_id: Vec<u8>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
+pub enum RawSubmitIngressResult {
+    Ok(RawMessageId),
+    Err(UserError),
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
 pub enum RawCanisterResult {
     Ok(RawWasmResult),
     Err(UserError),
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
+pub enum RawIngressStatus {
+    Unknown,
+    Received,
+    Processing,
+    Completed(RawCanisterResult),
+    Done,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
 pub enum RawWasmResult {
     /// Raw response, returned in a "happy" case
@@ -92,6 +117,14 @@ pub struct RawSetStableMemory {
     pub blob_id: BlobId,
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
+pub struct RawImportCanister {
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub canister_id: Vec<u8>,
+    pub blob_id: BlobId,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct RawStableMemory {
     #[serde(deserialize_with = "base64::deserialize")]
@@ -218,6 +251,116 @@ impl From<RawSubnetId> for Principal {
     }
 }
 
+#[derive(
+    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub enum CanisterHttpMethod {
+    GET,
+    POST,
+    HEAD,
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub struct CanisterHttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// A canister HTTP outcall that is waiting for a response.
+#[derive(
+    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub struct RawCanisterHttpRequest {
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub subnet_id: Vec<u8>,
+    pub request_id: u64,
+    pub http_method: CanisterHttpMethod,
+    pub url: String,
+    pub headers: Vec<CanisterHttpHeader>,
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub body: Vec<u8>,
+    pub max_response_bytes: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CanisterHttpRequest {
+    pub subnet_id: SubnetId,
+    pub request_id: u64,
+    pub http_method: CanisterHttpMethod,
+    pub url: String,
+    pub headers: Vec<CanisterHttpHeader>,
+    pub body: Vec<u8>,
+    pub max_response_bytes: Option<u64>,
+}
+
+impl From<RawCanisterHttpRequest> for CanisterHttpRequest {
+    fn from(raw: RawCanisterHttpRequest) -> Self {
+        Self {
+            subnet_id: Principal::from_slice(&raw.subnet_id),
+            request_id: raw.request_id,
+            http_method: raw.http_method,
+            url: raw.url,
+            headers: raw.headers,
+            body: raw.body,
+            max_response_bytes: raw.max_response_bytes,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CanisterHttpReply {
+    pub status: u16,
+    pub headers: Vec<CanisterHttpHeader>,
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub body: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CanisterHttpReject {
+    pub reject_code: u64,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum CanisterHttpResponse {
+    CanisterHttpReply(CanisterHttpReply),
+    CanisterHttpReject(CanisterHttpReject),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RawMockCanisterHttpResponse {
+    #[serde(deserialize_with = "base64::deserialize")]
+    #[serde(serialize_with = "base64::serialize")]
+    pub subnet_id: Vec<u8>,
+    pub request_id: u64,
+    pub response: CanisterHttpResponse,
+}
+
+/// A response to a pending canister HTTP outcall. The response is delivered
+/// to the canister as is, i.e., the transform function of the request is not
+/// applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockCanisterHttpResponse {
+    pub subnet_id: SubnetId,
+    pub request_id: u64,
+    pub response: CanisterHttpResponse,
+}
+
+impl From<MockCanisterHttpResponse> for RawMockCanisterHttpResponse {
+    fn from(mock: MockCanisterHttpResponse) -> Self {
+        Self {
+            subnet_id: mock.subnet_id.as_slice().to_vec(),
+            request_id: mock.request_id,
+            response: mock.response,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct RawVerifyCanisterSigArg {
     #[serde(deserialize_with = "base64::deserialize")]
@@ -33,9 +33,11 @@
 //! For more information, see the [README](https://crates.io/crates/pocket-ic).
 //!
 use crate::common::rest::{
-    ApiResponse, BlobCompression, BlobId, CreateInstanceResponse, ExtendedSubnetConfigSet,
-    InstanceId, RawAddCycles, RawCanisterCall, RawCanisterId, RawCanisterResult, RawCycles,
-    RawEffectivePrincipal, RawSetStableMemory, RawStableMemory, RawSubnetId, RawTime,
+    ApiResponse, BlobCompression, BlobId, CanisterHttpRequest, CreateInstanceResponse,
+    ExtendedSubnetConfigSet, InstanceId, MockCanisterHttpResponse, RawAddCycles, RawCanisterCall,
+    RawCanisterHttpRequest, RawCanisterId, RawCanisterResult, RawCycles, RawEffectivePrincipal,
+    RawImportCanister, RawIngressStatus, RawMessageId, RawMockCanisterHttpResponse,
+    RawSetStableMemory, RawStableMemory, RawSubmitIngressResult, RawSubnetId, RawTime,
     RawVerifyCanisterSigArg, RawWasmResult, SubnetId, SubnetSpec, Topology,
 };
 use candid::{
@@ -287,6 +289,27 @@ impl PocketIc {
         );
     }
 
+    /// Import a canister archive produced by `state-tool export_canister` under the given
+    /// canister ID, replacing the canister's Wasm module, memories and state. Optional GZIP
+    /// compression can be used for reduced data traffic.
+    #[instrument(skip(self, archive), fields(instance_id=self.instance_id, canister_id = %canister_id.to_string(), archive_len = %archive.len(), compression = ?compression))]
+    pub fn import_canister(
+        &self,
+        canister_id: CanisterId,
+        archive: Vec<u8>,
+        compression: BlobCompression,
+    ) {
+        let blob_id = self.upload_blob(archive, compression);
+        let endpoint = "update/import_canister";
+        self.post::<(), _>(
+            endpoint,
+            RawImportCanister {
+                canister_id: canister_id.as_slice().to_vec(),
+                blob_id,
+            },
+        );
+    }
+
     /// Get stable memory of a canister.
     #[instrument(skip(self), fields(instance_id=self.instance_id, canister_id = %canister_id.to_string()))]
     pub fn get_stable_memory(&self, canister_id: CanisterId) -> Vec<u8> {
@@ -431,6 +454,78 @@ impl PocketIc {
         )
     }
 
+    /// Submit an update call to a canister without executing it.
+    /// Use `await_call` to execute the call and obtain its result, or
+    /// `ingress_status` to check whether it has completed.
+    #[instrument(skip(self, payload), fields(instance_id=self.instance_id, canister_id = %canister_id.to_string(), sender = %sender.to_string(), method = %method, payload_len = %payload.len()))]
+    pub fn submit_call(
+        &self,
+        canister_id: CanisterId,
+        sender: Principal,
+        method: &str,
+        payload: Vec<u8>,
+    ) -> Result<RawMessageId, UserError> {
+        self.submit_call_with_effective_principal(
+            canister_id,
+            RawEffectivePrincipal::None,
+            sender,
+            method,
+            payload,
+        )
+    }
+
+    /// Submit an update call with a provided effective principal to a canister
+    /// without executing it.
+    #[instrument(skip(self, payload), fields(instance_id=self.instance_id, canister_id = %canister_id.to_string(), effective_principal = ?effective_principal, sender = %sender.to_string(), method = %method, payload_len = %payload.len()))]
+    pub fn submit_call_with_effective_principal(
+        &self,
+        canister_id: CanisterId,
+        effective_principal: RawEffectivePrincipal,
+        sender: Principal,
+        method: &str,
+        payload: Vec<u8>,
+    ) -> Result<RawMessageId, UserError> {
+        let endpoint = "update/submit_ingress_message";
+        let raw_canister_call = RawCanisterCall {
+            sender: sender.as_slice().to_vec(),
+            canister_id: canister_id.as_slice().to_vec(),
+            method: method.to_string(),
+            payload,
+            effective_principal,
+        };
+        let result: RawSubmitIngressResult = self.post(endpoint, raw_canister_call);
+        match result {
+            RawSubmitIngressResult::Ok(message_id) => Ok(message_id),
+            RawSubmitIngressResult::Err(user_error) => Err(user_error),
+        }
+    }
+
+    /// Execute rounds until a previously submitted update call completes and
+    /// return its result.
+    #[instrument(skip(self), fields(instance_id=self.instance_id, message_id = %hex::encode(&message_id.message_id)))]
+    pub fn await_call(&self, message_id: RawMessageId) -> Result<WasmResult, UserError> {
+        let endpoint = "update/await_ingress_message";
+        let result: RawCanisterResult = self.post(endpoint, message_id);
+        raw_canister_result_to_result(result)
+    }
+
+    /// Return the status of a previously submitted update call, including its
+    /// result once it has completed. Does not execute any rounds.
+    #[instrument(skip(self), fields(instance_id=self.instance_id, message_id = %hex::encode(&message_id.message_id)))]
+    pub fn ingress_status(&self, message_id: RawMessageId) -> IngressStatus {
+        let endpoint = "read/ingress_status";
+        let status: RawIngressStatus = self.post(endpoint, message_id);
+        match status {
+            RawIngressStatus::Unknown => IngressStatus::Unknown,
+            RawIngressStatus::Received => IngressStatus::Received,
+            RawIngressStatus::Processing => IngressStatus::Processing,
+            RawIngressStatus::Completed(result) => {
+                IngressStatus::Completed(raw_canister_result_to_result(result))
+            }
+            RawIngressStatus::Done => IngressStatus::Done,
+        }
+    }
+
     /// Execute a query call on a canister.
     #[instrument(skip(self, payload), fields(instance_id=self.instance_id, canister_id = %canister_id.to_string(), sender = %sender.to_string(), method = %method, payload_len = %payload.len()))]
     pub fn query_call(
@@ -451,6 +546,28 @@ impl PocketIc {
         )
     }
 
+    /// Get the pending canister HTTP outcalls of all subnets.
+    /// Use `mock_canister_http_response` to respond to them.
+    #[instrument(skip(self), fields(instance_id=self.instance_id))]
+    pub fn get_canister_http(&self) -> Vec<CanisterHttpRequest> {
+        let endpoint = "read/get_canister_http";
+        let result: Vec<RawCanisterHttpRequest> = self.get(endpoint);
+        result.into_iter().map(|r| r.into()).collect()
+    }
+
+    /// Respond to a pending canister HTTP outcall with a mocked response.
+    /// As on the IC, the response is passed through the transform function of the request
+    /// and turned into a reject if it exceeds the response size limit of the request.
+    #[instrument(skip(self), fields(instance_id=self.instance_id, request_id = %mock_canister_http_response.request_id))]
+    pub fn mock_canister_http_response(
+        &self,
+        mock_canister_http_response: MockCanisterHttpResponse,
+    ) {
+        let endpoint = "update/mock_canister_http";
+        let raw: RawMockCanisterHttpResponse = mock_canister_http_response.into();
+        self.post::<(), _>(endpoint, raw);
+    }
+
     /// Create a canister with default settings as the anonymous principal.
     #[instrument(ret(Display), skip(self), fields(instance_id=self.instance_id))]
     pub fn create_canister(&self) -> CanisterId {
@@ -785,13 +902,7 @@ impl PocketIc {
         };
 
         let result: RawCanisterResult = self.post(endpoint, raw_canister_call);
-        match result {
-            RawCanisterResult::Ok(raw_wasm_result) => match raw_wasm_result {
-                RawWasmResult::Reply(data) => Ok(WasmResult::Reply(data)),
-                RawWasmResult::Reject(text) => Ok(WasmResult::Reject(text)),
-            },
-            RawCanisterResult::Err(user_error) => Err(user_error),
-        }
+        raw_canister_result_to_result(result)
     }
 
     fn update_call_with_effective_principal(
@@ -814,6 +925,16 @@ impl PocketIc {
     }
 }
 
+fn raw_canister_result_to_result(result: RawCanisterResult) -> Result<WasmResult, UserError> {
+    match result {
+        RawCanisterResult::Ok(raw_wasm_result) => match raw_wasm_result {
+            RawWasmResult::Reply(data) => Ok(WasmResult::Reply(data)),
+            RawWasmResult::Reject(text) => Ok(WasmResult::Reject(text)),
+        },
+        RawCanisterResult::Err(user_error) => Err(user_error),
+    }
+}
+
 impl Default for PocketIc {
     fn default() -> Self {
         Self::new()
@@ -1175,6 +1296,23 @@ pub enum WasmResult {
     Reject(String),
 }
 
+/// The status of an update call submitted with `submit_call`.
+#[derive(PartialOrd, Ord, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IngressStatus {
+    /// The IC has no record of the call, e.g., because no round has been
+    /// executed since the call was submitted.
+    Unknown,
+    /// The call has been inducted, but its execution has not started yet.
+    Received,
+    /// The call is being executed, e.g., it is waiting for the responses to
+    /// downstream calls.
+    Processing,
+    /// The call has completed with the given result.
+    Completed(Result<WasmResult, UserError>),
+    /// The call has completed, but its result is no longer available.
+    Done,
+}
+
 /// Attempt to start a new PocketIC server if it's not already running.
 pub fn start_or_reuse_server() -> Url {
     let bin_path = match std::env::var_os("POCKET_IC_BIN") {
@@ -8,7 +8,7 @@ use icp_ledger::{
 };
 use pocket_ic::{
     common::rest::{BlobCompression, SubnetConfigSet, SubnetKind},
-    PocketIc, PocketIcBuilder, WasmResult,
+    IngressStatus, PocketIc, PocketIcBuilder, WasmResult,
 };
 use std::{collections::HashMap, io::Read, time::SystemTime};
 
@@ -38,6 +38,111 @@ fn test_counter_canister() {
     assert_eq!(reply, WasmResult::Reply(vec![2, 0, 0, 0]));
 }
 
+#[test]
+fn test_submit_and_await_call() {
+    let pic = PocketIc::new();
+
+    let can_id = pic.create_canister();
+    pic.add_cycles(can_id, INIT_CYCLES);
+    pic.install_canister(can_id, counter_wasm(), vec![], None);
+
+    // Submitting a call does not execute it.
+    let message_id = pic
+        .submit_call(
+            can_id,
+            Principal::anonymous(),
+            "write",
+            encode_one(()).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(
+        pic.ingress_status(message_id.clone()),
+        IngressStatus::Unknown
+    );
+    let reply = call_counter_can(&pic, can_id, "read");
+    assert_eq!(reply, WasmResult::Reply(vec![0, 0, 0, 0]));
+
+    // Awaiting the call executes it.
+    let reply = pic.await_call(message_id.clone()).unwrap();
+    assert_eq!(reply, WasmResult::Reply(vec![1, 0, 0, 0]));
+    assert_eq!(
+        pic.ingress_status(message_id),
+        IngressStatus::Completed(Ok(WasmResult::Reply(vec![1, 0, 0, 0])))
+    );
+}
+
+#[test]
+fn test_submitted_call_executes_on_tick() {
+    let pic = PocketIc::new();
+
+    let can_id = pic.create_canister();
+    pic.add_cycles(can_id, INIT_CYCLES);
+    pic.install_canister(can_id, counter_wasm(), vec![], None);
+
+    let message_id = pic
+        .submit_call(
+            can_id,
+            Principal::anonymous(),
+            "write",
+            encode_one(()).unwrap(),
+        )
+        .unwrap();
+    pic.tick();
+    assert_eq!(
+        pic.ingress_status(message_id),
+        IngressStatus::Completed(Ok(WasmResult::Reply(vec![1, 0, 0, 0])))
+    );
+}
+
+#[test]
+fn test_ingress_status_of_xnet_call() {
+    let pic = PocketIcBuilder::new()
+        .with_application_subnet()
+        .with_application_subnet()
+        .build();
+
+    let subnet_id_1 = pic.topology().get_app_subnets()[0];
+    let subnet_id_2 = pic.topology().get_app_subnets()[1];
+    let canister_1 = pic.create_canister_on_subnet(None, None, subnet_id_1);
+    let canister_2 = pic.create_canister_on_subnet(None, None, subnet_id_2);
+    pic.add_cycles(canister_1, INIT_CYCLES);
+    pic.add_cycles(canister_2, INIT_CYCLES);
+    pic.install_canister(canister_1, UNIVERSAL_CANISTER_WASM.to_vec(), vec![], None);
+    pic.install_canister(canister_2, UNIVERSAL_CANISTER_WASM.to_vec(), vec![], None);
+
+    let message_id = pic
+        .submit_call(
+            canister_1,
+            Principal::anonymous(),
+            "update",
+            wasm()
+                .inter_update(
+                    canister_2,
+                    CallArgs::default().other_side(wasm().reply_data(b"pong")),
+                )
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(
+        pic.ingress_status(message_id.clone()),
+        IngressStatus::Unknown
+    );
+
+    // The call waits for the response from the other subnet after one round.
+    pic.tick();
+    assert_eq!(
+        pic.ingress_status(message_id.clone()),
+        IngressStatus::Processing
+    );
+
+    let reply = pic.await_call(message_id.clone()).unwrap();
+    assert_eq!(reply, WasmResult::Reply(b"pong".to_vec()));
+    assert_eq!(
+        pic.ingress_status(message_id),
+        IngressStatus::Completed(Ok(WasmResult::Reply(b"pong".to_vec())))
+    );
+}
+
 fn counter_wasm() -> Vec<u8> {
     let wasm_path = std::env::var_os("COUNTER_WASM").expect("Missing counter wasm file");
     std::fs::read(wasm_path).unwrap()
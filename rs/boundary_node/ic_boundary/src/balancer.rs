@@ -0,0 +1,193 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use candid::Principal;
+use clap::ValueEnum;
+use dashmap::DashMap;
+use rand::seq::SliceRandom;
+
+use crate::{persist::RouteSubnet, routes::ErrorCause, snapshot::Node};
+
+// Latency that is recorded for failed requests so that failing nodes are avoided
+const ERROR_PENALTY: Duration = Duration::from_secs(2);
+
+// Latency (in seconds) assumed for nodes that have not served any requests yet.
+// It is low so that new nodes get probed quickly.
+const UNKNOWN_LATENCY: f64 = 0.001;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RoutingStrategy {
+    /// Pick nodes uniformly at random
+    Random,
+    /// Prefer nodes with the least number of in-flight requests
+    LeastOutstanding,
+    /// Pick the better one of two random nodes based on their EWMA latency
+    /// weighted by the number of in-flight requests
+    PowerOfTwoEwma,
+}
+
+#[derive(Default)]
+struct NodeStats {
+    in_flight: AtomicU64,
+    // Bits of the f64 EWMA latency in seconds, zero if no requests were completed yet
+    latency: AtomicU64,
+}
+
+impl NodeStats {
+    fn latency(&self) -> Option<f64> {
+        let v = f64::from_bits(self.latency.load(Ordering::Relaxed));
+        (v > 0.0).then_some(v)
+    }
+
+    fn in_flight(&self) -> u64 {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    fn observe(&self, latency: f64, ewma_param: f64) {
+        // The closure always returns Some so this can't fail
+        let _ = self
+            .latency
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
+                let old = f64::from_bits(x);
+
+                let new = if old > 0.0 {
+                    old + ewma_param * (latency - old)
+                } else {
+                    latency
+                };
+
+                Some(new.to_bits())
+            });
+    }
+
+    // Expected cost of sending one more request to the node
+    fn cost(&self) -> f64 {
+        self.latency().unwrap_or(UNKNOWN_LATENCY) * (self.in_flight() + 1) as f64
+    }
+}
+
+/// Tracks an outgoing request to a node.
+/// The request is counted as in-flight until the tracker is dropped.
+pub struct RequestTracker {
+    stats: Option<Arc<NodeStats>>,
+    ewma_param: f64,
+    start: Instant,
+}
+
+impl RequestTracker {
+    /// Records the latency of the finished request.
+    /// Failed requests are recorded with at least the error penalty latency.
+    pub fn finish(self, success: bool) {
+        if let Some(stats) = &self.stats {
+            let mut latency = self.start.elapsed();
+            if !success {
+                latency = latency.max(ERROR_PENALTY);
+            }
+
+            stats.observe(latency.as_secs_f64(), self.ewma_param);
+        }
+    }
+}
+
+impl Drop for RequestTracker {
+    fn drop(&mut self) {
+        if let Some(stats) = &self.stats {
+            stats.in_flight.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Selects the nodes to route requests to according to the configured strategy
+/// and keeps the per-node statistics that the strategies need.
+pub struct Balancer {
+    strategy: RoutingStrategy,
+    ewma_param: f64,
+    // Keyed by node ID, so it's bounded by the number of nodes in the registry
+    stats: DashMap<Principal, Arc<NodeStats>>,
+}
+
+impl Balancer {
+    pub fn new(strategy: RoutingStrategy, ewma_param: f64) -> Self {
+        Self {
+            strategy,
+            ewma_param,
+            stats: DashMap::new(),
+        }
+    }
+
+    fn stats(&self, node: &Node) -> Arc<NodeStats> {
+        if let Some(v) = self.stats.get(&node.id) {
+            return v.clone();
+        }
+
+        self.stats.entry(node.id).or_default().clone()
+    }
+
+    /// Picks up to `n` distinct nodes from the subnet in the order in which they should be tried
+    pub fn pick_nodes(&self, subnet: &RouteSubnet, n: usize) -> Result<Vec<Arc<Node>>, ErrorCause> {
+        if self.strategy == RoutingStrategy::Random {
+            return subnet.pick_random_nodes(n);
+        }
+
+        if subnet.nodes.is_empty() || n == 0 {
+            return Err(ErrorCause::NoHealthyNodes);
+        }
+
+        // Take a snapshot of the stats since they're updated concurrently
+        let mut nodes = subnet
+            .nodes
+            .iter()
+            .map(|x| {
+                let stats = self.stats(x);
+                (x.clone(), stats.in_flight(), stats.cost())
+            })
+            .collect::<Vec<_>>();
+
+        // Shuffle first so that the nodes that are equally good are picked at random
+        nodes.shuffle(&mut rand::thread_rng());
+
+        match self.strategy {
+            RoutingStrategy::LeastOutstanding => {
+                nodes.sort_by_key(|x| x.1);
+            }
+
+            RoutingStrategy::PowerOfTwoEwma => {
+                // The first two nodes are random ones, so pick the better of them first.
+                // The rest are ordered by cost to be used for retries.
+                if nodes.len() > 1 && nodes[1].2 < nodes[0].2 {
+                    nodes.swap(0, 1);
+                }
+
+                nodes[1..].sort_by(|a, b| a.2.total_cmp(&b.2));
+            }
+
+            RoutingStrategy::Random => unreachable!(),
+        }
+
+        Ok(nodes.into_iter().take(n).map(|x| x.0).collect())
+    }
+
+    /// Starts tracking a request to the given node
+    pub fn track(&self, node: &Node) -> RequestTracker {
+        // Random selection doesn't need any stats
+        let stats = (self.strategy != RoutingStrategy::Random).then(|| {
+            let stats = self.stats(node);
+            stats.in_flight.fetch_add(1, Ordering::Relaxed);
+            stats
+        });
+
+        RequestTracker {
+            stats,
+            ewma_param: self.ewma_param,
+            start: Instant::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test;
@@ -0,0 +1,133 @@
+use super::*;
+
+use anyhow::Error;
+
+use crate::routes::test::test_route_subnet;
+
+#[test]
+fn test_ewma() -> Result<(), Error> {
+    let stats = NodeStats::default();
+    assert_eq!(stats.latency(), None);
+    assert_eq!(stats.cost(), UNKNOWN_LATENCY);
+
+    // First measurement is taken as is
+    stats.observe(1.0, 0.5);
+    assert_eq!(stats.latency(), Some(1.0));
+
+    stats.observe(2.0, 0.5);
+    assert_eq!(stats.latency(), Some(1.5));
+
+    stats.in_flight.fetch_add(2, Ordering::Relaxed);
+    assert_eq!(stats.cost(), 4.5);
+
+    Ok(())
+}
+
+#[test]
+fn test_tracker() -> Result<(), Error> {
+    let subnet = test_route_subnet(1);
+    let node = &subnet.nodes[0];
+
+    // Random strategy doesn't track anything
+    let balancer = Balancer::new(RoutingStrategy::Random, 0.5);
+    balancer.track(node).finish(true);
+    assert!(balancer.stats.is_empty());
+
+    let balancer = Balancer::new(RoutingStrategy::PowerOfTwoEwma, 0.5);
+    let tracker = balancer.track(node);
+    assert_eq!(balancer.stats(node).in_flight(), 1);
+    tracker.finish(false);
+
+    let stats = balancer.stats(node);
+    assert_eq!(stats.in_flight(), 0);
+    assert!(stats.latency().unwrap() >= ERROR_PENALTY.as_secs_f64());
+
+    // Dropped trackers don't record latency
+    let tracker = balancer.track(node);
+    drop(tracker);
+    assert_eq!(stats.in_flight(), 0);
+    assert!(stats.latency().unwrap() >= ERROR_PENALTY.as_secs_f64());
+
+    Ok(())
+}
+
+#[test]
+fn test_pick_nodes() -> Result<(), Error> {
+    let subnet = test_route_subnet(5);
+
+    for strategy in [
+        RoutingStrategy::Random,
+        RoutingStrategy::LeastOutstanding,
+        RoutingStrategy::PowerOfTwoEwma,
+    ] {
+        let balancer = Balancer::new(strategy, 0.5);
+
+        // Nodes are distinct and capped by the subnet size
+        let mut nodes = balancer
+            .pick_nodes(&subnet, 10)?
+            .into_iter()
+            .map(|x| x.id)
+            .collect::<Vec<_>>();
+        assert_eq!(nodes.len(), 5);
+        nodes.sort();
+        nodes.dedup();
+        assert_eq!(nodes.len(), 5);
+
+        assert_eq!(balancer.pick_nodes(&subnet, 3)?.len(), 3);
+
+        assert!(matches!(
+            balancer.pick_nodes(&test_route_subnet(0), 3),
+            Err(ErrorCause::NoHealthyNodes)
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_pick_nodes_least_outstanding() -> Result<(), Error> {
+    let subnet = test_route_subnet(3);
+    let balancer = Balancer::new(RoutingStrategy::LeastOutstanding, 0.5);
+
+    // Make nodes 0 and 2 busy
+    let _trackers = [
+        balancer.track(&subnet.nodes[0]),
+        balancer.track(&subnet.nodes[0]),
+        balancer.track(&subnet.nodes[2]),
+    ];
+
+    for _ in 0..10 {
+        let nodes = balancer.pick_nodes(&subnet, 3)?;
+        assert_eq!(nodes[0].id, subnet.nodes[1].id);
+        assert_eq!(nodes[1].id, subnet.nodes[2].id);
+        assert_eq!(nodes[2].id, subnet.nodes[0].id);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_pick_nodes_power_of_two_ewma() -> Result<(), Error> {
+    let subnet = test_route_subnet(2);
+    let balancer = Balancer::new(RoutingStrategy::PowerOfTwoEwma, 0.5);
+
+    balancer.stats(&subnet.nodes[0]).observe(1.0, 0.5);
+    balancer.stats(&subnet.nodes[1]).observe(0.1, 0.5);
+
+    // With two nodes both are always compared, so the faster one goes first
+    for _ in 0..10 {
+        let nodes = balancer.pick_nodes(&subnet, 2)?;
+        assert_eq!(nodes[0].id, subnet.nodes[1].id);
+        assert_eq!(nodes[1].id, subnet.nodes[0].id);
+    }
+
+    // The faster node becomes worse when loaded with enough requests
+    let _trackers = (0..10)
+        .map(|_| balancer.track(&subnet.nodes[1]))
+        .collect::<Vec<_>>();
+
+    let nodes = balancer.pick_nodes(&subnet, 1)?;
+    assert_eq!(nodes[0].id, subnet.nodes[0].id);
+
+    Ok(())
+}
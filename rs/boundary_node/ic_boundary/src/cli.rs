@@ -3,7 +3,10 @@ use std::{net::SocketAddr, path::PathBuf};
 use clap::{Args, Parser};
 use url::Url;
 
-use crate::core::{AUTHOR_NAME, SERVICE_NAME};
+use crate::{
+    balancer::RoutingStrategy,
+    core::{AUTHOR_NAME, SERVICE_NAME},
+};
 
 #[derive(Parser)]
 #[clap(name = SERVICE_NAME)]
@@ -36,6 +39,9 @@ pub struct Cli {
 
     #[command(flatten, next_help_heading = "retry")]
     pub retry: RetryConfig,
+
+    #[command(flatten, next_help_heading = "routing")]
+    pub routing: RoutingConfig,
 }
 
 #[derive(Args)]
@@ -207,6 +213,14 @@ pub struct RateLimitingConfig {
     /// Allowed number of ledger transfer calls per second
     #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
     pub rate_limit_ledger_transfer: Option<u32>,
+    /// Path to a JSON file with rate limiting rules.
+    /// Each rule matches requests by canister ID, method name, request type, sender and client IP range,
+    /// and allows a given number of requests per second. Only the first matching rule applies.
+    #[clap(long)]
+    pub rate_limit_generic_file: Option<PathBuf>,
+    /// How frequently to reload the rate limiting rules file, in seconds
+    #[clap(long, default_value = "10")]
+    pub rate_limit_generic_reload_interval: u64,
 }
 
 #[derive(Args)]
@@ -237,3 +251,28 @@ pub struct RetryConfig {
     #[clap(long, default_value = "false")]
     pub retry_update_call: bool,
 }
+
+#[derive(Args)]
+pub struct RoutingConfig {
+    /// How to select the replicas in a subnet to send requests to.
+    /// Retries go to the next replicas in the selected order.
+    #[clap(long, value_enum, default_value = "random")]
+    pub routing_strategy: RoutingStrategy,
+
+    /// Exponential Weighted Moving Average parameter for replica latency tracking.
+    /// Value of 0.1 means that the next measurement would account for 10% of moving average.
+    /// Should be in range 0..1.
+    #[clap(long, default_value = "0.1", value_parser = parse_ewma_param)]
+    pub routing_ewma_param: f64,
+}
+
+fn parse_ewma_param(s: &str) -> Result<f64, String> {
+    let param: f64 = s.parse().map_err(|err| format!("{err}"))?;
+    if !(0.0..=1.0).contains(&param) {
+        return Err(format!("{param} is not in range 0.0..=1.0"));
+    }
+    Ok(param)
+}
+
+#[cfg(test)]
+pub mod test;
@@ -0,0 +1,28 @@
+use super::*;
+
+fn parse_cli(args: &[&str]) -> Result<Cli, clap::Error> {
+    let mut argv = vec!["", "--local-store-path", "/tmp"];
+    #[cfg(feature = "tls")]
+    argv.extend(["--hostname", "foobar"]);
+    argv.extend(args);
+    Cli::try_parse_from(argv)
+}
+
+#[test]
+fn test_routing_ewma_param() {
+    let cli = parse_cli(&[]).unwrap();
+    assert_eq!(cli.routing.routing_ewma_param, 0.1);
+
+    for value in ["0", "0.5", "1"] {
+        let cli = parse_cli(&["--routing-ewma-param", value]).unwrap();
+        assert_eq!(
+            cli.routing.routing_ewma_param,
+            value.parse::<f64>().unwrap()
+        );
+    }
+
+    for value in ["-0.1", "1.1", "NaN", "foo"] {
+        let err = parse_cli(&["--routing-ewma-param", value]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+}
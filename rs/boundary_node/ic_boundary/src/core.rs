@@ -36,6 +36,7 @@ use tower_http::{compression::CompressionLayer, request_id::MakeRequestUuid, Ser
 use tracing::{info, warn};
 
 use crate::{
+    balancer::Balancer,
     cache::{cache_middleware, Cache},
     check::{Checker, Runner as CheckRunner},
     cli::Cli,
@@ -49,7 +50,10 @@ use crate::{
         WithMetricsCheck, WithMetricsPersist, WithMetricsSnapshot,
     },
     persist::{Persister, Routes},
-    rate_limiting::RateLimit,
+    rate_limiting::{
+        generic::{self, GenericLimiter, RulesReloader},
+        RateLimit,
+    },
     retry::{retry_request, RetryParams},
     routes::{self, ErrorCause, Health, Lookup, Proxy, ProxyRouter, RootKey},
     snapshot::{RegistrySnapshot, SnapshotPersister, Snapshotter},
@@ -170,6 +174,19 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
         )
     });
 
+    // Generic rate limiting
+    let generic_limiter = match &cli.rate_limiting.rate_limit_generic_file {
+        Some(v) => {
+            let limiter = Arc::new(GenericLimiter::new(v.clone(), &metrics_registry));
+            limiter
+                .load_rules()
+                .await
+                .context("unable to load rate limiting rules")?;
+            Some(limiter)
+        }
+        None => None,
+    };
+
     // Server / API
     let routers_https = setup_router(
         registry_snapshot.clone(),
@@ -178,6 +195,7 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
         &cli,
         &metrics_registry,
         cache.clone(),
+        generic_limiter.clone(),
     );
 
     #[cfg(feature = "tls")]
@@ -323,7 +341,7 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
     );
 
     // Runners
-    let runners: Vec<Box<dyn Run>> = vec![
+    let mut runners: Vec<Box<dyn Run>> = vec![
         #[cfg(feature = "tls")]
         Box::new(configuration_runner),
         Box::new(snapshot_runner),
@@ -331,6 +349,20 @@ pub async fn main(cli: Cli) -> Result<(), Error> {
         Box::new(metrics_runner),
     ];
 
+    if let Some(v) = generic_limiter {
+        let rules_runner = WithMetrics(
+            RulesReloader(v),
+            MetricParams::new(&metrics_registry, "run_rate_limit_rules_reload"),
+        );
+
+        runners.push(Box::new(WithThrottle(
+            rules_runner,
+            ThrottleParams::new(Duration::from_secs(
+                cli.rate_limiting.rate_limit_generic_reload_interval,
+            )),
+        )));
+    }
+
     let (registry_replicator, nns_pub_key) = if !cli.registry.disable_registry_replicator {
         // Check if we require an NNS key
         let nns_pub_key = {
@@ -432,11 +464,18 @@ pub fn setup_router(
     cli: &Cli,
     metrics_registry: &Registry,
     cache: Option<Arc<Cache>>,
+    generic_limiter: Option<Arc<GenericLimiter>>,
 ) -> Router {
+    let balancer = Arc::new(Balancer::new(
+        cli.routing.routing_strategy,
+        cli.routing.routing_ewma_param,
+    ));
+
     let proxy_router = ProxyRouter::new(
         http_client.clone(),
         Arc::clone(&routing_table),
         Arc::clone(&registry_snapshot),
+        Arc::clone(&balancer),
     );
 
     let proxy_router = Arc::new(proxy_router);
@@ -546,6 +585,9 @@ pub fn setup_router(
                     )
                 }),
             ))
+            .layer(option_layer(generic_limiter.map(|x| {
+                middleware::from_fn_with_state(x, generic::generic_ratelimit_mw)
+            })))
             .layer(middleware::from_fn_with_state(
                 lookup.clone(),
                 routes::lookup_subnet,
@@ -554,6 +596,7 @@ pub fn setup_router(
                 RetryParams {
                     retry_count: cli.retry.retry_count as usize,
                     retry_update_call: cli.retry.retry_update_call,
+                    balancer,
                 },
                 retry_request,
             )),
@@ -1,4 +1,5 @@
 mod acme;
+mod balancer;
 mod cache;
 mod check;
 mod cli;
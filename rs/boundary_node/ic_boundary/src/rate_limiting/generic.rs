@@ -0,0 +1,270 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context, Error};
+use arc_swap::ArcSwap;
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    middleware::Next,
+    response::IntoResponse,
+    Extension,
+};
+use candid::Principal;
+use http::Request;
+use ic_types::CanisterId;
+use ipnet::IpNet;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use ratelimit::Ratelimiter;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    core::Run,
+    routes::{ApiError, ErrorCause, RateLimitCause, RequestContext, RequestType},
+};
+
+/// Rate limiting rule as it is defined in the rules file.
+/// All of the specified conditions must match for the rule to apply.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    /// Name of the rule, reported in the errors and metrics
+    pub name: String,
+    /// Canister that the request is routed to
+    pub canister_id: Option<String>,
+    /// Regex that the method name should match
+    pub methods_regex: Option<String>,
+    /// Request types to match
+    pub request_types: Option<Vec<RequestType>>,
+    /// Principal of the caller
+    pub sender: Option<String>,
+    /// IP range of the client in CIDR notation
+    pub ip: Option<String>,
+    /// Allowed number of requests per second, 0 blocks all matching requests
+    pub limit: u32,
+}
+
+struct Rule {
+    config: RuleConfig,
+    canister_id: Option<Principal>,
+    methods_regex: Option<Regex>,
+    sender: Option<Principal>,
+    ip: Option<IpNet>,
+    // None if all requests are blocked
+    limiter: Option<Ratelimiter>,
+}
+
+impl TryFrom<RuleConfig> for Rule {
+    type Error = Error;
+
+    fn try_from(config: RuleConfig) -> Result<Self, Self::Error> {
+        let canister_id = config
+            .canister_id
+            .as_deref()
+            .map(Principal::from_text)
+            .transpose()
+            .context("unable to parse canister_id")?;
+
+        let methods_regex = config
+            .methods_regex
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .context("unable to parse methods_regex")?;
+
+        let sender = config
+            .sender
+            .as_deref()
+            .map(Principal::from_text)
+            .transpose()
+            .context("unable to parse sender")?;
+
+        let ip = config
+            .ip
+            .as_deref()
+            .map(IpNet::from_str)
+            .transpose()
+            .context("unable to parse ip")?;
+
+        let limiter = if config.limit == 0 {
+            None
+        } else {
+            let interval = Duration::from_secs(1).checked_div(config.limit).unwrap();
+
+            Some(
+                Ratelimiter::builder(1, interval)
+                    .max_tokens(config.limit as u64)
+                    .initial_available(config.limit as u64)
+                    .build()
+                    .context("unable to build rate limiter")?,
+            )
+        };
+
+        Ok(Self {
+            config,
+            canister_id,
+            methods_regex,
+            sender,
+            ip,
+            limiter,
+        })
+    }
+}
+
+impl Rule {
+    fn matches(&self, ctx: &RequestContext, canister_id: Principal, ip: Option<IpAddr>) -> bool {
+        if let Some(v) = &self.config.request_types {
+            if !v.contains(&ctx.request_type) {
+                return false;
+            }
+        }
+
+        if let Some(v) = self.canister_id {
+            if v != canister_id {
+                return false;
+            }
+        }
+
+        if let Some(v) = &self.methods_regex {
+            if !ctx.method_name.as_deref().is_some_and(|x| v.is_match(x)) {
+                return false;
+            }
+        }
+
+        if let Some(v) = self.sender {
+            if ctx.sender != Some(v) {
+                return false;
+            }
+        }
+
+        if let Some(v) = &self.ip {
+            // We listen on IPv6 sockets, so IPv4 clients show up as mapped addresses
+            if !ip.is_some_and(|x| v.contains(&x.to_canonical())) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn allow(&self) -> bool {
+        self.limiter.as_ref().is_some_and(|x| x.try_wait().is_ok())
+    }
+}
+
+/// Rate limiter that applies the rules loaded from a file.
+/// The rules are evaluated in order and only the first matching one applies.
+pub struct GenericLimiter {
+    path: PathBuf,
+    rules: ArcSwap<Vec<Arc<Rule>>>,
+    metric: IntCounterVec,
+}
+
+impl GenericLimiter {
+    pub fn new(path: PathBuf, registry: &Registry) -> Self {
+        Self {
+            path,
+            rules: ArcSwap::new(Arc::new(vec![])),
+            metric: register_int_counter_vec_with_registry!(
+                "rate_limit_rule_total",
+                "Counts requests matched by rate limiting rules",
+                &["rule", "throttled"],
+                registry
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Parses the rules and installs them.
+    /// The unchanged rules keep their state so that reloading does not reset the limits.
+    pub fn apply_rules(&self, rules: &[u8]) -> Result<(), Error> {
+        let configs: Vec<RuleConfig> =
+            serde_json::from_slice(rules).context("unable to parse rules")?;
+
+        let old_rules = self.rules.load_full();
+
+        let rules = configs
+            .into_iter()
+            .map(|config| {
+                if let Some(v) = old_rules.iter().find(|x| x.config == config) {
+                    return Ok(v.clone());
+                }
+
+                let name = config.name.clone();
+                Rule::try_from(config)
+                    .map(Arc::new)
+                    .with_context(|| format!("invalid rule '{name}'"))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        self.rules.store(Arc::new(rules));
+        Ok(())
+    }
+
+    /// Loads the rules from the file
+    pub async fn load_rules(&self) -> Result<(), Error> {
+        let rules = tokio::fs::read(&self.path)
+            .await
+            .context("unable to read rules file")?;
+
+        self.apply_rules(&rules)
+    }
+
+    // Returns the name of the rule that throttles the request, if any
+    fn check(
+        &self,
+        ctx: &RequestContext,
+        canister_id: Principal,
+        ip: Option<IpAddr>,
+    ) -> Option<String> {
+        let rules = self.rules.load();
+        let rule = rules.iter().find(|x| x.matches(ctx, canister_id, ip))?;
+
+        let throttled = !rule.allow();
+        self.metric
+            .with_label_values(&[&rule.config.name, if throttled { "yes" } else { "no" }])
+            .inc();
+
+        throttled.then(|| rule.config.name.clone())
+    }
+}
+
+/// Periodically reloads the rules from the file.
+/// If the file can't be loaded then the current rules stay in effect.
+pub struct RulesReloader(pub Arc<GenericLimiter>);
+
+#[async_trait]
+impl Run for RulesReloader {
+    async fn run(&mut self) -> Result<(), Error> {
+        self.0.load_rules().await
+    }
+}
+
+pub async fn generic_ratelimit_mw(
+    State(state): State<Arc<GenericLimiter>>,
+    Extension(ctx): Extension<Arc<RequestContext>>,
+    Extension(canister_id): Extension<CanisterId>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<impl IntoResponse, ApiError> {
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|x| x.0.ip());
+
+    if let Some(rule) = state.check(&ctx, canister_id.get().0, ip) {
+        return Err(ErrorCause::RateLimited(RateLimitCause::Rule(rule)).into());
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod test;
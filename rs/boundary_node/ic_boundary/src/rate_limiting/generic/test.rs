@@ -0,0 +1,220 @@
+use super::*;
+
+use std::net::Ipv4Addr;
+
+use axum::{middleware, routing::method_routing::post, Router};
+use http::StatusCode;
+use tower::Service;
+
+const CANISTER_1: &str = "sqjm4-qahae-aq";
+const CANISTER_2: &str = "f7crg-kabae";
+const SENDER: &str = "2vxsx-fae";
+
+const RULES: &str = r#"[
+    {
+        "name": "block_canister_2",
+        "canister_id": "f7crg-kabae",
+        "limit": 0
+    },
+    {
+        "name": "canister_1_transfers",
+        "canister_id": "sqjm4-qahae-aq",
+        "methods_regex": "^(transfer|approve)$",
+        "request_types": ["call"],
+        "limit": 2
+    },
+    {
+        "name": "local_network",
+        "ip": "10.0.0.0/8",
+        "limit": 1
+    }
+]"#;
+
+fn limiter() -> GenericLimiter {
+    GenericLimiter::new(
+        PathBuf::from("/nonexistent"),
+        &Registry::new_custom(None, None).unwrap(),
+    )
+}
+
+fn gen_req(
+    canister_id: &str,
+    request_type: RequestType,
+    method: &str,
+    ip: Ipv4Addr,
+) -> Request<Body> {
+    let mut req = Request::post("/").body(Body::from("foobar")).unwrap();
+
+    let ctx = RequestContext {
+        request_type,
+        sender: Some(Principal::from_text(SENDER).unwrap()),
+        method_name: Some(method.into()),
+        ..Default::default()
+    };
+
+    req.extensions_mut().insert(Arc::new(ctx));
+    req.extensions_mut()
+        .insert(CanisterId::from_str(canister_id).unwrap());
+    // Emulate the IPv4 client connecting to the IPv6 socket
+    req.extensions_mut().insert(ConnectInfo(SocketAddr::new(
+        IpAddr::V6(ip.to_ipv6_mapped()),
+        8080,
+    )));
+
+    req
+}
+
+async fn handler() -> impl IntoResponse {
+    "foobar"
+}
+
+#[test]
+fn test_rule_matches() -> Result<(), Error> {
+    let rule = Rule::try_from(RuleConfig {
+        name: "foo".into(),
+        canister_id: Some(CANISTER_1.into()),
+        methods_regex: Some("^foo".into()),
+        request_types: Some(vec![RequestType::Query]),
+        sender: Some(SENDER.into()),
+        ip: Some("192.168.0.0/16".into()),
+        limit: 1,
+    })?;
+
+    let canister_1 = Principal::from_text(CANISTER_1)?;
+    let canister_2 = Principal::from_text(CANISTER_2)?;
+    let ip_in = Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)));
+    let ip_out = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+
+    let mut ctx = RequestContext {
+        request_type: RequestType::Query,
+        sender: Some(Principal::from_text(SENDER)?),
+        method_name: Some("foobar".into()),
+        ..Default::default()
+    };
+
+    assert!(rule.matches(&ctx, canister_1, ip_in));
+    assert!(!rule.matches(&ctx, canister_2, ip_in));
+    assert!(!rule.matches(&ctx, canister_1, ip_out));
+    assert!(!rule.matches(&ctx, canister_1, None));
+
+    ctx.method_name = Some("barfoo".into());
+    assert!(!rule.matches(&ctx, canister_1, ip_in));
+
+    ctx.method_name = Some("foobar".into());
+    ctx.request_type = RequestType::Call;
+    assert!(!rule.matches(&ctx, canister_1, ip_in));
+
+    ctx.request_type = RequestType::Query;
+    ctx.sender = Some(Principal::anonymous());
+    assert!(!rule.matches(&ctx, canister_1, ip_in));
+
+    // Rule without conditions matches everything
+    let rule = Rule::try_from(RuleConfig {
+        name: "bar".into(),
+        canister_id: None,
+        methods_regex: None,
+        request_types: None,
+        sender: None,
+        ip: None,
+        limit: 1,
+    })?;
+    assert!(rule.matches(&RequestContext::default(), canister_2, None));
+
+    Ok(())
+}
+
+#[test]
+fn test_apply_rules() -> Result<(), Error> {
+    let limiter = limiter();
+    limiter.apply_rules(RULES.as_bytes())?;
+    assert_eq!(limiter.rules.load().len(), 3);
+
+    // Invalid rules are rejected and the current ones are kept
+    assert!(limiter.apply_rules(b"foobar").is_err());
+    assert!(limiter
+        .apply_rules(br#"[{"name": "foo", "ip": "foobar", "limit": 1}]"#)
+        .is_err());
+    assert!(limiter
+        .apply_rules(br#"[{"name": "foo", "foo": "bar", "limit": 1}]"#)
+        .is_err());
+    assert_eq!(limiter.rules.load().len(), 3);
+
+    // Unchanged rules are kept as is
+    let old_rules = limiter.rules.load_full();
+    limiter.apply_rules(RULES.replace("\"limit\": 1", "\"limit\": 5").as_bytes())?;
+    let new_rules = limiter.rules.load_full();
+    assert!(Arc::ptr_eq(&old_rules[0], &new_rules[0]));
+    assert!(Arc::ptr_eq(&old_rules[1], &new_rules[1]));
+    assert!(!Arc::ptr_eq(&old_rules[2], &new_rules[2]));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generic_ratelimit_mw() -> Result<(), Error> {
+    let limiter = Arc::new(limiter());
+    limiter.apply_rules(RULES.as_bytes())?;
+
+    let mut app = Router::new()
+        .route("/", post(handler))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&limiter),
+            generic_ratelimit_mw,
+        ));
+
+    let ip = Ipv4Addr::new(192, 168, 0, 1);
+
+    // Blocked canister
+    let res = app
+        .call(gen_req(CANISTER_2, RequestType::Query, "foo", ip))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(matches!(
+        res.extensions().get::<ErrorCause>(),
+        Some(ErrorCause::RateLimited(RateLimitCause::Rule(x))) if x == "block_canister_2"
+    ));
+
+    // Non-matching requests are not limited
+    for _ in 0..10 {
+        for (request_type, method) in [(RequestType::Query, "transfer"), (RequestType::Call, "foo")]
+        {
+            let res = app
+                .call(gen_req(CANISTER_1, request_type, method, ip))
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    // Matching requests are limited
+    for _ in 0..2 {
+        let res = app
+            .call(gen_req(CANISTER_1, RequestType::Call, "transfer", ip))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let res = app
+        .call(gen_req(CANISTER_1, RequestType::Call, "approve", ip))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // IPv4-mapped client addresses are matched against IPv4 ranges
+    let ip = Ipv4Addr::new(10, 1, 2, 3);
+    let res = app
+        .call(gen_req(CANISTER_1, RequestType::Query, "foo", ip))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .call(gen_req(CANISTER_1, RequestType::Query, "foo", ip))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    Ok(())
+}
@@ -6,6 +6,7 @@ use hyper::body;
 use ic_types::CanisterId;
 
 use crate::{
+    balancer::Balancer,
     http::AxumResponse,
     persist::RouteSubnet,
     routes::{ApiError, ErrorCause, RequestContext, RequestType},
@@ -16,6 +17,7 @@ use crate::{
 pub struct RetryParams {
     pub retry_count: usize,
     pub retry_update_call: bool,
+    pub balancer: Arc<Balancer>,
 }
 
 #[derive(Clone)]
@@ -75,7 +77,9 @@ pub async fn retry_request(
     next: Next<Body>,
 ) -> Result<impl IntoResponse, ApiError> {
     // Select up to 1+retry_count nodes from the subnet if there are any
-    let nodes = subnet.pick_random_nodes(1 + params.retry_count)?;
+    let nodes = params
+        .balancer
+        .pick_nodes(&subnet, 1 + params.retry_count)?;
 
     // Skip retrying in certain cases
     if params.retry_count == 0
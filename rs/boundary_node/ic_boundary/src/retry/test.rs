@@ -15,7 +15,7 @@ use http::StatusCode;
 use ic_types::CanisterId;
 use tower::Service;
 
-use crate::routes::test::test_route_subnet;
+use crate::{balancer::RoutingStrategy, routes::test::test_route_subnet};
 
 struct TestState {
     failures: u8,
@@ -103,6 +103,7 @@ async fn test_retry() -> Result<(), Error> {
             RetryParams {
                 retry_count: 3,
                 retry_update_call: false,
+                balancer: Arc::new(Balancer::new(RoutingStrategy::Random, 0.1)),
             },
             retry_request,
         ));
@@ -168,6 +169,7 @@ async fn test_retry() -> Result<(), Error> {
             RetryParams {
                 retry_count: 3,
                 retry_update_call: true,
+                balancer: Arc::new(Balancer::new(RoutingStrategy::Random, 0.1)),
             },
             retry_request,
         ));
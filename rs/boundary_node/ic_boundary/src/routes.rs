@@ -35,6 +35,7 @@ use tower_governor::errors::GovernorError;
 use url::Url;
 
 use crate::{
+    balancer::Balancer,
     cache::CacheStatus,
     core::MAX_REQUEST_BODY_SIZE,
     http::{read_streaming_body, reqwest_error_infer, HttpClient},
@@ -95,8 +96,9 @@ lazy_static! {
 }
 
 // Type of IC request
-#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Display, IntoStaticStr)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Display, IntoStaticStr, Deserialize)]
 #[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum RequestType {
     #[default]
     Unknown,
@@ -111,6 +113,8 @@ pub enum RequestType {
 pub enum RateLimitCause {
     Normal,
     LedgerTransfer,
+    // Name of the rule that throttled the request
+    Rule(String),
 }
 
 // Categorized possible causes for request processing failures
@@ -175,6 +179,7 @@ impl ErrorCause {
             Self::ReplicaTLSErrorOther(x) => Some(x.clone()),
             Self::ReplicaTLSErrorCert(x) => Some(x.clone()),
             Self::ReplicaErrorOther(x) => Some(x.clone()),
+            Self::RateLimited(RateLimitCause::Rule(x)) => Some(format!("rule '{x}'")),
             _ => None,
         }
     }
@@ -349,6 +354,7 @@ pub struct ProxyRouter {
     http_client: Arc<dyn HttpClient>,
     published_routes: Arc<ArcSwapOption<Routes>>,
     published_registry_snapshot: Arc<ArcSwapOption<RegistrySnapshot>>,
+    balancer: Arc<Balancer>,
 }
 
 impl ProxyRouter {
@@ -356,11 +362,13 @@ impl ProxyRouter {
         http_client: Arc<dyn HttpClient>,
         published_routes: Arc<ArcSwapOption<Routes>>,
         published_registry_snapshot: Arc<ArcSwapOption<RegistrySnapshot>>,
+        balancer: Arc<Balancer>,
     ) -> Self {
         Self {
             http_client,
             published_routes,
             published_registry_snapshot,
+            balancer,
         }
     }
 }
@@ -388,12 +396,11 @@ impl Proxy for ProxyRouter {
         *request.headers_mut() = parts.headers;
         *request.body_mut() = Some(body.into());
 
-        // Execute request
-        let response = self
-            .http_client
-            .execute(request)
-            .await
-            .map_err(reqwest_error_infer)?;
+        // Execute request, recording its latency for the node selection
+        let tracker = self.balancer.track(&node);
+        let response = self.http_client.execute(request).await;
+        tracker.finish(matches!(&response, Ok(r) if !r.status().is_server_error()));
+        let response = response.map_err(reqwest_error_infer)?;
 
         // Convert Reqwest response into Axum one with body streaming
         let status = response.status();
@@ -243,6 +243,7 @@ pub fn setup_test_router(
         enable_cache.then_some(Arc::new(
             Cache::new(10485760, 262144, Duration::from_secs(1), false).unwrap(),
         )),
+        None,
     );
 
     let router = router.layer(MockConnectInfo(SocketAddr::from(([0, 0, 0, 0], 1337))));
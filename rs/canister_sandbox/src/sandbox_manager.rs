@@ -138,6 +138,7 @@ impl Execution {
                 allocated_message_bytes,
                 instance_stats,
                 system_api_call_counters,
+                canister_log,
             },
             deltas,
             instance_or_system_api,
@@ -204,6 +205,7 @@ impl Execution {
                     num_instructions_left,
                     instance_stats,
                     system_api_call_counters,
+                    canister_log,
                 };
                 self.sandbox_manager.controller.execution_finished(
                     protocol::ctlsvc::ExecutionFinishedRequest {
@@ -230,6 +232,7 @@ impl Execution {
                     allocated_message_bytes,
                     instance_stats,
                     system_api_call_counters,
+                    canister_log,
                 };
 
                 self.sandbox_manager.controller.execution_finished(
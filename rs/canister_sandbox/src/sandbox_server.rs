@@ -214,6 +214,8 @@ mod tests {
             0,
             ic00_aliases,
             SMALL_APP_SUBNET_MAX_SIZE,
+            subnet_test_id(1),
+            BTreeMap::new(),
             SchedulerConfig::application_subnet().dirty_page_overhead,
             CanisterTimer::Inactive,
             0,
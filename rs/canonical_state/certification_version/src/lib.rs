@@ -37,6 +37,8 @@ pub enum CertificationVersion {
     V14 = 14,
     /// Added subnet metrics in `subnet` subtree.
     V15 = 15,
+    /// Define optional `Request::deadline` and `Response::deadline` fields.
+    V16 = 16,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -69,13 +71,13 @@ impl std::convert::TryFrom<u32> for CertificationVersion {
 
 /// The Canonical State certification version that should be used for newly
 /// computed states.
-pub const CURRENT_CERTIFICATION_VERSION: CertificationVersion = CertificationVersion::V15;
+pub const CURRENT_CERTIFICATION_VERSION: CertificationVersion = CertificationVersion::V16;
 
 /// Maximum supported certification version.
 ///
 /// The replica will panic if requested to certify using a version higher than
 /// this.
-pub const MAX_SUPPORTED_CERTIFICATION_VERSION: CertificationVersion = CertificationVersion::V15;
+pub const MAX_SUPPORTED_CERTIFICATION_VERSION: CertificationVersion = CertificationVersion::V16;
 
 /// Returns a list of all certification versions up to [MAX_SUPPORTED_CERTIFICATION_VERSION].
 pub fn all_supported_versions() -> impl std::iter::Iterator<Item = CertificationVersion> {
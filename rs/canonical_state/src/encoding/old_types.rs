@@ -13,7 +13,7 @@ use crate::CertificationVersion;
 use super::types;
 use crate::encoding::types::{Bytes, Cycles, Funds, Response};
 use ic_protobuf::proxy::ProxyDecodeError;
-use ic_types::xnet::StreamHeader;
+use ic_types::{messages::NO_DEADLINE, xnet::StreamHeader};
 use serde::{Deserialize, Serialize};
 
 // Copy of `types::RequestOrResponse` at canonical version 13 (before the
@@ -128,6 +128,7 @@ impl TryFrom<RequestV13> for ic_types::messages::Request {
             method_name: request.method_name,
             method_payload: request.method_payload,
             metadata: None,
+            deadline: NO_DEADLINE,
         })
     }
 }
@@ -182,6 +183,7 @@ impl TryFrom<RequestV3> for ic_types::messages::Request {
             method_name: request.method_name,
             method_payload: request.method_payload,
             metadata: None,
+            deadline: NO_DEADLINE,
         })
     }
 }
@@ -231,6 +233,7 @@ impl TryFrom<ResponseV3> for ic_types::messages::Response {
             originator_reply_callback: response.originator_reply_callback.into(),
             refund: response.refund.cycles.try_into()?,
             response_payload: response.response_payload.try_into()?,
+            deadline: NO_DEADLINE,
         })
     }
 }
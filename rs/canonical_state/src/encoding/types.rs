@@ -13,7 +13,7 @@
 use crate::CertificationVersion;
 use ic_error_types::TryFromError;
 use ic_protobuf::proxy::ProxyDecodeError;
-use ic_types::{xnet::StreamIndex, Time};
+use ic_types::{messages::NO_DEADLINE, xnet::StreamIndex, CoarseTime, Time};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::VecDeque,
@@ -77,6 +77,8 @@ pub struct Request {
     pub cycles_payment: Option<Cycles>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<RequestMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<u32>,
 }
 
 /// Canonical representation of `ic_types::messages::Response`.
@@ -92,6 +94,8 @@ pub struct Response {
     pub response_payload: Payload,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cycles_refund: Option<Cycles>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<u32>,
 }
 
 /// Canonical representation of `ic_types::funds::Cycles`.
@@ -269,6 +273,22 @@ impl From<&ic_types::messages::RequestMetadata> for RequestMetadata {
     }
 }
 
+/// Encodes a message deadline, omitting `NO_DEADLINE` (i.e. guaranteed response
+/// calls) and anything before certification version 16.
+fn encode_deadline(
+    deadline: CoarseTime,
+    certification_version: CertificationVersion,
+) -> Option<u32> {
+    (certification_version >= CertificationVersion::V16 && deadline != NO_DEADLINE)
+        .then_some(deadline.as_secs_since_unix_epoch())
+}
+
+fn decode_deadline(deadline: Option<u32>) -> CoarseTime {
+    deadline
+        .map(CoarseTime::from_secs_since_unix_epoch)
+        .unwrap_or(NO_DEADLINE)
+}
+
 impl From<(&ic_types::messages::Request, CertificationVersion)> for Request {
     fn from(
         (request, certification_version): (&ic_types::messages::Request, CertificationVersion),
@@ -289,6 +309,7 @@ impl From<(&ic_types::messages::Request, CertificationVersion)> for Request {
             metadata: request.metadata.as_ref().and_then(|metadata| {
                 (certification_version >= CertificationVersion::V14).then_some(metadata.into())
             }),
+            deadline: encode_deadline(request.deadline, certification_version),
         }
     }
 }
@@ -324,6 +345,7 @@ impl TryFrom<Request> for ic_types::messages::Request {
             method_name: request.method_name,
             method_payload: request.method_payload,
             metadata: request.metadata.map(From::from),
+            deadline: decode_deadline(request.deadline),
         })
     }
 }
@@ -343,6 +365,7 @@ impl From<(&ic_types::messages::Response, CertificationVersion)> for Response {
             refund: funds,
             response_payload: (&response.response_payload, certification_version).into(),
             cycles_refund: None,
+            deadline: encode_deadline(response.deadline, certification_version),
         }
     }
 }
@@ -367,6 +390,7 @@ impl TryFrom<Response> for ic_types::messages::Response {
             originator_reply_callback: response.originator_reply_callback.into(),
             refund,
             response_payload: response.response_payload.try_into()?,
+            deadline: decode_deadline(response.deadline),
         })
     }
 }
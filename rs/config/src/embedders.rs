@@ -78,6 +78,9 @@ pub struct FeatureFlags {
     /// Track dirty pages with a write barrier instead of the signal handler.
     pub write_barrier: FlagStatus,
     pub wasm_native_stable_memory: FlagStatus,
+    /// Allow canisters to make best-effort calls via
+    /// `ic0.call_with_best_effort_response`.
+    pub best_effort_responses: FlagStatus,
 }
 
 impl FeatureFlags {
@@ -86,6 +89,7 @@ impl FeatureFlags {
             rate_limiting_of_debug_prints: FlagStatus::Enabled,
             write_barrier: FlagStatus::Disabled,
             wasm_native_stable_memory: FlagStatus::Enabled,
+            best_effort_responses: FlagStatus::Disabled,
         }
     }
 }
@@ -5,7 +5,7 @@ use ic_types::{
     Cycles, NumBytes, NumInstructions, MAX_STABLE_MEMORY_IN_BYTES, MAX_WASM_MEMORY_IN_BYTES,
 };
 use serde::{Deserialize, Serialize};
-use std::{str::FromStr, time::Duration};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 const MIB: u64 = 1024 * 1024;
 const GIB: u64 = MIB * 1024;
@@ -30,6 +30,10 @@ const SUBNET_MEMORY_CAPACITY: NumBytes = NumBytes::new(700 * GIB);
 /// responses; plus the maximum allowed response size per queue reservation.
 const SUBNET_MESSAGE_MEMORY_CAPACITY: NumBytes = NumBytes::new(25 * GIB);
 
+/// This is the upper limit on how much memory can be used by the messages of a
+/// single canister before its best-effort messages start being shed.
+const CANISTER_MESSAGE_MEMORY_CAPACITY: NumBytes = NumBytes::new(2 * GIB);
+
 /// This is the upper limit on how much memory can be used by the ingress
 /// history on a given subnet. It is lower than the subnet message memory
 /// capacity because here we count actual memory consumption as opposed to
@@ -129,6 +133,9 @@ const BITCOIN_MAINNET_SOFT_LAUNCH_CANISTER_ID: &str = "gsvzx-syaaa-aaaan-aaabq-c
 /// The capacity of the Wasm compilation cache.
 pub const MAX_COMPILATION_CACHE_SIZE: NumBytes = NumBytes::new(10 * GIB);
 
+/// The capacity of the on-disk part of the Wasm compilation cache.
+pub const MAX_COMPILATION_CACHE_DISK_SIZE: NumBytes = NumBytes::new(20 * GIB);
+
 /// Maximum number of controllers allowed in a request (specified in the interface spec).
 pub const MAX_ALLOWED_CONTROLLERS_COUNT: usize = 10;
 
@@ -158,6 +165,10 @@ pub struct Config {
     /// across the whole subnet.
     pub subnet_message_memory_capacity: NumBytes,
 
+    /// The maximum amount of logical storage available to the messages of a
+    /// single canister. Best-effort messages are shed above this limit.
+    pub canister_message_memory_capacity: NumBytes,
+
     /// The maximum amount of logical storage available to the ingress history
     /// across the whole subnet.
     pub ingress_history_memory_capacity: NumBytes,
@@ -246,6 +257,13 @@ pub struct Config {
     /// The capacity of the Wasm compilation cache.
     pub max_compilation_cache_size: NumBytes,
 
+    /// If set, the compiled Wasm modules are also stored in this directory so
+    /// that they don't have to be recompiled after a restart.
+    pub compilation_cache_dir: Option<PathBuf>,
+
+    /// The capacity of the on-disk part of the Wasm compilation cache.
+    pub max_compilation_cache_disk_size: NumBytes,
+
     /// Indicate whether query stats should be collected or not.
     pub query_stats_aggregation: FlagStatus,
 
@@ -286,6 +304,7 @@ impl Default for Config {
             subnet_memory_threshold: SUBNET_MEMORY_THRESHOLD,
             subnet_memory_capacity: SUBNET_MEMORY_CAPACITY,
             subnet_message_memory_capacity: SUBNET_MESSAGE_MEMORY_CAPACITY,
+            canister_message_memory_capacity: CANISTER_MESSAGE_MEMORY_CAPACITY,
             ingress_history_memory_capacity: INGRESS_HISTORY_MEMORY_CAPACITY,
             subnet_wasm_custom_sections_memory_capacity:
                 SUBNET_WASM_CUSTOM_SECTIONS_MEMORY_CAPACITY,
@@ -329,6 +348,8 @@ impl Default for Config {
             query_cache_max_expiry_time: QUERY_CACHE_MAX_EXPIRY_TIME,
             query_cache_data_certificate_expiry_time: QUERY_CACHE_DATA_CERTIFICATE_EXPIRY_TIME,
             max_compilation_cache_size: MAX_COMPILATION_CACHE_SIZE,
+            compilation_cache_dir: None,
+            max_compilation_cache_disk_size: MAX_COMPILATION_CACHE_DISK_SIZE,
             query_stats_aggregation: FlagStatus::Disabled,
             query_stats_epoch_length: QUERY_STATS_EPOCH_LENGTH,
             wasm_chunk_store: FlagStatus::Enabled,
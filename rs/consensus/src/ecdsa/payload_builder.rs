@@ -854,6 +854,7 @@ pub(crate) fn get_signing_requests<'a>(
                         context.key_id
                     ),
                 )),
+                deadline: context.request.deadline,
             };
             ecdsa_payload.signature_agreements.insert(
                 context.pseudo_random_id,
@@ -910,6 +911,7 @@ pub(crate) fn get_signing_requests<'a>(
                         RejectCode::CanisterError,
                         "Signature request expired",
                     )),
+                    deadline: context.request.deadline,
                 };
                 ecdsa_payload.signature_agreements.insert(
                     context.pseudo_random_id,
@@ -71,6 +71,7 @@ pub(crate) fn update_signature_agreements(
                 }
                 .encode(),
             ),
+            deadline: context.request.deadline,
         };
 
         completed.insert(
@@ -172,6 +173,7 @@ pub(crate) fn update_signature_agreements_improved_latency(
                         context.key_id
                     ),
                 )),
+                deadline: context.request.deadline,
             };
             payload.signature_agreements.insert(
                 context.pseudo_random_id,
@@ -201,6 +203,7 @@ pub(crate) fn update_signature_agreements_improved_latency(
                     RejectCode::CanisterError,
                     "Signature request expired",
                 )),
+                deadline: context.request.deadline,
             };
             payload.signature_agreements.insert(
                 context.pseudo_random_id,
@@ -234,6 +237,7 @@ pub(crate) fn update_signature_agreements_improved_latency(
                 }
                 .encode(),
             ),
+            deadline: context.request.deadline,
         };
         payload.signature_agreements.insert(
             context.pseudo_random_id,
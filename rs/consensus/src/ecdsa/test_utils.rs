@@ -69,6 +69,7 @@ pub(crate) fn empty_response() -> ic_types::messages::Response {
         // be refunded to the canister.
         refund: ic_types::Cycles::new(0),
         response_payload: ic_types::messages::Payload::Data(vec![]),
+        deadline: ic_types::messages::NO_DEADLINE,
     }
 }
 
@@ -256,6 +256,16 @@ impl CyclesAccountManager {
         self.scale_cost(self.config.xnet_call_fee, subnet_size)
     }
 
+    /// Returns the fee for performing a xnet call and transmitting its payload
+    /// of the given size in [`Cycles`]. This excludes the prepayments for the
+    /// response.
+    pub fn xnet_call_total_fee(&self, payload_size: NumBytes, subnet_size: usize) -> Cycles {
+        self.scale_cost(
+            self.config.xnet_call_fee + self.config.xnet_byte_transmission_fee * payload_size.get(),
+            subnet_size,
+        )
+    }
+
     /// Returns the fee per byte of transmitted xnet call in [`Cycles`].
     pub fn xnet_call_bytes_transmitted_fee(
         &self,
@@ -739,11 +749,8 @@ impl CyclesAccountManager {
         //   - the fee to send the request (by size)
         //   - the fee for the largest possible response
         //   - the fee for executing the largest allowed response when it eventually arrives.
-        let transmission_fee = self.scale_cost(
-            self.config.xnet_call_fee
-                + self.config.xnet_byte_transmission_fee * request.payload_size_bytes().get(),
-            subnet_size,
-        ) + prepayment_for_response_transmission;
+        let transmission_fee = self.xnet_call_total_fee(request.payload_size_bytes(), subnet_size)
+            + prepayment_for_response_transmission;
 
         let fee = transmission_fee + prepayment_for_response_execution;
 
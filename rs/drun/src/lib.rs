@@ -1,6 +1,7 @@
 //! Standalone interface for testing application canisters.
 
-use crate::message::{msg_stream_from_file, Message};
+use crate::message::{msg_stream_from_file, Expectation, ExpectedReply, Message, ReplyFormat};
+use candid::{IDLArgs, TypeEnv};
 use hex::encode;
 use ic_config::{subnet_config::SubnetConfig, Config};
 use ic_cycles_account_manager::CyclesAccountManager;
@@ -64,20 +65,27 @@ pub struct DrunOptions {
     pub subnet_type: SubnetType,
 }
 
+/// The result of the most recent message, which `expect` lines are checked
+/// against.
+type LastResult = (Result<WasmResult, UserError>, ReplyFormat);
+
 /// Deliver a single message to the Message Routing layer
 fn deliver_message(
     msg: SignedIngress,
+    reply_format: &ReplyFormat,
     message_routing: &dyn MessageRouting,
     ingress_hist_reader: &dyn IngressHistoryReader,
     extra_batches: u64,
-) {
+) -> Result<WasmResult, UserError> {
     let message_id = msg.id();
 
     let _ = execute_ingress_message(message_routing, msg, &message_id, ingress_hist_reader);
     // print result after waiting, to not interleave the result
     // with debug.print messages from subsequent calls. revise after DFN-1269.
     wait_extra_batches(message_routing, extra_batches);
-    print_ingress_result(&message_id, ingress_hist_reader);
+    let result = ingress_result(&message_id, ingress_hist_reader);
+    print_ingress_result(&result, reply_format);
+    result
 }
 
 fn setup_logger(log_file: PathBuf) -> Logger {
@@ -249,84 +257,214 @@ pub fn run_drun(uo: DrunOptions) -> Result<(), String> {
         MaliciousFlags::default(),
     );
 
+    let mut last_result: Option<LastResult> = None;
+    let mut failed_expectations = 0;
     msg_stream.try_for_each(|parse_result| {
         parse_result.map(|msg| match msg {
-            Message::Install(msg) => {
-                deliver_message(
+            Message::Install(msg) | Message::Create(msg) => {
+                let result = deliver_message(
                     msg,
+                    &ReplyFormat::Raw,
                     &message_routing,
                     ingress_hist_reader.as_ref(),
                     extra_batches,
                 );
+                last_result = Some((result, ReplyFormat::Raw));
             }
 
-            Message::Query(q) => {
+            Message::Query(q, reply_format) => {
                 // NOTE: Data certificates aren't supported in drun yet.
                 // To support them, we'd need to do something similar to
                 // http_handler::get_latest_certified_state_and_data_certificate
-                print_query_result(query_handler.query(
-                    q,
-                    state_manager.get_latest_state(),
-                    Vec::new(),
-                ));
+                let result = query_handler.query(q, state_manager.get_latest_state(), Vec::new());
+                print_query_result(&result, &reply_format);
+                last_result = Some((result, reply_format));
             }
 
-            Message::Ingress(msg) => {
-                deliver_message(
+            Message::Ingress(msg, reply_format) => {
+                let result = deliver_message(
                     msg,
+                    &reply_format,
                     &message_routing,
                     ingress_hist_reader.as_ref(),
                     extra_batches,
                 );
+                last_result = Some((result, reply_format));
             }
-            Message::Create(msg) => {
-                deliver_message(
-                    msg,
-                    &message_routing,
-                    ingress_hist_reader.as_ref(),
-                    extra_batches,
-                );
+
+            Message::Expect { expectation, line } => {
+                if let Err(e) = check_expectation(&expectation, last_result.as_ref()) {
+                    println!("Expectation failed (line {}): {}", line, e);
+                    failed_expectations += 1;
+                }
             }
         })
-    })
+    })?;
+
+    expectations_result(failed_expectations)
+}
+
+/// Fails if any expectation failed, so that `drun` exits with a non-zero code.
+fn expectations_result(failed_expectations: usize) -> Result<(), String> {
+    if failed_expectations > 0 {
+        return Err(format!("{} expectation(s) failed", failed_expectations));
+    }
+    Ok(())
+}
+
+fn ingress_result(
+    message_id: &MessageId,
+    ingress_hist_reader: &dyn IngressHistoryReader,
+) -> Result<WasmResult, UserError> {
+    match (ingress_hist_reader.get_latest_status())(message_id) {
+        IngressStatus::Known {
+            state: IngressState::Completed(result),
+            ..
+        } => Ok(result),
+        IngressStatus::Known {
+            state: IngressState::Failed(error),
+            ..
+        } => Err(error),
+        _ => panic!("Ingress message has not finished processing."),
+    }
 }
 
-fn print_query_result(res: Result<WasmResult, UserError>) {
+fn print_query_result(res: &Result<WasmResult, UserError>, reply_format: &ReplyFormat) {
     match res {
         Ok(payload) => {
             print!("Ok: ");
-            print_wasm_result(payload);
+            print_wasm_result(payload, reply_format);
         }
         Err(e) => println!("Err: {}", e),
     }
 }
 
-fn print_ingress_result(message_id: &MessageId, ingress_hist_reader: &dyn IngressHistoryReader) {
-    let status = (ingress_hist_reader.get_latest_status())(message_id);
+fn print_ingress_result(res: &Result<WasmResult, UserError>, reply_format: &ReplyFormat) {
     print!("ingress ");
-    match status {
-        IngressStatus::Known {
-            state: IngressState::Completed(result),
-            ..
-        } => {
+    match res {
+        Ok(result) => {
             print!("Completed: ");
-            print_wasm_result(result)
+            print_wasm_result(result, reply_format)
         }
-        IngressStatus::Known {
-            state: IngressState::Failed(error),
-            ..
-        } => println!("Err: {}", error),
-        _ => panic!("Ingress message has not finished processing."),
+        Err(error) => println!("Err: {}", error),
     };
 }
 
-fn print_wasm_result(wasm_result: WasmResult) {
+fn print_wasm_result(wasm_result: &WasmResult, reply_format: &ReplyFormat) {
     match wasm_result {
-        WasmResult::Reply(v) => println!("Reply: 0x{}", encode(v)),
+        WasmResult::Reply(v) => match decode_candid_reply(v, reply_format) {
+            Some(Ok(args)) => println!("Reply: {}", args),
+            // Fall back to hex if the reply is not valid Candid, so that
+            // the payload is still visible.
+            Some(Err(_)) | None => println!("Reply: 0x{}", encode(v)),
+        },
         WasmResult::Reject(e) => println!("Reject: {}", e),
     }
 }
 
+/// Decodes a reply as Candid, if the reply format asks for it.
+fn decode_candid_reply(
+    bytes: &[u8],
+    reply_format: &ReplyFormat,
+) -> Option<Result<IDLArgs, String>> {
+    let result = match reply_format {
+        ReplyFormat::Raw => return None,
+        ReplyFormat::Candid(Some(rets)) => {
+            IDLArgs::from_bytes_with_types(bytes, &rets.env, &rets.types)
+        }
+        ReplyFormat::Candid(None) => IDLArgs::from_bytes(bytes),
+    };
+    Some(result.map_err(|e| format!("failed to decode Candid reply: {}", e)))
+}
+
+/// Checks the result of the previous message against an expectation and
+/// describes the mismatch, if any.
+fn check_expectation(
+    expectation: &Expectation,
+    last_result: Option<&LastResult>,
+) -> Result<(), String> {
+    let (result, reply_format) =
+        last_result.ok_or_else(|| "no message was sent before the expectation".to_string())?;
+    match (expectation, result) {
+        (Expectation::Reply(expected), Ok(WasmResult::Reply(actual))) => {
+            check_reply(expected, actual, reply_format)
+        }
+        (Expectation::Reply(_), Ok(WasmResult::Reject(e))) => {
+            Err(format!("expected a reply, got reject: {}", e))
+        }
+        (Expectation::Reply(_), Err(e)) => Err(format!("expected a reply, got error: {}", e)),
+        (Expectation::Reject(_), Ok(WasmResult::Reply(v))) => {
+            Err(format!("expected a reject, got reply: 0x{}", encode(v)))
+        }
+        (Expectation::Reject(expected), Ok(WasmResult::Reject(actual))) => {
+            check_reject_message(expected.as_deref(), actual)
+        }
+        (Expectation::Reject(expected), Err(e)) => {
+            check_reject_message(expected.as_deref(), e.description())
+        }
+    }
+}
+
+fn check_reply(
+    expected: &ExpectedReply,
+    actual: &[u8],
+    reply_format: &ReplyFormat,
+) -> Result<(), String> {
+    match expected {
+        ExpectedReply::Bytes(expected) => {
+            if expected.as_slice() == actual {
+                Ok(())
+            } else {
+                Err(format!(
+                    "expected reply 0x{}, got 0x{}",
+                    encode(expected),
+                    encode(actual)
+                ))
+            }
+        }
+        ExpectedReply::Candid(expected) => {
+            let actual = match decode_candid_reply(actual, reply_format) {
+                Some(result) => result?,
+                None => IDLArgs::from_bytes(actual)
+                    .map_err(|e| format!("failed to decode Candid reply: {}", e))?,
+            };
+            // Literals in the expectation (e.g. numbers) are only resolved to
+            // concrete values once annotated with the types of the reply.
+            let expected = match reply_format {
+                ReplyFormat::Candid(Some(rets)) => {
+                    expected
+                        .clone()
+                        .annotate_types(true, &rets.env, &rets.types)
+                }
+                _ => expected
+                    .clone()
+                    .annotate_types(true, &TypeEnv::new(), &actual.get_types()),
+            }
+            .map_err(|e| {
+                format!(
+                    "expected reply {} does not match the reply type: {}",
+                    expected, e
+                )
+            })?;
+            if expected == actual {
+                Ok(())
+            } else {
+                Err(format!("expected reply {}, got {}", expected, actual))
+            }
+        }
+    }
+}
+
+fn check_reject_message(expected: Option<&str>, actual: &str) -> Result<(), String> {
+    match expected {
+        Some(expected) if !actual.contains(expected) => Err(format!(
+            "expected a reject containing {:?}, got: {}",
+            expected, actual
+        )),
+        _ => Ok(()),
+    }
+}
+
 fn get_random_seed() -> [u8; 32] {
     let step = Uniform::new(0, u8::MAX);
     let mut rng = rand::thread_rng();
@@ -447,4 +585,111 @@ mod tests {
         }
         assert_ne!(equal, len);
     }
+
+    fn reply(bytes: &[u8], reply_format: ReplyFormat) -> LastResult {
+        (Ok(WasmResult::Reply(bytes.to_vec())), reply_format)
+    }
+
+    fn candid(args: &str) -> IDLArgs {
+        candid_parser::parse_idl_args(args).unwrap()
+    }
+
+    #[test]
+    fn test_check_expectation_matching_reply() {
+        let expectation = Expectation::Reply(ExpectedReply::Bytes(vec![1, 2]));
+        assert_eq!(
+            check_expectation(&expectation, Some(&reply(&[1, 2], ReplyFormat::Raw))),
+            Ok(())
+        );
+
+        let expectation = Expectation::Reply(ExpectedReply::Candid(candid("(\"hello\", 42)")));
+        let bytes = candid::Encode!(&"hello", &42_u64).unwrap();
+        assert_eq!(
+            check_expectation(
+                &expectation,
+                Some(&reply(&bytes, ReplyFormat::Candid(None)))
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_check_expectation_differing_reply() {
+        let expectation = Expectation::Reply(ExpectedReply::Bytes(vec![1, 2]));
+        assert_eq!(
+            check_expectation(&expectation, Some(&reply(&[1, 3], ReplyFormat::Raw))),
+            Err("expected reply 0x0102, got 0x0103".to_string())
+        );
+
+        let expectation = Expectation::Reply(ExpectedReply::Candid(candid("(\"hello\")")));
+        let bytes = candid::Encode!(&"world").unwrap();
+        assert!(check_expectation(
+            &expectation,
+            Some(&reply(&bytes, ReplyFormat::Candid(None)))
+        )
+        .unwrap_err()
+        .starts_with("expected reply (\"hello\"), got"));
+    }
+
+    #[test]
+    fn test_check_expectation_expected_reject_got_reply() {
+        let expectation = Expectation::Reject(None);
+        assert_eq!(
+            check_expectation(&expectation, Some(&reply(&[1, 2], ReplyFormat::Raw))),
+            Err("expected a reject, got reply: 0x0102".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check_expectation_reject() {
+        let rejected = (
+            Ok(WasmResult::Reject("not allowed".to_string())),
+            ReplyFormat::Raw,
+        );
+        let failed = (
+            Err(UserError::new(
+                ErrorCode::CanisterCalledTrap,
+                "Canister trapped explicitly",
+            )),
+            ReplyFormat::Raw,
+        );
+        assert_eq!(
+            check_expectation(&Expectation::Reject(None), Some(&rejected)),
+            Ok(())
+        );
+        assert_eq!(
+            check_expectation(
+                &Expectation::Reject(Some("trapped".to_string())),
+                Some(&failed)
+            ),
+            Ok(())
+        );
+        assert!(check_expectation(
+            &Expectation::Reject(Some("trapped".to_string())),
+            Some(&rejected)
+        )
+        .is_err());
+        assert!(check_expectation(
+            &Expectation::Reply(ExpectedReply::Bytes(vec![])),
+            Some(&failed)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_check_expectation_without_message() {
+        assert_eq!(
+            check_expectation(&Expectation::Reject(None), None),
+            Err("no message was sent before the expectation".to_string())
+        );
+    }
+
+    #[test]
+    fn test_failed_expectations_make_drun_fail() {
+        assert_eq!(expectations_result(0), Ok(()));
+        assert_eq!(
+            expectations_result(2),
+            Err("2 expectation(s) failed".to_string())
+        );
+    }
 }
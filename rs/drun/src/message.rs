@@ -1,5 +1,7 @@
 use super::CanisterId;
 
+use candid::types::{Type, TypeInner};
+use candid::{IDLArgs, TypeEnv};
 use hex::decode;
 use ic_ic00_types::{self as ic00, CanisterInstallMode, Payload};
 use ic_types::{
@@ -9,20 +11,80 @@ use ic_types::{
 };
 
 use std::{
+    collections::BTreeMap,
     convert::TryFrom,
     fmt,
     fs::File,
     io::{self, Read},
     str::Chars,
     string::FromUtf8Error,
+    sync::Arc,
 };
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum Message {
-    Ingress(SignedIngress),
-    Query(UserQuery),
+    Ingress(SignedIngress, ReplyFormat),
+    Query(UserQuery, ReplyFormat),
     Install(SignedIngress),
     Create(SignedIngress),
+    /// Checks the result of the previous message.
+    Expect {
+        expectation: Expectation,
+        line: usize,
+    },
+}
+
+/// How replies to a message are decoded.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ReplyFormat {
+    /// Replies are printed as hex strings.
+    Raw,
+    /// Replies are decoded as Candid values, using the return types of the
+    /// method if the interface of the canister is known.
+    Candid(Option<MethodReturnTypes>),
+}
+
+/// The return types of a method declared in a `.did` file.
+#[derive(Clone, Debug)]
+pub(crate) struct MethodReturnTypes {
+    pub env: Arc<TypeEnv>,
+    pub types: Vec<Type>,
+}
+
+impl PartialEq for MethodReturnTypes {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.env, &other.env) && self.types == other.types
+    }
+}
+
+/// The expected result of a message.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Expectation {
+    /// The message is replied with a payload equal to the given one.
+    Reply(ExpectedReply),
+    /// The message is rejected or fails. If a text is given, the reject
+    /// message or error description must contain it.
+    Reject(Option<String>),
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ExpectedReply {
+    Bytes(Vec<u8>),
+    Candid(IDLArgs),
+}
+
+/// The Candid interface of a canister, loaded from a `.did` file.
+struct CandidInterface {
+    env: Arc<TypeEnv>,
+    service: Type,
+}
+
+/// Parses the lines of a message file. The parser keeps track of the Candid
+/// interfaces declared with `did` lines, which affect how subsequent messages
+/// to the same canister are encoded and decoded.
+#[derive(Default)]
+pub(crate) struct MessageParser {
+    interfaces: BTreeMap<CanisterId, CandidInterface>,
 }
 
 #[derive(Debug)]
@@ -128,6 +190,7 @@ pub(crate) fn msg_stream_from_file(
 ) -> Result<impl Iterator<Item = Result<Message, String>>, String> {
     let f = File::open(filename).map_err(|e| e.to_string())?;
     let line_iterator = LineIterator::new(f);
+    let mut parser = MessageParser::default();
 
     Ok(line_iterator
         .enumerate()
@@ -136,59 +199,140 @@ pub(crate) fn msg_stream_from_file(
             Ok(s) => !s.is_empty() && !s.starts_with('#'),
             _ => true,
         })
-        .map(|(i, line)| match line {
-            Ok(line) => {
-                parse_message(&line, i as u64).map_err(|e| format!("Line {}: {}", i + 1, e))
-            }
-            Err(e) => Err(format!("Error while reading line {}: {}", i, e)),
+        .filter_map(move |(i, line)| match line {
+            Ok(line) => parser
+                .parse_line(&line, i)
+                .map_err(|e| format!("Line {}: {}", i + 1, e))
+                .transpose(),
+            Err(e) => Some(Err(format!("Error while reading line {}: {}", i, e))),
         }))
 }
 
-fn parse_message(s: &str, nonce: u64) -> Result<Message, String> {
-    let s = s.trim_end();
-    let tokens: Vec<&str> = s.splitn(4, char::is_whitespace).collect();
-
-    match &tokens[..] {
-        [] => Err("Too few arguments.".to_string()),
-        ["ingress", canister_id, method_name, payload] => {
-            use ic_test_utilities::types::messages::SignedIngressBuilder;
-
-            let canister_id = parse_canister_id(canister_id)?;
-            let method_name = validate_method_name(method_name)?;
-            let method_payload = parse_octet_string(payload)?;
-
-            let signed_ingress = SignedIngressBuilder::new()
-                // `source` should become a self-authenticating id according
-                // to https://sdk.dfinity.org/docs/interface-spec/index.html#id-classes
-                .canister_id(canister_id)
-                .method_name(method_name)
-                .method_payload(method_payload)
-                .nonce(nonce)
-                .build();
-            Ok(Message::Ingress(signed_ingress))
-        }
-        ["query", canister_id, method_name, payload] => Ok(Message::Query(UserQuery {
-            source: UserId::from(PrincipalId::new_anonymous()),
-            receiver: parse_canister_id(canister_id)?,
-            method_name: validate_method_name(method_name)?,
-            method_payload: parse_octet_string(payload)?,
-            ingress_expiry: expiry_time_from_now().as_nanos_since_unix_epoch(),
-            nonce: Some(nonce.to_le_bytes().to_vec()),
-        })),
-        ["create"] => parse_create(nonce),
-        ["install", canister_id, wasm_file, payload] => {
-            parse_install(nonce, canister_id, payload, wasm_file, "install")
-        }
-        ["reinstall", canister_id, wasm_file, payload] => {
-            parse_install(nonce, canister_id, payload, wasm_file, "reinstall")
+impl MessageParser {
+    /// Parses the line with the given index. Returns `None` for lines that do
+    /// not produce a message, such as interface declarations.
+    fn parse_line(&mut self, s: &str, index: usize) -> Result<Option<Message>, String> {
+        let s = s.trim_end();
+        let tokens: Vec<&str> = s.splitn(3, char::is_whitespace).collect();
+
+        match &tokens[..] {
+            ["did", canister_id, did_file] => {
+                let canister_id = parse_canister_id(canister_id)?;
+                let interface = load_candid_interface(did_file)?;
+                self.interfaces.insert(canister_id, interface);
+                Ok(None)
+            }
+            ["expect", ..] => Ok(Some(Message::Expect {
+                expectation: parse_expectation(s)?,
+                line: index + 1,
+            })),
+            _ => self.parse_message(s, index as u64).map(Some),
         }
-        ["upgrade", canister_id, wasm_file, payload] => {
-            parse_install(nonce, canister_id, payload, wasm_file, "upgrade")
+    }
+
+    fn parse_message(&self, s: &str, nonce: u64) -> Result<Message, String> {
+        let s = s.trim_end();
+        let tokens: Vec<&str> = s.splitn(4, char::is_whitespace).collect();
+
+        match &tokens[..] {
+            [] => Err("Too few arguments.".to_string()),
+            ["ingress", canister_id, method_name, payload] => {
+                use ic_test_utilities::types::messages::SignedIngressBuilder;
+
+                let canister_id = parse_canister_id(canister_id)?;
+                let method_name = validate_method_name(method_name)?;
+                let (method_payload, reply_format) =
+                    self.parse_method_payload(&canister_id, &method_name, payload)?;
+
+                let signed_ingress = SignedIngressBuilder::new()
+                    // `source` should become a self-authenticating id according
+                    // to https://sdk.dfinity.org/docs/interface-spec/index.html#id-classes
+                    .canister_id(canister_id)
+                    .method_name(method_name)
+                    .method_payload(method_payload)
+                    .nonce(nonce)
+                    .build();
+                Ok(Message::Ingress(signed_ingress, reply_format))
+            }
+            ["query", canister_id, method_name, payload] => {
+                let receiver = parse_canister_id(canister_id)?;
+                let method_name = validate_method_name(method_name)?;
+                let (method_payload, reply_format) =
+                    self.parse_method_payload(&receiver, &method_name, payload)?;
+                Ok(Message::Query(
+                    UserQuery {
+                        source: UserId::from(PrincipalId::new_anonymous()),
+                        receiver,
+                        method_name,
+                        method_payload,
+                        ingress_expiry: expiry_time_from_now().as_nanos_since_unix_epoch(),
+                        nonce: Some(nonce.to_le_bytes().to_vec()),
+                    },
+                    reply_format,
+                ))
+            }
+            ["create"] => parse_create(nonce),
+            ["install", canister_id, wasm_file, payload] => {
+                parse_install(nonce, canister_id, payload, wasm_file, "install")
+            }
+            ["reinstall", canister_id, wasm_file, payload] => {
+                parse_install(nonce, canister_id, payload, wasm_file, "reinstall")
+            }
+            ["upgrade", canister_id, wasm_file, payload] => {
+                parse_install(nonce, canister_id, payload, wasm_file, "upgrade")
+            }
+            _ => Err(format!(
+                "Failed to parse line {}, don't have a pattern to match this with",
+                s
+            )),
         }
-        _ => Err(format!(
-            "Failed to parse line {}, don't have a pattern to match this with",
-            s
-        )),
+    }
+
+    /// Encodes the payload of a call to the given method and determines how
+    /// the reply should be decoded.
+    ///
+    /// Candid payloads are type-checked against the interface of the
+    /// canister, if one was declared.
+    fn parse_method_payload(
+        &self,
+        canister_id: &CanisterId,
+        method_name: &str,
+        payload: &str,
+    ) -> Result<(Vec<u8>, ReplyFormat), String> {
+        let method = match self.interfaces.get(canister_id) {
+            Some(interface) => {
+                let method = interface
+                    .env
+                    .get_method(&interface.service, method_name)
+                    .map_err(|e| format!("Method {} not found in interface: {}", method_name, e))?;
+                Some((interface, method))
+            }
+            None => None,
+        };
+
+        let method_payload = if is_candid_text(payload) {
+            let args = parse_candid_args(payload)?;
+            match &method {
+                Some((interface, method)) => args
+                    .to_bytes_with_types(&interface.env, &method.args)
+                    .map_err(|e| format!("Arguments do not match the interface: {}", e))?,
+                None => args
+                    .to_bytes()
+                    .map_err(|e| format!("Failed to encode Candid arguments: {}", e))?,
+            }
+        } else {
+            parse_octet_string(payload)?
+        };
+
+        let reply_format = match method {
+            Some((interface, method)) => ReplyFormat::Candid(Some(MethodReturnTypes {
+                env: Arc::clone(&interface.env),
+                types: method.rets.clone(),
+            })),
+            None if is_candid_text(payload) => ReplyFormat::Candid(None),
+            None => ReplyFormat::Raw,
+        };
+        Ok((method_payload, reply_format))
     }
 }
 
@@ -257,6 +401,55 @@ fn parse_install(
     Ok(Message::Install(signed_ingress))
 }
 
+fn load_candid_interface(did_file: &str) -> Result<CandidInterface, String> {
+    let (env, actor) = candid_parser::check_file(std::path::Path::new(did_file))
+        .map_err(|e| format!("Failed to load Candid interface {}: {}", did_file, e))?;
+    let service = match actor {
+        Some(actor) => match actor.as_ref() {
+            TypeInner::Class(_, service) => service.clone(),
+            _ => actor,
+        },
+        None => return Err(format!("{} does not declare a service", did_file)),
+    };
+    Ok(CandidInterface {
+        env: Arc::new(env),
+        service,
+    })
+}
+
+fn parse_expectation(s: &str) -> Result<Expectation, String> {
+    let tokens: Vec<&str> = s.splitn(3, char::is_whitespace).collect();
+
+    match &tokens[..] {
+        ["expect", "reply", payload] if is_candid_text(payload) => Ok(Expectation::Reply(
+            ExpectedReply::Candid(parse_candid_args(payload)?),
+        )),
+        ["expect", "reply", payload] => Ok(Expectation::Reply(ExpectedReply::Bytes(
+            parse_octet_string(payload)?,
+        ))),
+        ["expect", "reject"] => Ok(Expectation::Reject(None)),
+        ["expect", "reject", message] => {
+            let message = String::from_utf8(parse_quoted(message)?)
+                .map_err(|e| format!("Expected reject message is not UTF-8: {}", e))?;
+            Ok(Expectation::Reject(Some(message)))
+        }
+        _ => Err(format!(
+            "Failed to parse expectation {}, expected `expect reply <payload>` or `expect reject [<message>]`",
+            s
+        )),
+    }
+}
+
+/// Candid textual values are always enclosed in parentheses, e.g. `("hello", 42)`.
+fn is_candid_text(payload: &str) -> bool {
+    payload.starts_with('(')
+}
+
+fn parse_candid_args(payload: &str) -> Result<IDLArgs, String> {
+    candid_parser::parse_idl_args(payload)
+        .map_err(|e| format!("Failed to parse Candid arguments {}: {}", payload, e))
+}
+
 fn validate_method_name(method_name: &str) -> Result<String, String> {
     fn is_ident_start(c: char) -> bool {
         c.is_ascii() && (c.is_alphabetic() || c == '_')
@@ -371,6 +564,10 @@ mod tests {
     const APP_CANISTER_URL: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
     const APP_CANISTER_ID: u64 = 2;
 
+    fn parse_message(s: &str, nonce: u64) -> Result<Message, String> {
+        MessageParser::default().parse_message(s, nonce)
+    }
+
     #[test]
     fn test_parse_message_quoted_payload_succeeds() {
         let s = &format!(
@@ -379,7 +576,7 @@ mod tests {
         );
         let parsed_message = parse_message(s, 0).unwrap();
         let expiry_time = match &parsed_message {
-            Message::Ingress(signed_ingress) => signed_ingress.expiry_time(),
+            Message::Ingress(signed_ingress, _) => signed_ingress.expiry_time(),
             _ => panic!(
                 "parse_message() returned an unexpected message type: {:?}",
                 parsed_message
@@ -393,6 +590,7 @@ mod tests {
                 .nonce(0)
                 .expiry_time(expiry_time)
                 .build(),
+            ReplyFormat::Raw,
         );
         assert_eq!(expected, parsed_message);
     }
@@ -402,7 +600,7 @@ mod tests {
         let s = &format!("ingress {} write 0x010203", APP_CANISTER_URL);
         let parsed_message = parse_message(s, 0).unwrap();
         let expiry_time = match &parsed_message {
-            Message::Ingress(signed_ingress) => signed_ingress.expiry_time(),
+            Message::Ingress(signed_ingress, _) => signed_ingress.expiry_time(),
             _ => panic!(
                 "parse_message() returned an unexpected message type: {:?}",
                 parsed_message
@@ -416,6 +614,7 @@ mod tests {
                 .nonce(0)
                 .expiry_time(expiry_time)
                 .build(),
+            ReplyFormat::Raw,
         );
         assert_eq!(expected, parsed_message);
 
@@ -423,23 +622,117 @@ mod tests {
         let nonce: u64 = 0;
         let parsed_message = parse_message(s, 0).unwrap();
         let ingress_expiry = match &parsed_message {
-            Message::Query(query) => query.ingress_expiry,
+            Message::Query(query, _) => query.ingress_expiry,
             _ => panic!(
                 "parse_message() returned an unexpected message type: {:?}",
                 parsed_message
             ),
         };
-        let expected = Message::Query(UserQuery {
-            source: UserId::from(PrincipalId::new_anonymous()),
-            receiver: canister_test_id(APP_CANISTER_ID),
-            method_name: String::from("read"),
-            method_payload: vec![1, 2, 3],
-            ingress_expiry,
-            nonce: Some(nonce.to_le_bytes().to_vec()),
-        });
+        let expected = Message::Query(
+            UserQuery {
+                source: UserId::from(PrincipalId::new_anonymous()),
+                receiver: canister_test_id(APP_CANISTER_ID),
+                method_name: String::from("read"),
+                method_payload: vec![1, 2, 3],
+                ingress_expiry,
+                nonce: Some(nonce.to_le_bytes().to_vec()),
+            },
+            ReplyFormat::Raw,
+        );
         assert_eq!(expected, parsed_message);
     }
 
+    #[test]
+    fn test_parse_message_candid_payload_succeeds() {
+        let s = &format!("query {} greet (\"world\", 42 : nat)", APP_CANISTER_URL);
+        let parsed_message = parse_message(s, 0).unwrap();
+        match parsed_message {
+            Message::Query(query, reply_format) => {
+                assert_eq!(
+                    query.method_payload,
+                    candid::Encode!(&"world", &candid::Nat::from(42_u8)).unwrap()
+                );
+                assert_eq!(reply_format, ReplyFormat::Candid(None));
+            }
+            _ => panic!(
+                "parse_message() returned an unexpected message type: {:?}",
+                parsed_message
+            ),
+        }
+
+        let s = &format!("query {} greet (\"unterminated)", APP_CANISTER_URL);
+        assert!(parse_message(s, 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_message_checks_candid_interface() {
+        let did_file = std::env::temp_dir().join(format!("drun_test_{}.did", std::process::id()));
+        std::fs::write(
+            &did_file,
+            "service : { greet : (text, nat) -> (text) query }",
+        )
+        .unwrap();
+
+        let mut parser = MessageParser::default();
+        let did_line = format!("did {} {}", APP_CANISTER_URL, did_file.display());
+        assert_eq!(parser.parse_line(&did_line, 0), Ok(None));
+        std::fs::remove_file(&did_file).unwrap();
+
+        // Untyped numbers are encoded with the types of the interface.
+        let s = &format!("query {} greet (\"world\", 42)", APP_CANISTER_URL);
+        match parser.parse_line(s, 1).unwrap().unwrap() {
+            Message::Query(query, ReplyFormat::Candid(Some(rets))) => {
+                assert_eq!(
+                    query.method_payload,
+                    candid::Encode!(&"world", &candid::Nat::from(42_u8)).unwrap()
+                );
+                assert_eq!(rets.types, vec![candid::types::TypeInner::Text.into()]);
+            }
+            msg => panic!("parse_line() returned an unexpected message: {:?}", msg),
+        }
+
+        let s = &format!("query {} greet (42)", APP_CANISTER_URL);
+        assert!(parser.parse_line(s, 2).is_err());
+        let s = &format!("query {} unknown (42)", APP_CANISTER_URL);
+        assert!(parser.parse_line(s, 3).is_err());
+    }
+
+    #[test]
+    fn test_parse_expectation() {
+        let mut parser = MessageParser::default();
+        assert_eq!(
+            parser.parse_line("expect reply (\"hello\")", 4),
+            Ok(Some(Message::Expect {
+                expectation: Expectation::Reply(ExpectedReply::Candid(
+                    parse_candid_args("(\"hello\")").unwrap()
+                )),
+                line: 5,
+            }))
+        );
+        assert_eq!(
+            parser.parse_line("expect reply 0x0102", 0),
+            Ok(Some(Message::Expect {
+                expectation: Expectation::Reply(ExpectedReply::Bytes(vec![1, 2])),
+                line: 1,
+            }))
+        );
+        assert_eq!(
+            parser.parse_line("expect reject", 0),
+            Ok(Some(Message::Expect {
+                expectation: Expectation::Reject(None),
+                line: 1,
+            }))
+        );
+        assert_eq!(
+            parser.parse_line("expect reject \"trapped\"", 0),
+            Ok(Some(Message::Expect {
+                expectation: Expectation::Reject(Some("trapped".to_string())),
+                line: 1,
+            }))
+        );
+        assert!(parser.parse_line("expect something", 0).is_err());
+    }
+
     #[test]
     fn test_parse_message_invalid_escapes_fails() {
         let s = &format!("query {} read \"\\xzz\"", APP_CANISTER_URL);
@@ -51,6 +51,7 @@ pub fn transform_exported_globals<T>(exported_globals: Vec<(GlobalType, T)>) ->
             ValType::I64 => Some(Global::I64(0_i64)),
             ValType::F32 => Some(Global::F32(0 as f32)),
             ValType::F64 => Some(Global::F64(0 as f64)),
+            ValType::V128 => Some(Global::V128(0_u128)),
             _ => None,
         })
         .collect();
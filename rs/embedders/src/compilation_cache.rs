@@ -1,48 +1,319 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
 
 use crate::SerializedModule;
+use ic_crypto_sha2::Sha256;
 use ic_interfaces::execution_environment::HypervisorResult;
-use ic_types::NumBytes;
+use ic_types::{CountBytes, NumBytes};
 use ic_utils_lru_cache::LruCache;
 use ic_wasm_types::{CanisterModule, WasmHash};
 
+/// Version of the format of the files in the on-disk cache. It has to be
+/// bumped whenever the file layout or the `SerializedModule` type changes.
+const DISK_CACHE_FORMAT_VERSION: u32 = 2;
+
+/// Name of the subdirectory of the configured directory that is owned by the
+/// on-disk cache. Nothing outside of it is ever modified or removed.
+const DISK_CACHE_DIR_NAME: &str = "wasm_compilation_cache";
+
+const TMP_FILE_EXTENSION: &str = "tmp";
+
+/// Size of the checksum stored in front of every file in the on-disk cache.
+const CHECKSUM_SIZE: usize = 32;
+
 /// Stores the serialized modules of wasm code that has already been compiled so
 /// that it can be used again without recompiling.
+///
+/// The cache can optionally be backed by a directory on disk so that the
+/// compiled modules survive replica restarts. Entries evicted from memory
+/// are then loaded back from disk lazily on the next lookup.
 pub struct CompilationCache {
     cache: Mutex<LruCache<WasmHash, HypervisorResult<Arc<SerializedModule>>>>,
+    disk_cache: Option<DiskCache>,
 }
 
 impl CompilationCache {
     pub fn new(capacity: NumBytes) -> Self {
         Self {
             cache: Mutex::new(LruCache::new(capacity)),
+            disk_cache: None,
         }
     }
 
+    /// Creates a cache that persists the successfully compiled modules in a
+    /// dedicated subdirectory of `dir`. The entries are stored in a further
+    /// subdirectory named after `version`, which must change whenever the
+    /// compiled code may change, e.g. on a new replica binary or embedder
+    /// configuration. Any data left by other versions is removed.
+    pub fn new_with_disk_cache(
+        capacity: NumBytes,
+        dir: &Path,
+        version: &str,
+        max_disk_size: NumBytes,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            cache: Mutex::new(LruCache::new(capacity)),
+            disk_cache: Some(DiskCache::new(dir, version, max_disk_size)?),
+        })
+    }
+
     pub fn insert(
         &self,
         canister_module: &CanisterModule,
         serialized_module: HypervisorResult<Arc<SerializedModule>>,
     ) {
+        let wasm_hash = WasmHash::from(canister_module);
+        // Compilation errors are cheap to reproduce, so only the successfully
+        // compiled modules are persisted.
+        if let (Some(disk_cache), Ok(serialized_module)) = (&self.disk_cache, &serialized_module) {
+            disk_cache.insert(&wasm_hash, serialized_module);
+        }
         self.cache
             .lock()
             .unwrap()
-            .push(WasmHash::from(canister_module), serialized_module);
+            .push(wasm_hash, serialized_module);
     }
 
     pub fn get(
         &self,
         canister_module: &CanisterModule,
     ) -> Option<HypervisorResult<Arc<SerializedModule>>> {
+        let wasm_hash = WasmHash::from(canister_module);
+        let result = self
+            .cache
+            .lock()
+            .unwrap()
+            .get(&wasm_hash)
+            .map(|o| o.as_ref().map(Arc::clone).map_err(|e| e.clone()));
+        if result.is_some() {
+            return result;
+        }
+
+        // The lock is not held while reading from disk. In the worst case
+        // a concurrent lookup loads the same module twice.
+        let serialized_module = self.disk_cache.as_ref()?.get(&wasm_hash)?;
         self.cache
             .lock()
             .unwrap()
-            .get(&WasmHash::from(canister_module))
-            .map(|o| o.as_ref().map(Arc::clone).map_err(|e| e.clone()))
+            .push(wasm_hash, Ok(Arc::clone(&serialized_module)));
+        Some(Ok(serialized_module))
+    }
+
+    /// Removes the entry of the given module, e.g. because the cached module
+    /// could not be deserialized.
+    pub fn remove(&self, canister_module: &CanisterModule) {
+        let wasm_hash = WasmHash::from(canister_module);
+        self.cache.lock().unwrap().pop(&wasm_hash);
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.remove(&wasm_hash);
+        }
     }
 
     #[doc(hidden)]
     pub fn clear_for_testing(&self) {
-        self.cache.lock().unwrap().clear()
+        self.cache.lock().unwrap().clear();
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.clear();
+        }
+    }
+}
+
+/// Size of a file in the on-disk cache.
+struct FileSize(usize);
+
+impl CountBytes for FileSize {
+    fn count_bytes(&self) -> usize {
+        self.0
+    }
+}
+
+/// A directory with one file per compiled module, named after the hex-encoded
+/// `WasmHash` of the module. Every file starts with the SHA-256 checksum of
+/// the rest of the file, followed by the `WasmHash` it was written for and
+/// the serialized module. Both are checked when the file is loaded, before
+/// the module is handed to wasmtime.
+///
+/// All I/O errors after the creation are ignored: an entry that can't be
+/// written or read is simply missing and the module gets recompiled.
+struct DiskCache {
+    dir: PathBuf,
+    // Tracks the files in the order in which they were used to keep the total
+    // size of the directory bounded.
+    files: Mutex<LruCache<WasmHash, FileSize>>,
+    // Used to generate unique names for temporary files.
+    next_tmp_file: AtomicU64,
+}
+
+impl DiskCache {
+    fn new(dir: &Path, version: &str, max_size: NumBytes) -> io::Result<Self> {
+        let version = format!("v{}-{}", DISK_CACHE_FORMAT_VERSION, version);
+        let dir = dir.join(DISK_CACHE_DIR_NAME);
+        fs::create_dir_all(&dir)?;
+
+        // Everything that is not the current version is stale.
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.file_name().to_str() == Some(version.as_str()) {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                fs::remove_dir_all(entry.path())?;
+            } else {
+                fs::remove_file(entry.path())?;
+            }
+        }
+
+        let dir = dir.join(version);
+        fs::create_dir_all(&dir)?;
+
+        let mut existing = vec![];
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            match entry.file_name().to_str().and_then(parse_file_name) {
+                Some(wasm_hash) if metadata.is_file() => {
+                    existing.push((metadata.modified()?, wasm_hash, metadata.len() as usize));
+                }
+                // Leftovers of interrupted writes or unknown files.
+                _ => fs::remove_file(entry.path())?,
+            }
+        }
+        // Older files are pushed first so that they are evicted first.
+        existing.sort();
+
+        let cache = Self {
+            dir,
+            files: Mutex::new(LruCache::new(max_size)),
+            next_tmp_file: AtomicU64::new(0),
+        };
+        for (_, wasm_hash, size) in existing {
+            cache.track(wasm_hash, size);
+        }
+        Ok(cache)
+    }
+
+    fn path(&self, wasm_hash: &WasmHash) -> PathBuf {
+        self.dir.join(file_name(wasm_hash))
+    }
+
+    fn get(&self, wasm_hash: &WasmHash) -> Option<Arc<SerializedModule>> {
+        // Also marks the entry as recently used.
+        self.files.lock().unwrap().get(wasm_hash)?;
+
+        let result = fs::read(self.path(wasm_hash))
+            .ok()
+            .filter(|bytes| {
+                bytes.len() >= CHECKSUM_SIZE
+                    && bytes[..CHECKSUM_SIZE] == Sha256::hash(&bytes[CHECKSUM_SIZE..])
+            })
+            .and_then(|bytes| {
+                bincode::deserialize::<(_, SerializedModule)>(&bytes[CHECKSUM_SIZE..]).ok()
+            })
+            .filter(|(hash, _)| *hash == wasm_hash.to_slice());
+
+        match result {
+            Some((_, serialized_module)) => Some(Arc::new(serialized_module)),
+            None => {
+                // The file is missing or corrupted.
+                self.remove(wasm_hash);
+                None
+            }
+        }
+    }
+
+    fn insert(&self, wasm_hash: &WasmHash, serialized_module: &SerializedModule) {
+        if self.files.lock().unwrap().get(wasm_hash).is_some() {
+            return;
+        }
+
+        let payload = match bincode::serialize(&(wasm_hash.to_slice(), serialized_module)) {
+            Ok(payload) => payload,
+            Err(_) => return,
+        };
+        let mut bytes = Sha256::hash(&payload).to_vec();
+        bytes.extend_from_slice(&payload);
+
+        // The file is written under a temporary name and then renamed so that
+        // an interrupted write never leaves a truncated entry behind. The data
+        // is not synced: a file damaged by a crash fails the checksum and is
+        // recompiled.
+        let tmp_path = self.dir.join(format!(
+            "{}.{}.{}",
+            file_name(wasm_hash),
+            self.next_tmp_file.fetch_add(1, Ordering::Relaxed),
+            TMP_FILE_EXTENSION,
+        ));
+        let written = fs::File::create(&tmp_path)
+            .and_then(|mut file| file.write_all(&bytes))
+            .and_then(|()| fs::rename(&tmp_path, self.path(wasm_hash)));
+
+        match written {
+            Ok(()) => self.track(wasm_hash.clone(), bytes.len()),
+            Err(_) => {
+                let _ = fs::remove_file(&tmp_path);
+            }
+        }
+    }
+
+    /// Starts tracking a file and removes the files that don't fit anymore.
+    fn track(&self, wasm_hash: WasmHash, size: usize) {
+        let evicted = self
+            .files
+            .lock()
+            .unwrap()
+            .push(wasm_hash.clone(), FileSize(size));
+        for (evicted_hash, _) in evicted {
+            // The old entry of the same file is returned when it's replaced.
+            if evicted_hash != wasm_hash {
+                let _ = fs::remove_file(self.path(&evicted_hash));
+            }
+        }
+    }
+
+    fn remove(&self, wasm_hash: &WasmHash) {
+        self.files.lock().unwrap().pop(wasm_hash);
+        let _ = fs::remove_file(self.path(wasm_hash));
+    }
+
+    fn clear(&self) {
+        let mut files = self.files.lock().unwrap();
+        if let Ok(entries) = fs::read_dir(&self.dir) {
+            for entry in entries.flatten() {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+        files.clear();
+    }
+}
+
+fn file_name(wasm_hash: &WasmHash) -> String {
+    wasm_hash
+        .to_slice()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn parse_file_name(name: &str) -> Option<WasmHash> {
+    if !name.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
     }
+    let bytes = (0..name.len())
+        .step_by(2)
+        .map(|i| {
+            name.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    // Only the canonical names written by `file_name` are accepted.
+    WasmHash::try_from(bytes)
+        .ok()
+        .filter(|wasm_hash| file_name(wasm_hash) == name)
 }
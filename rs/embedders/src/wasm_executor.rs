@@ -8,7 +8,7 @@ use ic_replicated_state::{ExportedFunctions, Global, Memory, NumWasmPages, PageM
 use ic_system_api::sandbox_safe_system_state::{SandboxSafeSystemState, SystemStateChanges};
 use ic_system_api::{ApiType, DefaultOutOfInstructionsHandler};
 use ic_types::methods::{FuncRef, WasmMethod};
-use ic_types::NumPages;
+use ic_types::{canister_log::CanisterLog, NumPages};
 use prometheus::IntCounter;
 use serde::{Deserialize, Serialize};
 use wasmtime::Module;
@@ -378,42 +378,52 @@ impl WasmExecutorImpl {
         } else {
             match compilation_cache.get(&wasm_binary.binary) {
                 Some(Ok(serialized_module)) => {
-                    let instance_pre = self
+                    match self
                         .wasm_embedder
-                        .deserialize_module_and_pre_instantiate(&serialized_module.bytes);
-                    let cache = EmbedderCache::new(instance_pre.clone());
-                    *guard = Some(cache.clone());
-                    match instance_pre {
-                        Ok(_) => Ok(CacheLookup {
-                            cache,
-                            serialized_module: Some(serialized_module),
-                            compilation_result: None,
-                        }),
-                        Err(err) => Err(err),
+                        .deserialize_module_and_pre_instantiate(&serialized_module.bytes)
+                    {
+                        Ok(instance_pre) => {
+                            let cache = EmbedderCache::new(Ok(instance_pre));
+                            *guard = Some(cache.clone());
+                            return Ok(CacheLookup {
+                                cache,
+                                serialized_module: Some(serialized_module),
+                                compilation_result: None,
+                            });
+                        }
+                        Err(err) => {
+                            // The cached module may have been loaded from disk,
+                            // so the failure need not happen on other replicas.
+                            // Recompile instead of failing the execution.
+                            warn!(
+                                self.log,
+                                "Failed to deserialize a cached module, recompiling it: {}", err
+                            );
+                            compilation_cache.remove(&wasm_binary.binary);
+                        }
                     }
                 }
                 Some(Err(err)) => {
                     let cache: HypervisorResult<Module> = Err(err.clone());
                     *guard = Some(EmbedderCache::new(cache));
-                    Err(err)
-                }
-                None => {
-                    use std::borrow::Cow;
-                    let decoded_wasm: Cow<'_, BinaryEncodedWasm> =
-                        Cow::Owned(decode_wasm(wasm_binary.binary.to_shared_vec())?);
-                    let (cache, result) = compile(&self.wasm_embedder, decoded_wasm.as_ref());
-                    *guard = Some(cache.clone());
-                    let (compilation_result, serialized_module) = result?;
-                    let serialized_module = Arc::new(serialized_module);
-                    compilation_cache
-                        .insert(&wasm_binary.binary, Ok(Arc::clone(&serialized_module)));
-                    Ok(CacheLookup {
-                        cache,
-                        serialized_module: Some(serialized_module),
-                        compilation_result: Some(compilation_result),
-                    })
+                    return Err(err);
                 }
+                None => {}
             }
+
+            use std::borrow::Cow;
+            let decoded_wasm: Cow<'_, BinaryEncodedWasm> =
+                Cow::Owned(decode_wasm(wasm_binary.binary.to_shared_vec())?);
+            let (cache, result) = compile(&self.wasm_embedder, decoded_wasm.as_ref());
+            *guard = Some(cache.clone());
+            let (compilation_result, serialized_module) = result?;
+            let serialized_module = Arc::new(serialized_module);
+            compilation_cache.insert(&wasm_binary.binary, Ok(Arc::clone(&serialized_module)));
+            Ok(CacheLookup {
+                cache,
+                serialized_module: Some(serialized_module),
+                compilation_result: Some(compilation_result),
+            })
         }
     }
 
@@ -455,6 +465,7 @@ pub fn wasm_execution_error(
             allocated_message_bytes: NumBytes::from(0),
             instance_stats: InstanceStats::default(),
             system_api_call_counters: SystemApiCallCounters::default(),
+            canister_log: CanisterLog::default(),
         },
         None,
     )
@@ -619,6 +630,7 @@ pub fn process(
                     allocated_message_bytes: NumBytes::from(0),
                     instance_stats: InstanceStats::default(),
                     system_api_call_counters: SystemApiCallCounters::default(),
+                    canister_log: CanisterLog::default(),
                 },
                 None,
                 Err(system_api.unwrap()), // should be safe because we've passed Some(api) to new_instance
@@ -640,6 +652,7 @@ pub fn process(
     //unwrap should not fail, because we have passed Some(system_api) to the instance above
     let system_api = instance.store_data_mut().system_api_mut().unwrap();
     let system_api_call_counters = system_api.call_counters();
+    let canister_log = system_api.take_canister_log();
     let slice_instruction_limit = system_api.slice_instruction_limit();
     // Capping at the limit to preserve the existing behaviour. It should be
     // possible to remove capping after ensuring that all callers can handle
@@ -676,6 +689,7 @@ pub fn process(
                         allocated_message_bytes: NumBytes::from(0),
                         instance_stats,
                         system_api_call_counters,
+                        canister_log,
                     },
                     None,
                     Ok(instance),
@@ -780,6 +794,7 @@ pub fn process(
             allocated_message_bytes,
             instance_stats,
             system_api_call_counters,
+            canister_log,
         },
         wasm_state_changes,
         Ok(instance),
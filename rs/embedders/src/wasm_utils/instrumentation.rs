@@ -173,7 +173,7 @@ pub fn instruction_to_cost(i: &Operator) -> u64 {
 // Gets the cost of an instruction.
 pub fn instruction_to_cost_new(i: &Operator) -> u64 {
     // This aims to be a complete list of all instructions that can be executed, with certain exceptions.
-    // The exceptions are: SIMD instructions, atomic instructions, and the dynamic cost of
+    // The exceptions are: atomic instructions, and the dynamic cost of
     // of operations such as table/memory fill, copy, init. This
     // dynamic cost is treated separately. Here we only assign a static cost to these instructions.
     match i {
@@ -450,6 +450,155 @@ pub fn instruction_to_cost_new(i: &Operator) -> u64 {
         // translated to memory manipulation. Validated in benchmarks.
         Operator::RefFunc { .. } => 130,
 
+        // SIMD instructions are assigned the cost of their scalar
+        // counterparts, since most of them compile to a single vector
+        // instruction that operates on all lanes at once.
+
+        // All SIMD load/store instructions are of cost 1, same as scalar loads/stores.
+        Operator::V128Load { .. }
+        | Operator::V128Load8x8S { .. }
+        | Operator::V128Load8x8U { .. }
+        | Operator::V128Load16x4S { .. }
+        | Operator::V128Load16x4U { .. }
+        | Operator::V128Load32x2S { .. }
+        | Operator::V128Load32x2U { .. }
+        | Operator::V128Load8Splat { .. }
+        | Operator::V128Load16Splat { .. }
+        | Operator::V128Load32Splat { .. }
+        | Operator::V128Load64Splat { .. }
+        | Operator::V128Load32Zero { .. }
+        | Operator::V128Load64Zero { .. }
+        | Operator::V128Load8Lane { .. }
+        | Operator::V128Load16Lane { .. }
+        | Operator::V128Load32Lane { .. }
+        | Operator::V128Load64Lane { .. }
+        | Operator::V128Store { .. }
+        | Operator::V128Store8Lane { .. }
+        | Operator::V128Store16Lane { .. }
+        | Operator::V128Store32Lane { .. }
+        | Operator::V128Store64Lane { .. } => 1,
+
+        // Constants, lane accesses, splats and bitwise operations are of cost 1.
+        Operator::V128Const { .. }
+        | Operator::I8x16ExtractLaneS { .. }
+        | Operator::I8x16ExtractLaneU { .. }
+        | Operator::I8x16ReplaceLane { .. }
+        | Operator::I16x8ExtractLaneS { .. }
+        | Operator::I16x8ExtractLaneU { .. }
+        | Operator::I16x8ReplaceLane { .. }
+        | Operator::I32x4ExtractLane { .. }
+        | Operator::I32x4ReplaceLane { .. }
+        | Operator::I64x2ExtractLane { .. }
+        | Operator::I64x2ReplaceLane { .. }
+        | Operator::F32x4ExtractLane { .. }
+        | Operator::F32x4ReplaceLane { .. }
+        | Operator::F64x2ExtractLane { .. }
+        | Operator::F64x2ReplaceLane { .. }
+        | Operator::I8x16Splat { .. }
+        | Operator::I16x8Splat { .. }
+        | Operator::I32x4Splat { .. }
+        | Operator::I64x2Splat { .. }
+        | Operator::F32x4Splat { .. }
+        | Operator::F64x2Splat { .. }
+        | Operator::V128Not { .. }
+        | Operator::V128And { .. }
+        | Operator::V128AndNot { .. }
+        | Operator::V128Or { .. }
+        | Operator::V128Xor { .. }
+        | Operator::V128Bitselect { .. }
+        | Operator::V128AnyTrue { .. } => 1,
+
+        // Shuffles are more expensive because they may require
+        // several instructions depending on the lane pattern.
+        Operator::I8x16Shuffle { .. } => 3,
+        Operator::I8x16Swizzle { .. } => 1,
+
+        // Integer multiplications are of cost 2, except the 64-bit lane
+        // multiplication, which is emulated on most CPUs.
+        Operator::I16x8Mul { .. }
+        | Operator::I32x4Mul { .. }
+        | Operator::I16x8Q15MulrSatS { .. }
+        | Operator::I32x4DotI16x8S { .. }
+        | Operator::I16x8ExtMulLowI8x16S { .. }
+        | Operator::I16x8ExtMulHighI8x16S { .. }
+        | Operator::I16x8ExtMulLowI8x16U { .. }
+        | Operator::I16x8ExtMulHighI8x16U { .. }
+        | Operator::I32x4ExtMulLowI16x8S { .. }
+        | Operator::I32x4ExtMulHighI16x8S { .. }
+        | Operator::I32x4ExtMulLowI16x8U { .. }
+        | Operator::I32x4ExtMulHighI16x8U { .. }
+        | Operator::I64x2ExtMulLowI32x4S { .. }
+        | Operator::I64x2ExtMulHighI32x4S { .. }
+        | Operator::I64x2ExtMulLowI32x4U { .. }
+        | Operator::I64x2ExtMulHighI32x4U { .. } => 2,
+        Operator::I64x2Mul { .. } => 10,
+
+        // Population count is emulated on most CPUs.
+        Operator::I8x16Popcnt { .. } => 3,
+
+        // Floating point arithmetic is of cost 20, same as scalar floating point arithmetic.
+        Operator::F32x4Add { .. }
+        | Operator::F32x4Sub { .. }
+        | Operator::F32x4Mul { .. }
+        | Operator::F32x4Div { .. }
+        | Operator::F32x4Min { .. }
+        | Operator::F32x4Max { .. }
+        | Operator::F32x4Ceil { .. }
+        | Operator::F32x4Floor { .. }
+        | Operator::F32x4Trunc { .. }
+        | Operator::F32x4Nearest { .. }
+        | Operator::F32x4Sqrt { .. }
+        | Operator::F64x2Add { .. }
+        | Operator::F64x2Sub { .. }
+        | Operator::F64x2Mul { .. }
+        | Operator::F64x2Div { .. }
+        | Operator::F64x2Min { .. }
+        | Operator::F64x2Max { .. }
+        | Operator::F64x2Ceil { .. }
+        | Operator::F64x2Floor { .. }
+        | Operator::F64x2Trunc { .. }
+        | Operator::F64x2Nearest { .. }
+        | Operator::F64x2Sqrt { .. } => 20,
+
+        Operator::F32x4Abs { .. }
+        | Operator::F32x4Neg { .. }
+        | Operator::F64x2Abs { .. }
+        | Operator::F64x2Neg { .. } => 2,
+
+        // Floating point comparisons and pseudo min/max (which are implemented as
+        // a comparison followed by a select) are of cost 3.
+        Operator::F32x4Eq { .. }
+        | Operator::F32x4Ne { .. }
+        | Operator::F32x4Lt { .. }
+        | Operator::F32x4Gt { .. }
+        | Operator::F32x4Le { .. }
+        | Operator::F32x4Ge { .. }
+        | Operator::F64x2Eq { .. }
+        | Operator::F64x2Ne { .. }
+        | Operator::F64x2Lt { .. }
+        | Operator::F64x2Gt { .. }
+        | Operator::F64x2Le { .. }
+        | Operator::F64x2Ge { .. }
+        | Operator::F32x4PMin { .. }
+        | Operator::F32x4PMax { .. }
+        | Operator::F64x2PMin { .. }
+        | Operator::F64x2PMax { .. } => 3,
+
+        // Conversions between integers and floats are of cost 20, same as
+        // the scalar truncations. Promote and demote are of cost 1.
+        Operator::I32x4TruncSatF32x4S { .. }
+        | Operator::I32x4TruncSatF32x4U { .. }
+        | Operator::I32x4TruncSatF64x2SZero { .. }
+        | Operator::I32x4TruncSatF64x2UZero { .. }
+        | Operator::F32x4ConvertI32x4S { .. }
+        | Operator::F32x4ConvertI32x4U { .. }
+        | Operator::F64x2ConvertLowI32x4S { .. }
+        | Operator::F64x2ConvertLowI32x4U { .. } => 20,
+        Operator::F32x4DemoteF64x2Zero { .. } | Operator::F64x2PromoteLowF32x4 { .. } => 1,
+
+        // The remaining integer SIMD instructions (arithmetic, comparisons, shifts,
+        // narrowing and extending) are covered by the default cost of 1.
+
         // Default cost of an instruction is 1.
         _ => 1,
     }
@@ -3,7 +3,7 @@
 
 use super::{Complexity, WasmImportsDetails, WasmValidationDetails};
 
-use ic_config::embedders::Config as EmbeddersConfig;
+use ic_config::{embedders::Config as EmbeddersConfig, flag_status::FlagStatus};
 use ic_replicated_state::canister_state::execution_state::{
     CustomSection, CustomSectionType, WasmMetadata,
 };
@@ -282,6 +282,16 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 },
             )],
         ),
+        (
+            "call_with_best_effort_response",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ValType::I32],
+                    return_type: vec![],
+                },
+            )],
+        ),
         // Debugging aids
         (
             "debug_print",
@@ -603,6 +613,46 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 },
             )],
         ),
+        (
+            "cost_call",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ValType::I64, ValType::I64, ValType::I32],
+                    return_type: vec![],
+                },
+            )],
+        ),
+        (
+            "cost_create_canister",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ValType::I32],
+                    return_type: vec![],
+                },
+            )],
+        ),
+        (
+            "cost_http_request",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ValType::I64, ValType::I64, ValType::I32],
+                    return_type: vec![],
+                },
+            )],
+        ),
+        (
+            "cost_sign_with_ecdsa",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ValType::I32, ValType::I32, ValType::I32, ValType::I32],
+                    return_type: vec![ValType::I32],
+                },
+            )],
+        ),
     ];
 
     valid_system_apis
@@ -740,14 +790,22 @@ fn set_imports_details(import_details: &mut WasmImportsDetails, import_module: &
 // * If we import memory or table, we can only import from “env”.
 // * Any imported functions that appear in `valid_system_apis` have the correct
 //   signatures.
+// * `ic0.call_with_best_effort_response` is only imported if best-effort
+//   responses are enabled.
 //
 // Returns information about what IC0 methods are imported via
 // `WasmImportsDetails`.
-fn validate_import_section(module: &Module) -> Result<WasmImportsDetails, WasmValidationError> {
+fn validate_import_section(
+    module: &Module,
+    config: &EmbeddersConfig,
+) -> Result<WasmImportsDetails, WasmValidationError> {
     let mut imports_details = WasmImportsDetails::default();
 
     if !module.imports.is_empty() {
-        let valid_system_apis = get_valid_system_apis();
+        let mut valid_system_apis = get_valid_system_apis();
+        if config.feature_flags.best_effort_responses == FlagStatus::Disabled {
+            valid_system_apis.remove("call_with_best_effort_response");
+        }
         for entry in &module.imports {
             let import_module = entry.module;
             let field = entry.name;
@@ -1332,7 +1390,8 @@ pub fn wasmtime_validation_config(embedder_config: &EmbeddersConfig) -> wasmtime
     // Keep this in the alphabetical order to simplify comparison with new
     // `wasmtime::Config` methods in a new version of wasmtime.
 
-    // NaN canonicalization is needed for determinism.
+    // NaN canonicalization is needed for determinism. It applies to both
+    // scalar and SIMD floating-point operations.
     config.cranelift_nan_canonicalization(true);
     // Disable optimizations to keep compilation simple and fast.
     // The assumption is that Wasm binaries have already been optimized.
@@ -1351,9 +1410,12 @@ pub fn wasmtime_validation_config(embedder_config: &EmbeddersConfig) -> wasmtime
     config.wasm_memory64(false);
     config.wasm_multi_memory(false);
     config.wasm_reference_types(true);
-    // The SIMD instructions are disable for determinism.
+    // Relaxed SIMD instructions have implementation-defined results, so they
+    // are disabled for determinism.
     config.wasm_relaxed_simd(false);
-    config.wasm_simd(false);
+    // Fixed-width SIMD instructions are deterministic given the NaN
+    // canonicalization above.
+    config.wasm_simd(true);
     // Tail calls may be enabled in the future.
     config.wasm_tail_call(false);
     // Threads are disabled for determinism.
@@ -1439,7 +1501,7 @@ pub(super) fn validate_wasm_binary<'a>(
     can_compile(wasm, config)?;
     let module = Module::parse(wasm.as_slice(), false)
         .map_err(|err| WasmValidationError::DecodingError(format!("{}", err)))?;
-    let imports_details = validate_import_section(&module)?;
+    let imports_details = validate_import_section(&module, config)?;
     validate_export_section(
         &module,
         config.max_number_exported_functions,
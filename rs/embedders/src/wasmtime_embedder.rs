@@ -395,6 +395,7 @@ impl WasmtimeEmbedder {
                                 Global::I64(val) => Val::I64(*val),
                                 Global::F32(val) => Val::F32((val).to_bits()),
                                 Global::F64(val) => Val::F64((val).to_bits()),
+                                Global::V128(val) => Val::V128((*val).into()),
                             },
                         )
                         .unwrap_or_else(|e| {
@@ -403,6 +404,7 @@ impl WasmtimeEmbedder {
                                 Global::I64(val) => (val).to_string(),
                                 Global::F32(val) => (val).to_string(),
                                 Global::F64(val) => (val).to_string(),
+                                Global::V128(val) => (val).to_string(),
                             };
                             fatal!(
                                 self.log,
@@ -1106,6 +1108,12 @@ impl WasmtimeInstance {
                 ValType::F64 => Ok(Global::F64(
                     g.get(&mut self.store).f64().expect("global f64"),
                 )),
+                ValType::V128 => Ok(Global::V128(
+                    g.get(&mut self.store)
+                        .v128()
+                        .expect("global v128")
+                        .as_u128(),
+                )),
                 _ => Err(HypervisorError::WasmEngineError(WasmEngineError::Other(
                     "unexpected global value type".to_string(),
                 ))),
@@ -586,9 +586,15 @@ pub(crate) fn syscalls(
                     caller.data().system_api.as_ref().unwrap().subnet_type(),
                     feature_flags.rate_limiting_of_debug_prints,
                 ) {
-                    // Debug print is a no-op on non-system subnets with rate limiting.
-                    (SubnetType::Application, FlagStatus::Enabled) => Ok(()),
-                    (SubnetType::VerifiedApplication, FlagStatus::Enabled) => Ok(()),
+                    // Debug print does not produce output on non-system subnets with
+                    // rate limiting, but the message is still recorded in the canister log.
+                    (SubnetType::Application, FlagStatus::Enabled)
+                    | (SubnetType::VerifiedApplication, FlagStatus::Enabled) => {
+                        with_memory_and_system_api(&mut caller, |system_api, memory| {
+                            system_api.save_log_message(offset, length, memory);
+                            Ok(())
+                        })
+                    }
                     // If rate limiting is disabled or the subnet is a system subnet, then
                     // debug print produces output.
                     (_, FlagStatus::Disabled) | (SubnetType::System, FlagStatus::Enabled) => {
@@ -669,6 +675,20 @@ pub(crate) fn syscalls(
         })
         .unwrap();
 
+    linker
+        .func_wrap("ic0", "call_with_best_effort_response", {
+            move |mut caller: Caller<'_, StoreData>, timeout_seconds: u32| {
+                charge_for_cpu(
+                    &mut caller,
+                    overhead!(CALL_WITH_BEST_EFFORT_RESPONSE, metering_type),
+                )?;
+                with_system_api(&mut caller, |s| {
+                    s.ic0_call_with_best_effort_response(timeout_seconds)
+                })
+            }
+        })
+        .unwrap();
+
     linker
         .func_wrap("ic0", "call_cycles_add", {
             move |mut caller: Caller<'_, StoreData>, amount: u64| {
@@ -1168,6 +1188,84 @@ pub(crate) fn syscalls(
         })
         .unwrap();
 
+    linker
+        .func_wrap("ic0", "cost_call", {
+            move |mut caller: Caller<'_, StoreData>,
+                  method_name_size: u64,
+                  payload_size: u64,
+                  dst: u32| {
+                charge_for_cpu(&mut caller, overhead!(COST_CALL, metering_type))?;
+                with_memory_and_system_api(&mut caller, |s, memory| {
+                    s.ic0_cost_call(method_name_size, payload_size, dst, memory)
+                })?;
+                if feature_flags.write_barrier == FlagStatus::Enabled {
+                    mark_writes_on_bytemap(&mut caller, dst as usize, 16)
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("ic0", "cost_create_canister", {
+            move |mut caller: Caller<'_, StoreData>, dst: u32| {
+                charge_for_cpu(&mut caller, overhead!(COST_CREATE_CANISTER, metering_type))?;
+                with_memory_and_system_api(&mut caller, |s, memory| {
+                    s.ic0_cost_create_canister(dst, memory)
+                })?;
+                if feature_flags.write_barrier == FlagStatus::Enabled {
+                    mark_writes_on_bytemap(&mut caller, dst as usize, 16)
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("ic0", "cost_http_request", {
+            move |mut caller: Caller<'_, StoreData>,
+                  request_size: u64,
+                  max_res_bytes: u64,
+                  dst: u32| {
+                charge_for_cpu(&mut caller, overhead!(COST_HTTP_REQUEST, metering_type))?;
+                with_memory_and_system_api(&mut caller, |s, memory| {
+                    s.ic0_cost_http_request(request_size, max_res_bytes, dst, memory)
+                })?;
+                if feature_flags.write_barrier == FlagStatus::Enabled {
+                    mark_writes_on_bytemap(&mut caller, dst as usize, 16)
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("ic0", "cost_sign_with_ecdsa", {
+            move |mut caller: Caller<'_, StoreData>,
+                  src: u32,
+                  size: u32,
+                  curve: u32,
+                  dst: u32|
+                  -> Result<u32, anyhow::Error> {
+                charge_for_cpu_and_mem(
+                    &mut caller,
+                    overhead!(COST_SIGN_WITH_ECDSA, metering_type),
+                    size as u64,
+                )?;
+                let result = with_memory_and_system_api(&mut caller, |s, memory| {
+                    s.ic0_cost_sign_with_ecdsa(src, size, curve, dst, memory)
+                })?;
+                if feature_flags.write_barrier == FlagStatus::Enabled {
+                    mark_writes_on_bytemap(&mut caller, dst as usize, 16)?;
+                }
+                Ok(result)
+            }
+        })
+        .unwrap();
+
     linker
         .func_wrap("__", "internal_trap", {
             move |mut caller: Caller<'_, StoreData>, err_code: i32| -> Result<(), _> {
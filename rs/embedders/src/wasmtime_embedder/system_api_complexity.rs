@@ -27,6 +27,7 @@ pub mod overhead {
         pub const CALL_NEW: NumInstructions = NumInstructions::new(0);
         pub const CALL_ON_CLEANUP: NumInstructions = NumInstructions::new(0);
         pub const CALL_PERFORM: NumInstructions = NumInstructions::new(0);
+        pub const CALL_WITH_BEST_EFFORT_RESPONSE: NumInstructions = NumInstructions::new(0);
         pub const CANISTER_CYCLE_BALANCE: NumInstructions = NumInstructions::new(0);
         pub const CANISTER_CYCLE_BALANCE128: NumInstructions = NumInstructions::new(0);
         pub const CANISTER_SELF_COPY: NumInstructions = NumInstructions::new(0);
@@ -34,6 +35,10 @@ pub mod overhead {
         pub const CANISTER_STATUS: NumInstructions = NumInstructions::new(0);
         pub const CANISTER_VERSION: NumInstructions = NumInstructions::new(0);
         pub const CERTIFIED_DATA_SET: NumInstructions = NumInstructions::new(0);
+        pub const COST_CALL: NumInstructions = NumInstructions::new(0);
+        pub const COST_CREATE_CANISTER: NumInstructions = NumInstructions::new(0);
+        pub const COST_HTTP_REQUEST: NumInstructions = NumInstructions::new(0);
+        pub const COST_SIGN_WITH_ECDSA: NumInstructions = NumInstructions::new(0);
         pub const CYCLES_BURN: NumInstructions = NumInstructions::new(100);
         pub const DATA_CERTIFICATE_COPY: NumInstructions = NumInstructions::new(0);
         pub const DATA_CERTIFICATE_PRESENT: NumInstructions = NumInstructions::new(0);
@@ -82,6 +87,7 @@ pub mod overhead {
         pub const CALL_NEW: NumInstructions = NumInstructions::new(1_500);
         pub const CALL_ON_CLEANUP: NumInstructions = NumInstructions::new(500);
         pub const CALL_PERFORM: NumInstructions = NumInstructions::new(5_000);
+        pub const CALL_WITH_BEST_EFFORT_RESPONSE: NumInstructions = NumInstructions::new(500);
         pub const CANISTER_CYCLE_BALANCE: NumInstructions = NumInstructions::new(500);
         pub const CANISTER_CYCLE_BALANCE128: NumInstructions = NumInstructions::new(500);
         pub const CANISTER_SELF_COPY: NumInstructions = NumInstructions::new(500);
@@ -91,6 +97,10 @@ pub mod overhead {
         pub const CERTIFIED_DATA_SET: NumInstructions = NumInstructions::new(500);
         pub const CONTROLLER_COPY: NumInstructions = NumInstructions::new(500);
         pub const CONTROLLER_SIZE: NumInstructions = NumInstructions::new(500);
+        pub const COST_CALL: NumInstructions = NumInstructions::new(500);
+        pub const COST_CREATE_CANISTER: NumInstructions = NumInstructions::new(500);
+        pub const COST_HTTP_REQUEST: NumInstructions = NumInstructions::new(500);
+        pub const COST_SIGN_WITH_ECDSA: NumInstructions = NumInstructions::new(500);
         pub const DATA_CERTIFICATE_COPY: NumInstructions = NumInstructions::new(500);
         pub const DATA_CERTIFICATE_PRESENT: NumInstructions = NumInstructions::new(500);
         pub const DATA_CERTIFICATE_SIZE: NumInstructions = NumInstructions::new(500);
@@ -151,7 +151,7 @@ fn test_wasmtime_system_api() {
 
 #[test]
 fn test_initial_wasmtime_config() {
-    // The following proposals should be disabled: tail_call, simd, relaxed_simd,
+    // The following proposals should be disabled: tail_call, relaxed_simd,
     // threads, multi_memory, exceptions, memory64, extended_const, component_model,
     // function_references, memory_control, gc
     for (proposal, _url, wat, expected_err_msg) in [
@@ -162,10 +162,10 @@ fn test_initial_wasmtime_config() {
             "tail calls support is not enabled",
         ),
         (
-            "simd",
+            "relaxed_simd",
             "https://github.com/WebAssembly/relaxed-simd/",
-            "(module (func $f (drop (v128.const i64x2 0 0))))",
-            "SIMD support is not enabled",
+            "(module (func $f (drop (i32x4.relaxed_trunc_f32x4_s (v128.const i64x2 0 0)))))",
+            "relaxed SIMD support is not enabled",
         ),
         (
             "threads",
@@ -228,3 +228,32 @@ fn test_initial_wasmtime_config() {
         );
     }
 }
+
+#[test]
+fn test_wasmtime_simd_nan_canonicalization() {
+    let engine = Engine::new(&WasmtimeEmbedder::wasmtime_execution_config(
+        &EmbeddersConfig::default(),
+    ))
+    .expect("Failed to initialize Wasmtime engine");
+
+    // Returns the first lane of `0 / 0` computed on all four lanes.
+    // Without canonicalization the sign bit of the NaN depends on the CPU.
+    let wat = r#"
+    (module
+      (func (export "test") (result i32)
+        (i32x4.extract_lane 0
+          (f32x4.div (v128.const f32x4 0 0 0 0) (v128.const f32x4 0 0 0 0)))))"#;
+    let module =
+        Module::new(&engine, wat::parse_str(wat).unwrap()).expect("failed to compile module");
+
+    let mut store = Store::new(&engine, ());
+    let instance =
+        wasmtime::Instance::new(&mut store, &module, &[]).expect("failed to instantiate instance");
+    let result = instance
+        .get_typed_func::<(), i32>(&mut store, "test")
+        .expect("export not found")
+        .call(&mut store, ())
+        .expect("call failed");
+
+    assert_eq!(result as u32, 0x7fc0_0000);
+}
@@ -0,0 +1,197 @@
+use std::{fs, path::Path, sync::Arc};
+
+use ic_config::embedders::Config as EmbeddersConfig;
+use ic_embedders::{wasm_utils::compile, CompilationCache, SerializedModule, WasmtimeEmbedder};
+use ic_interfaces::execution_environment::HypervisorError;
+use ic_logger::replica_logger::no_op_logger;
+use ic_types::NumBytes;
+use ic_wasm_types::{BinaryEncodedWasm, CanisterModule, WasmEngineError};
+
+const MEMORY_CAPACITY: NumBytes = NumBytes::new(1 << 30);
+const DISK_CAPACITY: NumBytes = NumBytes::new(1 << 30);
+
+fn canister_module(function_name: &str) -> CanisterModule {
+    let wat = format!(
+        r#"(module
+            (func $run (export "canister_update {}") (drop (i32.const 0)))
+            (memory 1)
+        )"#,
+        function_name
+    );
+    CanisterModule::new(wat::parse_str(wat).unwrap())
+}
+
+fn compile_module(embedder: &WasmtimeEmbedder, module: &CanisterModule) -> Arc<SerializedModule> {
+    let wasm = BinaryEncodedWasm::new(module.as_slice().to_vec());
+    let (_, result) = compile(embedder, &wasm);
+    Arc::new(result.unwrap().1)
+}
+
+fn disk_cache(dir: &Path, version: &str, max_disk_size: NumBytes) -> CompilationCache {
+    CompilationCache::new_with_disk_cache(MEMORY_CAPACITY, dir, version, max_disk_size).unwrap()
+}
+
+fn files_in(dir: &Path) -> Vec<fs::DirEntry> {
+    fs::read_dir(dir.join("wasm_compilation_cache"))
+        .unwrap()
+        .flat_map(|entry| fs::read_dir(entry.unwrap().path()).unwrap())
+        .map(Result::unwrap)
+        .collect()
+}
+
+#[test]
+fn compiled_module_is_loaded_from_disk_after_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let embedder = WasmtimeEmbedder::new(EmbeddersConfig::default(), no_op_logger());
+    let module = canister_module("run");
+    let serialized_module = compile_module(&embedder, &module);
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    cache.insert(&module, Ok(Arc::clone(&serialized_module)));
+    drop(cache);
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    let loaded = cache.get(&module).unwrap().unwrap();
+    assert_eq!(loaded.bytes.as_slice(), serialized_module.bytes.as_slice());
+    assert_eq!(
+        loaded.exported_functions,
+        serialized_module.exported_functions
+    );
+    assert_eq!(loaded.compilation_cost, serialized_module.compilation_cost);
+    embedder
+        .deserialize_module_and_pre_instantiate(&loaded.bytes)
+        .unwrap();
+
+    assert!(cache.get(&canister_module("other")).is_none());
+}
+
+#[test]
+fn version_change_invalidates_disk_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    let embedder = WasmtimeEmbedder::new(EmbeddersConfig::default(), no_op_logger());
+    let module = canister_module("run");
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    cache.insert(&module, Ok(compile_module(&embedder, &module)));
+    drop(cache);
+    assert_eq!(files_in(dir.path()).len(), 1);
+
+    let cache = disk_cache(dir.path(), "2", DISK_CAPACITY);
+    assert!(cache.get(&module).is_none());
+    assert_eq!(files_in(dir.path()).len(), 0);
+    assert_eq!(
+        fs::read_dir(dir.path().join("wasm_compilation_cache"))
+            .unwrap()
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn unrelated_files_are_not_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("file"), b"data").unwrap();
+    fs::create_dir(dir.path().join("subdir")).unwrap();
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    drop(cache);
+    let cache = disk_cache(dir.path(), "2", DISK_CAPACITY);
+    drop(cache);
+
+    assert_eq!(fs::read(dir.path().join("file")).unwrap(), b"data");
+    assert!(dir.path().join("subdir").is_dir());
+}
+
+#[test]
+fn compilation_errors_are_not_persisted() {
+    let dir = tempfile::tempdir().unwrap();
+    let module = canister_module("run");
+    let error = HypervisorError::WasmEngineError(WasmEngineError::Other("error".into()));
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    cache.insert(&module, Err(error));
+    assert!(cache.get(&module).unwrap().is_err());
+    drop(cache);
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    assert!(cache.get(&module).is_none());
+}
+
+#[test]
+fn disk_cache_size_is_bounded() {
+    let dir = tempfile::tempdir().unwrap();
+    let embedder = WasmtimeEmbedder::new(EmbeddersConfig::default(), no_op_logger());
+    let module_1 = canister_module("run_1");
+    let module_2 = canister_module("run_2");
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    cache.insert(&module_1, Ok(compile_module(&embedder, &module_1)));
+    let file_size = files_in(dir.path())[0].metadata().unwrap().len();
+    drop(cache);
+
+    // Only one of the modules fits on disk.
+    let cache = disk_cache(dir.path(), "1", NumBytes::new(file_size * 3 / 2));
+    cache.insert(&module_2, Ok(compile_module(&embedder, &module_2)));
+    assert_eq!(files_in(dir.path()).len(), 1);
+    drop(cache);
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    assert!(cache.get(&module_1).is_none());
+    assert!(cache.get(&module_2).unwrap().is_ok());
+}
+
+#[test]
+fn corrupted_files_are_ignored() {
+    let dir = tempfile::tempdir().unwrap();
+    let embedder = WasmtimeEmbedder::new(EmbeddersConfig::default(), no_op_logger());
+    let module = canister_module("run");
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    cache.insert(&module, Ok(compile_module(&embedder, &module)));
+    drop(cache);
+
+    let path = files_in(dir.path())[0].path();
+    fs::write(&path, b"garbage").unwrap();
+    // Leftover of an interrupted write.
+    fs::write(path.with_extension("0.tmp"), b"garbage").unwrap();
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    assert_eq!(files_in(dir.path()).len(), 1);
+    assert!(cache.get(&module).is_none());
+    assert!(files_in(dir.path()).is_empty());
+}
+
+#[test]
+fn files_with_a_wrong_checksum_are_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let embedder = WasmtimeEmbedder::new(EmbeddersConfig::default(), no_op_logger());
+    let module = canister_module("run");
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    cache.insert(&module, Ok(compile_module(&embedder, &module)));
+    drop(cache);
+
+    // Flip a byte of the serialized module without touching its length.
+    let path = files_in(dir.path())[0].path();
+    let mut bytes = fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    fs::write(&path, bytes).unwrap();
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    assert!(cache.get(&module).is_none());
+    assert!(files_in(dir.path()).is_empty());
+}
+
+#[test]
+fn removed_entries_are_not_loaded_from_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let embedder = WasmtimeEmbedder::new(EmbeddersConfig::default(), no_op_logger());
+    let module = canister_module("run");
+
+    let cache = disk_cache(dir.path(), "1", DISK_CAPACITY);
+    cache.insert(&module, Ok(compile_module(&embedder, &module)));
+    cache.remove(&module);
+    assert!(cache.get(&module).is_none());
+    assert!(files_in(dir.path()).is_empty());
+}
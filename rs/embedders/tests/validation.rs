@@ -1,7 +1,10 @@
 use std::borrow::Cow;
 
 use assert_matches::assert_matches;
-use ic_config::embedders::Config as EmbeddersConfig;
+use ic_config::{
+    embedders::{Config as EmbeddersConfig, FeatureFlags},
+    flag_status::FlagStatus,
+};
 use ic_embedders::{
     wasm_utils::{
         validate_and_instrument_for_testing,
@@ -958,6 +961,41 @@ fn can_validate_performance_counter_import() {
     );
 }
 
+#[test]
+fn can_validate_call_with_best_effort_response_import_if_enabled() {
+    let wasm = wat2wasm(
+        r#"(module
+        (import "ic0" "call_with_best_effort_response" (func $ic0_call_with_best_effort_response (param i32)))
+    )"#,
+    )
+    .unwrap();
+    let config = EmbeddersConfig {
+        feature_flags: FeatureFlags {
+            best_effort_responses: FlagStatus::Enabled,
+            ..FeatureFlags::default()
+        },
+        ..EmbeddersConfig::default()
+    };
+    assert_eq!(
+        validate_wasm_binary(&wasm, &config),
+        Ok(WasmValidationDetails::default())
+    );
+}
+
+#[test]
+fn cannot_validate_call_with_best_effort_response_import_if_disabled() {
+    let wasm = wat2wasm(
+        r#"(module
+        (import "ic0" "call_with_best_effort_response" (func $ic0_call_with_best_effort_response (param i32)))
+    )"#,
+    )
+    .unwrap();
+    assert_matches!(
+        validate_wasm_binary(&wasm, &EmbeddersConfig::default()),
+        Err(WasmValidationError::InvalidImportSection(_))
+    );
+}
+
 /// The spec doesn't allow exported functions to have results.
 #[test]
 fn function_with_result_is_invalid() {
@@ -1087,6 +1125,34 @@ fn incorrect_wasm_code_size_is_invalid() {
     )
 }
 
+#[test]
+fn can_validate_module_with_simd_instructions() {
+    let wasm = wat2wasm(
+        r#"(module
+            (func $f (param i32) (result v128)
+                (f32x4.add
+                    (v128.load (local.get 0))
+                    (f32x4.splat (f32.const 1.0))))
+            (memory 1))"#,
+    )
+    .unwrap();
+    assert!(validate_wasm_binary(&wasm, &EmbeddersConfig::default()).is_ok());
+}
+
+#[test]
+fn cannot_validate_module_with_relaxed_simd_instructions() {
+    let wasm = wat2wasm(
+        r#"(module
+            (func $f (param v128) (result v128)
+                (i32x4.relaxed_trunc_f32x4_s (local.get 0))))"#,
+    )
+    .unwrap();
+    assert_matches!(
+        validate_wasm_binary(&wasm, &EmbeddersConfig::default()),
+        Err(WasmValidationError::WasmtimeValidation(_))
+    );
+}
+
 /// We're assuming there is at most one code section in the Wasm. The spec
 /// doesn't allow multiple code sections, so if there are multiple code sections
 /// the module should fail validation.
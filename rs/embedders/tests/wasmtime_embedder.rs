@@ -412,6 +412,57 @@ fn can_set_and_get_float_globals() {
     );
 }
 
+#[test]
+// Takes a Wasm with a mutable v128 global and checks whether we can set and get
+// its value.
+fn can_set_and_get_v128_globals() {
+    let wat = r#"
+                    (module
+                        (import "ic0" "msg_reply" (func $msg_reply))
+                        (func $test
+                            (call $msg_reply)
+                        )
+                        (global (export "g1") (mut v128) (v128.const i64x2 1 2))
+                        (func (export "canister_update test"))
+                    )"#;
+
+    // Initial read, the global should have its initial value.
+    let mut instance = WasmtimeInstanceBuilder::new().with_wat(wat).build();
+    let res = instance
+        .run(FuncRef::Method(WasmMethod::Update("test".to_string())))
+        .unwrap();
+    assert_eq!(
+        res.exported_globals[..],
+        [
+            Global::V128((2 << 64) | 1),
+            // Minus 1 instruction for function.
+            Global::I64(DEFAULT_NUM_INSTRUCTIONS.get() as i64 - 1),
+        ]
+    );
+
+    // Change the value of the global and verify we can get it back.
+    let mut instance = WasmtimeInstanceBuilder::new()
+        .with_wat(wat)
+        .with_globals(vec![
+            Global::V128(u128::MAX - 1),
+            // Last global is the instruction counter which will be
+            // overwritten anyway.
+            Global::I64(0),
+        ])
+        .build();
+    let res = instance
+        .run(FuncRef::Method(WasmMethod::Update("test".to_string())))
+        .unwrap();
+    assert_eq!(
+        res.exported_globals[..],
+        [
+            Global::V128(u128::MAX - 1),
+            // Minus 1 instruction for function.
+            Global::I64(DEFAULT_NUM_INSTRUCTIONS.get() as i64 - 1),
+        ]
+    );
+}
+
 #[test]
 #[should_panic(expected = "global of type I32 cannot be set to I64")]
 fn try_to_set_globals_with_wrong_types() {
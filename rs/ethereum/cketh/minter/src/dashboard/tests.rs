@@ -618,6 +618,8 @@ fn initial_state() -> State {
         minimum_withdrawal_amount: Wei::TWO.into(),
         next_transaction_nonce: TransactionNonce::ZERO.into(),
         last_scraped_block_number: candid::Nat::from(3_956_206_u32),
+        json_rpc_providers: None,
+        consensus_strategy: None,
     })
     .expect("valid init args")
 }
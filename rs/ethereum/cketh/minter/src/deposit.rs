@@ -1,6 +1,6 @@
-use crate::eth_logs::{report_transaction_error, ReceivedEthEventError};
-use crate::eth_rpc::{BlockSpec, HttpOutcallError};
-use crate::eth_rpc_client::EthRpcClient;
+use crate::eth_logs::{report_transaction_error, ReceivedEthEventError, ReceivedEvent};
+use crate::eth_rpc::{BlockSpec, HttpOutcallError, LogEntry};
+use crate::eth_rpc_client::{EthRpcClient, MultiCallError};
 use crate::guard::TimerGuard;
 use crate::logs::{DEBUG, INFO};
 use crate::numeric::{BlockNumber, LedgerMintIndex};
@@ -83,11 +83,163 @@ async fn mint_cketh() {
     }
 }
 
+async fn mint_ckerc20() {
+    use icrc_ledger_client_cdk::{CdkRuntime, ICRC1Client};
+    use icrc_ledger_types::icrc1::transfer::TransferArg;
+
+    let _guard = match TimerGuard::new(TaskType::MintCkErc20) {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+
+    let events = read_state(|s| s.erc20_events_to_mint.clone());
+
+    let mut error_count = 0;
+
+    for (event_source, event) in events {
+        let token = read_state(|s| s.ckerc20_tokens.get(&event.erc20_contract_address).cloned())
+            .unwrap_or_else(|| {
+                panic!(
+                    "BUG: no ckERC20 token for ERC-20 contract {}",
+                    event.erc20_contract_address
+                )
+            });
+        let client = ICRC1Client {
+            runtime: CdkRuntime,
+            ledger_canister_id: token.ckerc20_ledger_id,
+        };
+        let block_index = match client
+            .transfer(TransferArg {
+                from_subaccount: None,
+                to: event.principal.into(),
+                fee: None,
+                created_at_time: None,
+                memo: Some(event.clone().into()),
+                amount: candid::Nat::from(event.value),
+            })
+            .await
+        {
+            Ok(Ok(block_index)) => block_index.0.to_u64().expect("nat does not fit into u64"),
+            Ok(Err(err)) => {
+                log!(
+                    INFO,
+                    "Failed to mint {}: {event:?} {err}",
+                    token.ckerc20_token_symbol
+                );
+                error_count += 1;
+                continue;
+            }
+            Err(err) => {
+                log!(
+                    INFO,
+                    "Failed to send a message to the ledger ({}): {err:?}",
+                    token.ckerc20_ledger_id
+                );
+                error_count += 1;
+                continue;
+            }
+        };
+        mutate_state(|s| {
+            process_event(
+                s,
+                EventType::MintedCkErc20 {
+                    event_source,
+                    mint_block_index: LedgerMintIndex::new(block_index),
+                    ckerc20_token_symbol: token.ckerc20_token_symbol.clone(),
+                    erc20_contract_address: token.erc20_contract_address,
+                },
+            )
+        });
+        log!(
+            INFO,
+            "Minted {} {} to {} in block {block_index}",
+            event.value,
+            token.ckerc20_token_symbol,
+            event.principal
+        );
+    }
+
+    if error_count > 0 {
+        log!(
+            INFO,
+            "Failed to mint {error_count} ckERC20 events, rescheduling the minting"
+        );
+        ic_cdk_timers::set_timer(crate::MINT_RETRY_DELAY, || ic_cdk::spawn(mint_ckerc20()));
+    }
+}
+
+/// The helper smart contract logs scraped by the minter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum LogScraping {
+    /// `ReceivedEth` logs of the ETH helper smart contract.
+    Eth,
+    /// `ReceivedErc20` logs of the ERC-20 helper smart contract.
+    Erc20,
+}
+
+impl LogScraping {
+    fn contract_address(self, state: &State) -> Option<Address> {
+        match self {
+            LogScraping::Eth => state.ethereum_contract_address,
+            LogScraping::Erc20 => state.erc20_helper_contract_address,
+        }
+    }
+
+    fn last_scraped_block_number(self, state: &State) -> BlockNumber {
+        match self {
+            LogScraping::Eth => state.last_scraped_block_number,
+            LogScraping::Erc20 => state.last_erc20_scraped_block_number,
+        }
+    }
+
+    fn update_last_scraped_block_number(self, state: &mut State, block_number: BlockNumber) {
+        match self {
+            LogScraping::Eth => state.last_scraped_block_number = block_number,
+            LogScraping::Erc20 => state.last_erc20_scraped_block_number = block_number,
+        }
+    }
+
+    fn skipped_block_event(self, block_number: BlockNumber) -> EventType {
+        match self {
+            LogScraping::Eth => EventType::SkippedBlock(block_number),
+            LogScraping::Erc20 => EventType::SkippedErc20Block(block_number),
+        }
+    }
+
+    async fn last_received_events(
+        self,
+        contract_address: Address,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<(Vec<ReceivedEvent>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>>
+    {
+        fn into_received_events<E: Into<ReceivedEvent>>(
+            (events, errors): (Vec<E>, Vec<ReceivedEthEventError>),
+        ) -> (Vec<ReceivedEvent>, Vec<ReceivedEthEventError>) {
+            (events.into_iter().map(Into::into).collect(), errors)
+        }
+
+        match self {
+            LogScraping::Eth => {
+                crate::eth_logs::last_received_eth_events(contract_address, from, to)
+                    .await
+                    .map(into_received_events)
+            }
+            LogScraping::Erc20 => {
+                crate::eth_logs::last_received_erc20_events(contract_address, from, to)
+                    .await
+                    .map(into_received_events)
+            }
+        }
+    }
+}
+
 /// Scraps Ethereum logs between `from` and `min(from + MAX_BLOCK_SPREAD, to)` since certain RPC providers
 /// require that the number of blocks queried is no greater than MAX_BLOCK_SPREAD.
 /// Returns the last block number that was scraped (which is `min(from + MAX_BLOCK_SPREAD, to)`) if there
 /// was no error when querying the providers, otherwise returns `None`.
-async fn scrap_eth_logs_range_inclusive(
+async fn scrap_logs_range_inclusive(
+    scraping: LogScraping,
     contract_address: Address,
     from: BlockNumber,
     to: BlockNumber,
@@ -103,32 +255,32 @@ async fn scrap_eth_logs_range_inclusive(
             let mut last_block_number = min(max_to, to);
             log!(
                 DEBUG,
-                "Scrapping ETH logs from block {:?} to block {:?}...",
+                "Scrapping {scraping:?} logs from block {:?} to block {:?}...",
                 from,
                 last_block_number
             );
 
             let (transaction_events, errors) = loop {
-                match crate::eth_logs::last_received_eth_events(
-                    contract_address,
-                    from,
-                    last_block_number,
-                )
-                .await
+                match scraping
+                    .last_received_events(contract_address, from, last_block_number)
+                    .await
                 {
                     Ok((events, errors)) => break (events, errors),
                     Err(e) => {
                         log!(
                         INFO,
-                        "Failed to get ETH logs from block {from} to block {last_block_number}: {e:?}",
+                        "Failed to get {scraping:?} logs from block {from} to block {last_block_number}: {e:?}",
                     );
                         if e.has_http_outcall_error_matching(
                             HttpOutcallError::is_response_too_large,
                         ) {
                             if from == last_block_number {
                                 mutate_state(|s| {
-                                    process_event(s, EventType::SkippedBlock(last_block_number));
-                                    s.last_scraped_block_number = last_block_number;
+                                    process_event(
+                                        s,
+                                        scraping.skipped_block_event(last_block_number),
+                                    );
+                                    scraping.update_last_scraped_block_number(s, last_block_number);
                                 });
                                 return Some(last_block_number);
                             } else {
@@ -149,38 +301,53 @@ async fn scrap_eth_logs_range_inclusive(
             };
 
             for event in transaction_events {
-                log!(
-                    INFO,
-                    "Received event {event:?}; will mint {} wei to {}",
-                    event.value,
-                    event.principal
-                );
-                if crate::blocklist::is_blocked(event.from_address) {
+                log!(INFO, "Received event {event:?}");
+                let from_address = event.from_address();
+                if crate::blocklist::is_blocked(from_address) {
                     log!(
                         INFO,
-                        "Received event from a blocked address: {} for {} WEI",
-                        event.from_address,
-                        event.value,
+                        "Received event from a blocked address: {from_address}: {event:?}",
                     );
                     mutate_state(|s| {
                         process_event(
                             s,
                             EventType::InvalidDeposit {
-                                event_source: crate::eth_logs::EventSource {
-                                    transaction_hash: event.transaction_hash,
-                                    log_index: event.log_index,
-                                },
-                                reason: format!("blocked address {}", event.from_address),
+                                event_source: event.source(),
+                                reason: format!("blocked address {from_address}"),
                             },
                         )
                     });
-                } else {
-                    mutate_state(|s| process_event(s, EventType::AcceptedDeposit(event)));
+                    continue;
+                }
+                match event {
+                    ReceivedEvent::Eth(event) => {
+                        mutate_state(|s| process_event(s, EventType::AcceptedDeposit(event)));
+                    }
+                    ReceivedEvent::Erc20(event) => {
+                        let is_supported = read_state(|s| {
+                            s.ckerc20_tokens.contains_key(&event.erc20_contract_address)
+                        });
+                        let payload = if is_supported {
+                            EventType::AcceptedErc20Deposit(event)
+                        } else {
+                            EventType::InvalidDeposit {
+                                event_source: event.source(),
+                                reason: format!(
+                                    "unsupported ERC-20 token {}",
+                                    event.erc20_contract_address
+                                ),
+                            }
+                        };
+                        mutate_state(|s| process_event(s, payload));
+                    }
                 }
             }
             if read_state(State::has_events_to_mint) {
                 ic_cdk_timers::set_timer(Duration::from_secs(0), || ic_cdk::spawn(mint_cketh()));
             }
+            if read_state(State::has_erc20_events_to_mint) {
+                ic_cdk_timers::set_timer(Duration::from_secs(0), || ic_cdk::spawn(mint_ckerc20()));
+            }
             for error in errors {
                 if let ReceivedEthEventError::InvalidEventSource { source, error } = &error {
                     mutate_state(|s| {
@@ -195,7 +362,7 @@ async fn scrap_eth_logs_range_inclusive(
                 }
                 report_transaction_error(error);
             }
-            mutate_state(|s| s.last_scraped_block_number = last_block_number);
+            mutate_state(|s| scraping.update_last_scraped_block_number(s, last_block_number));
             Some(last_block_number)
         }
         Ordering::Greater => {
@@ -212,33 +379,49 @@ pub async fn scrap_eth_logs() {
         Ok(guard) => guard,
         Err(_) => return,
     };
-    let contract_address = match read_state(|s| s.ethereum_contract_address) {
-        Some(address) => address,
+    if read_state(|s| {
+        s.ethereum_contract_address.is_none() && s.erc20_helper_contract_address.is_none()
+    }) {
+        log!(
+            DEBUG,
+            "[scrap_eth_logs]: skipping scrapping logs: no contract address"
+        );
+        return;
+    }
+    let last_block_number = match update_last_observed_block_number().await {
+        Some(block_number) => block_number,
         None => {
             log!(
                 DEBUG,
-                "[scrap_eth_logs]: skipping scrapping ETH logs: no contract address"
+                "[scrap_eth_logs]: skipping scrapping ETH logs: no last observed block number"
             );
             return;
         }
     };
-    let last_block_number = match update_last_observed_block_number().await {
-        Some(block_number) => block_number,
+    for scraping in [LogScraping::Eth, LogScraping::Erc20] {
+        scrap_logs(scraping, last_block_number).await;
+    }
+}
+
+async fn scrap_logs(scraping: LogScraping, last_block_number: BlockNumber) {
+    let contract_address = match read_state(|s| scraping.contract_address(s)) {
+        Some(address) => address,
         None => {
             log!(
                 DEBUG,
-                "[scrap_eth_logs]: skipping scrapping ETH logs: no last observed block number"
+                "[scrap_logs]: skipping scrapping {scraping:?} logs: no contract address"
             );
             return;
         }
     };
-    let mut last_scraped_block_number = read_state(|s| s.last_scraped_block_number);
+    let mut last_scraped_block_number = read_state(|s| scraping.last_scraped_block_number(s));
 
     while last_scraped_block_number < last_block_number {
         let next_block_to_query = last_scraped_block_number
             .checked_increment()
             .unwrap_or(BlockNumber::MAX);
-        last_scraped_block_number = match scrap_eth_logs_range_inclusive(
+        last_scraped_block_number = match scrap_logs_range_inclusive(
+            scraping,
             contract_address,
             next_block_to_query,
             last_block_number,
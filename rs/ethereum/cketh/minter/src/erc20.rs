@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests;
+
+use crate::endpoints::AddCkErc20Token;
+use crate::lifecycle::EthereumNetwork;
+use candid::Principal;
+use ic_ethereum_types::Address;
+use minicbor::{Decode, Encode};
+use num_traits::ToPrimitive;
+use std::fmt;
+use std::str::FromStr;
+
+/// Maximum length of a ckERC20 token symbol, e.g., `ckUSDC`.
+const MAX_CKERC20_TOKEN_SYMBOL_LENGTH: usize = 20;
+
+/// An ERC-20 token supported by the minter.
+/// Deposits of that token are minted as ckERC20 tokens on a dedicated ledger,
+/// from which ckERC20 tokens are burned on withdrawal.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+pub struct CkErc20Token {
+    /// The network on which the ERC-20 smart contract is deployed.
+    #[n(0)]
+    pub erc20_ethereum_network: EthereumNetwork,
+    /// Address of the ERC-20 smart contract.
+    #[n(1)]
+    pub erc20_contract_address: Address,
+    /// Symbol of the ckERC20 token on the ledger, e.g., `ckUSDC`.
+    #[n(2)]
+    pub ckerc20_token_symbol: String,
+    /// The ledger on which the ckERC20 token is minted.
+    #[cbor(n(3), with = "crate::cbor::principal")]
+    pub ckerc20_ledger_id: Principal,
+}
+
+impl fmt::Debug for CkErc20Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CkErc20Token")
+            .field("erc20_ethereum_network", &self.erc20_ethereum_network)
+            .field("erc20_contract_address", &self.erc20_contract_address)
+            .field("ckerc20_token_symbol", &self.ckerc20_token_symbol)
+            .field(
+                "ckerc20_ledger_id",
+                &format_args!("{}", self.ckerc20_ledger_id),
+            )
+            .finish()
+    }
+}
+
+impl TryFrom<AddCkErc20Token> for CkErc20Token {
+    type Error = String;
+
+    fn try_from(value: AddCkErc20Token) -> Result<Self, Self::Error> {
+        let chain_id = value
+            .chain_id
+            .0
+            .to_u64()
+            .ok_or_else(|| format!("chain ID {} does not fit into u64", value.chain_id))?;
+        let erc20_contract_address = Address::from_str(&value.address)
+            .map_err(|e| format!("invalid ERC-20 contract address {}: {e}", value.address))?;
+        if erc20_contract_address == Address::ZERO {
+            return Err("ERC-20 contract address cannot be the zero address".to_string());
+        }
+        validate_ckerc20_token_symbol(&value.ckerc20_token_symbol)?;
+        if value.ckerc20_ledger_id == Principal::anonymous() {
+            return Err("ckERC20 ledger ID cannot be the anonymous principal".to_string());
+        }
+        Ok(Self {
+            erc20_ethereum_network: EthereumNetwork::from_chain_id(chain_id),
+            erc20_contract_address,
+            ckerc20_token_symbol: value.ckerc20_token_symbol,
+            ckerc20_ledger_id: value.ckerc20_ledger_id,
+        })
+    }
+}
+
+fn validate_ckerc20_token_symbol(symbol: &str) -> Result<(), String> {
+    if !symbol.starts_with("ck") {
+        return Err(format!("token symbol {symbol} must start with 'ck'"));
+    }
+    if symbol.len() > MAX_CKERC20_TOKEN_SYMBOL_LENGTH {
+        return Err(format!(
+            "token symbol {symbol} is longer than {MAX_CKERC20_TOKEN_SYMBOL_LENGTH} characters"
+        ));
+    }
+    if !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!(
+            "token symbol {symbol} must only contain ASCII alphanumeric characters"
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use crate::endpoints::AddCkErc20Token;
+use crate::erc20::CkErc20Token;
+use crate::lifecycle::EthereumNetwork;
+use assert_matches::assert_matches;
+use candid::{Nat, Principal};
+
+#[test]
+fn should_convert_valid_add_ckerc20_token_arg() {
+    let token = CkErc20Token::try_from(usdc()).unwrap();
+
+    assert_eq!(
+        token,
+        CkErc20Token {
+            erc20_ethereum_network: EthereumNetwork::Sepolia,
+            erc20_contract_address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238"
+                .parse()
+                .unwrap(),
+            ckerc20_token_symbol: "ckSepoliaUSDC".to_string(),
+            ckerc20_ledger_id: "mxzaz-hqaaa-aaaar-qaada-cai".parse().unwrap(),
+        }
+    );
+}
+
+#[test]
+fn should_fail_when_chain_id_does_not_fit_into_u64() {
+    assert_matches!(
+        CkErc20Token::try_from(AddCkErc20Token {
+            chain_id: Nat::from(u128::MAX),
+            ..usdc()
+        }),
+        Err(e) if e.contains("chain ID")
+    );
+}
+
+#[test]
+fn should_fail_when_contract_address_invalid() {
+    for address in [
+        "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C723",
+        "0x0000000000000000000000000000000000000000",
+    ] {
+        assert_matches!(
+            CkErc20Token::try_from(AddCkErc20Token {
+                address: address.to_string(),
+                ..usdc()
+            }),
+            Err(_)
+        );
+    }
+}
+
+#[test]
+fn should_fail_when_token_symbol_invalid() {
+    for symbol in ["USDC", "ckUSDC!", "ckAVeryLongTokenSymbol"] {
+        assert_matches!(
+            CkErc20Token::try_from(AddCkErc20Token {
+                ckerc20_token_symbol: symbol.to_string(),
+                ..usdc()
+            }),
+            Err(e) if e.contains(symbol)
+        );
+    }
+}
+
+#[test]
+fn should_fail_when_ledger_id_anonymous() {
+    assert_matches!(
+        CkErc20Token::try_from(AddCkErc20Token {
+            ckerc20_ledger_id: Principal::anonymous(),
+            ..usdc()
+        }),
+        Err(_)
+    );
+}
+
+fn usdc() -> AddCkErc20Token {
+    AddCkErc20Token {
+        chain_id: Nat::from(11155111_u64),
+        address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238".to_string(),
+        ckerc20_token_symbol: "ckSepoliaUSDC".to_string(),
+        ckerc20_ledger_id: "mxzaz-hqaaa-aaaar-qaada-cai".parse().unwrap(),
+    }
+}
@@ -1,10 +1,11 @@
 #[cfg(test)]
 mod tests;
 
+use crate::checked_amount::CheckedAmountOf;
 use crate::eth_rpc::{FixedSizeData, Hash, LogEntry};
 use crate::eth_rpc_client::{EthRpcClient, MultiCallError};
 use crate::logs::{DEBUG, INFO};
-use crate::numeric::{BlockNumber, LogIndex, Wei};
+use crate::numeric::{BlockNumber, Erc20Value, LogIndex, Wei};
 use crate::state::read_state;
 use candid::Principal;
 use hex_literal::hex;
@@ -17,6 +18,11 @@ use thiserror::Error;
 pub(crate) const RECEIVED_ETH_EVENT_TOPIC: [u8; 32] =
     hex!("257e057bb61920d8d0ed2cb7b720ac7f9c513cd1110bc9fa543079154f45f435");
 
+/// Topic of the `ReceivedErc20(address,address,uint256,bytes32)` event
+/// emitted by the ERC-20 helper smart contract.
+pub(crate) const RECEIVED_ERC20_EVENT_TOPIC: [u8; 32] =
+    hex!("4d69d0bd4287b7f66c548f90154dc81bc98f65a1b362775df5ae171a2ccd262b");
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
 pub struct ReceivedEthEvent {
     #[n(0)]
@@ -46,6 +52,39 @@ impl fmt::Debug for ReceivedEthEvent {
     }
 }
 
+/// A deposit of ERC-20 tokens made through the ERC-20 helper smart contract.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub struct ReceivedErc20Event {
+    #[n(0)]
+    pub transaction_hash: Hash,
+    #[n(1)]
+    pub block_number: BlockNumber,
+    #[cbor(n(2))]
+    pub log_index: LogIndex,
+    #[n(3)]
+    pub from_address: Address,
+    #[n(4)]
+    pub value: Erc20Value,
+    #[cbor(n(5), with = "crate::cbor::principal")]
+    pub principal: Principal,
+    #[n(6)]
+    pub erc20_contract_address: Address,
+}
+
+impl fmt::Debug for ReceivedErc20Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReceivedErc20Event")
+            .field("transaction_hash", &self.transaction_hash)
+            .field("block_number", &self.block_number)
+            .field("log_index", &self.log_index)
+            .field("from_address", &self.from_address)
+            .field("value", &self.value)
+            .field("principal", &format_args!("{}", self.principal))
+            .field("erc20_contract_address", &self.erc20_contract_address)
+            .finish()
+    }
+}
+
 /// A unique identifier of the event source: the source transaction hash and the log
 /// entry index.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
@@ -71,11 +110,75 @@ impl ReceivedEthEvent {
     }
 }
 
+impl ReceivedErc20Event {
+    pub fn source(&self) -> EventSource {
+        EventSource {
+            transaction_hash: self.transaction_hash,
+            log_index: self.log_index,
+        }
+    }
+}
+
+/// A deposit found in the logs of one of the helper smart contracts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReceivedEvent {
+    Eth(ReceivedEthEvent),
+    Erc20(ReceivedErc20Event),
+}
+
+impl ReceivedEvent {
+    pub fn source(&self) -> EventSource {
+        match self {
+            ReceivedEvent::Eth(event) => event.source(),
+            ReceivedEvent::Erc20(event) => event.source(),
+        }
+    }
+
+    pub fn from_address(&self) -> Address {
+        match self {
+            ReceivedEvent::Eth(event) => event.from_address,
+            ReceivedEvent::Erc20(event) => event.from_address,
+        }
+    }
+}
+
+impl From<ReceivedEthEvent> for ReceivedEvent {
+    fn from(event: ReceivedEthEvent) -> Self {
+        ReceivedEvent::Eth(event)
+    }
+}
+
+impl From<ReceivedErc20Event> for ReceivedEvent {
+    fn from(event: ReceivedErc20Event) -> Self {
+        ReceivedEvent::Erc20(event)
+    }
+}
+
 pub async fn last_received_eth_events(
     contract_address: Address,
     from: BlockNumber,
     to: BlockNumber,
 ) -> Result<(Vec<ReceivedEthEvent>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>> {
+    last_received_events(contract_address, RECEIVED_ETH_EVENT_TOPIC, from, to).await
+}
+
+pub async fn last_received_erc20_events(
+    contract_address: Address,
+    from: BlockNumber,
+    to: BlockNumber,
+) -> Result<(Vec<ReceivedErc20Event>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>> {
+    last_received_events(contract_address, RECEIVED_ERC20_EVENT_TOPIC, from, to).await
+}
+
+async fn last_received_events<E>(
+    contract_address: Address,
+    topic: [u8; 32],
+    from: BlockNumber,
+    to: BlockNumber,
+) -> Result<(Vec<E>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>>
+where
+    E: TryFrom<LogEntry, Error = ReceivedEthEventError>,
+{
     use crate::eth_rpc::GetLogsParam;
 
     if from > to {
@@ -90,15 +193,13 @@ pub async fn last_received_eth_events(
             from_block: from.into(),
             to_block: to.into(),
             address: vec![contract_address],
-            topics: vec![FixedSizeData(RECEIVED_ETH_EVENT_TOPIC)],
+            topics: vec![FixedSizeData(topic)],
         })
         .await?;
 
-    let (ok, not_ok): (Vec<_>, Vec<_>) = result
-        .into_iter()
-        .map(ReceivedEthEvent::try_from)
-        .partition(Result::is_ok);
-    let valid_transactions: Vec<ReceivedEthEvent> = ok.into_iter().map(Result::unwrap).collect();
+    let (ok, not_ok): (Vec<_>, Vec<_>) =
+        result.into_iter().map(E::try_from).partition(Result::is_ok);
+    let valid_transactions: Vec<E> = ok.into_iter().map(Result::unwrap).collect();
     let errors: Vec<ReceivedEthEventError> = not_ok.into_iter().map(Result::unwrap_err).collect();
     Ok((valid_transactions, errors))
 }
@@ -141,79 +242,16 @@ impl TryFrom<LogEntry> for ReceivedEthEvent {
     type Error = ReceivedEthEventError;
 
     fn try_from(entry: LogEntry) -> Result<Self, Self::Error> {
-        let _block_hash = entry
-            .block_hash
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let block_number = entry
-            .block_number
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let transaction_hash = entry
-            .transaction_hash
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let _transaction_index = entry
-            .transaction_index
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let log_index = entry
-            .log_index
-            .ok_or(ReceivedEthEventError::PendingLogEntry)?;
-        let event_source = EventSource {
-            transaction_hash,
-            log_index,
-        };
-
-        if entry.removed {
-            return Err(ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
-                error: EventSourceError::InvalidEvent(
-                    "this event has been removed from the chain".to_string(),
-                ),
-            });
-        }
-
-        if entry.topics.len() != 3 {
-            return Err(ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
-                error: EventSourceError::InvalidEvent(format!(
-                    "Expected exactly 3 topics, got {}",
-                    entry.topics.len()
-                )),
-            });
-        }
-        let from_address = Address::try_from(&entry.topics[1].0).map_err(|err| {
-            ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
-                error: EventSourceError::InvalidEvent(format!(
-                    "Invalid address in log entry: {}",
-                    err
-                )),
-            }
-        })?;
-        let principal = parse_principal_from_slice(entry.topics[2].as_ref()).map_err(|_err| {
-            ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
-                error: EventSourceError::InvalidPrincipal {
-                    invalid_principal: entry.topics[2].clone(),
-                },
-            }
-        })?;
-        let value_bytes: [u8; 32] =
-            entry
-                .data
-                .0
-                .try_into()
-                .map_err(|data| ReceivedEthEventError::InvalidEventSource {
-                    source: event_source,
-                    error: EventSourceError::InvalidEvent(format!(
-                        "Invalid data length; expected 32-byte value, got {}",
-                        hex::encode(data)
-                    )),
-                })?;
-        let value = Wei::from_be_bytes(value_bytes);
+        let (event_source, block_number) = parse_log_entry_source(&entry)?;
+        ensure_topics_count(&entry, event_source, 3)?;
+        let from_address = parse_address(&entry.topics[1], event_source)?;
+        let principal = parse_principal(&entry.topics[2], event_source)?;
+        let value = parse_value(entry.data.0, event_source)?;
 
         Ok(ReceivedEthEvent {
-            transaction_hash,
+            transaction_hash: event_source.transaction_hash,
             block_number,
-            log_index,
+            log_index: event_source.log_index,
             from_address,
             value,
             principal,
@@ -221,6 +259,122 @@ impl TryFrom<LogEntry> for ReceivedEthEvent {
     }
 }
 
+impl TryFrom<LogEntry> for ReceivedErc20Event {
+    type Error = ReceivedEthEventError;
+
+    fn try_from(entry: LogEntry) -> Result<Self, Self::Error> {
+        let (event_source, block_number) = parse_log_entry_source(&entry)?;
+        ensure_topics_count(&entry, event_source, 4)?;
+        let erc20_contract_address = parse_address(&entry.topics[1], event_source)?;
+        let from_address = parse_address(&entry.topics[2], event_source)?;
+        let principal = parse_principal(&entry.topics[3], event_source)?;
+        let value = parse_value(entry.data.0, event_source)?;
+
+        Ok(ReceivedErc20Event {
+            transaction_hash: event_source.transaction_hash,
+            block_number,
+            log_index: event_source.log_index,
+            from_address,
+            value,
+            principal,
+            erc20_contract_address,
+        })
+    }
+}
+
+/// Extracts the source of a log entry and the block it was included in.
+/// Fails if the log entry is pending or was removed from the chain.
+fn parse_log_entry_source(
+    entry: &LogEntry,
+) -> Result<(EventSource, BlockNumber), ReceivedEthEventError> {
+    let _block_hash = entry
+        .block_hash
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let block_number = entry
+        .block_number
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let transaction_hash = entry
+        .transaction_hash
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let _transaction_index = entry
+        .transaction_index
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let log_index = entry
+        .log_index
+        .ok_or(ReceivedEthEventError::PendingLogEntry)?;
+    let event_source = EventSource {
+        transaction_hash,
+        log_index,
+    };
+
+    if entry.removed {
+        return Err(ReceivedEthEventError::InvalidEventSource {
+            source: event_source,
+            error: EventSourceError::InvalidEvent(
+                "this event has been removed from the chain".to_string(),
+            ),
+        });
+    }
+    Ok((event_source, block_number))
+}
+
+fn ensure_topics_count(
+    entry: &LogEntry,
+    source: EventSource,
+    expected_count: usize,
+) -> Result<(), ReceivedEthEventError> {
+    if entry.topics.len() != expected_count {
+        return Err(ReceivedEthEventError::InvalidEventSource {
+            source,
+            error: EventSourceError::InvalidEvent(format!(
+                "Expected exactly {expected_count} topics, got {}",
+                entry.topics.len()
+            )),
+        });
+    }
+    Ok(())
+}
+
+fn parse_address(
+    topic: &FixedSizeData,
+    source: EventSource,
+) -> Result<Address, ReceivedEthEventError> {
+    Address::try_from(&topic.0).map_err(|err| ReceivedEthEventError::InvalidEventSource {
+        source,
+        error: EventSourceError::InvalidEvent(format!("Invalid address in log entry: {}", err)),
+    })
+}
+
+fn parse_principal(
+    topic: &FixedSizeData,
+    source: EventSource,
+) -> Result<Principal, ReceivedEthEventError> {
+    parse_principal_from_slice(topic.as_ref()).map_err(|_err| {
+        ReceivedEthEventError::InvalidEventSource {
+            source,
+            error: EventSourceError::InvalidPrincipal {
+                invalid_principal: topic.clone(),
+            },
+        }
+    })
+}
+
+fn parse_value<Unit>(
+    data: Vec<u8>,
+    source: EventSource,
+) -> Result<CheckedAmountOf<Unit>, ReceivedEthEventError> {
+    let value_bytes: [u8; 32] =
+        data.try_into()
+            .map_err(|data| ReceivedEthEventError::InvalidEventSource {
+                source,
+                error: EventSourceError::InvalidEvent(format!(
+                    "Invalid data length; expected 32-byte value, got {}",
+                    hex::encode(data)
+                )),
+            })?;
+    Ok(CheckedAmountOf::from_be_bytes(value_bytes))
+}
+
 /// Decode a candid::Principal from a slice of at most 32 bytes
 /// encoded as follows
 /// - the first byte is the number of bytes in the principal
@@ -117,3 +117,108 @@ mod parse_principal_from_slice {
         principal_bytes
     }
 }
+
+mod parse_received_erc20_event {
+    use crate::eth_logs::{
+        EventSource, EventSourceError, ReceivedErc20Event, ReceivedEthEventError,
+        RECEIVED_ERC20_EVENT_TOPIC,
+    };
+    use crate::eth_rpc::{Data, FixedSizeData, LogEntry};
+    use crate::numeric::{BlockNumber, Erc20Value, LogIndex};
+    use assert_matches::assert_matches;
+    use candid::Principal;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_parse_received_erc20_event() {
+        let event = ReceivedErc20Event::try_from(received_erc20_log_entry()).unwrap();
+
+        assert_eq!(
+            event,
+            ReceivedErc20Event {
+                transaction_hash:
+                    "0x44d8e93a8f4bbc89ad35fc4fbbdb12cb597b4832da09c0b2300777be180fde87"
+                        .parse()
+                        .unwrap(),
+                block_number: BlockNumber::new(5_539_137),
+                log_index: LogIndex::new(39),
+                from_address: "0xdd2851Cdd40aE6536831558DD46db62fAc7A844d"
+                    .parse()
+                    .unwrap(),
+                value: Erc20Value::new(1_000_000),
+                principal: Principal::from_str("2chl6-4hpzw-vqaaa-aaaaa-c").unwrap(),
+                erc20_contract_address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238"
+                    .parse()
+                    .unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn should_fail_when_topics_count_invalid() {
+        let mut entry = received_erc20_log_entry();
+        entry.topics.pop();
+
+        assert_matches!(
+            ReceivedErc20Event::try_from(entry),
+            Err(ReceivedEthEventError::InvalidEventSource {
+                source: EventSource { log_index, .. },
+                error: EventSourceError::InvalidEvent(_),
+            }) if log_index == LogIndex::new(39)
+        );
+    }
+
+    #[test]
+    fn should_ignore_pending_log_entry() {
+        let entry = LogEntry {
+            block_hash: None,
+            ..received_erc20_log_entry()
+        };
+
+        assert_eq!(
+            ReceivedErc20Event::try_from(entry),
+            Err(ReceivedEthEventError::PendingLogEntry)
+        );
+    }
+
+    fn received_erc20_log_entry() -> LogEntry {
+        LogEntry {
+            address: "0xE1788E4834c896F1932188645cc36c54d1b80AC1"
+                .parse()
+                .unwrap(),
+            topics: vec![
+                FixedSizeData(RECEIVED_ERC20_EVENT_TOPIC),
+                FixedSizeData::from_str(
+                    "0x0000000000000000000000001c7d4b196cb0c7b01d743fbc6116a902379c7238",
+                )
+                .unwrap(),
+                FixedSizeData::from_str(
+                    "0x000000000000000000000000dd2851cdd40ae6536831558dd46db62fac7a844d",
+                )
+                .unwrap(),
+                FixedSizeData::from_str(
+                    "0x09efcdab00000000000100000000000000000000000000000000000000000000",
+                )
+                .unwrap(),
+            ],
+            data: Data(
+                hex::decode("00000000000000000000000000000000000000000000000000000000000f4240")
+                    .unwrap(),
+            ),
+            block_number: Some(BlockNumber::new(5_539_137)),
+            transaction_hash: Some(
+                "0x44d8e93a8f4bbc89ad35fc4fbbdb12cb597b4832da09c0b2300777be180fde87"
+                    .parse()
+                    .unwrap(),
+            ),
+            transaction_index: Some(0x22_u32.into()),
+            block_hash: Some(
+                "0x8436209a391f7bc076123616ecb229602124eb6c1007f5eae84df8e098885d3c"
+                    .parse()
+                    .unwrap(),
+            ),
+            log_index: Some(LogIndex::new(39)),
+            removed: false,
+        }
+    }
+}
@@ -6,7 +6,7 @@ use crate::eth_rpc::{
 use crate::eth_rpc_client::providers::{RpcNodeProvider, MAINNET_PROVIDERS, SEPOLIA_PROVIDERS};
 use crate::eth_rpc_client::requests::GetTransactionCountParams;
 use crate::eth_rpc_client::responses::TransactionReceipt;
-use crate::lifecycle::EthereumNetwork;
+use crate::lifecycle::{ConsensusStrategy, EthereumNetwork, RpcApi};
 use crate::logs::{DEBUG, INFO};
 use crate::numeric::TransactionCount;
 use crate::state::State;
@@ -25,22 +25,48 @@ mod tests;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EthRpcClient {
     chain: EthereumNetwork,
+    providers: Vec<RpcNodeProvider>,
+    consensus_strategy: ConsensusStrategy,
 }
 
 impl EthRpcClient {
-    const fn new(chain: EthereumNetwork) -> Self {
-        Self { chain }
+    /// Creates a client for the given chain.
+    /// If `custom_providers` is empty, the default providers of the chain are used.
+    fn new(
+        chain: EthereumNetwork,
+        custom_providers: &[RpcApi],
+        consensus_strategy: ConsensusStrategy,
+    ) -> Self {
+        let providers = if custom_providers.is_empty() {
+            match chain {
+                EthereumNetwork::Mainnet => MAINNET_PROVIDERS.to_vec(),
+                EthereumNetwork::Sepolia => SEPOLIA_PROVIDERS.to_vec(),
+                EthereumNetwork::Custom(_) => vec![],
+            }
+        } else {
+            custom_providers
+                .iter()
+                .cloned()
+                .map(RpcNodeProvider::Custom)
+                .collect()
+        };
+        Self {
+            chain,
+            providers,
+            consensus_strategy,
+        }
     }
 
-    pub const fn from_state(state: &State) -> Self {
-        Self::new(state.ethereum_network())
+    pub fn from_state(state: &State) -> Self {
+        Self::new(
+            state.ethereum_network(),
+            &state.json_rpc_providers,
+            state.consensus_strategy,
+        )
     }
 
     fn providers(&self) -> &[RpcNodeProvider] {
-        match self.chain {
-            EthereumNetwork::Mainnet => &MAINNET_PROVIDERS,
-            EthereumNetwork::Sepolia => &SEPOLIA_PROVIDERS,
-        }
+        &self.providers
     }
 
     /// Query all providers in sequence until one returns an ok result
@@ -130,7 +156,7 @@ impl EthRpcClient {
         let results: MultiCallResults<Vec<LogEntry>> = self
             .parallel_call("eth_getLogs", vec![params], ResponseSizeEstimate::new(100))
             .await;
-        results.reduce_with_equality()
+        results.reduce(self.consensus_strategy)
     }
 
     pub async fn eth_get_block_by_number(
@@ -141,7 +167,7 @@ impl EthRpcClient {
 
         let expected_block_size = match self.chain {
             EthereumNetwork::Sepolia => 12 * 1024,
-            EthereumNetwork::Mainnet => 24 * 1024,
+            EthereumNetwork::Mainnet | EthereumNetwork::Custom(_) => 24 * 1024,
         };
 
         let results: MultiCallResults<Block> = self
@@ -154,7 +180,7 @@ impl EthRpcClient {
                 ResponseSizeEstimate::new(expected_block_size),
             )
             .await;
-        results.reduce_with_equality()
+        results.reduce(self.consensus_strategy)
     }
 
     pub async fn eth_get_transaction_receipt(
@@ -168,7 +194,7 @@ impl EthRpcClient {
                 ResponseSizeEstimate::new(700),
             )
             .await;
-        results.reduce_with_equality()
+        results.reduce(self.consensus_strategy)
     }
 
     pub async fn eth_fee_history(
@@ -306,6 +332,13 @@ impl<T> MultiCallError<T> {
 }
 
 impl<T: Debug + PartialEq> MultiCallResults<T> {
+    pub fn reduce(self, strategy: ConsensusStrategy) -> Result<T, MultiCallError<T>> {
+        match strategy {
+            ConsensusStrategy::Equality => self.reduce_with_equality(),
+            ConsensusStrategy::StrictMajority => self.reduce_with_strict_majority(),
+        }
+    }
+
     pub fn reduce_with_equality(self) -> Result<T, MultiCallError<T>> {
         let mut results = self.all_ok()?.into_iter();
         let (base_node_provider, base_result) = results
@@ -330,6 +363,43 @@ impl<T: Debug + PartialEq> MultiCallResults<T> {
         Ok(base_result)
     }
 
+    /// Returns the result returned by more providers than any other result.
+    pub fn reduce_with_strict_majority(self) -> Result<T, MultiCallError<T>> {
+        let mut ballots: Vec<BTreeMap<RpcNodeProvider, T>> = Vec::new();
+        for (provider, result) in self.all_ok()?.into_iter() {
+            match ballots
+                .iter_mut()
+                .find(|ballot| ballot.values().next() == Some(&result))
+            {
+                Some(ballot) => {
+                    ballot.insert(provider, result);
+                }
+                None => ballots.push(BTreeMap::from([(provider, result)])),
+            }
+        }
+
+        ballots.sort_unstable_by_key(|ballot| ballot.len());
+        let mut first = ballots
+            .pop()
+            .expect("BUG: MultiCallResults is guaranteed to be non-empty");
+        match ballots.pop() {
+            Some(second) if second.len() >= first.len() => {
+                let error =
+                    MultiCallError::InconsistentResults(MultiCallResults::from_non_empty_iter(
+                        first.into_iter().chain(second).map(|(provider, result)| {
+                            (provider, Ok(JsonRpcResult::Result(result)))
+                        }),
+                    ));
+                log!(
+                    INFO,
+                    "[reduce_with_strict_majority]: no strict majority {error:?}"
+                );
+                Err(error)
+            }
+            _ => Ok(first.pop_last().expect("BUG: ballot should be non-empty").1),
+        }
+    }
+
     pub fn reduce_with_min_by_key<F: FnMut(&T) -> K, K: Ord>(
         self,
         extractor: F,
@@ -1,11 +1,11 @@
 mod eth_rpc_client {
     use crate::eth_rpc_client::providers::{EthereumProvider, RpcNodeProvider, SepoliaProvider};
     use crate::eth_rpc_client::EthRpcClient;
-    use crate::lifecycle::EthereumNetwork;
+    use crate::lifecycle::{ConsensusStrategy, EthereumNetwork, RpcApi};
 
     #[test]
     fn should_retrieve_sepolia_providers_in_stable_order() {
-        let client = EthRpcClient::new(EthereumNetwork::Sepolia);
+        let client = EthRpcClient::new(EthereumNetwork::Sepolia, &[], ConsensusStrategy::default());
 
         let providers = client.providers();
 
@@ -20,7 +20,7 @@ mod eth_rpc_client {
 
     #[test]
     fn should_retrieve_mainnet_providers_in_stable_order() {
-        let client = EthRpcClient::new(EthereumNetwork::Mainnet);
+        let client = EthRpcClient::new(EthereumNetwork::Mainnet, &[], ConsensusStrategy::default());
 
         let providers = client.providers();
 
@@ -33,6 +33,35 @@ mod eth_rpc_client {
             ]
         );
     }
+
+    #[test]
+    fn should_use_custom_providers_in_given_order() {
+        let custom_providers = vec![
+            RpcApi {
+                url: "https://rpc2.example.com".to_string(),
+            },
+            RpcApi {
+                url: "https://rpc1.example.com".to_string(),
+            },
+        ];
+
+        for network in [
+            EthereumNetwork::Mainnet,
+            EthereumNetwork::Sepolia,
+            EthereumNetwork::Custom(31337),
+        ] {
+            let client =
+                EthRpcClient::new(network, &custom_providers, ConsensusStrategy::default());
+
+            assert_eq!(
+                client.providers(),
+                &[
+                    RpcNodeProvider::Custom(custom_providers[0].clone()),
+                    RpcNodeProvider::Custom(custom_providers[1].clone()),
+                ]
+            );
+        }
+    }
 }
 
 mod multi_call_results {
@@ -429,6 +458,81 @@ mod multi_call_results {
         }
     }
 
+    mod reduce_with_strict_majority {
+        use crate::eth_rpc::{HttpOutcallError, JsonRpcResult};
+        use crate::eth_rpc_client::tests::multi_call_results::{ANKR, CLOUDFLARE, PUBLIC_NODE};
+        use crate::eth_rpc_client::{MultiCallError, MultiCallResults};
+        use crate::lifecycle::ConsensusStrategy;
+        use ic_cdk::api::call::RejectionCode;
+
+        #[test]
+        fn should_get_result_with_2_out_of_3() {
+            for index_non_majority in 0..3_usize {
+                let mut values = ["hello", "hello", "hello"].map(|v| v.to_string());
+                values[index_non_majority] = "world".to_string();
+                let [ankr, cloudflare, public_node] = values;
+                let results: MultiCallResults<String> =
+                    MultiCallResults::from_non_empty_iter(vec![
+                        (ANKR, Ok(JsonRpcResult::Result(ankr))),
+                        (CLOUDFLARE, Ok(JsonRpcResult::Result(cloudflare))),
+                        (PUBLIC_NODE, Ok(JsonRpcResult::Result(public_node))),
+                    ]);
+
+                assert_eq!(
+                    results.clone().reduce_with_strict_majority(),
+                    Ok("hello".to_string())
+                );
+                assert_eq!(
+                    results.clone().reduce(ConsensusStrategy::StrictMajority),
+                    Ok("hello".to_string())
+                );
+                assert_eq!(
+                    results.clone().reduce(ConsensusStrategy::Equality),
+                    results.reduce_with_equality()
+                );
+            }
+        }
+
+        #[test]
+        fn should_fail_when_no_strict_majority() {
+            let results: MultiCallResults<String> = MultiCallResults::from_non_empty_iter(vec![
+                (ANKR, Ok(JsonRpcResult::Result("hello".to_string()))),
+                (PUBLIC_NODE, Ok(JsonRpcResult::Result("world".to_string()))),
+            ]);
+
+            let reduced = results.clone().reduce_with_strict_majority();
+
+            assert_eq!(reduced, Err(MultiCallError::InconsistentResults(results)));
+        }
+
+        #[test]
+        fn should_fail_upon_any_error() {
+            let results: MultiCallResults<String> = MultiCallResults::from_non_empty_iter(vec![
+                (ANKR, Ok(JsonRpcResult::Result("hello".to_string()))),
+                (CLOUDFLARE, Ok(JsonRpcResult::Result("hello".to_string()))),
+                (
+                    PUBLIC_NODE,
+                    Err(HttpOutcallError::IcError {
+                        code: RejectionCode::SysTransient,
+                        message: "transient".to_string(),
+                    }),
+                ),
+            ]);
+
+            let reduced = results.reduce_with_strict_majority();
+
+            assert_eq!(
+                reduced,
+                Err(MultiCallError::ConsistentHttpOutcallError(
+                    HttpOutcallError::IcError {
+                        code: RejectionCode::SysTransient,
+                        message: "transient".to_string(),
+                    }
+                ))
+            );
+        }
+    }
+
     mod has_http_outcall_error_matching {
         use super::*;
         use crate::eth_rpc::{HttpOutcallError, JsonRpcResult};
@@ -31,6 +31,7 @@ impl RequestsGuardedByPrincipal for PendingRetrieveEthRequests {
 
     fn pending_requests_count(state: &State) -> usize {
         state.eth_transactions.withdrawal_requests_len()
+            + state.eth_transactions.erc20_withdrawal_requests_len()
     }
 }
 
@@ -145,6 +145,8 @@ fn init_state() {
                 minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
                 next_transaction_nonce: Default::default(),
                 last_scraped_block_number: Default::default(),
+                json_rpc_providers: None,
+                consensus_strategy: None,
             })
             .expect("init args should be valid"),
         );
@@ -18,16 +18,13 @@ pub enum MinterArg {
     UpgradeArg(UpgradeArg),
 }
 
-#[derive(
-    CandidType, Clone, Copy, Default, Deserialize, Debug, Eq, PartialEq, Hash, Encode, Decode,
-)]
-#[cbor(index_only)]
+#[derive(CandidType, Clone, Copy, Default, Deserialize, Debug, Eq, PartialEq, Hash)]
 pub enum EthereumNetwork {
-    #[n(1)]
     Mainnet,
-    #[n(11155111)]
     #[default]
     Sepolia,
+    /// Any other EVM-compatible network, identified by its EIP-155 chain ID.
+    Custom(u64),
 }
 
 impl EthereumNetwork {
@@ -35,6 +32,15 @@ impl EthereumNetwork {
         match self {
             EthereumNetwork::Mainnet => 1,
             EthereumNetwork::Sepolia => 11155111,
+            EthereumNetwork::Custom(chain_id) => *chain_id,
+        }
+    }
+
+    pub fn from_chain_id(chain_id: u64) -> Self {
+        match chain_id {
+            1 => EthereumNetwork::Mainnet,
+            11155111 => EthereumNetwork::Sepolia,
+            _ => EthereumNetwork::Custom(chain_id),
         }
     }
 }
@@ -44,6 +50,55 @@ impl Display for EthereumNetwork {
         match self {
             EthereumNetwork::Mainnet => write!(f, "Ethereum Mainnet"),
             EthereumNetwork::Sepolia => write!(f, "Ethereum Testnet Sepolia"),
+            EthereumNetwork::Custom(chain_id) => write!(f, "EVM chain {chain_id}"),
         }
     }
 }
+
+// The network is encoded as its chain ID, which is backward compatible with
+// the former index-only encoding where the indices were the chain IDs.
+impl<C> minicbor::Encode<C> for EthereumNetwork {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.u64(self.chain_id())?;
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::Decode<'b, C> for EthereumNetwork {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        d.u64().map(Self::from_chain_id)
+    }
+}
+
+/// JSON-RPC endpoint of an Ethereum node provider.
+#[derive(
+    CandidType, Clone, Deserialize, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Encode, Decode,
+)]
+pub struct RpcApi {
+    #[n(0)]
+    pub url: String,
+}
+
+/// Determines how the responses of the different JSON-RPC providers are
+/// aggregated for queries that are critical for the minter, such as logs,
+/// blocks and transaction receipts.
+#[derive(
+    CandidType, Clone, Copy, Default, Deserialize, Debug, Eq, PartialEq, Hash, Encode, Decode,
+)]
+#[cbor(index_only)]
+pub enum ConsensusStrategy {
+    /// All providers must return the same response.
+    #[n(0)]
+    #[default]
+    Equality,
+    /// More providers must agree on a response than on any other response.
+    #[n(1)]
+    StrictMajority,
+}
@@ -1,6 +1,6 @@
 use crate::endpoints::CandidBlockTag;
 use crate::eth_rpc::BlockTag;
-use crate::lifecycle::EthereumNetwork;
+use crate::lifecycle::{ConsensusStrategy, EthereumNetwork, RpcApi};
 use crate::numeric::{BlockNumber, TransactionNonce, Wei};
 use crate::state::transactions::EthTransactions;
 use crate::state::{InvalidStateError, State};
@@ -28,6 +28,10 @@ pub struct InitArg {
     pub next_transaction_nonce: Nat,
     #[cbor(n(8), with = "crate::cbor::nat")]
     pub last_scraped_block_number: Nat,
+    #[n(9)]
+    pub json_rpc_providers: Option<Vec<RpcApi>>,
+    #[n(10)]
+    pub consensus_strategy: Option<ConsensusStrategy>,
 }
 
 impl TryFrom<InitArg> for State {
@@ -42,6 +46,8 @@ impl TryFrom<InitArg> for State {
             minimum_withdrawal_amount,
             next_transaction_nonce,
             last_scraped_block_number,
+            json_rpc_providers,
+            consensus_strategy,
         }: InitArg,
     ) -> Result<Self, Self::Error> {
         use std::str::FromStr;
@@ -71,6 +77,8 @@ impl TryFrom<InitArg> for State {
                 })?;
         let state = Self {
             ethereum_network,
+            json_rpc_providers: json_rpc_providers.unwrap_or_default(),
+            consensus_strategy: consensus_strategy.unwrap_or_default(),
             ecdsa_key_name,
             ethereum_contract_address,
             retrieve_eth_principals: Default::default(),
@@ -87,6 +95,13 @@ impl TryFrom<InitArg> for State {
             invalid_events: Default::default(),
             eth_balance: Default::default(),
             skipped_blocks: Default::default(),
+            erc20_helper_contract_address: None,
+            last_erc20_scraped_block_number: last_scraped_block_number,
+            ledger_suite_orchestrator_id: None,
+            ckerc20_tokens: Default::default(),
+            erc20_events_to_mint: Default::default(),
+            minted_erc20_events: Default::default(),
+            skipped_erc20_blocks: Default::default(),
             active_tasks: Default::default(),
             http_request_counter: 0,
         };
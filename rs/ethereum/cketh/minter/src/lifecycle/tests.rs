@@ -1,5 +1,6 @@
 mod init {
     use crate::lifecycle::init::InitArg;
+    use crate::lifecycle::{ConsensusStrategy, EthereumNetwork, RpcApi};
     use crate::numeric::{TransactionNonce, Wei};
     use crate::state::{InvalidStateError, State};
     use assert_matches::assert_matches;
@@ -69,6 +70,50 @@ mod init {
             }),
             Err(InvalidStateError::InvalidLastScrapedBlockNumber(_))
         );
+
+        assert_matches!(
+            State::try_from(InitArg {
+                ethereum_network: EthereumNetwork::Custom(31337),
+                ..valid_init_arg()
+            }),
+            Err(InvalidStateError::InvalidJsonRpcProviders(_))
+        );
+
+        assert_matches!(
+            State::try_from(InitArg {
+                ethereum_network: EthereumNetwork::Custom(1),
+                json_rpc_providers: Some(vec![RpcApi {
+                    url: "https://rpc.example.com".to_string(),
+                }]),
+                ..valid_init_arg()
+            }),
+            Err(InvalidStateError::InvalidEthereumNetwork(_))
+        );
+    }
+
+    #[test]
+    fn should_succeed_with_custom_network() {
+        let providers = vec![
+            RpcApi {
+                url: "https://rpc1.example.com".to_string(),
+            },
+            RpcApi {
+                url: "https://rpc2.example.com".to_string(),
+            },
+        ];
+        let init_arg = InitArg {
+            ethereum_network: EthereumNetwork::Custom(31337),
+            json_rpc_providers: Some(providers.clone()),
+            consensus_strategy: Some(ConsensusStrategy::StrictMajority),
+            ..valid_init_arg()
+        };
+
+        let state = State::try_from(init_arg).expect("valid init args");
+
+        assert_eq!(state.ethereum_network, EthereumNetwork::Custom(31337));
+        assert_eq!(state.ethereum_network.chain_id(), 31337);
+        assert_eq!(state.json_rpc_providers, providers);
+        assert_eq!(state.consensus_strategy, ConsensusStrategy::StrictMajority);
     }
 
     #[test]
@@ -99,6 +144,8 @@ mod init {
             minimum_withdrawal_amount: Wei::TWO.into(),
             next_transaction_nonce: TransactionNonce::ZERO.into(),
             last_scraped_block_number: Default::default(),
+            json_rpc_providers: None,
+            consensus_strategy: None,
         }
     }
 }
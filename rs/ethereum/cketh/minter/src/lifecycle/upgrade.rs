@@ -1,10 +1,11 @@
 use crate::endpoints::CandidBlockTag;
+use crate::lifecycle::{ConsensusStrategy, EthereumNetwork, RpcApi};
 use crate::logs::INFO;
 use crate::state::audit::{process_event, replay_events, EventType};
 use crate::state::mutate_state;
 use crate::state::STATE;
 use crate::storage::total_event_count;
-use candid::{CandidType, Deserialize, Nat};
+use candid::{CandidType, Deserialize, Nat, Principal};
 use ic_canister_log::log;
 use minicbor::{Decode, Encode};
 
@@ -18,6 +19,18 @@ pub struct UpgradeArg {
     pub ethereum_contract_address: Option<String>,
     #[n(3)]
     pub ethereum_block_height: Option<CandidBlockTag>,
+    #[n(4)]
+    pub ethereum_network: Option<EthereumNetwork>,
+    #[n(5)]
+    pub json_rpc_providers: Option<Vec<RpcApi>>,
+    #[n(6)]
+    pub consensus_strategy: Option<ConsensusStrategy>,
+    #[n(7)]
+    pub erc20_helper_contract_address: Option<String>,
+    #[cbor(n(8), with = "crate::cbor::nat::option")]
+    pub last_erc20_scraped_block_number: Option<Nat>,
+    #[cbor(n(9), with = "crate::cbor::principal::option")]
+    pub ledger_suite_orchestrator_id: Option<Principal>,
 }
 
 pub fn post_upgrade(upgrade_args: Option<UpgradeArg>) {
@@ -8,18 +8,25 @@ use ic_cketh_minter::endpoints::events::{
     Event as CandidEvent, EventSource as CandidEventSource, GetEventsArg, GetEventsResult,
 };
 use ic_cketh_minter::endpoints::{
-    Eip1559TransactionPrice, RetrieveEthRequest, RetrieveEthStatus, WithdrawalArg, WithdrawalError,
+    AddCkErc20Token, Eip1559TransactionPrice, LedgerError, RetrieveErc20Request,
+    RetrieveEthRequest, RetrieveEthStatus, WithdrawErc20Arg, WithdrawErc20Error, WithdrawalArg,
+    WithdrawalError,
 };
-use ic_cketh_minter::eth_logs::{EventSource, ReceivedEthEvent};
+use ic_cketh_minter::erc20::CkErc20Token;
+use ic_cketh_minter::eth_logs::{EventSource, ReceivedErc20Event, ReceivedEthEvent};
 use ic_cketh_minter::guard::retrieve_eth_guard;
 use ic_cketh_minter::lifecycle::MinterArg;
 use ic_cketh_minter::logs::{DEBUG, INFO};
 use ic_cketh_minter::memo::BurnMemo;
-use ic_cketh_minter::numeric::{LedgerBurnIndex, Wei};
+use ic_cketh_minter::numeric::{Erc20Value, LedgerBurnIndex, Wei};
 use ic_cketh_minter::state::audit::{process_event, Event, EventType};
-use ic_cketh_minter::state::transactions::{EthWithdrawalRequest, Reimbursed};
+use ic_cketh_minter::state::transactions::{
+    Erc20Reimbursed, Erc20WithdrawalRequest, EthWithdrawalRequest, Reimbursed, ReimbursementRequest,
+};
 use ic_cketh_minter::state::{lazy_call_ecdsa_public_key, mutate_state, read_state, State, STATE};
-use ic_cketh_minter::tx::estimate_transaction_price;
+use ic_cketh_minter::tx::{
+    estimate_transaction_price, TransactionPrice, CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT,
+};
 use ic_cketh_minter::withdraw::{
     eth_fee_history, process_reimbursement, process_retrieve_eth_requests,
 };
@@ -89,6 +96,11 @@ fn emit_preupgrade_events() {
         storage::record_event(EventType::SyncedToBlock {
             block_number: s.last_scraped_block_number,
         });
+        if s.erc20_helper_contract_address.is_some() {
+            storage::record_event(EventType::SyncedErc20ToBlock {
+                block_number: s.last_erc20_scraped_block_number,
+            });
+        }
     });
 }
 
@@ -236,6 +248,223 @@ async fn withdraw_eth(
     }
 }
 
+#[update]
+async fn withdraw_erc20(
+    WithdrawErc20Arg {
+        amount,
+        ckerc20_ledger_id,
+        recipient,
+    }: WithdrawErc20Arg,
+) -> Result<RetrieveErc20Request, WithdrawErc20Error> {
+    let caller = validate_caller_not_anonymous();
+    let _guard = retrieve_eth_guard(caller).unwrap_or_else(|e| {
+        ic_cdk::trap(&format!(
+            "Failed retrieving guard for principal {}: {:?}",
+            caller, e
+        ))
+    });
+
+    let destination = validate_address_as_destination(&recipient).map_err(|e| match e {
+        AddressValidationError::Invalid { .. } | AddressValidationError::NotSupported(_) => {
+            ic_cdk::trap(&e.to_string())
+        }
+        AddressValidationError::Blocked(address) => WithdrawErc20Error::RecipientAddressBlocked {
+            address: address.to_string(),
+        },
+    })?;
+    let ckerc20_withdrawal_amount =
+        Erc20Value::try_from(amount).expect("failed to convert Nat to u256");
+
+    let ckerc20_token = read_state(|s| s.find_ck_erc20_token_by_ledger_id(&ckerc20_ledger_id))
+        .ok_or_else(|| WithdrawErc20Error::TokenNotSupported {
+            supported_tokens: read_state(State::supported_ckerc20_token_symbols),
+        })?;
+
+    // The transaction fee is paid upfront in ckETH, so that the recipient
+    // receives exactly the requested amount of ERC-20 tokens.
+    let erc20_tx_fee = estimate_transaction_price(&eth_fee_history().await.map_err(|e| {
+        WithdrawErc20Error::TemporarilyUnavailable(format!("failed to retrieve fee history: {e:?}"))
+    })?)
+    .map(|price| {
+        TransactionPrice {
+            gas_limit: CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT,
+            ..price
+        }
+        .max_transaction_fee()
+    })
+    .map_err(|e| {
+        WithdrawErc20Error::TemporarilyUnavailable(format!(
+            "failed to estimate transaction price: {e:?}"
+        ))
+    })?;
+
+    let cketh_ledger_id = read_state(|s| s.ledger_id);
+    let now = ic_cdk::api::time();
+
+    log!(
+        INFO,
+        "[withdraw_erc20]: burning {:?} ckETH for the transaction fee",
+        erc20_tx_fee
+    );
+    let cketh_ledger_burn_index = match (ICRC1Client {
+        runtime: CdkRuntime,
+        ledger_canister_id: cketh_ledger_id,
+    })
+    .transfer_from(TransferFromArgs {
+        spender_subaccount: None,
+        from: caller.into(),
+        to: ic_cdk::id().into(),
+        amount: Nat::from(erc20_tx_fee),
+        fee: None,
+        memo: Some(Memo::from(BurnMemo::Erc20GasFee {
+            ckerc20_token_symbol: ckerc20_token.ckerc20_token_symbol.clone(),
+            ckerc20_withdrawal_amount,
+            to_address: destination,
+        })),
+        created_at_time: None,
+    })
+    .await
+    {
+        Ok(Ok(block_index)) => {
+            LedgerBurnIndex::new(block_index.0.to_u64().expect("nat does not fit into u64"))
+        }
+        Ok(Err(error)) => {
+            log!(
+                DEBUG,
+                "[withdraw_erc20]: failed to burn ckETH with error: {error:?}"
+            );
+            return Err(WithdrawErc20Error::CkEthLedgerError {
+                error: LedgerError::from_transfer_from_error(
+                    error,
+                    Nat::from(erc20_tx_fee),
+                    "ckETH",
+                    cketh_ledger_id,
+                ),
+            });
+        }
+        Err((error_code, message)) => {
+            log!(
+                DEBUG,
+                "[withdraw_erc20]: failed to call ckETH ledger with error_code: {error_code} and message: {message}",
+            );
+            return Err(WithdrawErc20Error::CkEthLedgerError {
+                error: LedgerError::TemporarilyUnavailable(format!(
+                    "failed to call ckETH ledger with error_code: {error_code} and message: {message}"
+                )),
+            });
+        }
+    };
+
+    log!(
+        INFO,
+        "[withdraw_erc20]: burning {:?} {}",
+        ckerc20_withdrawal_amount,
+        ckerc20_token.ckerc20_token_symbol
+    );
+    let ckerc20_burn_result = (ICRC1Client {
+        runtime: CdkRuntime,
+        ledger_canister_id: ckerc20_token.ckerc20_ledger_id,
+    })
+    .transfer_from(TransferFromArgs {
+        spender_subaccount: None,
+        from: caller.into(),
+        to: ic_cdk::id().into(),
+        amount: Nat::from(ckerc20_withdrawal_amount),
+        fee: None,
+        memo: Some(Memo::from(BurnMemo::Erc20Convert {
+            ckerc20_withdrawal_id: cketh_ledger_burn_index.get(),
+            to_address: destination,
+        })),
+        created_at_time: None,
+    })
+    .await;
+    let ckerc20_ledger_error = match ckerc20_burn_result {
+        Ok(Ok(block_index)) => {
+            let withdrawal_request = Erc20WithdrawalRequest {
+                max_transaction_fee: erc20_tx_fee,
+                withdrawal_amount: ckerc20_withdrawal_amount,
+                destination,
+                cketh_ledger_burn_index,
+                erc20_contract_address: ckerc20_token.erc20_contract_address,
+                ckerc20_ledger_id: ckerc20_token.ckerc20_ledger_id,
+                ckerc20_ledger_burn_index: LedgerBurnIndex::new(
+                    block_index.0.to_u64().expect("nat does not fit into u64"),
+                ),
+                from: caller,
+                from_subaccount: None,
+                created_at: now,
+            };
+            log!(
+                INFO,
+                "[withdraw_erc20]: queuing withdrawal request {:?}",
+                withdrawal_request,
+            );
+            mutate_state(|s| {
+                process_event(
+                    s,
+                    EventType::AcceptedErc20WithdrawalRequest(withdrawal_request.clone()),
+                );
+            });
+            return Ok(RetrieveErc20Request {
+                cketh_block_index: Nat::from(withdrawal_request.cketh_ledger_burn_index.get()),
+                ckerc20_block_index: Nat::from(withdrawal_request.ckerc20_ledger_burn_index.get()),
+            });
+        }
+        Ok(Err(error)) => LedgerError::from_transfer_from_error(
+            error,
+            Nat::from(ckerc20_withdrawal_amount),
+            &ckerc20_token.ckerc20_token_symbol,
+            ckerc20_token.ckerc20_ledger_id,
+        ),
+        Err((error_code, message)) => LedgerError::TemporarilyUnavailable(format!(
+            "failed to call {} ledger with error_code: {error_code} and message: {message}",
+            ckerc20_token.ckerc20_token_symbol
+        )),
+    };
+    log!(
+        INFO,
+        "[withdraw_erc20]: failed to burn {}: {ckerc20_ledger_error:?}, reimbursing {:?} ckETH",
+        ckerc20_token.ckerc20_token_symbol,
+        erc20_tx_fee
+    );
+    mutate_state(|s| {
+        process_event(
+            s,
+            EventType::FailedErc20WithdrawalRequest(ReimbursementRequest {
+                withdrawal_id: cketh_ledger_burn_index,
+                reimbursed_amount: erc20_tx_fee,
+                to: caller,
+                to_subaccount: None,
+                transaction_hash: None,
+            }),
+        );
+    });
+    Err(WithdrawErc20Error::CkErc20LedgerError {
+        cketh_block_index: Nat::from(cketh_ledger_burn_index.get()),
+        error: ckerc20_ledger_error,
+    })
+}
+
+#[update]
+fn add_ckerc20_token(erc20_token: AddCkErc20Token) {
+    let orchestrator_id = read_state(|s| s.ledger_suite_orchestrator_id)
+        .unwrap_or_else(|| ic_cdk::trap("ERROR: ERC-20 feature is not activated"));
+    if orchestrator_id != ic_cdk::caller() {
+        ic_cdk::trap(&format!(
+            "ERROR: only the orchestrator {} can add ERC-20 tokens",
+            orchestrator_id
+        ));
+    }
+    let ckerc20_token = CkErc20Token::try_from(erc20_token)
+        .unwrap_or_else(|e| ic_cdk::trap(&format!("ERROR: {}", e)));
+    mutate_state(|s| {
+        s.validate_ckerc20_token(&ckerc20_token)
+            .unwrap_or_else(|e| ic_cdk::trap(&format!("ERROR: {}", e)));
+        log!(INFO, "[add_ckerc20_token]: adding {ckerc20_token:?}");
+        process_event(s, EventType::AddedCkErc20Token(ckerc20_token));
+    });
+}
+
 #[update]
 async fn retrieve_eth_status(block_index: u64) -> RetrieveEthStatus {
     let ledger_burn_index = LedgerBurnIndex::new(block_index);
@@ -422,6 +651,106 @@ fn get_events(arg: GetEventsArg) -> GetEventsResult {
                 EventType::SkippedBlock(block_number) => EP::SkippedBlock {
                     block_number: block_number.into(),
                 },
+                EventType::AddedCkErc20Token(CkErc20Token {
+                    erc20_ethereum_network,
+                    erc20_contract_address,
+                    ckerc20_token_symbol,
+                    ckerc20_ledger_id,
+                }) => EP::AddedCkErc20Token {
+                    chain_id: erc20_ethereum_network.chain_id().into(),
+                    address: erc20_contract_address.to_string(),
+                    ckerc20_token_symbol,
+                    ckerc20_ledger_id,
+                },
+                EventType::AcceptedErc20Deposit(ReceivedErc20Event {
+                    transaction_hash,
+                    block_number,
+                    log_index,
+                    from_address,
+                    value,
+                    principal,
+                    erc20_contract_address,
+                }) => EP::AcceptedErc20Deposit {
+                    transaction_hash: transaction_hash.to_string(),
+                    block_number: block_number.into(),
+                    log_index: log_index.into(),
+                    from_address: from_address.to_string(),
+                    value: value.into(),
+                    principal,
+                    erc20_contract_address: erc20_contract_address.to_string(),
+                },
+                EventType::MintedCkErc20 {
+                    event_source,
+                    mint_block_index,
+                    ckerc20_token_symbol,
+                    erc20_contract_address,
+                } => EP::MintedCkErc20 {
+                    event_source: map_event_source(event_source),
+                    mint_block_index: mint_block_index.get().into(),
+                    ckerc20_token_symbol,
+                    erc20_contract_address: erc20_contract_address.to_string(),
+                },
+                EventType::SyncedErc20ToBlock { block_number } => EP::SyncedErc20ToBlock {
+                    block_number: block_number.into(),
+                },
+                EventType::AcceptedErc20WithdrawalRequest(Erc20WithdrawalRequest {
+                    max_transaction_fee,
+                    withdrawal_amount,
+                    destination,
+                    cketh_ledger_burn_index,
+                    erc20_contract_address,
+                    ckerc20_ledger_id,
+                    ckerc20_ledger_burn_index,
+                    from,
+                    from_subaccount,
+                    created_at,
+                }) => EP::AcceptedErc20WithdrawalRequest {
+                    max_transaction_fee: max_transaction_fee.into(),
+                    withdrawal_amount: withdrawal_amount.into(),
+                    erc20_contract_address: erc20_contract_address.to_string(),
+                    destination: destination.to_string(),
+                    cketh_ledger_burn_index: cketh_ledger_burn_index.get().into(),
+                    ckerc20_ledger_id,
+                    ckerc20_ledger_burn_index: ckerc20_ledger_burn_index.get().into(),
+                    from,
+                    from_subaccount: from_subaccount.map(|s| s.0),
+                    created_at,
+                },
+                EventType::ReimbursedErc20Withdrawal(Erc20Reimbursed {
+                    withdrawal_id,
+                    reimbursed_in_block,
+                    ledger_id,
+                    reimbursed_amount,
+                    transaction_hash,
+                }) => EP::ReimbursedErc20Withdrawal {
+                    withdrawal_id: withdrawal_id.get().into(),
+                    reimbursed_in_block: reimbursed_in_block.get().into(),
+                    ledger_id,
+                    reimbursed_amount: reimbursed_amount.into(),
+                    transaction_hash: transaction_hash.map(|h| h.to_string()),
+                },
+                EventType::SkippedErc20Block(block_number) => EP::SkippedErc20Block {
+                    block_number: block_number.into(),
+                },
+                EventType::FailedErc20WithdrawalRequest(ReimbursementRequest {
+                    withdrawal_id,
+                    reimbursed_amount,
+                    to,
+                    to_subaccount,
+                    transaction_hash: _,
+                }) => EP::FailedErc20WithdrawalRequest {
+                    withdrawal_id: withdrawal_id.get().into(),
+                    reimbursed_amount: reimbursed_amount.into(),
+                    to,
+                    to_subaccount: to_subaccount.map(|s| s.0),
+                },
+                EventType::CancelledWithdrawalRequest {
+                    withdrawal_id,
+                    reason,
+                } => EP::CancelledWithdrawalRequest {
+                    withdrawal_id: withdrawal_id.get().into(),
+                    reason,
+                },
             },
         }
     }
@@ -1,10 +1,10 @@
 #[cfg(test)]
 mod tests;
 
-use crate::eth_logs::ReceivedEthEvent;
+use crate::eth_logs::{ReceivedErc20Event, ReceivedEthEvent};
 use crate::eth_rpc::Hash;
-use crate::numeric::LogIndex;
-use crate::state::transactions::ReimbursementRequest;
+use crate::numeric::{Erc20Value, LogIndex};
+use crate::state::transactions::{Erc20ReimbursementRequest, ReimbursementRequest};
 use ic_ethereum_types::Address;
 use icrc_ledger_types::icrc1::transfer::Memo;
 use minicbor::{Decode, Encode, Encoder};
@@ -39,6 +39,14 @@ pub enum MintMemo {
         /// Hash of the failed transaction.
         tx_hash: Hash,
     },
+    #[n(2)]
+    /// The minter reimbursed a withdrawal request that failed
+    /// before any transaction was created.
+    ReimburseWithdrawal {
+        #[n(0)]
+        /// The id corresponding to the withdrawal request.
+        withdrawal_id: u64,
+    },
 }
 
 impl From<MintMemo> for Memo {
@@ -56,6 +64,31 @@ pub enum BurnMemo {
         /// The destination of the withdraw request.
         to_address: Address,
     },
+    #[n(1)]
+    /// The minter processed a ckERC20 withdrawal request
+    /// and burned ckETH to pay for the transaction fee.
+    Erc20GasFee {
+        #[n(0)]
+        /// The symbol of the withdrawn ckERC20 token.
+        ckerc20_token_symbol: String,
+        #[n(1)]
+        /// The amount of withdrawn ckERC20 tokens.
+        ckerc20_withdrawal_amount: Erc20Value,
+        #[n(2)]
+        /// The destination of the withdraw request.
+        to_address: Address,
+    },
+    #[n(2)]
+    /// The minter processed a ckERC20 withdrawal request.
+    Erc20Convert {
+        #[n(0)]
+        /// The id corresponding to the withdrawal request,
+        /// i.e., the index of the ckETH burn transaction.
+        ckerc20_withdrawal_id: u64,
+        #[n(1)]
+        /// The destination of the withdraw request.
+        to_address: Address,
+    },
 }
 
 impl From<BurnMemo> for Memo {
@@ -74,13 +107,38 @@ impl From<ReceivedEthEvent> for Memo {
     }
 }
 
+impl From<ReceivedErc20Event> for Memo {
+    fn from(event: ReceivedErc20Event) -> Self {
+        Memo::from(MintMemo::Convert {
+            from_address: event.from_address,
+            tx_hash: event.transaction_hash,
+            log_index: event.log_index,
+        })
+    }
+}
+
 impl From<ReimbursementRequest> for Memo {
     fn from(reimbursement_request: ReimbursementRequest) -> Self {
-        Memo::from(MintMemo::Reimburse {
-            withdrawal_id: reimbursement_request.withdrawal_id.get(),
-            tx_hash: reimbursement_request
-                .transaction_hash
-                .expect("A hash should be set for reimbursement memos."),
-        })
+        let withdrawal_id = reimbursement_request.withdrawal_id.get();
+        match reimbursement_request.transaction_hash {
+            Some(tx_hash) => Memo::from(MintMemo::Reimburse {
+                withdrawal_id,
+                tx_hash,
+            }),
+            None => Memo::from(MintMemo::ReimburseWithdrawal { withdrawal_id }),
+        }
+    }
+}
+
+impl From<Erc20ReimbursementRequest> for Memo {
+    fn from(reimbursement_request: Erc20ReimbursementRequest) -> Self {
+        let withdrawal_id = reimbursement_request.withdrawal_id.get();
+        match reimbursement_request.transaction_hash {
+            Some(tx_hash) => Memo::from(MintMemo::Reimburse {
+                withdrawal_id,
+                tx_hash,
+            }),
+            None => Memo::from(MintMemo::ReimburseWithdrawal { withdrawal_id }),
+        }
     }
 }
@@ -1,9 +1,10 @@
 use crate::address::ecdsa_public_key_to_address;
-use crate::eth_logs::{EventSource, ReceivedEthEvent};
+use crate::erc20::CkErc20Token;
+use crate::eth_logs::{EventSource, ReceivedErc20Event, ReceivedEthEvent};
 use crate::eth_rpc::BlockTag;
 use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
 use crate::lifecycle::upgrade::UpgradeArg;
-use crate::lifecycle::EthereumNetwork;
+use crate::lifecycle::{ConsensusStrategy, EthereumNetwork, RpcApi};
 use crate::logs::DEBUG;
 use crate::numeric::{BlockNumber, LedgerBurnIndex, LedgerMintIndex, TransactionNonce, Wei};
 use candid::Principal;
@@ -39,9 +40,25 @@ impl MintedEvent {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MintedErc20Event {
+    pub deposit_event: ReceivedErc20Event,
+    pub mint_block_index: LedgerMintIndex,
+}
+
+impl MintedErc20Event {
+    pub fn source(&self) -> EventSource {
+        self.deposit_event.source()
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct State {
     pub ethereum_network: EthereumNetwork,
+    /// JSON-RPC providers to query.
+    /// If empty, the default providers of `ethereum_network` are used.
+    pub json_rpc_providers: Vec<RpcApi>,
+    pub consensus_strategy: ConsensusStrategy,
     pub ecdsa_key_name: String,
     pub ledger_id: Principal,
     pub ethereum_contract_address: Option<Address>,
@@ -57,6 +74,18 @@ pub struct State {
     pub eth_transactions: EthTransactions,
     pub skipped_blocks: BTreeSet<BlockNumber>,
 
+    /// Address of the helper smart contract emitting `ReceivedErc20` logs.
+    /// ERC-20 deposits are not scraped if unset.
+    pub erc20_helper_contract_address: Option<Address>,
+    pub last_erc20_scraped_block_number: BlockNumber,
+    /// The only canister allowed to add new ckERC20 tokens.
+    pub ledger_suite_orchestrator_id: Option<Principal>,
+    /// ckERC20 tokens supported by the minter, indexed by their ERC-20 contract address.
+    pub ckerc20_tokens: BTreeMap<Address, CkErc20Token>,
+    pub erc20_events_to_mint: BTreeMap<EventSource, ReceivedErc20Event>,
+    pub minted_erc20_events: BTreeMap<EventSource, MintedErc20Event>,
+    pub skipped_erc20_blocks: BTreeSet<BlockNumber>,
+
     /// Current balance of ETH held by minter.
     /// Computed based on audit events.
     pub eth_balance: EthBalance,
@@ -73,12 +102,16 @@ pub struct State {
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum InvalidStateError {
+    InvalidEthereumNetwork(String),
+    InvalidJsonRpcProviders(String),
     InvalidTransactionNonce(String),
     InvalidEcdsaKeyName(String),
     InvalidLedgerId(String),
     InvalidEthereumContractAddress(String),
     InvalidMinimumWithdrawalAmount(String),
     InvalidLastScrapedBlockNumber(String),
+    InvalidErc20HelperContractAddress(String),
+    InvalidLedgerSuiteOrchestratorId(String),
 }
 
 impl State {
@@ -102,11 +135,52 @@ impl State {
                 "ethereum_contract_address cannot be the zero address".to_string(),
             ));
         }
+        if self
+            .erc20_helper_contract_address
+            .iter()
+            .any(|address| address == &Address::ZERO)
+        {
+            return Err(InvalidStateError::InvalidErc20HelperContractAddress(
+                "erc20_helper_contract_address cannot be the zero address".to_string(),
+            ));
+        }
+        if self.ledger_suite_orchestrator_id == Some(Principal::anonymous()) {
+            return Err(InvalidStateError::InvalidLedgerSuiteOrchestratorId(
+                "ledger_suite_orchestrator_id cannot be the anonymous principal".to_string(),
+            ));
+        }
         if self.minimum_withdrawal_amount == Wei::ZERO {
             return Err(InvalidStateError::InvalidMinimumWithdrawalAmount(
                 "minimum_withdrawal_amount must be positive".to_string(),
             ));
         }
+        if let EthereumNetwork::Custom(chain_id) = self.ethereum_network {
+            if chain_id == 0 || EthereumNetwork::from_chain_id(chain_id) != self.ethereum_network {
+                return Err(InvalidStateError::InvalidEthereumNetwork(format!(
+                    "invalid custom chain ID {chain_id}"
+                )));
+            }
+            if self.json_rpc_providers.is_empty() {
+                return Err(InvalidStateError::InvalidJsonRpcProviders(
+                    "json_rpc_providers cannot be empty for a custom network".to_string(),
+                ));
+            }
+        }
+        let mut urls = BTreeSet::new();
+        for provider in &self.json_rpc_providers {
+            if !provider.url.starts_with("https://") {
+                return Err(InvalidStateError::InvalidJsonRpcProviders(format!(
+                    "provider URL {} must use HTTPS",
+                    provider.url
+                )));
+            }
+            if !urls.insert(&provider.url) {
+                return Err(InvalidStateError::InvalidJsonRpcProviders(format!(
+                    "duplicate provider URL {}",
+                    provider.url
+                )));
+            }
+        }
         Ok(())
     }
 
@@ -136,13 +210,38 @@ impl State {
         !self.events_to_mint.is_empty()
     }
 
+    fn record_erc20_event_to_mint(&mut self, event: &ReceivedErc20Event) {
+        let event_source = event.source();
+        assert!(
+            !self.erc20_events_to_mint.contains_key(&event_source),
+            "there must be no two different events with the same source"
+        );
+        assert!(!self.minted_erc20_events.contains_key(&event_source));
+        assert!(!self.invalid_events.contains_key(&event_source));
+        assert!(
+            self.ckerc20_tokens
+                .contains_key(&event.erc20_contract_address),
+            "BUG: unsupported ERC-20 token {}",
+            event.erc20_contract_address
+        );
+
+        self.erc20_events_to_mint
+            .insert(event_source, event.clone());
+    }
+
+    pub fn has_erc20_events_to_mint(&self) -> bool {
+        !self.erc20_events_to_mint.is_empty()
+    }
+
     fn record_invalid_deposit(&mut self, source: EventSource, error: String) -> bool {
         assert!(
-            !self.events_to_mint.contains_key(&source),
+            !self.events_to_mint.contains_key(&source)
+                && !self.erc20_events_to_mint.contains_key(&source),
             "attempted to mark an accepted event as invalid"
         );
         assert!(
-            !self.minted_events.contains_key(&source),
+            !self.minted_events.contains_key(&source)
+                && !self.minted_erc20_events.contains_key(&source),
             "attempted to mark a minted event {source:?} as invalid"
         );
 
@@ -178,6 +277,93 @@ impl State {
         );
     }
 
+    fn record_successful_erc20_mint(
+        &mut self,
+        source: EventSource,
+        erc20_contract_address: &Address,
+        mint_block_index: LedgerMintIndex,
+    ) {
+        assert!(
+            !self.invalid_events.contains_key(&source),
+            "attempted to mint an event previously marked as invalid {source:?}"
+        );
+        let deposit_event = match self.erc20_events_to_mint.remove(&source) {
+            Some(event) => event,
+            None => panic!("attempted to mint ckERC20 for an unknown event {source:?}"),
+        };
+        assert_eq!(
+            &deposit_event.erc20_contract_address, erc20_contract_address,
+            "BUG: ERC-20 contract address mismatch for event {source:?}"
+        );
+
+        assert_eq!(
+            self.minted_erc20_events.insert(
+                source,
+                MintedErc20Event {
+                    deposit_event,
+                    mint_block_index
+                }
+            ),
+            None,
+            "attempted to mint ckERC20 twice for the same event {source:?}"
+        );
+    }
+
+    /// Checks that the given ckERC20 token can be added to the supported tokens.
+    pub fn validate_ckerc20_token(&self, token: &CkErc20Token) -> Result<(), String> {
+        if token.erc20_ethereum_network != self.ethereum_network {
+            return Err(format!(
+                "ERC-20 token is on {} but the minter is on {}",
+                token.erc20_ethereum_network, self.ethereum_network
+            ));
+        }
+        if token.ckerc20_ledger_id == self.ledger_id {
+            return Err("ckERC20 ledger cannot be the ckETH ledger".to_string());
+        }
+        for existing in self.ckerc20_tokens.values() {
+            if existing.erc20_contract_address == token.erc20_contract_address {
+                return Err(format!(
+                    "ERC-20 token {} is already supported",
+                    token.erc20_contract_address
+                ));
+            }
+            if existing.ckerc20_token_symbol == token.ckerc20_token_symbol {
+                return Err(format!(
+                    "ckERC20 token symbol {} is already used",
+                    token.ckerc20_token_symbol
+                ));
+            }
+            if existing.ckerc20_ledger_id == token.ckerc20_ledger_id {
+                return Err(format!(
+                    "ckERC20 ledger {} is already used",
+                    token.ckerc20_ledger_id
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn record_add_ckerc20_token(&mut self, token: CkErc20Token) {
+        self.validate_ckerc20_token(&token)
+            .unwrap_or_else(|e| panic!("BUG: invalid ckERC20 token {token:?}: {e}"));
+        self.ckerc20_tokens
+            .insert(token.erc20_contract_address, token);
+    }
+
+    pub fn find_ck_erc20_token_by_ledger_id(&self, ledger_id: &Principal) -> Option<CkErc20Token> {
+        self.ckerc20_tokens
+            .values()
+            .find(|token| &token.ckerc20_ledger_id == ledger_id)
+            .cloned()
+    }
+
+    pub fn supported_ckerc20_token_symbols(&self) -> Vec<String> {
+        self.ckerc20_tokens
+            .values()
+            .map(|token| token.ckerc20_token_symbol.clone())
+            .collect()
+    }
+
     pub fn record_finalized_transaction(
         &mut self,
         withdrawal_id: &LedgerBurnIndex,
@@ -236,6 +422,14 @@ impl State {
         );
     }
 
+    pub fn record_skipped_erc20_block(&mut self, block_number: BlockNumber) {
+        assert!(
+            self.skipped_erc20_blocks.insert(block_number),
+            "BUG: block {} was already skipped",
+            block_number
+        );
+    }
+
     pub const fn ethereum_network(&self) -> EthereumNetwork {
         self.ethereum_network
     }
@@ -252,6 +446,12 @@ impl State {
             minimum_withdrawal_amount,
             ethereum_contract_address,
             ethereum_block_height,
+            ethereum_network,
+            json_rpc_providers,
+            consensus_strategy,
+            erc20_helper_contract_address,
+            last_erc20_scraped_block_number,
+            ledger_suite_orchestrator_id,
         } = upgrade_args;
         if let Some(nonce) = next_transaction_nonce {
             let nonce = TransactionNonce::try_from(nonce)
@@ -270,9 +470,50 @@ impl State {
             })?;
             self.ethereum_contract_address = Some(ethereum_contract_address);
         }
+        if let Some(address) = erc20_helper_contract_address {
+            let erc20_helper_contract_address = Address::from_str(&address).map_err(|e| {
+                InvalidStateError::InvalidErc20HelperContractAddress(format!("ERROR: {}", e))
+            })?;
+            self.erc20_helper_contract_address = Some(erc20_helper_contract_address);
+        }
+        if let Some(block_number) = last_erc20_scraped_block_number {
+            self.last_erc20_scraped_block_number =
+                BlockNumber::try_from(block_number).map_err(|e| {
+                    InvalidStateError::InvalidLastScrapedBlockNumber(format!("ERROR: {}", e))
+                })?;
+        }
+        if let Some(orchestrator_id) = ledger_suite_orchestrator_id {
+            self.ledger_suite_orchestrator_id = Some(orchestrator_id);
+        }
         if let Some(block_height) = ethereum_block_height {
             self.ethereum_block_height = block_height.into();
         }
+        if let Some(network) = ethereum_network {
+            // Deposits and transactions are only meaningful on the network they happened on.
+            let has_activity = !self.events_to_mint.is_empty()
+                || !self.minted_events.is_empty()
+                || !self.erc20_events_to_mint.is_empty()
+                || !self.minted_erc20_events.is_empty()
+                || self.eth_transactions.has_pending_requests()
+                || self
+                    .eth_transactions
+                    .finalized_transactions_iter()
+                    .next()
+                    .is_some();
+            if network != self.ethereum_network && has_activity {
+                return Err(InvalidStateError::InvalidEthereumNetwork(
+                    "cannot change the network after deposits or withdrawals were processed"
+                        .to_string(),
+                ));
+            }
+            self.ethereum_network = network;
+        }
+        if let Some(providers) = json_rpc_providers {
+            self.json_rpc_providers = providers;
+        }
+        if let Some(strategy) = consensus_strategy {
+            self.consensus_strategy = strategy;
+        }
         self.validate_config()
     }
 
@@ -288,6 +529,8 @@ impl State {
         use ic_utils_ensure::ensure_eq;
 
         ensure_eq!(self.ethereum_network, other.ethereum_network);
+        ensure_eq!(self.json_rpc_providers, other.json_rpc_providers);
+        ensure_eq!(self.consensus_strategy, other.consensus_strategy);
         ensure_eq!(self.ledger_id, other.ledger_id);
         ensure_eq!(self.ecdsa_key_name, other.ecdsa_key_name);
         ensure_eq!(
@@ -310,6 +553,21 @@ impl State {
         ensure_eq!(self.events_to_mint, other.events_to_mint);
         ensure_eq!(self.minted_events, other.minted_events);
         ensure_eq!(self.invalid_events, other.invalid_events);
+        ensure_eq!(
+            self.erc20_helper_contract_address,
+            other.erc20_helper_contract_address
+        );
+        ensure_eq!(
+            self.last_erc20_scraped_block_number,
+            other.last_erc20_scraped_block_number
+        );
+        ensure_eq!(
+            self.ledger_suite_orchestrator_id,
+            other.ledger_suite_orchestrator_id
+        );
+        ensure_eq!(self.ckerc20_tokens, other.ckerc20_tokens);
+        ensure_eq!(self.erc20_events_to_mint, other.erc20_events_to_mint);
+        ensure_eq!(self.minted_erc20_events, other.minted_erc20_events);
 
         self.eth_transactions
             .is_equivalent_to(&other.eth_transactions)
@@ -464,6 +722,7 @@ impl EthBalance {
 #[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, EnumIter)]
 pub enum TaskType {
     MintCkEth,
+    MintCkErc20,
     RetrieveEth,
     ScrapEthLogs,
     Reimbursement,
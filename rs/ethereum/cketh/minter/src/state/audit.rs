@@ -1,6 +1,6 @@
 pub use super::event::{Event, EventType};
 use super::State;
-use crate::state::transactions::Reimbursed;
+use crate::state::transactions::{Erc20Reimbursed, Reimbursed};
 use crate::storage::{record_event, with_event_iter};
 
 /// Updates the state to reflect the given state transition.
@@ -82,6 +82,59 @@ pub fn apply_state_transition(state: &mut State, payload: &EventType) {
         EventType::SkippedBlock(block_number) => {
             state.record_skipped_block(*block_number);
         }
+        EventType::AddedCkErc20Token(token) => {
+            state.record_add_ckerc20_token(token.clone());
+        }
+        EventType::AcceptedErc20Deposit(erc20_event) => {
+            state.record_erc20_event_to_mint(erc20_event);
+        }
+        EventType::MintedCkErc20 {
+            event_source,
+            mint_block_index,
+            ckerc20_token_symbol: _,
+            erc20_contract_address,
+        } => {
+            state.record_successful_erc20_mint(
+                *event_source,
+                erc20_contract_address,
+                *mint_block_index,
+            );
+        }
+        EventType::SyncedErc20ToBlock { block_number } => {
+            state.last_erc20_scraped_block_number = *block_number;
+        }
+        EventType::AcceptedErc20WithdrawalRequest(request) => {
+            state
+                .eth_transactions
+                .record_erc20_withdrawal_request(request.clone());
+        }
+        EventType::ReimbursedErc20Withdrawal(Erc20Reimbursed {
+            withdrawal_id,
+            reimbursed_in_block,
+            ledger_id: _,
+            reimbursed_amount: _,
+            transaction_hash: _,
+        }) => {
+            state
+                .eth_transactions
+                .record_finalized_erc20_reimbursement(*withdrawal_id, *reimbursed_in_block);
+        }
+        EventType::SkippedErc20Block(block_number) => {
+            state.record_skipped_erc20_block(*block_number);
+        }
+        EventType::FailedErc20WithdrawalRequest(reimbursement_request) => {
+            state
+                .eth_transactions
+                .record_failed_withdrawal_request(reimbursement_request.clone());
+        }
+        EventType::CancelledWithdrawalRequest {
+            withdrawal_id,
+            reason: _,
+        } => {
+            state
+                .eth_transactions
+                .record_cancelled_withdrawal_request(*withdrawal_id);
+        }
     }
 }
 
@@ -1,16 +1,18 @@
 use crate::checked_amount::CheckedAmountOf;
 use crate::endpoints::CandidBlockTag;
-use crate::eth_logs::{EventSource, ReceivedEthEvent};
+use crate::erc20::CkErc20Token;
+use crate::eth_logs::{EventSource, ReceivedErc20Event, ReceivedEthEvent};
 use crate::eth_rpc::{BlockTag, Hash};
 use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
 use crate::lifecycle::init::InitArg;
 use crate::lifecycle::upgrade::UpgradeArg;
-use crate::lifecycle::EthereumNetwork;
+use crate::lifecycle::{ConsensusStrategy, EthereumNetwork, RpcApi};
 use crate::numeric::{
     wei_from_milli_ether, BlockNumber, GasAmount, LedgerBurnIndex, LedgerMintIndex, LogIndex,
     TransactionNonce, Wei, WeiPerGas,
 };
 use crate::state::event::{Event, EventType};
+use crate::state::transactions::{Erc20WithdrawalRequest, Subaccount};
 use crate::state::State;
 use crate::tx::{
     AccessList, AccessListItem, Eip1559Signature, Eip1559TransactionRequest,
@@ -57,6 +59,8 @@ fn a_state() -> State {
         minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
         next_transaction_nonce: Default::default(),
         last_scraped_block_number: Default::default(),
+        json_rpc_providers: None,
+        consensus_strategy: None,
     })
     .expect("init args should be valid")
 }
@@ -184,11 +188,165 @@ mod mint_transaction {
             minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
             next_transaction_nonce: Default::default(),
             last_scraped_block_number: Default::default(),
+            json_rpc_providers: None,
+            consensus_strategy: None,
         })
         .expect("init args should be valid")
     }
 }
 
+mod ckerc20 {
+    use crate::erc20::CkErc20Token;
+    use crate::eth_logs::ReceivedErc20Event;
+    use crate::lifecycle::EthereumNetwork;
+    use crate::numeric::{BlockNumber, Erc20Value, LedgerMintIndex, LogIndex};
+    use crate::state::audit::{apply_state_transition, EventType};
+    use crate::state::tests::a_state;
+    use crate::state::MintedErc20Event;
+
+    #[test]
+    fn should_add_ckerc20_token() {
+        let mut state = a_state();
+
+        apply_state_transition(&mut state, &EventType::AddedCkErc20Token(ckusdc()));
+
+        assert_eq!(
+            state.find_ck_erc20_token_by_ledger_id(&ckusdc().ckerc20_ledger_id),
+            Some(ckusdc())
+        );
+        assert_eq!(
+            state.supported_ckerc20_token_symbols(),
+            vec!["ckSepoliaUSDC".to_string()]
+        );
+    }
+
+    #[test]
+    fn should_reject_ckerc20_token_on_other_network() {
+        let state = a_state();
+
+        assert!(state
+            .validate_ckerc20_token(&CkErc20Token {
+                erc20_ethereum_network: EthereumNetwork::Mainnet,
+                ..ckusdc()
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn should_reject_duplicate_ckerc20_token() {
+        let mut state = a_state();
+        apply_state_transition(&mut state, &EventType::AddedCkErc20Token(ckusdc()));
+
+        for duplicate in [
+            ckusdc(),
+            CkErc20Token {
+                ckerc20_token_symbol: "ckSepoliaUSDT".to_string(),
+                ckerc20_ledger_id: "apia6-jaaaa-aaaar-qabma-cai".parse().unwrap(),
+                ..ckusdc()
+            },
+            CkErc20Token {
+                erc20_contract_address: "0x7169D38820dfd117C3FA1f22a697dBA58d90BA06"
+                    .parse()
+                    .unwrap(),
+                ckerc20_ledger_id: "apia6-jaaaa-aaaar-qabma-cai".parse().unwrap(),
+                ..ckusdc()
+            },
+            CkErc20Token {
+                erc20_contract_address: "0x7169D38820dfd117C3FA1f22a697dBA58d90BA06"
+                    .parse()
+                    .unwrap(),
+                ckerc20_token_symbol: "ckSepoliaUSDT".to_string(),
+                ..ckusdc()
+            },
+        ] {
+            assert!(
+                state.validate_ckerc20_token(&duplicate).is_err(),
+                "{duplicate:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn should_record_erc20_deposit_and_mint() {
+        let mut state = a_state();
+        apply_state_transition(&mut state, &EventType::AddedCkErc20Token(ckusdc()));
+        let event = received_erc20_event();
+
+        apply_state_transition(&mut state, &EventType::AcceptedErc20Deposit(event.clone()));
+        assert!(state.has_erc20_events_to_mint());
+
+        apply_state_transition(
+            &mut state,
+            &EventType::MintedCkErc20 {
+                event_source: event.source(),
+                mint_block_index: LedgerMintIndex::new(2),
+                ckerc20_token_symbol: ckusdc().ckerc20_token_symbol,
+                erc20_contract_address: ckusdc().erc20_contract_address,
+            },
+        );
+
+        assert!(!state.has_erc20_events_to_mint());
+        assert_eq!(
+            state.minted_erc20_events.get(&event.source()),
+            Some(&MintedErc20Event {
+                deposit_event: event,
+                mint_block_index: LedgerMintIndex::new(2),
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic = "unsupported ERC-20 token"]
+    fn should_not_record_erc20_deposit_of_unsupported_token() {
+        let mut state = a_state();
+
+        apply_state_transition(
+            &mut state,
+            &EventType::AcceptedErc20Deposit(received_erc20_event()),
+        );
+    }
+
+    #[test]
+    #[should_panic = "invalid"]
+    fn should_not_record_invalid_deposit_already_recorded_as_valid_erc20_deposit() {
+        let mut state = a_state();
+        apply_state_transition(&mut state, &EventType::AddedCkErc20Token(ckusdc()));
+        let event = received_erc20_event();
+        apply_state_transition(&mut state, &EventType::AcceptedErc20Deposit(event.clone()));
+
+        state.record_invalid_deposit(event.source(), "bad".to_string());
+    }
+
+    fn ckusdc() -> CkErc20Token {
+        CkErc20Token {
+            erc20_ethereum_network: EthereumNetwork::Sepolia,
+            erc20_contract_address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238"
+                .parse()
+                .unwrap(),
+            ckerc20_token_symbol: "ckSepoliaUSDC".to_string(),
+            ckerc20_ledger_id: "mxzaz-hqaaa-aaaar-qaada-cai".parse().unwrap(),
+        }
+    }
+
+    fn received_erc20_event() -> ReceivedErc20Event {
+        ReceivedErc20Event {
+            transaction_hash: "0xf353e17cbf7c1ab6e9f6e9b3c9b9a6c7aa2e1c0b7b6fa3ef0a1d3f8ab3ad0f4c"
+                .parse()
+                .unwrap(),
+            block_number: BlockNumber::new(5_539_851),
+            log_index: LogIndex::from(68_u8),
+            from_address: "0xdd2851Cdd40aE6536831558DD46db62fAc7A844d"
+                .parse()
+                .unwrap(),
+            value: Erc20Value::from(1_000_000_u64),
+            principal: "hkroy-sm7vs-yyjs7-ekppe-qqnwx-hm4zf-n7ybs-titsi-k6e3k-ucuiu-uqe"
+                .parse()
+                .unwrap(),
+            erc20_contract_address: ckusdc().erc20_contract_address,
+        }
+    }
+}
+
 fn received_eth_event() -> ReceivedEthEvent {
     ReceivedEthEvent {
         transaction_hash: "0xf1ac37d920fa57d9caeebc7136fea591191250309ffca95ae0e8a7739de89cc2"
@@ -209,7 +367,9 @@ fn received_eth_event() -> ReceivedEthEvent {
 mod upgrade {
     use crate::eth_rpc::BlockTag;
     use crate::lifecycle::upgrade::UpgradeArg;
+    use crate::lifecycle::{ConsensusStrategy, EthereumNetwork, RpcApi};
     use crate::numeric::{wei_from_milli_ether, TransactionNonce, Wei};
+    use crate::state::tests::received_eth_event;
     use crate::state::{InvalidStateError, State};
     use assert_matches::assert_matches;
     use candid::Nat;
@@ -258,6 +418,76 @@ mod upgrade {
             }),
             Err(InvalidStateError::InvalidEthereumContractAddress(_))
         );
+
+        let mut state = initial_state();
+        assert_matches!(
+            state.upgrade(UpgradeArg {
+                ethereum_network: Some(EthereumNetwork::Custom(31337)),
+                ..Default::default()
+            }),
+            Err(InvalidStateError::InvalidJsonRpcProviders(_))
+        );
+
+        let mut state = initial_state();
+        assert_matches!(
+            state.upgrade(UpgradeArg {
+                json_rpc_providers: Some(vec![RpcApi {
+                    url: "http://rpc.example.com".to_string(),
+                }]),
+                ..Default::default()
+            }),
+            Err(InvalidStateError::InvalidJsonRpcProviders(_))
+        );
+
+        let mut state = initial_state();
+        assert_matches!(
+            state.upgrade(UpgradeArg {
+                json_rpc_providers: Some(vec![
+                    RpcApi {
+                        url: "https://rpc.example.com".to_string(),
+                    },
+                    RpcApi {
+                        url: "https://rpc.example.com".to_string(),
+                    },
+                ]),
+                ..Default::default()
+            }),
+            Err(InvalidStateError::InvalidJsonRpcProviders(_))
+        );
+
+        let mut state = initial_state();
+        assert_matches!(
+            state.upgrade(UpgradeArg {
+                erc20_helper_contract_address: Some(
+                    "0x0000000000000000000000000000000000000000".to_string(),
+                ),
+                ..Default::default()
+            }),
+            Err(InvalidStateError::InvalidErc20HelperContractAddress(_))
+        );
+
+        let mut state = initial_state();
+        assert_matches!(
+            state.upgrade(UpgradeArg {
+                ledger_suite_orchestrator_id: Some(Principal::anonymous()),
+                ..Default::default()
+            }),
+            Err(InvalidStateError::InvalidLedgerSuiteOrchestratorId(_))
+        );
+    }
+
+    #[test]
+    fn should_not_change_network_after_deposit() {
+        let mut state = initial_state();
+        state.record_event_to_mint(&received_eth_event());
+
+        assert_matches!(
+            state.upgrade(UpgradeArg {
+                ethereum_network: Some(EthereumNetwork::Mainnet),
+                ..Default::default()
+            }),
+            Err(InvalidStateError::InvalidEthereumNetwork(_))
+        );
     }
 
     #[test]
@@ -271,6 +501,18 @@ mod upgrade {
                 "0xb44B5e756A894775FC32EDdf3314Bb1B1944dC34".to_string(),
             ),
             ethereum_block_height: Some(CandidBlockTag::Safe),
+            ethereum_network: Some(EthereumNetwork::Custom(31337)),
+            json_rpc_providers: Some(vec![RpcApi {
+                url: "https://rpc.example.com".to_string(),
+            }]),
+            consensus_strategy: Some(ConsensusStrategy::StrictMajority),
+            erc20_helper_contract_address: Some(
+                "0xE1788E4834c896F1932188645cc36c54d1b80AC1".to_string(),
+            ),
+            last_erc20_scraped_block_number: Some(Nat::from(5_000_000_u64)),
+            ledger_suite_orchestrator_id: Some(
+                Principal::from_text("vxkom-oyaaa-aaaar-qafda-cai").unwrap(),
+            ),
         };
 
         state.upgrade(upgrade_arg).expect("valid upgrade args");
@@ -285,6 +527,26 @@ mod upgrade {
             Some(Address::from_str("0xb44B5e756A894775FC32EDdf3314Bb1B1944dC34").unwrap())
         );
         assert_eq!(state.ethereum_block_height, BlockTag::Safe);
+        assert_eq!(state.ethereum_network, EthereumNetwork::Custom(31337));
+        assert_eq!(
+            state.json_rpc_providers,
+            vec![RpcApi {
+                url: "https://rpc.example.com".to_string(),
+            }]
+        );
+        assert_eq!(state.consensus_strategy, ConsensusStrategy::StrictMajority);
+        assert_eq!(
+            state.erc20_helper_contract_address,
+            Some(Address::from_str("0xE1788E4834c896F1932188645cc36c54d1b80AC1").unwrap())
+        );
+        assert_eq!(
+            state.last_erc20_scraped_block_number,
+            BlockNumber::new(5_000_000)
+        );
+        assert_eq!(
+            state.ledger_suite_orchestrator_id,
+            Some(Principal::from_text("vxkom-oyaaa-aaaar-qafda-cai").unwrap())
+        );
     }
 
     fn initial_state() -> State {
@@ -300,6 +562,8 @@ mod upgrade {
             minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
             next_transaction_nonce: Default::default(),
             last_scraped_block_number: Default::default(),
+            json_rpc_providers: None,
+            consensus_strategy: None,
         })
         .expect("valid init args")
     }
@@ -340,6 +604,21 @@ fn arb_block_tag() -> impl Strategy<Value = CandidBlockTag> {
     ]
 }
 
+fn arb_ethereum_network() -> impl Strategy<Value = EthereumNetwork> {
+    any::<u64>().prop_map(EthereumNetwork::from_chain_id)
+}
+
+fn arb_rpc_api() -> impl Strategy<Value = RpcApi> {
+    "https://[a-z]{1,10}\\.com".prop_map(|url| RpcApi { url })
+}
+
+fn arb_consensus_strategy() -> impl Strategy<Value = ConsensusStrategy> {
+    prop_oneof![
+        Just(ConsensusStrategy::Equality),
+        Just(ConsensusStrategy::StrictMajority),
+    ]
+}
+
 fn arb_nat() -> impl Strategy<Value = Nat> {
     any::<u128>().prop_map(Nat::from)
 }
@@ -368,16 +647,21 @@ prop_compose! {
         ledger_id in arb_principal(),
         ecdsa_key_name in "[a-z_]*",
         last_scraped_block_number in arb_nat(),
+        ethereum_network in arb_ethereum_network(),
+        json_rpc_providers in proptest::option::of(pvec(arb_rpc_api(), 0..5)),
+        consensus_strategy in proptest::option::of(arb_consensus_strategy()),
     ) -> InitArg {
         InitArg {
-            ethereum_network: EthereumNetwork::Sepolia,
+            ethereum_network,
             ecdsa_key_name,
             ethereum_contract_address: contract_address.map(|addr| addr.to_string()),
             ledger_id,
             ethereum_block_height,
             minimum_withdrawal_amount,
             next_transaction_nonce,
-            last_scraped_block_number
+            last_scraped_block_number,
+            json_rpc_providers,
+            consensus_strategy,
         }
     }
 }
@@ -388,12 +672,91 @@ prop_compose! {
         ethereum_block_height in proptest::option::of(arb_block_tag()),
         minimum_withdrawal_amount in proptest::option::of(arb_nat()),
         next_transaction_nonce in proptest::option::of(arb_nat()),
+        ethereum_network in proptest::option::of(arb_ethereum_network()),
+        json_rpc_providers in proptest::option::of(pvec(arb_rpc_api(), 0..5)),
+        consensus_strategy in proptest::option::of(arb_consensus_strategy()),
+        erc20_helper_contract_address in proptest::option::of(arb_address()),
+        last_erc20_scraped_block_number in proptest::option::of(arb_nat()),
+        ledger_suite_orchestrator_id in proptest::option::of(arb_principal()),
     ) -> UpgradeArg {
         UpgradeArg {
             ethereum_contract_address: contract_address.map(|addr| addr.to_string()),
             ethereum_block_height,
             minimum_withdrawal_amount,
             next_transaction_nonce,
+            ethereum_network,
+            json_rpc_providers,
+            consensus_strategy,
+            erc20_helper_contract_address: erc20_helper_contract_address
+                .map(|addr| addr.to_string()),
+            last_erc20_scraped_block_number,
+            ledger_suite_orchestrator_id,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_received_erc20_event()(
+        transaction_hash in arb_hash(),
+        block_number in arb_checked_amount_of(),
+        log_index in arb_checked_amount_of(),
+        from_address in arb_address(),
+        value in arb_checked_amount_of(),
+        principal in arb_principal(),
+        erc20_contract_address in arb_address(),
+    ) -> ReceivedErc20Event {
+        ReceivedErc20Event {
+            transaction_hash,
+            block_number,
+            log_index,
+            from_address,
+            value,
+            principal,
+            erc20_contract_address,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_ckerc20_token()(
+        erc20_ethereum_network in arb_ethereum_network(),
+        erc20_contract_address in arb_address(),
+        ckerc20_token_symbol in "ck[a-zA-Z0-9]{1,10}",
+        ckerc20_ledger_id in arb_principal(),
+    ) -> CkErc20Token {
+        CkErc20Token {
+            erc20_ethereum_network,
+            erc20_contract_address,
+            ckerc20_token_symbol,
+            ckerc20_ledger_id,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_erc20_withdrawal_request()(
+        max_transaction_fee in arb_checked_amount_of(),
+        withdrawal_amount in arb_checked_amount_of(),
+        destination in arb_address(),
+        cketh_ledger_burn_index in any::<u64>(),
+        erc20_contract_address in arb_address(),
+        ckerc20_ledger_id in arb_principal(),
+        ckerc20_ledger_burn_index in any::<u64>(),
+        from in arb_principal(),
+        from_subaccount in proptest::option::of(uniform32(any::<u8>())),
+        created_at in any::<u64>(),
+    ) -> Erc20WithdrawalRequest {
+        Erc20WithdrawalRequest {
+            max_transaction_fee,
+            withdrawal_amount,
+            destination,
+            cketh_ledger_burn_index: cketh_ledger_burn_index.into(),
+            erc20_contract_address,
+            ckerc20_ledger_id,
+            ckerc20_ledger_burn_index: ckerc20_ledger_burn_index.into(),
+            from,
+            from_subaccount: from_subaccount.map(Subaccount),
+            created_at,
         }
     }
 }
@@ -529,6 +892,34 @@ fn arb_event_type() -> impl Strategy<Value = EventType> {
                 transaction_receipt,
             }
         }),
+        arb_ckerc20_token().prop_map(EventType::AddedCkErc20Token),
+        arb_received_erc20_event().prop_map(EventType::AcceptedErc20Deposit),
+        (
+            arb_event_source(),
+            any::<u64>(),
+            "ck[a-zA-Z0-9]{1,10}",
+            arb_address()
+        )
+            .prop_map(
+                |(event_source, index, ckerc20_token_symbol, erc20_contract_address)| {
+                    EventType::MintedCkErc20 {
+                        event_source,
+                        mint_block_index: index.into(),
+                        ckerc20_token_symbol,
+                        erc20_contract_address,
+                    }
+                }
+            ),
+        arb_checked_amount_of()
+            .prop_map(|block_number| EventType::SyncedErc20ToBlock { block_number }),
+        arb_erc20_withdrawal_request().prop_map(EventType::AcceptedErc20WithdrawalRequest),
+        arb_checked_amount_of().prop_map(EventType::SkippedErc20Block),
+        (any::<u64>(), ".*").prop_map(|(withdrawal_id, reason)| {
+            EventType::CancelledWithdrawalRequest {
+                withdrawal_id: withdrawal_id.into(),
+                reason,
+            }
+        }),
     ]
 }
 
@@ -708,9 +1099,15 @@ fn state_equivalence() {
                 withdrawal_id: LedgerBurnIndex::new(6),
             },
         },
+        erc20_withdrawal_requests: Default::default(),
+        erc20_maybe_reimburse: Default::default(),
+        erc20_reimbursement_requests: Default::default(),
+        erc20_reimbursed: Default::default(),
     };
     let state = State {
         ethereum_network: EthereumNetwork::Mainnet,
+        json_rpc_providers: vec![],
+        consensus_strategy: ConsensusStrategy::Equality,
         ecdsa_key_name: "test_key".to_string(),
         ledger_id: "apia6-jaaaa-aaaar-qabma-cai".parse().unwrap(),
         ethereum_contract_address: Some(
@@ -759,6 +1156,13 @@ fn state_equivalence() {
         http_request_counter: 100,
         eth_balance: Default::default(),
         skipped_blocks: Default::default(),
+        erc20_helper_contract_address: None,
+        last_erc20_scraped_block_number: BlockNumber::new(1_000_000),
+        ledger_suite_orchestrator_id: None,
+        ckerc20_tokens: Default::default(),
+        erc20_events_to_mint: Default::default(),
+        minted_erc20_events: Default::default(),
+        skipped_erc20_blocks: Default::default(),
     };
 
     assert_eq!(
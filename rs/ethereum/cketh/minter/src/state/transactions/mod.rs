@@ -7,10 +7,12 @@ use crate::eth_rpc_client::responses::TransactionReceipt;
 use crate::eth_rpc_client::responses::TransactionStatus;
 use crate::lifecycle::EthereumNetwork;
 use crate::map::MultiKeyMap;
-use crate::numeric::{LedgerBurnIndex, LedgerMintIndex, TransactionCount, TransactionNonce, Wei};
+use crate::numeric::{
+    Erc20Value, LedgerBurnIndex, LedgerMintIndex, TransactionCount, TransactionNonce, Wei,
+};
 use crate::tx::{
-    Eip1559TransactionRequest, FinalizedEip1559Transaction, SignedEip1559TransactionRequest,
-    TransactionPrice,
+    erc20_transfer_data, Eip1559TransactionRequest, FinalizedEip1559Transaction,
+    SignedEip1559TransactionRequest, TransactionPrice,
 };
 use candid::Principal;
 use ic_ethereum_types::Address;
@@ -42,6 +44,44 @@ pub struct EthWithdrawalRequest {
     pub created_at: Option<u64>,
 }
 
+/// ERC-20 withdrawal request issued by the user.
+/// The user pays for the transaction fee in ckETH,
+/// so that the receiver gets exactly the requested amount of ERC-20 tokens.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+pub struct Erc20WithdrawalRequest {
+    /// The amount of ckETH burned to pay for the transaction fee.
+    #[n(0)]
+    pub max_transaction_fee: Wei,
+    /// The amount of ERC-20 tokens that the receiver will get.
+    #[n(1)]
+    pub withdrawal_amount: Erc20Value,
+    /// The address to which the minter will send the ERC-20 tokens.
+    #[n(2)]
+    pub destination: Address,
+    /// The transaction ID of the ckETH burn operation on the ckETH ledger,
+    /// which also identifies the withdrawal request.
+    #[cbor(n(3), with = "crate::cbor::id")]
+    pub cketh_ledger_burn_index: LedgerBurnIndex,
+    /// Address of the ERC-20 smart contract.
+    #[n(4)]
+    pub erc20_contract_address: Address,
+    /// The ckERC20 ledger on which the minter burned the ckERC20 tokens.
+    #[cbor(n(5), with = "crate::cbor::principal")]
+    pub ckerc20_ledger_id: Principal,
+    /// The transaction ID of the ckERC20 burn operation on the ckERC20 ledger.
+    #[cbor(n(6), with = "crate::cbor::id")]
+    pub ckerc20_ledger_burn_index: LedgerBurnIndex,
+    /// The owner of the account from which the minter burned ckETH and ckERC20 tokens.
+    #[cbor(n(7), with = "crate::cbor::principal")]
+    pub from: Principal,
+    /// The subaccount from which the minter burned ckETH and ckERC20 tokens.
+    #[n(8)]
+    pub from_subaccount: Option<Subaccount>,
+    /// The IC time at which the withdrawal request arrived.
+    #[n(9)]
+    pub created_at: u64,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
 pub struct ReimbursementRequest {
     #[cbor(n(0), with = "crate::cbor::id")]
@@ -71,6 +111,34 @@ pub struct Reimbursed {
     pub transaction_hash: Option<Hash>,
 }
 
+/// Reimbursement of ckERC20 tokens for a failed ERC-20 withdrawal transaction.
+/// The transaction fee is not reimbursed since it was consumed by the failed transaction.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Erc20ReimbursementRequest {
+    pub withdrawal_id: LedgerBurnIndex,
+    pub reimbursed_amount: Erc20Value,
+    pub ledger_id: Principal,
+    pub to: Principal,
+    pub to_subaccount: Option<Subaccount>,
+    /// Transaction hash of the failed ERC-20 transaction,
+    /// if the withdrawal request failed after a transaction was sent.
+    pub transaction_hash: Option<Hash>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct Erc20Reimbursed {
+    #[cbor(n(0), with = "crate::cbor::id")]
+    pub withdrawal_id: LedgerBurnIndex,
+    #[cbor(n(1), with = "crate::cbor::id")]
+    pub reimbursed_in_block: LedgerMintIndex,
+    #[cbor(n(2), with = "crate::cbor::principal")]
+    pub ledger_id: Principal,
+    #[n(3)]
+    pub reimbursed_amount: Erc20Value,
+    #[n(4)]
+    pub transaction_hash: Option<Hash>,
+}
+
 #[derive(Clone, Eq, PartialEq, Encode, Decode)]
 #[cbor(transparent)]
 pub struct Subaccount(#[cbor(n(0), with = "minicbor::bytes")] pub [u8; 32]);
@@ -101,6 +169,25 @@ impl fmt::Debug for EthWithdrawalRequest {
     }
 }
 
+impl fmt::Debug for Erc20WithdrawalRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Erc20WithdrawalRequest")
+            .field("max_transaction_fee", &self.max_transaction_fee)
+            .field("withdrawal_amount", &self.withdrawal_amount)
+            .field("destination", &self.destination)
+            .field("cketh_ledger_burn_index", &self.cketh_ledger_burn_index)
+            .field("erc20_contract_address", &self.erc20_contract_address)
+            .field(
+                "ckerc20_ledger_id",
+                &DebugPrincipal(&self.ckerc20_ledger_id),
+            )
+            .field("ckerc20_ledger_burn_index", &self.ckerc20_ledger_burn_index)
+            .field("from", &DebugPrincipal(&self.from))
+            .field("from_subaccount", &self.from_subaccount)
+            .finish()
+    }
+}
+
 /// State machine holding Ethereum transactions issued by the minter.
 /// Overall the transaction lifecycle is as follows:
 /// 1. The user's withdrawal request is enqueued and processed in a FIFO order.
@@ -115,6 +202,10 @@ impl fmt::Debug for EthWithdrawalRequest {
 ///    The others sent transactions for that nonce were never mined and can be discarded.
 /// 6. If a given transaction fails the minter will reimburse the user who requested the
 ///    withdrawal with the corresponding amount minus fees.
+///
+/// ERC-20 withdrawal requests follow the same lifecycle in a separate queue,
+/// sharing the transaction nonces with ETH withdrawal requests.
+/// Their transaction sends no ETH and calls `transfer` on the ERC-20 smart contract.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EthTransactions {
     pub(in crate::state) withdrawal_requests: VecDeque<EthWithdrawalRequest>,
@@ -129,6 +220,12 @@ pub struct EthTransactions {
     pub(in crate::state) maybe_reimburse: BTreeMap<LedgerBurnIndex, EthWithdrawalRequest>,
     pub(in crate::state) reimbursement_requests: BTreeMap<LedgerBurnIndex, ReimbursementRequest>,
     pub(in crate::state) reimbursed: BTreeMap<LedgerBurnIndex, Reimbursed>,
+
+    pub(in crate::state) erc20_withdrawal_requests: VecDeque<Erc20WithdrawalRequest>,
+    pub(in crate::state) erc20_maybe_reimburse: BTreeMap<LedgerBurnIndex, Erc20WithdrawalRequest>,
+    pub(in crate::state) erc20_reimbursement_requests:
+        BTreeMap<LedgerBurnIndex, Erc20ReimbursementRequest>,
+    pub(in crate::state) erc20_reimbursed: BTreeMap<LedgerBurnIndex, Erc20Reimbursed>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -138,6 +235,11 @@ pub enum CreateTransactionError {
         withdrawal_amount: Wei,
         max_transaction_fee: Wei,
     },
+    InsufficientTransactionFee {
+        ledger_burn_index: LedgerBurnIndex,
+        allowed_max_transaction_fee: Wei,
+        max_transaction_fee: Wei,
+    },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -148,6 +250,12 @@ pub enum ResubmitTransactionError {
         transaction_amount: Wei,
         max_transaction_fee: Wei,
     },
+    InsufficientTransactionFee {
+        ledger_burn_index: LedgerBurnIndex,
+        transaction_nonce: TransactionNonce,
+        allowed_max_transaction_fee: Wei,
+        max_transaction_fee: Wei,
+    },
 }
 
 impl EthTransactions {
@@ -161,6 +269,10 @@ impl EthTransactions {
             maybe_reimburse: Default::default(),
             reimbursement_requests: Default::default(),
             reimbursed: Default::default(),
+            erc20_withdrawal_requests: VecDeque::new(),
+            erc20_maybe_reimburse: Default::default(),
+            erc20_reimbursement_requests: Default::default(),
+            erc20_reimbursed: Default::default(),
         }
     }
 
@@ -180,19 +292,120 @@ impl EthTransactions {
         self.reimbursed.values().cloned().collect()
     }
 
+    pub fn get_erc20_reimbursement_requests(&self) -> Vec<Erc20ReimbursementRequest> {
+        self.erc20_reimbursement_requests
+            .values()
+            .cloned()
+            .collect()
+    }
+
     pub fn record_withdrawal_request(&mut self, request: EthWithdrawalRequest) {
-        let burn_index = request.ledger_burn_index;
+        self.assert_unknown_withdrawal_id(&request.ledger_burn_index);
+        self.withdrawal_requests.push_back(request);
+    }
+
+    pub fn record_erc20_withdrawal_request(&mut self, request: Erc20WithdrawalRequest) {
+        self.assert_unknown_withdrawal_id(&request.cketh_ledger_burn_index);
+        self.erc20_withdrawal_requests.push_back(request);
+    }
+
+    /// Records a withdrawal request that failed before any transaction could be created,
+    /// for which the user will be reimbursed.
+    pub fn record_failed_withdrawal_request(&mut self, request: ReimbursementRequest) {
+        assert_eq!(
+            request.transaction_hash, None,
+            "BUG: failed withdrawal request {request:?} must not have a transaction"
+        );
+        self.assert_unknown_withdrawal_id(&request.withdrawal_id);
+        assert_eq!(
+            self.reimbursement_requests
+                .insert(request.withdrawal_id, request),
+            None
+        );
+    }
+
+    /// Cancels a pending withdrawal request for which no transaction could be created
+    /// and records the reimbursement of the burned tokens: the withdrawn ckETH for an
+    /// ETH withdrawal request, and both the ckETH paid for the transaction fee and the
+    /// withdrawn ckERC20 tokens for an ERC-20 withdrawal request.
+    pub fn record_cancelled_withdrawal_request(&mut self, withdrawal_id: LedgerBurnIndex) {
+        if let Some(request) = self
+            .withdrawal_requests
+            .iter()
+            .find(|r| r.ledger_burn_index == withdrawal_id)
+            .cloned()
+        {
+            self.remove_withdrawal_request(&request);
+            assert_eq!(
+                self.reimbursement_requests.insert(
+                    withdrawal_id,
+                    ReimbursementRequest {
+                        withdrawal_id,
+                        reimbursed_amount: request.withdrawal_amount,
+                        to: request.from,
+                        to_subaccount: request.from_subaccount,
+                        transaction_hash: None,
+                    },
+                ),
+                None
+            );
+            return;
+        }
+        let request = self
+            .erc20_withdrawal_requests
+            .iter()
+            .find(|r| r.cketh_ledger_burn_index == withdrawal_id)
+            .cloned()
+            .unwrap_or_else(|| {
+                panic!("BUG: no pending withdrawal request with ledger burn index {withdrawal_id}")
+            });
+        self.erc20_withdrawal_requests.retain(|r| r != &request);
+        assert_eq!(
+            self.reimbursement_requests.insert(
+                withdrawal_id,
+                ReimbursementRequest {
+                    withdrawal_id,
+                    reimbursed_amount: request.max_transaction_fee,
+                    to: request.from,
+                    to_subaccount: request.from_subaccount.clone(),
+                    transaction_hash: None,
+                },
+            ),
+            None
+        );
+        assert_eq!(
+            self.erc20_reimbursement_requests.insert(
+                withdrawal_id,
+                Erc20ReimbursementRequest {
+                    withdrawal_id,
+                    reimbursed_amount: request.withdrawal_amount,
+                    ledger_id: request.ckerc20_ledger_id,
+                    to: request.from,
+                    to_subaccount: request.from_subaccount,
+                    transaction_hash: None,
+                },
+            ),
+            None
+        );
+    }
+
+    fn assert_unknown_withdrawal_id(&self, burn_index: &LedgerBurnIndex) {
         if self
             .withdrawal_requests
             .iter()
-            .any(|r| r.ledger_burn_index == burn_index)
-            || self.created_tx.contains_alt(&burn_index)
-            || self.sent_tx.contains_alt(&burn_index)
-            || self.finalized_tx.contains_alt(&burn_index)
+            .any(|r| &r.ledger_burn_index == burn_index)
+            || self
+                .erc20_withdrawal_requests
+                .iter()
+                .any(|r| &r.cketh_ledger_burn_index == burn_index)
+            || self.created_tx.contains_alt(burn_index)
+            || self.sent_tx.contains_alt(burn_index)
+            || self.finalized_tx.contains_alt(burn_index)
+            || self.reimbursement_requests.contains_key(burn_index)
+            || self.reimbursed.contains_key(burn_index)
         {
             panic!("BUG: duplicate ledger burn index {burn_index}");
         }
-        self.withdrawal_requests.push_back(request);
     }
 
     /// Move an existing withdrawal request to the back of the queue.
@@ -210,11 +423,34 @@ impl EthTransactions {
         self.record_withdrawal_request(request);
     }
 
+    /// Move an existing ERC-20 withdrawal request to the back of the queue.
+    pub fn reschedule_erc20_withdrawal_request(&mut self, request: Erc20WithdrawalRequest) {
+        assert_eq!(
+            self.erc20_withdrawal_requests
+                .iter()
+                .filter(|r| r.cketh_ledger_burn_index == request.cketh_ledger_burn_index)
+                .count(),
+            1,
+            "BUG: expected exactly one ERC-20 withdrawal request with ledger burn index {}",
+            request.cketh_ledger_burn_index
+        );
+        self.erc20_withdrawal_requests.retain(|r| r != &request);
+        self.record_erc20_withdrawal_request(request);
+    }
+
     pub fn record_created_transaction(
         &mut self,
         withdrawal_id: LedgerBurnIndex,
         transaction: Eip1559TransactionRequest,
     ) {
+        if let Some(erc20_withdrawal_request) = self
+            .erc20_withdrawal_requests
+            .iter()
+            .find(|req| req.cketh_ledger_burn_index == withdrawal_id)
+            .cloned()
+        {
+            return self.record_created_erc20_transaction(erc20_withdrawal_request, transaction);
+        }
         let withdrawal_request = self
             .withdrawal_requests
             .iter()
@@ -249,6 +485,51 @@ impl EthTransactions {
             .insert(withdrawal_id, withdrawal_request);
     }
 
+    fn record_created_erc20_transaction(
+        &mut self,
+        withdrawal_request: Erc20WithdrawalRequest,
+        transaction: Eip1559TransactionRequest,
+    ) {
+        assert_eq!(
+            withdrawal_request.erc20_contract_address, transaction.destination,
+            "BUG: ERC-20 transaction should be sent to the ERC-20 contract"
+        );
+        assert_eq!(
+            transaction.amount,
+            Wei::ZERO,
+            "BUG: ERC-20 transaction should not send any ETH"
+        );
+        assert_eq!(
+            transaction.data,
+            erc20_transfer_data(
+                &withdrawal_request.destination,
+                withdrawal_request.withdrawal_amount
+            ),
+            "BUG: ERC-20 transaction data does not match the withdrawal request"
+        );
+        assert!(
+            transaction.transaction_price().max_transaction_fee()
+                <= withdrawal_request.max_transaction_fee,
+            "BUG: ERC-20 transaction fee should be covered by the burned ckETH"
+        );
+        let nonce = self.next_nonce;
+        assert_eq!(transaction.nonce, nonce, "BUG: transaction nonce mismatch");
+        self.next_nonce = self
+            .next_nonce
+            .checked_increment()
+            .expect("Transaction nonce overflow");
+        let withdrawal_id = withdrawal_request.cketh_ledger_burn_index;
+        self.erc20_withdrawal_requests
+            .retain(|r| r != &withdrawal_request);
+        assert_eq!(
+            self.created_tx
+                .try_insert(nonce, withdrawal_id, transaction),
+            Ok(())
+        );
+        self.erc20_maybe_reimburse
+            .insert(withdrawal_id, withdrawal_request);
+    }
+
     pub fn record_signed_transaction(
         &mut self,
         signed_transaction: SignedEip1559TransactionRequest,
@@ -308,24 +589,46 @@ impl EthTransactions {
                 let new_tx_price = last_tx_price
                     .increase_by_10_percent()
                     .max(current_transaction_price.clone());
-                let new_amount = match last_tx.amount.checked_sub(
-                    new_tx_price
-                        .max_transaction_fee()
-                        .checked_sub(last_tx_max_fee)
-                        .expect("BUG: new price was increased by at least 10%"),
-                ) {
-                    Some(amount) => amount,
-                    None => {
+                let new_amount = if let Some(erc20_withdrawal_request) =
+                    self.erc20_maybe_reimburse.get(burn_index)
+                {
+                    // The transaction fee of an ERC-20 withdrawal is paid with the burned ckETH
+                    // and cannot be deducted from the withdrawn amount.
+                    if new_tx_price.max_transaction_fee()
+                        > erc20_withdrawal_request.max_transaction_fee
+                    {
                         transactions_to_resubmit.push(Err(
-                            ResubmitTransactionError::InsufficientTransactionAmount {
+                            ResubmitTransactionError::InsufficientTransactionFee {
                                 ledger_burn_index: *burn_index,
                                 transaction_nonce: *nonce,
-                                transaction_amount: last_tx.amount,
+                                allowed_max_transaction_fee: erc20_withdrawal_request
+                                    .max_transaction_fee,
                                 max_transaction_fee: new_tx_price.max_transaction_fee(),
                             },
                         ));
                         return transactions_to_resubmit;
                     }
+                    last_tx.amount
+                } else {
+                    match last_tx.amount.checked_sub(
+                        new_tx_price
+                            .max_transaction_fee()
+                            .checked_sub(last_tx_max_fee)
+                            .expect("BUG: new price was increased by at least 10%"),
+                    ) {
+                        Some(amount) => amount,
+                        None => {
+                            transactions_to_resubmit.push(Err(
+                                ResubmitTransactionError::InsufficientTransactionAmount {
+                                    ledger_burn_index: *burn_index,
+                                    transaction_nonce: *nonce,
+                                    transaction_amount: last_tx.amount,
+                                    max_transaction_fee: new_tx_price.max_transaction_fee(),
+                                },
+                            ));
+                            return transactions_to_resubmit;
+                        }
+                    }
                 };
                 let new_tx = Eip1559TransactionRequest {
                     max_priority_fee_per_gas: new_tx_price.max_priority_fee_per_gas,
@@ -408,6 +711,24 @@ impl EthTransactions {
             Ok(())
         );
 
+        if let Some(erc20_withdrawal_request) =
+            self.erc20_maybe_reimburse.remove(&ledger_burn_index)
+        {
+            if receipt.status == TransactionStatus::Failure {
+                self.erc20_reimbursement_requests.insert(
+                    ledger_burn_index,
+                    Erc20ReimbursementRequest {
+                        withdrawal_id: ledger_burn_index,
+                        reimbursed_amount: erc20_withdrawal_request.withdrawal_amount,
+                        ledger_id: erc20_withdrawal_request.ckerc20_ledger_id,
+                        to: erc20_withdrawal_request.from,
+                        to_subaccount: erc20_withdrawal_request.from_subaccount,
+                        transaction_hash: Some(receipt.transaction_hash),
+                    },
+                );
+            }
+            return;
+        }
         let maybe_reimburse = self.maybe_reimburse.remove(&ledger_burn_index).expect(
             "failed to remove entry from maybe_reimburse map with block index: {ledger_burn_index}",
         );
@@ -448,11 +769,39 @@ impl EthTransactions {
         );
     }
 
+    pub fn record_finalized_erc20_reimbursement(
+        &mut self,
+        withdrawal_id: LedgerBurnIndex,
+        reimbursed_in_block: LedgerMintIndex,
+    ) {
+        let reimbursement_request = self
+            .erc20_reimbursement_requests
+            .remove(&withdrawal_id)
+            .expect("failed to remove ERC-20 reimbursement request");
+        assert_eq!(
+            self.erc20_reimbursed.insert(
+                withdrawal_id,
+                Erc20Reimbursed {
+                    withdrawal_id,
+                    reimbursed_in_block,
+                    ledger_id: reimbursement_request.ledger_id,
+                    reimbursed_amount: reimbursement_request.reimbursed_amount,
+                    transaction_hash: reimbursement_request.transaction_hash,
+                },
+            ),
+            None
+        );
+    }
+
     pub fn transaction_status(&self, burn_index: &LedgerBurnIndex) -> RetrieveEthStatus {
         if self
             .withdrawal_requests
             .iter()
             .any(|r| &r.ledger_burn_index == burn_index)
+            || self
+                .erc20_withdrawal_requests
+                .iter()
+                .any(|r| &r.cketh_ledger_burn_index == burn_index)
         {
             return RetrieveEthStatus::Pending;
         }
@@ -473,6 +822,13 @@ impl EthTransactions {
                     reimbursed_amount: reimbursed.reimbursed_amount.into(),
                 });
             }
+            if let Some(reimbursed) = self.erc20_reimbursed.get(burn_index) {
+                return RetrieveEthStatus::TxFinalized(TxFinalizedStatus::Reimbursed {
+                    reimbursed_in_block: reimbursed.reimbursed_in_block.get().into(),
+                    transaction_hash: tx.transaction_hash().to_string(),
+                    reimbursed_amount: reimbursed.reimbursed_amount.into(),
+                });
+            }
             if tx.transaction_status() == &TransactionStatus::Failure {
                 return RetrieveEthStatus::TxFinalized(TxFinalizedStatus::PendingReimbursement(
                     EthTransaction {
@@ -493,6 +849,24 @@ impl EthTransactions {
         &self,
         requested_batch_size: usize,
     ) -> Vec<EthWithdrawalRequest> {
+        self.withdrawal_requests_iter()
+            .take(self.actual_batch_size(requested_batch_size))
+            .cloned()
+            .collect()
+    }
+
+    pub fn erc20_withdrawal_requests_batch(
+        &self,
+        requested_batch_size: usize,
+    ) -> Vec<Erc20WithdrawalRequest> {
+        self.erc20_withdrawal_requests
+            .iter()
+            .take(self.actual_batch_size(requested_batch_size))
+            .cloned()
+            .collect()
+    }
+
+    fn actual_batch_size(&self, requested_batch_size: usize) -> usize {
         // The number of pending transaction nonces is counted and not the number of pending transactions
         // because a nonce may be associated with several distinct transactions (due to re-submission and dynamic fees).
         // However, once a nonce is chosen for a withdrawal request, it's in our interest that the corresponding transaction be finalized asap.
@@ -500,15 +874,11 @@ impl EthTransactions {
         const MAX_NUM_PENDING_TRANSACTION_NONCES: usize = 1000;
         let unique_pending_transaction_nonces: BTreeSet<_> =
             self.created_tx.keys().chain(self.sent_tx.keys()).collect();
-        let actual_batch_size = min(
+        min(
             MAX_NUM_PENDING_TRANSACTION_NONCES
                 .saturating_sub(unique_pending_transaction_nonces.len()),
             requested_batch_size,
-        );
-        self.withdrawal_requests_iter()
-            .take(actual_batch_size)
-            .cloned()
-            .collect()
+        )
     }
 
     pub fn withdrawal_requests_iter(&self) -> impl Iterator<Item = &EthWithdrawalRequest> {
@@ -519,6 +889,10 @@ impl EthTransactions {
         self.withdrawal_requests.len()
     }
 
+    pub fn erc20_withdrawal_requests_len(&self) -> usize {
+        self.erc20_withdrawal_requests.len()
+    }
+
     pub fn transactions_to_sign_iter(
         &self,
     ) -> impl Iterator<
@@ -590,6 +964,7 @@ impl EthTransactions {
 
     pub fn has_pending_requests(&self) -> bool {
         !self.withdrawal_requests.is_empty()
+            || !self.erc20_withdrawal_requests.is_empty()
             || !self.created_tx.is_empty()
             || !self.sent_tx.is_empty()
     }
@@ -635,12 +1010,24 @@ impl EthTransactions {
             buf
         }
 
+        fn sorted_erc20_requests(
+            requests: &VecDeque<Erc20WithdrawalRequest>,
+        ) -> Vec<Erc20WithdrawalRequest> {
+            let mut buf: Vec<_> = requests.iter().cloned().collect();
+            buf.sort_unstable_by_key(|req| req.cketh_ledger_burn_index);
+            buf
+        }
+
         // We can reorder request in `reschedule_withdrawal_request`. The audit log won't
         // reflect this change, so we must sort the queues before comparing them.
         ensure_eq!(
             sorted_requests(&self.withdrawal_requests),
             sorted_requests(&other.withdrawal_requests)
         );
+        ensure_eq!(
+            sorted_erc20_requests(&self.erc20_withdrawal_requests),
+            sorted_erc20_requests(&other.erc20_withdrawal_requests)
+        );
         ensure_eq!(self.created_tx, other.created_tx);
         ensure_eq!(self.sent_tx, other.sent_tx);
         ensure_eq!(self.finalized_tx, other.finalized_tx);
@@ -650,15 +1037,28 @@ impl EthTransactions {
         ensure_eq!(self.reimbursement_requests, other.reimbursement_requests);
         ensure_eq!(self.reimbursed, other.reimbursed);
 
+        ensure_eq!(self.erc20_maybe_reimburse, other.erc20_maybe_reimburse);
+        ensure_eq!(
+            self.erc20_reimbursement_requests,
+            other.erc20_reimbursement_requests
+        );
+        ensure_eq!(self.erc20_reimbursed, other.erc20_reimbursed);
+
         Ok(())
     }
 
     pub fn oldest_incomplete_withdrawal_timestamp(&self) -> Option<u64> {
-        self.withdrawal_requests
+        let eth_timestamps = self
+            .withdrawal_requests
             .iter()
             .chain(self.maybe_reimburse.values())
-            .flat_map(|req| req.created_at.into_iter())
-            .min()
+            .flat_map(|req| req.created_at.into_iter());
+        let erc20_timestamps = self
+            .erc20_withdrawal_requests
+            .iter()
+            .chain(self.erc20_maybe_reimburse.values())
+            .map(|req| req.created_at);
+        eth_timestamps.chain(erc20_timestamps).min()
     }
 }
 
@@ -701,6 +1101,43 @@ pub fn create_transaction(
     })
 }
 
+/// Creates an EIP-1559 transaction calling `transfer` on the ERC-20 smart contract
+/// for the given ERC-20 withdrawal request.
+/// The transaction fees were paid upfront by burning ckETH,
+/// so that the transaction does not send any ETH.
+///
+/// # Errors
+/// * `CreateTransactionError::InsufficientTransactionFee` if the burned ckETH does not cover the transaction fee.
+pub fn create_erc20_transaction(
+    withdrawal_request: &Erc20WithdrawalRequest,
+    nonce: TransactionNonce,
+    transaction_price: TransactionPrice,
+    ethereum_network: EthereumNetwork,
+) -> Result<Eip1559TransactionRequest, CreateTransactionError> {
+    let max_transaction_fee = transaction_price.max_transaction_fee();
+    if max_transaction_fee > withdrawal_request.max_transaction_fee {
+        return Err(CreateTransactionError::InsufficientTransactionFee {
+            ledger_burn_index: withdrawal_request.cketh_ledger_burn_index,
+            allowed_max_transaction_fee: withdrawal_request.max_transaction_fee,
+            max_transaction_fee,
+        });
+    }
+    Ok(Eip1559TransactionRequest {
+        chain_id: ethereum_network.chain_id(),
+        nonce,
+        max_priority_fee_per_gas: transaction_price.max_priority_fee_per_gas,
+        max_fee_per_gas: transaction_price.max_fee_per_gas,
+        gas_limit: transaction_price.gas_limit,
+        destination: withdrawal_request.erc20_contract_address,
+        amount: Wei::ZERO,
+        data: erc20_transfer_data(
+            &withdrawal_request.destination,
+            withdrawal_request.withdrawal_amount,
+        ),
+        access_list: Default::default(),
+    })
+}
+
 /// Returns true if the two transactions are equal ignoring the transaction fee and amount.
 /// The following fields are ignored:
 /// * `max_fee_per_gas`
@@ -279,6 +279,57 @@ mod eth_transactions {
         }
     }
 
+    mod record_cancelled_withdrawal_request {
+        use crate::endpoints::RetrieveEthStatus;
+        use crate::numeric::{LedgerBurnIndex, TransactionNonce};
+        use crate::state::transactions::tests::eth_transactions::withdrawal_request_with_index;
+        use crate::state::transactions::tests::expect_panic_with_message;
+        use crate::state::transactions::{EthTransactions, ReimbursementRequest};
+
+        #[test]
+        fn should_cancel_and_reimburse_withdrawal_request() {
+            let mut transactions = EthTransactions::new(TransactionNonce::ZERO);
+            let first_request = withdrawal_request_with_index(LedgerBurnIndex::new(15));
+            let second_request = withdrawal_request_with_index(LedgerBurnIndex::new(16));
+            transactions.record_withdrawal_request(first_request.clone());
+            transactions.record_withdrawal_request(second_request.clone());
+
+            transactions.record_cancelled_withdrawal_request(first_request.ledger_burn_index);
+
+            assert_eq!(
+                transactions.withdrawal_requests_batch(5),
+                vec![second_request]
+            );
+            assert_eq!(
+                transactions.get_reimbursement_requests(),
+                vec![ReimbursementRequest {
+                    withdrawal_id: first_request.ledger_burn_index,
+                    reimbursed_amount: first_request.withdrawal_amount,
+                    to: first_request.from,
+                    to_subaccount: first_request.from_subaccount,
+                    transaction_hash: None,
+                }]
+            );
+            assert_eq!(transactions.get_erc20_reimbursement_requests(), vec![]);
+            assert_eq!(
+                transactions.transaction_status(&first_request.ledger_burn_index),
+                RetrieveEthStatus::NotFound
+            );
+        }
+
+        #[test]
+        fn should_fail_to_cancel_unknown_withdrawal_request() {
+            let mut transactions = EthTransactions::new(TransactionNonce::ZERO);
+            transactions
+                .record_withdrawal_request(withdrawal_request_with_index(LedgerBurnIndex::new(15)));
+
+            expect_panic_with_message(
+                || transactions.record_cancelled_withdrawal_request(LedgerBurnIndex::new(16)),
+                "no pending withdrawal request",
+            );
+        }
+    }
+
     mod record_created_transaction {
         use crate::lifecycle::EthereumNetwork;
         use crate::numeric::{LedgerBurnIndex, TransactionNonce};
@@ -1856,6 +1907,242 @@ pub mod arbitrary {
     }
 }
 
+mod erc20_withdrawal {
+    use crate::endpoints::{RetrieveEthStatus, TxFinalizedStatus};
+    use crate::lifecycle::EthereumNetwork;
+    use crate::numeric::{
+        Erc20Value, GasAmount, LedgerBurnIndex, LedgerMintIndex, TransactionCount,
+        TransactionNonce, Wei, WeiPerGas,
+    };
+    use crate::state::transactions::tests::{
+        sign_transaction, transaction_price, transaction_receipt, DEFAULT_CREATED_AT,
+        DEFAULT_PRINCIPAL, DEFAULT_RECIPIENT_ADDRESS,
+    };
+    use crate::state::transactions::{
+        create_erc20_transaction, CreateTransactionError, Erc20ReimbursementRequest,
+        Erc20WithdrawalRequest, EthTransactions, ReimbursementRequest, ResubmitTransactionError,
+        TransactionStatus,
+    };
+    use crate::tx::{
+        erc20_transfer_data, AccessList, Eip1559TransactionRequest, TransactionPrice,
+        CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT,
+    };
+    use std::str::FromStr;
+
+    const USDC_ADDRESS: &str = "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238";
+
+    #[test]
+    fn should_create_erc20_transaction() {
+        let request = erc20_withdrawal_request(LedgerBurnIndex::new(7));
+
+        let transaction = create_erc20_transaction(
+            &request,
+            TransactionNonce::ONE,
+            erc20_transaction_price(),
+            EthereumNetwork::Sepolia,
+        );
+
+        assert_eq!(
+            transaction,
+            Ok(Eip1559TransactionRequest {
+                chain_id: EthereumNetwork::Sepolia.chain_id(),
+                nonce: TransactionNonce::ONE,
+                max_priority_fee_per_gas: erc20_transaction_price().max_priority_fee_per_gas,
+                max_fee_per_gas: erc20_transaction_price().max_fee_per_gas,
+                gas_limit: CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT,
+                destination: request.erc20_contract_address,
+                amount: Wei::ZERO,
+                data: erc20_transfer_data(&request.destination, request.withdrawal_amount),
+                access_list: AccessList::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn should_fail_to_create_erc20_transaction_when_fee_not_covered() {
+        let request = Erc20WithdrawalRequest {
+            max_transaction_fee: Wei::new(1),
+            ..erc20_withdrawal_request(LedgerBurnIndex::new(7))
+        };
+
+        let result = create_erc20_transaction(
+            &request,
+            TransactionNonce::ONE,
+            erc20_transaction_price(),
+            EthereumNetwork::Sepolia,
+        );
+
+        assert_eq!(
+            result,
+            Err(CreateTransactionError::InsufficientTransactionFee {
+                ledger_burn_index: LedgerBurnIndex::new(7),
+                allowed_max_transaction_fee: Wei::new(1),
+                max_transaction_fee: erc20_transaction_price().max_transaction_fee(),
+            })
+        );
+    }
+
+    #[test]
+    fn should_reimburse_ckerc20_when_transaction_fails() {
+        let mut transactions = EthTransactions::new(TransactionNonce::ZERO);
+        let withdrawal_id = LedgerBurnIndex::new(7);
+        let request = erc20_withdrawal_request(withdrawal_id);
+        transactions.record_erc20_withdrawal_request(request.clone());
+        assert_eq!(
+            transactions.transaction_status(&withdrawal_id),
+            RetrieveEthStatus::Pending
+        );
+
+        let signed_tx = create_and_record_erc20_signed_transaction(&mut transactions, &request);
+        let receipt = transaction_receipt(&signed_tx, TransactionStatus::Failure);
+        transactions.record_finalized_transaction(withdrawal_id, receipt.clone());
+
+        assert_eq!(
+            transactions.get_erc20_reimbursement_requests(),
+            vec![Erc20ReimbursementRequest {
+                withdrawal_id,
+                reimbursed_amount: request.withdrawal_amount,
+                ledger_id: request.ckerc20_ledger_id,
+                to: request.from,
+                to_subaccount: None,
+                transaction_hash: Some(receipt.transaction_hash),
+            }]
+        );
+        assert_eq!(transactions.get_reimbursement_requests(), vec![]);
+
+        transactions.record_finalized_erc20_reimbursement(withdrawal_id, LedgerMintIndex::new(3));
+
+        assert_eq!(transactions.get_erc20_reimbursement_requests(), vec![]);
+        assert_eq!(
+            transactions.transaction_status(&withdrawal_id),
+            RetrieveEthStatus::TxFinalized(TxFinalizedStatus::Reimbursed {
+                transaction_hash: receipt.transaction_hash.to_string(),
+                reimbursed_amount: request.withdrawal_amount.into(),
+                reimbursed_in_block: 3_u64.into(),
+            })
+        );
+    }
+
+    #[test]
+    fn should_reimburse_cketh_and_ckerc20_when_withdrawal_request_cancelled() {
+        let mut transactions = EthTransactions::new(TransactionNonce::ZERO);
+        let withdrawal_id = LedgerBurnIndex::new(7);
+        let request = erc20_withdrawal_request(withdrawal_id);
+        transactions.record_erc20_withdrawal_request(request.clone());
+
+        transactions.record_cancelled_withdrawal_request(withdrawal_id);
+
+        assert_eq!(transactions.erc20_withdrawal_requests_batch(5), vec![]);
+        assert_eq!(
+            transactions.get_reimbursement_requests(),
+            vec![ReimbursementRequest {
+                withdrawal_id,
+                reimbursed_amount: request.max_transaction_fee,
+                to: request.from,
+                to_subaccount: None,
+                transaction_hash: None,
+            }]
+        );
+        assert_eq!(
+            transactions.get_erc20_reimbursement_requests(),
+            vec![Erc20ReimbursementRequest {
+                withdrawal_id,
+                reimbursed_amount: request.withdrawal_amount,
+                ledger_id: request.ckerc20_ledger_id,
+                to: request.from,
+                to_subaccount: None,
+                transaction_hash: None,
+            }]
+        );
+
+        transactions.record_finalized_reimbursement(withdrawal_id, LedgerMintIndex::new(3));
+        transactions.record_finalized_erc20_reimbursement(withdrawal_id, LedgerMintIndex::new(4));
+
+        assert_eq!(transactions.get_reimbursement_requests(), vec![]);
+        assert_eq!(transactions.get_erc20_reimbursement_requests(), vec![]);
+    }
+
+    #[test]
+    fn should_not_resubmit_erc20_transaction_when_fee_not_covered() {
+        let mut transactions = EthTransactions::new(TransactionNonce::ZERO);
+        let withdrawal_id = LedgerBurnIndex::new(7);
+        let request = Erc20WithdrawalRequest {
+            max_transaction_fee: erc20_transaction_price().max_transaction_fee(),
+            ..erc20_withdrawal_request(withdrawal_id)
+        };
+        transactions.record_erc20_withdrawal_request(request.clone());
+        create_and_record_erc20_signed_transaction(&mut transactions, &request);
+
+        let higher_price = TransactionPrice {
+            max_fee_per_gas: erc20_transaction_price()
+                .max_fee_per_gas
+                .checked_mul(2_u8)
+                .unwrap(),
+            ..erc20_transaction_price()
+        };
+        let resubmitted =
+            transactions.create_resubmit_transactions(TransactionCount::ZERO, higher_price);
+
+        assert_eq!(
+            resubmitted,
+            vec![Err(ResubmitTransactionError::InsufficientTransactionFee {
+                ledger_burn_index: withdrawal_id,
+                transaction_nonce: TransactionNonce::ZERO,
+                allowed_max_transaction_fee: request.max_transaction_fee,
+                max_transaction_fee: TransactionPrice {
+                    max_fee_per_gas: WeiPerGas::new(3_000_000_100),
+                    ..erc20_transaction_price()
+                }
+                .max_transaction_fee(),
+            })]
+        );
+    }
+
+    fn create_and_record_erc20_signed_transaction(
+        transactions: &mut EthTransactions,
+        request: &Erc20WithdrawalRequest,
+    ) -> crate::tx::SignedEip1559TransactionRequest {
+        let created_tx = create_erc20_transaction(
+            request,
+            transactions.next_transaction_nonce(),
+            erc20_transaction_price(),
+            EthereumNetwork::Sepolia,
+        )
+        .expect("failed to create ERC-20 transaction");
+        transactions
+            .record_created_transaction(request.cketh_ledger_burn_index, created_tx.clone());
+        let signed_tx = sign_transaction(created_tx);
+        transactions.record_signed_transaction(signed_tx.clone());
+        signed_tx
+    }
+
+    fn erc20_transaction_price() -> TransactionPrice {
+        let price = TransactionPrice {
+            gas_limit: CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT,
+            ..transaction_price()
+        };
+        assert_eq!(price.gas_limit, GasAmount::new(65_000));
+        price
+    }
+
+    fn erc20_withdrawal_request(
+        cketh_ledger_burn_index: LedgerBurnIndex,
+    ) -> Erc20WithdrawalRequest {
+        Erc20WithdrawalRequest {
+            max_transaction_fee: Wei::new(200_000_000_000_000),
+            withdrawal_amount: Erc20Value::new(1_000_000),
+            destination: DEFAULT_RECIPIENT_ADDRESS.parse().unwrap(),
+            cketh_ledger_burn_index,
+            erc20_contract_address: USDC_ADDRESS.parse().unwrap(),
+            ckerc20_ledger_id: candid::Principal::from_str("mxzaz-hqaaa-aaaar-qaada-cai").unwrap(),
+            ckerc20_ledger_burn_index: LedgerBurnIndex::new(2),
+            from: candid::Principal::from_str(DEFAULT_PRINCIPAL).unwrap(),
+            from_subaccount: None,
+            created_at: DEFAULT_CREATED_AT,
+        }
+    }
+}
+
 fn expect_panic_with_message<F: FnOnce() -> R, R: std::fmt::Debug>(f: F, expected_message: &str) {
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
     let error = result.unwrap_err();
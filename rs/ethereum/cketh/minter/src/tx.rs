@@ -3,7 +3,7 @@ mod tests;
 
 use crate::eth_rpc::{FeeHistory, Hash};
 use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
-use crate::numeric::{BlockNumber, GasAmount, TransactionNonce, Wei, WeiPerGas};
+use crate::numeric::{BlockNumber, Erc20Value, GasAmount, TransactionNonce, Wei, WeiPerGas};
 use crate::state::{lazy_call_ecdsa_public_key, read_state};
 use ethnum::u256;
 use ic_crypto_ecdsa_secp256k1::RecoveryId;
@@ -14,6 +14,15 @@ use rlp::RlpStream;
 
 const EIP1559_TX_ID: u8 = 2;
 
+/// Gas limit of a transaction withdrawing ERC-20 tokens.
+/// Since the gas consumed depends on the token contract,
+/// this is an upper bound on the cost of `transfer` for common ERC-20 tokens.
+pub const CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT: GasAmount = GasAmount::new(65_000);
+
+/// Selector of the ERC-20 function `transfer(address,uint256)`,
+/// i.e., the first 4 bytes of `keccak256("transfer(address,uint256)")`.
+const ERC20_TRANSFER_FUNCTION_SELECTOR: [u8; 4] = hex_literal::hex!("a9059cbb");
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Encode, Decode)]
 #[cbor(transparent)]
 pub struct AccessList(#[n(0)] pub Vec<AccessListItem>);
@@ -432,6 +441,19 @@ impl TransactionPrice {
         }
     }
 }
+
+/// Encodes the data of a call to the ERC-20 function `transfer(address,uint256)`
+/// that sends `value` tokens to `to`, following the Solidity contract ABI:
+/// the function selector followed by each argument left-padded to 32 bytes.
+pub fn erc20_transfer_data(to: &Address, value: Erc20Value) -> Vec<u8> {
+    let mut data = Vec::with_capacity(4 + 2 * 32);
+    data.extend(ERC20_TRANSFER_FUNCTION_SELECTOR);
+    data.extend([0_u8; 12]);
+    data.extend(to.as_ref());
+    data.extend(value.to_be_bytes());
+    data
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum TransactionPriceEstimationError {
     InvalidFeeHistory(String),
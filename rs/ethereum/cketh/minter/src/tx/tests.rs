@@ -127,6 +127,27 @@ mod transaction_price {
     }
 }
 
+mod erc20_transfer_data {
+    use crate::numeric::Erc20Value;
+    use crate::tx::erc20_transfer_data;
+    use ic_ethereum_types::Address;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_encode_transfer_call() {
+        let to = Address::from_str("0xdd2851Cdd40aE6536831558DD46db62fAc7A844d").unwrap();
+
+        let data = erc20_transfer_data(&to, Erc20Value::from(1_000_000_u32));
+
+        assert_eq!(
+            hex::encode(data),
+            "a9059cbb\
+             000000000000000000000000dd2851cdd40ae6536831558dd46db62fac7a844d\
+             00000000000000000000000000000000000000000000000000000000000f4240"
+        );
+    }
+}
+
 #[test]
 fn should_cbor_encoding_be_stable() {
     use crate::numeric::{GasAmount, TransactionNonce, Wei, WeiPerGas};
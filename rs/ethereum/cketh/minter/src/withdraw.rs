@@ -11,10 +11,13 @@ use crate::logs::{DEBUG, INFO};
 use crate::numeric::{LedgerBurnIndex, LedgerMintIndex, TransactionCount};
 use crate::state::audit::{process_event, EventType};
 use crate::state::transactions::{
-    create_transaction, CreateTransactionError, Reimbursed, ReimbursementRequest,
+    create_erc20_transaction, create_transaction, CreateTransactionError, Erc20Reimbursed,
+    Erc20ReimbursementRequest, Reimbursed, ReimbursementRequest,
 };
 use crate::state::{mutate_state, read_state, State, TaskType};
-use crate::tx::{estimate_transaction_price, TransactionPrice};
+use crate::tx::{
+    estimate_transaction_price, TransactionPrice, CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT,
+};
 use candid::Nat;
 use futures::future::join_all;
 use ic_canister_log::log;
@@ -23,11 +26,18 @@ use icrc_ledger_types::icrc1::{account::Account, transfer::TransferArg};
 use num_traits::ToPrimitive;
 use std::collections::{BTreeMap, BTreeSet};
 use std::iter::zip;
+use std::time::Duration;
 
 const WITHDRAWAL_REQUESTS_BATCH_SIZE: usize = 5;
 const TRANSACTIONS_TO_SIGN_BATCH_SIZE: usize = 5;
 const TRANSACTIONS_TO_SEND_BATCH_SIZE: usize = 5;
 
+/// How long the minter keeps trying to create a transaction for a withdrawal request
+/// failing for reasons other than an insufficient withdrawal amount (e.g., the ckETH
+/// paid for the transaction fee of an ERC-20 withdrawal no longer covers the current
+/// fee), before cancelling the request and reimbursing the user.
+const MAX_WITHDRAWAL_REQUEST_RETRY_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
 pub async fn process_reimbursement() {
     let _guard = match TimerGuard::new(TaskType::Reimbursement) {
         Ok(guard) => guard,
@@ -37,6 +47,11 @@ pub async fn process_reimbursement() {
         }
     };
 
+    process_cketh_reimbursements().await;
+    process_ckerc20_reimbursements().await;
+}
+
+async fn process_cketh_reimbursements() {
     let reimbursement_requests: Vec<ReimbursementRequest> =
         read_state(|s| s.eth_transactions.get_reimbursement_requests());
     if reimbursement_requests.is_empty() {
@@ -104,6 +119,75 @@ pub async fn process_reimbursement() {
     }
 }
 
+async fn process_ckerc20_reimbursements() {
+    let reimbursement_requests: Vec<Erc20ReimbursementRequest> =
+        read_state(|s| s.eth_transactions.get_erc20_reimbursement_requests());
+    if reimbursement_requests.is_empty() {
+        return;
+    }
+
+    let mut error_count = 0;
+
+    for reimbursement_request in reimbursement_requests {
+        let ledger_canister_id = reimbursement_request.ledger_id;
+        let client = ICRC1Client {
+            runtime: CdkRuntime,
+            ledger_canister_id,
+        };
+        let args = TransferArg {
+            from_subaccount: None,
+            to: Account {
+                owner: reimbursement_request.to,
+                subaccount: reimbursement_request
+                    .to_subaccount
+                    .as_ref()
+                    .map(|subaccount| subaccount.0),
+            },
+            fee: None,
+            created_at_time: None,
+            memo: Some(reimbursement_request.clone().into()),
+            amount: Nat::from(reimbursement_request.reimbursed_amount),
+        };
+        let block_index = match client.transfer(args).await {
+            Ok(Ok(block_index)) => block_index
+                .0
+                .to_u64()
+                .expect("block index should fit into u64"),
+            Ok(Err(err)) => {
+                log!(INFO, "[process_reimbursement] Failed to mint ckERC20 {err}");
+                error_count += 1;
+                continue;
+            }
+            Err(err) => {
+                log!(
+                    INFO,
+                    "[process_reimbursement] Failed to send a message to the ledger ({ledger_canister_id}): {err:?}"
+                );
+                error_count += 1;
+                continue;
+            }
+        };
+        mutate_state(|s| {
+            process_event(
+                s,
+                EventType::ReimbursedErc20Withdrawal(Erc20Reimbursed {
+                    withdrawal_id: reimbursement_request.withdrawal_id,
+                    reimbursed_in_block: LedgerMintIndex::new(block_index),
+                    ledger_id: reimbursement_request.ledger_id,
+                    reimbursed_amount: reimbursement_request.reimbursed_amount,
+                    transaction_hash: reimbursement_request.transaction_hash,
+                }),
+            )
+        });
+    }
+    if error_count > 0 {
+        log!(
+            INFO,
+            "[process_reimbursement] Failed to reimburse {error_count} ckERC20 withdrawals, retrying later."
+        );
+    }
+}
+
 pub async fn process_retrieve_eth_requests() {
     let _guard = match TimerGuard::new(TaskType::RetrieveEth) {
         Ok(guard) => guard,
@@ -256,8 +340,97 @@ fn create_transactions_batch(transaction_price: TransactionPrice) {
                 );
                 mutate_state(|s| s.eth_transactions.reschedule_withdrawal_request(request));
             }
+            Err(e) if retry_duration_exceeded(request.created_at) => {
+                log!(
+                    INFO,
+                    "[create_transactions_batch]: Failed to create transaction for {request:?}: {e:?}. Request cancelled and reimbursed."
+                );
+                cancel_withdrawal_request(request.ledger_burn_index, e);
+            }
+            Err(e) => {
+                log!(
+                    INFO,
+                    "[create_transactions_batch]: Failed to create transaction for {request:?}: {e:?}. Request moved back to end of queue."
+                );
+                mutate_state(|s| s.eth_transactions.reschedule_withdrawal_request(request));
+            }
         };
     }
+
+    let erc20_transaction_price = TransactionPrice {
+        gas_limit: CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT,
+        ..transaction_price
+    };
+    for request in read_state(|s| {
+        s.eth_transactions
+            .erc20_withdrawal_requests_batch(WITHDRAWAL_REQUESTS_BATCH_SIZE)
+    }) {
+        log!(DEBUG, "[create_transactions_batch]: processing {request:?}",);
+        let ethereum_network = read_state(State::ethereum_network);
+        let nonce = read_state(|s| s.eth_transactions.next_transaction_nonce());
+        match create_erc20_transaction(
+            &request,
+            nonce,
+            erc20_transaction_price.clone(),
+            ethereum_network,
+        ) {
+            Ok(transaction) => {
+                log!(
+                    DEBUG,
+                    "[create_transactions_batch]: created transaction {transaction:?}",
+                );
+
+                mutate_state(|s| {
+                    process_event(
+                        s,
+                        EventType::CreatedTransaction {
+                            withdrawal_id: request.cketh_ledger_burn_index,
+                            transaction,
+                        },
+                    );
+                });
+            }
+            Err(e) if retry_duration_exceeded(Some(request.created_at)) => {
+                log!(
+                    INFO,
+                    "[create_transactions_batch]: Failed to create transaction for {request:?}: {e:?}. Request cancelled and reimbursed."
+                );
+                cancel_withdrawal_request(request.cketh_ledger_burn_index, e);
+            }
+            Err(e) => {
+                log!(
+                    INFO,
+                    "[create_transactions_batch]: Failed to create transaction for {request:?}: {e:?}. Request moved back to end of queue."
+                );
+                mutate_state(|s| {
+                    s.eth_transactions
+                        .reschedule_erc20_withdrawal_request(request)
+                });
+            }
+        };
+    }
+}
+
+/// Returns true if the withdrawal request created at the given time has been pending
+/// for longer than [`MAX_WITHDRAWAL_REQUEST_RETRY_DURATION`].
+/// Requests without creation time, which predate the recording of that time, never expire.
+fn retry_duration_exceeded(created_at: Option<u64>) -> bool {
+    let now = ic_cdk::api::time();
+    created_at.is_some_and(|created_at| {
+        now.saturating_sub(created_at) > MAX_WITHDRAWAL_REQUEST_RETRY_DURATION.as_nanos() as u64
+    })
+}
+
+fn cancel_withdrawal_request(withdrawal_id: LedgerBurnIndex, error: CreateTransactionError) {
+    mutate_state(|s| {
+        process_event(
+            s,
+            EventType::CancelledWithdrawalRequest {
+                withdrawal_id,
+                reason: format!("{error:?}"),
+            },
+        )
+    });
 }
 
 async fn sign_transactions_batch() {
@@ -2,7 +2,7 @@
 /// Common System API benchmark functions, types, constants.
 ///
 use criterion::{BatchSize, Criterion};
-use ic_config::embedders::{Config as EmbeddersConfig, MeteringType};
+use ic_config::embedders::{Config as EmbeddersConfig, FeatureFlags, MeteringType};
 use ic_config::execution_environment::Config;
 use ic_config::flag_status::FlagStatus;
 use ic_config::subnet_config::{SchedulerConfig, SubnetConfig};
@@ -32,7 +32,7 @@ use ic_test_utilities::{
 use ic_test_utilities_execution_environment::generate_network_topology;
 use ic_test_utilities_time::mock_time;
 use ic_types::{
-    messages::{CallbackId, CanisterMessage, Payload, RejectContext, RequestMetadata},
+    messages::{CallbackId, CanisterMessage, Payload, RejectContext, RequestMetadata, NO_DEADLINE},
     methods::{Callback, WasmClosure},
     Cycles, MemoryAllocation, NumBytes, NumInstructions, Time,
 };
@@ -107,8 +107,11 @@ where
     canister_state.system_state.freeze_threshold = 0.into();
 
     // Create call context and callback
-    let call_origin =
-        CallOrigin::CanisterUpdate(canister_test_id(REMOTE_CANISTER_ID), CallbackId::new(0));
+    let call_origin = CallOrigin::CanisterUpdate(
+        canister_test_id(REMOTE_CANISTER_ID),
+        CallbackId::new(0),
+        NO_DEADLINE,
+    );
     let call_context_id = canister_state
         .system_state
         .call_context_manager_mut()
@@ -129,6 +132,7 @@ where
         WasmClosure::new(0, 1),
         WasmClosure::new(0, 1),
         None,
+        NO_DEADLINE,
     );
 
     // Create an Ingress message
@@ -257,6 +261,10 @@ where
     let config = Config {
         embedders_config: EmbeddersConfig {
             metering_type: MeteringType::New,
+            feature_flags: FeatureFlags {
+                best_effort_responses: FlagStatus::Enabled,
+                ..FeatureFlags::default()
+            },
             ..EmbeddersConfig::default()
         },
         ..Default::default()
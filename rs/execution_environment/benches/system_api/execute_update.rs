@@ -145,6 +145,11 @@ pub fn execute_update_bench(c: &mut Criterion) {
             Module::CallNewLoop.from_ic0("call_on_cleanup", Params2(33, 0), Result::No),
             2059000006,
         ),
+        common::Benchmark(
+            "call_new+ic0_call_with_best_effort_response()".into(),
+            Module::CallNewLoop.from_ic0("call_with_best_effort_response", Param1(10), Result::No),
+            2058000006,
+        ),
         common::Benchmark(
             "call_new+ic0_call_cycles_add()".into(),
             Module::CallNewLoop.from_ic0("call_cycles_add", Param1(100_i64), Result::No),
@@ -317,6 +322,30 @@ pub fn execute_update_bench(c: &mut Criterion) {
             Module::Test.from_ic0("cycles_burn128", Params3(1_i64, 2_i64, 3_i32), Result::No),
             19000006,
         ),
+        common::Benchmark(
+            "ic0_cost_call()".into(),
+            Module::Test.from_ic0("cost_call", Params3(1_i64, 2_i64, 3_i32), Result::No),
+            519000006,
+        ),
+        common::Benchmark(
+            "ic0_cost_create_canister()".into(),
+            Module::Test.from_ic0("cost_create_canister", Param1(0), Result::No),
+            517001006,
+        ),
+        common::Benchmark(
+            "ic0_cost_http_request()".into(),
+            Module::Test.from_ic0(
+                "cost_http_request",
+                Params3(1_i64, 2_i64, 3_i32),
+                Result::No,
+            ),
+            519000006,
+        ),
+        common::Benchmark(
+            "ic0_cost_sign_with_ecdsa()".into(),
+            Module::Test.from_ic0("cost_sign_with_ecdsa", Params4(0, 0, 0, 0), Result::I32),
+            517001006,
+        ),
     ];
     common::run_benchmarks(
         c,
@@ -1,6 +1,6 @@
 use crate::as_round_instructions;
 use crate::canister_settings::{validate_canister_settings, ValidatedCanisterSettings};
-use crate::execution::install_code::{validate_controller, OriginalContext};
+use crate::execution::install_code::{canister_layout, validate_controller, OriginalContext};
 use crate::execution::{install::execute_install, upgrade::execute_upgrade};
 use crate::execution_environment::{
     CompilationCostHandling, RoundContext, RoundCounters, RoundLimits,
@@ -16,9 +16,9 @@ use ic_config::flag_status::FlagStatus;
 use ic_cycles_account_manager::{CyclesAccountManager, ResourceSaturation};
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
-    CanisterChangeDetails, CanisterChangeOrigin, CanisterInstallModeV2, CanisterStatusResultV2,
-    CanisterStatusType, InstallChunkedCodeArgs, InstallCodeArgsV2, Method as Ic00Method,
-    StoredChunksReply, UploadChunkReply,
+    CanisterChangeDetails, CanisterChangeOrigin, CanisterInstallModeV2, CanisterSnapshotResponse,
+    CanisterStatusResultV2, CanisterStatusType, InstallChunkedCodeArgs, InstallCodeArgsV2,
+    Method as Ic00Method, StoredChunksReply, UploadChunkReply,
 };
 use ic_interfaces::execution_environment::{
     CanisterOutOfCyclesError, HypervisorError, IngressHistoryWriter, SubnetAvailableMemory,
@@ -28,6 +28,8 @@ use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::canister_state::system_state::ReservationError;
 use ic_replicated_state::{
+    canister_snapshots::{CanisterSnapshot, CanisterSnapshots, SnapshotId},
+    canister_state::execution_state::Memory,
     canister_state::system_state::{
         wasm_chunk_store::{self, WasmChunkStore},
         CyclesUseCase,
@@ -56,6 +58,9 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::{collections::BTreeSet, convert::TryFrom, str::FromStr, sync::Arc};
 
+/// The maximum number of snapshots a canister can hold at any time.
+pub(crate) const MAX_NUMBER_OF_SNAPSHOTS_PER_CANISTER: usize = 1;
+
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) struct InstallCodeResult {
     pub heap_delta: NumBytes,
@@ -410,6 +415,12 @@ pub(crate) struct UploadChunkResult {
     pub(crate) heap_delta_increase: NumBytes,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct TakeCanisterSnapshotResult {
+    pub(crate) reply: CanisterSnapshotResponse,
+    pub(crate) heap_delta_increase: NumBytes,
+}
+
 impl CanisterManager {
     pub(crate) fn new(
         hypervisor: Arc<Hypervisor>,
@@ -1272,6 +1283,10 @@ impl CanisterManager {
 
         // Take out the canister from `ReplicatedState`.
         let canister_to_delete = state.take_canister_state(&canister_id_to_delete).unwrap();
+        // Delete all the snapshots of the canister.
+        state
+            .canister_snapshots
+            .delete_snapshots(canister_id_to_delete);
         // Leftover cycles in the balance are considered `consumed`.
         let leftover_cycles = NominalCycles::from(canister_to_delete.system_state.balance());
         let consumed_cycles_by_canister_to_delete = leftover_cycles
@@ -1798,6 +1813,435 @@ impl CanisterManager {
             .collect();
         Ok(StoredChunksReply(keys))
     }
+
+    /// Takes a snapshot of the canister's current state and stores it in the
+    /// replicated state.
+    ///
+    /// If `replace_snapshot` is provided, the given snapshot is deleted once
+    /// the new one has been taken successfully. Otherwise, the request fails
+    /// if the canister already holds the maximum number of snapshots.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn take_canister_snapshot(
+        &self,
+        subnet_size: usize,
+        sender: PrincipalId,
+        canister_id: CanisterId,
+        replace_snapshot: Option<SnapshotId>,
+        state: &mut ReplicatedState,
+        round_limits: &mut RoundLimits,
+        resource_saturation: &ResourceSaturation,
+    ) -> Result<TakeCanisterSnapshotResult, CanisterManagerError> {
+        let time = state.time();
+
+        // Validate the snapshot to be replaced, if any.
+        let replaced_snapshot_size = match replace_snapshot {
+            Some(replace_snapshot) => {
+                let snapshot = state.canister_snapshots.get(replace_snapshot).ok_or(
+                    CanisterManagerError::CanisterSnapshotNotFound {
+                        canister_id,
+                        snapshot_id: replace_snapshot,
+                    },
+                )?;
+                if snapshot.canister_id() != canister_id {
+                    return Err(CanisterManagerError::CanisterSnapshotInvalidOwnership {
+                        canister_id,
+                        snapshot_id: replace_snapshot,
+                    });
+                }
+                snapshot.size()
+            }
+            None => {
+                if state.canister_snapshots.count_by_canister(&canister_id)
+                    >= MAX_NUMBER_OF_SNAPSHOTS_PER_CANISTER
+                {
+                    return Err(CanisterManagerError::CanisterSnapshotLimitExceeded {
+                        canister_id,
+                        limit: MAX_NUMBER_OF_SNAPSHOTS_PER_CANISTER,
+                    });
+                }
+                NumBytes::from(0)
+            }
+        };
+
+        let canister = match state.canister_states.get_mut(&canister_id) {
+            Some(canister) => canister,
+            None => return Err(CanisterManagerError::CanisterNotFound(canister_id)),
+        };
+        validate_controller(canister, &sender)?;
+        self.validate_heap_delta_rate_limit(canister)?;
+
+        let snapshot = CanisterSnapshot::from_canister(canister, time).map_err(|_| {
+            CanisterManagerError::CanisterSnapshotExecutionStateNotFound { canister_id }
+        })?;
+        let snapshot_size = snapshot.size();
+
+        // Copying the memories is charged as one instruction per byte.
+        let instructions = NumInstructions::from(snapshot_size.get());
+        self.charge_for_snapshot_operation(canister, sender, instructions, subnet_size)?;
+
+        if snapshot_size > replaced_snapshot_size {
+            let memory_increase = snapshot_size - replaced_snapshot_size;
+            let new_memory_usage = canister.memory_usage() + memory_increase;
+            self.reserve_memory_for_snapshot_operation(
+                canister,
+                new_memory_usage,
+                memory_increase,
+                round_limits,
+                subnet_size,
+                resource_saturation,
+            )?;
+        }
+
+        if self.config.rate_limiting_of_heap_delta == FlagStatus::Enabled {
+            canister.scheduler_state.heap_delta_debit += snapshot_size;
+        }
+        round_limits.instructions -= as_round_instructions(instructions);
+
+        let snapshot_id =
+            SnapshotId::new(canister_id, canister.system_state.new_local_snapshot_id());
+        if let Some(replace_snapshot) = replace_snapshot {
+            state.canister_snapshots.remove(replace_snapshot);
+        }
+        state
+            .canister_snapshots
+            .push(snapshot_id, Arc::new(snapshot));
+
+        canister.system_state.snapshots_memory_usage = state
+            .canister_snapshots
+            .memory_taken_by_canister(&canister_id);
+
+        Ok(TakeCanisterSnapshotResult {
+            reply: CanisterSnapshotResponse::new(
+                snapshot_id.to_vec(),
+                time.as_nanos_since_unix_epoch(),
+                snapshot_size.get(),
+            ),
+            heap_delta_increase: snapshot_size,
+        })
+    }
+
+    /// Replaces the canister's Wasm module, Wasm memory, stable memory,
+    /// exported globals and certified data with the ones stored in the given
+    /// snapshot.
+    ///
+    /// The canister is only modified once all validations, compilation and
+    /// charging have succeeded, so a failed load leaves it untouched.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn load_canister_snapshot(
+        &self,
+        subnet_size: usize,
+        origin: CanisterChangeOrigin,
+        canister_id: CanisterId,
+        snapshot_id: SnapshotId,
+        state: &mut ReplicatedState,
+        round_limits: &mut RoundLimits,
+        resource_saturation: &ResourceSaturation,
+    ) -> Result<NumBytes, CanisterManagerError> {
+        let sender = origin.origin();
+        let snapshot = match state.canister_snapshots.get(snapshot_id) {
+            Some(snapshot) => Arc::clone(snapshot),
+            None => {
+                return Err(CanisterManagerError::CanisterSnapshotNotFound {
+                    canister_id,
+                    snapshot_id,
+                })
+            }
+        };
+        if snapshot.canister_id() != canister_id {
+            return Err(CanisterManagerError::CanisterSnapshotInvalidOwnership {
+                canister_id,
+                snapshot_id,
+            });
+        }
+
+        let canister = match state.canister_states.get(&canister_id) {
+            Some(canister) => canister,
+            None => return Err(CanisterManagerError::CanisterNotFound(canister_id)),
+        };
+        validate_controller(canister, &sender)?;
+        self.validate_heap_delta_rate_limit(canister)?;
+
+        let execution_snapshot = snapshot.execution_snapshot();
+        let canister_layout_path =
+            canister_layout(&PathBuf::from("NOT_USED"), &canister_id).raw_path();
+        let (instructions_used, result) = self.hypervisor.create_execution_state(
+            execution_snapshot.wasm_binary.clone(),
+            canister_layout_path,
+            canister_id,
+            round_limits,
+            CompilationCostHandling::CountFullAmount,
+        );
+        let mut new_execution_state =
+            result.map_err(|err| CanisterManagerError::Hypervisor(canister_id, err))?;
+        new_execution_state.wasm_memory = Memory::from(&execution_snapshot.wasm_memory);
+        new_execution_state.stable_memory = Memory::from(&execution_snapshot.stable_memory);
+        new_execution_state.exported_globals = execution_snapshot.exported_globals.clone();
+
+        // Reserve memory and charge for the compilation on a copy of the
+        // canister, so that a failure of either step leaves the canister
+        // and the subnet memory untouched.
+        let mut new_canister = canister.clone();
+        let subnet_available_memory = round_limits.subnet_available_memory;
+        let old_execution_memory = new_canister
+            .execution_state
+            .as_ref()
+            .map_or(NumBytes::from(0), |es| es.memory_usage());
+        let new_execution_memory = new_execution_state.memory_usage();
+        if new_execution_memory > old_execution_memory {
+            let memory_increase = new_execution_memory - old_execution_memory;
+            let new_memory_usage = new_canister.memory_usage() + memory_increase;
+            self.reserve_memory_for_snapshot_operation(
+                &mut new_canister,
+                new_memory_usage,
+                memory_increase,
+                round_limits,
+                subnet_size,
+                resource_saturation,
+            )?;
+        }
+
+        // Compilation is charged like any other execution.
+        if let Err(err) = self.charge_for_snapshot_operation(
+            &mut new_canister,
+            sender,
+            instructions_used,
+            subnet_size,
+        ) {
+            round_limits.subnet_available_memory = subnet_available_memory;
+            return Err(err);
+        }
+
+        let heap_delta = new_execution_memory;
+        if self.config.rate_limiting_of_heap_delta == FlagStatus::Enabled {
+            new_canister.scheduler_state.heap_delta_debit += heap_delta;
+        }
+
+        new_canister.execution_state = Some(new_execution_state);
+        new_canister.system_state.certified_data = snapshot.certified_data().clone();
+        new_canister.system_state.canister_version += 1;
+        new_canister.system_state.add_canister_change(
+            state.time(),
+            origin,
+            CanisterChangeDetails::load_snapshot(
+                snapshot.canister_version(),
+                snapshot_id.to_vec(),
+                snapshot.taken_at_timestamp().as_nanos_since_unix_epoch(),
+            ),
+        );
+        state.put_canister_state(new_canister);
+        state
+            .canister_snapshots
+            .add_restore_operation(canister_id, snapshot_id);
+
+        Ok(heap_delta)
+    }
+
+    /// Returns the list of snapshots belonging to the canister.
+    pub(crate) fn list_canister_snapshots(
+        &self,
+        sender: PrincipalId,
+        canister: &CanisterState,
+        canister_snapshots: &CanisterSnapshots,
+    ) -> Result<Vec<CanisterSnapshotResponse>, CanisterManagerError> {
+        validate_controller(canister, &sender)?;
+
+        Ok(canister_snapshots
+            .list_snapshots(canister.canister_id())
+            .into_iter()
+            .map(|(snapshot_id, snapshot)| {
+                CanisterSnapshotResponse::new(
+                    snapshot_id.to_vec(),
+                    snapshot.taken_at_timestamp().as_nanos_since_unix_epoch(),
+                    snapshot.size().get(),
+                )
+            })
+            .collect())
+    }
+
+    /// Deletes the given snapshot of the canister and releases the memory it
+    /// was taking.
+    pub(crate) fn delete_canister_snapshot(
+        &self,
+        sender: PrincipalId,
+        canister_id: CanisterId,
+        snapshot_id: SnapshotId,
+        state: &mut ReplicatedState,
+    ) -> Result<(), CanisterManagerError> {
+        let canister = match state.canister_states.get_mut(&canister_id) {
+            Some(canister) => canister,
+            None => return Err(CanisterManagerError::CanisterNotFound(canister_id)),
+        };
+        validate_controller(canister, &sender)?;
+
+        match state.canister_snapshots.get(snapshot_id) {
+            None => {
+                return Err(CanisterManagerError::CanisterSnapshotNotFound {
+                    canister_id,
+                    snapshot_id,
+                })
+            }
+            Some(snapshot) => {
+                if snapshot.canister_id() != canister_id {
+                    return Err(CanisterManagerError::CanisterSnapshotInvalidOwnership {
+                        canister_id,
+                        snapshot_id,
+                    });
+                }
+            }
+        }
+
+        state.canister_snapshots.remove(snapshot_id);
+        canister.system_state.snapshots_memory_usage = state
+            .canister_snapshots
+            .memory_taken_by_canister(&canister_id);
+        Ok(())
+    }
+
+    fn validate_heap_delta_rate_limit(
+        &self,
+        canister: &CanisterState,
+    ) -> Result<(), CanisterManagerError> {
+        if self.config.rate_limiting_of_heap_delta == FlagStatus::Enabled
+            && canister.scheduler_state.heap_delta_debit >= self.config.heap_delta_rate_limit
+        {
+            return Err(CanisterManagerError::CanisterHeapDeltaRateLimited {
+                canister_id: canister.canister_id(),
+                value: canister.scheduler_state.heap_delta_debit,
+                limit: self.config.heap_delta_rate_limit,
+            });
+        }
+        Ok(())
+    }
+
+    /// Charges the canister for `instructions` worth of execution.
+    fn charge_for_snapshot_operation(
+        &self,
+        canister: &mut CanisterState,
+        sender: PrincipalId,
+        instructions: NumInstructions,
+        subnet_size: usize,
+    ) -> Result<(), CanisterManagerError> {
+        let memory_usage = canister.memory_usage();
+        let message_memory = canister.message_memory_usage();
+        let compute_allocation = canister.compute_allocation();
+        let reveal_top_up = canister.controllers().contains(&sender);
+        let prepaid_cycles = self
+            .cycles_account_manager
+            .prepay_execution_cycles(
+                &mut canister.system_state,
+                memory_usage,
+                message_memory,
+                compute_allocation,
+                instructions,
+                subnet_size,
+                reveal_top_up,
+            )
+            .map_err(CanisterManagerError::CanisterSnapshotNotEnoughCycles)?;
+        // To keep the invariant that `prepay_execution_cycles` is always paired
+        // with `refund_unused_execution_cycles` we refund zero immediately.
+        self.cycles_account_manager.refund_unused_execution_cycles(
+            &mut canister.system_state,
+            NumInstructions::from(0),
+            instructions,
+            prepaid_cycles,
+            // This counter is incremented if we refund more
+            // instructions than initially charged, which is impossible
+            // here.
+            &IntCounter::new("no_op", "no_op").unwrap(),
+            subnet_size,
+            &self.log,
+        );
+        Ok(())
+    }
+
+    /// Checks that the canister and the subnet can accommodate the memory
+    /// increase of a snapshot operation and reserves cycles and subnet memory
+    /// for it, following the same rules as `upload_chunk`.
+    fn reserve_memory_for_snapshot_operation(
+        &self,
+        canister: &mut CanisterState,
+        new_memory_usage: NumBytes,
+        memory_increase: NumBytes,
+        round_limits: &mut RoundLimits,
+        subnet_size: usize,
+        resource_saturation: &ResourceSaturation,
+    ) -> Result<(), CanisterManagerError> {
+        match canister.memory_allocation() {
+            MemoryAllocation::Reserved(bytes) => {
+                if bytes < new_memory_usage {
+                    return Err(CanisterManagerError::NotEnoughMemoryAllocationGiven {
+                        memory_allocation_given: canister.memory_allocation(),
+                        memory_usage_needed: new_memory_usage,
+                    });
+                }
+            }
+            MemoryAllocation::BestEffort => {
+                let reservation_cycles = self.cycles_account_manager.storage_reservation_cycles(
+                    memory_increase,
+                    resource_saturation,
+                    subnet_size,
+                );
+                let threshold = self.cycles_account_manager.freeze_threshold_cycles(
+                    canister.system_state.freeze_threshold,
+                    canister.memory_allocation(),
+                    new_memory_usage,
+                    canister.message_memory_usage(),
+                    canister.compute_allocation(),
+                    subnet_size,
+                    canister.system_state.reserved_balance() + reservation_cycles,
+                );
+                // Note: if the subtraction here saturates, then we will get an
+                // error later when trying to actually reserve the cycles.
+                if threshold > canister.system_state.balance() - reservation_cycles {
+                    return Err(CanisterManagerError::InsufficientCyclesInMemoryGrow {
+                        bytes: memory_increase,
+                        available: canister.system_state.balance(),
+                        threshold,
+                    });
+                }
+                round_limits
+                    .subnet_available_memory
+                    .check_available_memory(memory_increase, NumBytes::from(0), NumBytes::from(0))
+                    .map_err(
+                        |_| CanisterManagerError::SubnetMemoryCapacityOverSubscribed {
+                            requested: memory_increase,
+                            available: NumBytes::from(
+                                round_limits
+                                    .subnet_available_memory
+                                    .get_execution_memory()
+                                    .max(0) as u64,
+                            ),
+                        },
+                    )?;
+                canister
+                    .system_state
+                    .reserve_cycles(reservation_cycles)
+                    .map_err(|err| match err {
+                        ReservationError::InsufficientCycles {
+                            requested,
+                            available,
+                        } => CanisterManagerError::InsufficientCyclesInMemoryGrow {
+                            bytes: memory_increase,
+                            available,
+                            threshold: requested,
+                        },
+                        ReservationError::ReservedLimitExceed { requested, limit } => {
+                            CanisterManagerError::ReservedCyclesLimitExceededInMemoryGrow {
+                                bytes: memory_increase,
+                                requested,
+                                limit,
+                            }
+                        }
+                    })?;
+                // It's safe to unwrap here because we already checked the
+                // available memory above.
+                round_limits.subnet_available_memory
+                    .try_decrement(memory_increase, NumBytes::from(0), NumBytes::from(0))
+                    .expect("Error: Cannot fail to decrement SubnetAvailableMemory after checking for availability");
+            }
+        };
+        Ok(())
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -1879,6 +2323,27 @@ pub(crate) enum CanisterManagerError {
     WasmChunkStoreError {
         message: String,
     },
+    CanisterSnapshotNotFound {
+        canister_id: CanisterId,
+        snapshot_id: SnapshotId,
+    },
+    CanisterSnapshotInvalidOwnership {
+        canister_id: CanisterId,
+        snapshot_id: SnapshotId,
+    },
+    CanisterSnapshotLimitExceeded {
+        canister_id: CanisterId,
+        limit: usize,
+    },
+    CanisterSnapshotExecutionStateNotFound {
+        canister_id: CanisterId,
+    },
+    CanisterSnapshotNotEnoughCycles(CanisterOutOfCyclesError),
+    CanisterHeapDeltaRateLimited {
+        canister_id: CanisterId,
+        value: NumBytes,
+        limit: NumBytes,
+    },
 }
 
 impl From<CanisterManagerError> for UserError {
@@ -2115,6 +2580,58 @@ impl From<CanisterManagerError> for UserError {
                     )
                 )
             }
+            CanisterSnapshotNotFound { canister_id, snapshot_id } => {
+                Self::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "Could not find the snapshot ID {} for canister {}.",
+                        snapshot_id, canister_id,
+                    )
+                )
+            }
+            CanisterSnapshotInvalidOwnership { canister_id, snapshot_id } => {
+                Self::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "The snapshot {} does not belong to canister {}.",
+                        snapshot_id, canister_id,
+                    )
+                )
+            }
+            CanisterSnapshotLimitExceeded { canister_id, limit } => {
+                Self::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "Canister {} has reached the maximum number of snapshots allowed: {}. \
+                         Specify a snapshot to replace or delete an existing one.",
+                        canister_id, limit,
+                    )
+                )
+            }
+            CanisterSnapshotExecutionStateNotFound { canister_id } => {
+                Self::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "Failed to take snapshot of canister {}: the canister is empty.",
+                        canister_id,
+                    )
+                )
+            }
+            CanisterSnapshotNotEnoughCycles(err) => {
+                Self::new(
+                    ErrorCode::CanisterOutOfCycles,
+                    format!("Canister snapshot operation failed with `{}`", err),
+                )
+            }
+            CanisterHeapDeltaRateLimited { canister_id, value, limit } => {
+                Self::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    format!(
+                        "Canister {} is heap delta rate limited. Current delta debit: {}, limit: {}",
+                        canister_id, value, limit,
+                    )
+                )
+            }
         }
     }
 }
@@ -2200,7 +2717,7 @@ pub fn uninstall_canister(
                         },
                     }));
                 }
-                CallOrigin::CanisterUpdate(caller_canister_id, callback_id) => {
+                CallOrigin::CanisterUpdate(caller_canister_id, callback_id, deadline) => {
                     rejects.push(Response::Canister(CanisterResponse {
                         originator: *caller_canister_id,
                         respondent: canister_id,
@@ -2210,6 +2727,7 @@ pub fn uninstall_canister(
                             RejectCode::CanisterReject,
                             "Canister has been uninstalled.",
                         )),
+                        deadline: *deadline,
                     }));
                 }
                 CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => fatal!(
@@ -609,7 +609,7 @@ impl InstallCodeHelper {
     pub fn handle_wasm_execution(
         &mut self,
         canister_state_changes: Option<CanisterStateChanges>,
-        output: WasmExecutionOutput,
+        mut output: WasmExecutionOutput,
         original: &OriginalContext,
         round: &RoundContext,
     ) -> (NumInstructions, Result<(), CanisterManagerError>) {
@@ -618,6 +618,11 @@ impl InstallCodeHelper {
             output: output.clone(),
         });
 
+        self.canister
+            .system_state
+            .canister_log
+            .append_delta_log(&mut output.canister_log);
+
         let instructions_consumed = NumInstructions::from(
             self.execution_parameters
                 .instruction_limits
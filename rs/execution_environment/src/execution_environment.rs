@@ -1,7 +1,8 @@
 use crate::{
     canister_manager::{
         CanisterManager, CanisterManagerError, CanisterMgrConfig, DtsInstallCodeResult,
-        InstallCodeContext, PausedInstallCodeExecution, StopCanisterResult, UploadChunkResult,
+        InstallCodeContext, PausedInstallCodeExecution, StopCanisterResult,
+        TakeCanisterSnapshotResult, UploadChunkResult,
     },
     canister_settings::CanisterSettings,
     execution::{
@@ -32,12 +33,12 @@ use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
     CanisterChangeOrigin, CanisterHttpRequestArgs, CanisterIdRecord, CanisterInfoRequest,
     CanisterInfoResponse, CanisterSettingsArgs, CanisterStatusType, ClearChunkStoreArgs,
-    ComputeInitialEcdsaDealingsArgs, CreateCanisterArgs, ECDSAPublicKeyArgs,
-    ECDSAPublicKeyResponse, EcdsaKeyId, EmptyBlob, InstallChunkedCodeArgs, InstallCodeArgsV2,
-    Method as Ic00Method, NodeMetricsHistoryArgs, Payload as Ic00Payload,
-    ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs, SetupInitialDKGArgs,
-    SignWithECDSAArgs, StoredChunksArgs, UninstallCodeArgs, UpdateSettingsArgs, UploadChunkArgs,
-    IC_00,
+    ComputeInitialEcdsaDealingsArgs, CreateCanisterArgs, DeleteCanisterSnapshotArgs,
+    ECDSAPublicKeyArgs, ECDSAPublicKeyResponse, EcdsaKeyId, EmptyBlob, InstallChunkedCodeArgs,
+    InstallCodeArgsV2, ListCanisterSnapshotArgs, LoadCanisterSnapshotArgs, Method as Ic00Method,
+    NodeMetricsHistoryArgs, Payload as Ic00Payload, ProvisionalCreateCanisterWithCyclesArgs,
+    ProvisionalTopUpCanisterArgs, SetupInitialDKGArgs, SignWithECDSAArgs, StoredChunksArgs,
+    TakeCanisterSnapshotArgs, UninstallCodeArgs, UpdateSettingsArgs, UploadChunkArgs, IC_00,
 };
 use ic_interfaces::execution_environment::{
     ExecutionMode, IngressHistoryWriter, RegistryExecutionSettings, SubnetAvailableMemory,
@@ -47,6 +48,7 @@ use ic_metrics::MetricsRegistry;
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::{
+    canister_snapshots::SnapshotId,
     canister_state::system_state::PausedExecutionId,
     canister_state::{system_state::CyclesUseCase, NextExecution},
     metadata_state::subnet_call_context_manager::{
@@ -66,7 +68,7 @@ use ic_types::{
         extract_effective_canister_id, AnonymousQuery, CanisterCall, CanisterCallOrTask,
         CanisterMessage, CanisterMessageOrTask, CanisterTask, Payload, RejectContext, Request,
         Response, SignedIngressContent, StopCanisterCallId, StopCanisterContext,
-        MAX_INTER_CANISTER_PAYLOAD_IN_BYTES,
+        MAX_INTER_CANISTER_PAYLOAD_IN_BYTES, NO_DEADLINE,
     },
     methods::SystemMethod,
     nominal_cycles::NominalCycles,
@@ -467,6 +469,7 @@ impl ExecutionEnvironment {
                                 originator_reply_callback: request.sender_reply_callback,
                                 refund: request.payment,
                                 response_payload: response.response_payload.clone(),
+                                deadline: request.deadline,
                             }
                             .into(),
                         );
@@ -542,6 +545,7 @@ impl ExecutionEnvironment {
                                         "An empty message cannot be signed",
                                     ),
                                 ),
+                                deadline: request.deadline,
                             }
                             .into(),
                         );
@@ -1118,14 +1122,19 @@ impl ExecutionEnvironment {
 
             Ok(Ic00Method::TakeCanisterSnapshot) => match self.config.canister_snapshots {
                 FlagStatus::Enabled => {
-                    // TODO(EXC-1529): Implement take_canister_snapshot.
-                    Some((
-                        Err(UserError::new(
-                            ErrorCode::CanisterRejectedMessage,
-                            "Canister snapshotting API is not yet implemented.",
-                        )),
-                        msg.take_cycles(),
-                    ))
+                    let resource_saturation =
+                        self.subnet_memory_saturation(&round_limits.subnet_available_memory);
+                    let res = TakeCanisterSnapshotArgs::decode(payload).and_then(|args| {
+                        self.take_canister_snapshot(
+                            *msg.sender(),
+                            &mut state,
+                            args,
+                            round_limits,
+                            registry_settings.subnet_size,
+                            &resource_saturation,
+                        )
+                    });
+                    Some((res, msg.take_cycles()))
                 }
                 FlagStatus::Disabled => {
                     let err = Err(UserError::new(
@@ -1138,14 +1147,20 @@ impl ExecutionEnvironment {
 
             Ok(Ic00Method::LoadCanisterSnapshot) => match self.config.canister_snapshots {
                 FlagStatus::Enabled => {
-                    // TODO(EXC-1530): Implement load_canister_snapshot.
-                    Some((
-                        Err(UserError::new(
-                            ErrorCode::CanisterRejectedMessage,
-                            "Canister snapshotting API is not yet implemented.",
-                        )),
-                        msg.take_cycles(),
-                    ))
+                    let resource_saturation =
+                        self.subnet_memory_saturation(&round_limits.subnet_available_memory);
+                    let res = LoadCanisterSnapshotArgs::decode(payload).and_then(|args| {
+                        let origin = msg.canister_change_origin(args.get_sender_canister_version());
+                        self.load_canister_snapshot(
+                            origin,
+                            &mut state,
+                            args,
+                            round_limits,
+                            registry_settings.subnet_size,
+                            &resource_saturation,
+                        )
+                    });
+                    Some((res, msg.take_cycles()))
                 }
                 FlagStatus::Disabled => {
                     let err = Err(UserError::new(
@@ -1158,14 +1173,9 @@ impl ExecutionEnvironment {
 
             Ok(Ic00Method::ListCanisterSnapshots) => match self.config.canister_snapshots {
                 FlagStatus::Enabled => {
-                    // TODO(EXC-1531): Implement list_canister_snapshot.
-                    Some((
-                        Err(UserError::new(
-                            ErrorCode::CanisterRejectedMessage,
-                            "Canister snapshotting API is not yet implemented.",
-                        )),
-                        msg.take_cycles(),
-                    ))
+                    let res = ListCanisterSnapshotArgs::decode(payload)
+                        .and_then(|args| self.list_canister_snapshots(*msg.sender(), &state, args));
+                    Some((res, msg.take_cycles()))
                 }
                 FlagStatus::Disabled => {
                     let err = Err(UserError::new(
@@ -1178,14 +1188,10 @@ impl ExecutionEnvironment {
 
             Ok(Ic00Method::DeleteCanisterSnapshot) => match self.config.canister_snapshots {
                 FlagStatus::Enabled => {
-                    // TODO(EXC-1532): Implement delete_canister_snapshot.
-                    Some((
-                        Err(UserError::new(
-                            ErrorCode::CanisterRejectedMessage,
-                            "Canister snapshotting API is not yet implemented.",
-                        )),
-                        msg.take_cycles(),
-                    ))
+                    let res = DeleteCanisterSnapshotArgs::decode(payload).and_then(|args| {
+                        self.delete_canister_snapshot(*msg.sender(), &mut state, args)
+                    });
+                    Some((res, msg.take_cycles()))
                 }
                 FlagStatus::Disabled => {
                     let err = Err(UserError::new(
@@ -1701,6 +1707,97 @@ impl ExecutionEnvironment {
             .map_err(|err| err.into())
     }
 
+    fn take_canister_snapshot(
+        &self,
+        sender: PrincipalId,
+        state: &mut ReplicatedState,
+        args: TakeCanisterSnapshotArgs,
+        round_limits: &mut RoundLimits,
+        subnet_size: usize,
+        resource_saturation: &ResourceSaturation,
+    ) -> Result<Vec<u8>, UserError> {
+        let canister_id = args.get_canister_id();
+        let replace_snapshot = match args.replace_snapshot() {
+            Some(snapshot_id) => Some(decode_snapshot_id(snapshot_id)?),
+            None => None,
+        };
+        self.canister_manager
+            .take_canister_snapshot(
+                subnet_size,
+                sender,
+                canister_id,
+                replace_snapshot,
+                state,
+                round_limits,
+                resource_saturation,
+            )
+            .map(
+                |TakeCanisterSnapshotResult {
+                     reply,
+                     heap_delta_increase,
+                 }| {
+                    state.metadata.heap_delta_estimate += heap_delta_increase;
+                    reply.encode()
+                },
+            )
+            .map_err(|err| err.into())
+    }
+
+    fn load_canister_snapshot(
+        &self,
+        origin: CanisterChangeOrigin,
+        state: &mut ReplicatedState,
+        args: LoadCanisterSnapshotArgs,
+        round_limits: &mut RoundLimits,
+        subnet_size: usize,
+        resource_saturation: &ResourceSaturation,
+    ) -> Result<Vec<u8>, UserError> {
+        let canister_id = args.get_canister_id();
+        let snapshot_id = decode_snapshot_id(args.snapshot_id())?;
+        self.canister_manager
+            .load_canister_snapshot(
+                subnet_size,
+                origin,
+                canister_id,
+                snapshot_id,
+                state,
+                round_limits,
+                resource_saturation,
+            )
+            .map(|heap_delta_increase| {
+                state.metadata.heap_delta_estimate += heap_delta_increase;
+                EmptyBlob.encode()
+            })
+            .map_err(|err| err.into())
+    }
+
+    fn list_canister_snapshots(
+        &self,
+        sender: PrincipalId,
+        state: &ReplicatedState,
+        args: ListCanisterSnapshotArgs,
+    ) -> Result<Vec<u8>, UserError> {
+        let canister = get_canister(args.get_canister_id(), state)?;
+        self.canister_manager
+            .list_canister_snapshots(sender, canister, &state.canister_snapshots)
+            .map(|responses| Encode!(&responses).unwrap())
+            .map_err(|err| err.into())
+    }
+
+    fn delete_canister_snapshot(
+        &self,
+        sender: PrincipalId,
+        state: &mut ReplicatedState,
+        args: DeleteCanisterSnapshotArgs,
+    ) -> Result<Vec<u8>, UserError> {
+        let canister_id = args.get_canister_id();
+        let snapshot_id = decode_snapshot_id(args.snapshot_id())?;
+        self.canister_manager
+            .delete_canister_snapshot(sender, canister_id, snapshot_id, state)
+            .map(|()| EmptyBlob.encode())
+            .map_err(|err| err.into())
+    }
+
     fn clear_chunk_store(
         &self,
         sender: PrincipalId,
@@ -2030,6 +2127,7 @@ impl ExecutionEnvironment {
                     originator_reply_callback: req.sender_reply_callback,
                     refund,
                     response_payload: payload,
+                    deadline: req.deadline,
                 };
 
                 state.push_subnet_output_response(response.into());
@@ -2121,6 +2219,7 @@ impl ExecutionEnvironment {
                             RejectCode::CanisterError,
                             format!("Canister {}'s stop request cancelled", canister_id),
                         )),
+                        deadline: NO_DEADLINE,
                     };
                     state.push_subnet_output_response(response.into());
                 }
@@ -2883,6 +2982,7 @@ impl ExecutionEnvironment {
                     originator_reply_callback: *reply_callback,
                     refund: *cycles,
                     response_payload,
+                    deadline: NO_DEADLINE,
                 };
                 state.push_subnet_output_response(response.into());
             }
@@ -3133,6 +3233,15 @@ fn get_canister(
     }
 }
 
+fn decode_snapshot_id(bytes: &[u8]) -> Result<SnapshotId, UserError> {
+    SnapshotId::try_from(bytes).map_err(|err| {
+        UserError::new(
+            ErrorCode::InvalidManagementPayload,
+            format!("Invalid snapshot ID: {}", err),
+        )
+    })
+}
+
 fn get_canister_mut(
     canister_id: CanisterId,
     state: &mut ReplicatedState,
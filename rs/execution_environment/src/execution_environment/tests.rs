@@ -10,10 +10,12 @@ use ic_base_types::{NumBytes, NumSeconds};
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
     self as ic00, BitcoinGetUtxosArgs, BitcoinNetwork, BoundedHttpHeaders, CanisterChange,
-    CanisterHttpRequestArgs, CanisterIdRecord, CanisterStatusResultV2, CanisterStatusType,
-    DerivationPath, EcdsaCurve, EcdsaKeyId, EmptyBlob, FetchCanisterLogsRequest, HttpMethod,
-    LogVisibility, Method, Payload as Ic00Payload, ProvisionalCreateCanisterWithCyclesArgs,
-    ProvisionalTopUpCanisterArgs, TransformContext, TransformFunc, IC_00,
+    CanisterChangeDetails, CanisterHttpRequestArgs, CanisterIdRecord, CanisterSnapshotResponse,
+    CanisterStatusResultV2, CanisterStatusType, DeleteCanisterSnapshotArgs, DerivationPath,
+    EcdsaCurve, EcdsaKeyId, EmptyBlob, FetchCanisterLogsRequest, HttpMethod,
+    ListCanisterSnapshotArgs, LoadCanisterSnapshotArgs, LogVisibility, Method,
+    Payload as Ic00Payload, ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs,
+    TakeCanisterSnapshotArgs, TransformContext, TransformFunc, IC_00,
 };
 use ic_registry_routing_table::canister_id_into_u64;
 use ic_registry_routing_table::CanisterIdRange;
@@ -36,6 +38,7 @@ use ic_types::{
     ingress::{IngressState, IngressStatus, WasmResult},
     messages::{
         CallbackId, Payload, RejectContext, RequestOrResponse, Response, MAX_RESPONSE_COUNT_BYTES,
+        NO_DEADLINE,
     },
     CanisterId, Cycles, PrincipalId, RegistryVersion,
 };
@@ -1471,7 +1474,8 @@ fn setup_initial_dkg_sender_not_on_nns() {
                     ic00::Method::SetupInitialDKG,
                     other_canister,
                 )
-            ))
+            )),
+            deadline: NO_DEADLINE,
         }
         .into()
     );
@@ -3118,8 +3122,270 @@ fn output_requests_on_application_subnets_update_subnet_available_memory_reserve
     assert_correct_request(system_state, canister_id);
 }
 
+fn take_canister_snapshot(
+    test: &mut ExecutionTest,
+    canister_id: CanisterId,
+    replace_snapshot: Option<Vec<u8>>,
+) -> Result<CanisterSnapshotResponse, UserError> {
+    let args = TakeCanisterSnapshotArgs::new(canister_id, replace_snapshot);
+    test.subnet_message(Method::TakeCanisterSnapshot, args.encode())
+        .map(|result| CanisterSnapshotResponse::decode(&get_reply(result)).unwrap())
+}
+
+fn list_canister_snapshots(
+    test: &mut ExecutionTest,
+    canister_id: CanisterId,
+) -> Vec<CanisterSnapshotResponse> {
+    let args = ListCanisterSnapshotArgs::new(canister_id);
+    let result = test.subnet_message(Method::ListCanisterSnapshots, args.encode());
+    Decode!(&get_reply(result), Vec<CanisterSnapshotResponse>).unwrap()
+}
+
+#[test]
+fn take_canister_snapshot_succeeds_and_is_listed() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let memory_usage_before = test.canister_state(canister_id).memory_usage();
+
+    let snapshot = take_canister_snapshot(&mut test, canister_id, None).unwrap();
+
+    assert_eq!(
+        snapshot.taken_at_timestamp,
+        test.time().as_nanos_since_unix_epoch()
+    );
+    assert_eq!(
+        list_canister_snapshots(&mut test, canister_id),
+        vec![snapshot.clone()]
+    );
+    let canister = test.canister_state(canister_id);
+    assert_eq!(
+        canister.system_state.snapshots_memory_usage,
+        NumBytes::from(snapshot.total_size())
+    );
+    assert_eq!(
+        canister.memory_usage(),
+        memory_usage_before + NumBytes::from(snapshot.total_size())
+    );
+}
+
+#[test]
+fn take_canister_snapshot_fails_when_limit_is_reached() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+
+    let first = take_canister_snapshot(&mut test, canister_id, None).unwrap();
+    let err = take_canister_snapshot(&mut test, canister_id, None).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterRejectedMessage);
+    assert!(err
+        .description()
+        .contains("has reached the maximum number of snapshots"));
+
+    // Replacing the existing snapshot is allowed.
+    let second =
+        take_canister_snapshot(&mut test, canister_id, Some(first.snapshot_id().to_vec())).unwrap();
+    assert_ne!(first.snapshot_id(), second.snapshot_id());
+    assert_eq!(
+        list_canister_snapshots(&mut test, canister_id),
+        vec![second]
+    );
+}
+
+#[test]
+fn take_canister_snapshot_fails_for_empty_canister() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+
+    let err = take_canister_snapshot(&mut test, canister_id, None).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterRejectedMessage);
+    assert!(test.state().canister_snapshots.is_empty());
+}
+
+#[test]
+fn take_canister_snapshot_fails_for_non_controller() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+
+    test.set_user_id(user_test_id(42));
+    let err = take_canister_snapshot(&mut test, canister_id, None).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterInvalidController);
+    assert!(test.state().canister_snapshots.is_empty());
+}
+
+#[test]
+fn load_canister_snapshot_restores_memory_and_certified_data() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let data = [1, 2, 3, 5, 8, 13];
+    let update = wasm()
+        .stable_grow(1)
+        .stable_write(42, &data)
+        .certified_data_set(b"before")
+        .reply()
+        .build();
+    get_reply(test.ingress(canister_id, "update", update));
+
+    let snapshot_canister_version = test
+        .canister_state(canister_id)
+        .system_state
+        .canister_version;
+    let snapshot = take_canister_snapshot(&mut test, canister_id, None).unwrap();
+
+    let update = wasm()
+        .stable_write(42, &[0; 6])
+        .certified_data_set(b"after")
+        .reply()
+        .build();
+    get_reply(test.ingress(canister_id, "update", update));
+    let canister_version = test
+        .canister_state(canister_id)
+        .system_state
+        .canister_version;
+
+    let args = LoadCanisterSnapshotArgs::new(canister_id, snapshot.snapshot_id().to_vec(), None);
+    let result = test.subnet_message(Method::LoadCanisterSnapshot, args.encode());
+    assert_empty_reply(result);
+
+    let canister = test.canister_state(canister_id);
+    assert_eq!(canister.system_state.certified_data, b"before".to_vec());
+    assert_eq!(canister.system_state.canister_version, canister_version + 1);
+    let last_change = canister
+        .system_state
+        .get_canister_history()
+        .get_changes(1)
+        .next()
+        .unwrap();
+    assert_eq!(last_change.canister_version(), canister_version + 1);
+    assert_eq!(
+        last_change.details(),
+        &CanisterChangeDetails::load_snapshot(
+            snapshot_canister_version,
+            snapshot.snapshot_id().to_vec(),
+            snapshot.taken_at_timestamp,
+        )
+    );
+    let query = wasm()
+        .stable_read(42, data.len() as u32)
+        .append_and_reply()
+        .build();
+    let reply = get_reply(test.ingress(canister_id, "query", query));
+    assert_eq!(reply, data);
+}
+
+#[test]
+fn load_canister_snapshot_without_cycles_for_compilation_does_not_reserve_memory() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let snapshot = take_canister_snapshot(&mut test, canister_id, None).unwrap();
+    test.uninstall_code(canister_id).unwrap();
+
+    // Loading the snapshot into the empty canister grows its memory, which
+    // the canister can afford without a freezing threshold, but it cannot
+    // pay for compiling the module.
+    test.canister_state_mut(canister_id)
+        .system_state
+        .freeze_threshold = NumSeconds::from(0);
+    test.canister_state_mut(canister_id)
+        .system_state
+        .set_balance(Cycles::zero());
+    let subnet_available_memory = test.subnet_available_memory();
+    let canister_version = test
+        .canister_state(canister_id)
+        .system_state
+        .canister_version;
+
+    let args = LoadCanisterSnapshotArgs::new(canister_id, snapshot.snapshot_id().to_vec(), None);
+    let err = test
+        .subnet_message(Method::LoadCanisterSnapshot, args.encode())
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterOutOfCycles);
+
+    assert_eq!(
+        test.subnet_available_memory().get_execution_memory(),
+        subnet_available_memory.get_execution_memory()
+    );
+    let canister = test.canister_state(canister_id);
+    assert!(canister.execution_state.is_none());
+    assert_eq!(canister.system_state.canister_version, canister_version);
+    assert_eq!(canister.system_state.reserved_balance(), Cycles::zero());
+}
+
 #[test]
-fn test_request_snapshot_rejected_because_feature_is_enabled() {
+fn load_canister_snapshot_fails_for_snapshot_of_other_canister() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let other_canister_id = test.universal_canister().unwrap();
+    let snapshot = take_canister_snapshot(&mut test, other_canister_id, None).unwrap();
+
+    let args = LoadCanisterSnapshotArgs::new(canister_id, snapshot.snapshot_id().to_vec(), None);
+    let err = test
+        .subnet_message(Method::LoadCanisterSnapshot, args.encode())
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterRejectedMessage);
+    assert!(err.description().contains("does not belong to canister"));
+}
+
+#[test]
+fn delete_canister_snapshot_releases_memory() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    let memory_usage_before = test.canister_state(canister_id).memory_usage();
+    let snapshot = take_canister_snapshot(&mut test, canister_id, None).unwrap();
+
+    let args = DeleteCanisterSnapshotArgs::new(canister_id, snapshot.snapshot_id().to_vec());
+    let result = test.subnet_message(Method::DeleteCanisterSnapshot, args.encode());
+    assert_empty_reply(result);
+
+    assert_eq!(list_canister_snapshots(&mut test, canister_id), vec![]);
+    assert_eq!(
+        test.canister_state(canister_id).memory_usage(),
+        memory_usage_before
+    );
+
+    // Deleting the same snapshot again fails.
+    let args = DeleteCanisterSnapshotArgs::new(canister_id, snapshot.snapshot_id().to_vec());
+    let err = test
+        .subnet_message(Method::DeleteCanisterSnapshot, args.encode())
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterRejectedMessage);
+}
+
+#[test]
+fn delete_canister_deletes_its_snapshots() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_snapshots(FlagStatus::Enabled)
+        .build();
+    let canister_id = test.universal_canister().unwrap();
+    take_canister_snapshot(&mut test, canister_id, None).unwrap();
+
+    test.stop_canister(canister_id);
+    test.process_stopping_canisters();
+    let result = test.subnet_message(
+        Method::DeleteCanister,
+        CanisterIdRecord::from(canister_id).encode(),
+    );
+    assert_empty_reply(result);
+
+    assert!(test.state().canister_snapshots.is_empty());
+}
+
+#[test]
+fn snapshot_request_with_invalid_payload_is_rejected() {
     let own_subnet = subnet_test_id(1);
     let caller_canister = canister_test_id(1);
     let mut test = ExecutionTestBuilder::new()
@@ -3142,12 +3408,9 @@ fn test_request_snapshot_rejected_because_feature_is_enabled() {
     assert_matches!(response, RequestOrResponse::Response(_));
     if let RequestOrResponse::Response(res) = response {
         assert_eq!(res.originator, *receiver);
-        assert_eq!(
-            res.response_payload,
-            Payload::Reject(RejectContext::new(
-                RejectCode::CanisterReject,
-                "Canister snapshotting API is not yet implemented."
-            ))
+        assert_matches!(
+            &res.response_payload,
+            Payload::Reject(reject) if reject.code() == RejectCode::CanisterReject
         );
     }
 }
@@ -3199,17 +3462,29 @@ fn test_ingress_snapshot_rejected_because_feature_is_disabled() {
         Method::ListCanisterSnapshots,
     ];
     for method in snapshot_methods {
+        let payload = match method {
+            Method::TakeCanisterSnapshot => TakeCanisterSnapshotArgs::new(uni, None).encode(),
+            Method::LoadCanisterSnapshot => {
+                LoadCanisterSnapshotArgs::new(uni, vec![0; 18], None).encode()
+            }
+            Method::DeleteCanisterSnapshot => {
+                DeleteCanisterSnapshotArgs::new(uni, vec![0; 18]).encode()
+            }
+            Method::ListCanisterSnapshots => ListCanisterSnapshotArgs::new(uni).encode(),
+            _ => unreachable!(),
+        };
         let call = wasm()
             .call_simple(
                 ic00::IC_00,
                 method,
                 call_args()
-                    .other_side(vec![])
+                    .other_side(payload)
                     .on_reject(wasm().reject_message().reject()),
             )
             .build();
         let result = test.ingress(uni, "update", call).unwrap();
-        let expected_result = WasmResult::Reject(format!("Unable to route management canister request {}: UserError(UserError {{ code: CanisterRejectedMessage, description: {} }})", method, "\"Snapshotting API is not yet implemented\""));
+        let expected_result =
+            WasmResult::Reject("This API is not enabled on this subnet".to_string());
         assert_eq!(result, expected_result);
     }
 }
@@ -1,4 +1,5 @@
 use ic_canister_sandbox_backend_lib::replica_controller::sandboxed_execution_controller::SandboxedExecutionController;
+use ic_config::embedders::Config as EmbeddersConfig;
 use ic_config::execution_environment::{Config, MAX_COMPILATION_CACHE_SIZE};
 use ic_config::flag_status::FlagStatus;
 use ic_cycles_account_manager::CyclesAccountManager;
@@ -7,7 +8,7 @@ use ic_embedders::wasm_utils::decoding::decoded_wasm_size;
 use ic_embedders::{wasm_executor::WasmExecutorImpl, WasmExecutionInput, WasmtimeEmbedder};
 use ic_embedders::{CompilationCache, CompilationResult};
 use ic_interfaces::execution_environment::{HypervisorResult, WasmExecutionOutput};
-use ic_logger::ReplicaLogger;
+use ic_logger::{info, warn, ReplicaLogger};
 use ic_metrics::buckets::decimal_buckets_with_zero;
 use ic_metrics::{buckets::exponential_buckets, MetricsRegistry};
 use ic_registry_subnet_type::SubnetType;
@@ -16,12 +17,17 @@ use ic_replicated_state::{page_map::allocated_pages_count, ExecutionState, Syste
 use ic_system_api::ExecutionParameters;
 use ic_system_api::{sandbox_safe_system_state::SandboxSafeSystemState, ApiType};
 use ic_types::{
-    messages::RequestMetadata, methods::FuncRef, CanisterId, NumBytes, NumInstructions, SubnetId,
-    Time,
+    messages::RequestMetadata, methods::FuncRef, replica_version::REPLICA_BINARY_HASH, CanisterId,
+    NumBytes, NumInstructions, SubnetId, Time,
 };
 use ic_wasm_types::CanisterModule;
 use prometheus::{Histogram, IntCounter, IntGauge};
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use crate::execution::common::{apply_canister_state_changes, update_round_limits};
 use crate::execution_environment::{as_round_instructions, CompilationCostHandling, RoundLimits};
@@ -149,6 +155,63 @@ impl HypervisorMetrics {
     }
 }
 
+/// Creates the Wasm compilation cache. If a cache directory is configured, the
+/// compiled modules are persisted there, keyed by the replica binary and the
+/// embedder configuration so that a new version never reuses stale code.
+fn new_compilation_cache(
+    config: &Config,
+    embedder_config: &EmbeddersConfig,
+    log: &ReplicaLogger,
+) -> Arc<CompilationCache> {
+    let memory_only = || Arc::new(CompilationCache::new(config.max_compilation_cache_size));
+
+    let dir = match &config.compilation_cache_dir {
+        Some(dir) => dir,
+        None => return memory_only(),
+    };
+
+    // Without the binary hash there is no way to tell whether the compiled
+    // code on disk is still valid.
+    let binary_hash = match REPLICA_BINARY_HASH.get() {
+        Some(binary_hash) => binary_hash,
+        None => {
+            warn!(
+                log,
+                "Replica binary hash is unknown, the on-disk compilation cache is disabled"
+            );
+            return memory_only();
+        }
+    };
+    let mut hasher = DefaultHasher::new();
+    format!("{:?}", embedder_config).hash(&mut hasher);
+    let version = format!("{}-{:016x}", binary_hash, hasher.finish());
+
+    match CompilationCache::new_with_disk_cache(
+        config.max_compilation_cache_size,
+        dir,
+        &version,
+        config.max_compilation_cache_disk_size,
+    ) {
+        Ok(compilation_cache) => {
+            info!(
+                log,
+                "Using the on-disk compilation cache at {}",
+                dir.display()
+            );
+            Arc::new(compilation_cache)
+        }
+        Err(err) => {
+            warn!(
+                log,
+                "Failed to open the on-disk compilation cache at {}: {}",
+                dir.display(),
+                err
+            );
+            memory_only()
+        }
+    }
+}
+
 #[doc(hidden)]
 pub struct Hypervisor {
     wasm_executor: Arc<dyn WasmExecutor>,
@@ -235,6 +298,8 @@ impl Hypervisor {
         embedder_config.subnet_type = own_subnet_type;
         embedder_config.dirty_page_overhead = dirty_page_overhead;
 
+        let compilation_cache = new_compilation_cache(&config, &embedder_config, &log);
+
         let wasm_executor: Arc<dyn WasmExecutor> = match config.canister_sandboxing_flag {
             FlagStatus::Enabled => {
                 let executor = SandboxedExecutionController::new(
@@ -264,7 +329,7 @@ impl Hypervisor {
             own_subnet_type,
             log,
             cycles_account_manager,
-            compilation_cache: Arc::new(CompilationCache::new(config.max_compilation_cache_size)),
+            compilation_cache,
             deterministic_time_slicing: config.deterministic_time_slicing,
             cost_to_compile_wasm_instruction: config
                 .embedders_config
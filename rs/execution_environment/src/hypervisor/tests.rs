@@ -3306,6 +3306,188 @@ fn ic0_trap_preserves_some_cycles() {
     );
 }
 
+#[test]
+fn simd_instructions_are_charged() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let wat_without_simd = r#"
+        (module
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (func $update
+                (call $msg_reply)
+            )
+            (export "canister_update update" (func $update))
+        )"#;
+    let wat_with_simd = r#"
+        (module
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (func $update
+                (drop
+                    (i32x4.extract_lane 0
+                        (i32x4.mul
+                            (i32x4.splat (i32.const 3))
+                            (i32x4.splat (i32.const 5)))))
+                (drop
+                    (i64x2.extract_lane 1
+                        (i64x2.add
+                            (i64x2.splat (i64.const 7))
+                            (i64x2.splat (i64.const 11)))))
+                (call $msg_reply)
+            )
+            (export "canister_update update" (func $update))
+        )"#;
+    let canister_without_simd = test.canister_from_wat(wat_without_simd).unwrap();
+    let canister_with_simd = test.canister_from_wat(wat_with_simd).unwrap();
+
+    let executed_instructions_before = test.executed_instructions();
+    test.ingress(canister_without_simd, "update", vec![])
+        .unwrap();
+    let instructions_without_simd = test.executed_instructions() - executed_instructions_before;
+
+    let executed_instructions_before = test.executed_instructions();
+    test.ingress(canister_with_simd, "update", vec![]).unwrap();
+    let instructions_with_simd = test.executed_instructions() - executed_instructions_before;
+
+    let simd_cost = instruction_to_cost_new(&wasmparser::Operator::I32x4Mul)
+        + 2 * instruction_to_cost_new(&wasmparser::Operator::I32x4Splat)
+        + instruction_to_cost_new(&wasmparser::Operator::I32x4ExtractLane { lane: 0 })
+        + instruction_to_cost_new(&wasmparser::Operator::I64x2Add)
+        + 2 * instruction_to_cost_new(&wasmparser::Operator::I64x2Splat)
+        + instruction_to_cost_new(&wasmparser::Operator::I64x2ExtractLane { lane: 1 });
+    let scalar_cost = 2 * instruction_to_cost_new(&wasmparser::Operator::I32Const { value: 0 })
+        + 2 * instruction_to_cost_new(&wasmparser::Operator::I64Const { value: 0 })
+        + 2 * instruction_to_cost_new(&wasmparser::Operator::Drop);
+    // The 32-bit lane multiplication is charged more than a scalar one.
+    assert_eq!(2, instruction_to_cost_new(&wasmparser::Operator::I32x4Mul));
+    assert!(simd_cost > 0);
+    assert_eq!(
+        instructions_with_simd,
+        instructions_without_simd + NumInstructions::from(simd_cost + scalar_cost)
+    );
+}
+
+#[test]
+fn simd_loop_is_charged_less_than_scalar_baseline() {
+    // Both canisters sum the same 4096 32-bit integers, one lane at a time
+    // and four lanes at a time respectively.
+    const LEN: usize = 4096 * size_of::<u32>();
+    let data = "\\01\\00\\00\\00".repeat(LEN / size_of::<u32>());
+    let wat_scalar = format!(
+        r#"
+        (module
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i32 i32)))
+            (func $update (local $i i32) (local $sum i32)
+                (loop $sum_loop
+                    (local.set $sum (i32.add (local.get $sum) (i32.load (local.get $i))))
+                    (local.set $i (i32.add (local.get $i) (i32.const 4)))
+                    (br_if $sum_loop (i32.lt_u (local.get $i) (i32.const {LEN}))))
+                (i32.store (i32.const {LEN}) (local.get $sum))
+                (call $msg_reply_data_append (i32.const {LEN}) (i32.const 4))
+                (call $msg_reply)
+            )
+            (memory 1)
+            (data (i32.const 0) "{data}")
+            (export "canister_update update" (func $update))
+        )"#
+    );
+    let wat_simd = format!(
+        r#"
+        (module
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i32 i32)))
+            (func $update (local $i i32) (local $sum v128)
+                (loop $sum_loop
+                    (local.set $sum (i32x4.add (local.get $sum) (v128.load (local.get $i))))
+                    (local.set $i (i32.add (local.get $i) (i32.const 16)))
+                    (br_if $sum_loop (i32.lt_u (local.get $i) (i32.const {LEN}))))
+                (i32.store (i32.const {LEN})
+                    (i32.add
+                        (i32.add
+                            (i32x4.extract_lane 0 (local.get $sum))
+                            (i32x4.extract_lane 1 (local.get $sum)))
+                        (i32.add
+                            (i32x4.extract_lane 2 (local.get $sum))
+                            (i32x4.extract_lane 3 (local.get $sum)))))
+                (call $msg_reply_data_append (i32.const {LEN}) (i32.const 4))
+                (call $msg_reply)
+            )
+            (memory 1)
+            (data (i32.const 0) "{data}")
+            (export "canister_update update" (func $update))
+        )"#
+    );
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_scalar = test.canister_from_wat(wat_scalar).unwrap();
+    let canister_simd = test.canister_from_wat(wat_simd).unwrap();
+    let expected = WasmResult::Reply(4096_u32.to_le_bytes().to_vec());
+
+    let executed_instructions_before = test.executed_instructions();
+    let result = test.ingress(canister_scalar, "update", vec![]).unwrap();
+    assert_eq!(result, expected);
+    let instructions_scalar = test.executed_instructions() - executed_instructions_before;
+
+    let executed_instructions_before = test.executed_instructions();
+    let result = test.ingress(canister_simd, "update", vec![]).unwrap();
+    assert_eq!(result, expected);
+    let instructions_simd = test.executed_instructions() - executed_instructions_before;
+
+    // SIMD instructions are charged like their scalar counterparts, so
+    // processing four lanes per instruction costs about a quarter of the
+    // scalar loop, but no less than that.
+    assert!(
+        instructions_simd.get() * 3 < instructions_scalar.get(),
+        "SIMD: {}, scalar: {}",
+        instructions_simd,
+        instructions_scalar
+    );
+    assert!(
+        instructions_simd.get() * 4 >= instructions_scalar.get(),
+        "SIMD: {}, scalar: {}",
+        instructions_simd,
+        instructions_scalar
+    );
+}
+
+#[test]
+fn simd_float_nan_results_are_canonicalized() {
+    // The first operand of each operation is a negative NaN with a payload,
+    // which the CPU would otherwise propagate to the result.
+    let wat = r#"
+        (module
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i32 i32)))
+            (func $update
+                (v128.store (i32.const 0)
+                    (f32x4.add
+                        (v128.const i32x4 0xffc00001 0xffc00001 0xffc00001 0xffc00001)
+                        (v128.const f32x4 1 1 1 1)))
+                (v128.store (i32.const 16)
+                    (f64x2.mul
+                        (v128.const i64x2 0xfff8000000000001 0xfff8000000000001)
+                        (v128.const f64x2 1 1)))
+                (v128.store (i32.const 32)
+                    (f32x4.div (v128.const f32x4 0 0 0 0) (v128.const f32x4 0 0 0 0)))
+                (call $msg_reply_data_append (i32.const 0) (i32.const 48))
+                (call $msg_reply)
+            )
+            (memory 1)
+            (export "canister_update update" (func $update))
+        )"#;
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let result = test.ingress(canister_id, "update", vec![]).unwrap();
+
+    let canonical_f32_nan = 0x7fc0_0000_u32.to_le_bytes();
+    let canonical_f64_nan = 0x7ff8_0000_0000_0000_u64.to_le_bytes();
+    let mut expected = canonical_f32_nan.repeat(4);
+    expected.extend(canonical_f64_nan.repeat(2));
+    expected.extend(canonical_f32_nan.repeat(4));
+    assert_eq!(result, WasmResult::Reply(expected));
+}
+
 // If method is not exported, `execute_anonymous_query` fails.
 #[test]
 fn canister_anonymous_query_method_not_exported() {
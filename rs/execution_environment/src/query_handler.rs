@@ -358,9 +358,14 @@ fn fetch_canister_logs(
         )),
     }?;
 
-    // TODO(IC-272): temporarily return empty logs until full implementation is ready.
     let response = FetchCanisterLogsResponse {
-        canister_log_records: Vec::new(),
+        canister_log_records: canister
+            .system_state
+            .canister_log
+            .records()
+            .iter()
+            .cloned()
+            .collect(),
     };
     Ok(WasmResult::Reply(Encode!(&response).unwrap()))
 }
@@ -31,7 +31,7 @@ use ic_types::{
     ingress::WasmResult,
     messages::{
         CallContextId, CallbackId, Payload, RejectContext, Request, RequestOrResponse, Response,
-        UserQuery,
+        UserQuery, NO_DEADLINE,
     },
     methods::WasmMethod,
     CanisterId, Cycles, NumInstructions, NumMessages, Time,
@@ -561,7 +561,7 @@ impl<'a> QueryContext<'a> {
         };
         let func_ref = match call_origin {
             CallOrigin::Ingress(_, _)
-            | CallOrigin::CanisterUpdate(_, _)
+            | CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::SystemTask => unreachable!("Unreachable in the QueryContext."),
             CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => {
                 FuncRef::QueryClosure(closure)
@@ -692,7 +692,7 @@ impl<'a> QueryContext<'a> {
     ) -> (NumInstructions, Result<Option<WasmResult>, HypervisorError>) {
         let func_ref = match call_origin {
             CallOrigin::Ingress(_, _)
-            | CallOrigin::CanisterUpdate(_, _)
+            | CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::SystemTask => unreachable!("Unreachable in the QueryContext."),
             CallOrigin::CanisterQuery(_, _) | CallOrigin::Query(_) => {
                 FuncRef::QueryClosure(cleanup_closure)
@@ -763,6 +763,7 @@ impl<'a> QueryContext<'a> {
                 originator_reply_callback: request.sender_reply_callback,
                 response_payload: payload,
                 refund: Cycles::zero(),
+                deadline: request.deadline,
             })
         };
 
@@ -915,7 +916,7 @@ impl<'a> QueryContext<'a> {
             };
 
         match call_origin {
-            CallOrigin::CanisterUpdate(_, _)
+            CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::Ingress(_, _)
             | CallOrigin::SystemTask => {
                 error!(
@@ -950,6 +951,7 @@ impl<'a> QueryContext<'a> {
                         originator_reply_callback: callback_id,
                         refund: Cycles::zero(),
                         response_payload: payload,
+                        deadline: NO_DEADLINE,
                     };
                     QueryResponse::CanisterResponse(response)
                 };
@@ -1000,7 +1002,7 @@ impl<'a> QueryContext<'a> {
         );
         match call_origin {
             CallOrigin::Ingress(_, _)
-            | CallOrigin::CanisterUpdate(_, _)
+            | CallOrigin::CanisterUpdate(_, _, _)
             | CallOrigin::SystemTask => {
                 unreachable!("Expected a query call context");
             }
@@ -1012,6 +1014,7 @@ impl<'a> QueryContext<'a> {
                     originator_reply_callback: callback_id,
                     refund: Cycles::zero(),
                     response_payload: Payload::Reject(RejectContext::from(error)),
+                    deadline: NO_DEADLINE,
                 };
                 QueryResponse::CanisterResponse(response)
             }
@@ -25,13 +25,14 @@ use ic_replicated_state::{
     },
     page_map::PageAllocatorFileDescriptor,
     CanisterState, CanisterStatus, ExecutionTask, InputQueueType, NetworkTopology, ReplicatedState,
+    StateError,
 };
 use ic_system_api::InstructionLimits;
 use ic_types::{
     consensus::ecdsa::QuadrupleId,
     crypto::canister_threshold_sig::MasterEcdsaPublicKey,
     ingress::{IngressState, IngressStatus},
-    messages::{CanisterMessage, Ingress, MessageId, StopCanisterContext},
+    messages::{CanisterMessage, Ingress, MessageId, RequestOrResponse, StopCanisterContext},
     AccumulatedPriority, CanisterId, ComputeAllocation, Cycles, ExecutionRound, LongExecutionMode,
     MemoryAllocation, NumBytes, NumInstructions, NumSlices, Randomness, SubnetId, Time,
 };
@@ -1216,11 +1217,22 @@ impl SchedulerImpl {
                             state.metadata.own_subnet_type,
                             InputQueueType::LocalSubnet,
                         )
-                        .map_err(|(err, msg)| {
-                            error!(
-                                self.log,
-                                "Inducting {:?} on same subnet failed with error '{}'.", &msg, &err
-                            );
+                        .or_else(|(err, msg)| match (&err, &msg) {
+                            // Late best-effort responses are dropped, as their reserved slots
+                            // were already consumed by deadline expired reject responses.
+                            (
+                                StateError::NonMatchingResponse { .. },
+                                RequestOrResponse::Response(response),
+                            ) if response.is_best_effort() => Ok(()),
+                            _ => {
+                                error!(
+                                    self.log,
+                                    "Inducting {:?} on same subnet failed with error '{}'.",
+                                    &msg,
+                                    &err
+                                );
+                                Err(())
+                            }
                         }),
                     None => Err(()),
                 });
@@ -49,10 +49,13 @@ use ic_test_utilities::{
 };
 use ic_test_utilities_execution_environment::{generate_subnets, test_registry_settings};
 use ic_types::{
+    canister_log::CanisterLog,
     consensus::ecdsa::QuadrupleId,
     crypto::{canister_threshold_sig::MasterEcdsaPublicKey, AlgorithmId},
     ingress::{IngressState, IngressStatus},
-    messages::{CallContextId, Ingress, MessageId, Request, RequestOrResponse, Response},
+    messages::{
+        CallContextId, Ingress, MessageId, Request, RequestOrResponse, Response, NO_DEADLINE,
+    },
     methods::{Callback, FuncRef, SystemMethod, WasmClosure, WasmMethod},
     CanisterTimer, ComputeAllocation, Cycles, ExecutionRound, MemoryAllocation, NumInstructions,
     Randomness, Time, UserId,
@@ -1081,6 +1084,7 @@ impl TestWasmExecutorCore {
                 allocated_message_bytes: NumBytes::from(0),
                 instance_stats: InstanceStats::default(),
                 system_api_call_counters: SystemApiCallCounters::default(),
+                canister_log: CanisterLog::default(),
             };
             self.schedule
                 .push((self.round, canister_id, instructions_to_execute));
@@ -1126,6 +1130,7 @@ impl TestWasmExecutorCore {
             num_instructions_left: instructions_left,
             instance_stats,
             system_api_call_counters: SystemApiCallCounters::default(),
+            canister_log: CanisterLog::default(),
         };
         self.schedule
             .push((self.round, canister_id, instructions_to_execute));
@@ -1232,6 +1237,7 @@ impl TestWasmExecutorCore {
             method_name: "update".into(),
             method_payload: encode_message_id_as_payload(call_message_id),
             metadata: None,
+            deadline: NO_DEADLINE,
         };
         if let Err(req) = system_state.push_output_request(
             canister_current_memory_usage,
@@ -44,6 +44,7 @@ use ic_types::time::expiry_time_from_now;
 use ic_types::{
     messages::{
         CallbackId, Payload, RejectContext, Response, StopCanisterCallId, MAX_RESPONSE_COUNT_BYTES,
+        NO_DEADLINE,
     },
     Height,
 };
@@ -3049,6 +3050,7 @@ fn ecdsa_signature_agreements_metric_is_updated() {
         originator_reply_callback: *callback_id,
         refund: context.request.payment,
         response_payload: Payload::Reject(RejectContext::new(RejectCode::SysFatal, "")),
+        deadline: NO_DEADLINE,
     };
 
     test.state_mut().consensus_queue.push(response);
@@ -3097,6 +3099,7 @@ fn ecdsa_signature_agreements_metric_is_updated() {
             }
             .encode(),
         ),
+        deadline: NO_DEADLINE,
     };
 
     test.state_mut().consensus_queue.push(response);
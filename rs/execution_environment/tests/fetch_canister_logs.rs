@@ -10,22 +10,24 @@ use ic_state_machine_tests::{
     ErrorCode, PrincipalId, StateMachine, StateMachineBuilder, StateMachineConfig,
     SubmitIngressError, UserError,
 };
-use ic_test_utilities::universal_canister::UNIVERSAL_CANISTER_WASM;
+use ic_test_utilities::universal_canister::{wasm, UNIVERSAL_CANISTER_WASM};
 use ic_test_utilities_execution_environment::get_reply;
 use ic_types::{CanisterId, Cycles};
 
-fn setup(fetch_canister_logs: FlagStatus) -> (StateMachine, CanisterId) {
-    let subnet_type = SubnetType::Application;
-    let config = StateMachineConfig::new(
-        SubnetConfig::new(subnet_type),
+fn config(fetch_canister_logs: FlagStatus) -> StateMachineConfig {
+    StateMachineConfig::new(
+        SubnetConfig::new(SubnetType::Application),
         ExecutionConfig {
             fetch_canister_logs,
             ..ExecutionConfig::default()
         },
-    );
+    )
+}
+
+fn setup(fetch_canister_logs: FlagStatus) -> (StateMachine, CanisterId) {
     let env = StateMachineBuilder::new()
-        .with_config(Some(config))
-        .with_subnet_type(subnet_type)
+        .with_config(Some(config(fetch_canister_logs)))
+        .with_subnet_type(SubnetType::Application)
         .with_checkpoints_enabled(false)
         .build();
     let canister_id =
@@ -331,3 +333,110 @@ fn test_fetch_canister_logs_enabled_query_log_visibility_valid_controller_succee
         }
     );
 }
+
+fn fetch_canister_logs_as(
+    env: &StateMachine,
+    sender: PrincipalId,
+    canister_id: CanisterId,
+) -> FetchCanisterLogsResponse {
+    let result = env.query_as(
+        sender,
+        CanisterId::ic_00(),
+        "fetch_canister_logs",
+        FetchCanisterLogsRequest {
+            canister_id: canister_id.into(),
+        }
+        .encode(),
+    );
+    FetchCanisterLogsResponse::decode(&get_reply(result)).unwrap()
+}
+
+fn log_contents(response: &FetchCanisterLogsResponse) -> Vec<(u64, Vec<u8>)> {
+    response
+        .canister_log_records
+        .iter()
+        .map(|record| (record.idx, record.content.clone()))
+        .collect()
+}
+
+#[test]
+fn test_fetch_canister_logs_returns_debug_print_and_trap_records() {
+    // Arrange.
+    let (env, canister_id) = setup(FlagStatus::Enabled);
+    let not_a_controller = PrincipalId::new_user_test_id(42);
+    env.update_settings(
+        &canister_id,
+        CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::Public)
+            .build(),
+    )
+    .unwrap();
+    // Act.
+    // Print a message and then trap in a separate message.
+    let _ = env.execute_ingress(
+        canister_id,
+        "update",
+        wasm().debug_print(b"hello").reply().build(),
+    );
+    let result = env.execute_ingress(
+        canister_id,
+        "update",
+        wasm()
+            .debug_print(b"about to fail")
+            .trap_with_blob(b"boom")
+            .build(),
+    );
+    assert!(result.is_err());
+    let response = fetch_canister_logs_as(&env, not_a_controller, canister_id);
+    // Assert.
+    // Records of the failed message are kept as well.
+    assert_eq!(
+        log_contents(&response),
+        vec![
+            (0, b"hello".to_vec()),
+            (1, b"about to fail".to_vec()),
+            (2, b"[TRAP]: boom".to_vec()),
+        ]
+    );
+    assert!(response
+        .canister_log_records
+        .windows(2)
+        .all(|w| 0 < w[0].timestamp_nanos && w[0].timestamp_nanos <= w[1].timestamp_nanos));
+}
+
+#[test]
+fn test_fetch_canister_logs_survives_checkpoint() {
+    // Arrange.
+    let (env, canister_id) = setup(FlagStatus::Enabled);
+    let not_a_controller = PrincipalId::new_user_test_id(42);
+    env.update_settings(
+        &canister_id,
+        CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::Public)
+            .build(),
+    )
+    .unwrap();
+    let _ = env.execute_ingress(
+        canister_id,
+        "update",
+        wasm().debug_print(b"before restart").reply().build(),
+    );
+    // Act.
+    env.set_checkpoints_enabled(true);
+    env.tick();
+    let env = env.restart_node_with_config(config(FlagStatus::Enabled));
+    let _ = env.execute_ingress(
+        canister_id,
+        "update",
+        wasm().debug_print(b"after restart").reply().build(),
+    );
+    // Assert.
+    // Records and indices are preserved across the restart.
+    assert_eq!(
+        log_contents(&fetch_canister_logs_as(&env, not_a_controller, canister_id)),
+        vec![
+            (0, b"before restart".to_vec()),
+            (1, b"after restart".to_vec()),
+        ]
+    );
+}
@@ -306,7 +306,11 @@ pub mod test {
                 "enabled_tags": [],
                 "block_on_overflow": true
             },
-            "socks_proxy": "socks5://notaproxy.com:1080"        
+            "socks_proxy": "socks5://notaproxy.com:1080",
+            "allowed_hosts": ["*.example.com"],
+            "denied_hosts": ["bad.example.com"],
+            "max_concurrent_requests_per_host": 10,
+            "max_requests_per_second_per_host": 20
         }       
         "#;
 
@@ -333,6 +337,10 @@ pub mod test {
                 ..Default::default()
             },
             socks_proxy: "socks5://notaproxy.com:1080".to_string(),
+            allowed_hosts: vec!["*.example.com".to_string()],
+            denied_hosts: vec!["bad.example.com".to_string()],
+            max_concurrent_requests_per_host: Some(10),
+            max_requests_per_second_per_host: Some(20),
         };
         assert_eq!(config, expected_config);
     }
@@ -30,6 +30,19 @@ pub struct Config {
     /// is not present at adapter startup. So to enable/disable the proxy there exists a `socks_proxy_allowed` field in
     /// the adapter request.
     pub socks_proxy: String,
+    /// Hosts canisters are allowed to reach. Entries are either exact host names or
+    /// wildcards of the form `*.example.com`, which match all subdomains of `example.com`.
+    /// An empty list allows all hosts that are not denied.
+    pub allowed_hosts: Vec<String>,
+    /// Hosts canisters are not allowed to reach, in the same format as `allowed_hosts`.
+    /// Takes precedence over `allowed_hosts`.
+    pub denied_hosts: Vec<String>,
+    /// Maximum number of requests the adapter has in flight to a single host.
+    /// Unlimited if not set.
+    pub max_concurrent_requests_per_host: Option<usize>,
+    /// Maximum number of requests the adapter sends to a single host per second.
+    /// Unlimited if not set.
+    pub max_requests_per_second_per_host: Option<u32>,
 }
 
 impl Default for Config {
@@ -40,6 +53,10 @@ impl Default for Config {
             incoming_source: IncomingSource::default(),
             logger: LoggerConfig::default(),
             socks_proxy: "socks5://notaproxy:1080".to_string(),
+            allowed_hosts: Vec::new(),
+            denied_hosts: Vec::new(),
+            max_concurrent_requests_per_host: None,
+            max_requests_per_second_per_host: None,
         }
     }
 }
@@ -0,0 +1,255 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Length of the window used to count requests for the per-host rate limit.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HostPolicyError {
+    /// The host is on the deny list or not on a non-empty allow list.
+    Denied,
+    /// The host already has the maximum number of requests in flight.
+    ConcurrencyLimitExceeded,
+    /// The host already received the maximum number of requests in the current window.
+    RateLimitExceeded,
+}
+
+/// Returns the canonical form of a host name: lowercase and without the trailing
+/// dot of a fully qualified name, so that e.g. `Example.COM.` and `example.com`
+/// are the same host.
+fn normalize_host(host: &str) -> String {
+    let host = host.to_ascii_lowercase();
+    match host.strip_suffix('.') {
+        Some(host) => host.to_string(),
+        None => host,
+    }
+}
+
+/// Returns true if the normalized `host` matches `pattern`. A pattern of the form
+/// `*.example.com` matches all subdomains of `example.com` but not `example.com`
+/// itself. Any other pattern must match the host exactly. Matching is
+/// case-insensitive.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = normalize_host(pattern);
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .map_or(false, |prefix| prefix.len() > 1 && prefix.ends_with('.')),
+        None => pattern == host,
+    }
+}
+
+#[derive(Default)]
+struct HostUsage {
+    in_flight: usize,
+    window_start: Option<Instant>,
+    requests_in_window: u32,
+}
+
+/// Decides which hosts canisters may reach and limits the load put on each host.
+pub struct HostPolicy {
+    allowed_hosts: Vec<String>,
+    denied_hosts: Vec<String>,
+    max_concurrent_requests_per_host: Option<usize>,
+    max_requests_per_second_per_host: Option<u32>,
+    usage: Arc<Mutex<HashMap<String, HostUsage>>>,
+}
+
+impl HostPolicy {
+    pub fn new(
+        allowed_hosts: Vec<String>,
+        denied_hosts: Vec<String>,
+        max_concurrent_requests_per_host: Option<usize>,
+        max_requests_per_second_per_host: Option<u32>,
+    ) -> Self {
+        Self {
+            allowed_hosts,
+            denied_hosts,
+            max_concurrent_requests_per_host,
+            max_requests_per_second_per_host,
+            usage: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Checks whether a request to `host` may be made. On success, returns a permit
+    /// that counts towards the host's concurrency limit until it is dropped.
+    pub fn acquire(&self, host: &str) -> Result<HostPermit, HostPolicyError> {
+        self.acquire_at(host, Instant::now())
+    }
+
+    fn acquire_at(&self, host: &str, now: Instant) -> Result<HostPermit, HostPolicyError> {
+        let host = normalize_host(host);
+
+        // The deny list takes precedence over the allow list.
+        if self.denied_hosts.iter().any(|p| host_matches(p, &host))
+            || (!self.allowed_hosts.is_empty()
+                && !self.allowed_hosts.iter().any(|p| host_matches(p, &host)))
+        {
+            return Err(HostPolicyError::Denied);
+        }
+
+        let mut usage = self.usage.lock().unwrap();
+        let host_usage = usage.entry(host.clone()).or_default();
+
+        if let Some(max) = self.max_concurrent_requests_per_host {
+            if host_usage.in_flight >= max {
+                return Err(HostPolicyError::ConcurrencyLimitExceeded);
+            }
+        }
+
+        if let Some(max) = self.max_requests_per_second_per_host {
+            match host_usage.window_start {
+                Some(start) if now.duration_since(start) < RATE_LIMIT_WINDOW => {
+                    if host_usage.requests_in_window >= max {
+                        return Err(HostPolicyError::RateLimitExceeded);
+                    }
+                }
+                _ => {
+                    host_usage.window_start = Some(now);
+                    host_usage.requests_in_window = 0;
+                }
+            }
+            host_usage.requests_in_window += 1;
+        }
+
+        host_usage.in_flight += 1;
+        Ok(HostPermit {
+            host,
+            usage: Arc::clone(&self.usage),
+        })
+    }
+}
+
+/// Marks a request as in flight for as long as it is alive.
+pub struct HostPermit {
+    host: String,
+    usage: Arc<Mutex<HashMap<String, HostUsage>>>,
+}
+
+impl Drop for HostPermit {
+    fn drop(&mut self) {
+        let mut usage = self.usage.lock().unwrap();
+        if let Some(host_usage) = usage.get_mut(&self.host) {
+            host_usage.in_flight = host_usage.in_flight.saturating_sub(1);
+            // Forget hosts without activity to keep the map bounded.
+            let window_expired = host_usage
+                .window_start
+                .map_or(true, |start| start.elapsed() >= RATE_LIMIT_WINDOW);
+            if host_usage.in_flight == 0 && window_expired {
+                usage.remove(&self.host);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allowed: &[&str], denied: &[&str]) -> HostPolicy {
+        HostPolicy::new(
+            allowed.iter().map(|h| h.to_string()).collect(),
+            denied.iter().map(|h| h.to_string()).collect(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_host_matches() {
+        assert!(host_matches("example.com", "example.com"));
+        assert!(host_matches("EXAMPLE.com.", "example.com"));
+        assert!(!host_matches("example.com", "api.example.com"));
+        assert!(host_matches("*.example.com", "api.example.com"));
+        assert!(host_matches("*.example.com", "a.b.example.com"));
+        assert!(!host_matches("*.example.com", "example.com"));
+        assert!(!host_matches("*.example.com", "badexample.com"));
+    }
+
+    #[test]
+    fn test_hosts_are_normalized() {
+        let policy = HostPolicy::new(
+            vec!["*.example.com".to_string()],
+            vec!["bad.example.com".to_string()],
+            None,
+            Some(1),
+        );
+        for host in ["bad.example.com.", "BAD.Example.com", "Bad.example.COM."] {
+            assert_eq!(policy.acquire(host).err(), Some(HostPolicyError::Denied));
+        }
+        assert_eq!(
+            policy.acquire("example.com.").err(),
+            Some(HostPolicyError::Denied)
+        );
+
+        // All spellings of a host share its limits.
+        let now = Instant::now();
+        assert!(policy.acquire_at("api.example.com", now).is_ok());
+        assert_eq!(
+            policy.acquire_at("API.example.com.", now).err(),
+            Some(HostPolicyError::RateLimitExceeded)
+        );
+    }
+
+    #[test]
+    fn test_empty_lists_allow_everything() {
+        assert!(policy(&[], &[]).acquire("example.com").is_ok());
+    }
+
+    #[test]
+    fn test_allow_list() {
+        let policy = policy(&["example.com", "*.dfinity.org"], &[]);
+        assert!(policy.acquire("example.com").is_ok());
+        assert!(policy.acquire("api.dfinity.org").is_ok());
+        assert_eq!(
+            policy.acquire("other.com").err(),
+            Some(HostPolicyError::Denied)
+        );
+    }
+
+    #[test]
+    fn test_deny_list_takes_precedence() {
+        let policy = policy(&["*.example.com"], &["bad.example.com"]);
+        assert!(policy.acquire("good.example.com").is_ok());
+        assert_eq!(
+            policy.acquire("bad.example.com").err(),
+            Some(HostPolicyError::Denied)
+        );
+    }
+
+    #[test]
+    fn test_concurrency_limit() {
+        let policy = HostPolicy::new(vec![], vec![], Some(2), None);
+        let first = policy.acquire("example.com").unwrap();
+        let _second = policy.acquire("example.com").unwrap();
+        assert_eq!(
+            policy.acquire("example.com").err(),
+            Some(HostPolicyError::ConcurrencyLimitExceeded)
+        );
+        // Other hosts are not affected.
+        assert!(policy.acquire("other.com").is_ok());
+
+        drop(first);
+        assert!(policy.acquire("example.com").is_ok());
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let policy = HostPolicy::new(vec![], vec![], None, Some(2));
+        let now = Instant::now();
+        assert!(policy.acquire_at("example.com", now).is_ok());
+        assert!(policy.acquire_at("example.com", now).is_ok());
+        assert_eq!(
+            policy.acquire_at("example.com", now).err(),
+            Some(HostPolicyError::RateLimitExceeded)
+        );
+        assert!(policy.acquire_at("other.com", now).is_ok());
+
+        // A new window starts after one second.
+        let later = now + RATE_LIMIT_WINDOW;
+        assert!(policy.acquire_at("example.com", later).is_ok());
+    }
+}
@@ -11,11 +11,15 @@ mod config;
 /// Adapter metrics
 mod metrics;
 
+/// Per-host allow/deny lists and request limits.
+mod host_policy;
+
 pub use cli::Cli;
 pub use config::{Config, IncomingSource};
 pub use rpc_server::CanisterHttp;
 
 use futures::{Future, Stream};
+use host_policy::HostPolicy;
 use hyper::{client::connect::HttpConnector, Client};
 use hyper_rustls::HttpsConnectorBuilder;
 use hyper_socks2::SocksConnector;
@@ -64,7 +68,14 @@ impl AdapterServer {
             .enable_http1()
             .wrap_connector(http_connector);
         let https_client = Client::builder().build::<_, hyper::Body>(https_connector);
-        let canister_http = CanisterHttp::new(https_client, socks_client, logger, metrics);
+        let host_policy = HostPolicy::new(
+            config.allowed_hosts,
+            config.denied_hosts,
+            config.max_concurrent_requests_per_host,
+            config.max_requests_per_second_per_host,
+        );
+        let canister_http =
+            CanisterHttp::new(https_client, socks_client, host_policy, logger, metrics);
 
         Self(
             Server::builder()
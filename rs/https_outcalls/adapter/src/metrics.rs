@@ -11,6 +11,9 @@ pub(crate) const LABEL_RESPONSE_HEADERS: &str = "response_headers";
 pub(crate) const LABEL_REQUEST_HEADERS: &str = "request_headers";
 pub(crate) const LABEL_CONNECT: &str = "connect";
 pub(crate) const LABEL_URL_PARSE: &str = "url_parse";
+pub(crate) const LABEL_HOST_DENIED: &str = "host_denied";
+pub(crate) const LABEL_HOST_CONCURRENCY_LIMIT: &str = "host_concurrency_limit";
+pub(crate) const LABEL_HOST_RATE_LIMIT: &str = "host_rate_limit";
 pub(crate) const LABEL_UPLOAD: &str = "up";
 pub(crate) const LABEL_DOWNLOAD: &str = "down";
 
@@ -1,7 +1,9 @@
+use crate::host_policy::{HostPolicy, HostPolicyError};
 use crate::metrics::{
     AdapterMetrics, LABEL_BODY_RECEIVE_SIZE, LABEL_BODY_RECEIVE_TIMEOUT, LABEL_CONNECT,
-    LABEL_DOWNLOAD, LABEL_HEADER_RECEIVE_SIZE, LABEL_HTTP_METHOD, LABEL_HTTP_SCHEME,
-    LABEL_REQUEST_HEADERS, LABEL_RESPONSE_HEADERS, LABEL_UPLOAD, LABEL_URL_PARSE,
+    LABEL_DOWNLOAD, LABEL_HEADER_RECEIVE_SIZE, LABEL_HOST_CONCURRENCY_LIMIT, LABEL_HOST_DENIED,
+    LABEL_HOST_RATE_LIMIT, LABEL_HTTP_METHOD, LABEL_HTTP_SCHEME, LABEL_REQUEST_HEADERS,
+    LABEL_RESPONSE_HEADERS, LABEL_UPLOAD, LABEL_URL_PARSE,
 };
 use byte_unit::Byte;
 use core::convert::TryFrom;
@@ -37,6 +39,7 @@ const USER_AGENT_ADAPTER: &str = "ic/1.0";
 pub struct CanisterHttp {
     client: Client<HttpsConnector<HttpConnector>>,
     socks_client: Client<HttpsConnector<SocksConnector<HttpConnector>>>,
+    host_policy: HostPolicy,
     logger: ReplicaLogger,
     metrics: AdapterMetrics,
 }
@@ -45,12 +48,14 @@ impl CanisterHttp {
     pub fn new(
         client: Client<HttpsConnector<HttpConnector>>,
         socks_client: Client<HttpsConnector<SocksConnector<HttpConnector>>>,
+        host_policy: HostPolicy,
         logger: ReplicaLogger,
         metrics: &MetricsRegistry,
     ) -> Self {
         Self {
             client,
             socks_client,
+            host_policy,
             logger,
             metrics: AdapterMetrics::new(metrics),
         }
@@ -126,6 +131,41 @@ impl CanisterHttpService for CanisterHttp {
             err
         })?;
 
+        // The host policy is only consulted for well-formed requests, so that
+        // invalid requests do not count towards the limits of the host. The
+        // permit is held until the response body has been received.
+        let _host_permit = self
+            .host_policy
+            .acquire(uri.host().unwrap_or_default())
+            .map_err(|err| {
+                debug!(self.logger, "Rejected request to {}: {:?}", uri, err);
+                let (label, code, message) = match err {
+                    HostPolicyError::Denied => (
+                        LABEL_HOST_DENIED,
+                        tonic::Code::PermissionDenied,
+                        "Host is not allowed",
+                    ),
+                    HostPolicyError::ConcurrencyLimitExceeded => (
+                        LABEL_HOST_CONCURRENCY_LIMIT,
+                        tonic::Code::Unavailable,
+                        "Too many concurrent requests to host",
+                    ),
+                    HostPolicyError::RateLimitExceeded => (
+                        LABEL_HOST_RATE_LIMIT,
+                        tonic::Code::ResourceExhausted,
+                        "Too many requests per second to host",
+                    ),
+                };
+                self.metrics
+                    .request_errors
+                    .with_label_values(&[label])
+                    .inc();
+                Status::new(
+                    code,
+                    format!("{}: {:.50}", message, uri.host().unwrap_or_default()),
+                )
+            })?;
+
         // Add user-agent header if not present.
         add_fallback_user_agent_header(&mut headers);
 
@@ -7,7 +7,7 @@ mod test {
     use ic_https_outcalls_adapter::{AdapterServer, Config};
     use ic_https_outcalls_service::{
         canister_http_service_client::CanisterHttpServiceClient, CanisterHttpSendRequest,
-        HttpMethod,
+        HttpHeader, HttpMethod,
     };
     use ic_logger::replica_logger::no_op_logger;
     use ic_metrics::MetricsRegistry;
@@ -384,6 +384,136 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
         let _ = response.unwrap_err();
     }
 
+    #[tokio::test]
+    async fn test_denied_host() {
+        // Test that requests to a denied host are rejected before connecting.
+        let server_config = Config {
+            denied_hosts: vec!["localhost".to_string()],
+            ..Default::default()
+        };
+
+        let url = start_server(CERT_INIT.get_or_init(generate_certs));
+        let mut client = spawn_grpc_server(server_config);
+
+        let request = tonic::Request::new(CanisterHttpSendRequest {
+            url: format!("https://{}/get", &url),
+            headers: Vec::new(),
+            method: HttpMethod::Get as i32,
+            body: "hello".to_string().as_bytes().to_vec(),
+            max_response_size_bytes: 512,
+            socks_proxy_allowed: false,
+        });
+        let response = client.canister_http_send(request).await;
+        assert_eq!(response.unwrap_err().code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn test_host_not_in_allow_list() {
+        let server_config = Config {
+            allowed_hosts: vec!["*.example.com".to_string()],
+            ..Default::default()
+        };
+
+        let url = start_server(CERT_INIT.get_or_init(generate_certs));
+        let mut client = spawn_grpc_server(server_config);
+
+        let request = tonic::Request::new(CanisterHttpSendRequest {
+            url: format!("https://{}/get", &url),
+            headers: Vec::new(),
+            method: HttpMethod::Get as i32,
+            body: "hello".to_string().as_bytes().to_vec(),
+            max_response_size_bytes: 512,
+            socks_proxy_allowed: false,
+        });
+        let response = client.canister_http_send(request).await;
+        assert_eq!(response.unwrap_err().code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn test_host_rate_limit() {
+        let server_config = Config {
+            max_requests_per_second_per_host: Some(1),
+            ..Default::default()
+        };
+
+        let url = start_server(CERT_INIT.get_or_init(generate_certs));
+        let mut client = spawn_grpc_server(server_config);
+
+        let request = CanisterHttpSendRequest {
+            url: format!("https://{}/get", &url),
+            headers: Vec::new(),
+            method: HttpMethod::Get as i32,
+            body: "hello".to_string().as_bytes().to_vec(),
+            max_response_size_bytes: 512,
+            socks_proxy_allowed: false,
+        };
+        let response = client
+            .canister_http_send(tonic::Request::new(request.clone()))
+            .await;
+        assert_eq!(
+            response.unwrap().into_inner().status,
+            StatusCode::OK.as_u16() as u32
+        );
+        let err = client
+            .canister_http_send(tonic::Request::new(request))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+        assert!(
+            err.message()
+                .starts_with("Too many requests per second to host"),
+            "{}",
+            err.message()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests_do_not_count_towards_host_limits() {
+        let server_config = Config {
+            max_requests_per_second_per_host: Some(1),
+            ..Default::default()
+        };
+
+        let url = start_server(CERT_INIT.get_or_init(generate_certs));
+        let mut client = spawn_grpc_server(server_config);
+
+        let request = CanisterHttpSendRequest {
+            url: format!("https://{}/get", &url),
+            headers: Vec::new(),
+            method: HttpMethod::Get as i32,
+            body: "hello".to_string().as_bytes().to_vec(),
+            max_response_size_bytes: 512,
+            socks_proxy_allowed: false,
+        };
+        let invalid_requests = [
+            CanisterHttpSendRequest {
+                method: HttpMethod::Unspecified as i32,
+                ..request.clone()
+            },
+            CanisterHttpSendRequest {
+                headers: vec![HttpHeader {
+                    name: "invalid name".to_string(),
+                    value: "value".to_string(),
+                }],
+                ..request.clone()
+            },
+        ];
+        for invalid_request in invalid_requests {
+            let response = client
+                .canister_http_send(tonic::Request::new(invalid_request))
+                .await;
+            assert_eq!(response.unwrap_err().code(), tonic::Code::InvalidArgument);
+        }
+
+        let response = client
+            .canister_http_send(tonic::Request::new(request))
+            .await;
+        assert_eq!(
+            response.unwrap().into_inner().status,
+            StatusCode::OK.as_u16() as u32
+        );
+    }
+
     // Spawn grpc server and return canister http client
     fn spawn_grpc_server(config: Config) -> CanisterHttpServiceClient<Channel> {
         let uuid = Uuid::new_v4();
@@ -315,6 +315,8 @@ fn grpc_status_code_to_reject(code: Code) -> RejectCode {
     match code {
         // TODO: Is unavailable really transient
         Code::Unavailable => RejectCode::SysTransient,
+        // The adapter's per-host limits were hit, retrying later may succeed.
+        Code::ResourceExhausted => RejectCode::SysTransient,
         Code::InvalidArgument => RejectCode::SysFatal,
         _ => RejectCode::SysFatal,
     }
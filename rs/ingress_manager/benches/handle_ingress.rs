@@ -32,7 +32,9 @@ use ic_registry_client::client::RegistryClientImpl;
 use ic_registry_keys::make_subnet_record_key;
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
 use ic_registry_subnet_type::SubnetType;
-use ic_replicated_state::{CanisterQueues, ReplicatedState, SystemMetadata};
+use ic_replicated_state::{
+    canister_snapshots::CanisterSnapshots, CanisterQueues, ReplicatedState, SystemMetadata,
+};
 use ic_test_utilities::{
     crypto::temp_crypto_component_with_fake_registry,
     cycles_account_manager::CyclesAccountManagerBuilder,
@@ -189,6 +191,7 @@ where
                         metadata,
                         CanisterQueues::default(),
                         RawQueryStats::default(),
+                        CanisterSnapshots::default(),
                     )),
                 )
             });
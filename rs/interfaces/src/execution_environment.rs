@@ -10,6 +10,7 @@ use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_subnet_type::SubnetType;
 use ic_sys::{PageBytes, PageIndex};
 use ic_types::{
+    canister_log::CanisterLog,
     consensus::ecdsa::QuadrupleId,
     crypto::canister_threshold_sig::MasterEcdsaPublicKey,
     ingress::{IngressStatus, WasmResult},
@@ -101,6 +102,8 @@ pub enum SystemApiCallId {
     CallOnCleanup,
     /// Tracker for `ic0.call_perform()`
     CallPerform,
+    /// Tracker for `ic0.call_with_best_effort_response()`
+    CallWithBestEffortResponse,
     /// Tracker for `ic0.canister_cycle_balance()`
     CanisterCycleBalance,
     /// Tracker for `ic0.canister_cycle_balance128()`
@@ -115,6 +118,14 @@ pub enum SystemApiCallId {
     CanisterVersion,
     /// Tracker for `ic0.certified_data_set()`
     CertifiedDataSet,
+    /// Tracker for `ic0.cost_call()`
+    CostCall,
+    /// Tracker for `ic0.cost_create_canister()`
+    CostCreateCanister,
+    /// Tracker for `ic0.cost_http_request()`
+    CostHttpRequest,
+    /// Tracker for `ic0.cost_sign_with_ecdsa()`
+    CostSignWithEcdsa,
     /// Tracker for `ic0.cycles_burn128()`
     CyclesBurn128,
     /// Tracker for `ic0.data_certificate_copy()`
@@ -690,11 +701,12 @@ pub trait SystemApi {
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
 
-    /// Outputs the specified bytes on the heap as a string on STDOUT.
-    fn ic0_debug_print(&self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()>;
+    /// Outputs the specified bytes on the heap as a string on STDOUT and
+    /// records them in the canister log.
+    fn ic0_debug_print(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()>;
 
     /// Traps, with a possibly helpful message
-    fn ic0_trap(&self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()>;
+    fn ic0_trap(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()>;
 
     /// Begins assembling a call to the canister specified by
     /// callee_src/callee_size at method name_src/name_size. Two mandatory
@@ -731,6 +743,14 @@ pub trait SystemApi {
     /// See <https://sdk.dfinity.org/docs/interface-spec/index.html#system-api-call>
     fn ic0_call_on_cleanup(&mut self, fun: u32, env: u32) -> HypervisorResult<()>;
 
+    /// Turns the call under construction into a best-effort call that times
+    /// out `timeout_seconds` after the current time (bounded by the maximum
+    /// supported timeout). A timed out call is rejected with a
+    /// `SYS_TRANSIENT` reject and any late response is dropped.
+    /// Can be called at most once between `ic0.call_new` and
+    /// `ic0.call_perform`.
+    fn ic0_call_with_best_effort_response(&mut self, timeout_seconds: u32) -> HypervisorResult<()>;
+
     /// (deprecated) Please use `ic0_call_cycles_add128` instead, as this API
     /// can only add a 64-bit value.
     ///
@@ -1098,6 +1118,58 @@ pub trait SystemApi {
         dst: u32,
         heap: &mut [u8],
     ) -> HypervisorResult<()>;
+
+    /// Copies to `dst` the amount of cycles that `call_perform` withdraws for
+    /// a call with the given method name and payload sizes. The amount
+    /// includes the prepayment for the largest possible response, which is
+    /// partially refunded when the actual response arrives.
+    ///
+    /// This system call traps if dst+16 exceeds the size of the WebAssembly memory.
+    fn ic0_cost_call(
+        &self,
+        method_name_size: u64,
+        payload_size: u64,
+        dst: u32,
+        heap: &mut [u8],
+    ) -> HypervisorResult<()>;
+
+    /// Copies to `dst` the amount of cycles that have to be attached to the
+    /// `create_canister` call of the management canister.
+    ///
+    /// This system call traps if dst+16 exceeds the size of the WebAssembly memory.
+    fn ic0_cost_create_canister(&self, dst: u32, heap: &mut [u8]) -> HypervisorResult<()>;
+
+    /// Copies to `dst` the amount of cycles that have to be attached to the
+    /// `http_request` call of the management canister with the given request
+    /// size and maximal response size.
+    ///
+    /// This system call traps if dst+16 exceeds the size of the WebAssembly memory.
+    fn ic0_cost_http_request(
+        &self,
+        request_size: u64,
+        max_res_bytes: u64,
+        dst: u32,
+        heap: &mut [u8],
+    ) -> HypervisorResult<()>;
+
+    /// Copies to `dst` the amount of cycles that have to be attached to a
+    /// `sign_with_ecdsa` call of the management canister with the key whose
+    /// name is stored at `src..src+size` on the given curve. The amount is
+    /// computed for the size of the subnet that signs with the key.
+    ///
+    /// Returns 0 on success, 1 if the curve is invalid and 2 if no subnet
+    /// holds the key; nothing is copied in the latter two cases.
+    ///
+    /// This system call traps if src+size or dst+16 exceeds the size of the
+    /// WebAssembly memory or if the key name is not valid UTF-8.
+    fn ic0_cost_sign_with_ecdsa(
+        &self,
+        src: u32,
+        size: u32,
+        curve: u32,
+        dst: u32,
+        heap: &mut [u8],
+    ) -> HypervisorResult<u32>;
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -1203,6 +1275,9 @@ pub struct WasmExecutionOutput {
     pub instance_stats: InstanceStats,
     /// How many times each tracked System API call was invoked.
     pub system_api_call_counters: SystemApiCallCounters,
+    /// Log records produced by the canister during execution, including the
+    /// message of an explicit trap.
+    pub canister_log: CanisterLog,
 }
 
 impl fmt::Display for WasmExecutionOutput {
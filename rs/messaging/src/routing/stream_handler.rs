@@ -46,6 +46,8 @@ struct StreamHandlerMetrics {
     pub gced_xnet_messages: IntCounter,
     /// Garbage collected XNet reject signals.
     pub gced_xnet_reject_signals: IntCounter,
+    /// Best-effort messages shed due to message memory pressure.
+    pub shed_best_effort_messages: IntCounter,
     /// Backlog of XNet messages based on end in stream header and last message
     /// in slice, per subnet.
     pub xnet_message_backlog: IntGaugeVec,
@@ -69,6 +71,7 @@ const METRIC_INDUCTED_XNET_MESSAGES: &str = "mr_inducted_xnet_message_count";
 const METRIC_INDUCTED_XNET_PAYLOAD_SIZES: &str = "mr_inducted_xnet_payload_size_bytes";
 const METRIC_GCED_XNET_MESSAGES: &str = "mr_gced_xnet_message_count";
 const METRIC_GCED_XNET_REJECT_SIGNALS: &str = "mr_gced_xnet_reject_signal_count";
+const METRIC_SHED_BEST_EFFORT_MESSAGES: &str = "mr_shed_best_effort_message_count";
 
 const METRIC_XNET_MESSAGE_BACKLOG: &str = "mr_xnet_message_backlog";
 
@@ -111,6 +114,10 @@ impl StreamHandlerMetrics {
             METRIC_GCED_XNET_REJECT_SIGNALS,
             "Garbage collected XNet reject signals.",
         );
+        let shed_best_effort_messages = metrics_registry.int_counter(
+            METRIC_SHED_BEST_EFFORT_MESSAGES,
+            "Best-effort messages shed due to message memory pressure.",
+        );
         let xnet_message_backlog = metrics_registry.int_gauge_vec(
             METRIC_XNET_MESSAGE_BACKLOG,
             "Backlog of XNet messages, by sending subnet.",
@@ -150,6 +157,7 @@ impl StreamHandlerMetrics {
             inducted_xnet_payload_sizes,
             gced_xnet_messages,
             gced_xnet_reject_signals,
+            shed_best_effort_messages,
             xnet_message_backlog,
             critical_error_reject_signals_for_request,
             critical_error_induct_response_failed,
@@ -175,6 +183,7 @@ pub(crate) struct StreamHandlerImpl {
     subnet_id: SubnetId,
 
     subnet_message_memory_capacity: NumBytes,
+    canister_message_memory_capacity: NumBytes,
 
     metrics: StreamHandlerMetrics,
     /// Per-destination-subnet histogram of wall time spent by messages in the
@@ -201,6 +210,7 @@ impl StreamHandlerImpl {
         Self {
             subnet_id,
             subnet_message_memory_capacity: hypervisor_config.subnet_message_memory_capacity,
+            canister_message_memory_capacity: hypervisor_config.canister_message_memory_capacity,
             metrics: StreamHandlerMetrics::new(metrics_registry),
             time_in_stream_metrics,
             time_in_backlog_metrics: RefCell::new(LatencyMetrics::new_time_in_backlog(
@@ -248,9 +258,20 @@ impl StreamHandler for StreamHandlerImpl {
         self.observe_backlog_durations(&stream_slices);
 
         // Induct the messages in `stream_slices`, updating signals as appropriate.
-        let state = self.induct_stream_slices(state, stream_slices, &mut subnet_available_memory);
+        let mut state =
+            self.induct_stream_slices(state, stream_slices, &mut subnet_available_memory);
         debug_assert!(self.subnet_available_memory(&state) >= subnet_available_memory);
 
+        // Shed best-effort messages if over the subnet or any canister's message
+        // memory limit.
+        let shed_messages_count = state.shed_best_effort_messages(
+            self.subnet_message_memory_capacity,
+            self.canister_message_memory_capacity,
+        );
+        self.metrics
+            .shed_best_effort_messages
+            .inc_by(shed_messages_count);
+
         state
     }
 }
@@ -640,6 +661,17 @@ impl StreamHandlerImpl {
                                         .push(generate_reject_response(msg, code, err.to_string()))
                                         as i64;
                                 }
+                                // Best-effort responses may be legitimately dropped, e.g. late
+                                // responses, whose reserved slots were already consumed by
+                                // deadline expired reject responses.
+                                RequestOrResponse::Response(response)
+                                    if response.is_best_effort() =>
+                                {
+                                    debug!(
+                                        self.log,
+                                        "Dropping best-effort response: {} {:?}", err, response
+                                    );
+                                }
                                 RequestOrResponse::Response(response) => {
                                     // Critical error, responses should always be inducted successfully.
                                     error!(
@@ -875,6 +907,7 @@ fn generate_reject_response(
                 message,
                 MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN,
             )),
+            deadline: msg.deadline,
         }
         .into()
     } else {
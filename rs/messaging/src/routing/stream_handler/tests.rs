@@ -30,7 +30,10 @@ use ic_test_utilities_metrics::{
 };
 use ic_test_utilities_time::mock_time;
 use ic_types::{
-    messages::{CallbackId, Payload, Request, MAX_RESPONSE_COUNT_BYTES},
+    messages::{
+        CallbackId, CanisterMessage, Payload, Request, MAX_RESPONSE_COUNT_BYTES, NO_DEADLINE,
+    },
+    time::CoarseTime,
     xnet::{testing::StreamSliceTesting, StreamIndex, StreamIndexedQueue},
     CanisterId, CountBytes, Cycles,
 };
@@ -1373,6 +1376,7 @@ fn generate_reject_response_queue_full() {
             originator_reply_callback: msg.sender_reply_callback,
             refund: msg.payment,
             response_payload: Payload::Reject(RejectContext::new(RejectCode::SysTransient, &err)),
+            deadline: NO_DEADLINE,
         }
         .into(),
     );
@@ -1413,6 +1417,7 @@ fn generate_reject_response_canister_not_found() {
                 RejectCode::DestinationInvalid,
                 &err,
             )),
+            deadline: NO_DEADLINE,
         }
         .into(),
     );
@@ -3001,6 +3006,129 @@ fn process_stream_slices_with_invalid_messages() {
     });
 }
 
+/// Tests that `process_stream_slices()` sheds the largest best-effort messages
+/// of a canister over its message memory limit.
+#[test]
+fn process_stream_slices_sheds_best_effort_messages_over_canister_limit() {
+    with_test_replica_logger(|log| {
+        // A stream handler with a canister message memory limit that allows for
+        // 2 reservations plus change.
+        let config = HypervisorConfig {
+            canister_message_memory_capacity: NumBytes::new(
+                MAX_RESPONSE_COUNT_BYTES as u64 * 5 / 2,
+            ),
+            ..Default::default()
+        };
+        let (stream_handler, initial_state, metrics_registry) =
+            new_fixture_with_config(&log, config);
+
+        process_stream_slices_sheds_best_effort_messages_impl(
+            stream_handler,
+            initial_state,
+            metrics_registry,
+        );
+    });
+}
+
+/// Tests that `process_stream_slices()` sheds the largest best-effort messages
+/// across all canisters when over the subnet message memory limit.
+#[test]
+fn process_stream_slices_sheds_best_effort_messages_over_subnet_limit() {
+    with_test_replica_logger(|log| {
+        // A stream handler with a subnet message memory limit that allows for
+        // 2 reservations plus change.
+        let config = HypervisorConfig {
+            subnet_message_memory_capacity: NumBytes::new(MAX_RESPONSE_COUNT_BYTES as u64 * 5 / 2),
+            ..Default::default()
+        };
+        let (stream_handler, initial_state, metrics_registry) =
+            new_fixture_with_config(&log, config);
+
+        process_stream_slices_sheds_best_effort_messages_impl(
+            stream_handler,
+            initial_state,
+            metrics_registry,
+        );
+    });
+}
+
+/// Common implementation for the shedding tests above: `LOCAL_CANISTER` has
+/// one guaranteed response and three best-effort outgoing requests, i.e. 4
+/// reservations, which exceed the configured limit of 2.5 reservations. Two
+/// best-effort requests must be shed and rejected with `SYS_UNKNOWN`.
+fn process_stream_slices_sheds_best_effort_messages_impl(
+    stream_handler: StreamHandlerImpl,
+    mut initial_state: ReplicatedState,
+    metrics_registry: MetricsRegistry,
+) {
+    let mut canister = new_canister_state(
+        *LOCAL_CANISTER,
+        user_test_id(24).get(),
+        *INITIAL_CYCLES,
+        NumSeconds::from(100_000),
+    );
+    canister
+        .push_output_request(
+            test_request(*LOCAL_CANISTER, *REMOTE_CANISTER).into(),
+            mock_time(),
+        )
+        .unwrap();
+    for i in 0..3 {
+        let request = RequestBuilder::new()
+            .sender(*LOCAL_CANISTER)
+            .receiver(*REMOTE_CANISTER)
+            .sender_reply_callback(CallbackId::from(2 + i))
+            .payment(Cycles::new(13))
+            .deadline(CoarseTime::from_secs_since_unix_epoch(10))
+            .build();
+        canister
+            .push_output_request(request.into(), mock_time())
+            .unwrap();
+    }
+    initial_state.put_canister_state(canister);
+    assert_eq!(
+        NumBytes::new(4 * MAX_RESPONSE_COUNT_BYTES as u64),
+        initial_state.message_memory_taken()
+    );
+
+    let mut state = stream_handler.process_stream_slices(initial_state, Default::default());
+
+    // Two best-effort requests were shed...
+    assert_eq!(
+        Some(2),
+        fetch_int_counter(&metrics_registry, METRIC_SHED_BEST_EFFORT_MESSAGES)
+    );
+    assert!(state.message_memory_taken() <= NumBytes::new(MAX_RESPONSE_COUNT_BYTES as u64 * 5 / 2));
+
+    // ...and their callbacks rejected with `SYS_UNKNOWN`, refunding the payment.
+    let canister = state.canister_state_mut(&LOCAL_CANISTER).unwrap();
+    for _ in 0..2 {
+        match canister.pop_input() {
+            Some(CanisterMessage::Response(response)) => {
+                assert_eq!(Cycles::new(13), response.refund);
+                match &response.response_payload {
+                    Payload::Reject(context) => assert_eq!(RejectCode::SysUnknown, context.code()),
+                    payload => panic!("Expected a reject payload, got {:?}", payload),
+                }
+            }
+            msg => panic!("Expected a response, got {:?}", msg),
+        }
+    }
+    assert_eq!(None, canister.pop_input());
+
+    // The guaranteed response request and one best-effort request are left.
+    let requests: Vec<_> = canister
+        .output_into_iter()
+        .map(|(_, msg)| match msg {
+            RequestOrResponse::Request(request) => request,
+            msg => panic!("Expected a request, got {:?}", msg),
+        })
+        .collect();
+    assert_eq!(2, requests.len());
+    assert!(!requests[0].is_best_effort());
+    assert!(requests[1].is_best_effort());
+}
+
 /// Sets up the `StreamHandlerImpl`, `ReplicatedState` and `MetricsRegistry` to
 /// be used by a test.
 fn new_fixture(log: &ReplicaLogger) -> (StreamHandlerImpl, ReplicatedState, MetricsRegistry) {
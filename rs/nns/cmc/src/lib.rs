@@ -73,6 +73,34 @@ pub struct NotifyCreateCanister {
     pub settings: Option<CanisterSettingsArgs>,
 }
 
+/// Argument taken by the `create_canister_from_approval` endpoint
+#[derive(Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct CreateCanisterFromApprovalArg {
+    /// The subaccount of the caller that approved the cycles minting canister
+    /// to spend `amount` plus the ledger transfer fee.
+    pub from_subaccount: Option<icrc_ledger_types::icrc1::account::Subaccount>,
+    pub amount: Tokens,
+    /// Passed on to the ledger for deduplication, so that retrying a call
+    /// does not charge the caller twice.
+    pub created_at_time: Option<u64>,
+    pub controller: PrincipalId,
+    pub subnet_selection: Option<SubnetSelection>,
+    pub settings: Option<CanisterSettingsArgs>,
+}
+
+/// Argument taken by the `top_up_from_approval` endpoint
+#[derive(Serialize, Deserialize, CandidType, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct TopUpFromApprovalArg {
+    /// The subaccount of the caller that approved the cycles minting canister
+    /// to spend `amount` plus the ledger transfer fee.
+    pub from_subaccount: Option<icrc_ledger_types::icrc1::account::Subaccount>,
+    pub amount: Tokens,
+    /// Passed on to the ledger for deduplication, so that retrying a call
+    /// does not charge the caller twice.
+    pub created_at_time: Option<u64>,
+    pub canister_id: CanisterId,
+}
+
 /// Error for notify endpoints
 #[derive(Serialize, Deserialize, CandidType, Clone, Hash, Debug, PartialEq, Eq)]
 pub enum NotifyError {
@@ -134,6 +162,10 @@ pub enum NotifyErrorCode {
     RefundFailed = 3,
     /// The subnet selection parameters are set in an invalid way.
     BadSubnetSelection = 4,
+    /// The cycles minting canister failed to take the ICP from the caller's approval.
+    TransferFromFailed = 5,
+    /// Minting the requested amount of cycles would exceed the cycles minting limit.
+    CyclesLimitExceeded = 6,
 }
 
 impl NotifyError {
@@ -185,6 +217,20 @@ pub struct NotifyMintCyclesArg {
     pub deposit_memo: Option<Vec<u8>>,
 }
 
+/// Argument taken by the `mint_cycles_from_approval` endpoint
+#[derive(Serialize, Deserialize, CandidType, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct MintCyclesFromApprovalArg {
+    /// The subaccount of the caller that approved the cycles minting canister
+    /// to spend `amount` plus the ledger transfer fee.
+    pub from_subaccount: Option<icrc_ledger_types::icrc1::account::Subaccount>,
+    pub amount: Tokens,
+    /// Passed on to the ledger for deduplication, so that retrying a call
+    /// does not charge the caller twice.
+    pub created_at_time: Option<u64>,
+    pub to_subaccount: Option<icrc_ledger_types::icrc1::account::Subaccount>,
+    pub deposit_memo: Option<Vec<u8>>,
+}
+
 /// Result of `notify_mint_cycles` in case of success
 #[derive(Serialize, Deserialize, CandidType, Clone, Hash, Debug, PartialEq, Eq)]
 pub struct NotifyMintCyclesSuccess {
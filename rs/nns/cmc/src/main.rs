@@ -1,4 +1,4 @@
-use candid::{candid_method, CandidType, Decode, Encode};
+use candid::{candid_method, CandidType, Decode, Encode, Nat};
 use core::cmp::Ordering;
 use cycles_minting_canister::*;
 use dfn_candid::{candid_one, CandidOne};
@@ -29,9 +29,11 @@ use ic_nns_constants::{GOVERNANCE_CANISTER_ID, REGISTRY_CANISTER_ID};
 use ic_types::{CanisterId, Cycles, PrincipalId, SubnetId};
 use icp_ledger::{
     AccountIdentifier, Block, BlockIndex, BlockRes, CyclesResponse, Memo, Operation, SendArgs,
-    Subaccount, Tokens, TransactionNotification, DEFAULT_TRANSFER_FEE,
+    Subaccount, Tokens, Transaction, TransactionNotification, DEFAULT_TRANSFER_FEE,
 };
 use icrc_ledger_types::icrc1::account::Account;
+use icrc_ledger_types::icrc2::transfer_from::{TransferFromArgs, TransferFromError};
+use num_traits::ToPrimitive;
 use on_wire::{FromWire, IntoWire, NewType};
 use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use serde::{Deserialize, Serialize};
@@ -1195,6 +1197,21 @@ fn notify_mint_cycles_() {
     over_async(candid_one, notify_mint_cycles)
 }
 
+#[export_name = "canister_update create_canister_from_approval"]
+fn create_canister_from_approval_() {
+    over_async(candid_one, create_canister_from_approval)
+}
+
+#[export_name = "canister_update top_up_from_approval"]
+fn top_up_from_approval_() {
+    over_async(candid_one, top_up_from_approval)
+}
+
+#[export_name = "canister_update mint_cycles_from_approval"]
+fn mint_cycles_from_approval_() {
+    over_async(candid_one, mint_cycles_from_approval)
+}
+
 fn is_transient_error<T>(result: &Result<T, NotifyError>) -> bool {
     if let Err(e) = result {
         return e.is_retriable();
@@ -1439,6 +1456,265 @@ async fn notify_create_canister(
     }
 }
 
+/// Creates a canister paid for with ICP taken from the caller's ICRC-2
+/// approval, instead of a prior transfer followed by `notify_create_canister`.
+///
+/// The ICP are moved to the same subaccount that `notify_create_canister`
+/// uses, so a call interrupted after the transfer can also be completed
+/// by notifying the resulting block.
+#[candid_method(update, rename = "create_canister_from_approval")]
+async fn create_canister_from_approval(
+    CreateCanisterFromApprovalArg {
+        from_subaccount,
+        amount,
+        created_at_time,
+        controller,
+        subnet_selection,
+        settings,
+    }: CreateCanisterFromApprovalArg,
+) -> Result<CanisterId, NotifyError> {
+    let subnet_selection =
+        get_subnet_selection(None, subnet_selection).map_err(|error_message| {
+            NotifyError::Other {
+                error_code: NotifyErrorCode::BadSubnetSelection as u64,
+                error_message,
+            }
+        })?;
+    check_cycles_limit(tokens_to_cycles(amount)?)?;
+
+    let (block_index, from) = transfer_from_caller(
+        from_subaccount,
+        Subaccount::from(&controller),
+        amount,
+        MEMO_CREATE_CANISTER,
+        created_at_time,
+    )
+    .await?;
+
+    match start_processing(block_index)? {
+        Some(NotificationStatus::NotifiedCreateCanister(result)) => result,
+        Some(status) => Err(early_status_error(status)),
+        None => {
+            let result =
+                process_create_canister(controller, from, amount, subnet_selection, settings).await;
+            finish_processing(
+                block_index,
+                NotificationStatus::NotifiedCreateCanister(result.clone()),
+                is_transient_error(&result),
+            );
+            result
+        }
+    }
+}
+
+/// Tops up a canister with cycles paid for with ICP taken from the caller's
+/// ICRC-2 approval, instead of a prior transfer followed by `notify_top_up`.
+#[candid_method(update, rename = "top_up_from_approval")]
+async fn top_up_from_approval(
+    TopUpFromApprovalArg {
+        from_subaccount,
+        amount,
+        created_at_time,
+        canister_id,
+    }: TopUpFromApprovalArg,
+) -> Result<Cycles, NotifyError> {
+    check_cycles_limit(tokens_to_cycles(amount)?)?;
+
+    let (block_index, from) = transfer_from_caller(
+        from_subaccount,
+        Subaccount::from(&canister_id),
+        amount,
+        MEMO_TOP_UP_CANISTER,
+        created_at_time,
+    )
+    .await?;
+
+    match start_processing(block_index)? {
+        Some(NotificationStatus::NotifiedTopUp(result)) => result,
+        Some(status) => Err(early_status_error(status)),
+        None => {
+            let result = process_top_up(canister_id, from, amount).await;
+            finish_processing(
+                block_index,
+                NotificationStatus::NotifiedTopUp(result.clone()),
+                is_transient_error(&result),
+            );
+            result
+        }
+    }
+}
+
+/// Mints cycles into the caller's cycles ledger account, paid for with ICP
+/// taken from the caller's ICRC-2 approval, instead of a prior transfer
+/// followed by `notify_mint_cycles`.
+#[candid_method(update, rename = "mint_cycles_from_approval")]
+async fn mint_cycles_from_approval(
+    MintCyclesFromApprovalArg {
+        from_subaccount,
+        amount,
+        created_at_time,
+        to_subaccount,
+        deposit_memo,
+    }: MintCyclesFromApprovalArg,
+) -> NotifyMintCyclesResult {
+    if with_state(|state| state.cycles_ledger_canister_id.is_none()) {
+        return Err(NotifyError::Other {
+            error_code: NotifyErrorCode::Internal as u64,
+            error_message: "No cycles ledger canister id configured.".to_string(),
+        });
+    }
+    check_cycles_limit(tokens_to_cycles(amount)?)?;
+
+    let subaccount = Subaccount::from(&caller());
+    let to_account = Account {
+        owner: caller().into(),
+        subaccount: to_subaccount,
+    };
+
+    let (block_index, from) = transfer_from_caller(
+        from_subaccount,
+        subaccount,
+        amount,
+        MEMO_MINT_CYCLES,
+        created_at_time,
+    )
+    .await?;
+
+    match start_processing(block_index)? {
+        Some(NotificationStatus::NotifiedMint(result)) => result,
+        Some(status) => Err(early_status_error(status)),
+        None => {
+            let result =
+                process_mint_cycles(to_account, amount, deposit_memo, from, subaccount).await;
+            finish_processing(
+                block_index,
+                NotificationStatus::NotifiedMint(result.clone()),
+                is_transient_error(&result),
+            );
+            result
+        }
+    }
+}
+
+/// Moves `amount` ICP from the caller's account to the given subaccount of
+/// the cycles minting canister using the caller's ICRC-2 approval.
+///
+/// Returns the index of the ledger block and the account the ICP were taken
+/// from. If the ledger reports the transfer as a duplicate, the original
+/// block is returned so that an interrupted call can be retried.
+async fn transfer_from_caller(
+    from_subaccount: Option<icrc_ledger_types::icrc1::account::Subaccount>,
+    to_subaccount: Subaccount,
+    amount: Tokens,
+    memo: Memo,
+    created_at_time: Option<u64>,
+) -> Result<(BlockIndex, AccountIdentifier), NotifyError> {
+    let cmc_id = dfn_core::api::id();
+    let ledger_canister_id = with_state(|state| state.ledger_canister_id);
+    let from = Account {
+        owner: caller().into(),
+        subaccount: from_subaccount,
+    };
+
+    let result: Result<Result<Nat, TransferFromError>, (Option<i32>, String)> = call_with_cleanup(
+        ledger_canister_id,
+        "icrc2_transfer_from",
+        candid_one,
+        TransferFromArgs {
+            spender_subaccount: None,
+            from,
+            to: Account {
+                owner: cmc_id.get().into(),
+                subaccount: Some(to_subaccount.0),
+            },
+            amount: Nat::from(amount.get_e8s()),
+            fee: None,
+            memo: Some(icrc_ledger_types::icrc1::transfer::Memo::from(memo.0)),
+            created_at_time,
+        },
+    )
+    .await;
+
+    let block_index = match result {
+        Ok(Ok(block_index)) => block_index,
+        Ok(Err(TransferFromError::Duplicate { duplicate_of })) => duplicate_of,
+        Ok(Err(err)) => {
+            return Err(NotifyError::Other {
+                error_code: NotifyErrorCode::TransferFromFailed as u64,
+                error_message: format!("Failed to take {} from {}: {:?}", amount, from, err),
+            })
+        }
+        Err((code, err)) => {
+            return Err(NotifyError::Other {
+                error_code: NotifyErrorCode::TransferFromFailed as u64,
+                error_message: format!(
+                    "Failed to call icrc2_transfer_from on the ledger (code {}): {}",
+                    code.unwrap_or_default(),
+                    err
+                ),
+            })
+        }
+    };
+
+    let block_index = block_index.0.to_u64().ok_or_else(|| NotifyError::Other {
+        error_code: NotifyErrorCode::Internal as u64,
+        error_message: format!("Block index {} does not fit into u64", block_index),
+    })?;
+
+    Ok((block_index, AccountIdentifier::from(from)))
+}
+
+/// Marks the given block as being processed. If the block was already
+/// processed (or is being processed), its recorded status is returned instead.
+fn start_processing(block_index: BlockIndex) -> Result<Option<NotificationStatus>, NotifyError> {
+    with_state_mut(|state| {
+        state.purge_old_notifications(MAX_NOTIFY_HISTORY);
+
+        if block_index <= state.last_purged_notification {
+            return Err(NotifyError::TransactionTooOld(
+                state.last_purged_notification + 1,
+            ));
+        }
+
+        match state.blocks_notified.entry(block_index) {
+            Entry::Occupied(entry) => Ok(Some(entry.get().clone())),
+            Entry::Vacant(entry) => {
+                entry.insert(NotificationStatus::Processing);
+                Ok(None)
+            }
+        }
+    })
+}
+
+/// Records the outcome of processing the given block. Transient errors are
+/// not recorded so that processing the block can be retried.
+fn finish_processing(block_index: BlockIndex, status: NotificationStatus, is_transient: bool) {
+    with_state_mut(|state| {
+        if is_transient {
+            state.blocks_notified.remove(&block_index);
+        } else {
+            state.blocks_notified.insert(block_index, status);
+        }
+    });
+}
+
+/// The error returned when a block was already processed as a different
+/// kind of request (or is still being processed).
+fn early_status_error(status: NotificationStatus) -> NotifyError {
+    match status {
+        NotificationStatus::Processing => NotifyError::Processing,
+        NotificationStatus::NotifiedTopUp(_) => NotifyError::InvalidTransaction(
+            "The same payment is already processed as a top up request.".into(),
+        ),
+        NotificationStatus::NotifiedCreateCanister(_) => NotifyError::InvalidTransaction(
+            "The same payment is already processed as a create canister request.".into(),
+        ),
+        NotificationStatus::NotifiedMint(_) => NotifyError::InvalidTransaction(
+            "The same payment is already processed as a mint request.".into(),
+        ),
+    }
+}
+
 #[candid_method(update, rename = "create_canister")]
 #[allow(deprecated)]
 async fn create_canister(
@@ -1566,7 +1842,7 @@ async fn fetch_transaction(
         )));
     }
     let memo = block.transaction().memo;
-    if memo != expected_memo {
+    if !has_intent(&block.transaction(), expected_memo) {
         return Err(NotifyError::InvalidTransaction(format!(
             "Intent in the block ({} == {}) different than in the notification ({} == {})",
             memo.0,
@@ -1579,6 +1855,20 @@ async fn fetch_transaction(
     Ok((amount, from))
 }
 
+/// Returns whether `transaction` carries the intent `expected_memo`.
+///
+/// The intent is accepted either in the legacy `memo` field or, for transfers
+/// made through the ICRC-1 and ICRC-2 interfaces of the ledger, as the 8-byte
+/// big-endian encoding of the memo in the ICRC-1 memo. The latter lets
+/// `notify_top_up`, `notify_create_canister` and `notify_mint_cycles` process
+/// payments made with `icrc1_transfer`, as well as blocks created by the
+/// `*_from_approval` endpoints.
+fn has_intent(transaction: &Transaction, expected_memo: Memo) -> bool {
+    transaction.memo == expected_memo
+        || transaction.icrc1_memo.as_ref().map(|memo| memo.as_slice())
+            == Some(&expected_memo.0.to_be_bytes()[..])
+}
+
 /// Processes a legacy notification from the Ledger canister.
 async fn transaction_notification(tn: TransactionNotification) -> Result<CyclesResponse, String> {
     let caller = caller();
@@ -2101,21 +2391,38 @@ async fn do_create_canister(
     Err(last_err.unwrap_or_else(|| "No subnets in which to create a canister.".to_owned()))
 }
 
+fn check_cycles_limit_at(state: &mut State, now: SystemTime, cycles: Cycles) -> Result<(), String> {
+    state.limiter.purge_old(now);
+    let count = state.limiter.get_count();
+
+    if count + cycles > state.cycles_limit {
+        return Err(format!(
+            "More than {} cycles have been minted in the last {} seconds, please try again later.",
+            state.cycles_limit,
+            state.limiter.get_max_age().as_secs(),
+        ));
+    }
+    Ok(())
+}
+
+/// Fails early if minting `cycles` would currently exceed the cycles limit,
+/// before any ICP are taken from the caller. The limit is enforced again by
+/// [ensure_balance] when the cycles are actually minted.
+fn check_cycles_limit(cycles: Cycles) -> Result<(), NotifyError> {
+    let now = dfn_core::api::now();
+    with_state_mut(|state| check_cycles_limit_at(state, now, cycles)).map_err(|error_message| {
+        NotifyError::Other {
+            error_code: NotifyErrorCode::CyclesLimitExceeded as u64,
+            error_message,
+        }
+    })
+}
+
 fn ensure_balance(cycles: Cycles) -> Result<(), String> {
     let now = dfn_core::api::now();
 
     with_state_mut(|state| {
-        state.limiter.purge_old(now);
-        let count = state.limiter.get_count();
-
-        if count + cycles > state.cycles_limit {
-            return Err(format!(
-                "More than {} cycles have been minted in the last {} seconds, please try again later.",
-                state.cycles_limit,
-                state.limiter.get_max_age().as_secs(),
-            ));
-        }
-
+        check_cycles_limit_at(state, now, cycles)?;
         state.limiter.add(now, cycles);
         state.total_cycles_minted += cycles;
         Ok(())
@@ -2868,6 +3175,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_has_intent() {
+        let transaction = |memo: Memo, icrc1_memo: Option<Vec<u8>>| Transaction {
+            operation: Operation::Transfer {
+                from: AccountIdentifier::new(user_test_id(1).get(), None),
+                to: AccountIdentifier::new(user_test_id(2).get(), None),
+                amount: Tokens::from_e8s(100_000_000),
+                fee: DEFAULT_TRANSFER_FEE,
+                spender: None,
+            },
+            memo,
+            created_at_time: None,
+            icrc1_memo: icrc1_memo.map(Into::into),
+        };
+        let top_up_bytes = MEMO_TOP_UP_CANISTER.0.to_be_bytes().to_vec();
+
+        // The intent is taken from the legacy memo.
+        assert!(has_intent(
+            &transaction(MEMO_TOP_UP_CANISTER, None),
+            MEMO_TOP_UP_CANISTER
+        ));
+        assert!(!has_intent(
+            &transaction(MEMO_CREATE_CANISTER, None),
+            MEMO_TOP_UP_CANISTER
+        ));
+
+        // Transfers made through the ICRC-1 interface carry the intent in the
+        // ICRC-1 memo.
+        assert!(has_intent(
+            &transaction(Memo(0), Some(top_up_bytes.clone())),
+            MEMO_TOP_UP_CANISTER
+        ));
+        assert!(!has_intent(
+            &transaction(Memo(0), Some(top_up_bytes.clone())),
+            MEMO_CREATE_CANISTER
+        ));
+
+        // The ICRC-1 memo has to be exactly the 8-byte encoding of the intent.
+        assert!(!has_intent(
+            &transaction(Memo(0), Some(top_up_bytes[4..].to_vec())),
+            MEMO_TOP_UP_CANISTER
+        ));
+        assert!(!has_intent(
+            &transaction(Memo(0), Some(b"TPUP".to_vec())),
+            MEMO_TOP_UP_CANISTER
+        ));
+    }
+
     #[test]
     fn test_candid_interface_compatibility() {
         use candid_parser::utils::{service_equal, CandidSource};
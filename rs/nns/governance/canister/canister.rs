@@ -46,15 +46,15 @@ use ic_nns_governance::{
             ClaimOrRefresh, Command, NeuronIdOrSubaccount, RegisterVote,
         },
         manage_neuron_response, ClaimOrRefreshNeuronFromAccount,
-        ClaimOrRefreshNeuronFromAccountResponse, ExecuteNnsFunction,
-        GetNeuronsFundAuditInfoRequest, GetNeuronsFundAuditInfoResponse,
-        Governance as GovernanceProto, GovernanceError, ListKnownNeuronsResponse, ListNeurons,
-        ListNeuronsResponse, ListNodeProvidersResponse, ListProposalInfo, ListProposalInfoResponse,
-        ManageNeuron, ManageNeuronResponse, MostRecentMonthlyNodeProviderRewards, NetworkEconomics,
-        Neuron, NeuronInfo, NnsFunction, NodeProvider, Proposal, ProposalInfo, RewardEvent,
-        RewardNodeProviders, SettleCommunityFundParticipation,
-        SettleNeuronsFundParticipationRequest, SettleNeuronsFundParticipationResponse,
-        UpdateNodeProvider, Vote,
+        ClaimOrRefreshNeuronFromAccountResponse, ExecuteNnsFunction, GetNeuronVotingHistoryRequest,
+        GetNeuronVotingHistoryResponse, GetNeuronsFundAuditInfoRequest,
+        GetNeuronsFundAuditInfoResponse, Governance as GovernanceProto, GovernanceError,
+        ListKnownNeuronsResponse, ListNeurons, ListNeuronsResponse, ListNodeProvidersResponse,
+        ListProposalInfo, ListProposalInfoResponse, ManageNeuron, ManageNeuronResponse,
+        MostRecentMonthlyNodeProviderRewards, NetworkEconomics, Neuron, NeuronInfo, NnsFunction,
+        NodeProvider, Proposal, ProposalInfo, PublicNeuron, RewardEvent, RewardNodeProviders,
+        SettleCommunityFundParticipation, SettleNeuronsFundParticipationRequest,
+        SettleNeuronsFundParticipationResponse, UpdateNodeProvider, Vote,
     },
     storage::{grow_upgrades_memory_to, validate_stable_storage, with_upgrades_memory},
 };
@@ -598,6 +598,32 @@ fn get_neuron_info_by_id_or_subaccount_(
     governance().get_neuron_info_by_id_or_subaccount(&by)
 }
 
+/// Returns the controller and followees of a public neuron.
+#[export_name = "canister_query get_public_neuron"]
+fn get_public_neuron() {
+    debug_log("get_public_neuron");
+    over(candid_one, get_public_neuron_)
+}
+
+#[candid_method(query, rename = "get_public_neuron")]
+fn get_public_neuron_(neuron_id: NeuronId) -> Result<PublicNeuron, GovernanceError> {
+    governance().get_public_neuron(&NeuronIdProto::from(neuron_id), &caller())
+}
+
+/// Returns a page of the voting history of a public neuron.
+#[export_name = "canister_query get_neuron_voting_history"]
+fn get_neuron_voting_history() {
+    debug_log("get_neuron_voting_history");
+    over(candid_one, get_neuron_voting_history_)
+}
+
+#[candid_method(query, rename = "get_neuron_voting_history")]
+fn get_neuron_voting_history_(
+    request: GetNeuronVotingHistoryRequest,
+) -> Result<GetNeuronVotingHistoryResponse, GovernanceError> {
+    governance().get_neuron_voting_history(&request, &caller())
+}
+
 #[export_name = "canister_query get_proposal_info"]
 fn get_proposal_info() {
     debug_log("get_proposal_info");
@@ -243,6 +243,10 @@ pub struct Neuron {
     /// of the different states.
     #[prost(enumeration = "NeuronType", optional, tag = "22")]
     pub neuron_type: ::core::option::Option<i32>,
+    /// How much of this neuron is visible to everyone. See \[Visibility\].
+    /// Unset means private, also for known neurons.
+    #[prost(enumeration = "Visibility", optional, tag = "23")]
+    pub visibility: ::core::option::Option<i32>,
     /// At any time, at most one of `when_dissolved` and
     /// `dissolve_delay` are specified.
     ///
@@ -349,6 +353,8 @@ pub struct AbridgedNeuron {
     pub joined_community_fund_timestamp_seconds: ::core::option::Option<u64>,
     #[prost(enumeration = "NeuronType", optional, tag = "22")]
     pub neuron_type: ::core::option::Option<i32>,
+    #[prost(enumeration = "Visibility", optional, tag = "23")]
+    pub visibility: ::core::option::Option<i32>,
     #[prost(oneof = "abridged_neuron::DissolveState", tags = "9, 10")]
     pub dissolve_state: ::core::option::Option<abridged_neuron::DissolveState>,
 }
@@ -736,6 +742,14 @@ pub mod manage_neuron {
         #[prost(bool, tag = "1")]
         pub requested_setting_for_auto_stake_maturity: bool,
     }
+    /// Sets the visibility of the neuron. See \[Visibility\].
+    #[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct SetVisibility {
+        #[prost(enumeration = "super::Visibility", optional, tag = "1")]
+        pub visibility: ::core::option::Option<i32>,
+    }
     /// Commands that only configure a given neuron, but do not interact
     /// with the outside world. They all require the caller to be the
     /// controller of the neuron.
@@ -743,7 +757,7 @@ pub mod manage_neuron {
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct Configure {
-        #[prost(oneof = "configure::Operation", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9")]
+        #[prost(oneof = "configure::Operation", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10")]
         pub operation: ::core::option::Option<configure::Operation>,
     }
     /// Nested message and enum types in `Configure`.
@@ -772,6 +786,8 @@ pub mod manage_neuron {
             LeaveCommunityFund(super::LeaveCommunityFund),
             #[prost(message, tag = "9")]
             ChangeAutoStakeMaturity(super::ChangeAutoStakeMaturity),
+            #[prost(message, tag = "10")]
+            SetVisibility(super::SetVisibility),
         }
     }
     /// Disburse this neuron's stake: transfer the staked ICP to the
@@ -2699,6 +2715,52 @@ pub struct ListNeuronsResponse {
     #[prost(message, repeated, tag = "2")]
     pub full_neurons: ::prost::alloc::vec::Vec<Neuron>,
 }
+/// The information about a public neuron that is available to everyone.
+#[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PublicNeuron {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<::ic_nns_common::pb::v1::NeuronId>,
+    /// The principal that controls the neuron.
+    #[prost(message, optional, tag = "2")]
+    pub controller: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// See \[Neuron::followees\] for a description.
+    #[prost(map = "int32, message", tag = "3")]
+    pub followees: ::std::collections::HashMap<i32, neuron::Followees>,
+    /// Set if the neuron is a known neuron.
+    #[prost(message, optional, tag = "4")]
+    pub known_neuron_data: ::core::option::Option<KnownNeuronData>,
+}
+/// A request for a page of the voting history of a public neuron.
+///
+/// Only ballots cast while the neuron was public are recorded, and only
+/// the ballots on the last 10,000 proposals are kept. Ballots are
+/// returned in ascending order of proposal ID.
+#[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNeuronVotingHistoryRequest {
+    #[prost(message, optional, tag = "1")]
+    pub neuron_id: ::core::option::Option<::ic_nns_common::pb::v1::NeuronId>,
+    /// If set, only ballots on proposals with a larger ID are returned.
+    /// Set this to the last proposal ID of the previous page to get the
+    /// next page.
+    #[prost(message, optional, tag = "2")]
+    pub after_proposal: ::core::option::Option<::ic_nns_common::pb::v1::ProposalId>,
+    /// The maximum number of ballots to return. The number of ballots
+    /// returned is also capped by the governance canister.
+    #[prost(uint32, tag = "3")]
+    pub limit: u32,
+}
+/// A response to a `GetNeuronVotingHistoryRequest`.
+#[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNeuronVotingHistoryResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub ballots: ::prost::alloc::vec::Vec<BallotInfo>,
+}
 /// A response to "ListKnownNeurons"
 #[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3326,6 +3388,56 @@ impl NeuronType {
         }
     }
 }
+/// How much information about a neuron is available to principals that
+/// are neither its controller nor one of its hot keys.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    serde::Serialize,
+    comparable::Comparable,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum Visibility {
+    /// Placeholder value due to the proto3 requirement for a zero default.
+    Unspecified = 0,
+    /// Only the information in \[NeuronInfo\] is available.
+    Private = 1,
+    /// In addition, the neuron's controller, followees and the history of
+    /// its ballots (see \[PublicNeuron\] and \[GetNeuronVotingHistoryRequest\])
+    /// are available.
+    Public = 2,
+}
+impl Visibility {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Visibility::Unspecified => "VISIBILITY_UNSPECIFIED",
+            Visibility::Private => "VISIBILITY_PRIVATE",
+            Visibility::Public => "VISIBILITY_PUBLIC",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "VISIBILITY_UNSPECIFIED" => Some(Self::Unspecified),
+            "VISIBILITY_PRIVATE" => Some(Self::Private),
+            "VISIBILITY_PUBLIC" => Some(Self::Public),
+            _ => None,
+        }
+    }
+}
 /// The types of votes the Neuron can issue.
 #[derive(
     candid::CandidType,
@@ -36,6 +36,7 @@ use crate::{
         settle_neurons_fund_participation_request, settle_neurons_fund_participation_response,
         settle_neurons_fund_participation_response::NeuronsFundNeuron as NeuronsFundNeuronPb,
         swap_background_information, Ballot, CreateServiceNervousSystem, ExecuteNnsFunction,
+        GetNeuronVotingHistoryRequest, GetNeuronVotingHistoryResponse,
         GetNeuronsFundAuditInfoRequest, GetNeuronsFundAuditInfoResponse,
         Governance as GovernanceProto, GovernanceError, KnownNeuron, ListKnownNeuronsResponse,
         ListNeurons, ListNeuronsResponse, ListProposalInfo, ListProposalInfoResponse, ManageNeuron,
@@ -43,10 +44,10 @@ use crate::{
         Neuron, NeuronInfo, NeuronState, NeuronsFundAuditInfo, NeuronsFundData,
         NeuronsFundParticipation as NeuronsFundParticipationPb,
         NeuronsFundSnapshot as NeuronsFundSnapshotPb, NnsFunction, NodeProvider, Proposal,
-        ProposalData, ProposalInfo, ProposalRewardStatus, ProposalStatus, RewardEvent,
-        RewardNodeProvider, RewardNodeProviders, SettleNeuronsFundParticipationRequest,
-        SettleNeuronsFundParticipationResponse, Tally, Topic, UpdateNodeProvider, Vote,
-        WaitForQuietState,
+        ProposalData, ProposalInfo, ProposalRewardStatus, ProposalStatus, PublicNeuron,
+        RewardEvent, RewardNodeProvider, RewardNodeProviders,
+        SettleNeuronsFundParticipationRequest, SettleNeuronsFundParticipationResponse, Tally,
+        Topic, UpdateNodeProvider, Vote, WaitForQuietState,
     },
     proposals::create_service_nervous_system::ExecutedCreateServiceNervousSystemProposal,
     storage::with_stable_neuron_store,
@@ -156,6 +157,13 @@ pub const MAX_FOLLOWEES_PER_TOPIC: usize = 15;
 /// The maximum number of recent ballots to keep, per neuron.
 pub const MAX_NEURON_RECENT_BALLOTS: usize = 100;
 
+/// The voting history of a public neuron only contains its ballots on the last this many
+/// proposals.
+pub const MAX_NEURON_VOTING_HISTORY_PROPOSALS: u64 = 10_000;
+
+/// The maximum number of ballots returned by one call to `get_neuron_voting_history`.
+pub const MAX_NEURON_VOTING_HISTORY_PAGE_SIZE: usize = 1_000;
+
 /// The desired period for reward distribution events.
 ///
 /// No two consecutive reward events will happen with less then this duration in
@@ -2431,6 +2439,7 @@ impl Governance {
             known_neuron_data: None,
             spawn_at_timestamp_seconds: None,
             neuron_type: parent_neuron.neuron_type,
+            visibility: parent_neuron.visibility,
         };
 
         // Add the child neuron to the set of neurons undergoing ledger updates.
@@ -2717,6 +2726,7 @@ impl Governance {
             joined_community_fund_timestamp_seconds: None,
             known_neuron_data: None,
             neuron_type: None,
+            visibility: None,
         };
 
         // `add_neuron` will verify that `child_neuron.controller` `is_self_authenticating()`, so we don't need to check it here.
@@ -3031,6 +3041,7 @@ impl Governance {
             known_neuron_data: None,
             spawn_at_timestamp_seconds: None,
             neuron_type: None,
+            visibility: None,
         };
 
         self.add_neuron(child_nid.id, child_neuron.clone())?;
@@ -3163,6 +3174,54 @@ impl Governance {
         })
     }
 
+    /// Returns the controller, followees and known neuron data of a
+    /// neuron. Anyone can read this for public neurons; for private
+    /// neurons, only the controller and hot keys can.
+    pub fn get_public_neuron(
+        &self,
+        id: &NeuronId,
+        caller: &PrincipalId,
+    ) -> Result<PublicNeuron, GovernanceError> {
+        self.with_neuron(id, |neuron| {
+            if neuron.is_authorized_to_read_public_data(caller) {
+                Ok(neuron.get_public_neuron())
+            } else {
+                Err(GovernanceError::new_with_message(
+                    ErrorType::NotAuthorized,
+                    format!("Neuron {} is not public.", id.id),
+                ))
+            }
+        })?
+    }
+
+    /// Returns a page of the ballots that a neuron cast while it was
+    /// public. The same authorization rules as for
+    /// `get_public_neuron` apply.
+    pub fn get_neuron_voting_history(
+        &self,
+        request: &GetNeuronVotingHistoryRequest,
+        caller: &PrincipalId,
+    ) -> Result<GetNeuronVotingHistoryResponse, GovernanceError> {
+        let neuron_id = request.neuron_id.ok_or_else(|| {
+            GovernanceError::new_with_message(ErrorType::InvalidCommand, "neuron_id is required.")
+        })?;
+        let is_authorized = self.with_neuron(&neuron_id, |neuron| {
+            neuron.is_authorized_to_read_public_data(caller)
+        })?;
+        if !is_authorized {
+            return Err(GovernanceError::new_with_message(
+                ErrorType::NotAuthorized,
+                format!("Neuron {} is not public.", neuron_id.id),
+            ));
+        }
+
+        let limit = (request.limit as usize).min(MAX_NEURON_VOTING_HISTORY_PAGE_SIZE);
+        let ballots =
+            self.neuron_store
+                .list_neuron_voting_history(neuron_id, request.after_proposal, limit);
+        Ok(GetNeuronVotingHistoryResponse { ballots })
+    }
+
     /// Returns the complete neuron data for a given neuron `id` or
     /// `subaccount` after checking that the `caller` is authorized. The
     /// neuron's controller and hot keys are authorized, as are the
@@ -3813,6 +3872,7 @@ impl Governance {
                     known_neuron_data: None,
                     spawn_at_timestamp_seconds: None,
                     neuron_type: None,
+                    visibility: None,
                 };
                 self.add_neuron(nid.id, neuron)
             }
@@ -5137,9 +5197,19 @@ impl Governance {
                                 // Register the neuron's ballot in the
                                 // neuron itself.
                                 k_neuron.register_recent_ballot(topic, proposal_id, *v);
+                                k_neuron.is_public()
                             });
                         match register_ballot_result {
-                            Ok(_) => {
+                            Ok(is_public) => {
+                                // Public neurons also keep their full voting history.
+                                if is_public {
+                                    neuron_store.record_public_neuron_ballot(
+                                        *k,
+                                        topic,
+                                        *proposal_id,
+                                        *v,
+                                    );
+                                }
                                 // Only update a vote if it was previously unspecified. Following
                                 // can trigger votes for neurons that have already voted (manually)
                                 // and we don't change these votes.
@@ -5618,6 +5688,7 @@ impl Governance {
             known_neuron_data: None,
             spawn_at_timestamp_seconds: None,
             neuron_type: None,
+            visibility: None,
         };
 
         // This also verifies that there are not too many neurons already.
@@ -6,7 +6,8 @@ use crate::{
     },
     pb::v1::{
         governance_error::ErrorType, manage_neuron, neuron::DissolveState, Ballot, BallotInfo,
-        GovernanceError, Neuron, NeuronInfo, NeuronState, NeuronType, Topic, Vote,
+        GovernanceError, Neuron, NeuronInfo, NeuronState, NeuronType, PublicNeuron, Topic,
+        Visibility, Vote,
     },
 };
 #[cfg(target_arch = "wasm32")]
@@ -79,6 +80,20 @@ impl Neuron {
         self.is_hotkey_or_controller(principal)
     }
 
+    /// Returns true if and only if `principal` is authorized to read
+    /// the public part of this neuron (see [PublicNeuron]) and its
+    /// voting history, i.e., if this neuron is public or `principal` is
+    /// either the controller or one of the authorized hot keys.
+    pub(crate) fn is_authorized_to_read_public_data(&self, principal: &PrincipalId) -> bool {
+        self.is_public() || self.is_hotkey_or_controller(principal)
+    }
+
+    /// Returns true if and only if this neuron was made public with
+    /// `SetVisibility`. This also applies to known neurons.
+    pub fn is_public(&self) -> bool {
+        self.visibility == Some(Visibility::Public as i32)
+    }
+
     /// Returns true if and only if `principal` is either the controller or a hotkey
     fn is_hotkey_or_controller(&self, principal: &PrincipalId) -> bool {
         self.is_controlled_by(principal) || self.hot_keys.contains(principal)
@@ -630,6 +645,38 @@ impl Neuron {
                 }
                 Ok(())
             }
+            manage_neuron::configure::Operation::SetVisibility(set_visibility) => {
+                let visibility = match set_visibility
+                    .visibility
+                    .and_then(|visibility| Visibility::try_from(visibility).ok())
+                {
+                    Some(visibility @ (Visibility::Private | Visibility::Public)) => visibility,
+                    _ => {
+                        return Err(GovernanceError::new_with_message(
+                            ErrorType::InvalidCommand,
+                            "Operation SetVisibility requires the visibility to be either \
+                             private or public.",
+                        ))
+                    }
+                };
+                self.visibility = Some(visibility as i32);
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the information associated with this neuron that everyone
+    /// can read if the neuron is public.
+    pub fn get_public_neuron(&self) -> PublicNeuron {
+        let mut followees = self.followees.clone();
+        for topic in DEPRECATED_TOPICS {
+            followees.remove(&(topic as i32));
+        }
+        PublicNeuron {
+            id: self.id,
+            controller: self.controller,
+            followees,
+            known_neuron_data: self.known_neuron_data.clone(),
         }
     }
 
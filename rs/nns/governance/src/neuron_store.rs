@@ -3,11 +3,13 @@ use crate::{
         Environment, TimeWarp, LOG_PREFIX, MIN_DISSOLVE_DELAY_FOR_VOTE_ELIGIBILITY_SECONDS,
     },
     neuron::neuron_id_range_to_u64_range,
-    pb::v1::{governance_error::ErrorType, GovernanceError, Neuron, NeuronState, Topic},
+    pb::v1::{
+        governance_error::ErrorType, BallotInfo, GovernanceError, Neuron, NeuronState, Topic, Vote,
+    },
     storage::{
         neuron_indexes::{CorruptedNeuronIndexes, NeuronIndex},
         with_stable_neuron_indexes, with_stable_neuron_indexes_mut, with_stable_neuron_store,
-        with_stable_neuron_store_mut,
+        with_stable_neuron_store_mut, with_voting_history_store, with_voting_history_store_mut,
     },
     Clock, IcClock,
 };
@@ -19,7 +21,7 @@ use ic_nervous_system_governance::index::{
     neuron_following::{HeapNeuronFollowingIndex, NeuronFollowingIndex},
     neuron_principal::NeuronPrincipalIndex,
 };
-use ic_nns_common::pb::v1::NeuronId;
+use ic_nns_common::pb::v1::{NeuronId, ProposalId};
 use icp_ledger::{AccountIdentifier, Subaccount};
 use std::{
     borrow::Cow,
@@ -422,6 +424,9 @@ impl NeuronStore {
         }
 
         self.remove_neuron_from_indexes(&neuron_to_remove);
+        with_voting_history_store_mut(|voting_history_store| {
+            voting_history_store.delete_ballots(*neuron_id)
+        });
     }
 
     fn remove_neuron_from_indexes(&mut self, neuron: &Neuron) {
@@ -796,6 +801,37 @@ impl NeuronStore {
         (active_neurons_in_stable_store, neuron_id_for_next_batch)
     }
 
+    // Voting history of public neurons.
+
+    /// Records the ballot of a public neuron in its voting history. Like
+    /// `Neuron::register_recent_ballot`, ballots on `ExchangeRate` proposals are not recorded.
+    pub fn record_public_neuron_ballot(
+        &mut self,
+        neuron_id: NeuronId,
+        topic: Topic,
+        proposal_id: ProposalId,
+        vote: Vote,
+    ) {
+        if topic == Topic::ExchangeRate {
+            return;
+        }
+        with_voting_history_store_mut(|voting_history_store| {
+            voting_history_store.record_ballot(neuron_id, proposal_id, vote)
+        });
+    }
+
+    /// Returns a page of the voting history of the neuron. See `VotingHistoryStore::list_ballots`.
+    pub fn list_neuron_voting_history(
+        &self,
+        neuron_id: NeuronId,
+        after_proposal: Option<ProposalId>,
+        limit: usize,
+    ) -> Vec<BallotInfo> {
+        with_voting_history_store(|voting_history_store| {
+            voting_history_store.list_ballots(neuron_id, after_proposal, limit)
+        })
+    }
+
     // Census
 
     pub fn stable_neuron_store_len(&self) -> usize {
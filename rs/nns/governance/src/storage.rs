@@ -1,4 +1,7 @@
-use crate::{governance::LOG_PREFIX, pb::v1::AuditEvent};
+use crate::{
+    governance::{LOG_PREFIX, MAX_NEURON_VOTING_HISTORY_PROPOSALS},
+    pb::v1::AuditEvent,
+};
 
 #[cfg(target_arch = "wasm32")]
 use dfn_core::println;
@@ -26,8 +29,11 @@ const NEURON_FOLLOWING_INDEX_MEMORY_ID: MemoryId = MemoryId::new(11);
 const NEURON_KNOWN_NEURON_INDEX_MEMORY_ID: MemoryId = MemoryId::new(12);
 const NEURON_ACCOUNT_ID_INDEX_MEMORY_ID: MemoryId = MemoryId::new(13);
 
+const VOTING_HISTORY_MEMORY_ID: MemoryId = MemoryId::new(14);
+
 pub mod neuron_indexes;
 pub mod neurons;
+pub mod voting_history;
 
 type VM = VirtualMemory<DefaultMemoryImpl>;
 
@@ -50,6 +56,9 @@ struct State {
 
     // Neuron indexes stored in stable storage.
     stable_neuron_indexes: neuron_indexes::StableNeuronIndexes<VM>,
+
+    // Ballots cast by public neurons.
+    voting_history_store: voting_history::VotingHistoryStore<VM>,
 }
 
 impl State {
@@ -93,11 +102,19 @@ impl State {
             .build()
         });
 
+        let voting_history_store = MEMORY_MANAGER.with(|memory_manager| {
+            voting_history::VotingHistoryStore::new(
+                memory_manager.borrow().get(VOTING_HISTORY_MEMORY_ID),
+                MAX_NEURON_VOTING_HISTORY_PROPOSALS,
+            )
+        });
+
         Self {
             upgrades_memory,
             audit_events_log,
             stable_neuron_store,
             stable_neuron_indexes,
+            voting_history_store,
         }
     }
 
@@ -106,6 +123,7 @@ impl State {
     fn validate(&self) {
         self.stable_neuron_store.validate();
         self.stable_neuron_indexes.validate();
+        self.voting_history_store.validate();
     }
 }
 
@@ -159,6 +177,24 @@ pub(crate) fn with_stable_neuron_indexes_mut<R>(
     })
 }
 
+pub(crate) fn with_voting_history_store<R>(
+    f: impl FnOnce(&voting_history::VotingHistoryStore<VM>) -> R,
+) -> R {
+    STATE.with(|state| {
+        let voting_history_store = &state.borrow().voting_history_store;
+        f(voting_history_store)
+    })
+}
+
+pub(crate) fn with_voting_history_store_mut<R>(
+    f: impl FnOnce(&mut voting_history::VotingHistoryStore<VM>) -> R,
+) -> R {
+    STATE.with(|state| {
+        let voting_history_store = &mut state.borrow_mut().voting_history_store;
+        f(voting_history_store)
+    })
+}
+
 /// Validates that some of the data in stable storage can be read, in order to prevent broken
 /// schema. Should only be called in post_upgrade.
 pub fn validate_stable_storage() {
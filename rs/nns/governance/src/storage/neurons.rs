@@ -640,6 +640,7 @@ impl TryFrom<Neuron> for DecomposedNeuron {
             joined_community_fund_timestamp_seconds,
             known_neuron_data,
             neuron_type,
+            visibility,
             dissolve_state,
         } = source;
 
@@ -661,6 +662,7 @@ impl TryFrom<Neuron> for DecomposedNeuron {
             not_for_profit,
             joined_community_fund_timestamp_seconds,
             neuron_type,
+            visibility,
             dissolve_state: dissolve_state.map(AbridgedNeuronDissolveState::from),
         };
 
@@ -710,6 +712,7 @@ impl DecomposedNeuron {
             not_for_profit,
             joined_community_fund_timestamp_seconds,
             neuron_type,
+            visibility,
             dissolve_state,
         } = main;
 
@@ -734,6 +737,7 @@ impl DecomposedNeuron {
             joined_community_fund_timestamp_seconds,
             known_neuron_data,
             neuron_type,
+            visibility,
             dissolve_state: dissolve_state.map(NeuronDissolveState::from),
         }
     }
@@ -426,6 +426,7 @@ fn test_store_as_neuron_deserialized_as_abridged() {
         not_for_profit: true,
         joined_community_fund_timestamp_seconds: Some(8),
         neuron_type: Some(9),
+        visibility: Some(11),
         dissolve_state: Some(NeuronDissolveState::WhenDissolvedTimestampSeconds(10)),
         ..Default::default()
     };
@@ -453,6 +454,7 @@ fn test_store_as_neuron_deserialized_as_abridged() {
             not_for_profit: true,
             joined_community_fund_timestamp_seconds: Some(8),
             neuron_type: Some(9),
+            visibility: Some(11),
             dissolve_state: Some(AbridgedNeuronDissolveState::WhenDissolvedTimestampSeconds(
                 10
             )),
@@ -485,6 +487,7 @@ fn test_abridged_neuron_size() {
         not_for_profit: true,
         joined_community_fund_timestamp_seconds: Some(u64::MAX),
         neuron_type: Some(i32::MAX),
+        visibility: Some(i32::MAX),
         dissolve_state: Some(AbridgedNeuronDissolveState::WhenDissolvedTimestampSeconds(
             u64::MAX,
         )),
@@ -493,5 +496,5 @@ fn test_abridged_neuron_size() {
     assert!(abridged_neuron.encoded_len() as u32 <= AbridgedNeuron::BOUND.max_size());
     // This size can be updated. This assertion is created so that we are aware of the available
     // headroom.
-    assert_eq!(abridged_neuron.encoded_len(), 197);
+    assert_eq!(abridged_neuron.encoded_len(), 204);
 }
@@ -0,0 +1,186 @@
+use crate::{
+    pb::v1::{BallotInfo, Vote},
+    storage::validate_stable_btree_map,
+};
+use ic_nns_common::pb::v1::{NeuronId, ProposalId};
+use ic_stable_structures::StableBTreeMap;
+use std::ops::Bound::{Excluded, Included};
+
+/// The ballots cast by public neurons, stored in stable memory so that a much longer voting
+/// history of a public neuron is available than `Neuron::recent_ballots`, which only keeps the
+/// last `MAX_NEURON_RECENT_BALLOTS` ballots.
+///
+/// To bound the size of the voting history of each neuron, only the ballots on the last
+/// `max_proposals_per_neuron` proposals (counted from the proposal a neuron votes on) are kept.
+pub(crate) struct VotingHistoryStore<Memory>
+where
+    Memory: ic_stable_structures::Memory,
+{
+    ballots_map: StableBTreeMap<(NeuronId, /* proposal id */ u64), BallotInfo, Memory>,
+    max_proposals_per_neuron: u64,
+}
+
+impl<Memory> VotingHistoryStore<Memory>
+where
+    Memory: ic_stable_structures::Memory,
+{
+    pub fn new(memory: Memory, max_proposals_per_neuron: u64) -> Self {
+        Self {
+            ballots_map: StableBTreeMap::init(memory),
+            max_proposals_per_neuron,
+        }
+    }
+
+    /// Records the ballot of a neuron on a proposal, and removes the ballots of the neuron on
+    /// proposals that are `max_proposals_per_neuron` or more proposals older.
+    pub fn record_ballot(&mut self, neuron_id: NeuronId, proposal_id: ProposalId, vote: Vote) {
+        self.ballots_map.insert(
+            (neuron_id, proposal_id.id),
+            BallotInfo {
+                proposal_id: Some(proposal_id),
+                vote: vote as i32,
+            },
+        );
+
+        let Some(last_proposal_to_remove) =
+            proposal_id.id.checked_sub(self.max_proposals_per_neuron)
+        else {
+            return;
+        };
+        // Since this runs whenever the neuron votes, there are usually no or very few ballots to
+        // remove.
+        let keys = self
+            .ballots_map
+            .range((neuron_id, u64::MIN)..=(neuron_id, last_proposal_to_remove))
+            .map(|(key, _ballot_info)| key)
+            .collect::<Vec<_>>();
+        for key in keys {
+            self.ballots_map.remove(&key);
+        }
+    }
+
+    /// Returns at most `limit` ballots of the neuron on proposals with an id larger than
+    /// `after_proposal` (or all proposals if `after_proposal` is None), in ascending order of
+    /// proposal id.
+    pub fn list_ballots(
+        &self,
+        neuron_id: NeuronId,
+        after_proposal: Option<ProposalId>,
+        limit: usize,
+    ) -> Vec<BallotInfo> {
+        let first = match after_proposal {
+            Some(after_proposal) => Excluded((neuron_id, after_proposal.id)),
+            None => Included((neuron_id, u64::MIN)),
+        };
+        let last = Included((neuron_id, u64::MAX));
+
+        self.ballots_map
+            .range((first, last))
+            .take(limit)
+            .map(|(_key, ballot_info)| ballot_info)
+            .collect()
+    }
+
+    /// Removes the whole voting history of the neuron.
+    pub fn delete_ballots(&mut self, neuron_id: NeuronId) {
+        let keys = self
+            .ballots_map
+            .range((neuron_id, u64::MIN)..=(neuron_id, u64::MAX))
+            .map(|(key, _ballot_info)| key)
+            .collect::<Vec<_>>();
+
+        for key in keys {
+            self.ballots_map.remove(&key);
+        }
+    }
+
+    pub fn num_entries(&self) -> u64 {
+        self.ballots_map.len()
+    }
+
+    /// Validates that some of the data in stable storage can be read, in order to prevent broken
+    /// schema. Should only be called in post_upgrade.
+    pub fn validate(&self) {
+        validate_stable_btree_map(&self.ballots_map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_stable_structures::VectorMemory;
+
+    #[test]
+    fn test_list_ballots_pagination() {
+        let mut store = VotingHistoryStore::new(VectorMemory::default(), 100);
+        let neuron_id = NeuronId { id: 1 };
+        let other_neuron_id = NeuronId { id: 2 };
+        for id in 1..=5 {
+            store.record_ballot(neuron_id, ProposalId { id }, Vote::Yes);
+        }
+        store.record_ballot(other_neuron_id, ProposalId { id: 3 }, Vote::No);
+
+        let proposal_ids = |ballots: Vec<BallotInfo>| {
+            ballots
+                .into_iter()
+                .map(|ballot| ballot.proposal_id.unwrap().id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            proposal_ids(store.list_ballots(neuron_id, None, 2)),
+            vec![1, 2]
+        );
+        assert_eq!(
+            proposal_ids(store.list_ballots(neuron_id, Some(ProposalId { id: 2 }), 2)),
+            vec![3, 4]
+        );
+        assert_eq!(
+            proposal_ids(store.list_ballots(neuron_id, Some(ProposalId { id: 4 }), 2)),
+            vec![5]
+        );
+        assert_eq!(
+            store.list_ballots(other_neuron_id, None, 10),
+            vec![BallotInfo {
+                proposal_id: Some(ProposalId { id: 3 }),
+                vote: Vote::No as i32,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_delete_ballots() {
+        let mut store = VotingHistoryStore::new(VectorMemory::default(), 100);
+        let neuron_id = NeuronId { id: 1 };
+        let other_neuron_id = NeuronId { id: 2 };
+        store.record_ballot(neuron_id, ProposalId { id: 1 }, Vote::Yes);
+        store.record_ballot(neuron_id, ProposalId { id: 2 }, Vote::No);
+        store.record_ballot(other_neuron_id, ProposalId { id: 1 }, Vote::Yes);
+
+        store.delete_ballots(neuron_id);
+
+        assert_eq!(store.list_ballots(neuron_id, None, 10), vec![]);
+        assert_eq!(store.list_ballots(other_neuron_id, None, 10).len(), 1);
+        assert_eq!(store.num_entries(), 1);
+    }
+    #[test]
+    fn test_old_ballots_are_removed() {
+        let mut store = VotingHistoryStore::new(VectorMemory::default(), 3);
+        let neuron_id = NeuronId { id: 1 };
+        let other_neuron_id = NeuronId { id: 2 };
+        store.record_ballot(other_neuron_id, ProposalId { id: 1 }, Vote::Yes);
+        for id in [1, 2, 4, 5] {
+            store.record_ballot(neuron_id, ProposalId { id }, Vote::Yes);
+        }
+
+        let proposal_ids = store
+            .list_ballots(neuron_id, None, 10)
+            .into_iter()
+            .map(|ballot| ballot.proposal_id.unwrap().id)
+            .collect::<Vec<_>>();
+        assert_eq!(proposal_ids, vec![4, 5]);
+        // Only the ballots of the voting neuron are removed.
+        assert_eq!(store.list_ballots(other_neuron_id, None, 10).len(), 1);
+        assert_eq!(store.num_entries(), 3);
+    }
+}
@@ -65,7 +65,7 @@ use ic_nns_governance::{
             disburse::Amount,
             ChangeAutoStakeMaturity, ClaimOrRefresh, Command, Configure, Disburse,
             DisburseToNeuron, IncreaseDissolveDelay, JoinCommunityFund, LeaveCommunityFund,
-            MergeMaturity, NeuronIdOrSubaccount, SetDissolveTimestamp, Spawn, Split,
+            MergeMaturity, NeuronIdOrSubaccount, SetDissolveTimestamp, SetVisibility, Spawn, Split,
             StartDissolving,
         },
         manage_neuron_response::{self, Command as CommandResponse},
@@ -76,9 +76,9 @@ use ic_nns_governance::{
         settle_neurons_fund_participation_request, swap_background_information,
         AddOrRemoveNodeProvider, ApproveGenesisKyc, Ballot, BallotChange, BallotInfo,
         BallotInfoChange, CreateServiceNervousSystem, Empty, ExecuteNnsFunction,
-        Governance as GovernanceProto, GovernanceChange, GovernanceError,
-        IdealMatchedParticipationFunction, KnownNeuron, KnownNeuronData, ListNeurons,
-        ListNeuronsResponse, ListProposalInfo, ListProposalInfoResponse, ManageNeuron,
+        GetNeuronVotingHistoryRequest, Governance as GovernanceProto, GovernanceChange,
+        GovernanceError, IdealMatchedParticipationFunction, KnownNeuron, KnownNeuronData,
+        ListNeurons, ListNeuronsResponse, ListProposalInfo, ListProposalInfoResponse, ManageNeuron,
         ManageNeuronResponse, MostRecentMonthlyNodeProviderRewards, Motion, NetworkEconomics,
         Neuron, NeuronChange, NeuronState, NeuronType, NeuronsFundData, NeuronsFundParticipation,
         NeuronsFundSnapshot, NnsFunction, NodeProvider, Proposal, ProposalChange, ProposalData,
@@ -87,7 +87,7 @@ use ic_nns_governance::{
         ProposalStatus::{self, Rejected},
         RewardEvent, RewardNodeProvider, RewardNodeProviders, SetDefaultFollowees,
         SettleNeuronsFundParticipationRequest, SwapBackgroundInformation, SwapParticipationLimits,
-        Tally, TallyChange, Topic, UpdateNodeProvider, Vote, WaitForQuietState,
+        Tally, TallyChange, Topic, UpdateNodeProvider, Visibility, Vote, WaitForQuietState,
         WaitForQuietStateDesc,
     },
     proposals::create_service_nervous_system::ExecutedCreateServiceNervousSystemProposal,
@@ -11317,6 +11317,191 @@ async fn test_known_neurons() {
     assert!(gov.neuron_store.contains_known_neuron_name("Zwei"));
 }
 
+fn set_neuron_visibility(
+    gov: &mut Governance,
+    caller: &PrincipalId,
+    neuron_id: NeuronId,
+    visibility: Visibility,
+    now_seconds: u64,
+) -> Result<(), GovernanceError> {
+    gov.neuron_store
+        .with_neuron_mut(&neuron_id, |neuron| {
+            neuron.configure(
+                caller,
+                now_seconds,
+                &Configure {
+                    operation: Some(Operation::SetVisibility(SetVisibility {
+                        visibility: Some(visibility as i32),
+                    })),
+                },
+            )
+        })
+        .expect("Neuron not found")
+}
+
+/// Tests that the followees and voting history of a neuron can be read by
+/// anyone once the neuron is public, and only by its controller and hot keys
+/// while it is private.
+#[tokio::test]
+async fn test_public_neuron_voting_history() {
+    let mut driver = fake::FakeDriver::default();
+    let neurons = btreemap! {
+        1 => Neuron {
+            id: Some(NeuronId { id: 1 }),
+            account: driver.random_byte_array().to_vec(),
+            controller: Some(principal(1)),
+            cached_neuron_stake_e8s: 100_000_000,
+            dissolve_state: Some(DissolveState::DissolveDelaySeconds(MAX_DISSOLVE_DELAY_SECONDS)),
+            ..Default::default()
+        },
+        // Known neurons are private, too, unless they are made public.
+        2 => Neuron {
+            id: Some(NeuronId { id: 2 }),
+            account: driver.random_byte_array().to_vec(),
+            controller: Some(principal(2)),
+            cached_neuron_stake_e8s: 100_000_000,
+            dissolve_state: Some(DissolveState::DissolveDelaySeconds(MAX_DISSOLVE_DELAY_SECONDS)),
+            known_neuron_data: Some(KnownNeuronData {
+                name: "Zwei".to_string(),
+                description: None,
+            }),
+            ..Default::default()
+        },
+    };
+    let governance_proto = GovernanceProto {
+        economics: Some(NetworkEconomics::with_default_values()),
+        neurons,
+        ..Default::default()
+    };
+    let mut gov = Governance::new(
+        governance_proto,
+        driver.get_fake_env(),
+        driver.get_fake_ledger(),
+        driver.get_fake_cmc(),
+    );
+    let stranger = principal(42);
+    let now = driver.now();
+
+    // Only the controller can change the visibility of a neuron.
+    assert_matches!(
+        set_neuron_visibility(&mut gov, &stranger, NeuronId { id: 1 }, Visibility::Public, now),
+        Err(GovernanceError { error_type, .. }) if error_type == NotAuthorized as i32
+    );
+    set_neuron_visibility(
+        &mut gov,
+        &principal(1),
+        NeuronId { id: 1 },
+        Visibility::Public,
+        now,
+    )
+    .unwrap();
+
+    let make_motion = |gov: &mut Governance, id: u64| {
+        gov.make_proposal(
+            &NeuronId { id },
+            &principal(id),
+            &Proposal {
+                title: Some("A Reasonable Title".to_string()),
+                summary: format!("Motion from neuron {}", id),
+                action: Some(proposal::Action::Motion(Motion {
+                    motion_text: "Me like proposals.".to_string(),
+                })),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    };
+    let first_proposal_id = make_motion(&mut gov, 1);
+    let second_proposal_id = make_motion(&mut gov, 2);
+    fake::register_vote_assert_success(
+        &mut gov,
+        principal(1),
+        NeuronId { id: 1 },
+        second_proposal_id,
+        Vote::No,
+    );
+    fake::register_vote_assert_success(
+        &mut gov,
+        principal(2),
+        NeuronId { id: 2 },
+        first_proposal_id,
+        Vote::Yes,
+    );
+
+    // The public neuron can be read by anyone.
+    let public_neuron = gov
+        .get_public_neuron(&NeuronId { id: 1 }, &stranger)
+        .unwrap();
+    assert_eq!(public_neuron.id, Some(NeuronId { id: 1 }));
+    assert_eq!(public_neuron.controller, Some(principal(1)));
+
+    let history_request =
+        |neuron_id: u64, after_proposal: Option<ProposalId>| GetNeuronVotingHistoryRequest {
+            neuron_id: Some(NeuronId { id: neuron_id }),
+            after_proposal,
+            limit: 1,
+        };
+    let first_page = gov
+        .get_neuron_voting_history(&history_request(1, None), &stranger)
+        .unwrap();
+    assert_eq!(
+        first_page.ballots,
+        vec![BallotInfo {
+            proposal_id: Some(first_proposal_id),
+            vote: Vote::Yes as i32,
+        }]
+    );
+    let second_page = gov
+        .get_neuron_voting_history(&history_request(1, Some(first_proposal_id)), &stranger)
+        .unwrap();
+    assert_eq!(
+        second_page.ballots,
+        vec![BallotInfo {
+            proposal_id: Some(second_proposal_id),
+            vote: Vote::No as i32,
+        }]
+    );
+    let last_page = gov
+        .get_neuron_voting_history(&history_request(1, Some(second_proposal_id)), &stranger)
+        .unwrap();
+    assert_eq!(last_page.ballots, vec![]);
+
+    // The private (known) neuron can only be read by its controller, and its
+    // ballots are not recorded.
+    assert_matches!(
+        gov.get_public_neuron(&NeuronId { id: 2 }, &stranger),
+        Err(GovernanceError { error_type, .. }) if error_type == NotAuthorized as i32
+    );
+    assert_matches!(
+        gov.get_neuron_voting_history(&history_request(2, None), &stranger),
+        Err(GovernanceError { error_type, .. }) if error_type == NotAuthorized as i32
+    );
+    assert_eq!(
+        gov.get_neuron_voting_history(&history_request(2, None), &principal(2))
+            .unwrap()
+            .ballots,
+        vec![]
+    );
+
+    // Making the neuron private again hides it from everyone but its
+    // controller and hot keys.
+    set_neuron_visibility(
+        &mut gov,
+        &principal(1),
+        NeuronId { id: 1 },
+        Visibility::Private,
+        now,
+    )
+    .unwrap();
+    assert_matches!(
+        gov.get_public_neuron(&NeuronId { id: 1 }, &stranger),
+        Err(GovernanceError { error_type, .. }) if error_type == NotAuthorized as i32
+    );
+    assert!(gov
+        .get_public_neuron(&NeuronId { id: 1 }, &principal(1))
+        .is_ok());
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct ExpectedCallCanisterMethodCallArguments<'a> {
     target: CanisterId,
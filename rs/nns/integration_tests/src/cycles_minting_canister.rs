@@ -2,9 +2,11 @@ use candid::{Decode, Encode};
 use canister_test::Canister;
 use cycles_minting_canister::{
     ChangeSubnetTypeAssignmentArgs, CreateCanister, CreateCanisterError,
-    IcpXdrConversionRateCertifiedResponse, NotifyCreateCanister, NotifyError, NotifyMintCyclesArg,
-    NotifyMintCyclesSuccess, SubnetListWithType, SubnetTypesToSubnetsResponse,
-    UpdateSubnetTypeArgs, BAD_REQUEST_CYCLES_PENALTY, CYCLES_LEDGER_CANISTER_ID,
+    CreateCanisterFromApprovalArg, IcpXdrConversionRateCertifiedResponse,
+    MintCyclesFromApprovalArg, NotifyCreateCanister, NotifyError, NotifyErrorCode,
+    NotifyMintCyclesArg, NotifyMintCyclesSuccess, SubnetFilter, SubnetListWithType,
+    SubnetSelection, SubnetTypesToSubnetsResponse, TopUpFromApprovalArg, UpdateSubnetTypeArgs,
+    BAD_REQUEST_CYCLES_PENALTY, CREATE_CANISTER_REFUND_FEE, CYCLES_LEDGER_CANISTER_ID,
     MEMO_CREATE_CANISTER, MEMO_MINT_CYCLES, MEMO_TOP_UP_CANISTER,
 };
 use dfn_candid::candid_one;
@@ -30,13 +32,13 @@ use ic_nns_test_utils::{
     itest_helpers::{local_test_on_nns_subnet, NnsCanisters},
     neuron_helpers::get_neuron_1,
     state_test_helpers::{
-        cmc_set_default_authorized_subnetworks, set_up_universal_canister, setup_cycles_ledger,
-        setup_nns_canisters, update_with_sender,
+        cmc_set_default_authorized_subnetworks, icrc1_balance, set_up_universal_canister,
+        setup_cycles_ledger, setup_nns_canisters, update_with_sender,
     },
 };
 use ic_state_machine_tests::{StateMachine, WasmResult};
 use ic_test_utilities::universal_canister::{call_args, wasm};
-use ic_types::{CanisterId, PrincipalId};
+use ic_types::{CanisterId, Cycles, PrincipalId};
 use ic_types_test_utils::ids::subnet_test_id;
 use icp_ledger::{
     tokens_from_proto, AccountBalanceArgs, AccountIdentifier, BlockIndex, CyclesResponse, Memo,
@@ -44,6 +46,8 @@ use icp_ledger::{
     DEFAULT_TRANSFER_FEE,
 };
 use icrc_ledger_types::icrc1::account::Account;
+use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use std::time::UNIX_EPOCH;
 
 /// Test that the CMC's `icp_xdr_conversion_rate` can be updated via Governance
 /// proposal.
@@ -749,6 +753,127 @@ fn notify_mint_cycles(
     }
 }
 
+/// Approves the CMC to spend `amount` ICP from `TEST_USER1_PRINCIPAL`s ledger account.
+fn approve_cmc(state_machine: &StateMachine, amount: Tokens) {
+    let ledger = CanisterId::from_u64(LEDGER_CANISTER_INDEX_IN_NNS_SUBNET);
+    let approve_args = ApproveArgs {
+        from_subaccount: None,
+        spender: Account {
+            owner: CYCLES_MINTING_CANISTER_ID.get().into(),
+            subaccount: None,
+        },
+        amount: amount.get_e8s().into(),
+        expected_allowance: None,
+        expires_at: None,
+        fee: None,
+        memo: None,
+        created_at_time: None,
+    };
+    let WasmResult::Reply(res) = state_machine
+        .execute_ingress_as(
+            *TEST_USER1_PRINCIPAL,
+            ledger,
+            "icrc2_approve",
+            Encode!(&approve_args).unwrap(),
+        )
+        .unwrap()
+    else {
+        panic!("icrc2_approve rejected")
+    };
+    Decode!(&res, Result<candid::Nat, ApproveError>)
+        .unwrap()
+        .expect("icrc2_approve failed");
+}
+
+fn mint_cycles_from_approval(
+    state_machine: &StateMachine,
+    arg: &MintCyclesFromApprovalArg,
+) -> Result<NotifyMintCyclesSuccess, NotifyError> {
+    let WasmResult::Reply(res) = state_machine
+        .execute_ingress_as(
+            *TEST_USER1_PRINCIPAL,
+            CYCLES_MINTING_CANISTER_ID,
+            "mint_cycles_from_approval",
+            Encode!(arg).unwrap(),
+        )
+        .unwrap()
+    else {
+        panic!("mint_cycles_from_approval rejected")
+    };
+    Decode!(&res, Result<NotifyMintCyclesSuccess, NotifyError>).unwrap()
+}
+
+fn create_canister_from_approval(
+    state_machine: &StateMachine,
+    arg: &CreateCanisterFromApprovalArg,
+) -> Result<CanisterId, NotifyError> {
+    let WasmResult::Reply(res) = state_machine
+        .execute_ingress_as(
+            *TEST_USER1_PRINCIPAL,
+            CYCLES_MINTING_CANISTER_ID,
+            "create_canister_from_approval",
+            Encode!(arg).unwrap(),
+        )
+        .unwrap()
+    else {
+        panic!("create_canister_from_approval rejected")
+    };
+    Decode!(&res, Result<CanisterId, NotifyError>).unwrap()
+}
+
+fn top_up_from_approval(
+    state_machine: &StateMachine,
+    arg: &TopUpFromApprovalArg,
+) -> Result<Cycles, NotifyError> {
+    let WasmResult::Reply(res) = state_machine
+        .execute_ingress_as(
+            *TEST_USER1_PRINCIPAL,
+            CYCLES_MINTING_CANISTER_ID,
+            "top_up_from_approval",
+            Encode!(arg).unwrap(),
+        )
+        .unwrap()
+    else {
+        panic!("top_up_from_approval rejected")
+    };
+    Decode!(&res, Result<Cycles, NotifyError>).unwrap()
+}
+
+fn icp_balance_of_user1(state_machine: &StateMachine) -> Tokens {
+    icrc1_balance(
+        state_machine,
+        CanisterId::from_u64(LEDGER_CANISTER_INDEX_IN_NNS_SUBNET),
+        Account {
+            owner: (*TEST_USER1_PRINCIPAL).into(),
+            subaccount: None,
+        },
+    )
+}
+
+/// Sets up the NNS canisters and the cycles ledger, with `icpts` ICP in
+/// `TEST_USER1_PRINCIPAL`s ledger account and the state machine's subnet
+/// authorized for canister creation.
+fn setup_cmc_from_approval_test(icpts: Tokens) -> StateMachine {
+    let account = AccountIdentifier::new(*TEST_USER1_PRINCIPAL, None);
+    let neuron = get_neuron_1();
+
+    let mut state_machine = StateMachine::new();
+    let nns_init_payloads = NnsInitPayloadsBuilder::new()
+        .with_test_neurons()
+        .with_ledger_account(account, icpts)
+        .build();
+    setup_nns_canisters(&state_machine, nns_init_payloads);
+    setup_cycles_ledger(&state_machine);
+    let subnet_id = state_machine.get_subnet_id();
+    cmc_set_default_authorized_subnetworks(
+        &mut state_machine,
+        vec![subnet_id],
+        neuron.principal_id,
+        neuron.neuron_id,
+    );
+    state_machine
+}
+
 fn cycles_ledger_balance_of(state_machine: &StateMachine, account: Account) -> u128 {
     if let WasmResult::Reply(res) = state_machine
         .execute_ingress(
@@ -1098,3 +1223,199 @@ fn cmc_notify_mint_cycles() {
     assert_eq!(minted, minted_duplicate);
     assert_eq!(balance, balance_duplicate);
 }
+
+#[test]
+fn cmc_mint_cycles_and_create_canister_from_approval() {
+    let main_account = Account {
+        owner: (*TEST_USER1_PRINCIPAL).into(),
+        subaccount: None,
+    };
+    let state_machine = setup_cmc_from_approval_test(Tokens::new(100, 0).unwrap());
+
+    let created_at_time = Some(
+        state_machine
+            .time()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64,
+    );
+    let mint_arg = MintCyclesFromApprovalArg {
+        from_subaccount: None,
+        amount: Tokens::new(1, 0).unwrap(),
+        created_at_time,
+        to_subaccount: None,
+        deposit_memo: None,
+    };
+
+    // Without an approval the CMC cannot take the ICP.
+    let NotifyError::Other { error_code, .. } =
+        mint_cycles_from_approval(&state_machine, &mint_arg).unwrap_err()
+    else {
+        panic!("Expected the transfer from the caller to fail.")
+    };
+    assert_eq!(error_code, NotifyErrorCode::TransferFromFailed as u64);
+    assert_eq!(cycles_ledger_balance_of(&state_machine, main_account), 0);
+
+    approve_cmc(&state_machine, Tokens::new(10, 0).unwrap());
+
+    let minted = mint_cycles_from_approval(&state_machine, &mint_arg).unwrap();
+    assert_eq!(minted.minted, candid::Nat::from(100_000_000_000_000_u128));
+    assert_eq!(
+        cycles_ledger_balance_of(&state_machine, main_account),
+        100_000_000_000_000
+    );
+
+    // Retrying the same call returns the original result without taking the ICP again.
+    assert_eq!(
+        mint_cycles_from_approval(&state_machine, &mint_arg).unwrap(),
+        minted
+    );
+    assert_eq!(
+        cycles_ledger_balance_of(&state_machine, main_account),
+        100_000_000_000_000
+    );
+
+    let canister_id = create_canister_from_approval(
+        &state_machine,
+        &CreateCanisterFromApprovalArg {
+            from_subaccount: None,
+            amount: Tokens::new(2, 0).unwrap(),
+            created_at_time: None,
+            controller: *TEST_USER1_PRINCIPAL,
+            subnet_selection: None,
+            settings: None,
+        },
+    )
+    .unwrap();
+    let status = canister_status(&state_machine, *TEST_USER1_PRINCIPAL, canister_id).unwrap();
+    assert_eq!(status.controllers(), vec![*TEST_USER1_PRINCIPAL]);
+
+    let balance_before_top_up = icp_balance_of_user1(&state_machine);
+    let topped_up = top_up_from_approval(
+        &state_machine,
+        &TopUpFromApprovalArg {
+            from_subaccount: None,
+            amount: Tokens::new(1, 0).unwrap(),
+            created_at_time: None,
+            canister_id,
+        },
+    )
+    .unwrap();
+    assert_eq!(topped_up, Cycles::new(100_000_000_000_000));
+    let topped_up_status =
+        canister_status(&state_machine, *TEST_USER1_PRINCIPAL, canister_id).unwrap();
+    assert!(topped_up_status.cycles() >= status.cycles() + 99_000_000_000_000);
+    assert_eq!(
+        icp_balance_of_user1(&state_machine),
+        balance_before_top_up
+            .checked_sub(&Tokens::new(1, 0).unwrap())
+            .and_then(|balance| balance.checked_sub(&DEFAULT_TRANSFER_FEE))
+            .unwrap()
+    );
+}
+
+#[test]
+fn cmc_create_canister_from_approval_refunds_failed_creation() {
+    let state_machine = setup_cmc_from_approval_test(Tokens::new(100, 0).unwrap());
+    approve_cmc(&state_machine, Tokens::new(10, 0).unwrap());
+    let balance_before = icp_balance_of_user1(&state_machine);
+
+    // The subnet type is only resolved once the ICP have been taken, so the
+    // failed creation has to refund them.
+    let error = create_canister_from_approval(
+        &state_machine,
+        &CreateCanisterFromApprovalArg {
+            from_subaccount: None,
+            amount: Tokens::new(2, 0).unwrap(),
+            created_at_time: None,
+            controller: *TEST_USER1_PRINCIPAL,
+            subnet_selection: Some(SubnetSelection::Filter(SubnetFilter {
+                subnet_type: Some("fake_subnet_type".to_string()),
+            })),
+            settings: None,
+        },
+    )
+    .unwrap_err();
+    let NotifyError::Refunded {
+        reason,
+        block_index,
+    } = error
+    else {
+        panic!(
+            "Expected the failed creation to be refunded, got {:?}",
+            error
+        )
+    };
+    assert!(reason.contains("fake_subnet_type does not exist"));
+    assert!(block_index.is_some());
+
+    // The caller pays the fee of the transfer from their account, the fee of
+    // the refund and the refund fee of the cycles minting canister.
+    assert_eq!(
+        icp_balance_of_user1(&state_machine),
+        balance_before
+            .checked_sub(&DEFAULT_TRANSFER_FEE)
+            .and_then(|balance| balance.checked_sub(&DEFAULT_TRANSFER_FEE))
+            .and_then(|balance| balance.checked_sub(&CREATE_CANISTER_REFUND_FEE))
+            .unwrap()
+    );
+}
+
+#[test]
+fn cmc_from_approval_rejects_amounts_exceeding_cycles_limit() {
+    let state_machine = setup_cmc_from_approval_test(Tokens::new(100, 0).unwrap());
+    approve_cmc(&state_machine, Tokens::new(10, 0).unwrap());
+    let balance_before = icp_balance_of_user1(&state_machine);
+    // 1000 ICP are worth more cycles than the default limit of 50 Pcycles per hour.
+    let amount = Tokens::new(1_000, 0).unwrap();
+
+    let assert_cycles_limit_exceeded = |result: NotifyError| {
+        let NotifyError::Other { error_code, .. } = result else {
+            panic!("Expected the cycles limit to be exceeded, got {:?}", result)
+        };
+        assert_eq!(error_code, NotifyErrorCode::CyclesLimitExceeded as u64);
+    };
+
+    assert_cycles_limit_exceeded(
+        mint_cycles_from_approval(
+            &state_machine,
+            &MintCyclesFromApprovalArg {
+                from_subaccount: None,
+                amount,
+                created_at_time: None,
+                to_subaccount: None,
+                deposit_memo: None,
+            },
+        )
+        .unwrap_err(),
+    );
+    assert_cycles_limit_exceeded(
+        create_canister_from_approval(
+            &state_machine,
+            &CreateCanisterFromApprovalArg {
+                from_subaccount: None,
+                amount,
+                created_at_time: None,
+                controller: *TEST_USER1_PRINCIPAL,
+                subnet_selection: None,
+                settings: None,
+            },
+        )
+        .unwrap_err(),
+    );
+    assert_cycles_limit_exceeded(
+        top_up_from_approval(
+            &state_machine,
+            &TopUpFromApprovalArg {
+                from_subaccount: None,
+                amount,
+                created_at_time: None,
+                canister_id: CYCLES_MINTING_CANISTER_ID,
+            },
+        )
+        .unwrap_err(),
+    );
+
+    // The calls are rejected before any ICP are taken from the caller.
+    assert_eq!(icp_balance_of_user1(&state_machine), balance_before);
+}
@@ -1,4 +1,6 @@
-use crate::state_api::state::{HasStateLabel, OpOut, PocketIcError, StateLabel};
+use crate::state_api::state::{
+    to_canister_result, HasStateLabel, OpOut, PocketIcError, StateLabel,
+};
 use crate::OpId;
 use crate::Operation;
 use crate::{copy_dir, BlobStore};
@@ -6,21 +8,25 @@ use ic_config::execution_environment;
 use ic_config::subnet_config::SubnetConfig;
 use ic_crypto_sha2::Sha256;
 use ic_crypto_utils_threshold_sig_der::threshold_sig_public_key_to_der;
-use ic_ic00_types::CanisterInstallMode;
+use ic_ic00_types::{CanisterInstallMode, Payload, TransformArgs};
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
 use ic_registry_routing_table::{CanisterIdRange, RoutingTable, CANISTER_IDS_PER_SUBNET};
 use ic_registry_subnet_type::SubnetType;
 use ic_state_machine_tests::{
-    EcdsaCurve, EcdsaKeyId, IngressState, IngressStatus, StateMachine, StateMachineBuilder,
-    StateMachineConfig, SubmitIngressError, Time,
+    CanisterHttpRequestContext, CanisterHttpResponsePayload, EcdsaCurve, EcdsaKeyId, HttpHeader,
+    IngressState, IngressStatus, PayloadBuilder, RejectCode, StateMachine, StateMachineBuilder,
+    StateMachineConfig, SubmitIngressError, Time, WasmResult,
 };
 use ic_test_utilities::types::ids::subnet_test_id;
-use ic_types::messages::CertificateDelegation;
+use ic_types::canister_http::{CanisterHttpMethod, MAX_CANISTER_HTTP_RESPONSE_BYTES};
+use ic_types::messages::{CallbackId, CertificateDelegation};
 use ic_types::{CanisterId, PrincipalId, SubnetId};
 use itertools::Itertools;
 use pocket_ic::common::rest::{
-    self, BinaryBlob, BlobCompression, ExtendedSubnetConfigSet, RawAddCycles, RawCanisterCall,
-    RawEffectivePrincipal, RawSetStableMemory, SubnetKind, SubnetSpec, Topology,
+    self, BinaryBlob, BlobCompression, CanisterHttpHeader, CanisterHttpReject, CanisterHttpReply,
+    CanisterHttpResponse, ExtendedSubnetConfigSet, RawAddCycles, RawCanisterCall,
+    RawCanisterHttpRequest, RawEffectivePrincipal, RawImportCanister, RawMessageId,
+    RawMockCanisterHttpResponse, RawSetStableMemory, SubnetKind, SubnetSpec, Topology,
 };
 use rand::rngs::StdRng;
 use rand::Rng;
@@ -551,36 +557,7 @@ impl Operation for ExecuteIngressMessage {
                         eprintln!("Failed to submit ingress message: {:?}", e);
                         Err::<ic_state_machine_tests::WasmResult, ic_state_machine_tests::UserError>(e).into()
                     }
-                    Ok(msg_id) => {
-                        // Now, we execute on all subnets until we have the result
-                        let max_rounds = 100;
-                        for _i in 0..max_rounds {
-                            for subnet_ in pic.subnets.read().unwrap().values() {
-                                subnet_.execute_round();
-                            }
-                            match subnet.ingress_status(&msg_id) {
-                                IngressStatus::Known {
-                                    state: IngressState::Completed(result),
-                                    ..
-                                } => return Ok(result).into(),
-                                IngressStatus::Known {
-                                    state: IngressState::Failed(error),
-                                    ..
-                                } => {
-                                    return Err::<
-                                        ic_state_machine_tests::WasmResult,
-                                        ic_state_machine_tests::UserError,
-                                    >(error)
-                                    .into()
-                                }
-                                _ => {}
-                            }
-                        }
-                        panic!(
-                            "Failed to answer to ingress {} after {} xnet rounds.",
-                            msg_id, max_rounds
-                        );
-                    }
+                    Ok(msg_id) => await_ingress_message(pic, &subnet, &msg_id),
                 }
             }
             Err(e) => OpOut::Error(PocketIcError::BadIngressMessage(e)),
@@ -593,6 +570,141 @@ impl Operation for ExecuteIngressMessage {
     }
 }
 
+/// Submits an ingress message without executing it. The returned message ID
+/// can be used to await the result (`AwaitIngressMessage`) or to poll its
+/// status (`IngressMessageStatus`).
+#[derive(Clone, Debug)]
+pub struct SubmitIngressMessage(pub CanisterCall);
+
+impl Operation for SubmitIngressMessage {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let canister_call = self.0.clone();
+        let subnet = route_call(pic, canister_call);
+        match subnet {
+            Ok(subnet) => {
+                match subnet.submit_ingress_as(
+                    self.0.sender,
+                    self.0.canister_id,
+                    self.0.method,
+                    self.0.payload,
+                ) {
+                    Err(SubmitIngressError::HttpError(e)) => {
+                        eprintln!("Failed to submit ingress message: {}", e);
+                        OpOut::Error(PocketIcError::BadIngressMessage(e))
+                    }
+                    Err(ic_state_machine_tests::SubmitIngressError::UserError(e)) => {
+                        eprintln!("Failed to submit ingress message: {:?}", e);
+                        Err::<ic_state_machine_tests::WasmResult, ic_state_machine_tests::UserError>(e).into()
+                    }
+                    // The message ID is only meaningful on the subnet the message was
+                    // submitted to, so we return that subnet as the effective principal.
+                    Ok(msg_id) => OpOut::MessageId((
+                        EffectivePrincipal::SubnetId(subnet.get_subnet_id()),
+                        msg_id.as_bytes().to_vec(),
+                    )),
+                }
+            }
+            Err(e) => OpOut::Error(PocketIcError::BadIngressMessage(e)),
+        }
+    }
+
+    fn id(&self) -> OpId {
+        let call_id = self.0.id();
+        OpId(format!("submit_update_{}", call_id.0))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MessageId {
+    pub effective_principal: EffectivePrincipal,
+    pub msg_id: ic_types::messages::MessageId,
+}
+
+impl TryFrom<RawMessageId> for MessageId {
+    type Error = ConversionError;
+    fn try_from(
+        RawMessageId {
+            effective_principal,
+            message_id,
+        }: RawMessageId,
+    ) -> Result<Self, Self::Error> {
+        let effective_principal = effective_principal.try_into()?;
+        let msg_id = match ic_types::messages::MessageId::try_from(message_id.as_slice()) {
+            Ok(msg_id) => msg_id,
+            Err(_) => {
+                return Err(ConversionError {
+                    message: "Bad message id".to_string(),
+                })
+            }
+        };
+        Ok(MessageId {
+            effective_principal,
+            msg_id,
+        })
+    }
+}
+
+/// Executes rounds until a previously submitted ingress message completes
+/// and returns its result.
+#[derive(Clone, Debug)]
+pub struct AwaitIngressMessage(pub MessageId);
+
+impl Operation for AwaitIngressMessage {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        match route_message_id(pic, &self.0) {
+            Ok(subnet) => await_ingress_message(pic, &subnet, &self.0.msg_id),
+            Err(e) => OpOut::Error(PocketIcError::BadIngressMessage(e)),
+        }
+    }
+
+    fn id(&self) -> OpId {
+        OpId(format!(
+            "await_update_{:?}_{}",
+            self.0.effective_principal, self.0.msg_id
+        ))
+    }
+}
+
+/// Returns the result of a previously submitted ingress message if it has
+/// already completed, without executing any rounds.
+#[derive(Clone, Debug)]
+pub struct IngressMessageStatus(pub MessageId);
+
+impl Operation for IngressMessageStatus {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        match route_message_id(pic, &self.0) {
+            Ok(subnet) => OpOut::IngressStatus(match subnet.ingress_status(&self.0.msg_id) {
+                IngressStatus::Known { state, .. } => match state {
+                    IngressState::Received => pocket_ic::IngressStatus::Received,
+                    IngressState::Processing => pocket_ic::IngressStatus::Processing,
+                    IngressState::Completed(result) => {
+                        pocket_ic::IngressStatus::Completed(to_canister_result(Ok(result)))
+                    }
+                    IngressState::Failed(error) => {
+                        pocket_ic::IngressStatus::Completed(to_canister_result(Err(error)))
+                    }
+                    IngressState::Done => pocket_ic::IngressStatus::Done,
+                },
+                IngressStatus::Unknown => pocket_ic::IngressStatus::Unknown,
+            }),
+            Err(e) => OpOut::Error(PocketIcError::BadIngressMessage(e)),
+        }
+    }
+
+    fn id(&self) -> OpId {
+        OpId(format!(
+            "ingress_status_{:?}_{}",
+            self.0.effective_principal, self.0.msg_id
+        ))
+    }
+}
+
 pub struct Query(pub CanisterCall);
 
 impl Operation for Query {
@@ -623,13 +735,53 @@ impl Operation for Query {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum EffectivePrincipal {
     None,
     SubnetId(SubnetId),
     CanisterId(CanisterId),
 }
 
+impl TryFrom<RawEffectivePrincipal> for EffectivePrincipal {
+    type Error = ConversionError;
+    fn try_from(raw: RawEffectivePrincipal) -> Result<Self, Self::Error> {
+        match raw {
+            RawEffectivePrincipal::SubnetId(subnet_id) => {
+                let sid = PrincipalId::try_from(subnet_id);
+                match sid {
+                    Ok(sid) => Ok(EffectivePrincipal::SubnetId(SubnetId::new(sid))),
+                    Err(_) => Err(ConversionError {
+                        message: "Bad subnet id".to_string(),
+                    }),
+                }
+            }
+            RawEffectivePrincipal::CanisterId(canister_id) => {
+                match CanisterId::try_from(canister_id) {
+                    Ok(canister_id) => Ok(EffectivePrincipal::CanisterId(canister_id)),
+                    Err(_) => Err(ConversionError {
+                        message: "Bad effective canister id".to_string(),
+                    }),
+                }
+            }
+            RawEffectivePrincipal::None => Ok(EffectivePrincipal::None),
+        }
+    }
+}
+
+impl From<EffectivePrincipal> for RawEffectivePrincipal {
+    fn from(effective_principal: EffectivePrincipal) -> Self {
+        match effective_principal {
+            EffectivePrincipal::SubnetId(subnet_id) => {
+                RawEffectivePrincipal::SubnetId(subnet_id.get().to_vec())
+            }
+            EffectivePrincipal::CanisterId(canister_id) => {
+                RawEffectivePrincipal::CanisterId(canister_id.get().to_vec())
+            }
+            EffectivePrincipal::None => RawEffectivePrincipal::None,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CanisterCall {
     pub effective_principal: EffectivePrincipal,
@@ -650,30 +802,7 @@ impl TryFrom<RawCanisterCall> for CanisterCall {
             effective_principal,
         }: RawCanisterCall,
     ) -> Result<Self, Self::Error> {
-        let effective_principal = match effective_principal {
-            RawEffectivePrincipal::SubnetId(subnet_id) => {
-                let sid = PrincipalId::try_from(subnet_id);
-                match sid {
-                    Ok(sid) => EffectivePrincipal::SubnetId(SubnetId::new(sid)),
-                    Err(_) => {
-                        return Err(ConversionError {
-                            message: "Bad subnet id".to_string(),
-                        })
-                    }
-                }
-            }
-            RawEffectivePrincipal::CanisterId(canister_id) => {
-                match CanisterId::try_from(canister_id) {
-                    Ok(canister_id) => EffectivePrincipal::CanisterId(canister_id),
-                    Err(_) => {
-                        return Err(ConversionError {
-                            message: "Bad effective canister id".to_string(),
-                        })
-                    }
-                }
-            }
-            RawEffectivePrincipal::None => EffectivePrincipal::None,
-        };
+        let effective_principal = effective_principal.try_into()?;
         let sender = match PrincipalId::try_from(sender) {
             Ok(sender) => sender,
             Err(_) => {
@@ -713,6 +842,222 @@ impl CanisterCall {
     }
 }
 
+/// Returns the pending canister HTTP outcalls of all subnets.
+#[derive(Clone, Copy, Debug)]
+pub struct GetCanisterHttp;
+
+impl Operation for GetCanisterHttp {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let mut requests = vec![];
+        for (subnet_id, subnet) in pic.subnets.read().unwrap().iter() {
+            for (request_id, context) in subnet.canister_http_request_contexts() {
+                requests.push(RawCanisterHttpRequest {
+                    subnet_id: subnet_id.get().to_vec(),
+                    request_id: request_id.get(),
+                    http_method: match context.http_method {
+                        CanisterHttpMethod::GET => rest::CanisterHttpMethod::GET,
+                        CanisterHttpMethod::POST => rest::CanisterHttpMethod::POST,
+                        CanisterHttpMethod::HEAD => rest::CanisterHttpMethod::HEAD,
+                    },
+                    url: context.url,
+                    headers: context
+                        .headers
+                        .into_iter()
+                        .map(|header| CanisterHttpHeader {
+                            name: header.name,
+                            value: header.value,
+                        })
+                        .collect(),
+                    body: context.body.unwrap_or_default(),
+                    max_response_bytes: context.max_response_bytes.map(|bytes| bytes.get()),
+                });
+            }
+        }
+        OpOut::CanisterHttp(requests)
+    }
+
+    fn id(&self) -> OpId {
+        OpId("get_canister_http".into())
+    }
+}
+
+/// Delivers a mocked response to a pending canister HTTP outcall and executes
+/// a round on the subnet of the outcall. As on the IC, a reply is rejected if
+/// it exceeds the response size limit of the outcall and is passed through the
+/// transform function of the outcall (if any) before being delivered.
+#[derive(Clone, Debug)]
+pub struct MockCanisterHttp {
+    pub subnet_id: SubnetId,
+    pub request_id: CallbackId,
+    pub response: CanisterHttpResponse,
+}
+
+impl TryFrom<RawMockCanisterHttpResponse> for MockCanisterHttp {
+    type Error = ConversionError;
+    fn try_from(
+        RawMockCanisterHttpResponse {
+            subnet_id,
+            request_id,
+            response,
+        }: RawMockCanisterHttpResponse,
+    ) -> Result<Self, Self::Error> {
+        let subnet_id = match PrincipalId::try_from(subnet_id) {
+            Ok(subnet_id) => SubnetId::new(subnet_id),
+            Err(_) => {
+                return Err(ConversionError {
+                    message: "Bad subnet id".to_string(),
+                })
+            }
+        };
+        Ok(MockCanisterHttp {
+            subnet_id,
+            request_id: CallbackId::from(request_id),
+            response,
+        })
+    }
+}
+
+impl Operation for MockCanisterHttp {
+    type TargetType = PocketIc;
+
+    fn compute(self, pic: &mut PocketIc) -> OpOut {
+        let subnet = match pic.get_subnet_with_id(self.subnet_id) {
+            Some(subnet) => subnet,
+            None => return OpOut::Error(PocketIcError::SubnetNotFound(self.subnet_id.get().0)),
+        };
+        let context = match subnet
+            .canister_http_request_contexts()
+            .get(&self.request_id)
+            .cloned()
+        {
+            Some(context) => context,
+            None => {
+                return OpOut::Error(PocketIcError::InvalidCanisterHttpRequestId((
+                    self.subnet_id,
+                    self.request_id.get(),
+                )))
+            }
+        };
+        let response = match self.response {
+            CanisterHttpResponse::CanisterHttpReply(CanisterHttpReply {
+                status,
+                headers,
+                body,
+            }) => process_mock_canister_http_response(
+                &subnet,
+                &context,
+                CanisterHttpResponsePayload {
+                    status: status as u128,
+                    headers: headers
+                        .into_iter()
+                        .map(|header| HttpHeader {
+                            name: header.name,
+                            value: header.value,
+                        })
+                        .collect(),
+                    body,
+                },
+            ),
+            CanisterHttpResponse::CanisterHttpReject(CanisterHttpReject {
+                reject_code,
+                message,
+            }) => match RejectCode::try_from(reject_code) {
+                Ok(reject_code) => Err((reject_code, message)),
+                Err(_) => return OpOut::Error(PocketIcError::InvalidRejectCode(reject_code)),
+            },
+        };
+        let payload = match response {
+            Ok(response) => PayloadBuilder::new().http_response(self.request_id, &response),
+            Err((reject_code, message)) => {
+                PayloadBuilder::new().http_response_failure(self.request_id, reject_code, message)
+            }
+        };
+        subnet.execute_payload(payload);
+        OpOut::NoOutput
+    }
+
+    fn id(&self) -> OpId {
+        let mut hasher = Sha256::new();
+        hasher.write(format!("{:?}", self.response).as_bytes());
+        let hash = Digest(hasher.finish());
+        OpId(format!(
+            "mock_canister_http({},{},{})",
+            self.subnet_id, self.request_id, hash
+        ))
+    }
+}
+
+/// Mirrors the processing of a canister HTTP response on the replica: the
+/// response size limit of the request is enforced and the transform function
+/// of the request (if any) is applied to the response.
+fn process_mock_canister_http_response(
+    subnet: &StateMachine,
+    context: &CanisterHttpRequestContext,
+    response: CanisterHttpResponsePayload,
+) -> Result<CanisterHttpResponsePayload, (RejectCode, String)> {
+    let max_response_bytes = context
+        .max_response_bytes
+        .map(|bytes| bytes.get())
+        .unwrap_or(MAX_CANISTER_HTTP_RESPONSE_BYTES);
+    let headers_size: u64 = response
+        .headers
+        .iter()
+        .map(|header| (header.name.len() + header.value.len()) as u64)
+        .sum();
+    if headers_size > max_response_bytes {
+        return Err((
+            RejectCode::SysFatal,
+            format!(
+                "Header size exceeds specified response size limit {}",
+                max_response_bytes
+            ),
+        ));
+    }
+    let max_body_size = max_response_bytes - headers_size;
+    if response.body.len() as u64 > max_body_size {
+        return Err((
+            RejectCode::SysFatal,
+            format!("Http body exceeds size limit of {} bytes.", max_body_size),
+        ));
+    }
+    let transform = match &context.transform {
+        Some(transform) => transform,
+        None => return Ok(response),
+    };
+    let transform_args = TransformArgs {
+        response,
+        context: transform.context.clone(),
+    };
+    match subnet.query_as(
+        CanisterId::ic_00().get(),
+        context.request.sender,
+        transform.method_name.clone(),
+        transform_args.encode(),
+    ) {
+        Ok(WasmResult::Reply(bytes)) => {
+            if bytes.len() as u64 > MAX_CANISTER_HTTP_RESPONSE_BYTES {
+                return Err((
+                    RejectCode::SysFatal,
+                    format!(
+                        "Transformed http response exceeds limit: {}",
+                        MAX_CANISTER_HTTP_RESPONSE_BYTES
+                    ),
+                ));
+            }
+            CanisterHttpResponsePayload::decode(&bytes).map_err(|err| {
+                (
+                    RejectCode::SysFatal,
+                    format!("Failed to decode transformed http response: {}", err),
+                )
+            })
+        }
+        Ok(WasmResult::Reject(message)) => Err((RejectCode::CanisterReject, message)),
+        Err(err) => Err((err.reject_code(), err.description().to_string())),
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct SetStableMemory {
     pub canister_id: CanisterId,
@@ -784,6 +1129,65 @@ impl Operation for SetStableMemory {
     }
 }
 
+/// Imports a canister archive produced by `state-tool export_canister`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ImportCanister {
+    pub canister_id: CanisterId,
+    pub archive: Vec<u8>,
+}
+
+impl ImportCanister {
+    pub async fn from_store(
+        raw: RawImportCanister,
+        store: Arc<dyn BlobStore>,
+    ) -> Result<Self, ConversionError> {
+        if let Ok(canister_id) = CanisterId::try_from(raw.canister_id) {
+            if let Some(BinaryBlob { data, compression }) = store.fetch(raw.blob_id).await {
+                if let Some(archive) = decompress(data, compression) {
+                    Ok(ImportCanister {
+                        canister_id,
+                        archive,
+                    })
+                } else {
+                    Err(ConversionError {
+                        message: "Decompression failed".to_string(),
+                    })
+                }
+            } else {
+                Err(ConversionError {
+                    message: "Bad blob id".to_string(),
+                })
+            }
+        } else {
+            Err(ConversionError {
+                message: "Bad canister id".to_string(),
+            })
+        }
+    }
+}
+
+impl Operation for ImportCanister {
+    type TargetType = PocketIc;
+    fn compute(self, pocket_ic: &mut Self::TargetType) -> OpOut {
+        let Some(subnet) = pocket_ic.try_route_canister(self.canister_id) else {
+            return OpOut::Error(PocketIcError::CanisterNotFound(self.canister_id));
+        };
+        let mut archive_file =
+            tempfile::NamedTempFile::new().expect("failed to create a temp file");
+        std::io::Write::write_all(&mut archive_file, &self.archive)
+            .expect("failed to write canister archive");
+        subnet.import_canister(archive_file.path(), self.canister_id);
+        OpOut::NoOutput
+    }
+
+    fn id(&self) -> OpId {
+        let mut hasher = Sha256::new();
+        hasher.write(&self.archive);
+        let hash = Digest(hasher.finish());
+        OpId(format!("import_canister({}_{})", self.canister_id, hash))
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct GetStableMemory {
     pub canister_id: CanisterId,
@@ -969,6 +1373,58 @@ fn route_call(
     }
 }
 
+fn route_message_id(
+    pic: &mut PocketIc,
+    message_id: &MessageId,
+) -> Result<Arc<StateMachine>, String> {
+    match message_id.effective_principal {
+        EffectivePrincipal::SubnetId(subnet_id) => pic
+            .get_subnet_with_id(subnet_id)
+            .ok_or(format!("Subnet with ID {subnet_id} not found")),
+        EffectivePrincipal::CanisterId(effective_canister_id) => {
+            pic.try_route_canister(effective_canister_id).ok_or(format!(
+                "Desired canister ID {effective_canister_id} not contained on any subnet"
+            ))
+        }
+        EffectivePrincipal::None => Err("Message ID requires an effective principal".to_string()),
+    }
+}
+
+/// Executes rounds on all subnets until the ingress message `msg_id` submitted
+/// to `subnet` completes.
+fn await_ingress_message(
+    pic: &PocketIc,
+    subnet: &StateMachine,
+    msg_id: &ic_types::messages::MessageId,
+) -> OpOut {
+    let max_rounds = 100;
+    for _i in 0..max_rounds {
+        for subnet_ in pic.subnets.read().unwrap().values() {
+            subnet_.execute_round();
+        }
+        match subnet.ingress_status(msg_id) {
+            IngressStatus::Known {
+                state: IngressState::Completed(result),
+                ..
+            } => return Ok(result).into(),
+            IngressStatus::Known {
+                state: IngressState::Failed(error),
+                ..
+            } => {
+                return Err::<ic_state_machine_tests::WasmResult, ic_state_machine_tests::UserError>(
+                    error,
+                )
+                .into()
+            }
+            _ => {}
+        }
+    }
+    panic!(
+        "Failed to answer to ingress {} after {} xnet rounds.",
+        msg_id, max_rounds
+    );
+}
+
 fn systemtime_to_unix_epoch_nanos(st: SystemTime) -> u64 {
     st.duration_since(SystemTime::UNIX_EPOCH)
         .unwrap()
@@ -1054,6 +1510,242 @@ mod tests {
         compute_assert_state_change(&mut pic, update);
     }
 
+    #[test]
+    fn test_submit_and_await_message() {
+        let (mut pic, canister_id) = new_pic_counter_installed();
+        let amount: u128 = 20_000_000_000_000;
+        let add_cycles = AddCycles {
+            canister_id,
+            amount,
+        };
+        add_cycles.compute(&mut pic);
+
+        let submit = SubmitIngressMessage(CanisterCall {
+            sender: PrincipalId::new_anonymous(),
+            canister_id,
+            method: "write".into(),
+            payload: vec![],
+            effective_principal: EffectivePrincipal::None,
+        });
+        let OpOut::MessageId((effective_principal, msg_id)) = submit.compute(&mut pic) else {
+            unreachable!()
+        };
+        let message_id = MessageId {
+            effective_principal,
+            msg_id: ic_types::messages::MessageId::try_from(msg_id.as_slice()).unwrap(),
+        };
+
+        let status = IngressMessageStatus(message_id.clone());
+        assert_eq!(
+            compute_assert_state_immutable(&mut pic, status.clone()),
+            OpOut::IngressStatus(pocket_ic::IngressStatus::Unknown)
+        );
+
+        let result = compute_assert_state_change(&mut pic, AwaitIngressMessage(message_id));
+        assert_eq!(
+            result,
+            OpOut::CanisterResult(Ok(pocket_ic::WasmResult::Reply(vec![1, 0, 0, 0])))
+        );
+        assert_eq!(
+            compute_assert_state_immutable(&mut pic, status),
+            OpOut::IngressStatus(pocket_ic::IngressStatus::Completed(Ok(
+                pocket_ic::WasmResult::Reply(vec![1, 0, 0, 0])
+            )))
+        );
+    }
+
+    /// Installs the universal canister, runs the given setup program on it,
+    /// and makes it perform a canister HTTP outcall with the given arguments.
+    /// Returns the ID of the ingress message and the pending outcall. Rejects
+    /// of the outcall are relayed with their reject message.
+    fn submit_canister_http_request(
+        pic: &mut PocketIc,
+        setup: Vec<u8>,
+        args: impl FnOnce(CanisterId) -> ic_ic00_types::CanisterHttpRequestArgs,
+    ) -> (MessageId, rest::RawCanisterHttpRequest) {
+        use ic_test_utilities::universal_canister::{wasm, CallArgs, UNIVERSAL_CANISTER_WASM};
+
+        let canister_id = pic.any_subnet().create_canister(None);
+        let add_cycles = AddCycles {
+            canister_id,
+            amount: 20_000_000_000_000,
+        };
+        add_cycles.compute(pic);
+        let install_op = InstallCanisterAsController {
+            canister_id,
+            mode: CanisterInstallMode::Install,
+            module: UNIVERSAL_CANISTER_WASM.to_vec(),
+            payload: vec![],
+        };
+        compute_assert_state_change(pic, install_op);
+        if !setup.is_empty() {
+            compute_assert_state_change(pic, update_uc(canister_id, setup));
+        }
+
+        let submit = SubmitIngressMessage(CanisterCall {
+            sender: PrincipalId::new_anonymous(),
+            canister_id,
+            method: "update".into(),
+            payload: wasm()
+                .call_with_cycles(
+                    CanisterId::ic_00(),
+                    "http_request",
+                    CallArgs::default()
+                        .other_side(args(canister_id).encode())
+                        .on_reject(wasm().reject_message().reject().build()),
+                    ic_types::Cycles::new(1_000_000_000_000),
+                )
+                .build(),
+            effective_principal: EffectivePrincipal::None,
+        });
+        let OpOut::MessageId((effective_principal, msg_id)) = submit.compute(pic) else {
+            unreachable!()
+        };
+
+        // Execute the ingress message and the resulting management canister call.
+        Tick.compute(pic);
+        Tick.compute(pic);
+
+        let OpOut::CanisterHttp(mut requests) =
+            compute_assert_state_immutable(pic, GetCanisterHttp)
+        else {
+            unreachable!()
+        };
+        assert_eq!(requests.len(), 1);
+        let message_id = MessageId {
+            effective_principal,
+            msg_id: ic_types::messages::MessageId::try_from(msg_id.as_slice()).unwrap(),
+        };
+        (message_id, requests.remove(0))
+    }
+
+    fn update_uc(canister_id: CanisterId, payload: Vec<u8>) -> ExecuteIngressMessage {
+        ExecuteIngressMessage(CanisterCall {
+            sender: PrincipalId::new_anonymous(),
+            canister_id,
+            method: "update".into(),
+            payload,
+            effective_principal: EffectivePrincipal::None,
+        })
+    }
+
+    fn mock_canister_http_reply(
+        request: &rest::RawCanisterHttpRequest,
+        body: Vec<u8>,
+    ) -> MockCanisterHttp {
+        MockCanisterHttp {
+            subnet_id: SubnetId::new(PrincipalId::try_from(request.subnet_id.clone()).unwrap()),
+            request_id: CallbackId::from(request.request_id),
+            response: CanisterHttpResponse::CanisterHttpReply(CanisterHttpReply {
+                status: 200,
+                headers: vec![],
+                body,
+            }),
+        }
+    }
+
+    fn canister_http_request_args(
+        max_response_bytes: Option<u64>,
+        transform: Option<ic_ic00_types::TransformContext>,
+    ) -> ic_ic00_types::CanisterHttpRequestArgs {
+        use ic_ic00_types::{BoundedHttpHeaders, CanisterHttpRequestArgs, HttpMethod};
+
+        CanisterHttpRequestArgs {
+            url: "https://example.com".to_string(),
+            max_response_bytes,
+            headers: BoundedHttpHeaders::new(vec![]),
+            body: None,
+            method: HttpMethod::GET,
+            transform,
+        }
+    }
+
+    #[test]
+    fn test_mock_canister_http() {
+        let mut pic = PocketIc::default();
+        let (message_id, request) = submit_canister_http_request(&mut pic, vec![], |_| {
+            canister_http_request_args(None, None)
+        });
+        assert_eq!(request.url, "https://example.com");
+        assert_eq!(request.http_method, rest::CanisterHttpMethod::GET);
+
+        let mock = mock_canister_http_reply(&request, b"hello".to_vec());
+        compute_assert_state_change(&mut pic, mock);
+        assert_eq!(
+            compute_assert_state_immutable(&mut pic, GetCanisterHttp),
+            OpOut::CanisterHttp(vec![])
+        );
+
+        let expected = CanisterHttpResponsePayload {
+            status: 200,
+            headers: vec![],
+            body: b"hello".to_vec(),
+        };
+        assert_eq!(
+            AwaitIngressMessage(message_id).compute(&mut pic),
+            OpOut::CanisterResult(Ok(pocket_ic::WasmResult::Reply(expected.encode())))
+        );
+    }
+
+    #[test]
+    fn test_mock_canister_http_exceeding_max_response_bytes() {
+        let mut pic = PocketIc::default();
+        let (message_id, request) = submit_canister_http_request(&mut pic, vec![], |_| {
+            canister_http_request_args(Some(10), None)
+        });
+        assert_eq!(request.max_response_bytes, Some(10));
+
+        let mock = mock_canister_http_reply(&request, b"hello, world!".to_vec());
+        compute_assert_state_change(&mut pic, mock);
+
+        assert_eq!(
+            AwaitIngressMessage(message_id).compute(&mut pic),
+            OpOut::CanisterResult(Ok(pocket_ic::WasmResult::Reject(
+                "Http body exceeds size limit of 10 bytes.".to_string()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_mock_canister_http_applies_transform() {
+        use ic_ic00_types::{TransformContext, TransformFunc};
+        use ic_test_utilities::universal_canister::wasm;
+
+        let transformed = CanisterHttpResponsePayload {
+            status: 200,
+            headers: vec![],
+            body: b"transformed".to_vec(),
+        };
+        let mut pic = PocketIc::default();
+        let (message_id, request) = submit_canister_http_request(
+            &mut pic,
+            wasm()
+                .set_transform(wasm().reply_data(&transformed.encode()).build())
+                .reply()
+                .build(),
+            |canister_id| {
+                canister_http_request_args(
+                    None,
+                    Some(TransformContext {
+                        function: TransformFunc(candid::Func {
+                            principal: canister_id.get().0,
+                            method: "transform".to_string(),
+                        }),
+                        context: vec![],
+                    }),
+                )
+            },
+        );
+
+        let mock = mock_canister_http_reply(&request, b"hello".to_vec());
+        compute_assert_state_change(&mut pic, mock);
+
+        assert_eq!(
+            AwaitIngressMessage(message_id).compute(&mut pic),
+            OpOut::CanisterResult(Ok(pocket_ic::WasmResult::Reply(transformed.encode())))
+        );
+    }
+
     #[test]
     fn test_cycles_burn_app_subnet() {
         let (mut pic, canister_id) = new_pic_counter_installed();
@@ -1111,6 +1803,59 @@ mod tests {
         (query, update)
     }
 
+    #[test]
+    fn test_import_exported_canister() {
+        let (mut pic, canister_id) = new_pic_counter_installed();
+        let write = ExecuteIngressMessage(CanisterCall {
+            sender: PrincipalId::new_anonymous(),
+            canister_id,
+            method: "write".into(),
+            payload: vec![],
+            effective_principal: EffectivePrincipal::None,
+        });
+        compute_assert_state_change(&mut pic, write.clone());
+        compute_assert_state_change(&mut pic, write);
+
+        // Write a checkpoint and export the canister from it, as `state-tool` would.
+        let subnet = pic.try_route_canister(canister_id).unwrap();
+        subnet.set_checkpoints_enabled(true);
+        Tick.compute(&mut pic);
+        let state_layout = subnet.state_manager.state_layout();
+        let height = *state_layout.checkpoint_heights().unwrap().last().unwrap();
+        let checkpoint_path = state_layout
+            .checkpoint(height)
+            .unwrap()
+            .raw_path()
+            .to_path_buf();
+        let output_dir = tempfile::tempdir().unwrap();
+        let archive_path = output_dir.path().join("canister.tar");
+        ic_state_tool::commands::export_canister::do_export_canister(
+            checkpoint_path,
+            canister_id,
+            archive_path.clone(),
+        )
+        .unwrap();
+
+        let mut other_pic = PocketIc::default();
+        let import_op = ImportCanister {
+            canister_id,
+            archive: std::fs::read(archive_path).unwrap(),
+        };
+        compute_assert_state_change(&mut other_pic, import_op);
+
+        let read = Query(CanisterCall {
+            sender: PrincipalId::new_anonymous(),
+            canister_id,
+            method: "read".into(),
+            payload: vec![],
+            effective_principal: EffectivePrincipal::None,
+        });
+        assert_eq!(
+            read.compute(&mut other_pic),
+            OpOut::CanisterResult(Ok(pocket_ic::WasmResult::Reply(vec![2, 0, 0, 0])))
+        );
+    }
+
     fn new_pic_counter_installed() -> (PocketIc, CanisterId) {
         let mut pic = PocketIc::default();
         let canister_id = pic.any_subnet().create_canister(None);
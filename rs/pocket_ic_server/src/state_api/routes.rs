@@ -7,8 +7,9 @@
 use super::state::{InstanceState, OpOut, PocketIcApiState, PocketIcError, UpdateReply};
 use crate::pocket_ic::GetSubnet;
 use crate::pocket_ic::{
-    AddCycles, ExecuteIngressMessage, GetCyclesBalance, GetStableMemory, GetTime, PubKey, Query,
-    SetStableMemory, SetTime, Tick,
+    AddCycles, AwaitIngressMessage, ExecuteIngressMessage, GetCanisterHttp, GetCyclesBalance,
+    GetStableMemory, GetTime, ImportCanister, IngressMessageStatus, MockCanisterHttp, PubKey,
+    Query, SetStableMemory, SetTime, SubmitIngressMessage, Tick,
 };
 use crate::{pocket_ic::PocketIc, BindOperation, BlobStore, InstanceId, Operation};
 use aide::axum::routing::{delete, get, post, ApiMethodRouter};
@@ -22,11 +23,12 @@ use axum_extra::headers;
 use axum_extra::headers::HeaderMapExt;
 use ic_types::CanisterId;
 use pocket_ic::common::rest::{
-    self, ApiResponse, ExtendedSubnetConfigSet, RawAddCycles, RawCanisterCall, RawCanisterId,
-    RawCanisterResult, RawCycles, RawSetStableMemory, RawStableMemory, RawSubnetId, RawTime,
-    RawWasmResult,
+    self, ApiResponse, ExtendedSubnetConfigSet, RawAddCycles, RawCanisterCall,
+    RawCanisterHttpRequest, RawCanisterId, RawCanisterResult, RawCycles, RawImportCanister,
+    RawIngressStatus, RawMessageId, RawMockCanisterHttpResponse, RawSetStableMemory,
+    RawStableMemory, RawSubmitIngressResult, RawSubnetId, RawTime, RawWasmResult,
 };
-use pocket_ic::WasmResult;
+use pocket_ic::{IngressStatus, UserError, WasmResult};
 use serde::Serialize;
 use std::{sync::Arc, time::Duration};
 use tokio::{runtime::Runtime, sync::RwLock, time::Instant};
@@ -57,6 +59,8 @@ where
         .directory_route("/get_stable_memory", post(handler_get_stable_memory))
         .directory_route("/get_subnet", post(handler_get_subnet))
         .directory_route("/pub_key", post(handler_pub_key))
+        .directory_route("/ingress_status", post(handler_ingress_status))
+        .directory_route("/get_canister_http", get(handler_get_canister_http))
 }
 
 pub fn instance_update_routes<S>() -> ApiRouter<S>
@@ -69,10 +73,20 @@ where
             "/execute_ingress_message",
             post(handler_execute_ingress_message),
         )
+        .directory_route(
+            "/submit_ingress_message",
+            post(handler_submit_ingress_message),
+        )
+        .directory_route(
+            "/await_ingress_message",
+            post(handler_await_ingress_message),
+        )
         .directory_route("/set_time", post(handler_set_time))
         .directory_route("/add_cycles", post(handler_add_cycles))
         .directory_route("/set_stable_memory", post(handler_set_stable_memory))
+        .directory_route("/import_canister", post(handler_import_canister))
         .directory_route("/tick", post(handler_tick))
+        .directory_route("/mock_canister_http", post(handler_mock_canister_http))
 }
 
 pub fn instances_routes<S>() -> ApiRouter<S>
@@ -163,6 +177,12 @@ impl From<OpOut> for (StatusCode, ApiResponse<()>) {
     fn from(value: OpOut) -> Self {
         match value {
             OpOut::NoOutput => (StatusCode::OK, ApiResponse::Success(())),
+            OpOut::Error(e) => (
+                StatusCode::BAD_REQUEST,
+                ApiResponse::Error {
+                    message: format!("{:?}", e),
+                },
+            ),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 ApiResponse::Error {
@@ -206,25 +226,60 @@ impl From<OpOut> for (StatusCode, ApiResponse<RawStableMemory>) {
     }
 }
 
+fn to_raw_canister_result(result: Result<WasmResult, UserError>) -> RawCanisterResult {
+    match result {
+        Ok(WasmResult::Reply(wasm_result)) => {
+            RawCanisterResult::Ok(RawWasmResult::Reply(wasm_result))
+        }
+        Ok(WasmResult::Reject(error_message)) => {
+            RawCanisterResult::Ok(RawWasmResult::Reject(error_message))
+        }
+        Err(user_error) => RawCanisterResult::Err(user_error),
+    }
+}
+
 impl From<OpOut> for (StatusCode, ApiResponse<RawCanisterResult>) {
     fn from(value: OpOut) -> Self {
         match value {
-            OpOut::CanisterResult(wasm_result) => {
-                let inner = match wasm_result {
-                    Ok(WasmResult::Reply(wasm_result)) => {
-                        RawCanisterResult::Ok(RawWasmResult::Reply(wasm_result))
-                    }
-                    Ok(WasmResult::Reject(error_message)) => {
-                        RawCanisterResult::Ok(RawWasmResult::Reject(error_message))
+            OpOut::CanisterResult(wasm_result) => (
+                StatusCode::OK,
+                ApiResponse::Success(to_raw_canister_result(wasm_result)),
+            ),
+            OpOut::Error(e) => (
+                StatusCode::BAD_REQUEST,
+                ApiResponse::Error {
+                    message: format!("Canister call returned an error: {:?}", e),
+                },
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ApiResponse::Error {
+                    message: "operation returned invalid type".into(),
+                },
+            ),
+        }
+    }
+}
+
+impl From<OpOut> for (StatusCode, ApiResponse<RawIngressStatus>) {
+    fn from(value: OpOut) -> Self {
+        match value {
+            OpOut::IngressStatus(status) => {
+                let status = match status {
+                    IngressStatus::Unknown => RawIngressStatus::Unknown,
+                    IngressStatus::Received => RawIngressStatus::Received,
+                    IngressStatus::Processing => RawIngressStatus::Processing,
+                    IngressStatus::Completed(result) => {
+                        RawIngressStatus::Completed(to_raw_canister_result(result))
                     }
-                    Err(user_error) => RawCanisterResult::Err(user_error),
+                    IngressStatus::Done => RawIngressStatus::Done,
                 };
-                (StatusCode::OK, ApiResponse::Success(inner))
+                (StatusCode::OK, ApiResponse::Success(status))
             }
             OpOut::Error(e) => (
                 StatusCode::BAD_REQUEST,
                 ApiResponse::Error {
-                    message: format!("Canister call returned an error: {:?}", e),
+                    message: format!("{:?}", e),
                 },
             ),
             _ => (
@@ -237,6 +292,50 @@ impl From<OpOut> for (StatusCode, ApiResponse<RawCanisterResult>) {
     }
 }
 
+impl From<OpOut> for (StatusCode, ApiResponse<RawSubmitIngressResult>) {
+    fn from(value: OpOut) -> Self {
+        match value {
+            OpOut::MessageId((effective_principal, message_id)) => (
+                StatusCode::OK,
+                ApiResponse::Success(RawSubmitIngressResult::Ok(RawMessageId {
+                    effective_principal: effective_principal.into(),
+                    message_id,
+                })),
+            ),
+            OpOut::CanisterResult(Err(user_error)) => (
+                StatusCode::OK,
+                ApiResponse::Success(RawSubmitIngressResult::Err(user_error)),
+            ),
+            OpOut::Error(e) => (
+                StatusCode::BAD_REQUEST,
+                ApiResponse::Error {
+                    message: format!("Submitting the ingress message returned an error: {:?}", e),
+                },
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ApiResponse::Error {
+                    message: "operation returned invalid type".into(),
+                },
+            ),
+        }
+    }
+}
+
+impl From<OpOut> for (StatusCode, ApiResponse<Vec<RawCanisterHttpRequest>>) {
+    fn from(value: OpOut) -> Self {
+        match value {
+            OpOut::CanisterHttp(requests) => (StatusCode::OK, ApiResponse::Success(requests)),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ApiResponse::Error {
+                    message: "operation returned invalid type".into(),
+                },
+            ),
+        }
+    }
+}
+
 impl From<OpOut> for (StatusCode, ApiResponse<RawCanisterId>) {
     fn from(value: OpOut) -> Self {
         match value {
@@ -417,6 +516,39 @@ pub async fn handler_pub_key(
     (code, Json(res))
 }
 
+pub async fn handler_ingress_status(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    extract::Json(raw_message_id): extract::Json<RawMessageId>,
+) -> (StatusCode, Json<ApiResponse<RawIngressStatus>>) {
+    let timeout = timeout_or_default(headers);
+    match crate::pocket_ic::MessageId::try_from(raw_message_id) {
+        Ok(message_id) => {
+            let op = IngressMessageStatus(message_id);
+            let (code, response) = run_operation(&api_state, instance_id, timeout, op).await;
+            (code, Json(response))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                message: format!("{:?}", e),
+            }),
+        ),
+    }
+}
+
+pub async fn handler_get_canister_http(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<Vec<RawCanisterHttpRequest>>>) {
+    let timeout = timeout_or_default(headers);
+    let op = GetCanisterHttp;
+    let (code, response) = run_operation(&api_state, instance_id, timeout, op).await;
+    (code, Json(response))
+}
+
 // ----------------------------------------------------------------------------------------------------------------- //
 // Update handlers
 
@@ -443,6 +575,51 @@ pub async fn handler_execute_ingress_message(
     }
 }
 
+pub async fn handler_submit_ingress_message(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    extract::Json(raw_canister_call): extract::Json<RawCanisterCall>,
+) -> (StatusCode, Json<ApiResponse<RawSubmitIngressResult>>) {
+    let timeout = timeout_or_default(headers);
+    match crate::pocket_ic::CanisterCall::try_from(raw_canister_call) {
+        Ok(canister_call) => {
+            let ingress_op = SubmitIngressMessage(canister_call);
+            let (code, response) =
+                run_operation(&api_state, instance_id, timeout, ingress_op).await;
+            (code, Json(response))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                message: format!("{:?}", e),
+            }),
+        ),
+    }
+}
+
+pub async fn handler_await_ingress_message(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    extract::Json(raw_message_id): extract::Json<RawMessageId>,
+) -> (StatusCode, Json<ApiResponse<RawCanisterResult>>) {
+    let timeout = timeout_or_default(headers);
+    match crate::pocket_ic::MessageId::try_from(raw_message_id) {
+        Ok(message_id) => {
+            let await_op = AwaitIngressMessage(message_id);
+            let (code, response) = run_operation(&api_state, instance_id, timeout, await_op).await;
+            (code, Json(response))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                message: format!("{:?}", e),
+            }),
+        ),
+    }
+}
+
 pub async fn handler_set_time(
     State(AppState { api_state, .. }): State<AppState>,
     Path(instance_id): Path<InstanceId>,
@@ -504,6 +681,32 @@ pub async fn handler_set_stable_memory(
     }
 }
 
+pub async fn handler_import_canister(
+    State(AppState {
+        api_state,
+        min_alive_until: _,
+        runtime: _,
+        blob_store,
+    }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    axum::extract::Json(raw): axum::extract::Json<RawImportCanister>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let timeout = timeout_or_default(headers);
+    match ImportCanister::from_store(raw, blob_store).await {
+        Ok(import_op) => {
+            let (code, response) = run_operation(&api_state, instance_id, timeout, import_op).await;
+            (code, Json(response))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                message: format!("{:?}", e),
+            }),
+        ),
+    }
+}
+
 pub async fn handler_tick(
     State(AppState { api_state, .. }): State<AppState>,
     Path(instance_id): Path<InstanceId>,
@@ -515,6 +718,27 @@ pub async fn handler_tick(
     (code, Json(res))
 }
 
+pub async fn handler_mock_canister_http(
+    State(AppState { api_state, .. }): State<AppState>,
+    Path(instance_id): Path<InstanceId>,
+    headers: HeaderMap,
+    extract::Json(raw_mock): extract::Json<RawMockCanisterHttpResponse>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let timeout = timeout_or_default(headers);
+    match MockCanisterHttp::try_from(raw_mock) {
+        Ok(op) => {
+            let (code, response) = run_operation(&api_state, instance_id, timeout, op).await;
+            (code, Json(response))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                message: format!("{:?}", e),
+            }),
+        ),
+    }
+}
+
 // ----------------------------------------------------------------------------------------------------------------- //
 // Other handlers
 
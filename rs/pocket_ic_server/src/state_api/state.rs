@@ -2,12 +2,14 @@
 /// Axum handlers operate on a global state of type PocketIcApiState, whose
 /// interface guarantees consistency and determinism.
 ///
+use crate::pocket_ic::EffectivePrincipal;
 use crate::InstanceId;
 use crate::{Computation, OpId, Operation};
 use base64;
 use ic_types::{CanisterId, SubnetId};
 use ic_utils::thread::JoinOnDrop;
-use pocket_ic::{ErrorCode, UserError, WasmResult};
+use pocket_ic::common::rest::RawCanisterHttpRequest;
+use pocket_ic::{ErrorCode, IngressStatus, UserError, WasmResult};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc, thread::Builder as ThreadBuilder, time::Duration};
 use tokio::{
@@ -165,6 +167,9 @@ pub enum OpOut {
     Cycles(u128),
     Bytes(Vec<u8>),
     SubnetId(SubnetId),
+    MessageId((EffectivePrincipal, Vec<u8>)),
+    CanisterHttp(Vec<RawCanisterHttpRequest>),
+    IngressStatus(IngressStatus),
     Error(PocketIcError),
 }
 
@@ -173,23 +178,29 @@ pub enum PocketIcError {
     CanisterNotFound(CanisterId),
     BadIngressMessage(String),
     SubnetNotFound(candid::Principal),
+    InvalidCanisterHttpRequestId((SubnetId, u64)),
+    InvalidRejectCode(u64),
+}
+
+/// Converts the result of a canister call from the `StateMachine` types to the PocketIC types.
+pub(crate) fn to_canister_result(
+    r: Result<ic_state_machine_tests::WasmResult, ic_state_machine_tests::UserError>,
+) -> Result<WasmResult, UserError> {
+    match r {
+        Ok(ic_state_machine_tests::WasmResult::Reply(wasm)) => Ok(WasmResult::Reply(wasm)),
+        Ok(ic_state_machine_tests::WasmResult::Reject(s)) => Ok(WasmResult::Reject(s)),
+        Err(user_err) => Err(UserError {
+            code: ErrorCode::try_from(user_err.code() as u64).unwrap(),
+            description: user_err.description().to_string(),
+        }),
+    }
 }
 
 impl From<Result<ic_state_machine_tests::WasmResult, ic_state_machine_tests::UserError>> for OpOut {
     fn from(
         r: Result<ic_state_machine_tests::WasmResult, ic_state_machine_tests::UserError>,
     ) -> Self {
-        let res = {
-            match r {
-                Ok(ic_state_machine_tests::WasmResult::Reply(wasm)) => Ok(WasmResult::Reply(wasm)),
-                Ok(ic_state_machine_tests::WasmResult::Reject(s)) => Ok(WasmResult::Reject(s)),
-                Err(user_err) => Err(UserError {
-                    code: ErrorCode::try_from(user_err.code() as u64).unwrap(),
-                    description: user_err.description().to_string(),
-                }),
-            }
-        };
-        OpOut::CanisterResult(res)
+        OpOut::CanisterResult(to_canister_result(r))
     }
 }
 
@@ -227,8 +238,28 @@ impl std::fmt::Debug for OpOut {
             OpOut::Error(PocketIcError::SubnetNotFound(sid)) => {
                 write!(f, "SubnetNotFound({})", sid)
             }
+            OpOut::Error(PocketIcError::InvalidCanisterHttpRequestId((subnet_id, request_id))) => {
+                write!(
+                    f,
+                    "InvalidCanisterHttpRequestId({},{})",
+                    subnet_id, request_id
+                )
+            }
+            OpOut::Error(PocketIcError::InvalidRejectCode(code)) => {
+                write!(f, "InvalidRejectCode({})", code)
+            }
             OpOut::Bytes(bytes) => write!(f, "Bytes({})", base64::encode(bytes)),
             OpOut::SubnetId(subnet_id) => write!(f, "SubnetId({})", subnet_id),
+            OpOut::CanisterHttp(requests) => write!(f, "CanisterHttp({:?})", requests),
+            OpOut::IngressStatus(status) => write!(f, "IngressStatus({:?})", status),
+            OpOut::MessageId((effective_principal, message_id)) => {
+                write!(
+                    f,
+                    "MessageId({:?},{})",
+                    effective_principal,
+                    hex::encode(message_id)
+                )
+            }
         }
     }
 }
@@ -33,6 +33,8 @@ pub mod call_context {
         pub canister_id: ::core::option::Option<super::super::super::super::types::v1::CanisterId>,
         #[prost(uint64, tag = "2")]
         pub callback_id: u64,
+        #[prost(uint32, tag = "3")]
+        pub deadline_seconds: u32,
     }
     /// System task is either a Heartbeat or a GlobalTimer.
     #[allow(clippy::derive_partial_eq_without_eq)]
@@ -91,6 +93,8 @@ pub struct Callback {
     #[prost(message, optional, tag = "9")]
     pub prepayment_for_response_transmission:
         ::core::option::Option<super::super::queues::v1::Cycles>,
+    #[prost(uint32, tag = "10")]
+    pub deadline_seconds: u32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -111,6 +115,8 @@ pub struct CallContextManager {
     pub call_contexts: ::prost::alloc::vec::Vec<CallContextEntry>,
     #[prost(message, repeated, tag = "4")]
     pub callbacks: ::prost::alloc::vec::Vec<CallbackEntry>,
+    #[prost(uint64, repeated, tag = "5")]
+    pub unexpired_callbacks: ::prost::alloc::vec::Vec<u64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -122,7 +128,7 @@ pub struct CyclesAccount {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Global {
-    #[prost(oneof = "global::Global", tags = "1, 2, 3, 4")]
+    #[prost(oneof = "global::Global", tags = "1, 2, 3, 4, 5")]
     pub global: ::core::option::Option<global::Global>,
 }
 /// Nested message and enum types in `Global`.
@@ -138,6 +144,8 @@ pub mod global {
         F32(f32),
         #[prost(double, tag = "4")]
         F64(f64),
+        #[prost(bytes, tag = "5")]
+        V128(::prost::alloc::vec::Vec<u8>),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -447,6 +455,16 @@ pub struct CanisterControllersChange {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterLoadSnapshot {
+    #[prost(uint64, tag = "1")]
+    pub canister_version: u64,
+    #[prost(bytes = "vec", tag = "2")]
+    pub snapshot_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "3")]
+    pub taken_at_timestamp: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CanisterChange {
     #[prost(uint64, tag = "1")]
     pub timestamp_nanos: u64,
@@ -454,7 +472,7 @@ pub struct CanisterChange {
     pub canister_version: u64,
     #[prost(oneof = "canister_change::ChangeOrigin", tags = "3, 4")]
     pub change_origin: ::core::option::Option<canister_change::ChangeOrigin>,
-    #[prost(oneof = "canister_change::ChangeDetails", tags = "5, 6, 7, 8")]
+    #[prost(oneof = "canister_change::ChangeDetails", tags = "5, 6, 7, 8, 9")]
     pub change_details: ::core::option::Option<canister_change::ChangeDetails>,
 }
 /// Nested message and enum types in `CanisterChange`.
@@ -478,6 +496,8 @@ pub mod canister_change {
         CanisterCodeDeployment(super::CanisterCodeDeployment),
         #[prost(message, tag = "8")]
         CanisterControllersChange(super::CanisterControllersChange),
+        #[prost(message, tag = "9")]
+        CanisterLoadSnapshot(super::CanisterLoadSnapshot),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -518,6 +538,16 @@ pub struct WasmChunkData {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterLogRecord {
+    #[prost(uint64, tag = "1")]
+    pub idx: u64,
+    #[prost(uint64, tag = "2")]
+    pub timestamp_nanos: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub content: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct WasmChunkStoreMetadata {
     #[prost(message, repeated, tag = "1")]
     pub chunks: ::prost::alloc::vec::Vec<WasmChunkData>,
@@ -606,6 +636,18 @@ pub struct CanisterStateBits {
     /// Log visibility for the canister.
     #[prost(enumeration = "LogVisibility", tag = "42")]
     pub log_visibility: i32,
+    /// Memory taken by all snapshots of the canister, in bytes.
+    #[prost(uint64, tag = "43")]
+    pub snapshots_memory_usage: u64,
+    /// The local ID to be assigned to the next snapshot of the canister.
+    #[prost(uint64, tag = "44")]
+    pub next_snapshot_id: u64,
+    /// Log records of the canister.
+    #[prost(message, repeated, tag = "45")]
+    pub canister_log_records: ::prost::alloc::vec::Vec<CanisterLogRecord>,
+    /// The index to be assigned to the next log record of the canister.
+    #[prost(uint64, tag = "46")]
+    pub next_canister_log_record_idx: u64,
     #[prost(oneof = "canister_state_bits::CanisterStatus", tags = "11, 12, 13")]
     pub canister_status: ::core::option::Option<canister_state_bits::CanisterStatus>,
 }
@@ -622,6 +664,33 @@ pub mod canister_state_bits {
         Stopped(super::CanisterStatusStopped),
     }
 }
+/// Metadata of a canister snapshot. The Wasm module and memories are persisted
+/// as separate files next to it.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterSnapshotBits {
+    /// The canister-local part of the snapshot ID.
+    #[prost(uint64, tag = "1")]
+    pub snapshot_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub canister_id: ::core::option::Option<super::super::super::types::v1::CanisterId>,
+    #[prost(uint64, tag = "3")]
+    pub taken_at_timestamp: u64,
+    #[prost(uint64, tag = "4")]
+    pub canister_version: u64,
+    #[prost(bytes = "vec", tag = "5")]
+    pub binary_hash: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub certified_data: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, repeated, tag = "7")]
+    pub exported_globals: ::prost::alloc::vec::Vec<Global>,
+    /// The size of the Wasm memory, in Wasm pages.
+    #[prost(uint64, tag = "8")]
+    pub wasm_memory_size: u64,
+    /// The size of the stable memory, in Wasm pages.
+    #[prost(uint64, tag = "9")]
+    pub stable_memory_size: u64,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum CustomSectionType {
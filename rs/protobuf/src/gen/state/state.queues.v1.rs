@@ -70,6 +70,8 @@ pub struct Request {
     pub cycles_payment: ::core::option::Option<Cycles>,
     #[prost(message, optional, tag = "8")]
     pub metadata: ::core::option::Option<RequestMetadata>,
+    #[prost(uint32, tag = "9")]
+    pub deadline_seconds: u32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -94,6 +96,8 @@ pub struct Response {
     pub refund: ::core::option::Option<Funds>,
     #[prost(message, optional, tag = "7")]
     pub cycles_refund: ::core::option::Option<Cycles>,
+    #[prost(uint32, tag = "8")]
+    pub deadline_seconds: u32,
     #[prost(oneof = "response::ResponsePayload", tags = "5, 6")]
     pub response_payload: ::core::option::Option<response::ResponsePayload>,
 }
@@ -61,6 +61,7 @@ pub fn push_response(
                 originator_reply_callback: callback_id,
                 refund: context.request.take_cycles(),
                 response_payload,
+                deadline: context.request.deadline,
             });
 
             Ok(())
@@ -87,6 +88,7 @@ pub fn push_response(
                 originator_reply_callback: callback_id,
                 refund: context.request.take_cycles(),
                 response_payload,
+                deadline: context.request.deadline,
             });
 
             Ok(())
@@ -113,6 +115,7 @@ pub fn push_response(
                 originator_reply_callback: callback_id,
                 refund: context.request.take_cycles(),
                 response_payload: reject_payload,
+                deadline: context.request.deadline,
             });
 
             Ok(())
@@ -139,6 +142,7 @@ pub fn push_response(
                 originator_reply_callback: callback_id,
                 refund: context.request.take_cycles(),
                 response_payload: reject_payload,
+                deadline: context.request.deadline,
             });
 
             Ok(())
@@ -0,0 +1,400 @@
+use crate::{
+    canister_state::execution_state::{ExecutionState, Memory},
+    num_bytes_try_from, CanisterState, Global, NumWasmPages, PageMap,
+};
+use ic_types::{CanisterId, NumBytes, PrincipalId, Time};
+use ic_wasm_types::CanisterModule;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    fmt,
+    sync::Arc,
+};
+
+/// The length of the local part of a snapshot ID, in bytes.
+const LOCAL_SNAPSHOT_ID_LENGTH: usize = std::mem::size_of::<u64>();
+
+/// A globally unique identifier of a canister snapshot.
+///
+/// Consists of the ID of the canister the snapshot belongs to and a local ID
+/// that is unique among all snapshots ever taken of that canister. The binary
+/// representation is the canister ID bytes followed by the big-endian
+/// encoding of the local ID.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnapshotId {
+    canister_id: CanisterId,
+    local_id: u64,
+}
+
+impl SnapshotId {
+    pub fn new(canister_id: CanisterId, local_id: u64) -> Self {
+        Self {
+            canister_id,
+            local_id,
+        }
+    }
+
+    /// Returns the ID of the canister this snapshot belongs to.
+    pub fn get_canister_id(&self) -> CanisterId {
+        self.canister_id
+    }
+
+    /// Returns the canister-local part of the snapshot ID.
+    pub fn get_local_snapshot_id(&self) -> u64 {
+        self.local_id
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = self.canister_id.get_ref().as_slice().to_vec();
+        bytes.extend_from_slice(&self.local_id.to_be_bytes());
+        bytes
+    }
+}
+
+impl fmt::Display for SnapshotId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_vec()))
+    }
+}
+
+impl TryFrom<&[u8]> for SnapshotId {
+    type Error = String;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() <= LOCAL_SNAPSHOT_ID_LENGTH {
+            return Err(format!(
+                "Invalid snapshot ID {}: too short",
+                hex::encode(bytes)
+            ));
+        }
+        let (canister_bytes, local_bytes) = bytes.split_at(bytes.len() - LOCAL_SNAPSHOT_ID_LENGTH);
+        let principal = PrincipalId::try_from(canister_bytes)
+            .map_err(|err| format!("Invalid snapshot ID {}: {}", hex::encode(bytes), err))?;
+        let mut local_id = [0u8; LOCAL_SNAPSHOT_ID_LENGTH];
+        local_id.copy_from_slice(local_bytes);
+        Ok(Self {
+            canister_id: CanisterId::unchecked_from_principal(principal),
+            local_id: u64::from_be_bytes(local_id),
+        })
+    }
+}
+
+/// A copy of a canister's Wasm or stable memory that is not attached to a
+/// sandbox process.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageMemory {
+    /// The contents of this memory.
+    pub page_map: PageMap,
+    /// The size of the memory in Wasm pages.
+    pub size: NumWasmPages,
+}
+
+impl From<&Memory> for PageMemory {
+    fn from(memory: &Memory) -> Self {
+        Self {
+            page_map: memory.page_map.clone(),
+            size: memory.size,
+        }
+    }
+}
+
+impl From<&PageMemory> for Memory {
+    fn from(memory: &PageMemory) -> Self {
+        Memory::new(memory.page_map.clone(), memory.size)
+    }
+}
+
+/// The parts of a canister's `ExecutionState` that are captured by a snapshot.
+///
+/// Everything else (exports, custom sections, etc.) is derived from the Wasm
+/// module when the snapshot is loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExecutionStateSnapshot {
+    /// The raw canister module.
+    pub wasm_binary: CanisterModule,
+    /// The canister's exported globals.
+    pub exported_globals: Vec<Global>,
+    /// Snapshot of the canister's stable memory.
+    pub stable_memory: PageMemory,
+    /// Snapshot of the canister's Wasm memory.
+    pub wasm_memory: PageMemory,
+}
+
+impl From<&ExecutionState> for ExecutionStateSnapshot {
+    fn from(execution_state: &ExecutionState) -> Self {
+        Self {
+            wasm_binary: execution_state.wasm_binary.binary.clone(),
+            exported_globals: execution_state.exported_globals.clone(),
+            stable_memory: PageMemory::from(&execution_state.stable_memory),
+            wasm_memory: PageMemory::from(&execution_state.wasm_memory),
+        }
+    }
+}
+
+/// A snapshot of the state of a canister at a given point in time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CanisterSnapshot {
+    /// The ID of the canister the snapshot was taken of.
+    canister_id: CanisterId,
+    /// The time at which the snapshot was taken.
+    taken_at_timestamp: Time,
+    /// The canister version at the time the snapshot was taken.
+    canister_version: u64,
+    /// The certified data of the canister at the time the snapshot was taken.
+    certified_data: Vec<u8>,
+    /// The captured execution state.
+    execution_snapshot: ExecutionStateSnapshot,
+}
+
+impl CanisterSnapshot {
+    pub fn new(
+        canister_id: CanisterId,
+        taken_at_timestamp: Time,
+        canister_version: u64,
+        certified_data: Vec<u8>,
+        execution_snapshot: ExecutionStateSnapshot,
+    ) -> Self {
+        Self {
+            canister_id,
+            taken_at_timestamp,
+            canister_version,
+            certified_data,
+            execution_snapshot,
+        }
+    }
+
+    /// Captures the current state of `canister`.
+    ///
+    /// Returns an error if the canister has no execution state, i.e. if it is
+    /// empty.
+    pub fn from_canister(
+        canister: &CanisterState,
+        taken_at_timestamp: Time,
+    ) -> Result<Self, String> {
+        let execution_state = canister.execution_state.as_ref().ok_or_else(|| {
+            format!(
+                "Canister {} is empty and cannot be snapshotted",
+                canister.canister_id()
+            )
+        })?;
+        Ok(Self {
+            canister_id: canister.canister_id(),
+            taken_at_timestamp,
+            canister_version: canister.system_state.canister_version,
+            certified_data: canister.system_state.certified_data.clone(),
+            execution_snapshot: ExecutionStateSnapshot::from(execution_state),
+        })
+    }
+
+    pub fn canister_id(&self) -> CanisterId {
+        self.canister_id
+    }
+
+    pub fn taken_at_timestamp(&self) -> &Time {
+        &self.taken_at_timestamp
+    }
+
+    pub fn canister_version(&self) -> u64 {
+        self.canister_version
+    }
+
+    pub fn certified_data(&self) -> &Vec<u8> {
+        &self.certified_data
+    }
+
+    pub fn execution_snapshot(&self) -> &ExecutionStateSnapshot {
+        &self.execution_snapshot
+    }
+
+    pub fn execution_snapshot_mut(&mut self) -> &mut ExecutionStateSnapshot {
+        &mut self.execution_snapshot
+    }
+
+    /// Returns the amount of memory taken by the snapshot: its memories, the
+    /// values of its globals, the module and the certified data.
+    pub fn size(&self) -> NumBytes {
+        let execution_snapshot = &self.execution_snapshot;
+        let globals_size_bytes: u64 = execution_snapshot
+            .exported_globals
+            .iter()
+            .map(|global| global.size_bytes() as u64)
+            .sum();
+        num_bytes_try_from(execution_snapshot.wasm_memory.size)
+            .expect("could not convert from wasm memory number of pages to bytes")
+            + num_bytes_try_from(execution_snapshot.stable_memory.size)
+                .expect("could not convert from stable memory number of pages to bytes")
+            + NumBytes::from(globals_size_bytes)
+            + NumBytes::from(execution_snapshot.wasm_binary.len() as u64)
+            + NumBytes::from(self.certified_data.len() as u64)
+    }
+}
+
+/// An operation on the snapshots of a canister that has to be mirrored on the
+/// files in the tip directory before any page deltas are flushed.
+///
+/// Taking a snapshot clones the canister's `PageMap`s (and vice versa for
+/// loading one), so the files backing the source have to be copied to the
+/// destination before the unflushed deltas of the destination are persisted
+/// on top of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotOperation {
+    /// The canister's memories were copied into the given snapshot.
+    Backup(CanisterId, SnapshotId),
+    /// The memories of the given snapshot were copied into the canister.
+    Restore(CanisterId, SnapshotId),
+}
+
+/// All canister snapshots held by a subnet.
+#[derive(Clone, Debug, Default)]
+pub struct CanisterSnapshots {
+    snapshots: BTreeMap<SnapshotId, Arc<CanisterSnapshot>>,
+    /// Snapshot IDs, grouped by the canister they belong to.
+    snapshot_ids: BTreeMap<CanisterId, BTreeSet<SnapshotId>>,
+    /// Operations that have not yet been applied to the tip directory. Never
+    /// persisted; drained by the state manager every time page maps are
+    /// flushed.
+    unflushed_changes: Vec<SnapshotOperation>,
+}
+
+impl CanisterSnapshots {
+    pub fn new(snapshots: BTreeMap<SnapshotId, Arc<CanisterSnapshot>>) -> Self {
+        let mut snapshot_ids: BTreeMap<CanisterId, BTreeSet<SnapshotId>> = BTreeMap::new();
+        for snapshot_id in snapshots.keys() {
+            snapshot_ids
+                .entry(snapshot_id.get_canister_id())
+                .or_default()
+                .insert(*snapshot_id);
+        }
+        Self {
+            snapshots,
+            snapshot_ids,
+            unflushed_changes: Vec::new(),
+        }
+    }
+
+    /// Adds a new snapshot and records the corresponding `Backup` operation.
+    pub fn push(&mut self, snapshot_id: SnapshotId, snapshot: Arc<CanisterSnapshot>) {
+        let canister_id = snapshot.canister_id();
+        self.unflushed_changes
+            .push(SnapshotOperation::Backup(canister_id, snapshot_id));
+        self.snapshot_ids
+            .entry(canister_id)
+            .or_default()
+            .insert(snapshot_id);
+        self.snapshots.insert(snapshot_id, snapshot);
+    }
+
+    /// Removes the snapshot with the given ID, if it exists.
+    pub fn remove(&mut self, snapshot_id: SnapshotId) -> Option<Arc<CanisterSnapshot>> {
+        let removed = self.snapshots.remove(&snapshot_id);
+        if let Some(snapshot) = &removed {
+            let canister_id = snapshot.canister_id();
+            if let Some(ids) = self.snapshot_ids.get_mut(&canister_id) {
+                ids.remove(&snapshot_id);
+                if ids.is_empty() {
+                    self.snapshot_ids.remove(&canister_id);
+                }
+            }
+        }
+        removed
+    }
+
+    /// Removes all snapshots of the given canister.
+    pub fn delete_snapshots(&mut self, canister_id: CanisterId) {
+        if let Some(snapshot_ids) = self.snapshot_ids.remove(&canister_id) {
+            for snapshot_id in snapshot_ids {
+                self.snapshots.remove(&snapshot_id);
+            }
+        }
+    }
+
+    /// Records that the given snapshot was loaded into `canister_id`.
+    pub fn add_restore_operation(&mut self, canister_id: CanisterId, snapshot_id: SnapshotId) {
+        self.unflushed_changes
+            .push(SnapshotOperation::Restore(canister_id, snapshot_id));
+    }
+
+    pub fn get(&self, snapshot_id: SnapshotId) -> Option<&Arc<CanisterSnapshot>> {
+        self.snapshots.get(&snapshot_id)
+    }
+
+    pub fn get_mut(&mut self, snapshot_id: SnapshotId) -> Option<&mut Arc<CanisterSnapshot>> {
+        self.snapshots.get_mut(&snapshot_id)
+    }
+
+    /// Returns the snapshots of the given canister, in ascending order of IDs.
+    pub fn list_snapshots(
+        &self,
+        canister_id: CanisterId,
+    ) -> Vec<(SnapshotId, Arc<CanisterSnapshot>)> {
+        self.snapshot_ids
+            .get(&canister_id)
+            .into_iter()
+            .flatten()
+            .map(|snapshot_id| (*snapshot_id, Arc::clone(&self.snapshots[snapshot_id])))
+            .collect()
+    }
+
+    /// Returns the number of snapshots of the given canister.
+    pub fn count_by_canister(&self, canister_id: &CanisterId) -> usize {
+        self.snapshot_ids
+            .get(canister_id)
+            .map_or(0, |ids| ids.len())
+    }
+
+    /// Returns `true` if the snapshot exists and belongs to `canister_id`.
+    pub fn is_snapshot_owned_by(&self, snapshot_id: &SnapshotId, canister_id: &CanisterId) -> bool {
+        self.snapshot_ids
+            .get(canister_id)
+            .map_or(false, |ids| ids.contains(snapshot_id))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&SnapshotId, &Arc<CanisterSnapshot>)> {
+        self.snapshots.iter()
+    }
+
+    pub fn ids(&self) -> BTreeSet<SnapshotId> {
+        self.snapshots.keys().copied().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Returns the total memory taken by all snapshots of `canister_id`.
+    pub fn memory_taken_by_canister(&self, canister_id: &CanisterId) -> NumBytes {
+        self.snapshot_ids
+            .get(canister_id)
+            .into_iter()
+            .flatten()
+            .map(|snapshot_id| self.snapshots[snapshot_id].size())
+            .sum()
+    }
+
+    /// Takes the operations accumulated since the last call.
+    pub fn take_unflushed_changes(&mut self) -> Vec<SnapshotOperation> {
+        std::mem::take(&mut self.unflushed_changes)
+    }
+
+    /// Retains only the snapshots of canisters for which `f` returns `true`.
+    /// Used when splitting a subnet.
+    pub fn retain_canisters<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&CanisterId) -> bool,
+    {
+        self.snapshots
+            .retain(|snapshot_id, _| f(&snapshot_id.get_canister_id()));
+        self.snapshot_ids.retain(|canister_id, _| f(canister_id));
+    }
+}
+
+impl PartialEq for CanisterSnapshots {
+    fn eq(&self, other: &Self) -> bool {
+        // `unflushed_changes` is transient bookkeeping for the tip directory and
+        // not part of the logical state.
+        self.snapshots == other.snapshots
+    }
+}
+
+#[cfg(test)]
+mod tests;
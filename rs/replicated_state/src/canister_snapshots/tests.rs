@@ -0,0 +1,112 @@
+use super::*;
+use ic_test_utilities_time::mock_time;
+
+const CANISTER_ID: CanisterId = CanisterId::from_u64(42);
+const OTHER_CANISTER_ID: CanisterId = CanisterId::from_u64(13);
+
+fn fake_snapshot(canister_id: CanisterId, wasm_len: usize) -> CanisterSnapshot {
+    let page_memory = PageMemory {
+        page_map: PageMap::new_for_testing(),
+        size: NumWasmPages::new(1),
+    };
+    CanisterSnapshot::new(
+        canister_id,
+        mock_time(),
+        7,
+        vec![1, 2, 3],
+        ExecutionStateSnapshot {
+            wasm_binary: CanisterModule::new(vec![0; wasm_len]),
+            exported_globals: vec![Global::I32(1), Global::I64(2), Global::V128(3)],
+            stable_memory: page_memory.clone(),
+            wasm_memory: page_memory,
+        },
+    )
+}
+
+#[test]
+fn snapshot_id_round_trips_through_bytes() {
+    let snapshot_id = SnapshotId::new(CANISTER_ID, 17);
+    let bytes = snapshot_id.to_vec();
+    assert_eq!(SnapshotId::try_from(bytes.as_slice()), Ok(snapshot_id));
+    assert_eq!(snapshot_id.get_canister_id(), CANISTER_ID);
+    assert_eq!(snapshot_id.get_local_snapshot_id(), 17);
+}
+
+#[test]
+fn snapshot_id_rejects_short_input() {
+    assert!(SnapshotId::try_from(&[1, 2, 3][..]).is_err());
+    assert!(SnapshotId::try_from(&[0; LOCAL_SNAPSHOT_ID_LENGTH][..]).is_err());
+}
+
+#[test]
+fn snapshot_size_accounts_for_all_parts() {
+    let snapshot = fake_snapshot(CANISTER_ID, 100);
+    // Two Wasm pages, the globals (4 + 8 + 16 bytes), the module and the
+    // certified data.
+    let expected = 2 * 64 * 1024 + 28 + 100 + 3;
+    assert_eq!(snapshot.size(), NumBytes::from(expected));
+}
+
+#[test]
+fn push_list_and_remove_snapshots() {
+    let mut snapshots = CanisterSnapshots::default();
+    let first = SnapshotId::new(CANISTER_ID, 0);
+    let second = SnapshotId::new(CANISTER_ID, 1);
+    let other = SnapshotId::new(OTHER_CANISTER_ID, 0);
+
+    snapshots.push(first, Arc::new(fake_snapshot(CANISTER_ID, 10)));
+    snapshots.push(second, Arc::new(fake_snapshot(CANISTER_ID, 20)));
+    snapshots.push(other, Arc::new(fake_snapshot(OTHER_CANISTER_ID, 30)));
+
+    assert_eq!(snapshots.count_by_canister(&CANISTER_ID), 2);
+    assert_eq!(snapshots.count_by_canister(&OTHER_CANISTER_ID), 1);
+    assert!(snapshots.is_snapshot_owned_by(&first, &CANISTER_ID));
+    assert!(!snapshots.is_snapshot_owned_by(&other, &CANISTER_ID));
+    assert_eq!(
+        snapshots
+            .list_snapshots(CANISTER_ID)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        vec![first, second]
+    );
+    assert_eq!(
+        snapshots.memory_taken_by_canister(&CANISTER_ID),
+        snapshots.get(first).unwrap().size() + snapshots.get(second).unwrap().size()
+    );
+
+    assert!(snapshots.remove(first).is_some());
+    assert!(snapshots.remove(first).is_none());
+    assert_eq!(snapshots.count_by_canister(&CANISTER_ID), 1);
+
+    snapshots.delete_snapshots(CANISTER_ID);
+    assert_eq!(snapshots.count_by_canister(&CANISTER_ID), 0);
+    assert!(snapshots.get(second).is_none());
+    assert!(snapshots.get(other).is_some());
+}
+
+#[test]
+fn unflushed_changes_are_drained_and_ignored_by_equality() {
+    let mut snapshots = CanisterSnapshots::default();
+    let snapshot_id = SnapshotId::new(CANISTER_ID, 0);
+    snapshots.push(snapshot_id, Arc::new(fake_snapshot(CANISTER_ID, 10)));
+    snapshots.add_restore_operation(CANISTER_ID, snapshot_id);
+
+    let mut reloaded = CanisterSnapshots::new(
+        snapshots
+            .iter()
+            .map(|(id, snapshot)| (*id, Arc::clone(snapshot)))
+            .collect(),
+    );
+    assert_eq!(snapshots, reloaded);
+    assert!(reloaded.take_unflushed_changes().is_empty());
+
+    assert_eq!(
+        snapshots.take_unflushed_changes(),
+        vec![
+            SnapshotOperation::Backup(CANISTER_ID, snapshot_id),
+            SnapshotOperation::Restore(CANISTER_ID, snapshot_id),
+        ]
+    );
+    assert!(snapshots.take_unflushed_changes().is_empty());
+}
@@ -387,11 +387,12 @@ impl CanisterState {
     /// The amount of memory currently being used by the canister.
     ///
     /// This only includes execution memory (heap, stable, globals, Wasm),
-    /// canister history memory and wasm chunk storage.
+    /// canister history memory, wasm chunk storage and snapshots.
     pub fn memory_usage(&self) -> NumBytes {
         self.execution_memory_usage()
             + self.canister_history_memory_usage()
             + self.wasm_chunk_store_memory_usage()
+            + self.snapshots_memory_usage()
     }
 
     /// Returns the amount of execution memory (heap, stable, globals, Wasm)
@@ -425,6 +426,11 @@ impl CanisterState {
         self.system_state.wasm_chunk_store.memory_usage()
     }
 
+    /// Returns the memory taken by the snapshots of the canister in bytes.
+    pub fn snapshots_memory_usage(&self) -> NumBytes {
+        self.system_state.snapshots_memory_usage
+    }
+
     /// Sets the (transient) size in bytes of responses from this canister
     /// routed into streams and not yet garbage collected.
     pub(super) fn set_stream_responses_size_bytes(&mut self, size_bytes: usize) {
@@ -62,6 +62,7 @@ pub enum Global {
     I64(i64),
     F32(f32),
     F64(f64),
+    V128(u128),
 }
 
 impl Global {
@@ -71,6 +72,16 @@ impl Global {
             Global::I64(_) => "i64",
             Global::F32(_) => "f32",
             Global::F64(_) => "f64",
+            Global::V128(_) => "v128",
+        }
+    }
+
+    /// Returns the number of bytes taken by the value of the global.
+    pub fn size_bytes(&self) -> usize {
+        match self {
+            Global::I32(_) | Global::F32(_) => 4,
+            Global::I64(_) | Global::F64(_) => 8,
+            Global::V128(_) => 16,
         }
     }
 }
@@ -82,6 +93,7 @@ impl Hash for Global {
             Global::I64(val) => val.to_le_bytes().to_vec(),
             Global::F32(val) => val.to_le_bytes().to_vec(),
             Global::F64(val) => val.to_le_bytes().to_vec(),
+            Global::V128(val) => val.to_le_bytes().to_vec(),
         };
         bytes.hash(state)
     }
@@ -94,6 +106,7 @@ impl PartialEq<Global> for Global {
             (Global::I64(val), Global::I64(other_val)) => val == other_val,
             (Global::F32(val), Global::F32(other_val)) => val == other_val,
             (Global::F64(val), Global::F64(other_val)) => val == other_val,
+            (Global::V128(val), Global::V128(other_val)) => val == other_val,
             _ => false,
         }
     }
@@ -114,6 +127,9 @@ impl From<&Global> for pb::Global {
             Global::F64(value) => Self {
                 global: Some(pb::global::Global::F64(*value)),
             },
+            Global::V128(value) => Self {
+                global: Some(pb::global::Global::V128(value.to_le_bytes().to_vec())),
+            },
         }
     }
 }
@@ -126,6 +142,15 @@ impl TryFrom<pb::Global> for Global {
             pb::global::Global::I64(value) => Ok(Self::I64(value)),
             pb::global::Global::F32(value) => Ok(Self::F32(value)),
             pb::global::Global::F64(value) => Ok(Self::F64(value)),
+            pb::global::Global::V128(value) => {
+                let bytes: [u8; 16] = value.try_into().map_err(|value: Vec<u8>| {
+                    ProxyDecodeError::Other(format!(
+                        "Expected 16 bytes for Global::V128, got {}",
+                        value.len()
+                    ))
+                })?;
+                Ok(Self::V128(u128::from_le_bytes(bytes)))
+            }
         }
     }
 }
@@ -753,7 +778,7 @@ impl TryFrom<pb::WasmMetadata> for WasmMetadata {
 mod tests {
     use std::collections::BTreeSet;
 
-    use super::NextScheduledMethod;
+    use super::{pb, Global, NextScheduledMethod};
 
     #[test]
     fn test_next_scheduled_method() {
@@ -775,4 +800,26 @@ mod tests {
         // the 'NextScheduledMethod'.
         assert_eq!(values.len(), number_of_variants as usize);
     }
+
+    #[test]
+    fn global_proto_round_trip() {
+        for global in [
+            Global::I32(-7),
+            Global::I64(i64::MAX),
+            Global::F32(42.42),
+            Global::F64(-5.3),
+            Global::V128(0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10),
+        ] {
+            let encoded = pb::Global::from(&global);
+            assert_eq!(global, Global::try_from(encoded).unwrap());
+        }
+    }
+
+    #[test]
+    fn global_v128_from_proto_with_wrong_length_fails() {
+        let encoded = pb::Global {
+            global: Some(pb::global::Global::V128(vec![1, 2, 3])),
+        };
+        assert!(Global::try_from(encoded).is_err());
+    }
 }
@@ -14,11 +14,11 @@ use ic_protobuf::{
 };
 use ic_types::{
     messages::{
-        CanisterMessage, Ingress, Payload, RejectContext, Request, RequestOrResponse, Response,
-        MAX_RESPONSE_COUNT_BYTES,
+        CallbackId, CanisterMessage, Ingress, Payload, RejectContext, Request, RequestOrResponse,
+        Response, MAX_RESPONSE_COUNT_BYTES,
     },
     xnet::{QueueId, SessionId},
-    CanisterId, CountBytes, Cycles, Time,
+    CanisterId, CoarseTime, CountBytes, Cycles, Time,
 };
 use queue::{IngressQueue, InputQueue, OutputQueue};
 use std::{
@@ -581,8 +581,13 @@ impl CanisterQueues {
         let oq_stats_delta =
             OutputQueuesStats::stats_delta(&RequestOrResponse::Request(msg.clone()));
 
+        // Best-effort requests are timed out no later than their deadline.
+        let mut deadline = time + REQUEST_LIFETIME;
+        if msg.is_best_effort() {
+            deadline = deadline.min(Time::from(msg.deadline));
+        }
         output_queue
-            .push_request(msg, time + REQUEST_LIFETIME)
+            .push_request(msg, deadline)
             .expect("cannot fail due to the checks above");
 
         self.input_queues_stats.reserved_slots += 1;
@@ -626,6 +631,7 @@ impl CanisterQueues {
             originator_reply_callback: request.sender_reply_callback,
             refund: request.payment,
             response_payload: Payload::Reject(reject_context),
+            deadline: request.deadline,
         }));
         self.push_input(response, InputQueueType::LocalSubnet)
             .map_err(|(e, _msg)| e)
@@ -1052,6 +1058,306 @@ impl CanisterQueues {
         timed_out_requests_count
     }
 
+    /// Returns `true` if the input queue from `respondent` holds a response for
+    /// the given callback.
+    pub(crate) fn has_enqueued_response(
+        &self,
+        respondent: &CanisterId,
+        callback_id: CallbackId,
+    ) -> bool {
+        self.canister_queues
+            .get(respondent)
+            .map_or(false, |(input_queue, _)| {
+                input_queue.has_response_for_callback(callback_id)
+            })
+    }
+
+    /// Enqueues a reject response for an expired best-effort callback into the
+    /// input queue from `respondent`, consuming the slot reserved for the
+    /// actual response. Should the latter still arrive, it is dropped as a late
+    /// response.
+    ///
+    /// If the request is still enqueued in the output queue to `respondent`
+    /// (e.g. because it shares a deadline range with requests having later
+    /// deadlines), it is dropped and its payment refunded. Otherwise nothing is
+    /// refunded, since the respondent may still accept the cycles sent along
+    /// with the request.
+    ///
+    /// Updating the correct input queues schedule after enqueuing a reject response into a
+    /// previously empty queue also requires the full set of local canisters to decide whether
+    /// the respondent was local or remote.
+    ///
+    /// Returns `QueueFull` if there is no reserved slot for the response, e.g.
+    /// because the queue pair from `respondent` does not exist.
+    pub(crate) fn push_deadline_expired_response(
+        &mut self,
+        callback_id: CallbackId,
+        respondent: CanisterId,
+        deadline: CoarseTime,
+        own_canister_id: &CanisterId,
+        local_canisters: &BTreeMap<CanisterId, CanisterState>,
+    ) -> Result<(), StateError> {
+        let (input_queue, output_queue) = match self.canister_queues.get_mut(&respondent) {
+            Some(queues) if queues.0.reserved_slots() > 0 => queues,
+            _ => return Err(StateError::QueueFull { capacity: 0 }),
+        };
+
+        let refund = match output_queue.take_request_for_callback(callback_id) {
+            Some(request) => {
+                let refund = request.payment;
+
+                // Request was dropped, update stats.
+                let request = RequestOrResponse::Request(request);
+                self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &request);
+                self.output_queues_stats -= OutputQueuesStats::stats_delta(&request);
+                refund
+            }
+            None => Cycles::zero(),
+        };
+
+        let response = RequestOrResponse::Response(Arc::new(Response {
+            originator: *own_canister_id,
+            respondent,
+            originator_reply_callback: callback_id,
+            refund,
+            response_payload: Payload::Reject(RejectContext::new_with_message_length_limit(
+                RejectCode::SysTransient,
+                "Call deadline has expired.",
+                MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN,
+            )),
+            deadline,
+        }));
+
+        // Push response into the reserved slot, update stats.
+        let iq_stats_delta = InputQueuesStats::stats_delta(QueueOp::Push, &response);
+        let mu_stats_delta = MemoryUsageStats::stats_delta(QueueOp::Push, &response);
+        input_queue
+            .push(response)
+            .expect("cannot fail, a slot is reserved");
+        self.input_queues_stats += iq_stats_delta;
+        self.memory_usage_stats += mu_stats_delta;
+
+        // If this was a previously empty input queue, add it to input queue schedule.
+        if input_queue.num_messages() == 1 {
+            if &respondent == own_canister_id || local_canisters.contains_key(&respondent) {
+                self.local_subnet_input_schedule.push_back(respondent);
+            } else {
+                self.remote_subnet_input_schedule.push_back(respondent);
+            }
+        }
+
+        debug_assert!(self.stats_ok());
+        debug_assert!(self.schedules_ok(own_canister_id, local_canisters));
+
+        Ok(())
+    }
+
+    /// Returns the best-effort message with the largest memory footprint across
+    /// input and output queues, along with said footprint. Only considers
+    /// messages whose footprint is larger than that of a shed reject response,
+    /// so shedding always frees memory.
+    ///
+    /// Time complexity: O(num_messages).
+    fn largest_best_effort_message(&self) -> Option<(SheddableMessage, usize)> {
+        let shed_response_bytes = shed_response_size_bytes();
+        let mut largest: Option<(SheddableMessage, usize)> = None;
+        let mut consider = |message: SheddableMessage, msg: &RequestOrResponse| {
+            let bytes = match msg {
+                RequestOrResponse::Request(request) if request.is_best_effort() => {
+                    memory_required_to_push_request(request)
+                }
+                RequestOrResponse::Response(response) if response.is_best_effort() => {
+                    response.count_bytes()
+                }
+                _ => return,
+            };
+            if bytes > shed_response_bytes
+                && largest
+                    .as_ref()
+                    .map_or(true, |(_, largest)| bytes > *largest)
+            {
+                largest = Some((message, bytes));
+            }
+        };
+
+        for (canister_id, (input_queue, output_queue)) in self.canister_queues.iter() {
+            for (index, msg) in input_queue.iter().enumerate() {
+                let message = match msg {
+                    RequestOrResponse::Request(_) => {
+                        SheddableMessage::InputRequest(*canister_id, index)
+                    }
+                    RequestOrResponse::Response(_) => {
+                        SheddableMessage::InputResponse(*canister_id, index)
+                    }
+                };
+                consider(message, msg);
+            }
+            for (index, msg) in output_queue.iter().enumerate() {
+                if let Some(msg) = msg {
+                    let message = match msg {
+                        RequestOrResponse::Request(_) => {
+                            SheddableMessage::OutputRequest(*canister_id, index)
+                        }
+                        RequestOrResponse::Response(_) => {
+                            SheddableMessage::OutputResponse(*canister_id, index)
+                        }
+                    };
+                    consider(message, msg);
+                }
+            }
+        }
+        largest
+    }
+
+    /// Returns the memory footprint (in bytes) of the largest best-effort
+    /// message that [`Self::shed_largest_best_effort_message`] would shed, if
+    /// any.
+    ///
+    /// Time complexity: O(num_messages).
+    pub(crate) fn largest_best_effort_message_bytes(&self) -> Option<usize> {
+        self.largest_best_effort_message().map(|(_, bytes)| bytes)
+    }
+
+    /// Sheds the best-effort message with the largest memory footprint across
+    /// input and output queues, rejecting the matching callback with a
+    /// `SYS_UNKNOWN` reject response:
+    ///
+    ///  * An outgoing request is dropped and a reject response refunding its
+    ///    payment is enqueued into the input queue slot reserved for its response.
+    ///  * An incoming request is dropped and a reject response refunding its
+    ///    payment is enqueued into the output queue slot reserved for its response.
+    ///  * An incoming or outgoing response is replaced in place by a reject
+    ///    response carrying the same refund.
+    ///
+    /// Updating the correct input queues schedule after enqueuing a reject response into a
+    /// previously empty queue also requires the full set of local canisters to decide whether
+    /// the respondent was local or remote.
+    ///
+    /// Returns `false` if there was no best-effort message to shed.
+    ///
+    /// Time complexity: O(num_messages).
+    pub(crate) fn shed_largest_best_effort_message(
+        &mut self,
+        own_canister_id: &CanisterId,
+        local_canisters: &BTreeMap<CanisterId, CanisterState>,
+    ) -> bool {
+        let message = match self.largest_best_effort_message() {
+            Some((message, _)) => message,
+            None => return false,
+        };
+
+        match message {
+            SheddableMessage::OutputRequest(receiver, index) => {
+                let (input_queue, output_queue) = self.canister_queues.get_mut(&receiver).unwrap();
+                let request = output_queue.take_request(index);
+                let response = RequestOrResponse::Response(generate_shed_response(&request));
+
+                // Request was dropped, update stats.
+                let request = RequestOrResponse::Request(request);
+                self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &request);
+                self.output_queues_stats -= OutputQueuesStats::stats_delta(&request);
+
+                // Push response into the reserved slot, update stats.
+                let iq_stats_delta = InputQueuesStats::stats_delta(QueueOp::Push, &response);
+                let mu_stats_delta = MemoryUsageStats::stats_delta(QueueOp::Push, &response);
+                input_queue
+                    .push(response)
+                    .expect("cannot fail, a slot is reserved for every outgoing request");
+                self.input_queues_stats += iq_stats_delta;
+                self.memory_usage_stats += mu_stats_delta;
+
+                // If this was a previously empty input queue, add it to input queue schedule.
+                if input_queue.num_messages() == 1 {
+                    if &receiver == own_canister_id || local_canisters.contains_key(&receiver) {
+                        self.local_subnet_input_schedule.push_back(receiver);
+                    } else {
+                        self.remote_subnet_input_schedule.push_back(receiver);
+                    }
+                }
+            }
+
+            SheddableMessage::InputRequest(sender, index) => {
+                let (input_queue, output_queue) = self.canister_queues.get_mut(&sender).unwrap();
+                let request = input_queue.remove_request(index);
+                let response = generate_shed_response(&request);
+
+                // Request was dropped, update stats.
+                let request = RequestOrResponse::Request(request);
+                self.input_queues_stats -= InputQueuesStats::stats_delta(QueueOp::Pop, &request);
+                self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &request);
+
+                // If the input queue is now empty, remove it from the input queue schedules.
+                if input_queue.num_messages() == 0 {
+                    self.local_subnet_input_schedule.retain(|id| id != &sender);
+                    self.remote_subnet_input_schedule.retain(|id| id != &sender);
+                }
+
+                // Push response into the reserved slot, update stats.
+                let msg = RequestOrResponse::Response(Arc::clone(&response));
+                self.memory_usage_stats += MemoryUsageStats::stats_delta(QueueOp::Push, &msg);
+                self.output_queues_stats += OutputQueuesStats::stats_delta(&msg);
+                output_queue.push_response(response);
+            }
+
+            SheddableMessage::InputResponse(respondent, index) => {
+                let (input_queue, _) = self.canister_queues.get_mut(&respondent).unwrap();
+                let reject = match input_queue.iter().nth(index) {
+                    Some(RequestOrResponse::Response(response)) => generate_shed_reject(response),
+                    _ => unreachable!("Expected a response at index {}", index),
+                };
+                let old = RequestOrResponse::Response(
+                    input_queue.replace_response(index, Arc::clone(&reject)),
+                );
+                let new = RequestOrResponse::Response(reject);
+
+                // Response was replaced, update stats.
+                self.input_queues_stats -= InputQueuesStats::stats_delta(QueueOp::Pop, &old);
+                self.input_queues_stats += InputQueuesStats::stats_delta(QueueOp::Pop, &new);
+                self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &old);
+                self.memory_usage_stats += MemoryUsageStats::stats_delta(QueueOp::Pop, &new);
+            }
+
+            SheddableMessage::OutputResponse(originator, index) => {
+                let (_, output_queue) = self.canister_queues.get_mut(&originator).unwrap();
+                let reject = match output_queue.iter().nth(index) {
+                    Some(Some(RequestOrResponse::Response(response))) => {
+                        generate_shed_reject(response)
+                    }
+                    _ => unreachable!("Expected a response at index {}", index),
+                };
+                let old = RequestOrResponse::Response(
+                    output_queue.replace_response(index, Arc::clone(&reject)),
+                );
+                let new = RequestOrResponse::Response(reject);
+
+                // Response was replaced, update stats. Output queue stats are unaffected,
+                // as the refund is retained.
+                self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &old);
+                self.memory_usage_stats += MemoryUsageStats::stats_delta(QueueOp::Pop, &new);
+            }
+        }
+
+        debug_assert!(self.stats_ok());
+        debug_assert!(self.schedules_ok(own_canister_id, local_canisters));
+
+        true
+    }
+
+    /// Drops the message at the head of the output queue to `canister_id`, if
+    /// any. Used for discarding late best-effort responses that cannot be
+    /// inducted, so they do not block the queue.
+    pub(super) fn discard_output_head(&mut self, canister_id: &CanisterId) {
+        if let Some(msg) = self
+            .canister_queues
+            .get_mut(canister_id)
+            .and_then(|(_, output_queue)| output_queue.pop())
+        {
+            self.output_queues_stats -= OutputQueuesStats::stats_delta(&msg);
+            self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &msg);
+        }
+        debug_assert!(self.stats_ok());
+    }
+
     /// Re-partitions `self.local_subnet_input_schedule` and
     /// `self.remote_subnet_input_schedule` based on the set of all local canisters
     /// plus `own_canister_id` (since Rust's ownership rules would prevent us from
@@ -1096,6 +1402,65 @@ impl CanisterQueues {
     }
 }
 
+/// Position of a best-effort message that may be shed: the canister ID of the
+/// queue pair and the index of the message within the respective queue.
+enum SheddableMessage {
+    InputRequest(CanisterId, usize),
+    InputResponse(CanisterId, usize),
+    OutputRequest(CanisterId, usize),
+    OutputResponse(CanisterId, usize),
+}
+
+/// Reject message of the `SYS_UNKNOWN` reject responses produced by shedding.
+const SHED_REJECT_MESSAGE: &str = "Best-effort message was shed.";
+
+fn shed_reject_payload() -> Payload {
+    Payload::Reject(RejectContext::new_with_message_length_limit(
+        RejectCode::SysUnknown,
+        SHED_REJECT_MESSAGE,
+        MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN,
+    ))
+}
+
+/// Generates a `SYS_UNKNOWN` reject response for a shed request, refunding its
+/// payment.
+fn generate_shed_response(request: &Request) -> Arc<Response> {
+    Arc::new(Response {
+        originator: request.sender,
+        respondent: request.receiver,
+        originator_reply_callback: request.sender_reply_callback,
+        refund: request.payment,
+        response_payload: shed_reject_payload(),
+        deadline: request.deadline,
+    })
+}
+
+/// Generates a `SYS_UNKNOWN` reject response to replace a shed response,
+/// retaining its refund.
+fn generate_shed_reject(response: &Response) -> Arc<Response> {
+    Arc::new(Response {
+        originator: response.originator,
+        respondent: response.respondent,
+        originator_reply_callback: response.originator_reply_callback,
+        refund: response.refund,
+        response_payload: shed_reject_payload(),
+        deadline: response.deadline,
+    })
+}
+
+/// Returns the byte size of a `SYS_UNKNOWN` reject response produced by shedding.
+fn shed_response_size_bytes() -> usize {
+    Response {
+        originator: IC_00,
+        respondent: IC_00,
+        originator_reply_callback: CallbackId::from(0),
+        refund: Cycles::zero(),
+        response_payload: shed_reject_payload(),
+        deadline: CoarseTime::from_secs_since_unix_epoch(0),
+    }
+    .count_bytes()
+}
+
 /// Generates a timeout reject response from a request, refunding its payment.
 fn generate_timeout_response(request: &Arc<Request>) -> RequestOrResponse {
     RequestOrResponse::Response(Arc::new(Response {
@@ -1108,6 +1473,7 @@ fn generate_timeout_response(request: &Arc<Request>) -> RequestOrResponse {
             "Request timed out.",
             MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN,
         )),
+        deadline: request.deadline,
     }))
 }
 
@@ -5,7 +5,7 @@ mod tests;
 use ic_base_types::CanisterId;
 use ic_protobuf::proxy::ProxyDecodeError;
 use ic_protobuf::state::{ingress::v1 as pb_ingress, queues::v1 as pb_queues};
-use ic_types::messages::{Ingress, Request, RequestOrResponse, Response};
+use ic_types::messages::{CallbackId, Ingress, Request, RequestOrResponse, Response};
 use ic_types::{CountBytes, Cycles, Time};
 use std::collections::BTreeMap;
 use std::{
@@ -185,6 +185,21 @@ impl<T: QueueItem<T> + std::clone::Clone> QueueWithReservation<T> {
         msg
     }
 
+    /// Removes the item at the given position from the queue, releasing its
+    /// slot. Returns `None` if `index` is out of bounds.
+    fn remove(&mut self, index: usize) -> Option<T> {
+        let msg = self.queue.remove(index);
+        if let Some(msg) = &msg {
+            if msg.is_response() {
+                self.num_response_slots = self.num_response_slots.checked_sub(1).unwrap();
+            } else {
+                self.num_request_slots = self.num_request_slots.checked_sub(1).unwrap();
+            }
+        }
+        debug_assert!(self.check_invariants());
+        msg
+    }
+
     /// Returns a reference to the next item in the queue; or `None` if
     /// the queue is empty.
     fn peek(&self) -> Option<&T> {
@@ -390,6 +405,56 @@ impl InputQueue {
         self.queue.has_used_slots()
     }
 
+    /// Returns an iterator over the enqueued messages, front to back.
+    pub(super) fn iter(&self) -> impl Iterator<Item = &RequestOrResponse> {
+        self.queue.queue.iter()
+    }
+
+    /// Removes the request at the given position from the queue.
+    ///
+    /// # Panics
+    ///
+    /// If there is no request at `index`.
+    pub(super) fn remove_request(&mut self, index: usize) -> Arc<Request> {
+        match self.queue.queue.get(index) {
+            Some(RequestOrResponse::Request(_)) => {}
+            _ => panic!("No request at index {}", index),
+        }
+        match self.queue.remove(index) {
+            Some(RequestOrResponse::Request(request)) => request,
+            _ => unreachable!("Removed a request, got something else."),
+        }
+    }
+
+    /// Replaces the response at the given position with `response`. Returns the
+    /// replaced response.
+    ///
+    /// # Panics
+    ///
+    /// If there is no response at `index`.
+    pub(super) fn replace_response(
+        &mut self,
+        index: usize,
+        response: Arc<Response>,
+    ) -> Arc<Response> {
+        match self.queue.queue.get_mut(index) {
+            Some(RequestOrResponse::Response(old)) => std::mem::replace(old, response),
+            _ => panic!("No response at index {}", index),
+        }
+    }
+
+    /// Returns `true` if the queue holds a response for the given callback.
+    ///
+    /// Time complexity: O(num_messages).
+    pub(super) fn has_response_for_callback(&self, callback_id: CallbackId) -> bool {
+        self.queue.queue.iter().any(|msg| match msg {
+            RequestOrResponse::Response(response) => {
+                response.originator_reply_callback == callback_id
+            }
+            RequestOrResponse::Request(_) => false,
+        })
+    }
+
     /// Returns the amount of cycles contained in the queue.
     pub(super) fn cycles_in_queue(&self) -> Cycles {
         let mut total_cycles = Cycles::zero();
@@ -679,6 +744,68 @@ impl OutputQueue {
         self.queue.calculate_stat_sum(stat)
     }
 
+    /// Returns an iterator over the queue items (including `None` placeholders
+    /// for timed out or otherwise dropped requests), front to back.
+    pub(super) fn iter(&self) -> impl Iterator<Item = &Option<RequestOrResponse>> {
+        self.queue.queue.iter()
+    }
+
+    /// Removes the request for the given callback from the queue, if it is still
+    /// enqueued, leaving `None` in its place. Returns the removed request.
+    ///
+    /// Time complexity: O(num_messages).
+    pub(super) fn take_request_for_callback(
+        &mut self,
+        callback_id: CallbackId,
+    ) -> Option<Arc<Request>> {
+        let index = self.queue.queue.iter().position(|item| match item {
+            Some(RequestOrResponse::Request(request)) => {
+                request.sender_reply_callback == callback_id
+            }
+            _ => false,
+        })?;
+        Some(self.take_request(index))
+    }
+
+    /// Removes the request at the given position from the queue, leaving `None`
+    /// in its place.
+    ///
+    /// # Panics
+    ///
+    /// If there is no request at `index`.
+    pub(super) fn take_request(&mut self, index: usize) -> Arc<Request> {
+        let request = match self.queue.queue.get_mut(index) {
+            Some(item @ Some(RequestOrResponse::Request(_))) => item.take(),
+            _ => panic!("No request at index {}", index),
+        };
+
+        self.num_messages -= 1;
+        self.advance_to_next_message();
+        debug_assert!(self.check_invariants());
+
+        match request {
+            Some(RequestOrResponse::Request(request)) => request,
+            _ => unreachable!("Took a request, got something else."),
+        }
+    }
+
+    /// Replaces the response at the given position with `response`. Returns the
+    /// replaced response.
+    ///
+    /// # Panics
+    ///
+    /// If there is no response at `index`.
+    pub(super) fn replace_response(
+        &mut self,
+        index: usize,
+        response: Arc<Response>,
+    ) -> Arc<Response> {
+        match self.queue.queue.get_mut(index) {
+            Some(Some(RequestOrResponse::Response(old))) => std::mem::replace(old, response),
+            _ => panic!("No response at index {}", index),
+        }
+    }
+
     /// Returns true if there are any expired deadlines at `current_time`, false otherwise.
     pub(super) fn has_expired_deadlines(&self, current_time: Time) -> bool {
         match self.deadline_range_ends.front() {
@@ -16,7 +16,7 @@ use ic_test_utilities::{
 };
 use ic_test_utilities_time::mock_time;
 use ic_types::{
-    messages::{CallbackId, CanisterMessage},
+    messages::{CallbackId, CanisterMessage, NO_DEADLINE},
     time::expiry_time_from_now,
 };
 use maplit::btreemap;
@@ -1811,6 +1811,7 @@ fn time_out_requests_pushes_correct_reject_responses() {
                     method_name: "No-Op".to_string(),
                     method_payload: vec![],
                     metadata: None,
+                    deadline: NO_DEADLINE,
                 }),
                 deadline,
             )
@@ -1866,7 +1867,8 @@ fn time_out_requests_pushes_correct_reject_responses() {
                     RejectCode::SysTransient,
                     "Request timed out.",
                     MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN
-                ))
+                )),
+                deadline: NO_DEADLINE,
             }),
             *reject_response,
         );
@@ -1900,3 +1902,372 @@ fn time_out_requests_pushes_correct_reject_responses() {
         VecDeque::from(vec![remote_canister_id]),
     );
 }
+
+#[test]
+fn push_deadline_expired_response_enqueues_reject_response() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let remote_canister_id = canister_test_id(97);
+    let deadline = CoarseTime::from_secs_since_unix_epoch(10);
+
+    let request = RequestBuilder::default()
+        .sender(own_canister_id)
+        .receiver(remote_canister_id)
+        .sender_reply_callback(CallbackId::from(1))
+        .deadline(deadline)
+        .build();
+    canister_queues
+        .push_output_request(request.into(), mock_time())
+        .unwrap();
+    // Route the request.
+    canister_queues
+        .output_into_iter(own_canister_id)
+        .pop()
+        .unwrap();
+    assert_eq!(1, canister_queues.reserved_slots());
+
+    canister_queues
+        .push_deadline_expired_response(
+            CallbackId::from(1),
+            remote_canister_id,
+            deadline,
+            &own_canister_id,
+            &BTreeMap::new(),
+        )
+        .unwrap();
+
+    // The reject response consumed the reservation for the actual response.
+    assert_eq!(0, canister_queues.reserved_slots());
+    assert_eq!(
+        canister_queues.remote_subnet_input_schedule,
+        VecDeque::from(vec![remote_canister_id]),
+    );
+    assert_eq!(
+        Some(CanisterMessage::Response(Arc::new(Response {
+            originator: own_canister_id,
+            respondent: remote_canister_id,
+            originator_reply_callback: CallbackId::from(1),
+            refund: Cycles::zero(),
+            response_payload: Payload::Reject(RejectContext::new_with_message_length_limit(
+                RejectCode::SysTransient,
+                "Call deadline has expired.",
+                MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN
+            )),
+            deadline,
+        }))),
+        canister_queues.pop_input()
+    );
+    assert_eq!(0, canister_queues.memory_usage());
+
+    // A late response has no reservation to be inducted into.
+    let response = ResponseBuilder::default()
+        .originator(own_canister_id)
+        .respondent(remote_canister_id)
+        .originator_reply_callback(CallbackId::from(1))
+        .deadline(deadline)
+        .build();
+    assert_matches!(
+        canister_queues.push_input(response.into(), RemoteSubnet),
+        Err((StateError::QueueFull { .. }, _))
+    );
+    assert_eq!(0, canister_queues.reserved_slots());
+    assert_eq!(0, canister_queues.memory_usage());
+}
+
+#[test]
+fn push_deadline_expired_response_drops_enqueued_request() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let remote_canister_id = canister_test_id(97);
+    let deadline = CoarseTime::from_secs_since_unix_epoch(10);
+
+    let request = RequestBuilder::default()
+        .sender(own_canister_id)
+        .receiver(remote_canister_id)
+        .sender_reply_callback(CallbackId::from(1))
+        .payment(Cycles::new(13))
+        .deadline(deadline)
+        .build();
+    canister_queues
+        .push_output_request(request.into(), mock_time())
+        .unwrap();
+
+    // The request is still enqueued: it is dropped and its payment refunded.
+    canister_queues
+        .push_deadline_expired_response(
+            CallbackId::from(1),
+            remote_canister_id,
+            deadline,
+            &own_canister_id,
+            &BTreeMap::new(),
+        )
+        .unwrap();
+    assert!(!canister_queues.has_output());
+    assert_eq!(0, canister_queues.reserved_slots());
+    match canister_queues.pop_input() {
+        Some(CanisterMessage::Response(response)) => {
+            assert_eq!(Cycles::new(13), response.refund);
+        }
+        msg => panic!("Expected a response, got {:?}", msg),
+    }
+    assert_eq!(0, canister_queues.memory_usage());
+
+    // Nothing is left to time out.
+    assert_eq!(
+        0,
+        canister_queues.time_out_requests(
+            Time::from(deadline) + REQUEST_LIFETIME,
+            &own_canister_id,
+            &BTreeMap::new()
+        )
+    );
+}
+
+#[test]
+fn push_deadline_expired_response_without_reservation_fails() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let remote_canister_id = canister_test_id(97);
+
+    assert_matches!(
+        canister_queues.push_deadline_expired_response(
+            CallbackId::from(1),
+            remote_canister_id,
+            CoarseTime::from_secs_since_unix_epoch(10),
+            &own_canister_id,
+            &BTreeMap::new(),
+        ),
+        Err(StateError::QueueFull { .. })
+    );
+    assert_eq!(0, canister_queues.memory_usage());
+}
+
+/// Asserts that `response` is a `SYS_UNKNOWN` reject response produced by
+/// shedding, with the given refund.
+fn assert_shed_reject(response: &Response, refund: Cycles) {
+    assert_eq!(refund, response.refund);
+    match &response.response_payload {
+        Payload::Reject(context) => assert_eq!(RejectCode::SysUnknown, context.code()),
+        payload => panic!("Expected a reject payload, got {:?}", payload),
+    }
+}
+
+#[test]
+fn shed_largest_best_effort_message_sheds_output_request() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let remote_canister_id = canister_test_id(97);
+
+    // A guaranteed response request and a best-effort request.
+    let guaranteed_request = RequestBuilder::default()
+        .sender(own_canister_id)
+        .receiver(remote_canister_id)
+        .sender_reply_callback(CallbackId::from(1))
+        .build();
+    let best_effort_request = RequestBuilder::default()
+        .sender(own_canister_id)
+        .receiver(remote_canister_id)
+        .sender_reply_callback(CallbackId::from(2))
+        .payment(Cycles::new(13))
+        .deadline(CoarseTime::from_secs_since_unix_epoch(10))
+        .build();
+    canister_queues
+        .push_output_request(guaranteed_request.clone().into(), mock_time())
+        .unwrap();
+    canister_queues
+        .push_output_request(best_effort_request.into(), mock_time())
+        .unwrap();
+    let memory_usage_before = canister_queues.memory_usage();
+    assert_eq!(2 * MAX_RESPONSE_COUNT_BYTES, memory_usage_before);
+
+    assert!(canister_queues.shed_largest_best_effort_message(&own_canister_id, &BTreeMap::new()));
+
+    // The best-effort request was dropped and a reject response enqueued into
+    // the slot reserved for its response.
+    assert!(canister_queues.memory_usage() < memory_usage_before);
+    assert_eq!(1, canister_queues.reserved_slots());
+    assert_eq!(
+        canister_queues.remote_subnet_input_schedule,
+        VecDeque::from(vec![remote_canister_id]),
+    );
+    match canister_queues.pop_input() {
+        Some(CanisterMessage::Response(response)) => {
+            assert_eq!(CallbackId::from(2), response.originator_reply_callback);
+            assert_shed_reject(&response, Cycles::new(13));
+        }
+        msg => panic!("Expected a response, got {:?}", msg),
+    }
+
+    // Only the guaranteed response request is left and it is never shed.
+    assert!(!canister_queues.shed_largest_best_effort_message(&own_canister_id, &BTreeMap::new()));
+    let routed: Vec<_> = canister_queues
+        .output_into_iter(own_canister_id)
+        .map(|(_, msg)| msg)
+        .collect();
+    assert_eq!(vec![RequestOrResponse::from(guaranteed_request)], routed);
+}
+
+#[test]
+fn shed_largest_best_effort_message_sheds_input_request() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let remote_canister_id = canister_test_id(97);
+
+    let request = RequestBuilder::default()
+        .sender(remote_canister_id)
+        .receiver(own_canister_id)
+        .sender_reply_callback(CallbackId::from(1))
+        .payment(Cycles::new(13))
+        .deadline(CoarseTime::from_secs_since_unix_epoch(10))
+        .build();
+    canister_queues
+        .push_input(request.into(), RemoteSubnet)
+        .unwrap();
+    let memory_usage_before = canister_queues.memory_usage();
+
+    assert!(canister_queues.shed_largest_best_effort_message(&own_canister_id, &BTreeMap::new()));
+
+    // The request was dropped and removed from the input schedule...
+    assert!(canister_queues.memory_usage() < memory_usage_before);
+    assert!(!canister_queues.has_input());
+    assert!(canister_queues.remote_subnet_input_schedule.is_empty());
+    assert_eq!(0, canister_queues.reserved_slots());
+
+    // ...and a reject response was enqueued into the reserved output queue slot.
+    let mut routed: Vec<_> = canister_queues
+        .output_into_iter(own_canister_id)
+        .map(|(_, msg)| msg)
+        .collect();
+    match routed.pop() {
+        Some(RequestOrResponse::Response(response)) => {
+            assert_eq!(remote_canister_id, response.originator);
+            assert_eq!(CallbackId::from(1), response.originator_reply_callback);
+            assert_shed_reject(&response, Cycles::new(13));
+        }
+        msg => panic!("Expected a response, got {:?}", msg),
+    }
+    assert!(routed.is_empty());
+}
+
+#[test]
+fn shed_largest_best_effort_message_replaces_largest_response() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let remote_canister_id = canister_test_id(97);
+    let deadline = CoarseTime::from_secs_since_unix_epoch(10);
+
+    // Two best-effort calls, one incoming request.
+    for callback_id in 1..=2 {
+        canister_queues
+            .push_output_request(
+                RequestBuilder::default()
+                    .sender(own_canister_id)
+                    .receiver(remote_canister_id)
+                    .sender_reply_callback(CallbackId::from(callback_id))
+                    .deadline(deadline)
+                    .build()
+                    .into(),
+                mock_time(),
+            )
+            .unwrap();
+    }
+    canister_queues
+        .output_into_iter(own_canister_id)
+        .for_each(|_| {});
+    canister_queues
+        .push_input(
+            RequestBuilder::default()
+                .sender(remote_canister_id)
+                .receiver(own_canister_id)
+                .sender_reply_callback(CallbackId::from(3))
+                .deadline(deadline)
+                .build()
+                .into(),
+            RemoteSubnet,
+        )
+        .unwrap();
+
+    // A small response to callback 1 and a large response to callback 2.
+    for (callback_id, payload_size) in [(1_u64, 10), (2, 1000)] {
+        canister_queues
+            .push_input(
+                ResponseBuilder::default()
+                    .originator(own_canister_id)
+                    .respondent(remote_canister_id)
+                    .originator_reply_callback(CallbackId::from(callback_id))
+                    .refund(Cycles::from(callback_id))
+                    .response_payload(Payload::Data(vec![0; payload_size]))
+                    .deadline(deadline)
+                    .build()
+                    .into(),
+                RemoteSubnet,
+            )
+            .unwrap();
+    }
+    // And a large outgoing response to the incoming request.
+    let incoming_request = match canister_queues.pop_input() {
+        Some(CanisterMessage::Request(request)) => request,
+        msg => panic!("Expected a request, got {:?}", msg),
+    };
+    canister_queues.push_output_response(Arc::new(
+        ResponseBuilder::default()
+            .originator(remote_canister_id)
+            .respondent(own_canister_id)
+            .originator_reply_callback(incoming_request.sender_reply_callback)
+            .response_payload(Payload::Data(vec![0; 2000]))
+            .deadline(deadline)
+            .build(),
+    ));
+
+    // The outgoing response is the largest message and is shed first.
+    let memory_usage_before = canister_queues.memory_usage();
+    assert!(canister_queues.shed_largest_best_effort_message(&own_canister_id, &BTreeMap::new()));
+    assert!(canister_queues.memory_usage() < memory_usage_before);
+
+    // The large incoming response is shed next.
+    let memory_usage_before = canister_queues.memory_usage();
+    assert!(canister_queues.shed_largest_best_effort_message(&own_canister_id, &BTreeMap::new()));
+    assert!(canister_queues.memory_usage() < memory_usage_before);
+
+    // The small incoming response is not larger than a shed reject, so nothing
+    // else is shed.
+    assert!(!canister_queues.shed_largest_best_effort_message(&own_canister_id, &BTreeMap::new()));
+
+    // Both responses are still there, in order, with the large one rejected.
+    match canister_queues.pop_input() {
+        Some(CanisterMessage::Response(response)) => {
+            assert_eq!(CallbackId::from(1), response.originator_reply_callback);
+            assert_eq!(Payload::Data(vec![0; 10]), response.response_payload);
+        }
+        msg => panic!("Expected a response, got {:?}", msg),
+    }
+    match canister_queues.pop_input() {
+        Some(CanisterMessage::Response(response)) => {
+            assert_eq!(CallbackId::from(2), response.originator_reply_callback);
+            assert_shed_reject(&response, Cycles::new(2));
+        }
+        msg => panic!("Expected a response, got {:?}", msg),
+    }
+    let mut routed: Vec<_> = canister_queues
+        .output_into_iter(own_canister_id)
+        .map(|(_, msg)| msg)
+        .collect();
+    match routed.pop() {
+        Some(RequestOrResponse::Response(response)) => {
+            assert_eq!(
+                incoming_request.sender_reply_callback,
+                response.originator_reply_callback
+            );
+            assert_shed_reject(&response, Cycles::zero());
+        }
+        msg => panic!("Expected a response, got {:?}", msg),
+    }
+    assert!(routed.is_empty());
+    assert_eq!(0, canister_queues.memory_usage());
+}
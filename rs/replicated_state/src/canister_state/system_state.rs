@@ -19,12 +19,13 @@ use ic_protobuf::{
 
 use ic_registry_subnet_type::SubnetType;
 use ic_types::{
+    canister_log::CanisterLog,
     messages::{
         CanisterCall, CanisterMessage, CanisterMessageOrTask, CanisterTask, Ingress, RejectContext,
         Request, RequestOrResponse, Response, StopCanisterContext,
     },
     nominal_cycles::NominalCycles,
-    CanisterId, CanisterTimer, Cycles, MemoryAllocation, NumBytes, PrincipalId, Time,
+    CanisterId, CanisterTimer, CoarseTime, Cycles, MemoryAllocation, NumBytes, PrincipalId, Time,
 };
 use lazy_static::lazy_static;
 use maplit::btreeset;
@@ -334,6 +335,18 @@ pub struct SystemState {
 
     /// Log visibility of the canister.
     pub log_visibility: LogVisibility,
+
+    /// Memory taken by all snapshots of the canister. Kept up to date by
+    /// the snapshot management methods, so that the canister can be charged
+    /// for it without looking up the snapshots.
+    pub snapshots_memory_usage: NumBytes,
+
+    /// The local ID that will be assigned to the next snapshot of the canister.
+    next_snapshot_id: u64,
+
+    /// Log records produced by the canister via `ic0.debug_print` and
+    /// `ic0.trap`, bounded in size.
+    pub canister_log: CanisterLog,
 }
 
 /// A wrapper around the different canister statuses.
@@ -704,6 +717,9 @@ impl SystemState {
             canister_history: CanisterHistory::default(),
             wasm_chunk_store,
             log_visibility: LogVisibility::default(),
+            snapshots_memory_usage: NumBytes::from(0),
+            next_snapshot_id: 0,
+            canister_log: CanisterLog::default(),
         }
     }
 
@@ -748,6 +764,9 @@ impl SystemState {
         wasm_chunk_store_data: PageMap,
         wasm_chunk_store_metadata: WasmChunkStoreMetadata,
         log_visibility: LogVisibility,
+        snapshots_memory_usage: NumBytes,
+        next_snapshot_id: u64,
+        canister_log: CanisterLog,
     ) -> Self {
         Self {
             controllers,
@@ -771,6 +790,9 @@ impl SystemState {
                 wasm_chunk_store_metadata,
             ),
             log_visibility,
+            snapshots_memory_usage,
+            next_snapshot_id,
+            canister_log,
         }
     }
 
@@ -843,6 +865,19 @@ impl SystemState {
         self.canister_id
     }
 
+    /// Returns the local ID to be used for the next snapshot of the canister
+    /// and advances the counter.
+    pub fn new_local_snapshot_id(&mut self) -> u64 {
+        let local_snapshot_id = self.next_snapshot_id;
+        self.next_snapshot_id += 1;
+        local_snapshot_id
+    }
+
+    /// Returns the local ID that will be assigned to the next snapshot.
+    pub fn next_snapshot_id(&self) -> u64 {
+        self.next_snapshot_id
+    }
+
     /// Returns the amount of cycles that the balance holds.
     pub fn balance(&self) -> Cycles {
         self.cycles_balance
@@ -1045,8 +1080,17 @@ impl SystemState {
     }
 
     /// Extracts the next inter-canister or ingress message (round-robin).
+    ///
+    /// Popping a best-effort response marks its callback as no longer awaiting a
+    /// response, so that it is not timed out while its execution is pending.
     pub(crate) fn pop_input(&mut self) -> Option<CanisterMessage> {
-        self.queues.pop_input()
+        let msg = self.queues.pop_input();
+        if let (Some(CanisterMessage::Response(response)), Some(call_context_manager)) =
+            (&msg, self.call_context_manager_mut())
+        {
+            call_context_manager.mark_response_enqueued(response.originator_reply_callback);
+        }
+        msg
     }
 
     /// Returns true if there are messages in the input queues, false otherwise.
@@ -1097,15 +1141,15 @@ impl SystemState {
             msg.receiver()
         );
 
-        match (&msg, &self.status) {
+        match (&msg, &mut self.status) {
             // Requests and responses are both rejected when stopped.
             (_, CanisterStatus::Stopped { .. }) => {
-                Err((StateError::CanisterStopped(self.canister_id()), msg))
+                Err((StateError::CanisterStopped(self.canister_id), msg))
             }
 
             // Requests (only) are rejected while stopping.
             (RequestOrResponse::Request(_), CanisterStatus::Stopping { .. }) => {
-                Err((StateError::CanisterStopping(self.canister_id()), msg))
+                Err((StateError::CanisterStopping(self.canister_id), msg))
             }
 
             // Everything else is accepted iff there is available memory and queue slots.
@@ -1126,14 +1170,39 @@ impl SystemState {
                     call_context_manager
                         .validate_response(response)
                         .map_err(|err| (err, msg.clone()))?;
+                    // Late best-effort responses, whose reserved slots were already
+                    // consumed by deadline expired reject responses, are rejected.
+                    if !call_context_manager
+                        .is_awaiting_response(response.originator_reply_callback)
+                    {
+                        return Err((
+                            StateError::NonMatchingResponse {
+                                err_str: "late best-effort response".to_string(),
+                                originator: response.originator,
+                                callback_id: response.originator_reply_callback,
+                                respondent: response.respondent,
+                            },
+                            msg,
+                        ));
+                    }
                 }
+                let callback_id = match &msg {
+                    RequestOrResponse::Response(response) if response.is_best_effort() => {
+                        Some(response.originator_reply_callback)
+                    }
+                    _ => None,
+                };
                 push_input(
                     &mut self.queues,
                     msg,
                     subnet_available_memory,
                     own_subnet_type,
                     input_queue_type,
-                )
+                )?;
+                if let Some(callback_id) = callback_id {
+                    call_context_manager.mark_response_enqueued(callback_id);
+                }
+                Ok(())
             }
         }
     }
@@ -1259,6 +1328,28 @@ impl SystemState {
         let mut memory_usage = self.queues.memory_usage() as i64;
 
         while let Some(msg) = self.queues.peek_output(&self.canister_id) {
+            let best_effort_callback = match msg {
+                RequestOrResponse::Response(response) if response.is_best_effort() => {
+                    Some(response.originator_reply_callback)
+                }
+                _ => None,
+            };
+
+            if let Some(callback_id) = best_effort_callback {
+                if !self
+                    .call_context_manager()
+                    .map_or(false, |ccm| ccm.is_awaiting_response(callback_id))
+                {
+                    // Late best-effort response, whose reserved slot was already consumed
+                    // by a deadline expired reject response. Drop it.
+                    self.queues.discard_output_head(&self.canister_id);
+                    *subnet_available_memory += memory_usage;
+                    memory_usage = self.queues.memory_usage() as i64;
+                    *subnet_available_memory -= memory_usage;
+                    continue;
+                }
+            }
+
             // Ensure that enough memory is available for inducting `msg`.
             if own_subnet_type != SubnetType::System
                 && can_push(msg, *subnet_available_memory).is_err()
@@ -1275,6 +1366,11 @@ impl SystemState {
             {
                 return;
             }
+            if let (Some(callback_id), Some(call_context_manager)) =
+                (best_effort_callback, self.call_context_manager_mut())
+            {
+                call_context_manager.mark_response_enqueued(callback_id);
+            }
 
             // Adjust `subnet_available_memory` by `memory_usage_before - memory_usage_after`.
             // Defer the accounting to `CanisterQueues`, to avoid duplication or divergence.
@@ -1309,6 +1405,79 @@ impl SystemState {
             .time_out_requests(current_time, own_canister_id, local_canisters)
     }
 
+    /// Queries whether any best-effort callbacks have expired deadlines.
+    pub fn has_expired_callbacks(&self, current_time: Time) -> bool {
+        self.call_context_manager()
+            .map_or(false, |call_context_manager| {
+                call_context_manager.has_expired_callbacks(CoarseTime::floor(current_time))
+            })
+    }
+
+    /// Enqueues a `SYS_TRANSIENT` reject response for every best-effort callback
+    /// whose deadline has expired by `current_time`, consuming the slot reserved
+    /// for the actual response. Callbacks for which a response is already
+    /// enqueued are left alone. Either way, expired callbacks are no longer
+    /// awaiting a response, so any late response is dropped on arrival.
+    /// Returns the number of reject responses enqueued.
+    ///
+    /// See [`CanisterQueues::push_deadline_expired_response`] for further details.
+    pub fn time_out_callbacks(
+        &mut self,
+        current_time: Time,
+        own_canister_id: &CanisterId,
+        local_canisters: &BTreeMap<CanisterId, CanisterState>,
+    ) -> u64 {
+        let call_context_manager = match &mut self.status {
+            CanisterStatus::Running {
+                call_context_manager,
+            }
+            | CanisterStatus::Stopping {
+                call_context_manager,
+                ..
+            } => call_context_manager,
+            CanisterStatus::Stopped => return 0,
+        };
+
+        let mut expired_callbacks_count = 0;
+        for callback_id in call_context_manager.expired_callbacks(CoarseTime::floor(current_time)) {
+            let callback = call_context_manager
+                .callback(callback_id)
+                .expect("unexpired callbacks must be registered");
+            if !self
+                .queues
+                .has_enqueued_response(&callback.respondent, callback_id)
+                && self
+                    .queues
+                    .push_deadline_expired_response(
+                        callback_id,
+                        callback.respondent,
+                        callback.deadline,
+                        own_canister_id,
+                        local_canisters,
+                    )
+                    .is_ok()
+            {
+                expired_callbacks_count += 1;
+            }
+            call_context_manager.mark_response_enqueued(callback_id);
+        }
+        expired_callbacks_count
+    }
+
+    /// Sheds the best-effort message with the largest memory footprint from the
+    /// queues of this canister, rejecting the matching callback with a
+    /// `SYS_UNKNOWN` reject response. Returns `false` if there was nothing to shed.
+    ///
+    /// See [`CanisterQueues::shed_largest_best_effort_message`] for further details.
+    pub fn shed_largest_best_effort_message(
+        &mut self,
+        own_canister_id: &CanisterId,
+        local_canisters: &BTreeMap<CanisterId, CanisterState>,
+    ) -> bool {
+        self.queues
+            .shed_largest_best_effort_message(own_canister_id, local_canisters)
+    }
+
     /// Re-partitions the local and remote input schedules of `self.queues`
     /// following a canister migration, based on the updated set of local canisters.
     ///
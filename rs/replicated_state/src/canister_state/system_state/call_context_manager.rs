@@ -12,14 +12,14 @@ use ic_types::{
     ingress::WasmResult,
     messages::{
         CallContextId, CallbackId, CanisterCall, CanisterCallOrTask, MessageId, RequestMetadata,
-        Response,
+        Response, NO_DEADLINE,
     },
     methods::Callback,
-    user_id_into_protobuf, user_id_try_from_protobuf, CanisterId, Cycles, Funds, PrincipalId, Time,
-    UserId,
+    user_id_into_protobuf, user_id_try_from_protobuf, CanisterId, CoarseTime, Cycles, Funds,
+    PrincipalId, Time, UserId,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::{From, TryFrom, TryInto};
 use std::time::Duration;
 
@@ -244,12 +244,16 @@ pub struct CallContextManager {
     /// Maps call context to its responded status.
     call_contexts: BTreeMap<CallContextId, CallContext>,
     callbacks: BTreeMap<CallbackId, Callback>,
+    /// Best-effort callbacks still awaiting a response, i.e. for which neither
+    /// the actual response nor a deadline expired reject response was enqueued
+    /// yet; ordered by deadline.
+    unexpired_callbacks: BTreeSet<(CoarseTime, CallbackId)>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CallOrigin {
     Ingress(UserId, MessageId),
-    CanisterUpdate(CanisterId, CallbackId),
+    CanisterUpdate(CanisterId, CallbackId, CoarseTime),
     Query(UserId),
     CanisterQuery(CanisterId, CallbackId),
     /// System task is either a `Heartbeat` or a `GlobalTimer`.
@@ -261,7 +265,7 @@ impl CallOrigin {
     pub fn get_principal(&self) -> PrincipalId {
         match self {
             CallOrigin::Ingress(user_id, _) => user_id.get(),
-            CallOrigin::CanisterUpdate(canister_id, _) => canister_id.get(),
+            CallOrigin::CanisterUpdate(canister_id, _, _) => canister_id.get(),
             CallOrigin::Query(user_id) => user_id.get(),
             CallOrigin::CanisterQuery(canister_id, _) => canister_id.get(),
             CallOrigin::SystemTask => IC_00.get(),
@@ -276,10 +280,11 @@ impl From<&CallOrigin> for pb::call_context::CallOrigin {
                 user_id: Some(user_id_into_protobuf(*user_id)),
                 message_id: message_id.as_bytes().to_vec(),
             }),
-            CallOrigin::CanisterUpdate(canister_id, callback_id) => {
+            CallOrigin::CanisterUpdate(canister_id, callback_id, deadline) => {
                 Self::CanisterUpdate(pb::call_context::CanisterUpdateOrQuery {
                     canister_id: Some(pb_types::CanisterId::from(*canister_id)),
                     callback_id: callback_id.get(),
+                    deadline_seconds: deadline.as_secs_since_unix_epoch(),
                 })
             }
             CallOrigin::Query(user_id) => Self::Query(user_id_into_protobuf(*user_id)),
@@ -287,6 +292,7 @@ impl From<&CallOrigin> for pb::call_context::CallOrigin {
                 Self::CanisterQuery(pb::call_context::CanisterUpdateOrQuery {
                     canister_id: Some(pb_types::CanisterId::from(*canister_id)),
                     callback_id: callback_id.get(),
+                    deadline_seconds: NO_DEADLINE.as_secs_since_unix_epoch(),
                 })
             }
             CallOrigin::SystemTask => Self::SystemTask(pb::call_context::SystemTask {}),
@@ -312,10 +318,12 @@ impl TryFrom<pb::call_context::CallOrigin> for CallOrigin {
                 pb::call_context::CanisterUpdateOrQuery {
                     canister_id,
                     callback_id,
+                    deadline_seconds,
                 },
             ) => Self::CanisterUpdate(
                 try_from_option_field(canister_id, "CallOrigin::CanisterUpdate::canister_id")?,
                 callback_id.into(),
+                CoarseTime::from_secs_since_unix_epoch(deadline_seconds),
             ),
             pb::call_context::CallOrigin::Query(user_id) => {
                 Self::Query(user_id_try_from_protobuf(user_id)?)
@@ -324,6 +332,7 @@ impl TryFrom<pb::call_context::CallOrigin> for CallOrigin {
                 pb::call_context::CanisterUpdateOrQuery {
                     canister_id,
                     callback_id,
+                    ..
                 },
             ) => Self::CanisterQuery(
                 try_from_option_field(canister_id, "CallOrigin::CanisterQuery::canister_id")?,
@@ -536,6 +545,10 @@ impl CallContextManager {
     pub fn register_callback(&mut self, callback: Callback) -> CallbackId {
         self.next_callback_id += 1;
         let callback_id = CallbackId::from(self.next_callback_id);
+        if callback.deadline != NO_DEADLINE {
+            self.unexpired_callbacks
+                .insert((callback.deadline, callback_id));
+        }
         self.callbacks.insert(callback_id, callback);
         callback_id
     }
@@ -543,7 +556,52 @@ impl CallContextManager {
     /// If we get a response for one of the outstanding calls, we unregister
     /// the callback and return it.
     pub fn unregister_callback(&mut self, callback_id: CallbackId) -> Option<Callback> {
-        self.callbacks.remove(&callback_id)
+        let callback = self.callbacks.remove(&callback_id)?;
+        self.unexpired_callbacks
+            .remove(&(callback.deadline, callback_id));
+        Some(callback)
+    }
+
+    /// Returns `true` if the deadline of any best-effort callback still awaiting
+    /// a response expired at or before `now`.
+    pub fn has_expired_callbacks(&self, now: CoarseTime) -> bool {
+        self.unexpired_callbacks
+            .first()
+            .map_or(false, |(deadline, _)| *deadline <= now)
+    }
+
+    /// Returns the IDs of the best-effort callbacks whose deadlines expired at
+    /// or before `now`, in deadline order, excluding those for which a response
+    /// was already enqueued (see `mark_response_enqueued()`).
+    pub fn expired_callbacks(&self, now: CoarseTime) -> Vec<CallbackId> {
+        self.unexpired_callbacks
+            .iter()
+            .take_while(|(deadline, _)| *deadline <= now)
+            .map(|(_, callback_id)| *callback_id)
+            .collect()
+    }
+
+    /// Marks the given best-effort callback as no longer awaiting a response,
+    /// once a response (the actual response or a deadline expired reject
+    /// response) was enqueued for it, consuming the reserved slot. The callback
+    /// itself is retained until said response is executed.
+    pub fn mark_response_enqueued(&mut self, callback_id: CallbackId) {
+        if let Some(callback) = self.callbacks.get(&callback_id) {
+            self.unexpired_callbacks
+                .remove(&(callback.deadline, callback_id));
+        }
+    }
+
+    /// Returns `true` if the given callback is registered and no response has
+    /// been enqueued for it yet. Always `true` for guaranteed response callbacks.
+    pub fn is_awaiting_response(&self, callback_id: CallbackId) -> bool {
+        match self.callbacks.get(&callback_id) {
+            Some(callback) if callback.deadline != NO_DEADLINE => self
+                .unexpired_callbacks
+                .contains(&(callback.deadline, callback_id)),
+            Some(_) => true,
+            None => false,
+        }
     }
 
     /// Returns the call origin, which is either the message id of the ingress
@@ -601,9 +659,11 @@ impl CallContextManager {
 impl From<&CanisterCall> for CallOrigin {
     fn from(msg: &CanisterCall) -> Self {
         match msg {
-            CanisterCall::Request(request) => {
-                CallOrigin::CanisterUpdate(request.sender, request.sender_reply_callback)
-            }
+            CanisterCall::Request(request) => CallOrigin::CanisterUpdate(
+                request.sender,
+                request.sender_reply_callback,
+                request.deadline,
+            ),
             CanisterCall::Ingress(ingress) => {
                 CallOrigin::Ingress(ingress.source, ingress.message_id.clone())
             }
@@ -641,6 +701,11 @@ impl From<&CallContextManager> for pb::CallContextManager {
                     callback: Some(callback.into()),
                 })
                 .collect(),
+            unexpired_callbacks: item
+                .unexpired_callbacks
+                .iter()
+                .map(|(_, id)| id.get())
+                .collect(),
         }
     }
 }
@@ -671,11 +736,23 @@ impl TryFrom<pb::CallContextManager> for CallContextManager {
             );
         }
 
+        let mut unexpired_callbacks = BTreeSet::new();
+        for callback_id in value.unexpired_callbacks.into_iter().map(CallbackId::from) {
+            let callback = callbacks.get(&callback_id).ok_or_else(|| {
+                ProxyDecodeError::Other(format!(
+                    "CallContextManager: unexpired callback {} not found",
+                    callback_id
+                ))
+            })?;
+            unexpired_callbacks.insert((callback.deadline, callback_id));
+        }
+
         Ok(Self {
             next_call_context_id: value.next_call_context_id,
             next_callback_id: value.next_callback_id,
             call_contexts,
             callbacks,
+            unexpired_callbacks,
         })
     }
 }
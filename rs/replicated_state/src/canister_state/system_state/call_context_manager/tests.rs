@@ -9,14 +9,14 @@ fn call_context_origin() {
     let id = canister_test_id(42);
     let cb_id = CallbackId::from(1);
     let cc_id = ccm.new_call_context(
-        CallOrigin::CanisterUpdate(id, cb_id),
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE),
         Cycles::new(10),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
     );
     assert_eq!(
         ccm.call_contexts().get(&cc_id).unwrap().call_origin,
-        CallOrigin::CanisterUpdate(id, cb_id)
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE)
     );
 }
 
@@ -28,20 +28,20 @@ fn call_context_handling() {
 
     // On two incoming calls
     let call_context_id1 = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
     );
     let call_context_id2 = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(2)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(2), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
     );
 
     let call_context_id3 = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(3)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(3), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
@@ -83,6 +83,7 @@ fn call_context_handling() {
         WasmClosure::new(0, 1),
         WasmClosure::new(2, 3),
         None,
+        NO_DEADLINE,
     ));
     let callback_id2 = call_context_manager.register_callback(Callback::new(
         call_context_id1,
@@ -94,6 +95,7 @@ fn call_context_handling() {
         WasmClosure::new(4, 5),
         WasmClosure::new(6, 7),
         None,
+        NO_DEADLINE,
     ));
 
     // There are 2 ougoing calls
@@ -110,6 +112,7 @@ fn call_context_handling() {
         WasmClosure::new(8, 9),
         WasmClosure::new(10, 11),
         None,
+        NO_DEADLINE,
     ));
     // There is 1 outgoing call
     assert_eq!(call_context_manager.outstanding_calls(call_context_id2), 1);
@@ -257,7 +260,7 @@ fn withdraw_cycles_fails_when_not_enough_available_cycles() {
     let id = canister_test_id(42);
     let cb_id = CallbackId::from(1);
     let cc_id = ccm.new_call_context(
-        CallOrigin::CanisterUpdate(id, cb_id),
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE),
         Cycles::new(30),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
@@ -280,7 +283,7 @@ fn withdraw_cycles_succeeds_when_enough_available_cycles() {
     let id = canister_test_id(42);
     let cb_id = CallbackId::from(1);
     let cc_id = ccm.new_call_context(
-        CallOrigin::CanisterUpdate(id, cb_id),
+        CallOrigin::CanisterUpdate(id, cb_id, NO_DEADLINE),
         Cycles::new(30),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
@@ -298,7 +301,7 @@ fn withdraw_cycles_succeeds_when_enough_available_cycles() {
 fn test_call_context_instructions_executed_is_updated() {
     let mut call_context_manager = CallContextManager::default();
     let call_context_id = call_context_manager.new_call_context(
-        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1)),
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1), NO_DEADLINE),
         Cycles::zero(),
         Time::from_nanos_since_unix_epoch(0),
         RequestMetadata::new(0, mock_time()),
@@ -314,6 +317,7 @@ fn test_call_context_instructions_executed_is_updated() {
         WasmClosure::new(0, 1),
         WasmClosure::new(2, 3),
         None,
+        NO_DEADLINE,
     ));
 
     // Finish a successful execution with 1K instructions.
@@ -351,3 +355,93 @@ fn test_call_context_instructions_executed_is_updated() {
         (1_000 + 2_000).into()
     );
 }
+
+fn best_effort_callback(call_context_id: CallContextId, deadline: CoarseTime) -> Callback {
+    Callback::new(
+        call_context_id,
+        canister_test_id(1),
+        canister_test_id(2),
+        Cycles::zero(),
+        Cycles::zero(),
+        Cycles::zero(),
+        WasmClosure::new(0, 1),
+        WasmClosure::new(2, 3),
+        None,
+        deadline,
+    )
+}
+
+#[test]
+fn expired_callbacks() {
+    let mut ccm = CallContextManager::default();
+    let cc_id = ccm.new_call_context(
+        CallOrigin::CanisterUpdate(canister_test_id(123), CallbackId::from(1), NO_DEADLINE),
+        Cycles::zero(),
+        Time::from_nanos_since_unix_epoch(0),
+        RequestMetadata::new(0, mock_time()),
+    );
+
+    let d1 = CoarseTime::from_secs_since_unix_epoch(10);
+    let d2 = CoarseTime::from_secs_since_unix_epoch(20);
+    let guaranteed_id = ccm.register_callback(best_effort_callback(cc_id, NO_DEADLINE));
+    let cb_id1 = ccm.register_callback(best_effort_callback(cc_id, d2));
+    let cb_id2 = ccm.register_callback(best_effort_callback(cc_id, d1));
+    let cb_id3 = ccm.register_callback(best_effort_callback(cc_id, d2));
+
+    // Nothing has expired before the earliest deadline.
+    let before = CoarseTime::from_secs_since_unix_epoch(9);
+    assert!(!ccm.has_expired_callbacks(before));
+    assert!(ccm.expired_callbacks(before).is_empty());
+
+    // Callbacks are returned in deadline order; guaranteed response callbacks
+    // never expire.
+    assert!(ccm.has_expired_callbacks(d1));
+    assert_eq!(vec![cb_id2], ccm.expired_callbacks(d1));
+    assert_eq!(
+        vec![cb_id2, cb_id1, cb_id3],
+        ccm.expired_callbacks(CoarseTime::from_secs_since_unix_epoch(u32::MAX))
+    );
+
+    // Enqueuing a response for a callback retains it, but no longer reports it
+    // as expired or awaiting a response.
+    assert!(ccm.is_awaiting_response(cb_id2));
+    ccm.mark_response_enqueued(cb_id2);
+    assert!(!ccm.has_expired_callbacks(d1));
+    assert!(!ccm.is_awaiting_response(cb_id2));
+    assert!(ccm.callbacks().contains_key(&cb_id2));
+    assert_eq!(vec![cb_id1, cb_id3], ccm.expired_callbacks(d2));
+
+    // Unregistering a callback also removes it from the expired callbacks.
+    ccm.unregister_callback(cb_id1).unwrap();
+    assert_eq!(vec![cb_id3], ccm.expired_callbacks(d2));
+    assert!(!ccm.is_awaiting_response(cb_id1));
+    assert!(ccm.callbacks().contains_key(&guaranteed_id));
+    assert!(ccm.is_awaiting_response(guaranteed_id));
+}
+
+#[test]
+fn unexpired_callbacks_roundtrip_encode() {
+    let mut ccm = CallContextManager::default();
+    let cc_id = ccm.new_call_context(
+        CallOrigin::CanisterUpdate(
+            canister_test_id(123),
+            CallbackId::from(1),
+            CoarseTime::from_secs_since_unix_epoch(30),
+        ),
+        Cycles::zero(),
+        Time::from_nanos_since_unix_epoch(0),
+        RequestMetadata::new(0, mock_time()),
+    );
+    let d1 = CoarseTime::from_secs_since_unix_epoch(10);
+    let d2 = CoarseTime::from_secs_since_unix_epoch(20);
+    ccm.register_callback(best_effort_callback(cc_id, NO_DEADLINE));
+    let cb_id1 = ccm.register_callback(best_effort_callback(cc_id, d1));
+    let cb_id2 = ccm.register_callback(best_effort_callback(cc_id, d2));
+    ccm.mark_response_enqueued(cb_id1);
+
+    let encoded = pb::CallContextManager::from(&ccm);
+    let decoded = CallContextManager::try_from(encoded).unwrap();
+
+    assert_eq!(ccm, decoded);
+    assert_eq!(vec![cb_id2], decoded.expired_callbacks(d2));
+}
@@ -29,6 +29,7 @@
 //!   as it could change the past.
 //!
 mod bitcoin;
+pub mod canister_snapshots;
 pub mod canister_state;
 pub(crate) mod hash;
 pub mod metadata_state;
@@ -3,6 +3,7 @@ use super::{
     metadata_state::{IngressHistoryState, Stream, Streams, SystemMetadata},
 };
 use crate::{
+    canister_snapshots::CanisterSnapshots,
     canister_state::queues::CanisterQueuesLoopDetector,
     canister_state::system_state::{push_input, CanisterOutputQueuesIterator},
     metadata_state::{subnet_call_context_manager::SignWithEcdsaContext, StreamMap},
@@ -406,6 +407,9 @@ pub struct ReplicatedState {
     /// Temporary query stats received during the current epoch.
     /// Reset during the start of each epoch.
     pub epoch_query_stats: RawQueryStats,
+
+    /// Manages the canister snapshots.
+    pub canister_snapshots: CanisterSnapshots,
 }
 
 impl ReplicatedState {
@@ -417,6 +421,7 @@ impl ReplicatedState {
             subnet_queues: CanisterQueues::default(),
             consensus_queue: Vec::new(),
             epoch_query_stats: RawQueryStats::default(),
+            canister_snapshots: CanisterSnapshots::default(),
         }
     }
 
@@ -426,6 +431,7 @@ impl ReplicatedState {
         metadata: SystemMetadata,
         subnet_queues: CanisterQueues,
         epoch_query_stats: RawQueryStats,
+        canister_snapshots: CanisterSnapshots,
     ) -> Self {
         let mut res = Self {
             canister_states,
@@ -433,6 +439,7 @@ impl ReplicatedState {
             subnet_queues,
             consensus_queue: Vec::new(),
             epoch_query_stats,
+            canister_snapshots,
         };
         res.update_stream_responses_size_bytes();
         res
@@ -596,6 +603,7 @@ impl ReplicatedState {
             wasm_custom_sections_memory_taken,
             canister_history_memory_taken,
             wasm_chunk_store_memory_usage,
+            snapshots_memory_usage,
         ) = self
             .canisters_iter()
             .map(|canister| {
@@ -608,6 +616,7 @@ impl ReplicatedState {
                     canister.wasm_custom_sections_memory_usage(),
                     canister.canister_history_memory_usage(),
                     canister.wasm_chunk_store_memory_usage(),
+                    canister.snapshots_memory_usage(),
                 )
             })
             .reduce(|accum, val| {
@@ -617,6 +626,7 @@ impl ReplicatedState {
                     accum.2 + val.2,
                     accum.3 + val.3,
                     accum.4 + val.4,
+                    accum.5 + val.5,
                 )
             })
             .unwrap_or_default();
@@ -626,7 +636,8 @@ impl ReplicatedState {
         MemoryTaken {
             execution: raw_memory_taken
                 + canister_history_memory_taken
-                + wasm_chunk_store_memory_usage,
+                + wasm_chunk_store_memory_usage
+                + snapshots_memory_usage,
             messages: message_memory_taken,
             wasm_custom_sections: wasm_custom_sections_memory_taken,
             canister_history: canister_history_memory_taken,
@@ -885,6 +896,109 @@ impl ReplicatedState {
         timed_out_requests_count
     }
 
+    /// Enqueues reject responses for all best-effort callbacks with expired
+    /// deadlines, for all canisters. Returns the number of expired callbacks.
+    ///
+    /// See [`crate::SystemState::time_out_callbacks`] for further details.
+    pub fn time_out_callbacks(&mut self) -> u64 {
+        let current_time = self.metadata.time();
+        // Same as for `time_out_requests()`, only remove-call-replace the canisters
+        // that actually have expired callbacks.
+        let canister_ids_with_expired_callbacks = self
+            .canister_states
+            .iter()
+            .filter(|(_, canister_state)| {
+                canister_state
+                    .system_state
+                    .has_expired_callbacks(current_time)
+            })
+            .map(|(canister_id, _)| *canister_id)
+            .collect::<Vec<_>>();
+
+        let mut expired_callbacks_count = 0;
+        for canister_id in canister_ids_with_expired_callbacks {
+            let mut canister = self.canister_states.remove(&canister_id).unwrap();
+            expired_callbacks_count += canister.system_state.time_out_callbacks(
+                current_time,
+                &canister_id,
+                &self.canister_states,
+            );
+            self.canister_states.insert(canister_id, canister);
+        }
+
+        expired_callbacks_count
+    }
+
+    /// Sheds best-effort messages (largest first) until the message memory usage
+    /// of every canister is at most `canister_message_memory_capacity` and the
+    /// total message memory usage is at most `subnet_message_memory_capacity`;
+    /// or there are no more best-effort messages to shed. The callbacks of shed
+    /// messages are rejected with `SYS_UNKNOWN` reject responses.
+    ///
+    /// Returns the number of messages shed.
+    ///
+    /// See [`crate::SystemState::shed_largest_best_effort_message`] for further
+    /// details.
+    pub fn shed_best_effort_messages(
+        &mut self,
+        subnet_message_memory_capacity: NumBytes,
+        canister_message_memory_capacity: NumBytes,
+    ) -> u64 {
+        let mut shed_messages_count = 0;
+
+        // Enforce the per-canister limit first. Same as for `time_out_requests()`,
+        // only remove-call-replace the canisters that are over the limit.
+        let canister_ids_over_limit = self
+            .canister_states
+            .iter()
+            .filter(|(_, canister_state)| {
+                canister_state.system_state.message_memory_usage()
+                    > canister_message_memory_capacity
+            })
+            .map(|(canister_id, _)| *canister_id)
+            .collect::<Vec<_>>();
+        for canister_id in canister_ids_over_limit {
+            let mut canister = self.canister_states.remove(&canister_id).unwrap();
+            while canister.system_state.message_memory_usage() > canister_message_memory_capacity
+                && canister
+                    .system_state
+                    .shed_largest_best_effort_message(&canister_id, &self.canister_states)
+            {
+                shed_messages_count += 1;
+            }
+            self.canister_states.insert(canister_id, canister);
+        }
+
+        // Then shed the largest best-effort messages across all canisters, until
+        // under the subnet-wide limit.
+        while self.message_memory_taken() > subnet_message_memory_capacity {
+            let canister_id = match self
+                .canister_states
+                .iter()
+                .filter_map(|(canister_id, canister_state)| {
+                    canister_state
+                        .system_state
+                        .queues()
+                        .largest_best_effort_message_bytes()
+                        .map(|bytes| (bytes, *canister_id))
+                })
+                .max()
+            {
+                Some((_, canister_id)) => canister_id,
+                None => break,
+            };
+
+            let mut canister = self.canister_states.remove(&canister_id).unwrap();
+            canister
+                .system_state
+                .shed_largest_best_effort_message(&canister_id, &self.canister_states);
+            self.canister_states.insert(canister_id, canister);
+            shed_messages_count += 1;
+        }
+
+        shed_messages_count
+    }
+
     /// Splits the replicated state as part of subnet splitting phase 1, retaining
     /// only the canisters of `subnet_id` (as determined by the provided routing
     /// table).
@@ -923,6 +1037,7 @@ impl ReplicatedState {
             mut subnet_queues,
             consensus_queue,
             epoch_query_stats: _,
+            mut canister_snapshots,
         } = self;
 
         // Consensus queue is always empty at the end of the round.
@@ -934,6 +1049,11 @@ impl ReplicatedState {
         canister_states
             .retain(|canister_id, _| routing_table.route(canister_id.get()) == Some(subnet_id));
 
+        // Snapshots follow the canisters they belong to.
+        canister_snapshots.retain_canisters(|canister_id| {
+            routing_table.route(canister_id.get()) == Some(subnet_id)
+        });
+
         // All subnet messages (ingress and canister) only remain on subnet A' because:
         //
         //  * Message Routing would drop a response from subnet B to a request it had
@@ -958,6 +1078,7 @@ impl ReplicatedState {
             subnet_queues,
             consensus_queue,
             epoch_query_stats: RawQueryStats::default(), // Don't preserve query stats during subnet splitting.
+            canister_snapshots,
         })
     }
 
@@ -980,6 +1101,7 @@ impl ReplicatedState {
             ref mut subnet_queues,
             consensus_queue: _,
             epoch_query_stats: _,
+            canister_snapshots: _,
         } = self;
 
         // Reset query stats after subnet split
@@ -1140,6 +1262,8 @@ pub mod testing {
             subnet_queues: Default::default(),
             consensus_queue: Default::default(),
             epoch_query_stats: Default::default(),
+            // Covered in `split()`, snapshots are retained based on the routing table.
+            canister_snapshots: Default::default(),
         };
     }
 }
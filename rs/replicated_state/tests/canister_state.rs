@@ -1,6 +1,8 @@
+use assert_matches::assert_matches;
 use ic_base_types::CanisterId;
+use ic_error_types::RejectCode;
 use ic_registry_subnet_type::SubnetType;
-use ic_replicated_state::{CanisterState, InputQueueType, StateError};
+use ic_replicated_state::{CallOrigin, CanisterState, InputQueueType, StateError};
 use ic_test_utilities::{
     state::{get_running_canister, get_stopped_canister, get_stopping_canister, register_callback},
     types::ids::canister_test_id,
@@ -8,9 +10,12 @@ use ic_test_utilities::{
 };
 use ic_test_utilities_time::mock_time;
 use ic_types::{
-    messages::{CallbackId, Request, RequestOrResponse},
+    messages::{CallbackId, CanisterMessage, Payload, Request, RequestMetadata, RequestOrResponse},
+    methods::{Callback, WasmClosure},
     xnet::QueueId,
+    CoarseTime, Cycles, Time,
 };
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 const CANISTER_ID: CanisterId = CanisterId::from_u64(0);
@@ -46,6 +51,33 @@ fn output_request_to(canister_id: CanisterId, callback_id: CallbackId) -> Reques
         .build()
 }
 
+fn best_effort_request_to(
+    canister_id: CanisterId,
+    callback_id: CallbackId,
+    deadline: CoarseTime,
+) -> Request {
+    RequestBuilder::new()
+        .sender(CANISTER_ID)
+        .receiver(canister_id)
+        .sender_reply_callback(callback_id)
+        .deadline(deadline)
+        .build()
+}
+
+fn best_effort_response_from(
+    respondent: CanisterId,
+    callback_id: CallbackId,
+    deadline: CoarseTime,
+) -> RequestOrResponse {
+    ResponseBuilder::new()
+        .originator(CANISTER_ID)
+        .respondent(respondent)
+        .originator_reply_callback(callback_id)
+        .deadline(deadline)
+        .build()
+        .into()
+}
+
 /// Fixture for `CanisterState` for use in tests. This always assumes two canisters,
 /// one with a canister id set to `CANISTER_ID` and a remote canister with canister id
 /// set to `OTHER_CANISTER_ID`. The remote canister is always assumed to be located on
@@ -105,6 +137,51 @@ impl CanisterFixture {
         iter.pop()
     }
 
+    /// Registers a best-effort callback for a call to `respondent` and makes
+    /// the matching output request and input queue reservation.
+    fn make_best_effort_call(
+        &mut self,
+        respondent: CanisterId,
+        deadline: CoarseTime,
+    ) -> CallbackId {
+        let call_context_manager = self
+            .canister_state
+            .system_state
+            .call_context_manager_mut()
+            .unwrap();
+        let call_context_id = call_context_manager.new_call_context(
+            CallOrigin::CanisterUpdate(respondent, CallbackId::from(0), deadline),
+            Cycles::zero(),
+            Time::from_nanos_since_unix_epoch(0),
+            RequestMetadata::new(0, mock_time()),
+        );
+        let callback_id = call_context_manager.register_callback(Callback::new(
+            call_context_id,
+            CANISTER_ID,
+            respondent,
+            Cycles::zero(),
+            Cycles::new(42),
+            Cycles::new(84),
+            WasmClosure::new(0, 2),
+            WasmClosure::new(0, 2),
+            None,
+            deadline,
+        ));
+
+        self.push_output_request(best_effort_request_to(respondent, callback_id, deadline))
+            .unwrap();
+        self.pop_output().unwrap();
+        callback_id
+    }
+
+    fn time_out_callbacks(&mut self, current_time: Time) -> u64 {
+        self.canister_state.system_state.time_out_callbacks(
+            current_time,
+            &CANISTER_ID,
+            &BTreeMap::new(),
+        )
+    }
+
     fn with_input_reservation(&mut self) {
         self.push_output_request(output_request_to(
             OTHER_CANISTER_ID,
@@ -254,3 +331,94 @@ fn validate_responses_against_callback_details() {
         .push_input(input_response_from(canister_b_id, callback_id_1))
         .unwrap();
 }
+
+#[test]
+fn time_out_callbacks_consumes_response_reservations() {
+    const CALL_COUNT: usize = 5;
+    let mut fixture = CanisterFixture::running();
+    let deadline = CoarseTime::from_secs_since_unix_epoch(10);
+
+    let callback_ids: Vec<_> = (0..CALL_COUNT)
+        .map(|_| fixture.make_best_effort_call(OTHER_CANISTER_ID, deadline))
+        .collect();
+    let queues = fixture.canister_state.system_state.queues();
+    assert_eq!(CALL_COUNT, queues.reserved_slots());
+    assert!(queues.memory_usage() > 0);
+
+    // Nothing times out before the deadline.
+    assert_eq!(
+        0,
+        fixture.time_out_callbacks(Time::from_nanos_since_unix_epoch(9_000_000_000))
+    );
+
+    // All calls time out at the deadline, each reject response consuming a reservation.
+    let current_time = Time::from(deadline);
+    assert_eq!(CALL_COUNT as u64, fixture.time_out_callbacks(current_time));
+    assert_eq!(
+        0,
+        fixture
+            .canister_state
+            .system_state
+            .queues()
+            .reserved_slots()
+    );
+    // Timing out again is a no-op.
+    assert_eq!(0, fixture.time_out_callbacks(current_time));
+
+    for callback_id in &callback_ids {
+        match fixture.canister_state.pop_input() {
+            Some(CanisterMessage::Response(response)) => {
+                assert_eq!(*callback_id, response.originator_reply_callback);
+                match &response.response_payload {
+                    Payload::Reject(context) => {
+                        assert_eq!(RejectCode::SysTransient, context.code())
+                    }
+                    Payload::Data(_) => panic!("Expected a reject response"),
+                }
+            }
+            msg => panic!("Expected a response, got {:?}", msg),
+        }
+    }
+
+    // No reserved slots or memory are left behind.
+    let queues = fixture.canister_state.system_state.queues();
+    assert_eq!(0, queues.reserved_slots());
+    assert_eq!(0, queues.memory_usage());
+
+    // Late responses are rejected.
+    for callback_id in callback_ids {
+        let response = best_effort_response_from(OTHER_CANISTER_ID, callback_id, deadline);
+        assert_matches!(
+            fixture.push_input(response),
+            Err((StateError::NonMatchingResponse { .. }, _))
+        );
+    }
+    let queues = fixture.canister_state.system_state.queues();
+    assert_eq!(0, queues.reserved_slots());
+    assert_eq!(0, queues.memory_usage());
+}
+
+#[test]
+fn time_out_callbacks_skips_callbacks_with_enqueued_responses() {
+    let mut fixture = CanisterFixture::running();
+    let deadline = CoarseTime::from_secs_since_unix_epoch(10);
+
+    let callback_id = fixture.make_best_effort_call(OTHER_CANISTER_ID, deadline);
+    let response = best_effort_response_from(OTHER_CANISTER_ID, callback_id, deadline);
+    fixture.push_input(response.clone()).unwrap();
+
+    // The actual response was enqueued before the deadline, no reject is generated.
+    assert_eq!(0, fixture.time_out_callbacks(Time::from(deadline)));
+    assert_eq!(
+        Some(CanisterMessage::Response(match response {
+            RequestOrResponse::Response(response) => response,
+            RequestOrResponse::Request(_) => unreachable!(),
+        })),
+        fixture.canister_state.pop_input()
+    );
+    assert_eq!(None, fixture.canister_state.pop_input());
+    assert_eq!(
+        0,
+        fixture.canister_state.system_state.queues().memory_usage()
+    );
+}
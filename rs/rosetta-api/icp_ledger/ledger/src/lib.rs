@@ -6,7 +6,10 @@ use ic_ledger_canister_core::ledger::{
     self as core_ledger, LedgerContext, LedgerData, TransactionInfo,
 };
 use ic_ledger_core::{
-    approvals::AllowanceTable, balances::Balances, block::EncodedBlock, timestamp::TimeStamp,
+    approvals::{AllowanceTable, HeapAllowancesData},
+    balances::Balances,
+    block::EncodedBlock,
+    timestamp::TimeStamp,
 };
 use ic_ledger_core::{block::BlockIndex, tokens::Tokens};
 use ic_ledger_hash_of::HashOf;
@@ -64,7 +67,7 @@ fn unknown_token() -> String {
 pub struct Ledger {
     pub balances: LedgerBalances,
     #[serde(default)]
-    pub approvals: AllowanceTable<ApprovalKey, AccountIdentifier, Tokens>,
+    pub approvals: AllowanceTable<HeapAllowancesData<ApprovalKey, AccountIdentifier, Tokens>>,
     pub blockchain: Blockchain<dfn_runtime::DfnRuntime, IcpLedgerArchiveWasm>,
     // A cap on the maximum number of accounts.
     pub maximum_number_of_accounts: usize,
@@ -109,7 +112,7 @@ pub struct Ledger {
 
 impl LedgerContext for Ledger {
     type AccountId = AccountIdentifier;
-    type Approvals = AllowanceTable<ApprovalKey, Self::AccountId, Tokens>;
+    type Approvals = AllowanceTable<HeapAllowancesData<ApprovalKey, Self::AccountId, Tokens>>;
     type BalancesStore = BTreeMap<AccountIdentifier, Tokens>;
     type Tokens = Tokens;
 
@@ -176,20 +179,35 @@ impl LedgerData for Ledger {
         &mut self.blockchain
     }
 
-    fn transactions_by_hash(&self) -> &BTreeMap<HashOf<Self::Transaction>, BlockIndex> {
-        &self.transactions_by_hash
+    fn get_transaction_by_hash(&self, hash: &HashOf<Self::Transaction>) -> Option<BlockIndex> {
+        self.transactions_by_hash.get(hash).copied()
     }
 
-    fn transactions_by_hash_mut(&mut self) -> &mut BTreeMap<HashOf<Self::Transaction>, BlockIndex> {
-        &mut self.transactions_by_hash
+    fn insert_transaction_by_hash(&mut self, hash: HashOf<Self::Transaction>, height: BlockIndex) {
+        self.transactions_by_hash.insert(hash, height);
     }
 
-    fn transactions_by_height(&self) -> &VecDeque<TransactionInfo<Self::Transaction>> {
-        &self.transactions_by_height
+    fn remove_transaction_by_hash(
+        &mut self,
+        hash: &HashOf<Self::Transaction>,
+    ) -> Option<BlockIndex> {
+        self.transactions_by_hash.remove(hash)
+    }
+
+    fn transactions_by_height_len(&self) -> usize {
+        self.transactions_by_height.len()
+    }
+
+    fn get_transaction_by_height(&self, i: usize) -> Option<TransactionInfo<Self::Transaction>> {
+        self.transactions_by_height.get(i).cloned()
     }
 
-    fn transactions_by_height_mut(&mut self) -> &mut VecDeque<TransactionInfo<Self::Transaction>> {
-        &mut self.transactions_by_height
+    fn push_transaction_by_height(&mut self, tx_info: TransactionInfo<Self::Transaction>) {
+        self.transactions_by_height.push_back(tx_info);
+    }
+
+    fn pop_transaction_by_height(&mut self) -> Option<TransactionInfo<Self::Transaction>> {
+        self.transactions_by_height.pop_front()
     }
 
     fn on_purged_transaction(&mut self, height: BlockIndex) {
@@ -420,10 +438,6 @@ impl Ledger {
         self.transactions_by_hash.len()
     }
 
-    pub fn transactions_by_height_len(&self) -> usize {
-        self.transactions_by_height.len()
-    }
-
     pub fn transfer_fee(&self) -> TransferFee {
         TransferFee {
             transfer_fee: self.transfer_fee,
@@ -15,7 +15,7 @@ use ic_ledger_canister_core::{
     archive::{Archive, ArchiveOptions},
     ledger::{
         apply_transaction, archive_blocks, block_locations, find_block_in_archive, LedgerAccess,
-        TransferError as CoreTransferError,
+        LedgerData, TransferError as CoreTransferError,
     },
     range_utils,
 };
@@ -37,6 +37,13 @@ use icp_ledger::{
 use icrc_ledger_types::icrc1::transfer::TransferArg;
 use icrc_ledger_types::icrc2::allowance::{Allowance, AllowanceArgs};
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use icrc_ledger_types::icrc21::{
+    consent_message::{build_icrc21_consent_info_with_account_formatter, TokenInfo},
+    errors::{ErrorInfo, Icrc21Error},
+    requests::ConsentMessageRequest,
+    responses::ConsentInfo,
+};
+use icrc_ledger_types::icrc4::transfer_batch::{TransferBatchArgs, TransferBatchResults};
 use icrc_ledger_types::{
     icrc::generic_metadata_value::MetadataValue as Value, icrc3::archive::QueryArchiveFn,
 };
@@ -55,6 +62,14 @@ use std::{
     time::Duration,
 };
 
+/// The maximum number of transfers in a single `icrc4_transfer_batch` call.
+const MAX_TRANSFERS_PER_BATCH: usize = 100;
+
+/// The number of instructions after which `icrc4_transfer_batch` stops
+/// processing a batch, leaving headroom below the update instruction limit for
+/// archiving. The remaining transfers of the batch are reported as `None`.
+const MAX_INSTRUCTIONS_PER_BATCH: u64 = 10_000_000_000;
+
 #[derive(Clone)]
 struct DebugOutSink;
 
@@ -249,7 +264,7 @@ async fn send(
     Ok(height)
 }
 
-async fn icrc1_send(
+fn icrc1_send_not_async(
     memo: Option<icrc_ledger_types::icrc1::transfer::Memo>,
     amount: Nat,
     fee: Option<Nat>,
@@ -346,6 +361,27 @@ async fn icrc1_send(
 
         block_index
     };
+    Ok(block_index)
+}
+
+async fn icrc1_send(
+    memo: Option<icrc_ledger_types::icrc1::transfer::Memo>,
+    amount: Nat,
+    fee: Option<Nat>,
+    from_account: Account,
+    to_account: Account,
+    spender_account: Option<Account>,
+    created_at_time: Option<u64>,
+) -> Result<BlockIndex, CoreTransferError<Tokens>> {
+    let block_index = icrc1_send_not_async(
+        memo,
+        amount,
+        fee,
+        from_account,
+        to_account,
+        spender_account,
+        created_at_time,
+    )?;
 
     let max_msg_size = *MAX_MESSAGE_SIZE_BYTES.read().unwrap();
     archive_blocks::<Access>(DebugOutSink, max_msg_size as u64).await;
@@ -592,9 +628,26 @@ fn icrc1_supported_standards() -> Vec<StandardRecord> {
             url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2".to_string(),
         });
     }
+    standards.push(StandardRecord {
+        name: "ICRC-4".to_string(),
+        url: "https://github.com/dfinity/ICRC/blob/main/ICRCs/ICRC-4/ICRC-4.md".to_string(),
+    });
+    standards.push(StandardRecord {
+        name: "ICRC-10".to_string(),
+        url: "https://github.com/dfinity/ICRC/blob/main/ICRCs/ICRC-10/ICRC-10.md".to_string(),
+    });
+    standards.push(StandardRecord {
+        name: "ICRC-21".to_string(),
+        url: "https://github.com/dfinity/wg-identity-authentication/blob/main/topics/ICRC-21/icrc_21_consent_msg.md".to_string(),
+    });
     standards
 }
 
+#[candid_method(query, rename = "icrc10_supported_standards")]
+fn icrc10_supported_standards() -> Vec<StandardRecord> {
+    icrc1_supported_standards()
+}
+
 #[candid_method(query, rename = "icrc1_minting_account")]
 fn icrc1_minting_account() -> Option<Account> {
     LEDGER.read().unwrap().icrc1_minting_account
@@ -916,6 +969,84 @@ fn icrc1_transfer_candid() {
     })
 }
 
+#[candid_method(update, rename = "icrc4_transfer_batch")]
+async fn icrc4_transfer_batch(args: TransferBatchArgs) -> TransferBatchResults {
+    if args.len() > MAX_TRANSFERS_PER_BATCH {
+        trap_with(&format!(
+            "the batch contains {} transfers, at most {} are allowed",
+            args.len(),
+            MAX_TRANSFERS_PER_BATCH
+        ));
+    }
+    // Reject malformed batches before applying any transfer: a trap in the
+    // middle of the batch would roll back the transfers that succeeded.
+    if args
+        .iter()
+        .filter_map(|arg| arg.memo.as_ref())
+        .any(|memo| memo.0.len() > MEMO_SIZE_BYTES)
+    {
+        trap_with("the memo field is too large");
+    }
+
+    let caller = caller();
+    let results: TransferBatchResults = args
+        .into_iter()
+        .map(|arg| {
+            if dfn_core::api::performance_counter(0) > MAX_INSTRUCTIONS_PER_BATCH {
+                return None;
+            }
+            let from_account = Account {
+                owner: caller.into(),
+                subaccount: arg.from_subaccount,
+            };
+            let result = icrc1_send_not_async(
+                arg.memo,
+                arg.amount,
+                arg.fee,
+                from_account,
+                arg.to,
+                None,
+                arg.created_at_time,
+            )
+            .map(Nat::from)
+            .map_err(convert_transfer_error)
+            .map_err(|err| {
+                let err: icrc_ledger_types::icrc1::transfer::TransferError = match err.try_into() {
+                    Ok(err) => err,
+                    Err(err) => trap_with(&err),
+                };
+                err
+            });
+            Some(result)
+        })
+        .collect();
+
+    let max_msg_size = *MAX_MESSAGE_SIZE_BYTES.read().unwrap();
+    archive_blocks::<Access>(DebugOutSink, max_msg_size as u64).await;
+    results
+}
+
+#[export_name = "canister_update icrc4_transfer_batch"]
+fn icrc4_transfer_batch_candid() {
+    over_async_may_reject(candid_one, |args: TransferBatchArgs| async {
+        if !LEDGER.read().unwrap().can_send(&caller()) {
+            return Err("Anonymous principal cannot hold tokens on the ledger.".to_string());
+        }
+
+        Ok(icrc4_transfer_batch(args).await)
+    })
+}
+
+#[candid_method(query, rename = "icrc4_maximum_update_batch_size")]
+fn icrc4_maximum_update_batch_size() -> Option<Nat> {
+    Some(Nat::from(MAX_TRANSFERS_PER_BATCH))
+}
+
+#[export_name = "canister_query icrc4_maximum_update_batch_size"]
+fn icrc4_maximum_update_batch_size_candid() {
+    over(candid_one, |()| icrc4_maximum_update_batch_size())
+}
+
 #[candid_method(update, rename = "icrc2_transfer_from")]
 async fn icrc2_transfer_from(arg: TransferFromArgs) -> Result<Nat, TransferFromError> {
     if !LEDGER.read().unwrap().feature_flags.icrc2 {
@@ -1151,6 +1282,11 @@ fn icrc1_supported_standards_candid() {
     over(candid_one, |()| icrc1_supported_standards())
 }
 
+#[export_name = "canister_query icrc10_supported_standards"]
+fn icrc10_supported_standards_candid() {
+    over(candid_one, |()| icrc10_supported_standards())
+}
+
 #[candid_method(query, rename = "query_blocks")]
 fn query_blocks(GetBlocksArgs { start, length }: GetBlocksArgs) -> QueryBlocksResponse {
     let ledger = LEDGER.read().unwrap();
@@ -1499,6 +1635,46 @@ fn icrc2_allowance_candid() {
     over(candid_one, icrc2_allowance)
 }
 
+#[candid_method(update, rename = "icrc21_canister_call_consent_message")]
+fn icrc21_canister_call_consent_message(
+    consent_msg_request: ConsentMessageRequest,
+) -> Result<ConsentInfo, Icrc21Error> {
+    let ledger = LEDGER.read().unwrap();
+    let icrc2_method = matches!(
+        consent_msg_request.method.as_str(),
+        "icrc2_approve" | "icrc2_transfer_from"
+    );
+    if icrc2_method && !ledger.feature_flags.icrc2 {
+        return Err(Icrc21Error::UnsupportedCanisterCall(ErrorInfo {
+            description: "ICRC-2 features are not enabled on the ledger.".to_string(),
+        }));
+    }
+    let token = TokenInfo {
+        symbol: ledger.token_symbol.clone(),
+        decimals: DECIMAL_PLACES as u8,
+        fee: Nat::from(ledger.transfer_fee.get_e8s()),
+    };
+    // Many ICP users only know their account identifier, so show it next to
+    // the ICRC-1 textual representation of each account.
+    build_icrc21_consent_info_with_account_formatter(
+        consent_msg_request,
+        caller().0,
+        token,
+        |account| {
+            format!(
+                "{} (account identifier {})",
+                account,
+                AccountIdentifier::from(*account)
+            )
+        },
+    )
+}
+
+#[export_name = "canister_update icrc21_canister_call_consent_message"]
+fn icrc21_canister_call_consent_message_candid() {
+    over(candid_one, icrc21_canister_call_consent_message)
+}
+
 candid::export_service!();
 
 #[export_name = "canister_query __get_candid_interface_tmp_hack"]
@@ -21,6 +21,7 @@ use icrc_ledger_types::icrc1::{
 };
 use icrc_ledger_types::icrc2::allowance::AllowanceArgs;
 use icrc_ledger_types::icrc2::approve::ApproveArgs;
+use icrc_ledger_types::icrc21::responses::ConsentMessage;
 use num_traits::cast::ToPrimitive;
 use on_wire::{FromWire, IntoWire};
 use serde_bytes::ByteBuf;
@@ -160,6 +161,11 @@ fn test_single_transfer() {
     ic_icrc1_ledger_sm_tests::test_single_transfer(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_transfer_batch() {
+    ic_icrc1_ledger_sm_tests::test_transfer_batch(ledger_wasm(), encode_init_args);
+}
+
 #[ignore = "requires fix for FI-541"]
 #[test]
 fn test_tx_deduplication() {
@@ -498,6 +504,52 @@ fn test_block_transformation() {
     }
 }
 
+#[test]
+fn test_icrc21_consent_message() {
+    ic_icrc1_ledger_sm_tests::test_icrc21_consent_message(ledger_wasm(), encode_init_args);
+}
+
+#[test]
+fn test_icrc21_consent_message_shows_account_identifiers() {
+    let from = Account::from(PrincipalId::new_user_test_id(1).0);
+    let to = Account {
+        owner: PrincipalId::new_user_test_id(2).0,
+        subaccount: Some([7; 32]),
+    };
+    let (env, canister_id) =
+        ic_icrc1_ledger_sm_tests::setup(ledger_wasm(), encode_init_args, vec![]);
+
+    let transfer_arg = TransferArg {
+        from_subaccount: None,
+        to,
+        fee: None,
+        created_at_time: None,
+        memo: None,
+        amount: Nat::from(100_000_000_u64),
+    };
+    let info = ic_icrc1_ledger_sm_tests::icrc21_consent_message(
+        &env,
+        canister_id,
+        from.owner,
+        "icrc1_transfer",
+        Encode!(&transfer_arg).unwrap(),
+    )
+    .expect("failed to build the icrc1_transfer consent message");
+    let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+        panic!("expected a generic display message");
+    };
+    assert!(message.contains(&format!(
+        "**From:**\n{} (account identifier {})",
+        from,
+        AccountIdentifier::from(from)
+    )));
+    assert!(message.contains(&format!(
+        "**To:**\n{} (account identifier {})",
+        to,
+        AccountIdentifier::from(to)
+    )));
+}
+
 #[test]
 fn test_approve_smoke() {
     ic_icrc1_ledger_sm_tests::test_approve_smoke(ledger_wasm(), encode_init_args);
@@ -620,7 +672,10 @@ fn test_feature_flags() {
         standards.push(standard.name);
     }
     standards.sort();
-    assert_eq!(standards, vec!["ICRC-1", "ICRC-2"]);
+    assert_eq!(
+        standards,
+        vec!["ICRC-1", "ICRC-10", "ICRC-2", "ICRC-21", "ICRC-4"]
+    );
 
     let block_index =
         send_approval(&env, canister_id, from.0, &approve_args).expect("approval failed");
@@ -8,8 +8,13 @@ use ic_stable_structures::{
     cell::Cell as StableCell, log::Log as StableLog, memory_manager::MemoryManager,
     storable::Bound, DefaultMemoryImpl, RestrictedMemory, Storable,
 };
+use icrc_ledger_types::icrc3::archive::{GetArchivesArgs, GetArchivesResult};
 use icrc_ledger_types::icrc3::blocks::BlockRange;
 use icrc_ledger_types::icrc3::blocks::GenericBlock as IcrcBlock;
+use icrc_ledger_types::icrc3::blocks::{
+    icrc1_and_icrc2_supported_block_types, BlockWithId, GetBlocksArgs, GetBlocksResult,
+    SupportedBlockType,
+};
 
 use icrc_ledger_types::icrc3::transactions::Transaction;
 use icrc_ledger_types::icrc3::transactions::{GetTransactionsRequest, TransactionRange};
@@ -273,6 +278,58 @@ fn get_blocks(req: GetTransactionsRequest) -> BlockRange {
     BlockRange { blocks }
 }
 
+/// Get the blocks in the requested ranges as defined by the ICRC-3 standard.
+#[query]
+#[candid_method(query)]
+fn icrc3_get_blocks(reqs: GetBlocksArgs) -> GetBlocksResult {
+    let (block_index_offset, max_blocks) =
+        with_archive_opts(|opts| (opts.block_index_offset, opts.max_transactions_per_response));
+    let log_length = block_index_offset + with_blocks(|blocks| blocks.len());
+    let mut blocks = vec![];
+    for req in reqs {
+        // Unlike the legacy endpoints, requests (or parts of requests) for blocks
+        // that this archive does not store are answered with no blocks.
+        let Ok((start, length)) = req.as_start_and_length() else {
+            continue;
+        };
+        let end = start.saturating_add(length).min(log_length);
+        let start = start.max(block_index_offset);
+        if start >= end {
+            continue;
+        }
+        let length = (end - start).min(max_blocks.saturating_sub(blocks.len() as u64));
+        let decoded_blocks = decode_block_range(start, length, decode_icrc1_block);
+        blocks.extend(
+            (start..)
+                .zip(decoded_blocks)
+                .map(|(id, block)| BlockWithId {
+                    id: id.into(),
+                    block: block.into(),
+                }),
+        );
+    }
+    GetBlocksResult {
+        log_length: log_length.into(),
+        blocks,
+        // Archives store all the blocks they serve locally.
+        archived_blocks: vec![],
+    }
+}
+
+/// The archive does not delegate blocks to other canisters, so the list of
+/// archives is always empty.
+#[query]
+#[candid_method(query)]
+fn icrc3_get_archives(_args: GetArchivesArgs) -> GetArchivesResult {
+    vec![]
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_supported_block_types() -> Vec<SupportedBlockType> {
+    icrc1_and_icrc2_supported_block_types()
+}
+
 #[query(hidden = true)]
 fn __get_candid_interface_tmp_hack() -> &'static str {
     include_str!(env!("ARCHIVE_DID_PATH"))
@@ -66,6 +66,75 @@ pub struct GetAccountTransactionsError {
 pub type GetAccountTransactionsResult =
     Result<GetAccountTransactionsResponse, GetAccountTransactionsError>;
 
+#[derive(CandidType, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum BalanceAt {
+    // The balance after the block with the given index.
+    BlockIndex(BlockIndex),
+    // The balance after all the blocks with a timestamp lower
+    // than or equal to the given one (in nanoseconds since
+    // the UNIX epoch).
+    Timestamp(u64),
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct GetBalanceAtArgs {
+    pub account: Account,
+    pub at: BalanceAt,
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub enum GetBalanceAtError {
+    // The requested block has not been indexed yet.
+    NotSyncedYet {
+        num_blocks_synced: BlockIndex,
+    },
+    // The index started to record the balance history after
+    // the requested block. Balances are available for blocks
+    // greater than or equal to first_block_with_history.
+    HistoryNotAvailable {
+        first_block_with_history: BlockIndex,
+    },
+}
+
+pub type GetBalanceAtResult = Result<Nat, GetBalanceAtError>;
+
+#[derive(CandidType, Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum TransactionKind {
+    Mint,
+    Burn,
+    Transfer,
+    Approve,
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct GetTransactionsInTimeRangeArgs {
+    // If set then only the transactions of this account are returned.
+    pub account: Option<Account>,
+    // The start of the time range (inclusive) in nanoseconds
+    // since the UNIX epoch.
+    pub start_time: u64,
+    // The end of the time range (exclusive) in nanoseconds
+    // since the UNIX epoch.
+    pub end_time: u64,
+    // If set then only the transactions of these kinds are returned.
+    pub kinds: Option<Vec<TransactionKind>>,
+    // The next_start returned by the previous call. If None then
+    // the results will start from the most recent transaction
+    // in the time range. The start txid is not included.
+    pub start: Option<BlockIndex>,
+    // Maximum number of transactions to examine.
+    pub max_results: Nat,
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct GetTransactionsInTimeRangeResponse {
+    // The transactions in the time range, most recent first.
+    pub transactions: Vec<TransactionWithId>,
+    // If set then there may be more transactions in the time
+    // range. Pass this value as start to fetch them.
+    pub next_start: Option<BlockIndex>,
+}
+
 #[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
 pub struct ListSubaccountsArgs {
     pub owner: Principal,
@@ -9,9 +9,11 @@ use ic_crypto_sha2::Sha256;
 use ic_icrc1::blocks::{encoded_block_to_generic_block, generic_block_to_encoded_block};
 use ic_icrc1::{Block, Operation};
 use ic_icrc1_index_ng::{
-    FeeCollectorRanges, GetAccountTransactionsArgs, GetAccountTransactionsResponse,
-    GetAccountTransactionsResult, IndexArg, ListSubaccountsArgs, Log, LogEntry, Status,
-    TransactionWithId, DEFAULT_MAX_BLOCKS_PER_RESPONSE,
+    BalanceAt, FeeCollectorRanges, GetAccountTransactionsArgs, GetAccountTransactionsResponse,
+    GetAccountTransactionsResult, GetBalanceAtArgs, GetBalanceAtError, GetBalanceAtResult,
+    GetTransactionsInTimeRangeArgs, GetTransactionsInTimeRangeResponse, IndexArg,
+    ListSubaccountsArgs, Log, LogEntry, Status, TransactionKind, TransactionWithId,
+    DEFAULT_MAX_BLOCKS_PER_RESPONSE,
 };
 use ic_ledger_core::block::{BlockIndex as BlockIndex64, BlockType, EncodedBlock};
 use ic_ledger_core::tokens::{CheckedAdd, CheckedSub, Zero};
@@ -51,6 +53,7 @@ const BLOCK_LOG_INDEX_MEMORY_ID: MemoryId = MemoryId::new(1);
 const BLOCK_LOG_DATA_MEMORY_ID: MemoryId = MemoryId::new(2);
 const ACCOUNT_BLOCK_IDS_MEMORY_ID: MemoryId = MemoryId::new(3);
 const ACCOUNT_DATA_MEMORY_ID: MemoryId = MemoryId::new(4);
+const ACCOUNT_BALANCE_CHECKPOINTS_MEMORY_ID: MemoryId = MemoryId::new(5);
 
 const DEFAULT_MAX_WAIT_TIME: Duration = Duration::from_secs(1);
 
@@ -73,6 +76,12 @@ type AccountBlockIdsMap = StableBTreeMap<AccountBlockIdsMapKey, (), VM>;
 type AccountDataMapKey = (AccountDataType, (Blob<29>, [u8; 32]));
 type AccountDataMap = StableBTreeMap<AccountDataMapKey, Tokens, VM>;
 
+// The balance of an account after a block that changed it. Like the
+// block ids, the block indexes are stored in reverse order so that
+// the most recent checkpoint before a given block is the first one
+// returned by a range query.
+type AccountBalanceCheckpointsMap = StableBTreeMap<AccountBlockIdsMapKey, Tokens, VM>;
+
 thread_local! {
     /// Static memory manager to manage the memory available for stable structures.
     static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> = RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
@@ -100,6 +109,12 @@ thread_local! {
         RefCell::new(AccountDataMap::init(memory_manager.get(ACCOUNT_DATA_MEMORY_ID)))
     });
 
+    /// Map that contains the balance of an account after each block that
+    /// changed it. The account is hashed to save space.
+    static ACCOUNT_BALANCE_CHECKPOINTS: RefCell<AccountBalanceCheckpointsMap> = with_memory_manager(|memory_manager| {
+        RefCell::new(AccountBalanceCheckpointsMap::init(memory_manager.get(ACCOUNT_BALANCE_CHECKPOINTS_MEMORY_ID)))
+    });
+
     /// Profiling data to understand cycles usage
     static PROFILING_DATA: RefCell<SpanStats> = RefCell::new(SpanStats::default());
 }
@@ -123,6 +138,12 @@ struct State {
 
     /// This fee is used if no fee nor effetive_fee is found in Approve blocks.
     pub last_fee: Option<Tokens>,
+
+    /// The number of blocks that were indexed when the index started to
+    /// record balance checkpoints. Balances are known for every block
+    /// from this one minus one onwards.
+    #[serde(default)]
+    balance_history_start: Option<BlockIndex64>,
 }
 
 // NOTE: the default configuration is dysfunctional, but it's convenient to have
@@ -136,6 +157,7 @@ impl Default for State {
             last_wait_time: Duration::from_secs(0),
             fee_collectors: Default::default(),
             last_fee: None,
+            balance_history_start: None,
         }
     }
 }
@@ -232,6 +254,11 @@ fn with_account_data<R>(f: impl FnOnce(&mut AccountDataMap) -> R) -> R {
     ACCOUNT_DATA.with(|cell| f(&mut cell.borrow_mut()))
 }
 
+/// A helper function to access the account balance checkpoints.
+fn with_balance_checkpoints<R>(f: impl FnOnce(&mut AccountBalanceCheckpointsMap) -> R) -> R {
+    ACCOUNT_BALANCE_CHECKPOINTS.with(|cell| f(&mut cell.borrow_mut()))
+}
+
 /// A helper function that returns a decoded block stored in the
 /// block log at the given index or None if there is no block at that index.
 /// This function can trap if the index at the given block cannot be decoded
@@ -260,6 +287,40 @@ fn change_balance(account: Account, f: impl FnOnce(Tokens) -> Tokens) {
     with_account_data(|account_data| account_data.insert(key, new_balance));
 }
 
+/// A helper function to change the balance of an account as a result of
+/// the block at the given index. It also records a checkpoint with the new
+/// balance of the account.
+fn change_balance_at(
+    block_index: BlockIndex64,
+    account: Account,
+    f: impl FnOnce(Tokens) -> Tokens,
+) {
+    let account_hash = account_sha256(account);
+    let history_start = with_state(|state| state.balance_history_start.unwrap_or_default());
+    if history_start > 0 && !has_balance_checkpoints(account_hash) {
+        // The index started to record checkpoints after this account was
+        // last changed. Record the balance it had at that time first.
+        let previous_balance = get_balance(account);
+        with_balance_checkpoints(|checkpoints| {
+            checkpoints.insert((account_hash, Reverse(history_start - 1)), previous_balance)
+        });
+    }
+    change_balance(account, f);
+    let new_balance = get_balance(account);
+    with_balance_checkpoints(|checkpoints| {
+        checkpoints.insert((account_hash, Reverse(block_index)), new_balance)
+    });
+}
+
+fn has_balance_checkpoints(account_hash: [u8; Sha256::DIGEST_LEN]) -> bool {
+    with_balance_checkpoints(|checkpoints| {
+        checkpoints
+            .range((account_hash, Reverse(u64::MAX))..)
+            .next()
+            .map_or(false, |((hash, _), _)| hash == account_hash)
+    })
+}
+
 fn balance_key(account: Account) -> (AccountDataType, (Blob<29>, [u8; 32])) {
     let owner = Blob::try_from(account.owner.as_slice()).unwrap();
     (
@@ -279,6 +340,7 @@ fn init(index_arg: Option<IndexArg>) {
     // stable memory initialization
     mutate_state(|state| {
         state.ledger_id = init_arg.ledger_id;
+        state.balance_history_start = Some(0);
     });
 
     // set the first build_index to be called after init
@@ -331,6 +393,20 @@ fn post_upgrade(index_arg: Option<IndexArg>) {
         _ => (),
     };
 
+    // Indexes installed before balance checkpoints were introduced record
+    // them only for the blocks indexed from now on.
+    if with_state(|state| state.balance_history_start.is_none()) {
+        let num_blocks = with_blocks(|blocks| blocks.len());
+        log!(
+            P1,
+            "Recording balance checkpoints starting from block {}",
+            num_blocks
+        );
+        mutate_state(|state| {
+            state.balance_history_start = Some(num_blocks);
+        });
+    }
+
     // set the first build_index to be called after init
     set_build_index_timer(DEFAULT_MAX_WAIT_TIME);
 }
@@ -594,7 +670,7 @@ fn process_balance_changes(block_index: BlockIndex64, block: &Block<Tokens>) {
 }
 
 fn debit(block_index: BlockIndex64, account: Account, amount: Tokens) {
-    change_balance(account, |balance| {
+    change_balance_at(block_index, account, |balance| {
         balance.checked_sub(&amount).unwrap_or_else(|| {
             ic_cdk::trap(&format!("Block {} caused an underflow for account {} when calculating balance {} - amount {}",
                 block_index, account, balance, amount));
@@ -603,7 +679,7 @@ fn debit(block_index: BlockIndex64, account: Account, amount: Tokens) {
 }
 
 fn credit(block_index: BlockIndex64, account: Account, amount: Tokens) {
-    change_balance(account, |balance| {
+    change_balance_at(block_index, account, |balance| {
         balance.checked_add(&amount).unwrap_or_else(|| {
             ic_cdk::trap(&format!("Block {} caused an overflow for account {} when calculating balance {} + amount {}",
                 block_index, account, balance, amount))
@@ -641,6 +717,15 @@ fn get_accounts(block: &Block<Tokens>) -> Vec<Account> {
     }
 }
 
+fn get_transaction_kind(block: &Block<Tokens>) -> TransactionKind {
+    match block.transaction.operation {
+        Operation::Burn { .. } => TransactionKind::Burn,
+        Operation::Mint { .. } => TransactionKind::Mint,
+        Operation::Transfer { .. } => TransactionKind::Transfer,
+        Operation::Approve { .. } => TransactionKind::Approve,
+    }
+}
+
 fn get_fee_collector(block_index: BlockIndex64, block: &Block<Tokens>) -> Option<Account> {
     if block.fee_collector.is_some() {
         block.fee_collector
@@ -792,6 +877,138 @@ fn icrc1_balance_of(account: Account) -> Nat {
     get_balance(account).into()
 }
 
+/// Returns the number of indexed blocks with a timestamp strictly lower
+/// than the given one. Block timestamps are monotonic so this is a
+/// binary search over the block log.
+fn num_blocks_before(timestamp: u64) -> u64 {
+    let (mut lo, mut hi) = (0, with_blocks(|blocks| blocks.len()));
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let block = get_decoded_block(mid)
+            .unwrap_or_else(|| trap(&format!("Block {} not found in the block log", mid)));
+        if block.timestamp < timestamp {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+fn get_balance_at_block(
+    account: Account,
+    block_index: BlockIndex64,
+) -> Result<Tokens, GetBalanceAtError> {
+    let num_blocks_synced = with_blocks(|blocks| blocks.len());
+    if block_index >= num_blocks_synced {
+        return Err(GetBalanceAtError::NotSyncedYet {
+            num_blocks_synced: num_blocks_synced.into(),
+        });
+    }
+    let history_start = with_state(|state| state.balance_history_start.unwrap_or_default());
+    if block_index.saturating_add(1) < history_start {
+        return Err(GetBalanceAtError::HistoryNotAvailable {
+            first_block_with_history: (history_start - 1).into(),
+        });
+    }
+    let account_hash = account_sha256(account);
+    let checkpoint = with_balance_checkpoints(|checkpoints| {
+        checkpoints
+            .range((account_hash, Reverse(block_index))..)
+            .next()
+            .filter(|((hash, _), _)| *hash == account_hash)
+            .map(|(_, balance)| balance)
+    });
+    Ok(match checkpoint {
+        Some(balance) => balance,
+        // The account has checkpoints only after the requested block,
+        // so it didn't hold any tokens at that block.
+        None if has_balance_checkpoints(account_hash) => Tokens::zero(),
+        // The balance of the account didn't change since the index
+        // started to record checkpoints.
+        None => get_balance(account),
+    })
+}
+
+#[query]
+#[candid_method(query)]
+fn get_balance_at(args: GetBalanceAtArgs) -> GetBalanceAtResult {
+    let block_index = match args.at {
+        BalanceAt::BlockIndex(block_index) => block_index.0.to_u64().unwrap_or(u64::MAX),
+        BalanceAt::Timestamp(timestamp) => {
+            match num_blocks_before(timestamp.saturating_add(1)).checked_sub(1) {
+                Some(block_index) => block_index,
+                // No account had any token before the first block.
+                None => return Ok(Nat::from(0u8)),
+            }
+        }
+    };
+    get_balance_at_block(args.account, block_index).map(Nat::from)
+}
+
+#[query]
+#[candid_method(query)]
+fn get_transactions_in_time_range(
+    arg: GetTransactionsInTimeRangeArgs,
+) -> GetTransactionsInTimeRangeResponse {
+    let length = arg
+        .max_results
+        .0
+        .to_u64()
+        .expect("The length must be a u64!")
+        .min(with_state(|opts| opts.max_blocks_per_response))
+        .min(usize::MAX as u64) as usize;
+    let first_block = num_blocks_before(arg.start_time);
+    let start = arg
+        .start
+        .map_or(u64::MAX, |n| n.0.to_u64().expect("start must be a u64!"));
+    let end_block = num_blocks_before(arg.end_time).min(start);
+    if length == 0 || end_block <= first_block {
+        return GetTransactionsInTimeRangeResponse {
+            transactions: vec![],
+            next_start: None,
+        };
+    }
+
+    let indices: Vec<BlockIndex64> = match arg.account {
+        Some(account) => {
+            let key = account_block_ids_key(account, end_block - 1);
+            with_account_block_ids(|account_block_ids| {
+                account_block_ids
+                    .range(key..)
+                    .take_while(|(k, _)| k.0 == key.0 && k.1 .0 >= first_block)
+                    .take(length)
+                    .map(|(k, _)| k.1 .0)
+                    .collect()
+            })
+        }
+        None => (first_block..end_block).rev().take(length).collect(),
+    };
+    let next_start = match indices.last() {
+        Some(&oldest) if indices.len() == length && oldest > first_block => Some(oldest.into()),
+        _ => None,
+    };
+
+    let mut transactions = vec![];
+    for id in indices {
+        let block = get_decoded_block(id)
+            .unwrap_or_else(|| trap(&format!("Block {} not found in the block log", id)));
+        if let Some(kinds) = &arg.kinds {
+            if !kinds.contains(&get_transaction_kind(&block)) {
+                continue;
+            }
+        }
+        transactions.push(TransactionWithId {
+            id: id.into(),
+            transaction: block.into(),
+        });
+    }
+    GetTransactionsInTimeRangeResponse {
+        transactions,
+        next_start,
+    }
+}
+
 #[query]
 #[candid_method(query)]
 fn status() -> Status {
@@ -888,6 +1105,11 @@ pub fn encode_metrics(w: &mut ic_metrics_encoder::MetricsEncoder<Vec<u8>>) -> st
         with_blocks(|blocks| blocks.len()) as f64,
         "Total number of blocks stored in the stable memory.",
     )?;
+    w.encode_gauge(
+        "index_number_of_balance_checkpoints",
+        with_balance_checkpoints(|checkpoints| checkpoints.len()) as f64,
+        "Total number of account balance checkpoints stored in the stable memory.",
+    )?;
     w.encode_gauge(
         "index_last_wait_time",
         with_state(|state| state.last_wait_time)
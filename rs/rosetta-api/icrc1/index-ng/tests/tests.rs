@@ -5,9 +5,11 @@ use ic_canisters_http_types::{HttpRequest, HttpResponse};
 use ic_icrc1::blocks::generic_block_to_encoded_block;
 use ic_icrc1::Block;
 use ic_icrc1_index_ng::{
-    FeeCollectorRanges, GetAccountTransactionsArgs, GetAccountTransactionsResponse,
-    GetAccountTransactionsResult, GetBlocksResponse, IndexArg, InitArg as IndexInitArg,
-    ListSubaccountsArgs, Log, Status, TransactionWithId, DEFAULT_MAX_BLOCKS_PER_RESPONSE,
+    BalanceAt, FeeCollectorRanges, GetAccountTransactionsArgs, GetAccountTransactionsResponse,
+    GetAccountTransactionsResult, GetBalanceAtArgs, GetBalanceAtError, GetBalanceAtResult,
+    GetBlocksResponse, GetTransactionsInTimeRangeArgs, GetTransactionsInTimeRangeResponse,
+    IndexArg, InitArg as IndexInitArg, ListSubaccountsArgs, Log, Status, TransactionKind,
+    TransactionWithId, DEFAULT_MAX_BLOCKS_PER_RESPONSE,
 };
 use ic_icrc1_ledger::{
     ChangeFeeCollector, FeatureFlags, InitArgsBuilder as LedgerInitArgsBuilder, LedgerArgument,
@@ -485,6 +487,42 @@ fn get_account_transactions(
         .expect("Failed to perform GetAccountTransactionsArgs")
 }
 
+fn get_balance_at(
+    env: &StateMachine,
+    index_id: CanisterId,
+    account: Account,
+    at: BalanceAt,
+) -> GetBalanceAtResult {
+    let req =
+        Encode!(&GetBalanceAtArgs { account, at }).expect("Failed to encode GetBalanceAtArgs");
+    let res = env
+        .execute_ingress(index_id, "get_balance_at", req)
+        .expect("Failed to get_balance_at")
+        .bytes();
+    Decode!(&res, GetBalanceAtResult).expect("Failed to decode GetBalanceAtResult")
+}
+
+fn get_transactions_in_time_range(
+    env: &StateMachine,
+    index_id: CanisterId,
+    arg: GetTransactionsInTimeRangeArgs,
+) -> GetTransactionsInTimeRangeResponse {
+    let req = Encode!(&arg).expect("Failed to encode GetTransactionsInTimeRangeArgs");
+    let res = env
+        .execute_ingress(index_id, "get_transactions_in_time_range", req)
+        .expect("Failed to get_transactions_in_time_range")
+        .bytes();
+    Decode!(&res, GetTransactionsInTimeRangeResponse)
+        .expect("Failed to decode GetTransactionsInTimeRangeResponse")
+}
+
+fn time_nanos(env: &StateMachine) -> u64 {
+    env.time()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64
+}
+
 fn list_subaccounts(
     env: &StateMachine,
     index: CanisterId,
@@ -1481,3 +1519,181 @@ fn test_principal_subaccounts() {
     // The subaccount 1 should show up in a `list_subaccount` query although it has only been involved in an Approve transaction
     assert!(subaccounts.contains(&account(2, 1).subaccount.unwrap()));
 }
+
+#[test]
+fn test_get_balance_at() {
+    let env = &StateMachine::new();
+    let minter = minter_identity().sender().unwrap();
+    let ledger_id = install_ledger(
+        env,
+        vec![(account(1, 0), 1_000_000)],
+        default_archive_options(),
+        None,
+        minter,
+    );
+    let index_id = install_index_ng(env, ledger_id);
+    wait_until_sync_is_completed(env, index_id, ledger_id);
+    let time_0 = time_nanos(env);
+    env.advance_time(Duration::from_secs(1));
+
+    // Block 1
+    transfer(env, ledger_id, account(1, 0), account(2, 0), 100_000);
+    wait_until_sync_is_completed(env, index_id, ledger_id);
+    let time_1 = time_nanos(env);
+    env.advance_time(Duration::from_secs(1));
+
+    // Block 2
+    transfer(env, ledger_id, account(2, 0), account(3, 0), 50_000);
+    // Block 3
+    approve(env, ledger_id, account(1, 0), account(3, 0), 10);
+    wait_until_sync_is_completed(env, index_id, ledger_id);
+
+    let balance_at = |account: Account, at: BalanceAt| {
+        get_balance_at(env, index_id, account, at)
+            .expect("Failed to get the balance")
+            .0
+            .to_u64()
+            .unwrap()
+    };
+    let block = |n: u64| BalanceAt::BlockIndex(n.into());
+
+    let expected_balances = [
+        // account(1, 0), account(2, 0), account(3, 0)
+        [1_000_000, 0, 0],
+        [1_000_000 - 100_000 - FEE, 100_000, 0],
+        [1_000_000 - 100_000 - FEE, 100_000 - 50_000 - FEE, 50_000],
+        [
+            1_000_000 - 100_000 - 2 * FEE,
+            100_000 - 50_000 - FEE,
+            50_000,
+        ],
+    ];
+    for (block_index, balances) in expected_balances.iter().enumerate() {
+        for (i, balance) in balances.iter().enumerate() {
+            let account = account(i as u64 + 1, 0);
+            assert_eq!(balance_at(account, block(block_index as u64)), *balance);
+        }
+    }
+    // The balance at the latest block matches the current balance.
+    for i in 1..=3 {
+        assert_eq!(
+            balance_at(account(i, 0), block(3)),
+            icrc1_balance_of(env, index_id, account(i, 0))
+        );
+    }
+
+    // Balances by timestamp.
+    assert_eq!(balance_at(account(1, 0), BalanceAt::Timestamp(0)), 0);
+    assert_eq!(
+        balance_at(account(1, 0), BalanceAt::Timestamp(time_0)),
+        1_000_000
+    );
+    assert_eq!(
+        balance_at(account(2, 0), BalanceAt::Timestamp(time_1)),
+        100_000
+    );
+    assert_eq!(balance_at(account(3, 0), BalanceAt::Timestamp(time_1)), 0);
+    assert_eq!(
+        balance_at(account(3, 0), BalanceAt::Timestamp(u64::MAX)),
+        50_000
+    );
+
+    assert_eq!(
+        get_balance_at(env, index_id, account(1, 0), block(4)),
+        Err(GetBalanceAtError::NotSyncedYet {
+            num_blocks_synced: 4_u64.into()
+        })
+    );
+}
+
+#[test]
+fn test_get_transactions_in_time_range() {
+    let env = &StateMachine::new();
+    let minter = minter_identity().sender().unwrap();
+    let ledger_id = install_ledger(
+        env,
+        vec![(account(1, 0), 1_000_000)],
+        default_archive_options(),
+        None,
+        minter,
+    );
+    let index_id = install_index_ng(env, ledger_id);
+    wait_until_sync_is_completed(env, index_id, ledger_id);
+    let time_0 = time_nanos(env);
+    env.advance_time(Duration::from_secs(1));
+
+    // Block 1
+    transfer(env, ledger_id, account(1, 0), account(2, 0), 100_000);
+    // Block 2
+    approve(env, ledger_id, account(1, 0), account(3, 0), 10);
+    // Block 3
+    transfer(env, ledger_id, account(2, 0), account(3, 0), 50_000);
+    wait_until_sync_is_completed(env, index_id, ledger_id);
+    let time_1 = time_nanos(env);
+    env.advance_time(Duration::from_secs(1));
+
+    // Block 4
+    transfer(env, ledger_id, account(1, 0), account(3, 0), 1);
+    wait_until_sync_is_completed(env, index_id, ledger_id);
+
+    let ids = |res: &GetTransactionsInTimeRangeResponse| -> Vec<u64> {
+        res.transactions
+            .iter()
+            .map(|tx| tx.id.0.to_u64().unwrap())
+            .collect()
+    };
+    let args = |account: Option<Account>, kinds: Option<Vec<TransactionKind>>| {
+        GetTransactionsInTimeRangeArgs {
+            account,
+            start_time: time_0 + 1,
+            end_time: time_1 + 1,
+            kinds,
+            start: None,
+            max_results: 100_u64.into(),
+        }
+    };
+
+    let res = get_transactions_in_time_range(env, index_id, args(None, None));
+    assert_eq!(ids(&res), vec![3, 2, 1]);
+    assert_eq!(res.next_start, None);
+
+    let res = get_transactions_in_time_range(
+        env,
+        index_id,
+        args(None, Some(vec![TransactionKind::Approve])),
+    );
+    assert_eq!(ids(&res), vec![2]);
+    assert_eq!(res.transactions[0].transaction.kind, "approve");
+
+    let res = get_transactions_in_time_range(env, index_id, args(Some(account(3, 0)), None));
+    assert_eq!(ids(&res), vec![3]);
+
+    let res = get_transactions_in_time_range(
+        env,
+        index_id,
+        args(Some(account(1, 0)), Some(vec![TransactionKind::Transfer])),
+    );
+    assert_eq!(ids(&res), vec![1]);
+
+    // Paginate over the time range one block at a time.
+    let mut arg = GetTransactionsInTimeRangeArgs {
+        max_results: 1_u64.into(),
+        ..args(None, None)
+    };
+    let mut paginated_ids = vec![];
+    loop {
+        let res = get_transactions_in_time_range(env, index_id, arg);
+        paginated_ids.extend(ids(&res));
+        match res.next_start {
+            Some(next_start) => {
+                arg = GetTransactionsInTimeRangeArgs {
+                    start: Some(next_start),
+                    max_results: 1_u64.into(),
+                    ..args(None, None)
+                }
+            }
+            None => break,
+        }
+    }
+    assert_eq!(paginated_ids, vec![3, 2, 1]);
+}
@@ -1,5 +1,6 @@
 use candid::{CandidType, Decode, Encode, Int, Nat, Principal};
 use ic_base_types::PrincipalId;
+use ic_crypto_tree_hash::{LookupStatus, MixedHashTree};
 use ic_error_types::UserError;
 use ic_ic00_types::{self as ic00, CanisterInfoRequest, CanisterInfoResponse, Method, Payload};
 use ic_icrc1::blocks::encoded_block_to_generic_block;
@@ -12,22 +13,32 @@ use ic_state_machine_tests::{CanisterId, ErrorCode, StateMachine, WasmResult};
 use ic_types::Cycles;
 use ic_universal_canister::{call_args, wasm, UNIVERSAL_CANISTER_WASM};
 use icrc_ledger_types::icrc::generic_metadata_value::MetadataValue as Value;
+use icrc_ledger_types::icrc::generic_value::ICRC3Value;
 use icrc_ledger_types::icrc::generic_value::Value as GenericValue;
 use icrc_ledger_types::icrc1::account::{Account, Subaccount};
 use icrc_ledger_types::icrc1::transfer::{Memo, TransferArg, TransferError};
 use icrc_ledger_types::icrc2::allowance::{Allowance, AllowanceArgs};
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
 use icrc_ledger_types::icrc2::transfer_from::{TransferFromArgs, TransferFromError};
+use icrc_ledger_types::icrc21::{
+    errors::{ErrorInfo, Icrc21Error},
+    requests::{ConsentMessageMetadata, ConsentMessageRequest, ConsentMessageSpec},
+    responses::{ConsentInfo, ConsentMessage},
+};
 use icrc_ledger_types::icrc3;
-use icrc_ledger_types::icrc3::archive::ArchiveInfo;
+use icrc_ledger_types::icrc3::archive::{ArchiveInfo, GetArchivesArgs, GetArchivesResult};
 use icrc_ledger_types::icrc3::blocks::BlockRange;
 use icrc_ledger_types::icrc3::blocks::GenericBlock as IcrcBlock;
 use icrc_ledger_types::icrc3::blocks::GetBlocksResponse;
+use icrc_ledger_types::icrc3::blocks::{
+    DataCertificate, GetBlocksRequest, GetBlocksResult, ICRC3DataCertificate, SupportedBlockType,
+};
 use icrc_ledger_types::icrc3::transactions::GetTransactionsRequest;
 use icrc_ledger_types::icrc3::transactions::GetTransactionsResponse;
 use icrc_ledger_types::icrc3::transactions::Transaction as Tx;
 use icrc_ledger_types::icrc3::transactions::TransactionRange;
 use icrc_ledger_types::icrc3::transactions::Transfer;
+use icrc_ledger_types::icrc4::transfer_batch::{TransferBatchArgs, TransferBatchResults};
 use num_traits::ToPrimitive;
 use proptest::prelude::*;
 use proptest::test_runner::{Config as TestRunnerConfig, TestCaseResult, TestRunner};
@@ -201,6 +212,39 @@ fn send_transfer(
     .map(|n| n.0.to_u64().unwrap())
 }
 
+fn send_transfer_batch(
+    env: &StateMachine,
+    ledger: CanisterId,
+    from: Principal,
+    args: &TransferBatchArgs,
+) -> Result<TransferBatchResults, UserError> {
+    env.execute_ingress_as(
+        PrincipalId(from),
+        ledger,
+        "icrc4_transfer_batch",
+        Encode!(args).unwrap(),
+    )
+    .map(|res| {
+        Decode!(&res.bytes(), TransferBatchResults)
+            .expect("failed to decode icrc4_transfer_batch response")
+    })
+}
+
+fn maximum_update_batch_size(env: &StateMachine, ledger: CanisterId) -> Option<u64> {
+    Decode!(
+        &env.query(
+            ledger,
+            "icrc4_maximum_update_batch_size",
+            Encode!().unwrap()
+        )
+        .expect("failed to query icrc4_maximum_update_batch_size")
+        .bytes(),
+        Option<Nat>
+    )
+    .expect("failed to decode icrc4_maximum_update_batch_size response")
+    .map(|n| n.0.to_u64().unwrap())
+}
+
 pub fn system_time_to_nanos(t: SystemTime) -> u64 {
     t.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_nanos() as u64
 }
@@ -353,6 +397,50 @@ fn get_archive_blocks(
     get_transactions_as(env, archive, start, length, "get_blocks".to_string())
 }
 
+fn icrc3_get_archives(
+    env: &StateMachine,
+    ledger: CanisterId,
+    from: Option<Principal>,
+) -> GetArchivesResult {
+    Decode!(
+        &env.query(
+            ledger,
+            "icrc3_get_archives",
+            Encode!(&GetArchivesArgs { from }).unwrap()
+        )
+        .expect("failed to query icrc3_get_archives")
+        .bytes(),
+        GetArchivesResult
+    )
+    .expect("failed to decode icrc3_get_archives response")
+}
+
+fn icrc3_get_blocks(
+    env: &StateMachine,
+    canister: Principal,
+    method: &str,
+    ranges: Vec<(u64, u64)>,
+) -> GetBlocksResult {
+    let args: Vec<_> = ranges
+        .into_iter()
+        .map(|(start, length)| GetBlocksRequest {
+            start: Nat::from(start),
+            length: Nat::from(length),
+        })
+        .collect();
+    Decode!(
+        &env.query(
+            CanisterId::unchecked_from_principal(canister.into()),
+            method,
+            Encode!(&args).unwrap()
+        )
+        .expect("failed to query icrc3_get_blocks")
+        .bytes(),
+        GetBlocksResult
+    )
+    .expect("failed to decode icrc3_get_blocks response")
+}
+
 fn get_phash(block: &IcrcBlock) -> Result<Option<Hash>, String> {
     match block {
         IcrcBlock::Map(map) => {
@@ -751,7 +839,10 @@ where
         standards.push(standard.name);
     }
     standards.sort();
-    assert_eq!(standards, vec!["ICRC-1", "ICRC-2"]);
+    assert_eq!(
+        standards,
+        vec!["ICRC-1", "ICRC-10", "ICRC-2", "ICRC-21", "ICRC-4"]
+    );
 }
 pub fn test_metadata<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
 where
@@ -819,7 +910,10 @@ where
         standards.push(standard.name);
     }
     standards.sort();
-    assert_eq!(standards, vec!["ICRC-1", "ICRC-2"]);
+    assert_eq!(
+        standards,
+        vec!["ICRC-1", "ICRC-10", "ICRC-2", "ICRC-21", "ICRC-3", "ICRC-4"]
+    );
 }
 
 pub fn test_total_supply<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
@@ -873,6 +967,78 @@ where
     assert_eq!(6_000_000u64, balance_of(&env, canister_id, p2.0));
 }
 
+pub fn test_transfer_batch<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let p1 = PrincipalId::new_user_test_id(1);
+    let p2 = PrincipalId::new_user_test_id(2);
+    let p3 = PrincipalId::new_user_test_id(3);
+    let (env, canister_id) = setup(
+        ledger_wasm,
+        encode_init_args,
+        vec![(Account::from(p1.0), 10_000_000)],
+    );
+    let now = system_time_to_nanos(env.time());
+    let transfer_arg = |to: Principal, amount: u64| TransferArg {
+        from_subaccount: None,
+        to: to.into(),
+        fee: None,
+        created_at_time: None,
+        amount: Nat::from(amount),
+        memo: None,
+    };
+
+    let batch = vec![
+        transfer_arg(p2.0, 1_000_000),
+        TransferArg {
+            fee: Some(Nat::from(FEE + 1)),
+            ..transfer_arg(p3.0, 1_000_000)
+        },
+        transfer_arg(p3.0, 100_000_000),
+        TransferArg {
+            created_at_time: Some(now),
+            ..transfer_arg(p3.0, 2_000_000)
+        },
+        TransferArg {
+            created_at_time: Some(now),
+            ..transfer_arg(p3.0, 2_000_000)
+        },
+    ];
+    let results = send_transfer_batch(&env, canister_id, p1.0, &batch).expect("batch failed");
+    // Each successful transfer gets its own block, failed transfers don't
+    // affect the others.
+    assert_eq!(
+        results,
+        vec![
+            Some(Ok(Nat::from(1_u64))),
+            Some(Err(TransferError::BadFee {
+                expected_fee: Nat::from(FEE)
+            })),
+            Some(Err(TransferError::InsufficientFunds {
+                balance: Nat::from(9_000_000 - FEE)
+            })),
+            Some(Ok(Nat::from(2_u64))),
+            Some(Err(TransferError::Duplicate {
+                duplicate_of: Nat::from(2_u64)
+            })),
+        ]
+    );
+    assert_eq!(
+        balance_of(&env, canister_id, p1.0),
+        10_000_000 - 3_000_000 - 2 * FEE
+    );
+    assert_eq!(balance_of(&env, canister_id, p2.0), 1_000_000);
+    assert_eq!(balance_of(&env, canister_id, p3.0), 2_000_000);
+
+    // Batches above the maximum size are rejected as a whole.
+    let max_batch_size = maximum_update_batch_size(&env, canister_id)
+        .expect("the ledger should report a maximum batch size");
+    let batch = vec![transfer_arg(p2.0, 1); max_batch_size as usize + 1];
+    assert!(send_transfer_batch(&env, canister_id, p1.0, &batch).is_err());
+    assert_eq!(balance_of(&env, canister_id, p2.0), 1_000_000);
+}
+
 pub fn test_tx_deduplication<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
 where
     T: CandidType,
@@ -1565,6 +1731,293 @@ where
     assert_eq!(0, missing_blocks_reply.archived_blocks.len());
 }
 
+pub fn test_icrc3_get_blocks<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let p1 = PrincipalId::new_user_test_id(1);
+    let p2 = PrincipalId::new_user_test_id(2);
+
+    let (env, canister_id) = setup(
+        ledger_wasm,
+        encode_init_args,
+        vec![(Account::from(p1.0), 10_000_000)],
+    );
+
+    for i in 0..ARCHIVE_TRIGGER_THRESHOLD {
+        transfer(&env, canister_id, p1.0, p2.0, 10_000 + i * 10_000).expect("transfer failed");
+    }
+
+    env.run_until_completion(/*max_ticks=*/ 10);
+
+    let archives = icrc3_get_archives(&env, canister_id, None);
+    assert_eq!(archives.len(), 1);
+    assert_eq!(archives[0].start, Nat::from(0_u8));
+    assert_eq!(archives[0].end, Nat::from(NUM_BLOCKS_TO_ARCHIVE - 1));
+    let archive_principal = archives[0].canister_id;
+    assert_eq!(
+        icrc3_get_archives(&env, canister_id, Some(archive_principal)),
+        vec![]
+    );
+
+    let resp = icrc3_get_blocks(
+        &env,
+        canister_id.get().0,
+        "icrc3_get_blocks",
+        vec![(0, 1_000_000)],
+    );
+    let log_length = ARCHIVE_TRIGGER_THRESHOLD + 1;
+    assert_eq!(resp.log_length, Nat::from(log_length));
+    let local_ids: Vec<_> = resp.blocks.iter().map(|b| b.id.clone()).collect();
+    let expected_local_ids: Vec<_> = (NUM_BLOCKS_TO_ARCHIVE..log_length).map(Nat::from).collect();
+    assert_eq!(local_ids, expected_local_ids);
+    assert_eq!(resp.archived_blocks.len(), 1);
+    let archived = &resp.archived_blocks[0];
+    assert_eq!(archived.callback.canister_id, archive_principal);
+    assert_eq!(
+        archived.args,
+        vec![GetBlocksRequest {
+            start: Nat::from(0_u8),
+            length: Nat::from(NUM_BLOCKS_TO_ARCHIVE),
+        }]
+    );
+
+    let archived_resp = icrc3_get_blocks(
+        &env,
+        archived.callback.canister_id,
+        &archived.callback.method,
+        vec![(0, NUM_BLOCKS_TO_ARCHIVE)],
+    );
+    assert!(archived_resp.archived_blocks.is_empty());
+
+    // Check that the archive skips the blocks it does not store instead of trapping.
+    let out_of_range_resp = icrc3_get_blocks(
+        &env,
+        archived.callback.canister_id,
+        &archived.callback.method,
+        vec![
+            (NUM_BLOCKS_TO_ARCHIVE, 5),
+            (u64::MAX, u64::MAX),
+            (NUM_BLOCKS_TO_ARCHIVE - 1, 5),
+        ],
+    );
+    let ids: Vec<_> = out_of_range_resp
+        .blocks
+        .iter()
+        .map(|b| b.id.clone())
+        .collect();
+    assert_eq!(ids, vec![Nat::from(NUM_BLOCKS_TO_ARCHIVE - 1)]);
+    assert_eq!(
+        out_of_range_resp.log_length,
+        Nat::from(NUM_BLOCKS_TO_ARCHIVE)
+    );
+    let too_large_start_resp = Decode!(
+        &env.query(
+            CanisterId::unchecked_from_principal(archive_principal.into()),
+            "icrc3_get_blocks",
+            Encode!(&vec![GetBlocksRequest {
+                start: Nat::from(u128::MAX),
+                length: Nat::from(1_u8),
+            }])
+            .unwrap()
+        )
+        .expect("failed to query icrc3_get_blocks")
+        .bytes(),
+        GetBlocksResult
+    )
+    .expect("failed to decode icrc3_get_blocks response");
+    assert!(too_large_start_resp.blocks.is_empty());
+
+    // Check that the ledger clamps out-of-range requests instead of trapping.
+    let out_of_range_resp = Decode!(
+        &env.query(
+            canister_id,
+            "icrc3_get_blocks",
+            Encode!(&vec![
+                GetBlocksRequest {
+                    start: Nat::from(log_length - 1),
+                    length: Nat::from(u128::MAX),
+                },
+                GetBlocksRequest {
+                    start: Nat::from(u64::MAX),
+                    length: Nat::from(u64::MAX),
+                },
+                GetBlocksRequest {
+                    start: Nat::from(u128::MAX),
+                    length: Nat::from(1_u8),
+                },
+            ])
+            .unwrap()
+        )
+        .expect("failed to query icrc3_get_blocks")
+        .bytes(),
+        GetBlocksResult
+    )
+    .expect("failed to decode icrc3_get_blocks response");
+    let ids: Vec<_> = out_of_range_resp
+        .blocks
+        .iter()
+        .map(|b| b.id.clone())
+        .collect();
+    assert_eq!(ids, vec![Nat::from(log_length - 1)]);
+    assert_eq!(out_of_range_resp.log_length, Nat::from(log_length));
+    assert!(out_of_range_resp.archived_blocks.is_empty());
+
+    // Check that the ICRC-3 blocks match the legacy blocks and form a hash chain.
+    let legacy_resp = get_blocks(&env, canister_id.get().0, 0, 1_000_000);
+    let legacy_archived_blocks =
+        get_archive_blocks(&env, archive_principal, 0, NUM_BLOCKS_TO_ARCHIVE as usize).blocks;
+    let legacy_blocks: Vec<_> = legacy_archived_blocks
+        .into_iter()
+        .chain(legacy_resp.blocks)
+        .collect();
+    let blocks: Vec<_> = archived_resp
+        .blocks
+        .into_iter()
+        .chain(resp.blocks)
+        .collect();
+    assert_eq!(blocks.len(), legacy_blocks.len());
+
+    let mut prev_hash = None;
+    for (i, (block, legacy_block)) in blocks.into_iter().zip(legacy_blocks).enumerate() {
+        assert_eq!(block.id, Nat::from(i));
+        assert_eq!(block.block, ICRC3Value::from(legacy_block.clone()));
+        assert_eq!(block.block.hash(), legacy_block.hash());
+        assert_eq!(
+            prev_hash,
+            get_phash(&legacy_block).expect("cannot get the hash of the previous block")
+        );
+        prev_hash = Some(block.block.hash());
+    }
+
+    // Check that the ranges are served in order and that missing blocks are skipped.
+    let resp = icrc3_get_blocks(
+        &env,
+        canister_id.get().0,
+        "icrc3_get_blocks",
+        vec![(log_length - 1, 1), (100, 5), (log_length - 2, 1)],
+    );
+    let ids: Vec<_> = resp.blocks.iter().map(|b| b.id.clone()).collect();
+    assert_eq!(
+        ids,
+        vec![Nat::from(log_length - 1), Nat::from(log_length - 2)]
+    );
+    assert!(resp.archived_blocks.is_empty());
+
+    let tip_certificate = Decode!(
+        &env.query(canister_id, "icrc3_get_tip_certificate", Encode!().unwrap())
+            .expect("failed to query icrc3_get_tip_certificate")
+            .bytes(),
+        Option<ICRC3DataCertificate>
+    )
+    .expect("failed to decode icrc3_get_tip_certificate response")
+    .expect("the ledger should return a tip certificate");
+    let data_certificate = Decode!(
+        &env.query(canister_id, "get_data_certificate", Encode!().unwrap())
+            .expect("failed to query get_data_certificate")
+            .bytes(),
+        DataCertificate
+    )
+    .expect("failed to decode get_data_certificate response");
+    assert_eq!(tip_certificate.hash_tree, data_certificate.hash_tree);
+
+    let block_types: Vec<_> = Decode!(
+        &env.query(
+            canister_id,
+            "icrc3_supported_block_types",
+            Encode!().unwrap()
+        )
+        .expect("failed to query icrc3_supported_block_types")
+        .bytes(),
+        Vec<SupportedBlockType>
+    )
+    .expect("failed to decode icrc3_supported_block_types response")
+    .into_iter()
+    .map(|t| t.block_type)
+    .collect();
+    assert_eq!(
+        block_types,
+        vec!["1burn", "1mint", "1xfer", "2approve", "2xfer"]
+    );
+}
+
+/// Checks the tip certificate of a ledger. Ledgers built with the
+/// `icrc3-compatible-data-certificate` feature certify the labels specified by
+/// ICRC-3, other ledgers certify the legacy `tip_hash` and big-endian
+/// `last_block_index` labels.
+pub fn test_icrc3_get_tip_certificate<T>(
+    ledger_wasm: Vec<u8>,
+    encode_init_args: fn(InitArgs) -> T,
+    icrc3_compatible_data_certificate: bool,
+) where
+    T: CandidType,
+{
+    let p1 = PrincipalId::new_user_test_id(1);
+    let p2 = PrincipalId::new_user_test_id(2);
+
+    let (env, canister_id) = setup(
+        ledger_wasm,
+        encode_init_args,
+        vec![(Account::from(p1.0), 10_000_000)],
+    );
+
+    let check_tip_certificate = |env: &StateMachine| {
+        let tip_certificate = Decode!(
+            &env.query(canister_id, "icrc3_get_tip_certificate", Encode!().unwrap())
+                .expect("failed to query icrc3_get_tip_certificate")
+                .bytes(),
+            Option<ICRC3DataCertificate>
+        )
+        .expect("failed to decode icrc3_get_tip_certificate response")
+        .expect("the ledger should return a tip certificate");
+
+        let hash_tree: MixedHashTree =
+            ciborium::de::from_reader(tip_certificate.hash_tree.as_slice())
+                .expect("failed to decode the certified hash tree");
+        ic_certification::verify_certified_data(
+            tip_certificate.certificate.as_slice(),
+            &canister_id,
+            &env.root_key(),
+            &hash_tree.digest().0,
+        )
+        .expect("the tip certificate should certify the hash tree");
+
+        let resp = get_blocks(env, canister_id.get().0, 0, 1_000_000);
+        let last_block_index = resp.chain_length - 1;
+        let last_block = resp
+            .blocks
+            .last()
+            .expect("the ledger should have at least one block");
+
+        let (last_block_index_encoded, last_block_hash_label) = if icrc3_compatible_data_certificate
+        {
+            let mut last_block_index_encoded = vec![];
+            leb128::write::unsigned(&mut last_block_index_encoded, last_block_index).unwrap();
+            (last_block_index_encoded, b"last_block_hash".as_slice())
+        } else {
+            (
+                last_block_index.to_be_bytes().to_vec(),
+                b"tip_hash".as_slice(),
+            )
+        };
+        assert_eq!(
+            hash_tree.lookup(&[b"last_block_index"]),
+            LookupStatus::Found(&MixedHashTree::Leaf(last_block_index_encoded))
+        );
+        assert_eq!(
+            hash_tree.lookup(&[last_block_hash_label]),
+            LookupStatus::Found(&MixedHashTree::Leaf(last_block.hash().to_vec()))
+        );
+    };
+
+    check_tip_certificate(&env);
+
+    for i in 0..3 {
+        transfer(&env, canister_id, p1.0, p2.0, 10_000 + i).expect("transfer failed");
+        check_tip_certificate(&env);
+    }
+}
+
 // Generate random blocks and check that their CBOR encoding complies with the CDDL spec.
 pub fn block_encoding_agrees_with_the_schema() {
     use std::path::PathBuf;
@@ -2254,6 +2707,114 @@ pub fn default_transfer_from_args(
     }
 }
 
+pub fn icrc21_consent_message(
+    env: &StateMachine,
+    ledger: CanisterId,
+    caller: Principal,
+    method: &str,
+    arg: Vec<u8>,
+) -> Result<ConsentInfo, Icrc21Error> {
+    let request = ConsentMessageRequest {
+        method: method.to_string(),
+        arg: serde_bytes::ByteBuf::from(arg),
+        user_preferences: ConsentMessageSpec {
+            metadata: ConsentMessageMetadata {
+                language: "en".to_string(),
+                utc_offset_minutes: None,
+            },
+            device_spec: None,
+        },
+    };
+    Decode!(
+        &env.execute_ingress_as(
+            PrincipalId(caller),
+            ledger,
+            "icrc21_canister_call_consent_message",
+            Encode!(&request).unwrap()
+        )
+        .expect("failed to get the consent message")
+        .bytes(),
+        Result<ConsentInfo, Icrc21Error>
+    )
+    .expect("failed to decode icrc21_canister_call_consent_message response")
+}
+
+pub fn test_icrc21_consent_message<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let from = PrincipalId::new_user_test_id(1);
+    let to = PrincipalId::new_user_test_id(2);
+    let (env, canister_id) = setup(ledger_wasm, encode_init_args, vec![]);
+
+    let symbol = Decode!(
+        &env.query(canister_id, "icrc1_symbol", Encode!().unwrap())
+            .expect("failed to query icrc1_symbol")
+            .bytes(),
+        String
+    )
+    .expect("failed to decode icrc1_symbol response");
+
+    let transfer_arg = TransferArg {
+        from_subaccount: None,
+        to: Account::from(to.0),
+        fee: None,
+        created_at_time: None,
+        memo: None,
+        amount: Nat::from(150_000_000_u64),
+    };
+    let info = icrc21_consent_message(
+        &env,
+        canister_id,
+        from.0,
+        "icrc1_transfer",
+        Encode!(&transfer_arg).unwrap(),
+    )
+    .expect("failed to build the icrc1_transfer consent message");
+    let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+        panic!("expected a generic display message");
+    };
+    assert!(message.contains(&format!("**Amount:**\n1.5 {}", symbol)));
+    assert!(message.contains(&format!("**From:**\n{}", Account::from(from.0))));
+    assert!(message.contains(&format!("**To:**\n{}", Account::from(to.0))));
+    assert!(message.contains(&format!("**Fee:**\n0.0001 {}", symbol)));
+
+    let approve_arg = default_approve_args(to.0, 100_000_000);
+    let info = icrc21_consent_message(
+        &env,
+        canister_id,
+        from.0,
+        "icrc2_approve",
+        Encode!(&approve_arg).unwrap(),
+    )
+    .expect("failed to build the icrc2_approve consent message");
+    let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+        panic!("expected a generic display message");
+    };
+    assert!(message.contains(&format!("**Spender:**\n{}", Account::from(to.0))));
+
+    assert!(matches!(
+        icrc21_consent_message(
+            &env,
+            canister_id,
+            from.0,
+            "icrc1_balance_of",
+            Encode!(&Account::from(from.0)).unwrap(),
+        ),
+        Err(Icrc21Error::UnsupportedCanisterCall(_))
+    ));
+    assert!(matches!(
+        icrc21_consent_message(
+            &env,
+            canister_id,
+            from.0,
+            "icrc1_transfer",
+            Encode!(&approve_arg).unwrap(),
+        ),
+        Err(Icrc21Error::UnsupportedCanisterCall(_))
+    ));
+}
+
 pub fn test_approve_smoke<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
 where
     T: CandidType,
@@ -2655,9 +3216,20 @@ pub fn expect_icrc2_disabled(
         "Expected ICRC-2 disabled error, got: {}",
         err.description()
     );
+    for (method, arg) in [
+        ("icrc2_approve", Encode!(approve_args).unwrap()),
+        ("icrc2_transfer_from", Encode!(transfer_from_args).unwrap()),
+    ] {
+        assert_eq!(
+            icrc21_consent_message(env, canister_id, from.0, method, arg),
+            Err(Icrc21Error::UnsupportedCanisterCall(ErrorInfo {
+                description: "ICRC-2 features are not enabled on the ledger.".to_string()
+            }))
+        );
+    }
     let standards = supported_standards(env, canister_id);
-    assert_eq!(standards.len(), 1);
-    assert_eq!(standards[0].name, "ICRC-1");
+    assert!(standards.iter().any(|standard| standard.name == "ICRC-1"));
+    assert!(!standards.iter().any(|standard| standard.name == "ICRC-2"));
 }
 
 pub fn test_feature_flags<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
@@ -2729,7 +3301,10 @@ where
         standards.push(standard.name);
     }
     standards.sort();
-    assert_eq!(standards, vec!["ICRC-1", "ICRC-2"]);
+    assert_eq!(
+        standards,
+        vec!["ICRC-1", "ICRC-10", "ICRC-2", "ICRC-21", "ICRC-3", "ICRC-4"]
+    );
 
     let block_index =
         send_approval(&env, canister_id, from.0, &approve_args).expect("approval failed");
@@ -1,9 +1,11 @@
 pub mod cdk_runtime;
+pub mod stable_storage;
 
 #[cfg(test)]
 mod tests;
 
 use crate::cdk_runtime::CdkRuntime;
+use crate::stable_storage::{StableAllowancesData, StableBalances, StoredTransactionInfo};
 use candid::{
     types::number::{Int, Nat},
     CandidType, Principal,
@@ -20,7 +22,7 @@ use ic_ledger_canister_core::{
     range_utils,
 };
 use ic_ledger_core::{
-    approvals::AllowanceTable,
+    approvals::{AllowanceTable, Approvals, HeapAllowancesData},
     balances::Balances,
     block::{BlockIndex, BlockType, EncodedBlock, FeeCollector},
     timestamp::TimeStamp,
@@ -29,10 +31,13 @@ use ic_ledger_core::{
 use ic_ledger_hash_of::HashOf;
 use icrc_ledger_types::icrc1::account::Account;
 use icrc_ledger_types::icrc3::transactions::Transaction as Tx;
-use icrc_ledger_types::icrc3::{blocks::GetBlocksResponse, transactions::GetTransactionsResponse};
+use icrc_ledger_types::icrc3::{
+    blocks::{ArchivedBlocks, BlockWithId, GetBlocksRequest, GetBlocksResponse, GetBlocksResult},
+    transactions::GetTransactionsResponse,
+};
 use icrc_ledger_types::{
     icrc::generic_metadata_value::MetadataValue as Value,
-    icrc3::archive::{ArchivedRange, QueryBlockArchiveFn, QueryTxArchiveFn},
+    icrc3::archive::{ArchivedRange, QueryArchiveFn, QueryBlockArchiveFn, QueryTxArchiveFn},
 };
 use serde::{Deserialize, Serialize};
 use serde_bytes::ByteBuf;
@@ -41,7 +46,9 @@ use std::collections::{BTreeMap, VecDeque};
 use std::time::Duration;
 
 const TRANSACTION_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
-const MAX_ACCOUNTS: usize = 28_000_000;
+/// The maximum number of accounts that older versions of the ledger, which
+/// kept the balances on the heap, used when none was configured.
+const HEAP_LEDGER_MAX_ACCOUNTS: usize = 28_000_000;
 /// The maximum number of transactions the ledger should return for a single
 /// get_transactions request.
 const MAX_TRANSACTIONS_PER_REQUEST: usize = 2_000;
@@ -277,14 +284,24 @@ pub enum LedgerArgument {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(bound = "")]
 pub struct Ledger<Tokens: TokensType> {
+    /// Balances kept on the heap by older versions of the ledger. Empty once
+    /// the ledger is ready, see [Ledger::migrate_next_part].
     balances: LedgerBalances<Tokens>,
+    /// Allowances kept on the heap by older versions of the ledger. Empty
+    /// once the ledger is ready.
+    #[serde(default)]
+    approvals: AllowanceTable<HeapAllowancesData<ApprovalKey, Account, Tokens>>,
     #[serde(default)]
-    approvals: AllowanceTable<ApprovalKey, Account, Tokens>,
+    stable_balances: Balances<StableBalances<Tokens>>,
+    #[serde(default)]
+    stable_approvals: AllowanceTable<StableAllowancesData<Tokens>>,
     blockchain: Blockchain<CdkRuntime, Icrc1ArchiveWasm>,
 
     minting_account: Account,
     fee_collector: Option<FeeCollector<Account>>,
 
+    /// The deduplication window kept on the heap by older versions of the
+    /// ledger. Empty once the ledger is ready.
     transactions_by_hash: BTreeMap<HashOf<Transaction<Tokens>>, BlockIndex>,
     transactions_by_height: VecDeque<TransactionInfo<Transaction<Tokens>>>,
     transfer_fee: Tokens,
@@ -301,14 +318,26 @@ pub struct Ledger<Tokens: TokensType> {
     #[serde(default)]
     feature_flags: FeatureFlags,
 
-    #[serde(default = "default_maximum_number_of_accounts")]
-    maximum_number_of_accounts: usize,
+    /// The number of accounts above which the ledger trims the accounts with
+    /// the lowest balances. The number of accounts is not limited if unset.
+    #[serde(default)]
+    maximum_number_of_accounts: Option<usize>,
     #[serde(default = "default_accounts_overflow_trim_quantity")]
     accounts_overflow_trim_quantity: usize,
+
+    #[serde(default)]
+    state: LedgerState,
 }
 
-fn default_maximum_number_of_accounts() -> usize {
-    MAX_ACCOUNTS
+/// Whether the ledger is still moving the data structures that older versions
+/// of the ledger kept on the heap into stable memory.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LedgerState {
+    /// The heap data structures are being moved into stable memory. The
+    /// ledger does not serve balances, allowances or transactions.
+    Migrating,
+    #[default]
+    Ready,
 }
 
 fn default_accounts_overflow_trim_quantity() -> usize {
@@ -369,6 +398,8 @@ impl<Tokens: TokensType> Ledger<Tokens> {
         let mut ledger = Self {
             balances: LedgerBalances::default(),
             approvals: Default::default(),
+            stable_balances: Balances::default(),
+            stable_approvals: Default::default(),
             blockchain: Blockchain::new_with_archive(archive_options),
             transactions_by_hash: BTreeMap::new(),
             transactions_by_height: VecDeque::new(),
@@ -389,14 +420,12 @@ impl<Tokens: TokensType> Ledger<Tokens> {
                 .collect(),
             max_memo_length: max_memo_length.unwrap_or(DEFAULT_MAX_MEMO_LENGTH),
             feature_flags: feature_flags.unwrap_or_default(),
-            maximum_number_of_accounts: maximum_number_of_accounts
-                .unwrap_or_else(|| MAX_ACCOUNTS.try_into().unwrap())
-                .try_into()
-                .unwrap(),
+            maximum_number_of_accounts: maximum_number_of_accounts.map(|n| n.try_into().unwrap()),
             accounts_overflow_trim_quantity: accounts_overflow_trim_quantity
                 .unwrap_or_else(|| ACCOUNTS_OVERFLOW_TRIM_QUANTITY.try_into().unwrap())
                 .try_into()
                 .unwrap(),
+            state: LedgerState::Ready,
         };
 
         for (account, balance) in initial_balances.into_iter() {
@@ -417,6 +446,66 @@ impl<Tokens: TokensType> Ledger<Tokens> {
 
         ledger
     }
+
+    /// Returns true if the ledger does not keep any data structures on the
+    /// heap that must be moved into stable memory first.
+    pub fn is_ready(&self) -> bool {
+        self.state == LedgerState::Ready
+    }
+
+    /// Starts moving the balances, allowances and the deduplication window
+    /// that older versions of the ledger kept on the heap into stable memory.
+    /// Does nothing if the heap data structures are empty or if the migration
+    /// has already started.
+    pub fn start_migration(&mut self) {
+        let heap_structures_are_empty = self.balances.store.is_empty()
+            && self.approvals.len() == 0
+            && self.transactions_by_hash.is_empty()
+            && self.transactions_by_height.is_empty();
+        if self.is_ready() && !heap_structures_are_empty {
+            // Older versions stored their default limit, which only existed
+            // because the balances were kept on the heap.
+            if self.maximum_number_of_accounts == Some(HEAP_LEDGER_MAX_ACCOUNTS) {
+                self.maximum_number_of_accounts = None;
+            }
+            self.stable_balances.token_pool = self.balances.token_pool;
+            self.state = LedgerState::Migrating;
+        }
+    }
+
+    /// Moves entries of the heap data structures into stable memory one by
+    /// one until all of them are moved or `should_stop` returns true, so
+    /// that a large ledger can be migrated over multiple messages. Returns
+    /// true if the ledger is ready.
+    pub fn migrate_next_part(&mut self, should_stop: impl Fn() -> bool) -> bool {
+        while !self.is_ready() {
+            if let Some((account, balance)) = self.balances.store.pop_first() {
+                self.stable_balances.credit(&account, balance);
+            } else if let Some(((account, spender), allowance)) =
+                self.approvals.pop_first_allowance()
+            {
+                self.stable_approvals
+                    .insert_allowance(&account, &spender, allowance);
+            } else if let Some((hash, height)) = self.transactions_by_hash.pop_first() {
+                self.insert_transaction_by_hash(hash, height);
+            } else if let Some(tx_info) = self.transactions_by_height.pop_front() {
+                self.push_transaction_by_height(tx_info);
+            } else {
+                self.balances = LedgerBalances::default();
+                self.state = LedgerState::Ready;
+                break;
+            }
+            if should_stop() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns the number of transactions in the deduplication window.
+    pub fn transactions_by_hash_len(&self) -> usize {
+        stable_storage::transactions_by_hash_len()
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
@@ -436,24 +525,24 @@ impl From<ApprovalKey> for (Account, Account) {
 
 impl<Tokens: TokensType> LedgerContext for Ledger<Tokens> {
     type AccountId = Account;
-    type Approvals = AllowanceTable<ApprovalKey, Account, Tokens>;
-    type BalancesStore = BTreeMap<Self::AccountId, Tokens>;
+    type Approvals = AllowanceTable<StableAllowancesData<Tokens>>;
+    type BalancesStore = StableBalances<Tokens>;
     type Tokens = Tokens;
 
     fn balances(&self) -> &Balances<Self::BalancesStore> {
-        &self.balances
+        &self.stable_balances
     }
 
     fn balances_mut(&mut self) -> &mut Balances<Self::BalancesStore> {
-        &mut self.balances
+        &mut self.stable_balances
     }
 
     fn approvals(&self) -> &Self::Approvals {
-        &self.approvals
+        &self.stable_approvals
     }
 
     fn approvals_mut(&mut self) -> &mut Self::Approvals {
-        &mut self.approvals
+        &mut self.stable_approvals
     }
 
     fn fee_collector(&self) -> Option<&FeeCollector<Self::AccountId>> {
@@ -480,7 +569,7 @@ impl<Tokens: TokensType> LedgerData for Ledger<Tokens> {
     }
 
     fn max_number_of_accounts(&self) -> usize {
-        self.maximum_number_of_accounts
+        self.maximum_number_of_accounts.unwrap_or(usize::MAX)
     }
 
     fn accounts_overflow_trim_quantity(&self) -> usize {
@@ -503,20 +592,38 @@ impl<Tokens: TokensType> LedgerData for Ledger<Tokens> {
         &mut self.blockchain
     }
 
-    fn transactions_by_hash(&self) -> &BTreeMap<HashOf<Self::Transaction>, BlockIndex> {
-        &self.transactions_by_hash
+    fn get_transaction_by_hash(&self, hash: &HashOf<Self::Transaction>) -> Option<BlockIndex> {
+        stable_storage::get_transaction_by_hash(&hash.into_bytes())
+    }
+
+    fn insert_transaction_by_hash(&mut self, hash: HashOf<Self::Transaction>, height: BlockIndex) {
+        stable_storage::insert_transaction_by_hash(hash.into_bytes(), height);
     }
 
-    fn transactions_by_hash_mut(&mut self) -> &mut BTreeMap<HashOf<Self::Transaction>, BlockIndex> {
-        &mut self.transactions_by_hash
+    fn remove_transaction_by_hash(
+        &mut self,
+        hash: &HashOf<Self::Transaction>,
+    ) -> Option<BlockIndex> {
+        stable_storage::remove_transaction_by_hash(&hash.into_bytes())
     }
 
-    fn transactions_by_height(&self) -> &VecDeque<TransactionInfo<Self::Transaction>> {
-        &self.transactions_by_height
+    fn transactions_by_height_len(&self) -> usize {
+        stable_storage::transactions_by_height_len()
     }
 
-    fn transactions_by_height_mut(&mut self) -> &mut VecDeque<TransactionInfo<Self::Transaction>> {
-        &mut self.transactions_by_height
+    fn get_transaction_by_height(&self, i: usize) -> Option<TransactionInfo<Self::Transaction>> {
+        stable_storage::get_transaction_by_height(i).map(TransactionInfo::from)
+    }
+
+    fn push_transaction_by_height(&mut self, tx_info: TransactionInfo<Self::Transaction>) {
+        stable_storage::push_transaction_by_height(StoredTransactionInfo {
+            block_timestamp: tx_info.block_timestamp,
+            transaction_hash: tx_info.transaction_hash.into_bytes(),
+        });
+    }
+
+    fn pop_transaction_by_height(&mut self) -> Option<TransactionInfo<Self::Transaction>> {
+        stable_storage::pop_transaction_by_height().map(TransactionInfo::from)
     }
 
     fn on_purged_transaction(&mut self, _height: BlockIndex) {}
@@ -611,7 +718,7 @@ impl<Tokens: TokensType> Ledger<Tokens> {
             self.feature_flags = feature_flags;
         }
         if let Some(maximum_number_of_accounts) = args.maximum_number_of_accounts {
-            self.maximum_number_of_accounts = maximum_number_of_accounts.try_into().unwrap();
+            self.maximum_number_of_accounts = Some(maximum_number_of_accounts.try_into().unwrap());
         }
         if let Some(accounts_overflow_trim_quantity) = args.accounts_overflow_trim_quantity {
             self.accounts_overflow_trim_quantity =
@@ -619,13 +726,9 @@ impl<Tokens: TokensType> Ledger<Tokens> {
         }
     }
 
-    /// Returns the root hash of the certified ledger state.
-    /// The canister code must call set_certified_data with the value this function returns after
-    /// each successful modification of the ledger.
-    pub fn root_hash(&self) -> [u8; 32] {
-        self.construct_hash_tree().digest().0
-    }
-
+    /// Constructs the legacy certified hash tree of the ledger: the `tip_hash` label points to
+    /// the hash of the last block and the `last_block_index` label points to the big-endian
+    /// index of the last block.
     pub fn construct_hash_tree(&self) -> MixedHashTree {
         match self.blockchain().last_hash {
             Some(hash) => {
@@ -645,6 +748,34 @@ impl<Tokens: TokensType> Ledger<Tokens> {
         }
     }
 
+    /// Constructs the certified hash tree of the ledger as specified by ICRC-3: the
+    /// `last_block_index` label points to the LEB128-encoded index of the last block and
+    /// the `last_block_hash` label points to the hash of the last block.
+    ///
+    /// Both trees use the `last_block_index` label with different encodings, so a ledger
+    /// can only certify one of them.
+    pub fn construct_icrc3_hash_tree(&self) -> MixedHashTree {
+        match self.blockchain().last_hash {
+            Some(hash) => {
+                let last_block_index = self.blockchain().chain_length().checked_sub(1).unwrap();
+                let mut last_block_index_encoded = Vec::with_capacity(10);
+                leb128::write::unsigned(&mut last_block_index_encoded, last_block_index)
+                    .expect("failed to LEB128-encode the last block index");
+                MixedHashTree::Fork(Box::new((
+                    MixedHashTree::Labeled(
+                        Label::from("last_block_hash"),
+                        Box::new(MixedHashTree::Leaf(hash.as_slice().to_vec())),
+                    ),
+                    MixedHashTree::Labeled(
+                        Label::from("last_block_index"),
+                        Box::new(MixedHashTree::Leaf(last_block_index_encoded)),
+                    ),
+                )))
+            }
+            None => MixedHashTree::Empty,
+        }
+    }
+
     fn query_blocks<ArchiveFn, B>(
         &self,
         start: BlockIndex,
@@ -715,4 +846,54 @@ impl<Tokens: TokensType> Ledger<Tokens> {
             archived_blocks,
         }
     }
+    /// Returns blocks in the specified ranges as defined by the ICRC-3 standard.
+    /// Blocks stored in archives are returned as callbacks to the archives'
+    /// `icrc3_get_blocks` endpoint, one per archive.
+    pub fn icrc3_get_blocks(&self, args: Vec<GetBlocksRequest>) -> GetBlocksResult {
+        let mut blocks = vec![];
+        let mut archived_ranges: BTreeMap<Principal, Vec<GetBlocksRequest>> = BTreeMap::new();
+        for arg in args {
+            // Like the archive, answer requests for blocks that cannot exist with no
+            // blocks instead of trapping.
+            let Ok((start, length)) = arg.as_start_and_length() else {
+                continue;
+            };
+            let length = usize::try_from(length).unwrap_or(usize::MAX);
+            let locations = block_locations(self, start, length);
+
+            let max_local_blocks = MAX_TRANSACTIONS_PER_REQUEST.saturating_sub(blocks.len());
+            let local_blocks_range = range_utils::take(&locations.local_blocks, max_local_blocks);
+            let local_blocks = self.blockchain.block_slice(local_blocks_range.clone());
+            for (id, block) in local_blocks_range.zip(local_blocks) {
+                blocks.push(BlockWithId {
+                    id: Nat::from(id),
+                    block: encoded_block_to_generic_block(block).into(),
+                });
+            }
+
+            for (canister_id, slice) in locations.archived_blocks {
+                archived_ranges
+                    .entry(canister_id.get().0)
+                    .or_default()
+                    .push(GetBlocksRequest {
+                        start: Nat::from(slice.start),
+                        length: Nat::from(range_utils::range_len(&slice)),
+                    });
+            }
+        }
+
+        let archived_blocks = archived_ranges
+            .into_iter()
+            .map(|(canister_id, args)| ArchivedBlocks {
+                args,
+                callback: QueryArchiveFn::new(canister_id, "icrc3_get_blocks"),
+            })
+            .collect();
+
+        GetBlocksResult {
+            log_length: Nat::from(self.blockchain.chain_length()),
+            blocks,
+            archived_blocks,
+        }
+    }
 }
@@ -1,28 +1,40 @@
 use candid::candid_method;
 use candid::types::number::Nat;
-use ic_canister_log::{declare_log_buffer, export};
+use ic_canister_log::{declare_log_buffer, export, log};
 use ic_canisters_http_types::{HttpRequest, HttpResponse, HttpResponseBuilder};
-use ic_cdk::api::stable::{StableReader, StableWriter};
+use ic_cdk::api::stable::StableReader;
 use ic_cdk_macros::{init, post_upgrade, pre_upgrade, query, update};
+use ic_crypto_tree_hash::MixedHashTree;
 use ic_icrc1::{
     endpoints::{convert_transfer_error, StandardRecord},
     Operation, Transaction,
 };
-use ic_icrc1_ledger::{Ledger, LedgerArgument};
+use ic_icrc1_ledger::{stable_storage, Ledger, LedgerArgument};
 use ic_ledger_canister_core::ledger::{
     apply_transaction, archive_blocks, LedgerAccess, LedgerContext, LedgerData,
     TransferError as CoreTransferError,
 };
+use ic_ledger_core::balances::InspectableBalancesStore;
 use ic_ledger_core::tokens::Zero;
 use ic_ledger_core::{approvals::Approvals, timestamp::TimeStamp};
+use ic_stable_structures::{reader::Reader, writer::Writer};
 use icrc_ledger_types::icrc1::transfer::Memo;
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use icrc_ledger_types::icrc21::{
+    consent_message::{build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints, TokenInfo},
+    errors::{ErrorInfo, Icrc21Error},
+    requests::ConsentMessageRequest,
+    responses::ConsentInfo,
+};
 use icrc_ledger_types::icrc3::blocks::DataCertificate;
 use icrc_ledger_types::{
     icrc::generic_metadata_value::MetadataValue as Value,
     icrc3::{
-        archive::ArchiveInfo,
-        blocks::{GetBlocksRequest, GetBlocksResponse},
+        archive::{ArchiveInfo, GetArchivesArgs, GetArchivesResult, ICRC3ArchiveInfo},
+        blocks::{
+            icrc1_and_icrc2_supported_block_types, GetBlocksArgs, GetBlocksRequest,
+            GetBlocksResponse, GetBlocksResult, ICRC3DataCertificate, SupportedBlockType,
+        },
         transactions::{GetTransactionsRequest, GetTransactionsResponse},
     },
 };
@@ -33,19 +45,69 @@ use icrc_ledger_types::{
 use icrc_ledger_types::{
     icrc1::transfer::{TransferArg, TransferError},
     icrc2::transfer_from::{TransferFromArgs, TransferFromError},
+    icrc4::transfer_batch::{TransferBatchArgs, TransferBatchResults},
 };
 use num_traits::{bounds::Bounded, ToPrimitive};
 use serde_bytes::ByteBuf;
 use std::cell::RefCell;
+use std::time::Duration;
 
 const MAX_MESSAGE_SIZE: u64 = 1024 * 1024;
 
+/// The maximum number of transfers in a single `icrc4_transfer_batch` call.
+const MAX_TRANSFERS_PER_BATCH: usize = 100;
+
+/// The number of instructions after which `icrc4_transfer_batch` stops
+/// processing a batch, leaving headroom below the update instruction limit for
+/// archiving. The remaining transfers of the batch are reported as `None`.
+const MAX_INSTRUCTIONS_PER_BATCH: u64 = 10_000_000_000;
+
+/// The number of instructions that `post_upgrade` may use to move heap data
+/// structures into stable memory, well below the upgrade instruction limit.
+#[cfg(not(feature = "low-upgrade-instruction-limits"))]
+const MAX_INSTRUCTIONS_FOR_MIGRATION_IN_UPGRADE: u64 = 100_000_000_000;
+
+/// The number of instructions that each timer call may use to move heap data
+/// structures into stable memory, well below the message instruction limit.
+#[cfg(not(feature = "low-upgrade-instruction-limits"))]
+const MAX_INSTRUCTIONS_FOR_MIGRATION_PER_TIMER_CALL: u64 = 1_900_000_000;
+
+// Moves a single entry per message, so that tests can observe and upgrade a
+// ledger in the middle of the migration.
+#[cfg(feature = "low-upgrade-instruction-limits")]
+const MAX_INSTRUCTIONS_FOR_MIGRATION_IN_UPGRADE: u64 = 0;
+
+#[cfg(feature = "low-upgrade-instruction-limits")]
+const MAX_INSTRUCTIONS_FOR_MIGRATION_PER_TIMER_CALL: u64 = 0;
+
 #[cfg(not(feature = "u256-tokens"))]
 type Tokens = ic_icrc1_tokens_u64::U64;
 
 #[cfg(feature = "u256-tokens")]
 type Tokens = ic_icrc1_tokens_u256::U256;
 
+/// Returns the hash tree certified by the ledger.
+///
+/// Ledgers built with the `icrc3-compatible-data-certificate` feature certify the tree
+/// specified by ICRC-3, other ledgers keep certifying the legacy tree that existing
+/// clients look up.
+#[cfg(not(feature = "icrc3-compatible-data-certificate"))]
+fn certified_hash_tree(ledger: &Ledger<Tokens>) -> MixedHashTree {
+    ledger.construct_hash_tree()
+}
+
+#[cfg(feature = "icrc3-compatible-data-certificate")]
+fn certified_hash_tree(ledger: &Ledger<Tokens>) -> MixedHashTree {
+    ledger.construct_icrc3_hash_tree()
+}
+
+/// Returns the root hash of the certified ledger state.
+/// The canister code must call set_certified_data with the value this function returns after
+/// each successful modification of the ledger.
+fn root_hash(ledger: &Ledger<Tokens>) -> [u8; 32] {
+    certified_hash_tree(ledger).digest().0
+}
+
 thread_local! {
     static LEDGER: RefCell<Option<Ledger<Tokens>>> = RefCell::new(None);
 }
@@ -89,23 +151,32 @@ fn init(args: LedgerArgument) {
             panic!("Cannot initialize the canister with an Upgrade argument. Please provide an Init argument.");
         }
     }
-    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
+    ic_cdk::api::set_certified_data(&Access::with_ledger(root_hash));
 }
 
 #[pre_upgrade]
 fn pre_upgrade() {
-    Access::with_ledger(|ledger| ciborium::ser::into_writer(ledger, StableWriter::default()))
-        .expect("failed to encode ledger state");
+    let mut upgrades_memory = stable_storage::upgrades_memory();
+    Access::with_ledger(|ledger| {
+        ciborium::ser::into_writer(ledger, Writer::new(&mut upgrades_memory, 0))
+    })
+    .expect("failed to encode ledger state");
 }
 
 #[post_upgrade]
 fn post_upgrade(args: Option<LedgerArgument>) {
-    LEDGER.with(|cell| {
-        *cell.borrow_mut() = Some(
-            ciborium::de::from_reader(StableReader::default())
-                .expect("failed to decode ledger state"),
-        );
-    });
+    // Older versions of the ledger wrote the whole ledger state directly into
+    // stable memory. The state must be read before the memory manager takes
+    // over the stable memory.
+    let ledger: Ledger<Tokens> = if stable_storage::is_memory_manager_initialized() {
+        ciborium::de::from_reader(Reader::new(&stable_storage::upgrades_memory(), 0))
+    } else {
+        ciborium::de::from_reader(StableReader::default())
+    }
+    .expect("failed to decode ledger state");
+    LEDGER.with(|cell| *cell.borrow_mut() = Some(ledger));
+    Access::with_ledger_mut(Ledger::start_migration);
+    migrate_next_part(MAX_INSTRUCTIONS_FOR_MIGRATION_IN_UPGRADE);
 
     if let Some(args) = args {
         match args {
@@ -119,6 +190,34 @@ fn post_upgrade(args: Option<LedgerArgument>) {
     }
 }
 
+/// Moves heap data structures into stable memory until the ledger is ready
+/// or the instruction counter reaches `instruction_limit`. Schedules a timer
+/// that continues the migration in the latter case.
+fn migrate_next_part(instruction_limit: u64) {
+    if Access::with_ledger(Ledger::is_ready) {
+        return;
+    }
+    let is_ready = Access::with_ledger_mut(|ledger| {
+        ledger.migrate_next_part(|| ic_cdk::api::instruction_counter() >= instruction_limit)
+    });
+    if is_ready {
+        log!(
+            LOG,
+            "[ledger] the ledger data structures are in stable memory"
+        );
+    } else {
+        ic_cdk_timers::set_timer(Duration::from_secs(0), || {
+            migrate_next_part(MAX_INSTRUCTIONS_FOR_MIGRATION_PER_TIMER_CALL)
+        });
+    }
+}
+
+fn panic_if_not_ready() {
+    if !Access::with_ledger(Ledger::is_ready) {
+        ic_cdk::trap("the ledger is moving its data into stable memory, please retry later");
+    }
+}
+
 fn encode_metrics(w: &mut ic_metrics_encoder::MetricsEncoder<Vec<u8>>) -> std::io::Result<()> {
     w.encode_gauge(
         "ledger_stable_memory_pages",
@@ -143,12 +242,12 @@ fn encode_metrics(w: &mut ic_metrics_encoder::MetricsEncoder<Vec<u8>>) -> std::i
     Access::with_ledger(|ledger| {
         w.encode_gauge(
             "ledger_transactions_by_hash_cache_entries",
-            ledger.transactions_by_hash().len() as f64,
+            ledger.transactions_by_hash_len() as f64,
             "Total number of entries in the transactions_by_hash cache.",
         )?;
         w.encode_gauge(
             "ledger_transactions_by_height_entries",
-            ledger.transactions_by_height().len() as f64,
+            ledger.transactions_by_height_len() as f64,
             "Total number of entries in the transaction_by_height queue.",
         )?;
         w.encode_gauge(
@@ -266,16 +365,18 @@ fn icrc1_minting_account() -> Option<Account> {
 #[query(name = "icrc1_balance_of")]
 #[candid_method(query, rename = "icrc1_balance_of")]
 fn icrc1_balance_of(account: Account) -> Nat {
+    panic_if_not_ready();
     Access::with_ledger(|ledger| ledger.balances().account_balance(&account).into())
 }
 
 #[query(name = "icrc1_total_supply")]
 #[candid_method(query, rename = "icrc1_total_supply")]
 fn icrc1_total_supply() -> Nat {
+    panic_if_not_ready();
     Access::with_ledger(|ledger| ledger.balances().total_supply().into())
 }
 
-async fn execute_transfer(
+fn execute_transfer_not_async(
     from_account: Account,
     to: Account,
     spender: Option<Account>,
@@ -283,8 +384,9 @@ async fn execute_transfer(
     amount: Nat,
     memo: Option<Memo>,
     created_at_time: Option<u64>,
-) -> Result<Nat, CoreTransferError<Tokens>> {
-    let block_idx = Access::with_ledger_mut(|ledger| {
+) -> Result<u64, CoreTransferError<Tokens>> {
+    panic_if_not_ready();
+    Access::with_ledger_mut(|ledger| {
         let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
         let created_at_time = created_at_time.map(TimeStamp::from_nanos_since_unix_epoch);
 
@@ -375,11 +477,31 @@ async fn execute_transfer(
 
         let (block_idx, _) = apply_transaction(ledger, tx, now, effective_fee)?;
         Ok(block_idx)
-    })?;
+    })
+}
+
+async fn execute_transfer(
+    from_account: Account,
+    to: Account,
+    spender: Option<Account>,
+    fee: Option<Nat>,
+    amount: Nat,
+    memo: Option<Memo>,
+    created_at_time: Option<u64>,
+) -> Result<Nat, CoreTransferError<Tokens>> {
+    let block_idx = execute_transfer_not_async(
+        from_account,
+        to,
+        spender,
+        fee,
+        amount,
+        memo,
+        created_at_time,
+    )?;
 
     // NB. we need to set the certified data before the first async call to make sure that the
     // blockchain state agrees with the certificate while archiving is in progress.
-    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
+    ic_cdk::api::set_certified_data(&Access::with_ledger(root_hash));
 
     archive_blocks::<Access>(&LOG, MAX_MESSAGE_SIZE).await;
     Ok(Nat::from(block_idx))
@@ -412,6 +534,77 @@ async fn icrc1_transfer(arg: TransferArg) -> Result<Nat, TransferError> {
     })
 }
 
+#[update]
+#[candid_method(update)]
+async fn icrc4_transfer_batch(args: TransferBatchArgs) -> TransferBatchResults {
+    panic_if_not_ready();
+    if args.len() > MAX_TRANSFERS_PER_BATCH {
+        ic_cdk::trap(&format!(
+            "the batch contains {} transfers, at most {} are allowed",
+            args.len(),
+            MAX_TRANSFERS_PER_BATCH
+        ))
+    }
+    // Reject malformed batches before applying any transfer: a trap in the
+    // middle of the batch would roll back the transfers that succeeded.
+    let max_memo_length = Access::with_ledger(|ledger| ledger.max_memo_length() as usize);
+    for memo in args.iter().filter_map(|arg| arg.memo.as_ref()) {
+        if memo.0.len() > max_memo_length {
+            ic_cdk::trap(&format!(
+                "the memo field size of {} bytes is above the allowed limit of {} bytes",
+                memo.0.len(),
+                max_memo_length
+            ))
+        }
+    }
+
+    let caller = ic_cdk::api::caller();
+    let results: TransferBatchResults = args
+        .into_iter()
+        .map(|arg| {
+            if ic_cdk::api::instruction_counter() > MAX_INSTRUCTIONS_PER_BATCH {
+                return None;
+            }
+            let from_account = Account {
+                owner: caller,
+                subaccount: arg.from_subaccount,
+            };
+            let result = execute_transfer_not_async(
+                from_account,
+                arg.to,
+                None,
+                arg.fee,
+                arg.amount,
+                arg.memo,
+                arg.created_at_time,
+            )
+            .map(Nat::from)
+            .map_err(convert_transfer_error)
+            .map_err(|err| {
+                let err: TransferError = match err.try_into() {
+                    Ok(err) => err,
+                    Err(err) => ic_cdk::trap(&err),
+                };
+                err
+            });
+            Some(result)
+        })
+        .collect();
+
+    // NB. we need to set the certified data before the first async call to make sure that the
+    // blockchain state agrees with the certificate while archiving is in progress.
+    ic_cdk::api::set_certified_data(&Access::with_ledger(root_hash));
+
+    archive_blocks::<Access>(&LOG, MAX_MESSAGE_SIZE).await;
+    results
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc4_maximum_update_batch_size() -> Option<Nat> {
+    Some(Nat::from(MAX_TRANSFERS_PER_BATCH))
+}
+
 #[update]
 #[candid_method(update)]
 async fn icrc2_transfer_from(arg: TransferFromArgs) -> Result<Nat, TransferFromError> {
@@ -475,10 +668,32 @@ fn supported_standards() -> Vec<StandardRecord> {
             name: "ICRC-2".to_string(),
             url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2".to_string(),
         },
+        StandardRecord {
+            name: "ICRC-3".to_string(),
+            url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-3".to_string(),
+        },
+        StandardRecord {
+            name: "ICRC-4".to_string(),
+            url: "https://github.com/dfinity/ICRC/blob/main/ICRCs/ICRC-4/ICRC-4.md".to_string(),
+        },
+        StandardRecord {
+            name: "ICRC-10".to_string(),
+            url: "https://github.com/dfinity/ICRC/blob/main/ICRCs/ICRC-10/ICRC-10.md".to_string(),
+        },
+        StandardRecord {
+            name: "ICRC-21".to_string(),
+            url: "https://github.com/dfinity/wg-identity-authentication/blob/main/topics/ICRC-21/icrc_21_consent_msg.md".to_string(),
+        },
     ];
     standards
 }
 
+#[query]
+#[candid_method(query)]
+fn icrc10_supported_standards() -> Vec<StandardRecord> {
+    supported_standards()
+}
+
 #[query]
 #[candid_method(query)]
 fn get_transactions(req: GetTransactionsRequest) -> GetTransactionsResponse {
@@ -500,7 +715,7 @@ fn get_blocks(req: GetBlocksRequest) -> GetBlocksResponse {
 #[query]
 #[candid_method(query)]
 fn get_data_certificate() -> DataCertificate {
-    let hash_tree = Access::with_ledger(|ledger| ledger.construct_hash_tree());
+    let hash_tree = Access::with_ledger(certified_hash_tree);
     let mut tree_buf = vec![];
     ciborium::ser::into_writer(&hash_tree, &mut tree_buf).unwrap();
     DataCertificate {
@@ -509,9 +724,74 @@ fn get_data_certificate() -> DataCertificate {
     }
 }
 
+#[query]
+#[candid_method(query)]
+fn icrc3_get_archives(args: GetArchivesArgs) -> GetArchivesResult {
+    let archives = Access::with_ledger(|ledger| {
+        ledger
+            .blockchain()
+            .archive
+            .read()
+            .unwrap()
+            .as_ref()
+            .iter()
+            .flat_map(|archive| {
+                archive
+                    .index()
+                    .into_iter()
+                    .map(|((start, end), canister_id)| ICRC3ArchiveInfo {
+                        canister_id: canister_id.get().0,
+                        start: Nat::from(start),
+                        end: Nat::from(end),
+                    })
+            })
+            .collect::<Vec<_>>()
+    });
+    match args.from {
+        None => archives,
+        // Only return the archives coming after the last archive the client has seen.
+        Some(from) => archives
+            .into_iter()
+            .skip_while(|info| info.canister_id != from)
+            .skip(1)
+            .collect(),
+    }
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_get_blocks(args: GetBlocksArgs) -> GetBlocksResult {
+    Access::with_ledger(|ledger| ledger.icrc3_get_blocks(args))
+}
+
+/// Returns the certificate of the ledger tip.
+///
+/// The hash tree is the one returned by [get_data_certificate]. It only has the
+/// labels specified by ICRC-3 if the ledger is built with the
+/// `icrc3-compatible-data-certificate` feature, see [certified_hash_tree].
+#[query]
+#[candid_method(query)]
+fn icrc3_get_tip_certificate() -> Option<ICRC3DataCertificate> {
+    let certificate = ByteBuf::from(ic_cdk::api::data_certificate()?);
+    let hash_tree = Access::with_ledger(certified_hash_tree);
+    let mut tree_buf = vec![];
+    ciborium::ser::into_writer(&hash_tree, &mut tree_buf).unwrap();
+    Some(ICRC3DataCertificate {
+        certificate,
+        hash_tree: ByteBuf::from(tree_buf),
+    })
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_supported_block_types() -> Vec<SupportedBlockType> {
+    icrc1_and_icrc2_supported_block_types()
+}
+
 #[update]
 #[candid_method(update)]
 async fn icrc2_approve(arg: ApproveArgs) -> Result<Nat, ApproveError> {
+    panic_if_not_ready();
     let block_idx = Access::with_ledger_mut(|ledger| {
         let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
 
@@ -581,7 +861,7 @@ async fn icrc2_approve(arg: ApproveArgs) -> Result<Nat, ApproveError> {
 
     // NB. we need to set the certified data before the first async call to make sure that the
     // blockchain state agrees with the certificate while archiving is in progress.
-    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
+    ic_cdk::api::set_certified_data(&Access::with_ledger(root_hash));
 
     archive_blocks::<Access>(&LOG, MAX_MESSAGE_SIZE).await;
     Ok(Nat::from(block_idx))
@@ -590,6 +870,7 @@ async fn icrc2_approve(arg: ApproveArgs) -> Result<Nat, ApproveError> {
 #[query]
 #[candid_method(query)]
 fn icrc2_allowance(arg: AllowanceArgs) -> Allowance {
+    panic_if_not_ready();
     Access::with_ledger(|ledger| {
         let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
         let allowance = ledger
@@ -602,6 +883,35 @@ fn icrc2_allowance(arg: AllowanceArgs) -> Allowance {
     })
 }
 
+#[update]
+#[candid_method(update)]
+fn icrc21_canister_call_consent_message(
+    consent_msg_request: ConsentMessageRequest,
+) -> Result<ConsentInfo, Icrc21Error> {
+    let icrc2_method = matches!(
+        consent_msg_request.method.as_str(),
+        "icrc2_approve" | "icrc2_transfer_from"
+    );
+    let (icrc2_enabled, token) = Access::with_ledger(|ledger| {
+        let token = TokenInfo {
+            symbol: ledger.token_symbol().to_string(),
+            decimals: ledger.decimals(),
+            fee: ledger.transfer_fee().into(),
+        };
+        (ledger.feature_flags().icrc2, token)
+    });
+    if icrc2_method && !icrc2_enabled {
+        return Err(Icrc21Error::UnsupportedCanisterCall(ErrorInfo {
+            description: "ICRC-2 features are not enabled on the ledger.".to_string(),
+        }));
+    }
+    build_icrc21_consent_info_for_icrc1_and_icrc2_endpoints(
+        consent_msg_request,
+        ic_cdk::api::caller(),
+        token,
+    )
+}
+
 candid::export_service!();
 
 #[query]
@@ -0,0 +1,595 @@
+//! Ledger data structures that live in stable memory.
+//!
+//! Balances, allowances (with their expiration and arrival queues) and the
+//! transaction deduplication window are kept in `ic-stable-structures` maps,
+//! so that their size does not affect the cost of upgrades. The rest of the
+//! ledger state is small and is serialized into the upgrades memory in
+//! `pre_upgrade`.
+
+use crate::ApprovalKey;
+use candid::Principal;
+use ic_ledger_canister_core::ledger::TransactionInfo;
+use ic_ledger_core::{
+    approvals::{Allowance, AllowancesData},
+    balances::{BalancesStore, InspectableBalancesStore},
+    timestamp::TimeStamp,
+    tokens::{TokensType, Zero},
+};
+use ic_ledger_hash_of::{HashOf, HASH_LENGTH};
+use ic_stable_structures::memory_manager::{MemoryId, MemoryManager, VirtualMemory};
+use ic_stable_structures::storable::{Bound, Storable};
+use ic_stable_structures::{DefaultMemoryImpl, Memory as _, StableBTreeMap};
+use icrc_ledger_types::icrc1::account::{Account, Subaccount, DEFAULT_SUBACCOUNT};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+const UPGRADES_MEMORY_ID: MemoryId = MemoryId::new(0);
+const BALANCES_MEMORY_ID: MemoryId = MemoryId::new(1);
+const ALLOWANCES_MEMORY_ID: MemoryId = MemoryId::new(2);
+const ALLOWANCES_EXPIRATIONS_MEMORY_ID: MemoryId = MemoryId::new(3);
+const ALLOWANCES_ARRIVALS_MEMORY_ID: MemoryId = MemoryId::new(4);
+const TRANSACTIONS_BY_HASH_MEMORY_ID: MemoryId = MemoryId::new(5);
+const TRANSACTIONS_BY_HEIGHT_MEMORY_ID: MemoryId = MemoryId::new(6);
+
+/// The magic bytes at the beginning of stable memory managed by a
+/// [MemoryManager].
+const MEMORY_MANAGER_MAGIC: &[u8; 3] = b"MGR";
+
+/// The maximum length of a principal in bytes.
+const MAX_PRINCIPAL_LENGTH: usize = 29;
+
+/// The length of an encoded account: the length of the owner, the owner
+/// padded to [MAX_PRINCIPAL_LENGTH] bytes and the effective subaccount.
+const ACCOUNT_LENGTH: usize = 1 + MAX_PRINCIPAL_LENGTH + 32;
+
+pub type Memory = VirtualMemory<DefaultMemoryImpl>;
+
+thread_local! {
+    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
+        RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+
+    /// CBOR-encoded balances of all accounts with a non-zero balance.
+    static BALANCES: RefCell<StableBTreeMap<AccountKey, Vec<u8>, Memory>> =
+        MEMORY_MANAGER.with(|memory_manager| {
+            RefCell::new(StableBTreeMap::init(memory_manager.borrow().get(BALANCES_MEMORY_ID)))
+        });
+
+    /// CBOR-encoded allowances keyed by (account, spender) pairs.
+    static ALLOWANCES: RefCell<StableBTreeMap<ApprovalKey, Vec<u8>, Memory>> =
+        MEMORY_MANAGER.with(|memory_manager| {
+            RefCell::new(StableBTreeMap::init(memory_manager.borrow().get(ALLOWANCES_MEMORY_ID)))
+        });
+
+    /// Allowances with an expiration date ordered by expiration date.
+    static ALLOWANCES_EXPIRATIONS: RefCell<StableBTreeMap<ApprovalQueueKey, (), Memory>> =
+        MEMORY_MANAGER.with(|memory_manager| {
+            RefCell::new(StableBTreeMap::init(
+                memory_manager.borrow().get(ALLOWANCES_EXPIRATIONS_MEMORY_ID),
+            ))
+        });
+
+    /// Allowances ordered by the time of the last approval.
+    static ALLOWANCES_ARRIVALS: RefCell<StableBTreeMap<ApprovalQueueKey, (), Memory>> =
+        MEMORY_MANAGER.with(|memory_manager| {
+            RefCell::new(StableBTreeMap::init(
+                memory_manager.borrow().get(ALLOWANCES_ARRIVALS_MEMORY_ID),
+            ))
+        });
+
+    /// The block index of each transaction in the deduplication window.
+    static TRANSACTIONS_BY_HASH: RefCell<StableBTreeMap<[u8; HASH_LENGTH], u64, Memory>> =
+        MEMORY_MANAGER.with(|memory_manager| {
+            RefCell::new(StableBTreeMap::init(
+                memory_manager.borrow().get(TRANSACTIONS_BY_HASH_MEMORY_ID),
+            ))
+        });
+
+    /// The transactions in the deduplication window keyed by a sequence
+    /// number, i.e., in the order in which they were added to the ledger.
+    static TRANSACTIONS_BY_HEIGHT: RefCell<StableBTreeMap<u64, StoredTransactionInfo, Memory>> =
+        MEMORY_MANAGER.with(|memory_manager| {
+            RefCell::new(StableBTreeMap::init(
+                memory_manager.borrow().get(TRANSACTIONS_BY_HEIGHT_MEMORY_ID),
+            ))
+        });
+}
+
+/// Returns the memory that `pre_upgrade` serializes the heap part of the
+/// ledger state into.
+pub fn upgrades_memory() -> Memory {
+    MEMORY_MANAGER.with(|memory_manager| memory_manager.borrow().get(UPGRADES_MEMORY_ID))
+}
+
+/// Returns true if the stable memory is managed by a [MemoryManager], i.e.,
+/// the ledger state was written by a ledger that stores its data structures
+/// in stable memory. Older ledgers wrote the CBOR-encoded ledger state
+/// directly into stable memory.
+///
+/// This function must be called before any of the stable structures is
+/// accessed, as their initialization initializes the memory manager.
+pub fn is_memory_manager_initialized() -> bool {
+    let memory = DefaultMemoryImpl::default();
+    if memory.size() == 0 {
+        return false;
+    }
+    let mut magic = [0u8; 3];
+    memory.read(0, &mut magic);
+    &magic == MEMORY_MANAGER_MAGIC
+}
+
+fn encode_account(account: &Account, buf: &mut Vec<u8>) {
+    let owner = account.owner.as_slice();
+    buf.push(owner.len() as u8);
+    buf.extend_from_slice(owner);
+    buf.resize(buf.len() + MAX_PRINCIPAL_LENGTH - owner.len(), 0);
+    buf.extend_from_slice(account.effective_subaccount());
+}
+
+fn decode_account(bytes: &[u8]) -> Account {
+    assert_eq!(
+        bytes.len(),
+        ACCOUNT_LENGTH,
+        "bug: invalid account encoding length"
+    );
+    let owner_len = bytes[0] as usize;
+    let owner = Principal::from_slice(&bytes[1..1 + owner_len]);
+    let subaccount: Subaccount = bytes[1 + MAX_PRINCIPAL_LENGTH..]
+        .try_into()
+        .expect("bug: invalid subaccount encoding");
+    Account {
+        owner,
+        subaccount: (&subaccount != DEFAULT_SUBACCOUNT).then_some(subaccount),
+    }
+}
+
+fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    ciborium::ser::into_writer(value, &mut buf).expect("failed to encode value");
+    buf
+}
+
+fn decode<T: DeserializeOwned>(bytes: Vec<u8>) -> T {
+    ciborium::de::from_reader(&bytes[..]).expect("failed to decode value")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct AccountKey(Account);
+
+impl Storable for AccountKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut buf = Vec::with_capacity(ACCOUNT_LENGTH);
+        encode_account(&self.0, &mut buf);
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        Self(decode_account(&bytes))
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: ACCOUNT_LENGTH as u32,
+        is_fixed_size: true,
+    };
+}
+
+impl Storable for ApprovalKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut buf = Vec::with_capacity(2 * ACCOUNT_LENGTH);
+        encode_account(&self.0, &mut buf);
+        encode_account(&self.1, &mut buf);
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        Self(
+            decode_account(&bytes[..ACCOUNT_LENGTH]),
+            decode_account(&bytes[ACCOUNT_LENGTH..]),
+        )
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: 2 * ACCOUNT_LENGTH as u32,
+        is_fixed_size: true,
+    };
+}
+
+/// An entry of the allowance expiration or arrival queue.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct ApprovalQueueKey(TimeStamp, ApprovalKey);
+
+impl Storable for ApprovalQueueKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut buf = self.0.as_nanos_since_unix_epoch().to_be_bytes().to_vec();
+        buf.extend_from_slice(&self.1.to_bytes());
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        let timestamp = u64::from_be_bytes(bytes[..8].try_into().unwrap());
+        Self(
+            TimeStamp::from_nanos_since_unix_epoch(timestamp),
+            ApprovalKey::from_bytes(Cow::Borrowed(&bytes[8..])),
+        )
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: 8 + 2 * ACCOUNT_LENGTH as u32,
+        is_fixed_size: true,
+    };
+}
+
+/// A transaction in the deduplication window.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredTransactionInfo {
+    pub block_timestamp: TimeStamp,
+    pub transaction_hash: [u8; HASH_LENGTH],
+}
+
+impl Storable for StoredTransactionInfo {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut buf = self
+            .block_timestamp
+            .as_nanos_since_unix_epoch()
+            .to_be_bytes()
+            .to_vec();
+        buf.extend_from_slice(&self.transaction_hash);
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        Self {
+            block_timestamp: TimeStamp::from_nanos_since_unix_epoch(u64::from_be_bytes(
+                bytes[..8].try_into().unwrap(),
+            )),
+            transaction_hash: bytes[8..].try_into().unwrap(),
+        }
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: 8 + HASH_LENGTH as u32,
+        is_fixed_size: true,
+    };
+}
+
+impl<T> From<StoredTransactionInfo> for TransactionInfo<T> {
+    fn from(tx_info: StoredTransactionInfo) -> Self {
+        Self {
+            block_timestamp: tx_info.block_timestamp,
+            transaction_hash: HashOf::new(tx_info.transaction_hash),
+        }
+    }
+}
+
+/// A [BalancesStore] backed by a stable map.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(bound = "")]
+pub struct StableBalances<Tokens> {
+    #[serde(skip)]
+    _marker: PhantomData<Tokens>,
+}
+
+impl<Tokens> Default for StableBalances<Tokens> {
+    fn default() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Tokens: TokensType> BalancesStore for StableBalances<Tokens> {
+    type AccountId = Account;
+    type Tokens = Tokens;
+
+    fn get_balance(&self, k: &Account) -> Option<Tokens> {
+        BALANCES
+            .with(|balances| balances.borrow().get(&AccountKey(*k)))
+            .map(decode)
+    }
+
+    fn update<F, E>(&mut self, k: Account, mut f: F) -> Result<Tokens, E>
+    where
+        F: FnMut(Option<&Tokens>) -> Result<Tokens, E>,
+    {
+        let prev = self.get_balance(&k);
+        let new_v = f(prev.as_ref())?;
+        BALANCES.with(|balances| {
+            let mut balances = balances.borrow_mut();
+            if new_v.is_zero() {
+                balances.remove(&AccountKey(k));
+            } else {
+                balances.insert(AccountKey(k), encode(&new_v));
+            }
+        });
+        Ok(new_v)
+    }
+}
+
+impl<Tokens: TokensType> InspectableBalancesStore for StableBalances<Tokens> {
+    fn iter(&self) -> Box<dyn Iterator<Item = (Account, Tokens)> + '_> {
+        Box::new(StableBalancesIter {
+            last: None,
+            chunk: Vec::new().into_iter(),
+        })
+    }
+
+    fn len(&self) -> usize {
+        BALANCES.with(|balances| balances.borrow().len()) as usize
+    }
+}
+
+/// The number of balances that [StableBalancesIter] reads at once.
+const BALANCES_ITER_CHUNK_SIZE: usize = 1_000;
+
+/// Iterates over the balances in chunks: the stable map cannot be borrowed
+/// beyond a single call, and copying the whole map would use memory
+/// proportional to the number of accounts.
+struct StableBalancesIter<Tokens> {
+    /// The last account that was read, if any.
+    last: Option<AccountKey>,
+    chunk: std::vec::IntoIter<(Account, Tokens)>,
+}
+
+impl<Tokens: TokensType> Iterator for StableBalancesIter<Tokens> {
+    type Item = (Account, Tokens);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(entry) = self.chunk.next() {
+            return Some(entry);
+        }
+        let start = match self.last {
+            Some(last) => std::ops::Bound::Excluded(last),
+            None => std::ops::Bound::Unbounded,
+        };
+        let chunk: Vec<_> = BALANCES.with(|balances| {
+            balances
+                .borrow()
+                .range((start, std::ops::Bound::Unbounded))
+                .take(BALANCES_ITER_CHUNK_SIZE)
+                .map(|(account, balance)| (account.0, decode(balance)))
+                .collect()
+        });
+        self.last = chunk.last().map(|(account, _)| AccountKey(*account));
+        self.chunk = chunk.into_iter();
+        self.chunk.next()
+    }
+}
+
+/// An [AllowancesData] implementation backed by stable maps.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(bound = "")]
+pub struct StableAllowancesData<Tokens> {
+    #[serde(skip)]
+    _marker: PhantomData<Tokens>,
+}
+
+impl<Tokens> Default for StableAllowancesData<Tokens> {
+    fn default() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Tokens: TokensType> AllowancesData for StableAllowancesData<Tokens> {
+    type AccountId = Account;
+    type Tokens = Tokens;
+
+    fn get_allowance(&self, account: &Account, spender: &Account) -> Option<Allowance<Tokens>> {
+        ALLOWANCES
+            .with(|allowances| {
+                allowances
+                    .borrow()
+                    .get(&ApprovalKey::from((account, spender)))
+            })
+            .map(decode)
+    }
+
+    fn set_allowance(
+        &mut self,
+        account: &Account,
+        spender: &Account,
+        allowance: Allowance<Tokens>,
+    ) {
+        ALLOWANCES.with(|allowances| {
+            allowances
+                .borrow_mut()
+                .insert(ApprovalKey::from((account, spender)), encode(&allowance))
+        });
+    }
+
+    fn remove_allowance(&mut self, account: &Account, spender: &Account) {
+        ALLOWANCES.with(|allowances| {
+            allowances
+                .borrow_mut()
+                .remove(&ApprovalKey::from((account, spender)))
+        });
+    }
+
+    fn insert_expiry(&mut self, timestamp: TimeStamp, account: &Account, spender: &Account) {
+        let key = ApprovalQueueKey(timestamp, ApprovalKey::from((account, spender)));
+        ALLOWANCES_EXPIRATIONS.with(|expirations| expirations.borrow_mut().insert(key, ()));
+    }
+
+    fn remove_expiry(&mut self, timestamp: TimeStamp, account: &Account, spender: &Account) {
+        let key = ApprovalQueueKey(timestamp, ApprovalKey::from((account, spender)));
+        ALLOWANCES_EXPIRATIONS.with(|expirations| expirations.borrow_mut().remove(&key));
+    }
+
+    fn insert_arrival(&mut self, timestamp: TimeStamp, account: &Account, spender: &Account) {
+        let key = ApprovalQueueKey(timestamp, ApprovalKey::from((account, spender)));
+        ALLOWANCES_ARRIVALS.with(|arrivals| arrivals.borrow_mut().insert(key, ()));
+    }
+
+    fn remove_arrival(&mut self, timestamp: TimeStamp, account: &Account, spender: &Account) {
+        let key = ApprovalQueueKey(timestamp, ApprovalKey::from((account, spender)));
+        ALLOWANCES_ARRIVALS.with(|arrivals| arrivals.borrow_mut().remove(&key));
+    }
+
+    fn first_expiry(&self) -> Option<(TimeStamp, (Account, Account))> {
+        ALLOWANCES_EXPIRATIONS.with(|expirations| {
+            expirations
+                .borrow()
+                .first_key_value()
+                .map(|(ApprovalQueueKey(timestamp, key), ())| (timestamp, key.into()))
+        })
+    }
+
+    fn oldest_arrivals(&self, n: usize) -> Vec<(Account, Account)> {
+        ALLOWANCES_ARRIVALS.with(|arrivals| {
+            arrivals
+                .borrow()
+                .iter()
+                .take(n)
+                .map(|(ApprovalQueueKey(_arrival, key), ())| key.into())
+                .collect()
+        })
+    }
+
+    fn len_allowances(&self) -> usize {
+        ALLOWANCES.with(|allowances| allowances.borrow().len()) as usize
+    }
+
+    fn len_expirations(&self) -> usize {
+        ALLOWANCES_EXPIRATIONS.with(|expirations| expirations.borrow().len()) as usize
+    }
+
+    fn len_arrivals(&self) -> usize {
+        ALLOWANCES_ARRIVALS.with(|arrivals| arrivals.borrow().len()) as usize
+    }
+}
+
+pub fn get_transaction_by_hash(hash: &[u8; HASH_LENGTH]) -> Option<u64> {
+    TRANSACTIONS_BY_HASH.with(|transactions| transactions.borrow().get(hash))
+}
+
+pub fn insert_transaction_by_hash(hash: [u8; HASH_LENGTH], height: u64) {
+    TRANSACTIONS_BY_HASH.with(|transactions| transactions.borrow_mut().insert(hash, height));
+}
+
+pub fn remove_transaction_by_hash(hash: &[u8; HASH_LENGTH]) -> Option<u64> {
+    TRANSACTIONS_BY_HASH.with(|transactions| transactions.borrow_mut().remove(hash))
+}
+
+pub fn transactions_by_hash_len() -> usize {
+    TRANSACTIONS_BY_HASH.with(|transactions| transactions.borrow().len()) as usize
+}
+
+pub fn transactions_by_height_len() -> usize {
+    TRANSACTIONS_BY_HEIGHT.with(|transactions| transactions.borrow().len()) as usize
+}
+
+/// Returns the `i`-th oldest transaction in the deduplication window.
+///
+/// Transactions are only appended with increasing sequence numbers and
+/// removed from the front, so the sequence numbers are contiguous.
+pub fn get_transaction_by_height(i: usize) -> Option<StoredTransactionInfo> {
+    TRANSACTIONS_BY_HEIGHT.with(|transactions| {
+        let transactions = transactions.borrow();
+        let (first, _) = transactions.first_key_value()?;
+        transactions.get(&(first + i as u64))
+    })
+}
+
+pub fn push_transaction_by_height(tx_info: StoredTransactionInfo) {
+    TRANSACTIONS_BY_HEIGHT.with(|transactions| {
+        let mut transactions = transactions.borrow_mut();
+        let next = transactions
+            .last_key_value()
+            .map(|(last, _)| last + 1)
+            .unwrap_or(0);
+        transactions.insert(next, tx_info);
+    });
+}
+
+pub fn pop_transaction_by_height() -> Option<StoredTransactionInfo> {
+    TRANSACTIONS_BY_HEIGHT.with(|transactions| {
+        let mut transactions = transactions.borrow_mut();
+        let (first, _) = transactions.first_key_value()?;
+        transactions.remove(&first)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_key_round_trip() {
+        for account in [
+            Account {
+                owner: Principal::anonymous(),
+                subaccount: None,
+            },
+            Account {
+                owner: Principal::management_canister(),
+                subaccount: Some([1; 32]),
+            },
+            Account {
+                owner: Principal::from_slice(&[7; MAX_PRINCIPAL_LENGTH]),
+                subaccount: Some([0xff; 32]),
+            },
+        ] {
+            let key = AccountKey(account);
+            let bytes = key.to_bytes();
+            assert_eq!(bytes.len(), ACCOUNT_LENGTH);
+            let decoded = AccountKey::from_bytes(bytes);
+            assert_eq!(decoded.0.owner, account.owner);
+            assert_eq!(decoded.0.subaccount, account.subaccount);
+        }
+    }
+
+    #[test]
+    fn default_subaccount_is_decoded_as_none() {
+        let account = Account {
+            owner: Principal::anonymous(),
+            subaccount: Some(*DEFAULT_SUBACCOUNT),
+        };
+        let decoded = AccountKey::from_bytes(AccountKey(account).to_bytes());
+        assert_eq!(decoded.0.subaccount, None);
+        assert_eq!(decoded.0, account);
+    }
+
+    #[test]
+    fn transactions_by_height_is_a_queue() {
+        let tx = |n: u8| StoredTransactionInfo {
+            block_timestamp: TimeStamp::from_nanos_since_unix_epoch(n as u64),
+            transaction_hash: [n; HASH_LENGTH],
+        };
+        for n in 0..3 {
+            push_transaction_by_height(tx(n));
+        }
+        assert_eq!(transactions_by_height_len(), 3);
+        assert_eq!(pop_transaction_by_height(), Some(tx(0)));
+        push_transaction_by_height(tx(3));
+        assert_eq!(get_transaction_by_height(0), Some(tx(1)));
+        assert_eq!(get_transaction_by_height(2), Some(tx(3)));
+        assert_eq!(get_transaction_by_height(3), None);
+        assert_eq!(transactions_by_height_len(), 3);
+    }
+
+    #[test]
+    fn balances_are_iterated_in_chunks() {
+        use ic_ledger_core::tokens::Tokens;
+
+        let mut balances = StableBalances::<Tokens>::default();
+        let num_accounts = 2 * BALANCES_ITER_CHUNK_SIZE as u64 + 1;
+        let account = |n: u64| Account {
+            owner: Principal::from_slice(&n.to_be_bytes()),
+            subaccount: None,
+        };
+        for n in 0..num_accounts {
+            balances
+                .update(account(n), |_| Ok::<_, ()>(Tokens::from_e8s(n + 1)))
+                .unwrap();
+        }
+
+        let iterated: Vec<_> = balances.iter().collect();
+        assert_eq!(iterated.len() as u64, num_accounts);
+        for (n, (account_n, balance)) in (0..num_accounts).zip(iterated) {
+            assert_eq!(account_n, account(n));
+            assert_eq!(balance, Tokens::from_e8s(n + 1));
+        }
+    }
+}
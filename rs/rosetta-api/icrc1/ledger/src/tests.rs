@@ -3,10 +3,13 @@ use ic_base_types::PrincipalId;
 use ic_canister_log::Sink;
 use ic_icrc1::{Operation, Transaction};
 use ic_ledger_canister_core::archive::ArchiveOptions;
-use ic_ledger_canister_core::ledger::{LedgerContext, LedgerTransaction, TxApplyError};
-use ic_ledger_core::approvals::{Allowance, Approvals};
+use ic_ledger_canister_core::ledger::{
+    LedgerContext, LedgerData, LedgerTransaction, TransactionInfo, TxApplyError,
+};
+use ic_ledger_core::approvals::{Allowance, Approvals, PrunableApprovals};
 use ic_ledger_core::timestamp::TimeStamp;
 use ic_ledger_core::Tokens;
+use ic_ledger_hash_of::HashOf;
 use icrc_ledger_types::icrc::generic_metadata_value::MetadataValue as Value;
 use icrc_ledger_types::icrc1::account::Account;
 
@@ -615,3 +618,64 @@ fn test_approval_burn_from() {
     assert_eq!(ctx.balances().account_balance(&spender), Tokens::ZERO);
     assert_eq!(ctx.balances().total_supply().get_e8s(), 90_000);
 }
+
+#[test]
+fn test_migrate_to_stable_structures() {
+    let now = ts(12345678);
+
+    let mut ledger = Ledger::from_init_args(DummyLogger, default_init_args(), now);
+
+    let from = test_account_id(1);
+    let spender = test_account_id(2);
+    let expiration = ts(now.as_nanos_since_unix_epoch() + 1_000);
+    let hash = HashOf::new([1; 32]);
+
+    // Populate the heap data structures used by older versions of the ledger.
+    ledger.balances.mint(&from, tokens(100_000)).unwrap();
+    ledger
+        .approvals
+        .approve(&from, &spender, tokens(5_000), Some(expiration), now, None)
+        .unwrap();
+    ledger.transactions_by_hash.insert(hash, 7);
+    ledger.transactions_by_height.push_back(TransactionInfo {
+        block_timestamp: now,
+        transaction_hash: hash,
+    });
+
+    // Older versions of the ledger stored their default limit.
+    ledger.maximum_number_of_accounts = Some(28_000_000);
+
+    ledger.start_migration();
+    assert!(!ledger.is_ready());
+    assert_eq!(ledger.max_number_of_accounts(), usize::MAX);
+
+    // Move one entry per call to check that the migration can be resumed.
+    let mut num_calls = 1;
+    while !ledger.migrate_next_part(|| true) {
+        num_calls += 1;
+    }
+    assert!(ledger.is_ready());
+    assert_eq!(num_calls, 5);
+
+    assert_eq!(ledger.balances().account_balance(&from), tokens(100_000));
+    assert_eq!(ledger.balances().total_supply(), tokens(100_000));
+    assert_eq!(
+        ledger.approvals().allowance(&from, &spender, now),
+        Allowance {
+            amount: tokens(5_000),
+            expires_at: Some(expiration),
+            arrived_at: now,
+        }
+    );
+    assert_eq!(ledger.get_transaction_by_hash(&hash), Some(7));
+    assert_eq!(ledger.transactions_by_height_len(), 1);
+
+    assert!(ledger.balances.store.is_empty());
+    assert_eq!(ledger.approvals.len(), 0);
+    assert!(ledger.transactions_by_hash.is_empty());
+    assert!(ledger.transactions_by_height.is_empty());
+
+    // The expired approval is pruned from the stable data structures.
+    ledger.approvals_mut().prune(expiration, 10);
+    assert_eq!(ledger.approvals().len(), 0);
+}
@@ -55,6 +55,14 @@ fn ledger_wasm() -> Vec<u8> {
     )
 }
 
+fn ledger_wasm_icrc3_compatible_data_certificate() -> Vec<u8> {
+    ic_test_utilities_load_wasm::load_wasm(
+        std::env::var("CARGO_MANIFEST_DIR").unwrap(),
+        "ic-icrc1-ledger",
+        &["icrc3-compatible-data-certificate"],
+    )
+}
+
 fn archive_wasm() -> Vec<u8> {
     ic_test_utilities_load_wasm::load_wasm(
         PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
@@ -122,6 +130,11 @@ fn test_single_transfer() {
     ic_icrc1_ledger_sm_tests::test_single_transfer(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_transfer_batch() {
+    ic_icrc1_ledger_sm_tests::test_transfer_batch(ledger_wasm(), encode_init_args);
+}
+
 #[test]
 fn test_account_canonicalization() {
     ic_icrc1_ledger_sm_tests::test_account_canonicalization(ledger_wasm(), encode_init_args);
@@ -142,6 +155,29 @@ fn test_get_blocks() {
     ic_icrc1_ledger_sm_tests::test_get_blocks(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_icrc3_get_blocks() {
+    ic_icrc1_ledger_sm_tests::test_icrc3_get_blocks(ledger_wasm(), encode_init_args);
+}
+
+#[test]
+fn test_icrc3_get_tip_certificate() {
+    ic_icrc1_ledger_sm_tests::test_icrc3_get_tip_certificate(
+        ledger_wasm(),
+        encode_init_args,
+        false,
+    );
+}
+
+#[test]
+fn test_icrc3_get_tip_certificate_with_icrc3_compatible_data_certificate() {
+    ic_icrc1_ledger_sm_tests::test_icrc3_get_tip_certificate(
+        ledger_wasm_icrc3_compatible_data_certificate(),
+        encode_init_args,
+        true,
+    );
+}
+
 // Generate random blocks and check that their CBOR encoding complies with the CDDL spec.
 #[test]
 fn block_encoding_agrees_with_the_schema() {
@@ -192,6 +228,11 @@ fn check_memo_max_len() {
     ic_icrc1_ledger_sm_tests::test_memo_max_len(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_icrc21_consent_message() {
+    ic_icrc1_ledger_sm_tests::test_icrc21_consent_message(ledger_wasm(), encode_init_args);
+}
+
 #[test]
 fn test_approve_smoke() {
     ic_icrc1_ledger_sm_tests::test_approve_smoke(ledger_wasm(), encode_init_args);
@@ -424,6 +465,184 @@ fn test_upgrade_from_first_version() {
     transfer(&env, ledger_id, account(1), account(3), 1_000_000);
 }
 
+fn ledger_wasm_low_upgrade_instruction_limits() -> Vec<u8> {
+    ic_test_utilities_load_wasm::load_wasm(
+        std::env::var("CARGO_MANIFEST_DIR").unwrap(),
+        "ic-icrc1-ledger",
+        &["low-upgrade-instruction-limits"],
+    )
+}
+
+fn transfer_with_created_at_time(
+    env: &StateMachine,
+    ledger_id: CanisterId,
+    from: Account,
+    to: Account,
+    amount: u64,
+    created_at_time: u64,
+) -> Result<Nat, TransferError> {
+    let args = Encode!(&TransferArg {
+        from_subaccount: None,
+        to,
+        amount: amount.into(),
+        fee: None,
+        created_at_time: Some(created_at_time),
+        memo: None
+    })
+    .unwrap();
+    let res = env
+        .execute_ingress_as(from.owner.into(), ledger_id, "icrc1_transfer", args)
+        .expect("Unable to perform icrc1_transfer")
+        .bytes();
+    Decode!(&res, Result<Nat, TransferError>).unwrap()
+}
+
+#[cfg_attr(feature = "u256-tokens", ignore)]
+#[test]
+fn test_upgrade_from_deployed_version_moves_state_into_stable_memory() {
+    let env = StateMachine::new();
+
+    let ledger_wasm_deployed_version =
+        std::fs::read(std::env::var("IC_ICRC1_LEDGER_DEPLOYED_VERSION_WASM_PATH").unwrap())
+            .unwrap();
+    let init_args = Encode!(&encode_init_args(ic_icrc1_ledger_sm_tests::InitArgs {
+        minting_account: MINTER,
+        fee_collector_account: None,
+        initial_balances: (1..=5)
+            .map(|n| (account(n), Nat::from(10_000_000_u64)))
+            .collect(),
+        decimals: None,
+        transfer_fee: FEE.into(),
+        token_name: TOKEN_NAME.to_string(),
+        token_symbol: TOKEN_SYMBOL.to_string(),
+        metadata: vec![],
+        archive_options: ArchiveOptions {
+            trigger_threshold: ARCHIVE_TRIGGER_THRESHOLD as usize,
+            num_blocks_to_archive: NUM_BLOCKS_TO_ARCHIVE as usize,
+            node_max_memory_size_bytes: None,
+            max_message_size_bytes: None,
+            controller_id: PrincipalId::new_user_test_id(100),
+            more_controller_ids: None,
+            cycles_for_archive_creation: None,
+            max_transactions_per_response: None,
+        },
+        feature_flags: None,
+        maximum_number_of_accounts: None,
+        accounts_overflow_trim_quantity: None,
+    }))
+    .unwrap();
+    let ledger_id = env
+        .install_canister(ledger_wasm_deployed_version, init_args, None)
+        .unwrap();
+
+    // Populate the heap data structures of the deployed version: balances,
+    // allowances and the deduplication window.
+    let created_at_time = env
+        .time()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64;
+    let deduplicated_block = transfer_with_created_at_time(
+        &env,
+        ledger_id,
+        account(1),
+        account(6),
+        1_000_000,
+        created_at_time,
+    )
+    .unwrap();
+    transfer(&env, ledger_id, account(2), account(7), 2_000_000);
+    for n in 3..=5 {
+        send_approval(
+            &env,
+            ledger_id,
+            account(n).owner,
+            &ApproveArgs {
+                from_subaccount: None,
+                spender: account(n + 10),
+                amount: (n * 100_000).into(),
+                expected_allowance: None,
+                expires_at: None,
+                fee: None,
+                memo: None,
+                created_at_time: Some(created_at_time),
+            },
+        )
+        .unwrap();
+    }
+    let balances: Vec<_> = (1..=7)
+        .map(|n| balance_of(&env, ledger_id, account(n)))
+        .collect();
+    let allowances: Vec<_> = (3..=5)
+        .map(|n| get_allowance(&env, ledger_id, account(n), account(n + 10)))
+        .collect();
+
+    // The ledger moves a single entry per message, so the migration is still
+    // in progress after the upgrade.
+    env.upgrade_canister(
+        ledger_id,
+        ledger_wasm_low_upgrade_instruction_limits(),
+        Encode!(&LedgerArgument::Upgrade(None)).unwrap(),
+    )
+    .expect("Unable to upgrade the ledger canister");
+    let args = Encode!(&account(1)).unwrap();
+    assert!(env
+        .query(ledger_id, "icrc1_balance_of", args.clone())
+        .is_err());
+
+    // Upgrade in the middle of the migration.
+    env.tick();
+    env.upgrade_canister(
+        ledger_id,
+        ledger_wasm_low_upgrade_instruction_limits(),
+        Encode!(&LedgerArgument::Upgrade(None)).unwrap(),
+    )
+    .expect("Unable to upgrade the ledger canister during the migration");
+    assert!(env
+        .query(ledger_id, "icrc1_balance_of", args.clone())
+        .is_err());
+
+    let mut num_ticks = 0;
+    while env
+        .query(ledger_id, "icrc1_balance_of", args.clone())
+        .is_err()
+    {
+        assert!(num_ticks < 100, "the migration did not finish");
+        env.tick();
+        num_ticks += 1;
+    }
+
+    env.upgrade_canister(
+        ledger_id,
+        ledger_wasm(),
+        Encode!(&LedgerArgument::Upgrade(None)).unwrap(),
+    )
+    .expect("Unable to upgrade the ledger canister");
+
+    for (n, balance) in (1..=7).zip(balances) {
+        assert_eq!(balance_of(&env, ledger_id, account(n)), balance);
+    }
+    for (n, allowance) in (3..=5).zip(allowances) {
+        assert_eq!(
+            get_allowance(&env, ledger_id, account(n), account(n + 10)),
+            allowance
+        );
+    }
+    assert_eq!(
+        transfer_with_created_at_time(
+            &env,
+            ledger_id,
+            account(1),
+            account(6),
+            1_000_000,
+            created_at_time
+        ),
+        Err(TransferError::Duplicate {
+            duplicate_of: deduplicated_block
+        })
+    );
+}
+
 #[test]
 fn test_icrc2_feature_flag_doesnt_disable_icrc2_endpoints() {
     // Disable ICRC-2 and check the endpoints still work
@@ -6,7 +6,6 @@ use ic_ledger_core::approvals::{
 };
 use ic_ledger_core::tokens::Zero;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, VecDeque};
 use std::ops::Range;
 use std::time::Duration;
 
@@ -19,7 +18,7 @@ use ic_ledger_hash_of::HashOf;
 /// The memo to use for balances burned and approvals reset to 0 during trimming
 const TRIMMED_MEMO: u64 = u64::MAX;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TransactionInfo<TransactionType> {
     pub block_timestamp: TimeStamp,
     pub transaction_hash: HashOf<TransactionType>,
@@ -177,11 +176,34 @@ pub trait LedgerData: LedgerContext {
     fn blockchain(&self) -> &Blockchain<Self::Runtime, Self::ArchiveWasm>;
     fn blockchain_mut(&mut self) -> &mut Blockchain<Self::Runtime, Self::ArchiveWasm>;
 
-    fn transactions_by_hash(&self) -> &BTreeMap<HashOf<Self::Transaction>, BlockIndex>;
-    fn transactions_by_hash_mut(&mut self) -> &mut BTreeMap<HashOf<Self::Transaction>, BlockIndex>;
+    // Transaction deduplication window
 
-    fn transactions_by_height(&self) -> &VecDeque<TransactionInfo<Self::Transaction>>;
-    fn transactions_by_height_mut(&mut self) -> &mut VecDeque<TransactionInfo<Self::Transaction>>;
+    /// Returns the index of the block containing the transaction with the
+    /// specified hash if the transaction is within the deduplication window.
+    fn get_transaction_by_hash(&self, hash: &HashOf<Self::Transaction>) -> Option<BlockIndex>;
+
+    /// Remembers that the transaction with the specified hash is in the block
+    /// with the specified index.
+    fn insert_transaction_by_hash(&mut self, hash: HashOf<Self::Transaction>, height: BlockIndex);
+
+    /// Forgets the transaction with the specified hash and returns the index
+    /// of the block containing it.
+    fn remove_transaction_by_hash(
+        &mut self,
+        hash: &HashOf<Self::Transaction>,
+    ) -> Option<BlockIndex>;
+
+    /// Returns the number of transactions within the deduplication window.
+    fn transactions_by_height_len(&self) -> usize;
+
+    /// Returns the `i`-th oldest transaction within the deduplication window.
+    fn get_transaction_by_height(&self, i: usize) -> Option<TransactionInfo<Self::Transaction>>;
+
+    /// Appends a transaction to the deduplication window.
+    fn push_transaction_by_height(&mut self, tx_info: TransactionInfo<Self::Transaction>);
+
+    /// Removes the oldest transaction from the deduplication window.
+    fn pop_transaction_by_height(&mut self) -> Option<TransactionInfo<Self::Transaction>>;
 
     /// The callback that the ledger framework calls when it purges a transaction.
     fn on_purged_transaction(&mut self, height: BlockIndex);
@@ -243,9 +265,9 @@ where
             return Err(TransferError::TxCreatedInFuture { ledger_time: now });
         }
 
-        if let Some(block_height) = ledger.transactions_by_hash().get(&tx_hash) {
+        if let Some(block_height) = ledger.get_transaction_by_hash(&tx_hash) {
             return Err(TransferError::TxDuplicate {
-                duplicate_of: *block_height,
+                duplicate_of: block_height,
             });
         }
     }
@@ -291,17 +313,18 @@ where
     if let Some((_, tx_hash)) = maybe_time_and_hash {
         // The caller requested deduplication, so we have to remember this
         // transaction within the dedup window.
-        ledger.transactions_by_hash_mut().insert(tx_hash, height);
+        ledger.insert_transaction_by_hash(tx_hash, height);
 
-        ledger
-            .transactions_by_height_mut()
-            .push_back(TransactionInfo {
-                block_timestamp,
-                transaction_hash: tx_hash,
-            });
+        ledger.push_transaction_by_height(TransactionInfo {
+            block_timestamp,
+            transaction_hash: tx_hash,
+        });
     }
-    let effective_max_number_of_accounts =
-        ledger.max_number_of_accounts() + ledger.accounts_overflow_trim_quantity() - 1;
+    // Ledgers that do not limit the number of accounts return usize::MAX
+    // from max_number_of_accounts(), so the sum must not overflow.
+    let effective_max_number_of_accounts = ledger
+        .max_number_of_accounts()
+        .saturating_add(ledger.accounts_overflow_trim_quantity() - 1);
 
     let to_trim = if ledger.balances().store.len() > effective_max_number_of_accounts {
         select_accounts_to_trim(ledger)
@@ -411,7 +434,7 @@ pub fn find_block_in_archive<L: LedgerData>(ledger: &L, block_height: u64) -> Op
 /// Returns true if the next transaction should be throttled due to high
 /// load on the ledger.
 fn throttle<L: LedgerData>(ledger: &L, now: TimeStamp) -> bool {
-    let num_in_window = ledger.transactions_by_height().len();
+    let num_in_window = ledger.transactions_by_height_len();
     // We admit the first half of max_transactions_in_window freely.
     // After that we start throttling on per-second basis.
     // This way we guarantee that at most max_transactions_in_window will
@@ -423,8 +446,7 @@ fn throttle<L: LedgerData>(ledger: &L, now: TimeStamp) -> bool {
         .ceil() as usize;
 
         if ledger
-            .transactions_by_height()
-            .get(num_in_window.saturating_sub(max_rate))
+            .get_transaction_by_height(num_in_window.saturating_sub(max_rate))
             .map(|tx| tx.block_timestamp)
             .unwrap_or_else(|| TimeStamp::from_nanos_since_unix_epoch(0))
             + Duration::from_secs(1)
@@ -443,7 +465,7 @@ pub fn purge_old_transactions<L: LedgerData>(ledger: &mut L, now: TimeStamp) ->
     let max_tx_to_purge = ledger.max_transactions_to_purge();
     let mut num_tx_purged = 0usize;
 
-    while let Some(tx_info) = ledger.transactions_by_height().front() {
+    while let Some(tx_info) = ledger.get_transaction_by_height(0) {
         if tx_info.block_timestamp + ledger.transaction_window() + ic_constants::PERMITTED_DRIFT
             >= now
         {
@@ -453,7 +475,7 @@ pub fn purge_old_transactions<L: LedgerData>(ledger: &mut L, now: TimeStamp) ->
 
         let transaction_hash = tx_info.transaction_hash;
 
-        match ledger.transactions_by_hash_mut().remove(&transaction_hash) {
+        match ledger.remove_transaction_by_hash(&transaction_hash) {
             None => unreachable!(
                 concat!(
                     "invariant violation: transaction with hash {} ",
@@ -464,7 +486,7 @@ pub fn purge_old_transactions<L: LedgerData>(ledger: &mut L, now: TimeStamp) ->
             Some(block_height) => ledger.on_purged_transaction(block_height),
         }
 
-        ledger.transactions_by_height_mut().pop_front();
+        ledger.pop_transaction_by_height();
 
         num_tx_purged += 1;
         if num_tx_purged >= max_tx_to_purge {
@@ -490,15 +512,15 @@ where
 
     // Accumulate up to `trim_quantity` accounts
     for (account, balance) in iter.by_ref().take(num_accounts) {
-        to_trim.push((balance.clone(), account.clone()));
+        to_trim.push((balance, account));
     }
 
     for (account, balance) in iter {
         // If any account's balance is lower than the maximum in our set,
         // include that account, and remove the current maximum
         if let Some((greatest_balance, _)) = to_trim.peek() {
-            if balance < greatest_balance {
-                to_trim.push((balance.clone(), account.clone()));
+            if &balance < greatest_balance {
+                to_trim.push((balance, account));
                 to_trim.pop();
             }
         }
@@ -32,7 +32,7 @@ impl From<Key> for (Account, Account) {
     }
 }
 
-type TestAllowanceTable = AllowanceTable<Key, Account, Tokens>;
+type TestAllowanceTable = AllowanceTable<HeapAllowancesData<Key, Account, Tokens>>;
 
 #[test]
 fn allowance_table_default() {
@@ -47,9 +47,10 @@ use ic_sns_governance::{
         GetModeResponse, GetNeuron, GetNeuronResponse, GetProposal, GetProposalResponse,
         GetRunningSnsVersionRequest, GetRunningSnsVersionResponse,
         GetSnsInitializationParametersRequest, GetSnsInitializationParametersResponse,
-        Governance as GovernanceProto, ListNervousSystemFunctionsResponse, ListNeurons,
-        ListNeuronsResponse, ListProposals, ListProposalsResponse, ManageNeuron,
-        ManageNeuronResponse, NervousSystemParameters, RewardEvent, SetMode, SetModeResponse,
+        GetTreasuryBalancesRequest, GetTreasuryBalancesResponse, Governance as GovernanceProto,
+        ListNervousSystemFunctionsResponse, ListNeurons, ListNeuronsResponse, ListProposals,
+        ListProposalsResponse, ManageNeuron, ManageNeuronResponse, NervousSystemParameters,
+        RewardEvent, SetMode, SetModeResponse,
     },
     types::{Environment, HeapGrowthPotential},
 };
@@ -625,6 +626,20 @@ fn get_maturity_modulation_(
     )
 }
 
+/// Returns the most recently fetched balances of the ICRC-1 tokens held in the SNS treasury
+/// (other than ICP and the SNS token).
+#[export_name = "canister_query get_treasury_balances"]
+fn get_treasury_balances() {
+    log!(INFO, "get_treasury_balances");
+    over(candid_one, get_treasury_balances_)
+}
+
+/// Internal method for calling get_treasury_balances.
+#[candid_method(query, rename = "get_treasury_balances")]
+fn get_treasury_balances_(request: GetTreasuryBalancesRequest) -> GetTreasuryBalancesResponse {
+    governance().get_treasury_balances(request)
+}
+
 /// The canister's heartbeat.
 #[export_name = "canister_heartbeat"]
 fn canister_heartbeat() {
@@ -348,6 +348,11 @@ pub struct TransferSnsTreasuryFunds {
     /// An (optional) Subaccount of the principal to transfer the funds to.
     #[prost(message, optional, tag = "5")]
     pub to_subaccount: ::core::option::Option<Subaccount>,
+    /// The ledger of the token to transfer. Must be set if and only if from_treasury is
+    /// TRANSFER_FROM_ICRC1_TOKEN_TREASURY, in which case it must be the ledger of one of
+    /// the tokens in Governance.treasury_tokens.
+    #[prost(message, optional, tag = "6")]
+    pub ledger_canister_id: ::core::option::Option<::ic_base_types::PrincipalId>,
 }
 /// Nested message and enum types in `TransferSnsTreasuryFunds`.
 pub mod transfer_sns_treasury_funds {
@@ -372,6 +377,9 @@ pub mod transfer_sns_treasury_funds {
         Unspecified = 0,
         IcpTreasury = 1,
         SnsTokenTreasury = 2,
+        /// Transfer from the treasury of one of the ICRC-1 tokens in
+        /// Governance.treasury_tokens, identified by ledger_canister_id.
+        Icrc1TokenTreasury = 3,
     }
     impl TransferFrom {
         /// String value of the enum field names used in the ProtoBuf definition.
@@ -383,6 +391,7 @@ pub mod transfer_sns_treasury_funds {
                 TransferFrom::Unspecified => "TRANSFER_FROM_UNSPECIFIED",
                 TransferFrom::IcpTreasury => "TRANSFER_FROM_ICP_TREASURY",
                 TransferFrom::SnsTokenTreasury => "TRANSFER_FROM_SNS_TOKEN_TREASURY",
+                TransferFrom::Icrc1TokenTreasury => "TRANSFER_FROM_ICRC1_TOKEN_TREASURY",
             }
         }
         /// Creates an enum from field names used in the ProtoBuf definition.
@@ -391,11 +400,69 @@ pub mod transfer_sns_treasury_funds {
                 "TRANSFER_FROM_UNSPECIFIED" => Some(Self::Unspecified),
                 "TRANSFER_FROM_ICP_TREASURY" => Some(Self::IcpTreasury),
                 "TRANSFER_FROM_SNS_TOKEN_TREASURY" => Some(Self::SnsTokenTreasury),
+                "TRANSFER_FROM_ICRC1_TOKEN_TREASURY" => Some(Self::Icrc1TokenTreasury),
                 _ => None,
             }
         }
     }
 }
+/// An ICRC-1 token, other than ICP and the SNS token, that is held in the
+/// treasury of the SNS, i.e., in the default account of the governance canister.
+#[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TreasuryToken {
+    /// The ledger canister of the token.
+    #[prost(message, optional, tag = "1")]
+    pub ledger_canister_id: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// The symbol of the token (e.g. "ckBTC"). Only used for display purposes.
+    #[prost(string, optional, tag = "2")]
+    pub symbol: ::core::option::Option<::prost::alloc::string::String>,
+    /// The symbol under which the exchange rate canister lists the asset that the
+    /// token represents (e.g. "BTC" for ckBTC). Used to value the treasury of the
+    /// token, e.g. to limit the transfers out of it.
+    #[prost(string, optional, tag = "3")]
+    pub exchange_rate_asset_symbol: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// A proposal to change which ICRC-1 tokens are tracked in the SNS treasury.
+#[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ManageTreasuryTokens {
+    /// The tokens to start tracking. If a token with the same ledger is already
+    /// tracked, its entry is replaced.
+    #[prost(message, repeated, tag = "1")]
+    pub tokens_to_add: ::prost::alloc::vec::Vec<TreasuryToken>,
+    /// The ledgers of the tokens to stop tracking.
+    #[prost(message, repeated, tag = "2")]
+    pub ledger_canister_ids_to_remove: ::prost::alloc::vec::Vec<::ic_base_types::PrincipalId>,
+}
+/// A proposal to approve (as defined by ICRC-2) a spender to transfer funds out of
+/// the treasury of one of the ICRC-1 tokens in Governance.treasury_tokens.
+#[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ApproveSnsTreasuryFunds {
+    /// The ledger of the token to approve.
+    #[prost(message, optional, tag = "1")]
+    pub ledger_canister_id: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// The allowance granted to the spender, in e8s.
+    #[prost(uint64, optional, tag = "2")]
+    pub amount_e8s: ::core::option::Option<u64>,
+    /// An optional memo to use for the approval.
+    #[prost(uint64, optional, tag = "3")]
+    pub memo: ::core::option::Option<u64>,
+    /// The principal that is allowed to spend the funds.
+    #[prost(message, optional, tag = "4")]
+    pub spender_principal: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// An (optional) Subaccount of the spender.
+    #[prost(message, optional, tag = "5")]
+    pub spender_subaccount: ::core::option::Option<Subaccount>,
+    /// When the allowance expires (seconds since UNIX epoch). If not set, the
+    /// allowance does not expire.
+    #[prost(uint64, optional, tag = "6")]
+    pub expires_at_timestamp_seconds: ::core::option::Option<u64>,
+}
 /// A proposal function that changes the ledger's parameters.
 /// Fields with None values will remain unchanged.
 #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
@@ -493,6 +560,26 @@ pub struct ManageDappCanisterSettings {
     #[prost(enumeration = "LogVisibility", optional, tag = "6")]
     pub log_visibility: ::core::option::Option<i32>,
 }
+/// A proposal to execute several actions, one after the other, as a single unit.
+///
+/// The actions are voted on together. The proposal is as critical as the most
+/// critical action that it contains, and following is based on the function id
+/// of that action.
+///
+/// When executed, the actions are performed in order. If one of them fails, the
+/// remaining ones are skipped, and changes that earlier actions made to the
+/// state of governance itself (e.g. nervous system parameters) are rolled back.
+/// Effects outside of governance (e.g. transfers, upgrades) cannot be undone.
+#[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecuteActionsInSequence {
+    /// The actions to perform. Only the `action` field of each element is used;
+    /// `title`, `summary`, and `url` must be empty, because those are taken from
+    /// the enclosing proposal. Nested ExecuteActionsInSequence are not allowed.
+    #[prost(message, repeated, tag = "1")]
+    pub actions: ::prost::alloc::vec::Vec<Proposal>,
+}
 /// A proposal is the immutable input of a proposal submission.
 #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
 #[compare_default]
@@ -522,7 +609,7 @@ pub struct Proposal {
     /// of this mapping.
     #[prost(
         oneof = "proposal::Action",
-        tags = "4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18"
+        tags = "4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21"
     )]
     pub action: ::core::option::Option<proposal::Action>,
 }
@@ -628,6 +715,21 @@ pub mod proposal {
         /// Id = 14.
         #[prost(message, tag = "18")]
         ManageDappCanisterSettings(super::ManageDappCanisterSettings),
+        /// Change which ICRC-1 tokens are tracked in the SNS treasury.
+        ///
+        /// Id = 15.
+        #[prost(message, tag = "19")]
+        ManageTreasuryTokens(super::ManageTreasuryTokens),
+        /// Approve a spender to transfer funds out of the treasury of an ICRC-1 token.
+        ///
+        /// Id = 16.
+        #[prost(message, tag = "20")]
+        ApproveSnsTreasuryFunds(super::ApproveSnsTreasuryFunds),
+        /// Execute several of the above actions, in order, as a single unit.
+        ///
+        /// Id = 17.
+        #[prost(message, tag = "21")]
+        ExecuteActionsInSequence(super::ExecuteActionsInSequence),
     }
 }
 #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
@@ -835,6 +937,9 @@ pub struct ProposalData {
     /// Id 8 - ManageSnsMetadata proposals.
     /// Id 9 - TransferSnsTreasuryFunds proposals.
     /// Id 13 - ManageLedgerParameters proposals.
+    /// Id 15 - ManageTreasuryTokens proposals.
+    /// Id 16 - ApproveSnsTreasuryFunds proposals.
+    /// Id 17 - ExecuteActionsInSequence proposals.
     #[prost(uint64, tag = "1")]
     pub action: u64,
     /// This is stored here temporarily. It is also stored on the map
@@ -1335,6 +1440,14 @@ pub struct Governance {
     pub is_finalizing_disburse_maturity: ::core::option::Option<bool>,
     #[prost(message, optional, tag = "26")]
     pub maturity_modulation: ::core::option::Option<governance::MaturityModulation>,
+    /// The ICRC-1 tokens, other than ICP and the SNS token, that are held in the
+    /// treasury. Changed via ManageTreasuryTokens proposals.
+    #[prost(message, repeated, tag = "27")]
+    pub treasury_tokens: ::prost::alloc::vec::Vec<TreasuryToken>,
+    /// Periodically refreshed by the heartbeat, so that the balances can be served
+    /// by a query.
+    #[prost(message, optional, tag = "28")]
+    pub cached_treasury_balances: ::core::option::Option<governance::CachedTreasuryBalances>,
 }
 /// Nested message and enum types in `Governance`.
 pub mod governance {
@@ -1553,6 +1666,19 @@ pub mod governance {
         #[prost(uint64, optional, tag = "2")]
         pub updated_at_timestamp_seconds: ::core::option::Option<u64>,
     }
+    #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct CachedTreasuryBalances {
+        /// The balances of the treasury_tokens treasuries. The ICP and SNS token
+        /// treasuries are not included, their balances are served by the ledgers.
+        #[prost(message, repeated, tag = "1")]
+        pub balances: ::prost::alloc::vec::Vec<super::TreasuryBalance>,
+        /// When the balances were last fetched from the ledgers (seconds since UNIX
+        /// epoch).
+        #[prost(uint64, optional, tag = "2")]
+        pub updated_at_timestamp_seconds: ::core::option::Option<u64>,
+    }
     #[derive(
         candid::CandidType,
         candid::Deserialize,
@@ -1601,6 +1727,39 @@ pub mod governance {
         }
     }
 }
+/// The balance of one of the treasuries of the SNS.
+#[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TreasuryBalance {
+    /// The ledger canister of the token.
+    #[prost(message, optional, tag = "1")]
+    pub ledger_canister_id: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// The symbol of the token (e.g. "ckBTC").
+    #[prost(string, optional, tag = "2")]
+    pub symbol: ::core::option::Option<::prost::alloc::string::String>,
+    /// The balance of the treasury account, in e8s. Not set if the balance could
+    /// not be fetched from the ledger.
+    #[prost(uint64, optional, tag = "3")]
+    pub balance_e8s: ::core::option::Option<u64>,
+}
+/// Request message for 'get_treasury_balances'.
+#[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTreasuryBalancesRequest {}
+/// Response message for 'get_treasury_balances'.
+#[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTreasuryBalancesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub balances: ::prost::alloc::vec::Vec<TreasuryBalance>,
+    /// When the balances were last fetched from the ledgers (seconds since UNIX
+    /// epoch).
+    #[prost(uint64, optional, tag = "2")]
+    pub updated_at_timestamp_seconds: ::core::option::Option<u64>,
+}
 /// Request message for 'get_metadata'.
 #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
 #[allow(clippy::derive_partial_eq_without_eq)]
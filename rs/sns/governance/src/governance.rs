@@ -40,39 +40,45 @@ use crate::{
             neuron::{DissolveState, Followees},
             proposal::Action,
             transfer_sns_treasury_funds::TransferFrom,
-            Account as AccountProto, Ballot, ClaimSwapNeuronsError, ClaimSwapNeuronsRequest,
-            ClaimSwapNeuronsResponse, ClaimedSwapNeuronStatus, DefaultFollowees,
-            DeregisterDappCanisters, DisburseMaturityInProgress, Empty,
-            ExecuteGenericNervousSystemFunction, FailStuckUpgradeInProgressRequest,
-            FailStuckUpgradeInProgressResponse, GetMaturityModulationRequest,
-            GetMaturityModulationResponse, GetMetadataRequest, GetMetadataResponse, GetMode,
-            GetModeResponse, GetNeuron, GetNeuronResponse, GetProposal, GetProposalResponse,
-            GetSnsInitializationParametersRequest, GetSnsInitializationParametersResponse,
-            Governance as GovernanceProto, GovernanceError, ListNervousSystemFunctionsResponse,
-            ListNeurons, ListNeuronsResponse, ListProposals, ListProposalsResponse,
-            ManageDappCanisterSettings, ManageLedgerParameters, ManageNeuron, ManageNeuronResponse,
-            ManageSnsMetadata, MintSnsTokens, NervousSystemFunction, NervousSystemParameters,
-            Neuron, NeuronId, NeuronPermission, NeuronPermissionList, NeuronPermissionType,
-            Proposal, ProposalData, ProposalDecisionStatus, ProposalId, ProposalRewardStatus,
-            RegisterDappCanisters, RewardEvent, Tally, TransferSnsTreasuryFunds,
+            Account as AccountProto, ApproveSnsTreasuryFunds, Ballot, ClaimSwapNeuronsError,
+            ClaimSwapNeuronsRequest, ClaimSwapNeuronsResponse, ClaimedSwapNeuronStatus,
+            DefaultFollowees, DeregisterDappCanisters, DisburseMaturityInProgress, Empty,
+            ExecuteActionsInSequence, ExecuteGenericNervousSystemFunction,
+            FailStuckUpgradeInProgressRequest, FailStuckUpgradeInProgressResponse,
+            GetMaturityModulationRequest, GetMaturityModulationResponse, GetMetadataRequest,
+            GetMetadataResponse, GetMode, GetModeResponse, GetNeuron, GetNeuronResponse,
+            GetProposal, GetProposalResponse, GetSnsInitializationParametersRequest,
+            GetSnsInitializationParametersResponse, GetTreasuryBalancesRequest,
+            GetTreasuryBalancesResponse, Governance as GovernanceProto, GovernanceError,
+            ListNervousSystemFunctionsResponse, ListNeurons, ListNeuronsResponse, ListProposals,
+            ListProposalsResponse, ManageDappCanisterSettings, ManageLedgerParameters,
+            ManageNeuron, ManageNeuronResponse, ManageSnsMetadata, ManageTreasuryTokens,
+            MintSnsTokens, NervousSystemFunction, NervousSystemParameters, Neuron, NeuronId,
+            NeuronPermission, NeuronPermissionList, NeuronPermissionType, Proposal, ProposalData,
+            ProposalDecisionStatus, ProposalId, ProposalRewardStatus, RegisterDappCanisters,
+            RewardEvent, Tally, TransferSnsTreasuryFunds, TreasuryBalance, TreasuryToken,
             UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote, VotingRewardsParameters,
             WaitForQuietState,
         },
     },
     proposal::{
-        validate_and_render_proposal, ValidGenericNervousSystemFunction, MAX_LIST_PROPOSAL_RESULTS,
+        treasury_transfer_total_is_small_enough_or_err, validate_and_render_proposal,
+        ValidGenericNervousSystemFunction, MAX_LIST_PROPOSAL_RESULTS,
         MAX_NUMBER_OF_PROPOSALS_WITH_BALLOTS,
     },
     sns_upgrade::{
         get_all_sns_canisters, get_running_version, get_upgrade_params, get_wasm, SnsCanisterType,
         UpgradeSnsParams,
     },
+    treasury::{
+        self, find_treasury_token, is_valid_exchange_rate_asset_symbol, MAX_TREASURY_TOKENS,
+    },
     types::{
         function_id_to_proposal_criticality, is_registered_function_id, Environment,
         HeapGrowthPotential, LedgerUpdateLock,
     },
 };
-use candid::{Decode, Encode};
+use candid::{Decode, Encode, Nat};
 use dfn_core::api::{spawn, CanisterId};
 use ic_base_types::PrincipalId;
 use ic_canister_log::log;
@@ -95,8 +101,15 @@ use ic_nervous_system_governance::maturity_modulation::{
 use ic_nervous_system_root::change_canister::ChangeCanisterRequest;
 use ic_nns_constants::LEDGER_CANISTER_ID as NNS_LEDGER_CANISTER_ID;
 use ic_sns_governance_proposal_criticality::ProposalCriticality;
+use ic_sns_governance_token_valuation::try_get_icrc1_token_balance_valuation;
 use icp_ledger::DEFAULT_TRANSFER_FEE as NNS_DEFAULT_TRANSFER_FEE;
-use icrc_ledger_types::icrc1::account::{Account, Subaccount};
+use icrc_ledger_types::{
+    icrc1::{
+        account::{Account, Subaccount},
+        transfer::{Memo, TransferArg},
+    },
+    icrc2::approve::ApproveArgs,
+};
 use lazy_static::lazy_static;
 use maplit::hashset;
 use rust_decimal::Decimal;
@@ -151,6 +164,9 @@ pub fn log_prefix() -> String {
 /// The static MEMO used when calculating the SNS Treasury subaccount.
 pub const TREASURY_SUBACCOUNT_NONCE: u64 = 0;
 
+/// How often the balances of the treasury tokens are fetched from their ledgers.
+pub const TREASURY_BALANCES_REFRESH_INTERVAL_SECONDS: u64 = 60 * 60;
+
 /// Converts bytes to a subaccountpub fn bytes_to_subaccount(bytes: &[u8]) -> Result<icrc_ledger_types::icrc1::account::Subaccount, GovernanceError> {
 pub fn bytes_to_subaccount(
     bytes: &[u8],
@@ -2011,6 +2027,31 @@ impl Governance {
     /// that is what this proposal is supposed to do as a result of the proposal being
     /// adopted.
     async fn perform_action(&mut self, proposal_id: u64, action: Action) {
+        let result = match action {
+            Action::ExecuteActionsInSequence(execute_actions_in_sequence) => {
+                self.perform_execute_actions_in_sequence(proposal_id, execute_actions_in_sequence)
+                    .await
+            }
+            action => match self.perform_single_action(proposal_id, action).await {
+                Some(result) => result,
+                // The action has been kicked off, but not completed. Its outcome is recorded
+                // later (see UpgradeSnsToNextVersion).
+                None => return,
+            },
+        };
+
+        self.set_proposal_execution_status(proposal_id, result);
+    }
+
+    /// Performs an action other than ExecuteActionsInSequence, and returns its result.
+    ///
+    /// Returns None if the action has only been kicked off, in which case the status of the
+    /// proposal is set later, once the action completes.
+    async fn perform_single_action(
+        &mut self,
+        proposal_id: u64,
+        action: Action,
+    ) -> Option<Result<(), GovernanceError>> {
         let result = match action {
             // Execution of Motion proposals is trivial.
             Action::Motion(_) => Ok(()),
@@ -2037,7 +2078,7 @@ impl Governance {
                 // want to set the proposal status, and passing the value through is sufficient.
                 match upgrade_sns_result {
                     Ok(true) => Ok(()),
-                    Ok(false) => return,
+                    Ok(false) => return None,
                     Err(e) => Err(e),
                 }
             }
@@ -2063,9 +2104,17 @@ impl Governance {
                 self.perform_manage_sns_metadata(manage_sns_metadata)
             }
             Action::TransferSnsTreasuryFunds(transfer) => {
-                self.perform_transfer_sns_treasury_funds(transfer).await
+                self.perform_transfer_sns_treasury_funds(proposal_id, transfer)
+                    .await
             }
             Action::MintSnsTokens(mint) => self.perform_mint_sns_tokens(mint).await,
+            Action::ManageTreasuryTokens(manage_treasury_tokens) => {
+                self.perform_manage_treasury_tokens(manage_treasury_tokens)
+            }
+            Action::ApproveSnsTreasuryFunds(approve) => {
+                self.perform_approve_sns_treasury_funds(proposal_id, approve)
+                    .await
+            }
             Action::ManageLedgerParameters(manage_ledger_parameters) => {
                 self.perform_manage_ledger_parameters(proposal_id, manage_ledger_parameters)
                     .await
@@ -2074,6 +2123,12 @@ impl Governance {
                 self.perform_manage_dapp_canister_settings(manage_dapp_canister_settings)
                     .await
             }
+            // Sequences are performed by perform_execute_actions_in_sequence, and cannot be
+            // nested.
+            Action::ExecuteActionsInSequence(_) => Err(GovernanceError::new_with_message(
+                ErrorType::InvalidProposal,
+                "ExecuteActionsInSequence cannot be nested.",
+            )),
             // This should not be possible, because Proposal validation is performed when
             // a proposal is first made.
             Action::Unspecified(_) => Err(GovernanceError::new_with_message(
@@ -2086,7 +2141,101 @@ impl Governance {
             )),
         };
 
-        self.set_proposal_execution_status(proposal_id, result);
+        Some(result)
+    }
+
+    /// Performs the actions of an ExecuteActionsInSequence proposal, one after the other.
+    ///
+    /// Stops at the first action that fails. In that case, the changes that the preceding
+    /// actions made to the state of governance itself are rolled back (see
+    /// `SequenceRollback`), and the returned error says which action failed, and which
+    /// effects could not be rolled back.
+    async fn perform_execute_actions_in_sequence(
+        &mut self,
+        proposal_id: u64,
+        execute_actions_in_sequence: ExecuteActionsInSequence,
+    ) -> Result<(), GovernanceError> {
+        let actions = execute_actions_in_sequence
+            .actions
+            .into_iter()
+            .map(|proposal| proposal.action)
+            .collect::<Vec<_>>();
+        let action_count = actions.len();
+
+        let mut rollback = SequenceRollback::default();
+        let mut performed_action_names = vec![];
+        for (index, action) in actions.into_iter().enumerate() {
+            let action_name = action
+                .as_ref()
+                .map(|action| NervousSystemFunction::from(action.clone()).name)
+                .unwrap_or_default();
+
+            let result = match action {
+                None => Err(GovernanceError::new_with_message(
+                    ErrorType::InvalidProposal,
+                    "No action was specified.",
+                )),
+                Some(action) => {
+                    let state_part = SequenceStatePart::changed_by(&action);
+                    if let Some(state_part) = &state_part {
+                        rollback.save(state_part, &self.proto);
+                    }
+                    let result = self
+                        .perform_single_action(proposal_id, action)
+                        .await
+                        .unwrap_or_else(|| {
+                            Err(GovernanceError::new_with_message(
+                                ErrorType::InvalidProposal,
+                                "The action did not complete right away, which is not \
+                                 supported in a sequence.",
+                            ))
+                        });
+                    if let (Ok(()), Some(state_part)) = (&result, &state_part) {
+                        rollback.record(state_part, &self.proto);
+                    }
+                    result
+                }
+            };
+
+            if let Err(err) = result {
+                let outcome = rollback.restore(&mut self.proto);
+                log!(
+                    ERROR,
+                    "Action {} of {} ({}) of proposal {} failed: {}. Rolled back: {:?}. \
+                     Not rolled back, because changed in the meantime: {:?}",
+                    index + 1,
+                    action_count,
+                    action_name,
+                    proposal_id,
+                    err,
+                    outcome.rolled_back,
+                    outcome.changed_in_the_meantime,
+                );
+                return Err(GovernanceError::new_with_message(
+                    err.error_type(),
+                    format!(
+                        "Action {} of {} ({}) failed: {}\n\
+                         The remaining actions were skipped.\n\
+                         Previously performed actions: [{}].\n\
+                         Rolled back changes to: [{}].\n\
+                         Not rolled back, because changed by something else after the sequence \
+                         changed them: [{}]. Any other effects of the previously performed \
+                         actions remain in place.",
+                        index + 1,
+                        action_count,
+                        action_name,
+                        err.error_message,
+                        performed_action_names.join(", "),
+                        outcome.rolled_back.join(", "),
+                        outcome.changed_in_the_meantime.join(", "),
+                    ),
+                ));
+            }
+
+            performed_action_names.push(action_name);
+        }
+
+        Ok(())
     }
 
     /// Adds a new nervous system function to Governance if the given id for the nervous system
@@ -2570,6 +2719,7 @@ impl Governance {
 
     async fn perform_transfer_sns_treasury_funds(
         &mut self,
+        proposal_id: u64,
         transfer: TransferSnsTreasuryFunds,
     ) -> Result<(), GovernanceError> {
         let to = Account {
@@ -2624,6 +2774,36 @@ impl Governance {
                         )
                     })
             }
+            TransferFrom::Icrc1TokenTreasury => {
+                let ledger_canister_id = transfer.ledger_canister_id.ok_or_else(|| {
+                    GovernanceError::new_with_message(
+                        ErrorType::InvalidProposal,
+                        "Expected transfer to have a ledger_canister_id",
+                    )
+                })?;
+                let transaction_fee_e8s = self
+                    .treasury_token_transaction_fee_e8s(&ledger_canister_id)
+                    .await?;
+                self.treasury_token_transfer_total_is_small_enough_or_err(
+                    proposal_id,
+                    ledger_canister_id,
+                )
+                .await?;
+                treasury::icrc1_transfer(
+                    &*self.env,
+                    ledger_canister_id,
+                    TransferArg {
+                        from_subaccount: None,
+                        to,
+                        fee: Some(Nat::from(transaction_fee_e8s)),
+                        created_at_time: None,
+                        memo: Some(Memo::from(transfer.memo.unwrap_or(0))),
+                        amount: Nat::from(transfer.amount_e8s),
+                    },
+                )
+                .await
+                .map(|_| ())
+            }
             TransferFrom::Unspecified => Err(GovernanceError::new_with_message(
                 ErrorType::PreconditionFailed,
                 "Invalid 'from_treasury' in transfer.",
@@ -2631,6 +2811,200 @@ impl Governance {
         }
     }
 
+    async fn perform_approve_sns_treasury_funds(
+        &mut self,
+        proposal_id: u64,
+        approve: ApproveSnsTreasuryFunds,
+    ) -> Result<(), GovernanceError> {
+        let ledger_canister_id = approve.ledger_canister_id.ok_or_else(|| {
+            GovernanceError::new_with_message(
+                ErrorType::InvalidProposal,
+                "Expected approval to have a ledger_canister_id",
+            )
+        })?;
+        let amount_e8s = approve.amount_e8s.ok_or_else(|| {
+            GovernanceError::new_with_message(
+                ErrorType::InvalidProposal,
+                "Expected approval to have an amount_e8s",
+            )
+        })?;
+        let spender = Account {
+            owner: approve
+                .spender_principal
+                .ok_or_else(|| {
+                    GovernanceError::new_with_message(
+                        ErrorType::InvalidProposal,
+                        "Expected approval to have a spender principal",
+                    )
+                })?
+                .0,
+            subaccount: approve
+                .spender_subaccount
+                .as_ref()
+                .map(|s| bytes_to_subaccount(&s.subaccount[..]))
+                .transpose()?,
+        };
+        let transaction_fee_e8s = self
+            .treasury_token_transaction_fee_e8s(&ledger_canister_id)
+            .await?;
+        self.treasury_token_transfer_total_is_small_enough_or_err(proposal_id, ledger_canister_id)
+            .await?;
+        let expires_at = approve
+            .expires_at_timestamp_seconds
+            .map(|seconds| {
+                seconds.checked_mul(1_000_000_000).ok_or_else(|| {
+                    GovernanceError::new_with_message(
+                        ErrorType::InvalidProposal,
+                        format!("expires_at_timestamp_seconds is too large: {}", seconds),
+                    )
+                })
+            })
+            .transpose()?;
+
+        treasury::icrc2_approve(
+            &*self.env,
+            ledger_canister_id,
+            ApproveArgs {
+                from_subaccount: None,
+                spender,
+                amount: Nat::from(amount_e8s),
+                expected_allowance: None,
+                expires_at,
+                fee: Some(Nat::from(transaction_fee_e8s)),
+                memo: Some(Memo::from(approve.memo.unwrap_or(0))),
+                created_at_time: None,
+            },
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Returns the treasury token whose ledger is `ledger_canister_id`, or an error if the token
+    /// is not (or no longer) one of the treasury tokens.
+    fn treasury_token_or_err(
+        &self,
+        ledger_canister_id: &PrincipalId,
+    ) -> Result<&TreasuryToken, GovernanceError> {
+        find_treasury_token(&self.proto.treasury_tokens, ledger_canister_id).ok_or_else(|| {
+            GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                format!(
+                    "Ledger {} is not one of the treasury tokens of the SNS.",
+                    ledger_canister_id
+                ),
+            )
+        })
+    }
+
+    /// Returns the transaction fee that the ledger of the treasury token whose ledger is
+    /// `ledger_canister_id` currently charges, or an error if the token is not (or no longer)
+    /// one of the treasury tokens.
+    async fn treasury_token_transaction_fee_e8s(
+        &self,
+        ledger_canister_id: &PrincipalId,
+    ) -> Result<u64, GovernanceError> {
+        self.treasury_token_or_err(ledger_canister_id)?;
+        treasury::icrc1_fee(&*self.env, *ledger_canister_id).await
+    }
+
+    /// Returns an error if performing the transfers and approvals of proposal `proposal_id` out
+    /// of the treasury of the token whose ledger is `ledger_canister_id` would exceed the limit
+    /// on treasury transfers (see `treasury_transfer_total_is_small_enough_or_err`).
+    async fn treasury_token_transfer_total_is_small_enough_or_err(
+        &self,
+        proposal_id: u64,
+        ledger_canister_id: PrincipalId,
+    ) -> Result<(), GovernanceError> {
+        let token = self.treasury_token_or_err(&ledger_canister_id)?;
+        let symbol = token.symbol.clone().unwrap_or_default();
+        let exchange_rate_asset_symbol = token
+            .exchange_rate_asset_symbol
+            .clone()
+            .filter(|symbol| is_valid_exchange_rate_asset_symbol(symbol))
+            .ok_or_else(|| {
+                GovernanceError::new_with_message(
+                    ErrorType::PreconditionFailed,
+                    format!(
+                        "The {} treasury token (ledger {}) does not have a valid exchange rate \
+                         asset symbol, so the value of its treasury cannot be determined.",
+                        symbol, ledger_canister_id
+                    ),
+                )
+            })?;
+        let treasury_account = Account {
+            owner: self.env.canister_id().get().0,
+            subaccount: None,
+        };
+        let valuation = try_get_icrc1_token_balance_valuation(
+            treasury_account,
+            CanisterId::unchecked_from_principal(ledger_canister_id),
+            exchange_rate_asset_symbol,
+        )
+        .await
+        .map_err(|err| {
+            GovernanceError::new_with_message(
+                ErrorType::External,
+                format!(
+                    "Unable to determine the value of the {} treasury (ledger {}): {:?}",
+                    symbol, ledger_canister_id, err
+                ),
+            )
+        })?;
+
+        treasury_transfer_total_is_small_enough_or_err(
+            proposal_id,
+            TransferFrom::Icrc1TokenTreasury,
+            Some(ledger_canister_id),
+            valuation,
+            &self.proto.proposals,
+            self.env.now(),
+        )
+    }
+
+    /// Adds (or replaces) and removes tokens in the registry of treasury tokens.
+    fn perform_manage_treasury_tokens(
+        &mut self,
+        manage_treasury_tokens: ManageTreasuryTokens,
+    ) -> Result<(), GovernanceError> {
+        let ManageTreasuryTokens {
+            tokens_to_add,
+            ledger_canister_ids_to_remove,
+        } = manage_treasury_tokens;
+
+        let mut treasury_tokens = self.proto.treasury_tokens.clone();
+        treasury_tokens.retain(|token| match &token.ledger_canister_id {
+            Some(ledger_canister_id) => !ledger_canister_ids_to_remove.contains(ledger_canister_id),
+            None => false,
+        });
+        for token in tokens_to_add {
+            match treasury_tokens
+                .iter_mut()
+                .find(|existing| existing.ledger_canister_id == token.ledger_canister_id)
+            {
+                Some(existing) => *existing = token,
+                None => treasury_tokens.push(token),
+            }
+        }
+
+        if treasury_tokens.len() > MAX_TREASURY_TOKENS {
+            return Err(GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                format!(
+                    "The SNS can hold at most {} treasury tokens.",
+                    MAX_TREASURY_TOKENS
+                ),
+            ));
+        }
+        self.proto.treasury_tokens = treasury_tokens;
+
+        // Make sure that the next heartbeat fetches the balances of the new set of tokens.
+        if let Some(cached_treasury_balances) = self.proto.cached_treasury_balances.as_mut() {
+            cached_treasury_balances.updated_at_timestamp_seconds = None;
+        }
+
+        Ok(())
+    }
+
     async fn perform_mint_sns_tokens(
         &mut self,
         mint: MintSnsTokens,
@@ -3190,7 +3564,7 @@ impl Governance {
             .expect("Proposer not found.")
             .neuron_fees_e8s += proposal_data.reject_cost_e8s;
 
-        let function_id = u64::from(action);
+        let function_id = action.following_function_id();
         // Cast a 'yes'-vote for the proposer, including following.
         Governance::cast_vote_and_cascade_follow(
             &proposal_id,
@@ -3438,7 +3812,7 @@ impl Governance {
         }
 
         // Update ballots.
-        let function_id = u64::from(action);
+        let function_id = action.following_function_id();
         Governance::cast_vote_and_cascade_follow(
             proposal_id,
             neuron_id,
@@ -4580,6 +4954,10 @@ impl Governance {
             self.update_maturity_modulation().await;
         }
 
+        if self.should_refresh_treasury_balances() {
+            self.refresh_treasury_balances().await;
+        }
+
         self.maybe_finalize_disburse_maturity().await;
 
         self.maybe_move_staked_maturity();
@@ -4711,6 +5089,91 @@ impl Governance {
         self.proto.maturity_modulation = Some(new_maturity_modulation);
     }
 
+    fn should_refresh_treasury_balances(&self) -> bool {
+        let updated_at_timestamp_seconds = self
+            .proto
+            .cached_treasury_balances
+            .as_ref()
+            .and_then(|cached| cached.updated_at_timestamp_seconds)
+            .unwrap_or_default();
+
+        let age_seconds = self.env.now().saturating_sub(updated_at_timestamp_seconds);
+        age_seconds >= TREASURY_BALANCES_REFRESH_INTERVAL_SECONDS
+    }
+
+    /// Fetches the balances of the treasury tokens from their ledgers, and stores them in
+    /// `cached_treasury_balances`. If the balance of a token cannot be fetched, its previously
+    /// cached balance (if any) is kept.
+    async fn refresh_treasury_balances(&mut self) {
+        if !self.should_refresh_treasury_balances() {
+            return;
+        }
+
+        // Bump the timestamp before making any calls, so that concurrent heartbeats do not
+        // start another refresh while this one is still waiting for replies.
+        let now = self.env.now();
+        let previous_balances = {
+            let cached = self
+                .proto
+                .cached_treasury_balances
+                .get_or_insert_with(Default::default);
+            cached.updated_at_timestamp_seconds = Some(now);
+            cached.balances.clone()
+        };
+
+        let treasury_account = Account {
+            owner: self.env.canister_id().get().0,
+            subaccount: None,
+        };
+        let mut balances = vec![];
+        for token in self.proto.treasury_tokens.clone() {
+            let Some(ledger_canister_id) = token.ledger_canister_id else {
+                continue;
+            };
+            let balance_e8s =
+                match treasury::icrc1_balance_of(&*self.env, ledger_canister_id, treasury_account)
+                    .await
+                {
+                    Ok(balance_e8s) => Some(balance_e8s),
+                    Err(err) => {
+                        log!(
+                            ERROR,
+                            "Couldn't fetch the treasury balance of ledger {}: {}",
+                            ledger_canister_id,
+                            err
+                        );
+                        previous_balances
+                            .iter()
+                            .find(|balance| balance.ledger_canister_id == Some(ledger_canister_id))
+                            .and_then(|balance| balance.balance_e8s)
+                    }
+                };
+            balances.push(TreasuryBalance {
+                ledger_canister_id: Some(ledger_canister_id),
+                symbol: token.symbol,
+                balance_e8s,
+            });
+        }
+
+        // The registry may have changed while the calls were in flight. Only report tokens
+        // that are still registered.
+        let treasury_tokens = &self.proto.treasury_tokens;
+        balances.retain(|balance| {
+            balance
+                .ledger_canister_id
+                .map(|ledger_canister_id| {
+                    find_treasury_token(treasury_tokens, &ledger_canister_id).is_some()
+                })
+                .unwrap_or(false)
+        });
+
+        let cached = self
+            .proto
+            .cached_treasury_balances
+            .get_or_insert_with(Default::default);
+        cached.balances = balances;
+    }
+
     /// Returns `true` if rewards should be distributed (which is the case if
     /// enough time has passed since the last reward event) and `false` otherwise
     fn should_distribute_rewards(&self) -> bool {
@@ -5435,6 +5898,22 @@ impl Governance {
         }
     }
 
+    /// Returns the most recently fetched balances of the treasury tokens.
+    pub fn get_treasury_balances(
+        &self,
+        _: GetTreasuryBalancesRequest,
+    ) -> GetTreasuryBalancesResponse {
+        let cached = self
+            .proto
+            .cached_treasury_balances
+            .clone()
+            .unwrap_or_default();
+        GetTreasuryBalancesResponse {
+            balances: cached.balances,
+            updated_at_timestamp_seconds: cached.updated_at_timestamp_seconds,
+        }
+    }
+
     #[cfg(feature = "test")]
     pub fn add_maturity(
         &mut self,
@@ -5501,6 +5980,227 @@ thread_local! {
     static ATTEMPTED_FIXING_MEMORY_ALLOCATIONS: RefCell<bool> = RefCell::new(false);
 }
 
+/// A part of the state of governance that an action in an ExecuteActionsInSequence proposal
+/// may change, and that can be rolled back if a later action in the sequence fails.
+enum SequenceStatePart {
+    Parameters,
+    SnsMetadata,
+    TreasuryTokens,
+    NervousSystemFunction(u64),
+    /// The ledger's transfer fee, which is mirrored in
+    /// `NervousSystemParameters.transaction_fee_e8s`.
+    LedgerParameters,
+}
+
+impl SequenceStatePart {
+    /// Returns the part of the state of governance that `action` changes, if any.
+    fn changed_by(action: &Action) -> Option<Self> {
+        match action {
+            Action::ManageNervousSystemParameters(_) => Some(Self::Parameters),
+            Action::ManageSnsMetadata(_) => Some(Self::SnsMetadata),
+            Action::ManageTreasuryTokens(_) => Some(Self::TreasuryTokens),
+            Action::AddGenericNervousSystemFunction(NervousSystemFunction { id, .. })
+            | Action::RemoveGenericNervousSystemFunction(id) => {
+                Some(Self::NervousSystemFunction(*id))
+            }
+            Action::ManageLedgerParameters(_) => Some(Self::LedgerParameters),
+            _ => None,
+        }
+    }
+}
+
+/// The value of a part of the state of governance before an ExecuteActionsInSequence proposal
+/// first changed it, and the value that the sequence last changed it to.
+struct SequenceSavedValue<T> {
+    before: T,
+    after: T,
+}
+
+impl<T: Clone + PartialEq> SequenceSavedValue<T> {
+    fn new(current: &T) -> Self {
+        Self {
+            before: current.clone(),
+            after: current.clone(),
+        }
+    }
+
+    /// Returns the value to roll back to, unless `current` is no longer what the sequence
+    /// changed it to (i.e. something else, such as another proposal executed while the
+    /// sequence was awaiting a call, changed it in the meantime).
+    fn rollback_value(self, current: &T) -> Option<T> {
+        (*current == self.after).then_some(self.before)
+    }
+}
+
+/// Remembers the parts of the state of governance that actions in an ExecuteActionsInSequence
+/// proposal change, both as they were before the sequence started and as the sequence left
+/// them, so that those changes can be rolled back if a later action in the sequence fails.
+///
+/// A part is only rolled back if it still holds the value that the sequence wrote. Otherwise,
+/// it was changed by something else while the sequence was awaiting a call, and that change
+/// is preserved.
+///
+/// Only effects that are confined to governance can be rolled back. Effects on other canisters
+/// (e.g. transfers, upgrades, and changes to dapp canisters) stay in place.
+#[derive(Default)]
+struct SequenceRollback {
+    parameters: Option<SequenceSavedValue<Option<NervousSystemParameters>>>,
+    sns_metadata: Option<SequenceSavedValue<Option<SnsMetadata>>>,
+    treasury_tokens: Option<SequenceSavedValue<Vec<TreasuryToken>>>,
+    /// For each touched function ID, the function that was registered (if any).
+    nervous_system_functions: BTreeMap<u64, SequenceSavedValue<Option<NervousSystemFunction>>>,
+    /// Whether the ledger's transfer fee (which is mirrored in
+    /// `NervousSystemParameters.transaction_fee_e8s`) might have been changed.
+    ledger_parameters_changed: bool,
+}
+
+/// What `SequenceRollback::restore` did, as descriptions of parts of the state of governance.
+struct SequenceRollbackOutcome {
+    rolled_back: Vec<String>,
+    changed_in_the_meantime: Vec<String>,
+}
+
+impl SequenceRollback {
+    /// Must be called before an action that changes `state_part` is performed. Only the first
+    /// call for a given part of the state has an effect, so that the state before the whole
+    /// sequence is restored.
+    fn save(&mut self, state_part: &SequenceStatePart, proto: &GovernanceProto) {
+        match state_part {
+            SequenceStatePart::Parameters => {
+                self.parameters
+                    .get_or_insert_with(|| SequenceSavedValue::new(&proto.parameters));
+            }
+            SequenceStatePart::SnsMetadata => {
+                self.sns_metadata
+                    .get_or_insert_with(|| SequenceSavedValue::new(&proto.sns_metadata));
+            }
+            SequenceStatePart::TreasuryTokens => {
+                self.treasury_tokens
+                    .get_or_insert_with(|| SequenceSavedValue::new(&proto.treasury_tokens));
+            }
+            SequenceStatePart::NervousSystemFunction(id) => {
+                self.nervous_system_functions.entry(*id).or_insert_with(|| {
+                    SequenceSavedValue::new(&proto.id_to_nervous_system_functions.get(id).cloned())
+                });
+            }
+            SequenceStatePart::LedgerParameters => {
+                self.ledger_parameters_changed = true;
+            }
+        }
+    }
+
+    /// Must be called right after an action that changes `state_part` was successfully
+    /// performed, to remember what the sequence changed it to.
+    fn record(&mut self, state_part: &SequenceStatePart, proto: &GovernanceProto) {
+        match state_part {
+            SequenceStatePart::Parameters => {
+                if let Some(saved) = self.parameters.as_mut() {
+                    saved.after = proto.parameters.clone();
+                }
+            }
+            SequenceStatePart::SnsMetadata => {
+                if let Some(saved) = self.sns_metadata.as_mut() {
+                    saved.after = proto.sns_metadata.clone();
+                }
+            }
+            SequenceStatePart::TreasuryTokens => {
+                if let Some(saved) = self.treasury_tokens.as_mut() {
+                    saved.after = proto.treasury_tokens.clone();
+                }
+            }
+            SequenceStatePart::NervousSystemFunction(id) => {
+                if let Some(saved) = self.nervous_system_functions.get_mut(id) {
+                    saved.after = proto.id_to_nervous_system_functions.get(id).cloned();
+                }
+            }
+            SequenceStatePart::LedgerParameters => (),
+        }
+    }
+
+    /// Restores the saved parts of the state that still hold the value the sequence wrote, and
+    /// returns a description of what was and was not restored.
+    fn restore(self, proto: &mut GovernanceProto) -> SequenceRollbackOutcome {
+        let mut outcome = SequenceRollbackOutcome {
+            rolled_back: vec![],
+            changed_in_the_meantime: vec![],
+        };
+        let mut report = |description: String, rolled_back: bool| {
+            if rolled_back {
+                outcome.rolled_back.push(description);
+            } else {
+                outcome.changed_in_the_meantime.push(description);
+            }
+        };
+
+        if let Some(saved) = self.parameters {
+            // The transfer fee must stay in sync with the ledger, whose upgrade cannot be undone.
+            // Hence, it is neither compared nor restored.
+            let current_transaction_fee_e8s = proto
+                .parameters
+                .as_ref()
+                .and_then(|parameters| parameters.transaction_fee_e8s);
+            let with_transaction_fee = |parameters: &Option<NervousSystemParameters>| {
+                let mut parameters = parameters.clone();
+                if self.ledger_parameters_changed {
+                    if let Some(parameters) = parameters.as_mut() {
+                        parameters.transaction_fee_e8s = current_transaction_fee_e8s;
+                    }
+                }
+                parameters
+            };
+            let saved = SequenceSavedValue {
+                before: with_transaction_fee(&saved.before),
+                after: with_transaction_fee(&saved.after),
+            };
+            let rollback_value = saved.rollback_value(&proto.parameters);
+            report(
+                "nervous system parameters".to_string(),
+                rollback_value.is_some(),
+            );
+            if let Some(parameters) = rollback_value {
+                proto.parameters = parameters;
+            }
+        }
+        if let Some(saved) = self.sns_metadata {
+            let rollback_value = saved.rollback_value(&proto.sns_metadata);
+            report("SNS metadata".to_string(), rollback_value.is_some());
+            if let Some(sns_metadata) = rollback_value {
+                proto.sns_metadata = sns_metadata;
+            }
+        }
+        if let Some(saved) = self.treasury_tokens {
+            let rollback_value = saved.rollback_value(&proto.treasury_tokens);
+            report("treasury tokens".to_string(), rollback_value.is_some());
+            if let Some(treasury_tokens) = rollback_value {
+                proto.treasury_tokens = treasury_tokens;
+                // Make sure that the next heartbeat fetches the balances of the restored tokens.
+                if let Some(cached_treasury_balances) = proto.cached_treasury_balances.as_mut() {
+                    cached_treasury_balances.updated_at_timestamp_seconds = None;
+                }
+            }
+        }
+        for (id, saved) in self.nervous_system_functions {
+            let rollback_value =
+                saved.rollback_value(&proto.id_to_nervous_system_functions.get(&id).cloned());
+            report(
+                format!("nervous system function {}", id),
+                rollback_value.is_some(),
+            );
+            match rollback_value {
+                Some(Some(function)) => {
+                    proto.id_to_nervous_system_functions.insert(id, function);
+                }
+                Some(None) => {
+                    proto.id_to_nervous_system_functions.remove(&id);
+                }
+                None => (),
+            }
+        }
+
+        outcome
+    }
+}
+
 fn err_if_another_upgrade_is_in_progress(
     id_to_proposal_data: &BTreeMap</* proposal ID */ u64, ProposalData>,
     executing_proposal_id: u64,
@@ -5516,7 +6216,19 @@ fn err_if_another_upgrade_is_in_progress(
             continue;
         }
 
-        if !upgrade_action_ids.contains(&proposal_data.action) {
+        // Sequences may contain upgrades too.
+        let is_upgrade = upgrade_action_ids.contains(&proposal_data.action)
+            || proposal_data
+                .proposal
+                .as_ref()
+                .and_then(|proposal| proposal.action.as_ref())
+                .map_or(false, |action| {
+                    action
+                        .constituent_actions()
+                        .into_iter()
+                        .any(|action| upgrade_action_ids.contains(&u64::from(action)))
+                });
+        if !is_upgrade {
             continue;
         }
 
@@ -5594,8 +6306,8 @@ mod tests {
             manage_neuron_response,
             nervous_system_function::{FunctionType, GenericNervousSystemFunction},
             neuron, Account as AccountProto, Motion, NeuronPermissionType, ProposalData,
-            ProposalId, Tally, UpgradeSnsControlledCanister, UpgradeSnsToNextVersion,
-            VotingRewardsParameters, WaitForQuietState,
+            ProposalId, Tally, TreasuryToken, UpgradeSnsControlledCanister,
+            UpgradeSnsToNextVersion, VotingRewardsParameters, WaitForQuietState,
         },
         reward,
         sns_upgrade::{
@@ -5621,6 +6333,7 @@ mod tests {
     use ic_nervous_system_common_test_keys::{
         TEST_NEURON_1_OWNER_PRINCIPAL, TEST_NEURON_2_OWNER_PRINCIPAL, TEST_USER1_KEYPAIR,
     };
+    use ic_nervous_system_common_test_utils::InterleavingTestLedger;
     use ic_nns_constants::SNS_WASM_CANISTER_ID;
     use ic_protobuf::types::v1::CanisterInstallMode as CanisterInstallModeProto;
     use ic_sns_test_utils::itest_helpers::UserInfo;
@@ -9973,4 +10686,358 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_manage_treasury_tokens_and_refresh_treasury_balances() {
+        // Step 1: Prepare the world.
+        let governance_canister_id = *TEST_GOVERNANCE_CANISTER_ID;
+        let ckbtc_ledger_canister_id = canister_test_id(2000);
+        let cketh_ledger_canister_id = canister_test_id(2001);
+
+        let mut env = NativeEnvironment::new(Some(governance_canister_id));
+        env.now = 1_700_000_000;
+        let treasury_account = Account {
+            owner: governance_canister_id.get().0,
+            subaccount: None,
+        };
+        env.set_call_canister_response(
+            ckbtc_ledger_canister_id,
+            "icrc1_balance_of",
+            Encode!(&treasury_account).unwrap(),
+            Ok(Encode!(&Nat::from(123_456_u64)).unwrap()),
+        );
+        // The ckETH ledger does not reply with a valid balance.
+        env.set_call_canister_response(
+            cketh_ledger_canister_id,
+            "icrc1_balance_of",
+            Encode!(&treasury_account).unwrap(),
+            Err((Some(1), "out of cycles".to_string())),
+        );
+
+        let mut governance = Governance::new(
+            basic_governance_proto().try_into().unwrap(),
+            Box::new(env),
+            Box::new(DoNothingLedger {}),
+            Box::new(DoNothingLedger {}),
+            Box::new(FakeCmc::new()),
+        );
+
+        let ckbtc = TreasuryToken {
+            ledger_canister_id: Some(ckbtc_ledger_canister_id.get()),
+            symbol: Some("ckBTC".to_string()),
+            exchange_rate_asset_symbol: Some("BTC".to_string()),
+        };
+        let cketh = TreasuryToken {
+            ledger_canister_id: Some(cketh_ledger_canister_id.get()),
+            symbol: Some("ckETH".to_string()),
+            exchange_rate_asset_symbol: Some("ETH".to_string()),
+        };
+
+        // Step 2: Run code under test.
+        governance
+            .perform_manage_treasury_tokens(ManageTreasuryTokens {
+                tokens_to_add: vec![ckbtc.clone(), cketh.clone()],
+                ledger_canister_ids_to_remove: vec![],
+            })
+            .unwrap();
+        assert!(governance.should_refresh_treasury_balances());
+        governance
+            .refresh_treasury_balances()
+            .now_or_never()
+            .unwrap();
+
+        // Step 3: Inspect results.
+        assert_eq!(governance.proto.treasury_tokens, vec![ckbtc.clone(), cketh]);
+        assert_eq!(
+            governance.get_treasury_balances(GetTreasuryBalancesRequest {}),
+            GetTreasuryBalancesResponse {
+                balances: vec![
+                    TreasuryBalance {
+                        ledger_canister_id: Some(ckbtc_ledger_canister_id.get()),
+                        symbol: Some("ckBTC".to_string()),
+                        balance_e8s: Some(123_456),
+                    },
+                    TreasuryBalance {
+                        ledger_canister_id: Some(cketh_ledger_canister_id.get()),
+                        symbol: Some("ckETH".to_string()),
+                        balance_e8s: None,
+                    },
+                ],
+                updated_at_timestamp_seconds: Some(1_700_000_000),
+            }
+        );
+        // Balances are not refreshed again until the set of tokens changes...
+        assert!(!governance.should_refresh_treasury_balances());
+        governance
+            .perform_manage_treasury_tokens(ManageTreasuryTokens {
+                tokens_to_add: vec![],
+                ledger_canister_ids_to_remove: vec![cketh_ledger_canister_id.get()],
+            })
+            .unwrap();
+        assert_eq!(governance.proto.treasury_tokens, vec![ckbtc]);
+        assert!(governance.should_refresh_treasury_balances());
+        governance
+            .refresh_treasury_balances()
+            .now_or_never()
+            .unwrap();
+        assert_eq!(
+            governance
+                .get_treasury_balances(GetTreasuryBalancesRequest {})
+                .balances,
+            vec![TreasuryBalance {
+                ledger_canister_id: Some(ckbtc_ledger_canister_id.get()),
+                symbol: Some("ckBTC".to_string()),
+                balance_e8s: Some(123_456),
+            }],
+        );
+
+        // ... or the refresh interval has passed.
+        assert!(!governance.should_refresh_treasury_balances());
+        governance.env.set_time_warp(TimeWarp {
+            delta_s: TREASURY_BALANCES_REFRESH_INTERVAL_SECONDS as i64,
+        });
+        assert!(governance.should_refresh_treasury_balances());
+    }
+
+    #[test]
+    fn test_execute_actions_in_sequence_rolls_back_on_failure() {
+        // Step 1: Prepare the world.
+        let now = 1_700_000_000;
+        let mut env = NativeEnvironment::new(Some(*TEST_GOVERNANCE_CANISTER_ID));
+        env.now = now;
+
+        let member = |action: Action| Proposal {
+            action: Some(action),
+            ..Default::default()
+        };
+        let rename = |name: &str| {
+            Action::ManageSnsMetadata(ManageSnsMetadata {
+                name: Some(name.to_string()),
+                ..Default::default()
+            })
+        };
+        let add_ckbtc = Action::ManageTreasuryTokens(ManageTreasuryTokens {
+            tokens_to_add: vec![TreasuryToken {
+                ledger_canister_id: Some(canister_test_id(2000).get()),
+                symbol: Some("ckBTC".to_string()),
+                exchange_rate_asset_symbol: Some("BTC".to_string()),
+            }],
+            ledger_canister_ids_to_remove: vec![],
+        });
+        let adopted_proposal = |id: u64, action: Action| ProposalData {
+            id: Some(ProposalId { id }),
+            decided_timestamp_seconds: now,
+            proposal: Some(member(action)),
+            latest_tally: Some(Tally {
+                timestamp_seconds: now,
+                yes: 100_000_000,
+                no: 0,
+                total: 100_000_000,
+            }),
+            ..Default::default()
+        };
+
+        let succeeding_sequence = Action::ExecuteActionsInSequence(ExecuteActionsInSequence {
+            actions: vec![member(rename("First")), member(rename("Second"))],
+        });
+        let failing_sequence = Action::ExecuteActionsInSequence(ExecuteActionsInSequence {
+            actions: vec![
+                member(rename("Third")),
+                member(add_ckbtc),
+                // There is no such function, so this fails.
+                member(Action::RemoveGenericNervousSystemFunction(1000)),
+                member(rename("Fourth")),
+            ],
+        });
+
+        let mut governance = Governance::new(
+            GovernanceProto {
+                proposals: btreemap! {
+                    1 => adopted_proposal(1, succeeding_sequence.clone()),
+                    2 => adopted_proposal(2, failing_sequence.clone()),
+                },
+                ..basic_governance_proto()
+            }
+            .try_into()
+            .unwrap(),
+            Box::new(env),
+            Box::new(DoNothingLedger {}),
+            Box::new(DoNothingLedger {}),
+            Box::new(FakeCmc::new()),
+        );
+
+        // Step 2 & 3: Run code under test, and inspect results.
+
+        // All actions of a successful sequence are performed.
+        governance
+            .perform_action(1, succeeding_sequence)
+            .now_or_never()
+            .unwrap();
+        let proposal = governance.get_proposal_data(ProposalId { id: 1 }).unwrap();
+        assert_eq!(proposal.status(), ProposalDecisionStatus::Executed);
+        assert_eq!(
+            governance.proto.sns_metadata.as_ref().unwrap().name,
+            Some("Second".to_string())
+        );
+
+        // When an action fails, the changes of the preceding actions are rolled back, and the
+        // remaining actions are skipped.
+        governance
+            .perform_action(2, failing_sequence)
+            .now_or_never()
+            .unwrap();
+        let proposal = governance.get_proposal_data(ProposalId { id: 2 }).unwrap();
+        assert_eq!(proposal.status(), ProposalDecisionStatus::Failed);
+        let failure_reason = proposal.failure_reason.clone().unwrap();
+        assert_eq!(failure_reason.error_type(), ErrorType::NotFound);
+        for expected in [
+            "Action 3 of 4 (Remove nervous system function) failed",
+            "Previously performed actions: [Manage SNS metadata, Manage treasury tokens]",
+            "Rolled back changes to: [SNS metadata, treasury tokens]",
+        ] {
+            assert!(
+                failure_reason.error_message.contains(expected),
+                "{}",
+                failure_reason.error_message
+            );
+        }
+        assert_eq!(
+            governance.proto.sns_metadata.as_ref().unwrap().name,
+            Some("Second".to_string())
+        );
+        assert_eq!(governance.proto.treasury_tokens, vec![]);
+    }
+
+    #[test]
+    fn test_execute_actions_in_sequence_keeps_concurrent_changes_on_rollback() {
+        // Step 1: Prepare the world.
+        let now = 1_700_000_000;
+        let mut env = NativeEnvironment::new(Some(*TEST_GOVERNANCE_CANISTER_ID));
+        env.now = now;
+
+        let member = |action: Action| Proposal {
+            action: Some(action),
+            ..Default::default()
+        };
+        let rename = |name: &str| {
+            Action::ManageSnsMetadata(ManageSnsMetadata {
+                name: Some(name.to_string()),
+                ..Default::default()
+            })
+        };
+        let adopted_proposal = |id: u64, action: Action| ProposalData {
+            id: Some(ProposalId { id }),
+            decided_timestamp_seconds: now,
+            proposal: Some(member(action)),
+            latest_tally: Some(Tally {
+                timestamp_seconds: now,
+                yes: 100_000_000,
+                no: 0,
+                total: 100_000_000,
+            }),
+            ..Default::default()
+        };
+
+        let sequence = Action::ExecuteActionsInSequence(ExecuteActionsInSequence {
+            actions: vec![
+                member(rename("Sequence")),
+                member(Action::ManageNervousSystemParameters(
+                    NervousSystemParameters {
+                        reject_cost_e8s: Some(42),
+                        ..Default::default()
+                    },
+                )),
+                // The ledger is made to fail this transfer (see below).
+                member(Action::TransferSnsTreasuryFunds(TransferSnsTreasuryFunds {
+                    from_treasury: TransferFrom::IcpTreasury as i32,
+                    amount_e8s: 100_000_000,
+                    to_principal: Some(*TEST_NEURON_1_OWNER_PRINCIPAL),
+                    ..Default::default()
+                })),
+            ],
+        });
+        let concurrent_rename = rename("Concurrent");
+
+        let (ledger_sender, mut ledger_receiver) = futures::channel::mpsc::unbounded();
+        let proto = GovernanceProto {
+            proposals: btreemap! {
+                1 => adopted_proposal(1, sequence.clone()),
+                2 => adopted_proposal(2, concurrent_rename.clone()),
+            },
+            ..basic_governance_proto()
+        };
+        let original_parameters = proto.parameters.clone();
+        let mut governance = Governance::new(
+            proto.try_into().unwrap(),
+            Box::new(env),
+            Box::new(DoNothingLedger {}),
+            Box::new(InterleavingTestLedger::new(
+                Box::new(DoNothingLedger {}),
+                ledger_sender,
+            )),
+            Box::new(FakeCmc::new()),
+        );
+
+        // Step 2: Run code under test.
+
+        // This lets us execute another proposal while the sequence is awaiting the ledger.
+        let raw_governance = &mut governance as *mut Governance;
+
+        // Step 2.1: Start executing the sequence, which stops at the ICP transfer.
+        let mut sequence_future =
+            Box::pin(unsafe { raw_governance.as_mut().unwrap().perform_action(1, sequence) });
+        let mut context = std::task::Context::from_waker(futures::task::noop_waker_ref());
+        assert!(sequence_future.poll_unpin(&mut context).is_pending());
+        let (_transfer, continue_transfer) = ledger_receiver
+            .try_next()
+            .unwrap()
+            .expect("The sequence should be awaiting the ICP transfer.");
+
+        // Step 2.2: Meanwhile, another proposal changes the SNS metadata again.
+        unsafe {
+            raw_governance
+                .as_mut()
+                .unwrap()
+                .perform_action(2, concurrent_rename)
+                .now_or_never()
+                .unwrap();
+        }
+        assert_eq!(
+            governance.proto.sns_metadata.as_ref().unwrap().name,
+            Some("Concurrent".to_string())
+        );
+
+        // Step 2.3: Let the transfer fail, which makes the sequence roll back.
+        continue_transfer
+            .send(Err(NervousSystemError::new_with_message(
+                "Transfer rejected by test.",
+            )))
+            .unwrap();
+        assert!(sequence_future.poll_unpin(&mut context).is_ready());
+        drop(sequence_future);
+
+        // Step 3: Inspect results.
+        let proposal = governance.get_proposal_data(ProposalId { id: 1 }).unwrap();
+        assert_eq!(proposal.status(), ProposalDecisionStatus::Failed);
+        let failure_reason = proposal.failure_reason.clone().unwrap();
+        for expected in [
+            "Action 3 of 3 (Transfer SNS treasury funds) failed",
+            "Rolled back changes to: [nervous system parameters]",
+            "changed them: [SNS metadata]",
+        ] {
+            assert!(
+                failure_reason.error_message.contains(expected),
+                "{}",
+                failure_reason.error_message
+            );
+        }
+
+        // The parameters, which only the sequence changed, are rolled back ...
+        assert_eq!(governance.proto.parameters, original_parameters);
+        // ... but the change that the other proposal made to the SNS metadata is kept.
+        assert_eq!(
+            governance.proto.sns_metadata.as_ref().unwrap().name,
+            Some("Concurrent".to_string())
+        );
+    }
 }
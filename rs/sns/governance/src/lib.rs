@@ -11,6 +11,7 @@ pub mod pb;
 pub mod proposal;
 pub mod reward;
 pub mod sns_upgrade;
+pub mod treasury;
 pub mod types;
 
 trait Len {
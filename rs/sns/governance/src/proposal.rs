@@ -4,17 +4,24 @@ use crate::{
     logs::{ERROR, INFO},
     pb::v1::{
         governance::{SnsMetadata, Version},
+        governance_error::ErrorType,
         nervous_system_function::{FunctionType, GenericNervousSystemFunction},
         proposal,
         proposal::Action,
         transfer_sns_treasury_funds::TransferFrom,
-        DeregisterDappCanisters, ExecuteGenericNervousSystemFunction, Governance, LogVisibility,
-        ManageDappCanisterSettings, ManageLedgerParameters, ManageSnsMetadata, MintSnsTokens,
-        Motion, NervousSystemFunction, NervousSystemParameters, Proposal, ProposalData,
-        ProposalDecisionStatus, ProposalRewardStatus, RegisterDappCanisters, Tally,
-        TransferSnsTreasuryFunds, UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote,
+        ApproveSnsTreasuryFunds, DeregisterDappCanisters, ExecuteActionsInSequence,
+        ExecuteGenericNervousSystemFunction, Governance, GovernanceError, LogVisibility,
+        ManageDappCanisterSettings, ManageLedgerParameters, ManageSnsMetadata,
+        ManageTreasuryTokens, MintSnsTokens, Motion, NervousSystemFunction,
+        NervousSystemParameters, Proposal, ProposalData, ProposalDecisionStatus,
+        ProposalRewardStatus, RegisterDappCanisters, Tally, TransferSnsTreasuryFunds,
+        TreasuryToken, UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote,
     },
     sns_upgrade::{get_upgrade_params, UpgradeSnsParams},
+    treasury::{
+        find_treasury_token, icrc1_fee, is_valid_exchange_rate_asset_symbol,
+        MAX_EXCHANGE_RATE_ASSET_SYMBOL_BYTES, MAX_TREASURY_TOKENS, MAX_TREASURY_TOKEN_SYMBOL_BYTES,
+    },
     types::{Environment, DEFAULT_TRANSFER_FEE},
     validate_chars_count, validate_len, validate_required_field,
 };
@@ -24,10 +31,13 @@ use ic_canister_log::log;
 use ic_crypto_sha2::Sha256;
 use ic_nervous_system_common::{i2d, E8, SECONDS_PER_DAY};
 use ic_nervous_system_proto::pb::v1::Percentage;
+use ic_nns_constants::LEDGER_CANISTER_ID as NNS_LEDGER_CANISTER_ID;
+use ic_sns_governance_token_valuation::Valuation;
+use ic_sns_governance_treasury_transfer_limit::TreasuryTransferTotalUpperBound;
 use icp_ledger::DEFAULT_TRANSFER_FEE as NNS_DEFAULT_TRANSFER_FEE;
 use icrc_ledger_types::icrc1::account::Account;
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     convert::TryFrom,
     fmt::Write,
 };
@@ -51,6 +61,9 @@ pub const MAX_NUMBER_OF_PROPOSALS_WITH_BALLOTS: usize = 700;
 /// The maximum number of GenericNervousSystemFunctions the system allows.
 pub const MAX_NUMBER_OF_GENERIC_NERVOUS_SYSTEM_FUNCTIONS: usize = 200_000;
 
+/// The maximum number of actions in an ExecuteActionsInSequence proposal.
+pub const MAX_ACTIONS_IN_SEQUENCE: usize = 10;
+
 /// The maximum number of dapps that can be managed in a single
 /// proposal (RegisterDappCanisters, DeregisterDappCanisters,
 /// or ManageDappCanisterSettings).
@@ -164,6 +177,35 @@ pub async fn validate_and_render_action(
     env: &dyn Environment,
     governance_proto: &Governance,
     reserved_canister_targets: Vec<CanisterId>,
+) -> Result<String, String> {
+    match action {
+        Some(Action::ExecuteActionsInSequence(execute_actions_in_sequence)) => {
+            validate_and_render_execute_actions_in_sequence(
+                execute_actions_in_sequence,
+                env,
+                governance_proto,
+                reserved_canister_targets,
+            )
+            .await
+        }
+        _ => {
+            validate_and_render_single_action(
+                action,
+                env,
+                governance_proto,
+                reserved_canister_targets,
+            )
+            .await
+        }
+    }
+}
+
+/// Like validate_and_render_action, but for actions other than ExecuteActionsInSequence.
+async fn validate_and_render_single_action(
+    action: &Option<proposal::Action>,
+    env: &dyn Environment,
+    governance_proto: &Governance,
+    reserved_canister_targets: Vec<CanisterId>,
 ) -> Result<String, String> {
     let current_parameters = governance_proto
         .parameters
@@ -240,7 +282,30 @@ pub async fn validate_and_render_action(
                 .as_ref()
                 .and_then(|params| params.transaction_fee_e8s)
                 .unwrap_or(DEFAULT_TRANSFER_FEE.get_e8s());
-            validate_and_render_transfer_sns_treasury_funds(transfer, sns_transfer_fee_e8s)
+            // Only the ledgers of tracked treasury tokens are asked for their fee. (Transfers from
+            // the treasuries of other tokens are invalid anyway.)
+            let treasury_token_fee_e8s = match &transfer.ledger_canister_id {
+                Some(ledger_canister_id)
+                    if transfer.from_treasury() == TransferFrom::Icrc1TokenTreasury
+                        && find_treasury_token(
+                            &governance_proto.treasury_tokens,
+                            ledger_canister_id,
+                        )
+                        .is_some() =>
+                {
+                    let fee_e8s = icrc1_fee(env, *ledger_canister_id)
+                        .await
+                        .map_err(|err| err.error_message)?;
+                    Some(fee_e8s)
+                }
+                _ => None,
+            };
+            validate_and_render_transfer_sns_treasury_funds(
+                transfer,
+                sns_transfer_fee_e8s,
+                treasury_token_fee_e8s,
+                &governance_proto.treasury_tokens,
+            )
         }
         proposal::Action::MintSnsTokens(mint) => {
             let sns_transfer_fee_e8s = governance_proto
@@ -256,9 +321,122 @@ pub async fn validate_and_render_action(
         proposal::Action::ManageDappCanisterSettings(manage_dapp_canister_settings) => {
             validate_and_render_manage_dapp_canister_settings(manage_dapp_canister_settings)
         }
+        proposal::Action::ManageTreasuryTokens(manage_treasury_tokens) => {
+            validate_and_render_manage_treasury_tokens(manage_treasury_tokens, governance_proto)
+        }
+        proposal::Action::ApproveSnsTreasuryFunds(approve) => {
+            validate_and_render_approve_sns_treasury_funds(
+                approve,
+                &governance_proto.treasury_tokens,
+            )
+        }
+        proposal::Action::ExecuteActionsInSequence(_) => {
+            Err("ExecuteActionsInSequence cannot be nested.".into())
+        }
     }
 }
 
+/// Validates and renders a proposal with action ExecuteActionsInSequence.
+///
+/// Each action is validated against the current state of governance, i.e. without taking the
+/// effects of the preceding actions in the sequence into account.
+async fn validate_and_render_execute_actions_in_sequence(
+    execute_actions_in_sequence: &ExecuteActionsInSequence,
+    env: &dyn Environment,
+    governance_proto: &Governance,
+    reserved_canister_targets: Vec<CanisterId>,
+) -> Result<String, String> {
+    let actions = &execute_actions_in_sequence.actions;
+    if actions.is_empty() {
+        return Err("ExecuteActionsInSequence must contain at least one action.".into());
+    }
+    if actions.len() > MAX_ACTIONS_IN_SEQUENCE {
+        return Err(format!(
+            "ExecuteActionsInSequence must not contain more than {} actions, but it contains {}.",
+            MAX_ACTIONS_IN_SEQUENCE,
+            actions.len()
+        ));
+    }
+
+    let mut defects = vec![];
+    let mut renderings = vec![];
+    for (index, proposal) in actions.iter().enumerate() {
+        let Proposal {
+            title,
+            summary,
+            url,
+            action,
+        } = proposal;
+        let prefix = format!("Action {} of {}: ", index + 1, actions.len());
+
+        if !title.is_empty() || !summary.is_empty() || !url.is_empty() {
+            defects.push(format!(
+                "{}title, summary and url must be empty. They are set on the enclosing proposal.",
+                prefix
+            ));
+        }
+
+        // UpgradeSnsToNextVersion completes asynchronously, after the rest of the sequence
+        // would have been performed.
+        if let Some(Action::UpgradeSnsToNextVersion(_)) = action {
+            defects.push(format!(
+                "{}UpgradeSnsToNextVersion cannot be performed in a sequence.",
+                prefix
+            ));
+            continue;
+        }
+
+        match validate_and_render_single_action(
+            action,
+            env,
+            governance_proto,
+            reserved_canister_targets.clone(),
+        )
+        .await
+        {
+            Ok(rendering) => {
+                let name = action
+                    .as_ref()
+                    .map(|action| NervousSystemFunction::from(action.clone()).name)
+                    .unwrap_or_default();
+                renderings.push(format!(
+                    "## Action {} of {}: {}\n\n{}",
+                    index + 1,
+                    actions.len(),
+                    name,
+                    rendering
+                ));
+            }
+            Err(err) => defects.push(format!("{}{}", prefix, err)),
+        }
+    }
+
+    // The sequence is voted on under the function ID of its first critical action, so mixing
+    // different critical actions would let the others avoid the following rules of their own
+    // function ID.
+    let critical_function_ids = execute_actions_in_sequence.critical_function_ids();
+    if critical_function_ids.len() > 1 {
+        defects.push(format!(
+            "ExecuteActionsInSequence must not contain critical actions of different types, \
+             but it contains critical actions with function IDs {:?}.",
+            critical_function_ids
+        ));
+    }
+
+    if !defects.is_empty() {
+        return Err(format!(
+            "ExecuteActionsInSequence is invalid:\n{}",
+            defects.join("\n")
+        ));
+    }
+
+    Ok(format!(
+        "# Proposal to execute {} actions in sequence:\n\n{}",
+        actions.len(),
+        renderings.join("\n\n")
+    ))
+}
+
 /// Validates and renders a proposal with action Motion.
 fn validate_and_render_motion(motion: &Motion) -> Result<String, String> {
     validate_len(
@@ -298,26 +476,79 @@ fn validate_and_render_manage_nervous_system_parameters(
 }
 
 /// Validates and render TransferSnsTreasuryFunds proposal
+///
+/// `treasury_token_fee_e8s` is the fee of the ledger of the treasury token to transfer from (as
+/// reported by its icrc1_fee method), if the transfer is from the treasury of a tracked ICRC-1
+/// token.
 fn validate_and_render_transfer_sns_treasury_funds(
     transfer: &TransferSnsTreasuryFunds,
     sns_transfer_fee_e8s: u64,
+    treasury_token_fee_e8s: Option<u64>,
+    treasury_tokens: &[TreasuryToken],
 ) -> Result<String, String> {
     let mut defects: Vec<String> = vec![];
+
+    // Only transfers from the treasury of an ICRC-1 token refer to a ledger; it must be
+    // one of the tracked treasury tokens.
+    let treasury_token = match (transfer.from_treasury(), &transfer.ledger_canister_id) {
+        (TransferFrom::Icrc1TokenTreasury, Some(ledger_canister_id)) => {
+            let treasury_token = find_treasury_token(treasury_tokens, ledger_canister_id);
+            if treasury_token.is_none() {
+                defects.push(format!(
+                    "Ledger {} is not one of the treasury tokens of the SNS.",
+                    ledger_canister_id
+                ));
+            }
+            treasury_token
+        }
+        (TransferFrom::Icrc1TokenTreasury, None) => {
+            defects
+                .push("Must specify the ledger_canister_id of the token to transfer.".to_string());
+            None
+        }
+        (_, Some(_)) => {
+            defects.push(
+                "ledger_canister_id must only be specified for transfers from the treasury \
+                 of an ICRC-1 token."
+                    .to_string(),
+            );
+            None
+        }
+        (_, None) => None,
+    };
+    let treasury_token_symbol = treasury_token
+        .and_then(|token| token.symbol.clone())
+        .unwrap_or_default();
+
     let (from, unit) = match transfer.from_treasury() {
-        TransferFrom::IcpTreasury => ("ICP Treasury (ICP Ledger)", "ICP"),
-        TransferFrom::SnsTokenTreasury => ("SNS Token Treasury (SNS Ledger)", "SNS Tokens"),
+        TransferFrom::IcpTreasury => ("ICP Treasury (ICP Ledger)".to_string(), "ICP"),
+        TransferFrom::SnsTokenTreasury => {
+            ("SNS Token Treasury (SNS Ledger)".to_string(), "SNS Tokens")
+        }
+        TransferFrom::Icrc1TokenTreasury => (
+            format!(
+                "{} Treasury (Ledger {})",
+                treasury_token_symbol,
+                transfer
+                    .ledger_canister_id
+                    .unwrap_or_else(PrincipalId::new_anonymous)
+            ),
+            treasury_token_symbol.as_str(),
+        ),
         TransferFrom::Unspecified => {
             defects.push(
-                "Must specify a treasury from which to transfer the funds (ICP/SNS Token)."
+                "Must specify a treasury from which to transfer the funds (ICP/SNS Token/ICRC-1 \
+                 Token)."
                     .to_string(),
             );
-            ("", "")
+            ("".to_string(), "")
         }
     };
 
     let minimum_transaction = match transfer.from_treasury() {
         TransferFrom::IcpTreasury => NNS_DEFAULT_TRANSFER_FEE.get_e8s(),
         TransferFrom::SnsTokenTreasury => sns_transfer_fee_e8s,
+        TransferFrom::Icrc1TokenTreasury => treasury_token_fee_e8s.unwrap_or_default(),
         TransferFrom::Unspecified => 0,
     };
 
@@ -446,6 +677,247 @@ fn validate_and_render_mint_sns_tokens(
     ))
 }
 
+/// Validates and renders a proposal with action ManageTreasuryTokens.
+fn validate_and_render_manage_treasury_tokens(
+    manage_treasury_tokens: &ManageTreasuryTokens,
+    governance_proto: &Governance,
+) -> Result<String, String> {
+    let ManageTreasuryTokens {
+        tokens_to_add,
+        ledger_canister_ids_to_remove,
+    } = manage_treasury_tokens;
+    let mut defects: Vec<String> = vec![];
+
+    if tokens_to_add.is_empty() && ledger_canister_ids_to_remove.is_empty() {
+        defects.push("Must specify at least one token to add or remove.".to_string());
+    }
+
+    // The ICP and SNS token treasuries are managed separately.
+    let reserved_ledger_canister_ids = [
+        Some(NNS_LEDGER_CANISTER_ID.get()),
+        governance_proto.ledger_canister_id,
+    ];
+
+    let mut token_ledger_canister_ids: BTreeSet<PrincipalId> = governance_proto
+        .treasury_tokens
+        .iter()
+        .filter_map(|token| token.ledger_canister_id)
+        .collect();
+    let mut mentioned_ledger_canister_ids = HashSet::new();
+
+    let mut tokens_to_add_rendering = String::new();
+    for token in tokens_to_add {
+        let TreasuryToken {
+            ledger_canister_id,
+            symbol,
+            exchange_rate_asset_symbol,
+        } = token;
+
+        let Some(ledger_canister_id) = ledger_canister_id else {
+            defects.push("Every token to add must specify a ledger_canister_id.".to_string());
+            continue;
+        };
+        if reserved_ledger_canister_ids.contains(&Some(*ledger_canister_id)) {
+            defects.push(format!(
+                "Ledger {} is the ICP or SNS ledger, and therefore cannot be added as a \
+                 treasury token.",
+                ledger_canister_id
+            ));
+        }
+        if !mentioned_ledger_canister_ids.insert(*ledger_canister_id) {
+            defects.push(format!(
+                "Ledger {} is mentioned more than once.",
+                ledger_canister_id
+            ));
+        }
+
+        let symbol = symbol.clone().unwrap_or_default();
+        if symbol.is_empty() || symbol.len() > MAX_TREASURY_TOKEN_SYMBOL_BYTES {
+            defects.push(format!(
+                "The symbol of the token with ledger {} must be between 1 and {} bytes long.",
+                ledger_canister_id, MAX_TREASURY_TOKEN_SYMBOL_BYTES
+            ));
+        }
+
+        // The display symbol of a token generally differs from the symbol of
+        // the asset it represents (e.g. ckBTC and BTC), so the latter must be
+        // given explicitly.
+        let exchange_rate_asset_symbol = exchange_rate_asset_symbol.clone().unwrap_or_default();
+        if !is_valid_exchange_rate_asset_symbol(&exchange_rate_asset_symbol) {
+            defects.push(format!(
+                "The exchange rate asset symbol of the token with ledger {} must consist of \
+                 between 1 and {} uppercase letters and digits, as listed by the exchange \
+                 rate canister (e.g. BTC).",
+                ledger_canister_id, MAX_EXCHANGE_RATE_ASSET_SYMBOL_BYTES
+            ));
+        }
+
+        token_ledger_canister_ids.insert(*ledger_canister_id);
+        let _ = writeln!(
+            tokens_to_add_rendering,
+            "- {} (ledger: {}, valued as: {})",
+            symbol, ledger_canister_id, exchange_rate_asset_symbol
+        );
+    }
+
+    let mut tokens_to_remove_rendering = String::new();
+    for ledger_canister_id in ledger_canister_ids_to_remove {
+        if !mentioned_ledger_canister_ids.insert(*ledger_canister_id) {
+            defects.push(format!(
+                "Ledger {} is mentioned more than once.",
+                ledger_canister_id
+            ));
+        }
+
+        let Some(token) =
+            find_treasury_token(&governance_proto.treasury_tokens, ledger_canister_id)
+        else {
+            defects.push(format!(
+                "Ledger {} is not one of the treasury tokens of the SNS.",
+                ledger_canister_id
+            ));
+            continue;
+        };
+
+        token_ledger_canister_ids.remove(ledger_canister_id);
+        let _ = writeln!(
+            tokens_to_remove_rendering,
+            "- {} (ledger: {})",
+            token.symbol.clone().unwrap_or_default(),
+            ledger_canister_id
+        );
+    }
+
+    if token_ledger_canister_ids.len() > MAX_TREASURY_TOKENS {
+        defects.push(format!(
+            "The SNS can hold at most {} treasury tokens, but it would hold {}.",
+            MAX_TREASURY_TOKENS,
+            token_ledger_canister_ids.len()
+        ));
+    }
+
+    // Generate final report.
+    if !defects.is_empty() {
+        return Err(format!(
+            "ManageTreasuryTokens proposal was invalid for the following reason(s):\n{}",
+            defects.join("\n"),
+        ));
+    }
+
+    Ok(format!(
+        r"# Proposal to change the tokens held in the SNS treasury:
+## Tokens to add:
+{tokens_to_add_rendering}
+## Tokens to remove:
+{tokens_to_remove_rendering}"
+    ))
+}
+
+/// Validates and render ApproveSnsTreasuryFunds proposal
+fn validate_and_render_approve_sns_treasury_funds(
+    approve: &ApproveSnsTreasuryFunds,
+    treasury_tokens: &[TreasuryToken],
+) -> Result<String, String> {
+    let mut defects: Vec<String> = vec![];
+
+    let (ledger_canister_id, symbol) = match &approve.ledger_canister_id {
+        Some(ledger_canister_id) => {
+            let symbol = match find_treasury_token(treasury_tokens, ledger_canister_id) {
+                Some(token) => token.symbol.clone().unwrap_or_default(),
+                None => {
+                    defects.push(format!(
+                        "Ledger {} is not one of the treasury tokens of the SNS.",
+                        ledger_canister_id
+                    ));
+                    "".to_string()
+                }
+            };
+            (*ledger_canister_id, symbol)
+        }
+        None => {
+            defects
+                .push("Must specify the ledger_canister_id of the token to approve.".to_string());
+            (PrincipalId::new_anonymous(), "".to_string())
+        }
+    };
+
+    if approve.amount_e8s.is_none() {
+        defects.push("Must specify an amount_e8s to approve.".to_string());
+    }
+
+    let spender_principal = if let Some(spender_principal) = approve.spender_principal {
+        if spender_principal == PrincipalId::new_anonymous() {
+            defects.push("spender_principal must not be anonymous.".to_string());
+        }
+        spender_principal
+    } else {
+        defects.push("Must specify a spender_principal to approve.".to_string());
+        PrincipalId::new_anonymous()
+    };
+
+    let spender_account = match &approve.spender_subaccount {
+        None => Account {
+            owner: spender_principal.0,
+            subaccount: None,
+        }
+        .to_string(),
+        Some(s) => match bytes_to_subaccount(&s.subaccount[..]) {
+            Ok(s) => Account {
+                owner: spender_principal.0,
+                subaccount: Some(s),
+            }
+            .to_string(),
+            Err(e) => {
+                defects.push(e.error_message);
+                "".to_string()
+            }
+        },
+    };
+
+    let expires_at = match approve.expires_at_timestamp_seconds {
+        None => "never".to_string(),
+        Some(expires_at_timestamp_seconds) => {
+            // The ledger expects the expiration time in nanoseconds.
+            if expires_at_timestamp_seconds
+                .checked_mul(1_000_000_000)
+                .is_none()
+            {
+                defects.push(format!(
+                    "expires_at_timestamp_seconds is too large: {}",
+                    expires_at_timestamp_seconds
+                ));
+            }
+            format!(
+                "{} (seconds since UNIX epoch)",
+                expires_at_timestamp_seconds
+            )
+        }
+    };
+
+    // Generate final report.
+    if !defects.is_empty() {
+        return Err(format!(
+            "ApproveSnsTreasuryFunds proposal was invalid for the following reason(s):\n{}",
+            defects.join("\n"),
+        ));
+    }
+
+    let amount_e8s = approve.amount_e8s.unwrap_or_default();
+    let display_amount_tokens = i2d(amount_e8s) / i2d(E8);
+
+    Ok(format!(
+        r"# Proposal to approve a spender of SNS Treasury funds:
+## Source treasury: {symbol} Treasury (Ledger {ledger_canister_id})
+## Amount: {display_amount_tokens:.8} {symbol}
+## Amount (e8s): {amount_e8s}
+## Spender principal: {spender_principal}
+## Spender account: {spender_account}
+## Expires at: {expires_at}
+## Memo: {memo}",
+        memo = approve.memo.unwrap_or(0)
+    ))
+}
+
 /// Validates and renders a proposal with action UpgradeSnsControlledCanister.
 fn validate_and_render_upgrade_sns_controlled_canister(
     upgrade: &UpgradeSnsControlledCanister,
@@ -1469,26 +1941,36 @@ impl ProposalData {
             return false;
         }
 
-        // At this point, we can let go of most proposals. The only special case is
-        // TransferSnsTreasuryFunds. We want to hang onto those for at least 7 days after they have
-        // been successfully executed. This is because they are still needed for the purposes of
-        // limiting the total amount that is transferred out of the treasury in that time window.
+        // At this point, we can let go of most proposals. The only special cases are
+        // TransferSnsTreasuryFunds and ApproveSnsTreasuryFunds. We want to hang onto those for at
+        // least 7 days after they have been successfully executed. This is because they are still
+        // needed for the purposes of limiting the total amount that is transferred out of the
+        // treasury in that time window.
 
         let Some(proposal) = &self.proposal else {
             log!(ERROR, "Proposal {:?} missing `proposal` field", self.id);
             return true;
         };
-        match &proposal.action {
-            Some(Action::TransferSnsTreasuryFunds(_)) => (),
-            _ => return true,
-        };
+        // This includes ExecuteActionsInSequence proposals that contain such actions.
+        let moves_treasury_funds = proposal.action.iter().any(|action| {
+            action.constituent_actions().into_iter().any(|action| {
+                matches!(
+                    action,
+                    Action::TransferSnsTreasuryFunds(_) | Action::ApproveSnsTreasuryFunds(_)
+                )
+            })
+        });
+        if !moves_treasury_funds {
+            return true;
+        }
 
         // No need to hang onto proposals that did not execute successfully.
         if self.executed_timestamp_seconds == 0 {
             return true;
         }
 
-        // Only hang onto TransferSnsTreasuryFunds that were executed recently enough.
+        // Only hang onto TransferSnsTreasuryFunds and ApproveSnsTreasuryFunds that were executed
+        // recently enough.
         let earliest_unpurgeable_executed_timestamp_seconds =
             now_seconds - EXECUTED_TRANSFER_SNS_TREASURY_FUNDS_PROPOSAL_RETENTION_DURATION_SECONDS;
         self.executed_timestamp_seconds < earliest_unpurgeable_executed_timestamp_seconds
@@ -1530,18 +2012,21 @@ impl ProposalRewardStatus {
 }
 
 /// Returns the total amount (in e8s) that was transfered from the treasury via
-/// TransferSnsTreasuryFunds proposals, or None if there was an overflow.
+/// TransferSnsTreasuryFunds proposals (and, in the case of ICRC-1 token treasuries, approved via
+/// ApproveSnsTreasuryFunds proposals), or None if there was an overflow.
 ///
 /// Arguments:
 /// * `proposals` - Self-explanatory.
-/// * `filter_from_treasury` - Specify the token type (ICP or SNS). The name of this parameter is
-///   based on TransferSnsTreasuryFunds.from_treasury, which specifies which token the proposal is
-///   concerned about. Furthermore, that field is compared against this parameter.
+/// * `filter_from_treasury` - Specify the token type (ICP, SNS, or ICRC-1 token). The name of this
+///   parameter is based on TransferSnsTreasuryFunds.from_treasury, which specifies which token the
+///   proposal is concerned about. Furthermore, that field is compared against this parameter.
+/// * `filter_ledger_canister_id` - When `filter_from_treasury` is Icrc1TokenTreasury, specifies
+///   the ledger of the token. Ignored otherwise.
 /// * `min_executed_timestamp_seconds` - Older proposals are not considered.
-#[allow(unused)] // TODO(NNS1-2808): Delete `allow(unused)`.
-fn total_treasury_transfer_amount_e8s(
-    proposals: &[ProposalData],
+fn total_treasury_transfer_amount_e8s<'a>(
+    proposals: impl IntoIterator<Item = &'a ProposalData>,
     filter_from_treasury: TransferFrom,
+    filter_ledger_canister_id: Option<PrincipalId>,
     min_executed_timestamp_seconds: u64,
 ) -> Option<u64> {
     let mut total_e8s: u64 = 0;
@@ -1562,36 +2047,56 @@ fn total_treasury_transfer_amount_e8s(
             );
             continue;
         };
-        let transfer_sns_treasury_funds = match &proposal.action {
-            Some(Action::TransferSnsTreasuryFunds(ok)) => ok,
-            _ => continue,
-        };
-
-        // Skip proposals that do not deal with the type of token that the caller asked for.
-        let observed_from_treasury =
-            TransferFrom::try_from(transfer_sns_treasury_funds.from_treasury);
-        if observed_from_treasury != Ok(filter_from_treasury) {
-            continue;
-        }
+        // ExecuteActionsInSequence proposals may contain several transfers and approvals.
+        let actions = proposal
+            .action
+            .iter()
+            .flat_map(|action| action.constituent_actions());
+        for action in actions {
+            let (observed_from_treasury, observed_ledger_canister_id, amount_e8s) = match action {
+                Action::TransferSnsTreasuryFunds(transfer) => (
+                    TransferFrom::try_from(transfer.from_treasury),
+                    transfer.ledger_canister_id,
+                    transfer.amount_e8s,
+                ),
+                Action::ApproveSnsTreasuryFunds(approve) => (
+                    Ok(TransferFrom::Icrc1TokenTreasury),
+                    approve.ledger_canister_id,
+                    approve.amount_e8s.unwrap_or_default(),
+                ),
+                _ => continue,
+            };
 
-        // At this point, the proposal was executed recently, and deals with the type of token that
-        // the caller is interested in. Therefore, increment result by the amount in the proposal.
-        let total_e8s_or_none = total_e8s.checked_add(transfer_sns_treasury_funds.amount_e8s);
-        total_e8s = match total_e8s_or_none {
-            Some(ok) => ok,
-
-            None => {
-                log!(
-                    ERROR,
-                    "Overflow while totaling treasury transfers since {}: \
-                     {} + {} (last proposal ID = {:?})",
-                    min_executed_timestamp_seconds,
-                    total_e8s,
-                    transfer_sns_treasury_funds.amount_e8s,
-                    proposal_id,
-                );
+            // Skip actions that do not deal with the type of token that the caller asked for.
+            if observed_from_treasury != Ok(filter_from_treasury) {
+                continue;
+            }
+            if filter_from_treasury == TransferFrom::Icrc1TokenTreasury
+                && observed_ledger_canister_id != filter_ledger_canister_id
+            {
+                continue;
+            }
 
-                return None;
+            // At this point, the proposal was executed recently, and the action deals with the
+            // type of token that the caller is interested in. Therefore, increment result by the
+            // amount in the action.
+            let total_e8s_or_none = total_e8s.checked_add(amount_e8s);
+            total_e8s = match total_e8s_or_none {
+                Some(ok) => ok,
+
+                None => {
+                    log!(
+                        ERROR,
+                        "Overflow while totaling treasury transfers since {}: \
+                         {} + {} (last proposal ID = {:?})",
+                        min_executed_timestamp_seconds,
+                        total_e8s,
+                        amount_e8s,
+                        proposal_id,
+                    );
+
+                    return None;
+                }
             }
         }
     }
@@ -1599,6 +2104,105 @@ fn total_treasury_transfer_amount_e8s(
     Some(total_e8s)
 }
 
+/// Returns Err if executing proposal `proposal_id` would make the total amount transferred (and
+/// approved) out of a treasury exceed what TreasuryTransferTotalUpperBound allows, given the
+/// current `valuation` of the treasury.
+///
+/// Counted against the upper bound are
+///
+///   1. all transfers and approvals out of the treasury in proposal `proposal_id` (there can be
+///      several of them in an ExecuteActionsInSequence proposal),
+///   2. those in proposals that were executed in the last
+///      EXECUTED_TRANSFER_SNS_TREASURY_FUNDS_PROPOSAL_RETENTION_DURATION_SECONDS, and
+///   3. those in other adopted proposals that have not finished executing yet (so that
+///      concurrently executing proposals cannot exceed the upper bound together).
+///
+/// `from_treasury` and `ledger_canister_id` specify the treasury, like the arguments of
+/// total_treasury_transfer_amount_e8s.
+pub(crate) fn treasury_transfer_total_is_small_enough_or_err(
+    proposal_id: u64,
+    from_treasury: TransferFrom,
+    ledger_canister_id: Option<PrincipalId>,
+    valuation: Valuation,
+    proposals: &BTreeMap<u64, ProposalData>,
+    now_timestamp_seconds: u64,
+) -> Result<(), GovernanceError> {
+    let total_e8s = |proposals: Vec<&ProposalData>, min_executed_timestamp_seconds: u64| {
+        total_treasury_transfer_amount_e8s(
+            proposals,
+            from_treasury,
+            ledger_canister_id,
+            min_executed_timestamp_seconds,
+        )
+        .ok_or_else(|| {
+            GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                "Unable to total the amounts transferred out of the treasury, because of an \
+                 overflow.",
+            )
+        })
+    };
+
+    let proposal = proposals.get(&proposal_id).ok_or_else(|| {
+        GovernanceError::new_with_message(
+            ErrorType::NotFound,
+            format!("Proposal {} not found.", proposal_id),
+        )
+    })?;
+    let other_proposals = || {
+        proposals
+            .iter()
+            .filter(move |(id, _)| **id != proposal_id)
+            .map(|(_, proposal)| proposal)
+    };
+
+    let proposal_e8s = total_e8s(vec![proposal], 0)?;
+    let recently_executed_e8s = total_e8s(
+        other_proposals().collect(),
+        now_timestamp_seconds.saturating_sub(
+            EXECUTED_TRANSFER_SNS_TREASURY_FUNDS_PROPOSAL_RETENTION_DURATION_SECONDS,
+        ),
+    )?;
+    let executing_e8s = total_e8s(
+        other_proposals()
+            .filter(|proposal| proposal.status() == ProposalDecisionStatus::Adopted)
+            .collect(),
+        0,
+    )?;
+
+    let allowance_tokens =
+        TreasuryTransferTotalUpperBound::in_tokens(valuation).map_err(|err| {
+            GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                format!(
+                    "Unable to determine how much can be transferred out of the treasury: {:?}",
+                    err
+                ),
+            )
+        })?;
+    let to_tokens = |e8s: u64| i2d(e8s) / i2d(E8);
+    let previous_tokens = to_tokens(recently_executed_e8s) + to_tokens(executing_e8s);
+    let proposal_tokens = to_tokens(proposal_e8s);
+
+    if previous_tokens + proposal_tokens > allowance_tokens {
+        return Err(GovernanceError::new_with_message(
+            ErrorType::PreconditionFailed,
+            format!(
+                "Executing the proposal would transfer (or approve) {} tokens out of the \
+                 treasury, but only {} tokens can be transferred out of it within {} days, of \
+                 which {} tokens were already transferred (or approved) by other proposals.",
+                proposal_tokens,
+                allowance_tokens,
+                EXECUTED_TRANSFER_SNS_TREASURY_FUNDS_PROPOSAL_RETENTION_DURATION_SECONDS
+                    / SECONDS_PER_DAY,
+                previous_tokens,
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod treasury_tests;
 
@@ -1617,7 +2221,7 @@ mod tests {
             GetWasmResponse, SnsCanisterType, SnsVersion, SnsWasm,
         },
         tests::{assert_is_err, assert_is_ok},
-        types::test_helpers::NativeEnvironment,
+        types::{native_action_ids, test_helpers::NativeEnvironment},
     };
     use candid::Encode;
     use futures::FutureExt;
@@ -1665,6 +2269,8 @@ mod tests {
             sns_initialization_parameters: "".to_string(),
             is_finalizing_disburse_maturity: None,
             maturity_modulation: None,
+            treasury_tokens: vec![],
+            cached_treasury_balances: None,
         }
     }
 
@@ -2649,9 +3255,12 @@ Version {
                     amount_e8s: 1000000,
                     memo: Some(1000),
                     to_principal: Some(basic_principal_id()),
-                    to_subaccount: None
+                    to_subaccount: None,
+                    ledger_canister_id: None
                 },
-                0
+                0,
+                None,
+                &[]
             )
             .unwrap(),
             r"# Proposal to transfer SNS Treasury funds:
@@ -2673,9 +3282,12 @@ Version {
                     to_principal: Some(basic_principal_id()),
                     to_subaccount: Some(Subaccount {
                         subaccount: vec![0; 32]
-                    })
+                    }),
+                    ledger_canister_id: None
                 },
-                0
+                0,
+                None,
+                &[]
             )
             .unwrap(),
             r"# Proposal to transfer SNS Treasury funds:
@@ -2697,9 +3309,12 @@ Version {
                     amount_e8s: E8,
                     memo: None,
                     to_principal: Some(basic_principal_id()),
-                    to_subaccount: Some(subaccount_1())
+                    to_subaccount: Some(subaccount_1()),
+                    ledger_canister_id: None
                 },
-                0
+                0,
+                None,
+                &[]
             )
             .unwrap(),
             r"# Proposal to transfer SNS Treasury funds:
@@ -2719,9 +3334,12 @@ Version {
                     amount_e8s: 1000000,
                     memo: Some(1000),
                     to_principal: Some(basic_principal_id()),
-                    to_subaccount: None
+                    to_subaccount: None,
+                    ledger_canister_id: None
                 },
-                0
+                0,
+                None,
+                &[]
             )
             .unwrap(),
             r"# Proposal to transfer SNS Treasury funds:
@@ -2746,9 +3364,12 @@ Version {
                     to_principal: None,
                     to_subaccount: Some(Subaccount {
                         subaccount: vec![0; 32]
-                    })
+                    }),
+                    ledger_canister_id: None
                 },
-                0
+                0,
+                None,
+                &[]
             )
             .unwrap_err(),
             "TransferSnsTreasuryFunds proposal was invalid for the following reason(s):\nMust specify a principal to make the transfer to.".to_string()
@@ -2765,9 +3386,12 @@ Version {
                     amount_e8s: 1000000,
                     memo: None,
                     to_principal: Some(PrincipalId::new_anonymous()),
-                    to_subaccount: None
+                    to_subaccount: None,
+                    ledger_canister_id: None
                 },
-                0
+                0,
+                None,
+                &[]
             )
             .unwrap_err(),
             "TransferSnsTreasuryFunds proposal was invalid for the following reason(s):\nto_principal must not be anonymous.".to_string()
@@ -2786,9 +3410,12 @@ Version {
                     to_principal: Some(basic_principal_id()),
                     to_subaccount: Some(Subaccount {
                         subaccount: vec![1, 2]
-                    })
+                    }),
+                    ledger_canister_id: None
                 },
-                0
+                0,
+                None,
+                &[]
             )
             .unwrap_err(),
             "TransferSnsTreasuryFunds proposal was invalid for the following reason(s):\nInvalid subaccount".to_string()
@@ -2804,9 +3431,12 @@ Version {
                     amount_e8s: 1000,
                     memo: Some(1000),
                     to_principal: Some(basic_principal_id()),
-                    to_subaccount: None
+                    to_subaccount: None,
+                    ledger_canister_id: None
                 },
-                0
+                0,
+                None,
+                &[]
             )
             .unwrap_err(),
             "TransferSnsTreasuryFunds proposal was invalid for the following reason(s):\nFor transactions from ICP Treasury (ICP Ledger), the fee and minimum transaction is 10000 e8s"
@@ -2818,15 +3448,443 @@ Version {
                     amount_e8s: 999,
                     memo: Some(1000),
                     to_principal: Some(basic_principal_id()),
-                    to_subaccount: None
+                    to_subaccount: None,
+                    ledger_canister_id: None
                 },
-                1000
+                1000,
+                None,
+                &[]
             )
             .unwrap_err(),
             "TransferSnsTreasuryFunds proposal was invalid for the following reason(s):\nFor transactions from SNS Token Treasury (SNS Ledger), the fee and minimum transaction is 1000 e8s"
         );
     }
 
+    fn ckbtc_treasury_token() -> TreasuryToken {
+        TreasuryToken {
+            ledger_canister_id: Some(canister_test_id(2000).get()),
+            symbol: Some("ckBTC".to_string()),
+            exchange_rate_asset_symbol: Some("BTC".to_string()),
+        }
+    }
+
+    #[test]
+    fn validate_and_render_transfer_sns_treasury_funds_icrc1_token() {
+        let treasury_tokens = vec![ckbtc_treasury_token()];
+        let ledger_canister_id = canister_test_id(2000).get();
+
+        let rendering = validate_and_render_transfer_sns_treasury_funds(
+            &TransferSnsTreasuryFunds {
+                from_treasury: TransferFrom::Icrc1TokenTreasury.into(),
+                amount_e8s: 1000000,
+                memo: Some(1000),
+                to_principal: Some(basic_principal_id()),
+                to_subaccount: None,
+                ledger_canister_id: Some(ledger_canister_id),
+            },
+            0,
+            Some(10),
+            &treasury_tokens,
+        )
+        .unwrap();
+        assert!(
+            rendering.contains(&format!(
+                "## Source treasury: ckBTC Treasury (Ledger {})",
+                ledger_canister_id
+            )),
+            "{}",
+            rendering
+        );
+        assert!(
+            rendering.contains("## Amount: 0.01000000 ckBTC"),
+            "{}",
+            rendering
+        );
+
+        // The amount must cover the fee of the token (as reported by its ledger).
+        assert_eq!(
+            validate_and_render_transfer_sns_treasury_funds(
+                &TransferSnsTreasuryFunds {
+                    from_treasury: TransferFrom::Icrc1TokenTreasury.into(),
+                    amount_e8s: 9,
+                    memo: None,
+                    to_principal: Some(basic_principal_id()),
+                    to_subaccount: None,
+                    ledger_canister_id: Some(ledger_canister_id),
+                },
+                0,
+                Some(10),
+                &treasury_tokens,
+            )
+            .unwrap_err(),
+            format!(
+                "TransferSnsTreasuryFunds proposal was invalid for the following reason(s):\n\
+                 For transactions from ckBTC Treasury (Ledger {}), the fee and minimum \
+                 transaction is 10 e8s",
+                ledger_canister_id
+            )
+        );
+
+        // The token must be one of the treasury tokens.
+        let err = validate_and_render_transfer_sns_treasury_funds(
+            &TransferSnsTreasuryFunds {
+                from_treasury: TransferFrom::Icrc1TokenTreasury.into(),
+                amount_e8s: 1000000,
+                memo: None,
+                to_principal: Some(basic_principal_id()),
+                to_subaccount: None,
+                ledger_canister_id: Some(ledger_canister_id),
+            },
+            0,
+            None,
+            &[],
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("is not one of the treasury tokens of the SNS"),
+            "{}",
+            err
+        );
+
+        // The ledger must be specified.
+        let err = validate_and_render_transfer_sns_treasury_funds(
+            &TransferSnsTreasuryFunds {
+                from_treasury: TransferFrom::Icrc1TokenTreasury.into(),
+                amount_e8s: 1000000,
+                memo: None,
+                to_principal: Some(basic_principal_id()),
+                to_subaccount: None,
+                ledger_canister_id: None,
+            },
+            0,
+            None,
+            &treasury_tokens,
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("Must specify the ledger_canister_id of the token to transfer."),
+            "{}",
+            err
+        );
+
+        // ... but only for ICRC-1 token treasuries.
+        let err = validate_and_render_transfer_sns_treasury_funds(
+            &TransferSnsTreasuryFunds {
+                from_treasury: TransferFrom::IcpTreasury.into(),
+                amount_e8s: 1000000,
+                memo: None,
+                to_principal: Some(basic_principal_id()),
+                to_subaccount: None,
+                ledger_canister_id: Some(ledger_canister_id),
+            },
+            0,
+            None,
+            &treasury_tokens,
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("ledger_canister_id must only be specified"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn validate_and_render_manage_treasury_tokens_renders_for_valid_inputs() {
+        let governance_proto = governance_proto_for_proposal_tests(None);
+
+        let rendering = validate_and_render_manage_treasury_tokens(
+            &ManageTreasuryTokens {
+                tokens_to_add: vec![ckbtc_treasury_token()],
+                ledger_canister_ids_to_remove: vec![],
+            },
+            &governance_proto,
+        )
+        .unwrap();
+        assert!(
+            rendering.contains(&format!(
+                "- ckBTC (ledger: {}, valued as: BTC)",
+                canister_test_id(2000).get()
+            )),
+            "{}",
+            rendering
+        );
+
+        let governance_proto = GovernanceProto {
+            treasury_tokens: vec![ckbtc_treasury_token()],
+            ..governance_proto
+        };
+        let rendering = validate_and_render_manage_treasury_tokens(
+            &ManageTreasuryTokens {
+                tokens_to_add: vec![],
+                ledger_canister_ids_to_remove: vec![canister_test_id(2000).get()],
+            },
+            &governance_proto,
+        )
+        .unwrap();
+        assert!(
+            rendering.contains(&format!(
+                "- ckBTC (ledger: {})",
+                canister_test_id(2000).get()
+            )),
+            "{}",
+            rendering
+        );
+    }
+
+    #[test]
+    fn validate_and_render_manage_treasury_tokens_invalid_inputs() {
+        let governance_proto = governance_proto_for_proposal_tests(None);
+
+        let assert_invalid = |manage_treasury_tokens: ManageTreasuryTokens, expected: &str| {
+            let err = validate_and_render_manage_treasury_tokens(
+                &manage_treasury_tokens,
+                &governance_proto,
+            )
+            .unwrap_err();
+            assert!(err.contains(expected), "{}", err);
+        };
+
+        assert_invalid(
+            ManageTreasuryTokens::default(),
+            "Must specify at least one token to add or remove.",
+        );
+        assert_invalid(
+            ManageTreasuryTokens {
+                tokens_to_add: vec![TreasuryToken {
+                    ledger_canister_id: Some(NNS_LEDGER_CANISTER_ID.get()),
+                    ..ckbtc_treasury_token()
+                }],
+                ledger_canister_ids_to_remove: vec![],
+            },
+            "is the ICP or SNS ledger",
+        );
+        assert_invalid(
+            ManageTreasuryTokens {
+                tokens_to_add: vec![TreasuryToken {
+                    ledger_canister_id: Some(SNS_LEDGER_CANISTER_ID.get()),
+                    ..ckbtc_treasury_token()
+                }],
+                ledger_canister_ids_to_remove: vec![],
+            },
+            "is the ICP or SNS ledger",
+        );
+        assert_invalid(
+            ManageTreasuryTokens {
+                tokens_to_add: vec![ckbtc_treasury_token(), ckbtc_treasury_token()],
+                ledger_canister_ids_to_remove: vec![],
+            },
+            "is mentioned more than once.",
+        );
+        assert_invalid(
+            ManageTreasuryTokens {
+                tokens_to_add: vec![TreasuryToken {
+                    symbol: None,
+                    ..ckbtc_treasury_token()
+                }],
+                ledger_canister_ids_to_remove: vec![],
+            },
+            "must be between 1 and 32 bytes long.",
+        );
+        for exchange_rate_asset_symbol in [None, Some(""), Some("ckBTC"), Some("BTC ")] {
+            assert_invalid(
+                ManageTreasuryTokens {
+                    tokens_to_add: vec![TreasuryToken {
+                        exchange_rate_asset_symbol: exchange_rate_asset_symbol
+                            .map(|symbol| symbol.to_string()),
+                        ..ckbtc_treasury_token()
+                    }],
+                    ledger_canister_ids_to_remove: vec![],
+                },
+                "must consist of between 1 and 20 uppercase letters and digits",
+            );
+        }
+        assert_invalid(
+            ManageTreasuryTokens {
+                tokens_to_add: vec![],
+                ledger_canister_ids_to_remove: vec![canister_test_id(2000).get()],
+            },
+            "is not one of the treasury tokens of the SNS.",
+        );
+        assert_invalid(
+            ManageTreasuryTokens {
+                tokens_to_add: (0..=MAX_TREASURY_TOKENS as u64)
+                    .map(|i| TreasuryToken {
+                        ledger_canister_id: Some(canister_test_id(3000 + i).get()),
+                        ..ckbtc_treasury_token()
+                    })
+                    .collect(),
+                ledger_canister_ids_to_remove: vec![],
+            },
+            "The SNS can hold at most 50 treasury tokens",
+        );
+    }
+
+    #[test]
+    fn validate_and_render_approve_sns_treasury_funds_renders_for_valid_inputs() {
+        let ledger_canister_id = canister_test_id(2000).get();
+        let rendering = validate_and_render_approve_sns_treasury_funds(
+            &ApproveSnsTreasuryFunds {
+                ledger_canister_id: Some(ledger_canister_id),
+                amount_e8s: Some(E8),
+                memo: Some(7),
+                spender_principal: Some(basic_principal_id()),
+                spender_subaccount: Some(subaccount_1()),
+                expires_at_timestamp_seconds: Some(1_700_000_000),
+            },
+            &[ckbtc_treasury_token()],
+        )
+        .unwrap();
+        assert_eq!(
+            rendering,
+            format!(
+                r"# Proposal to approve a spender of SNS Treasury funds:
+## Source treasury: ckBTC Treasury (Ledger {ledger_canister_id})
+## Amount: 1.00000000 ckBTC
+## Amount (e8s): 100000000
+## Spender principal: bg4sm-wzk
+## Spender account: bg4sm-wzk-msokwai.1
+## Expires at: 1700000000 (seconds since UNIX epoch)
+## Memo: 7"
+            )
+        );
+    }
+
+    #[test]
+    fn validate_and_render_approve_sns_treasury_funds_invalid_inputs() {
+        let err = validate_and_render_approve_sns_treasury_funds(
+            &ApproveSnsTreasuryFunds {
+                ledger_canister_id: Some(canister_test_id(2000).get()),
+                amount_e8s: None,
+                memo: None,
+                spender_principal: Some(PrincipalId::new_anonymous()),
+                spender_subaccount: None,
+                expires_at_timestamp_seconds: Some(u64::MAX),
+            },
+            &[],
+        )
+        .unwrap_err();
+        for expected in [
+            "is not one of the treasury tokens of the SNS.",
+            "Must specify an amount_e8s to approve.",
+            "spender_principal must not be anonymous.",
+            "expires_at_timestamp_seconds is too large",
+        ] {
+            assert!(err.contains(expected), "{}", err);
+        }
+    }
+
+    fn motion_action(motion_text: &str) -> Proposal {
+        Proposal {
+            action: Some(Action::Motion(Motion {
+                motion_text: motion_text.to_string(),
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validate_and_render_execute_actions_in_sequence_renders_for_valid_inputs() {
+        let rendering = validate_default_action(&Some(Action::ExecuteActionsInSequence(
+            ExecuteActionsInSequence {
+                actions: vec![motion_action("first"), motion_action("second")],
+            },
+        )))
+        .unwrap();
+        assert_eq!(
+            rendering,
+            r"# Proposal to execute 2 actions in sequence:
+
+## Action 1 of 2: Motion
+
+# Motion Proposal:
+## Motion Text:
+
+first
+
+## Action 2 of 2: Motion
+
+# Motion Proposal:
+## Motion Text:
+
+second"
+        );
+    }
+
+    #[test]
+    fn validate_and_render_execute_actions_in_sequence_invalid_inputs() {
+        let validate = |actions: Vec<Proposal>| {
+            validate_default_action(&Some(Action::ExecuteActionsInSequence(
+                ExecuteActionsInSequence { actions },
+            )))
+            .unwrap_err()
+        };
+
+        let err = validate(vec![]);
+        assert!(err.contains("must contain at least one action"), "{}", err);
+
+        let err = validate(vec![motion_action("motion"); MAX_ACTIONS_IN_SEQUENCE + 1]);
+        assert!(err.contains("must not contain more than"), "{}", err);
+
+        let err = validate(vec![
+            Proposal {
+                title: "title".to_string(),
+                ..motion_action("motion")
+            },
+            Proposal {
+                action: Some(Action::ExecuteActionsInSequence(ExecuteActionsInSequence {
+                    actions: vec![motion_action("motion")],
+                })),
+                ..Default::default()
+            },
+            Proposal {
+                action: Some(Action::UpgradeSnsToNextVersion(UpgradeSnsToNextVersion {})),
+                ..Default::default()
+            },
+            Proposal::default(),
+            motion_action("motion"),
+        ]);
+        for expected in [
+            "Action 1 of 5: title, summary and url must be empty.",
+            "Action 2 of 5: ExecuteActionsInSequence cannot be nested.",
+            "Action 3 of 5: UpgradeSnsToNextVersion cannot be performed in a sequence.",
+            "Action 4 of 5: No action was specified.",
+        ] {
+            assert!(err.contains(expected), "{}", err);
+        }
+        assert!(!err.contains("Action 5 of 5"), "{}", err);
+    }
+
+    #[test]
+    fn validate_and_render_execute_actions_in_sequence_rejects_mixed_critical_actions() {
+        let err = validate_default_action(&Some(Action::ExecuteActionsInSequence(
+            ExecuteActionsInSequence {
+                actions: vec![
+                    Proposal {
+                        action: Some(Action::TransferSnsTreasuryFunds(Default::default())),
+                        ..Default::default()
+                    },
+                    motion_action("motion"),
+                    Proposal {
+                        action: Some(Action::MintSnsTokens(Default::default())),
+                        ..Default::default()
+                    },
+                ],
+            },
+        )))
+        .unwrap_err();
+        assert!(
+            err.contains(&format!(
+                "must not contain critical actions of different types, but it contains critical \
+                 actions with function IDs {{{}, {}}}.",
+                native_action_ids::TRANSFER_SNS_TREASURY_FUNDS,
+                native_action_ids::MINT_SNS_TOKENS,
+            )),
+            "{}",
+            err
+        );
+    }
+
     #[test]
     fn validate_and_render_mint_sns_tokens_renders_for_valid_inputs() {
         // Valid case
@@ -1,4 +1,8 @@
 use super::*;
+use ic_sns_governance_token_valuation::{Token, ValuationFactors};
+use maplit::btreemap;
+use rust_decimal::Decimal;
+use std::time::{Duration, SystemTime};
 
 #[test]
 fn test_can_be_purged_retain_recent_transfer_sns_treasury_funds() {
@@ -106,6 +110,7 @@ fn test_total_treasury_transfer_amount_e8s() {
         memo: None,
         to_principal: Some(PrincipalId::new_user_test_id(42)),
         to_subaccount: None,
+        ledger_canister_id: None,
     };
 
     let new_action = |transfer_sns_treasury_funds: TransferSnsTreasuryFunds| -> Option<Action> {
@@ -206,6 +211,7 @@ fn test_total_treasury_transfer_amount_e8s() {
         total_treasury_transfer_amount_e8s(
             &proposals,
             TransferFrom::IcpTreasury,
+            None,
             min_executed_timestamp_seconds,
         ),
         Some(4321),
@@ -218,6 +224,7 @@ fn test_total_treasury_transfer_amount_e8s() {
         total_treasury_transfer_amount_e8s(
             &proposals,
             TransferFrom::IcpTreasury,
+            None,
             // This is somewhat pathological, but the behavior is still well-defined. Therefore, the
             // code under test should be able to handle this even though we do not expect to see
             // this in practice.
@@ -252,6 +259,7 @@ fn test_total_treasury_transfer_amount_e8s() {
         total_treasury_transfer_amount_e8s(
             &proposals,
             TransferFrom::IcpTreasury,
+            None,
             min_executed_timestamp_seconds,
         ),
         Some(u64::MAX),
@@ -281,8 +289,268 @@ fn test_total_treasury_transfer_amount_e8s() {
         total_treasury_transfer_amount_e8s(
             &proposals,
             TransferFrom::IcpTreasury,
+            None,
             min_executed_timestamp_seconds,
         ),
         None, // Quiet explosion.
     );
 }
+
+#[test]
+fn test_total_treasury_transfer_amount_e8s_icrc1_token() {
+    let min_executed_timestamp_seconds = 123_456_789;
+    let ledger_canister_id = PrincipalId::new_user_test_id(1000);
+    let other_ledger_canister_id = PrincipalId::new_user_test_id(1001);
+
+    let new_proposal_data = |action: Action| ProposalData {
+        proposal: Some(Proposal {
+            action: Some(action),
+            ..Default::default()
+        }),
+        executed_timestamp_seconds: min_executed_timestamp_seconds,
+        ..Default::default()
+    };
+    let transfer = |ledger_canister_id: PrincipalId, amount_e8s: u64| {
+        Action::TransferSnsTreasuryFunds(TransferSnsTreasuryFunds {
+            from_treasury: TransferFrom::Icrc1TokenTreasury as i32,
+            ledger_canister_id: Some(ledger_canister_id),
+            amount_e8s,
+            to_principal: Some(PrincipalId::new_user_test_id(42)),
+            ..Default::default()
+        })
+    };
+    let approve = |ledger_canister_id: PrincipalId, amount_e8s: u64| {
+        Action::ApproveSnsTreasuryFunds(ApproveSnsTreasuryFunds {
+            ledger_canister_id: Some(ledger_canister_id),
+            amount_e8s: Some(amount_e8s),
+            spender_principal: Some(PrincipalId::new_user_test_id(43)),
+            ..Default::default()
+        })
+    };
+
+    let proposals = vec![
+        // Ok.
+        new_proposal_data(transfer(ledger_canister_id, 1)),
+        // Ok, because approvals count the same as transfers.
+        new_proposal_data(approve(ledger_canister_id, 20)),
+        // Skip because a different token.
+        new_proposal_data(transfer(other_ledger_canister_id, 300)),
+        new_proposal_data(approve(other_ledger_canister_id, 4000)),
+        // Skip because ICP.
+        new_proposal_data(Action::TransferSnsTreasuryFunds(TransferSnsTreasuryFunds {
+            from_treasury: TransferFrom::IcpTreasury as i32,
+            amount_e8s: 50_000,
+            to_principal: Some(PrincipalId::new_user_test_id(42)),
+            ..Default::default()
+        })),
+    ];
+
+    assert_eq!(
+        total_treasury_transfer_amount_e8s(
+            &proposals,
+            TransferFrom::Icrc1TokenTreasury,
+            Some(ledger_canister_id),
+            min_executed_timestamp_seconds,
+        ),
+        Some(21),
+    );
+    assert_eq!(
+        total_treasury_transfer_amount_e8s(
+            &proposals,
+            TransferFrom::Icrc1TokenTreasury,
+            Some(other_ledger_canister_id),
+            min_executed_timestamp_seconds,
+        ),
+        Some(4300),
+    );
+    // Approvals only exist for ICRC-1 tokens.
+    assert_eq!(
+        total_treasury_transfer_amount_e8s(
+            &proposals,
+            TransferFrom::IcpTreasury,
+            None,
+            min_executed_timestamp_seconds,
+        ),
+        Some(50_000),
+    );
+}
+
+#[test]
+fn test_treasury_transfers_in_execute_actions_in_sequence() {
+    let now_timestamp_seconds = 123_456_789;
+
+    let member = |action: Action| Proposal {
+        action: Some(action),
+        ..Default::default()
+    };
+    let transfer = |amount_e8s: u64| {
+        Action::TransferSnsTreasuryFunds(TransferSnsTreasuryFunds {
+            from_treasury: TransferFrom::IcpTreasury as i32,
+            amount_e8s,
+            to_principal: Some(PrincipalId::new_user_test_id(42)),
+            ..Default::default()
+        })
+    };
+    let proposal_data = ProposalData {
+        decided_timestamp_seconds: now_timestamp_seconds - 15,
+        executed_timestamp_seconds: now_timestamp_seconds - 10,
+        reward_event_end_timestamp_seconds: Some(now_timestamp_seconds - 5),
+        proposal: Some(Proposal {
+            action: Some(Action::ExecuteActionsInSequence(ExecuteActionsInSequence {
+                actions: vec![
+                    member(transfer(1)),
+                    member(Action::Motion(Default::default())),
+                    member(transfer(20)),
+                ],
+            })),
+            ..Default::default()
+        }),
+        is_eligible_for_rewards: true,
+        ..Default::default()
+    };
+
+    // All transfers in the sequence count towards the total.
+    assert_eq!(
+        total_treasury_transfer_amount_e8s(
+            &[proposal_data.clone()],
+            TransferFrom::IcpTreasury,
+            None,
+            now_timestamp_seconds - 10,
+        ),
+        Some(21),
+    );
+
+    // Like TransferSnsTreasuryFunds proposals, the proposal is retained for 7 days.
+    assert!(!proposal_data.can_be_purged(now_timestamp_seconds));
+    assert!(proposal_data.can_be_purged(now_timestamp_seconds + 7 * SECONDS_PER_DAY));
+}
+
+#[test]
+fn test_icrc1_treasury_transfer_over_limit_is_rejected() {
+    // Step 1: Prepare the world.
+    let now_timestamp_seconds = 123_456_789;
+    let ledger_canister_id = PrincipalId::new_user_test_id(1000);
+    let other_ledger_canister_id = PrincipalId::new_user_test_id(1001);
+
+    // The treasury holds 1_000_000 tokens worth 1 XDR each. That is a "medium" treasury, of which
+    // 25% (i.e. 250_000 tokens) can be transferred out within 7 days.
+    let valuation = Valuation {
+        token: Token::Icrc1Token,
+        account: Account {
+            owner: PrincipalId::new_user_test_id(1).0,
+            subaccount: None,
+        },
+        timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(now_timestamp_seconds),
+        valuation_factors: ValuationFactors {
+            tokens: Decimal::from(1_000_000),
+            icps_per_token: Decimal::from(1),
+            xdrs_per_icp: Decimal::from(1),
+        },
+    };
+
+    let transfer = |ledger_canister_id: PrincipalId, tokens: u64| {
+        Action::TransferSnsTreasuryFunds(TransferSnsTreasuryFunds {
+            from_treasury: TransferFrom::Icrc1TokenTreasury as i32,
+            ledger_canister_id: Some(ledger_canister_id),
+            amount_e8s: tokens * E8,
+            to_principal: Some(PrincipalId::new_user_test_id(42)),
+            ..Default::default()
+        })
+    };
+    let approve = |tokens: u64| {
+        Action::ApproveSnsTreasuryFunds(ApproveSnsTreasuryFunds {
+            ledger_canister_id: Some(ledger_canister_id),
+            amount_e8s: Some(tokens * E8),
+            spender_principal: Some(PrincipalId::new_user_test_id(43)),
+            ..Default::default()
+        })
+    };
+    let adopted_proposal = |action: Action| ProposalData {
+        proposal: Some(Proposal {
+            action: Some(action),
+            ..Default::default()
+        }),
+        decided_timestamp_seconds: now_timestamp_seconds - 10,
+        latest_tally: Some(Tally {
+            timestamp_seconds: now_timestamp_seconds - 10,
+            yes: 100,
+            no: 0,
+            total: 100,
+        }),
+        ..Default::default()
+    };
+    let executed_proposal = |action: Action, executed_timestamp_seconds: u64| ProposalData {
+        executed_timestamp_seconds,
+        ..adopted_proposal(action)
+    };
+    let sequence = |actions: Vec<Action>| {
+        Action::ExecuteActionsInSequence(ExecuteActionsInSequence {
+            actions: actions
+                .into_iter()
+                .map(|action| Proposal {
+                    action: Some(action),
+                    ..Default::default()
+                })
+                .collect(),
+        })
+    };
+
+    let proposals = |proposal_to_execute: ProposalData| {
+        btreemap! {
+            // Counts, because executed recently.
+            1 => executed_proposal(transfer(ledger_canister_id, 100_000), now_timestamp_seconds - 10),
+            // Does not count, because executed more than 7 days ago.
+            2 => executed_proposal(
+                transfer(ledger_canister_id, 1_000_000),
+                now_timestamp_seconds - 8 * SECONDS_PER_DAY,
+            ),
+            // Does not count, because a different token.
+            3 => executed_proposal(
+                transfer(other_ledger_canister_id, 1_000_000),
+                now_timestamp_seconds - 10,
+            ),
+            // Counts, because it might be executing right now.
+            4 => adopted_proposal(approve(50_000)),
+            10 => proposal_to_execute,
+        }
+    };
+    let check = |action: Action| {
+        treasury_transfer_total_is_small_enough_or_err(
+            10,
+            TransferFrom::Icrc1TokenTreasury,
+            Some(ledger_canister_id),
+            valuation,
+            &proposals(adopted_proposal(action)),
+            now_timestamp_seconds,
+        )
+    };
+
+    // Step 2 & 3: Run code under test, and inspect results.
+
+    // Exactly what remains of the 250_000 tokens can be transferred (or approved).
+    assert_eq!(check(transfer(ledger_canister_id, 100_000)), Ok(()));
+    assert_eq!(check(approve(100_000)), Ok(()));
+
+    // More than that cannot.
+    let err = check(transfer(ledger_canister_id, 100_001)).unwrap_err();
+    assert_eq!(err.error_type(), ErrorType::PreconditionFailed, "{:?}", err);
+    for expected in ["would transfer (or approve)", "within 7 days"] {
+        assert!(err.error_message.contains(expected), "{:?}", err);
+    }
+    assert!(check(approve(100_001)).is_err());
+
+    // All transfers and approvals in a sequence count together.
+    assert_eq!(
+        check(sequence(vec![
+            transfer(ledger_canister_id, 60_000),
+            transfer(other_ledger_canister_id, 1_000_000),
+            approve(40_000),
+        ])),
+        Ok(())
+    );
+    assert!(check(sequence(vec![
+        transfer(ledger_canister_id, 60_000),
+        approve(40_001),
+    ]))
+    .is_err());
+}
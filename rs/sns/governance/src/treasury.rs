@@ -0,0 +1,177 @@
+//! Access to the treasuries of ICRC-1 tokens (other than ICP and the SNS token) that are
+//! tracked in `Governance.treasury_tokens`.
+//!
+//! Unlike the ICP and SNS ledgers, which governance talks to through `ICRC1Ledger` objects
+//! that are fixed at construction time, the ledgers of these tokens are only known at run
+//! time. Therefore, they are called via `Environment::call_canister`.
+
+use crate::{
+    pb::v1::{governance_error::ErrorType, GovernanceError, TreasuryToken},
+    types::Environment,
+};
+use candid::{CandidType, Decode, Encode, Nat};
+use dfn_core::api::CanisterId;
+use ic_base_types::PrincipalId;
+use icrc_ledger_types::{
+    icrc1::{
+        account::Account,
+        transfer::{TransferArg, TransferError},
+    },
+    icrc2::approve::{ApproveArgs, ApproveError},
+};
+use num_traits::ToPrimitive;
+use serde::Deserialize;
+
+/// The maximum number of tokens that can be tracked in `Governance.treasury_tokens`.
+pub const MAX_TREASURY_TOKENS: usize = 50;
+
+/// The maximum length (in bytes) of `TreasuryToken.symbol`.
+pub const MAX_TREASURY_TOKEN_SYMBOL_BYTES: usize = 32;
+
+/// The maximum length (in bytes) of `TreasuryToken.exchange_rate_asset_symbol`.
+pub const MAX_EXCHANGE_RATE_ASSET_SYMBOL_BYTES: usize = 20;
+
+/// Returns true if `symbol` has the form of the symbols of the assets listed by
+/// the exchange rate canister (e.g. "BTC"): it is non-empty, not longer than
+/// `MAX_EXCHANGE_RATE_ASSET_SYMBOL_BYTES`, and consists of uppercase ASCII
+/// letters and digits.
+pub fn is_valid_exchange_rate_asset_symbol(symbol: &str) -> bool {
+    !symbol.is_empty()
+        && symbol.len() <= MAX_EXCHANGE_RATE_ASSET_SYMBOL_BYTES
+        && symbol
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+}
+
+/// Returns the token in `treasury_tokens` whose ledger is `ledger_canister_id`, if any.
+pub fn find_treasury_token<'a>(
+    treasury_tokens: &'a [TreasuryToken],
+    ledger_canister_id: &PrincipalId,
+) -> Option<&'a TreasuryToken> {
+    treasury_tokens
+        .iter()
+        .find(|token| token.ledger_canister_id.as_ref() == Some(ledger_canister_id))
+}
+
+/// Returns the balance (in e8s) of `account` on the given ledger.
+pub(crate) async fn icrc1_balance_of(
+    env: &dyn Environment,
+    ledger_canister_id: PrincipalId,
+    account: Account,
+) -> Result<u64, GovernanceError> {
+    let balance: Nat = call_ledger(env, ledger_canister_id, "icrc1_balance_of", account).await?;
+    nat_to_u64(ledger_canister_id, "icrc1_balance_of", balance)
+}
+
+/// Returns the fee (in e8s) that the given ledger charges for transfers and approvals.
+pub(crate) async fn icrc1_fee(
+    env: &dyn Environment,
+    ledger_canister_id: PrincipalId,
+) -> Result<u64, GovernanceError> {
+    let fee: Nat = call_ledger(env, ledger_canister_id, "icrc1_fee", ()).await?;
+    nat_to_u64(ledger_canister_id, "icrc1_fee", fee)
+}
+
+/// Makes a transfer on the given ledger, and returns the index of the resulting block.
+pub(crate) async fn icrc1_transfer(
+    env: &dyn Environment,
+    ledger_canister_id: PrincipalId,
+    arg: TransferArg,
+) -> Result<u64, GovernanceError> {
+    let result: Result<Nat, TransferError> =
+        call_ledger(env, ledger_canister_id, "icrc1_transfer", arg).await?;
+    let block_index = result.map_err(|err| {
+        GovernanceError::new_with_message(
+            ErrorType::External,
+            format!(
+                "'icrc1_transfer' of ledger {} failed. Error: {:?}",
+                ledger_canister_id, err
+            ),
+        )
+    })?;
+    nat_to_u64(ledger_canister_id, "icrc1_transfer", block_index)
+}
+
+/// Makes an approval on the given ledger, and returns the index of the resulting block.
+pub(crate) async fn icrc2_approve(
+    env: &dyn Environment,
+    ledger_canister_id: PrincipalId,
+    arg: ApproveArgs,
+) -> Result<u64, GovernanceError> {
+    let result: Result<Nat, ApproveError> =
+        call_ledger(env, ledger_canister_id, "icrc2_approve", arg).await?;
+    let block_index = result.map_err(|err| {
+        GovernanceError::new_with_message(
+            ErrorType::External,
+            format!(
+                "'icrc2_approve' of ledger {} failed. Error: {:?}",
+                ledger_canister_id, err
+            ),
+        )
+    })?;
+    nat_to_u64(ledger_canister_id, "icrc2_approve", block_index)
+}
+
+async fn call_ledger<Arg, Response>(
+    env: &dyn Environment,
+    ledger_canister_id: PrincipalId,
+    method_name: &str,
+    arg: Arg,
+) -> Result<Response, GovernanceError>
+where
+    Arg: CandidType,
+    Response: CandidType + for<'de> Deserialize<'de>,
+{
+    let arg = Encode!(&arg).map_err(|err| {
+        GovernanceError::new_with_message(
+            ErrorType::External,
+            format!(
+                "Unable to encode the argument of '{}': {}",
+                method_name, err
+            ),
+        )
+    })?;
+
+    let reply = env
+        .call_canister(
+            CanisterId::unchecked_from_principal(ledger_canister_id),
+            method_name,
+            arg,
+        )
+        .await
+        .map_err(|(code, msg)| {
+            GovernanceError::new_with_message(
+                ErrorType::External,
+                format!(
+                    "Error calling method '{}' of ledger {}. Code: {:?}. Message: {}",
+                    method_name, ledger_canister_id, code, msg
+                ),
+            )
+        })?;
+
+    Decode!(&reply, Response).map_err(|err| {
+        GovernanceError::new_with_message(
+            ErrorType::External,
+            format!(
+                "Unable to decode the reply of '{}' from ledger {}: {}",
+                method_name, ledger_canister_id, err
+            ),
+        )
+    })
+}
+
+fn nat_to_u64(
+    ledger_canister_id: PrincipalId,
+    method_name: &str,
+    n: Nat,
+) -> Result<u64, GovernanceError> {
+    n.0.to_u64().ok_or_else(|| {
+        GovernanceError::new_with_message(
+            ErrorType::External,
+            format!(
+                "The reply of '{}' from ledger {} does not fit into a u64: {}",
+                method_name, ledger_canister_id, n
+            ),
+        )
+    })
+}
@@ -21,9 +21,10 @@ use crate::{
             nervous_system_function::FunctionType,
             neuron::Followees,
             proposal::Action,
-            ClaimSwapNeuronsError, ClaimSwapNeuronsResponse, ClaimedSwapNeuronStatus,
-            DefaultFollowees, DeregisterDappCanisters, Empty, ExecuteGenericNervousSystemFunction,
-            GovernanceError, ManageDappCanisterSettings, ManageNeuronResponse, MintSnsTokens,
+            ApproveSnsTreasuryFunds, ClaimSwapNeuronsError, ClaimSwapNeuronsResponse,
+            ClaimedSwapNeuronStatus, DefaultFollowees, DeregisterDappCanisters, Empty,
+            ExecuteActionsInSequence, ExecuteGenericNervousSystemFunction, GovernanceError,
+            ManageDappCanisterSettings, ManageNeuronResponse, ManageTreasuryTokens, MintSnsTokens,
             Motion, NervousSystemFunction, NervousSystemParameters, Neuron, NeuronId,
             NeuronPermission, NeuronPermissionList, NeuronPermissionType, ProposalId,
             RegisterDappCanisters, RewardEvent, TransferSnsTreasuryFunds,
@@ -113,6 +114,15 @@ pub mod native_action_ids {
 
     /// ManageDappCanisterSettings Action.
     pub const MANAGE_DAPP_CANISTER_SETTINGS: u64 = 14;
+
+    /// ManageTreasuryTokens Action.
+    pub const MANAGE_TREASURY_TOKENS: u64 = 15;
+
+    /// ApproveSnsTreasuryFunds Action.
+    pub const APPROVE_SNS_TREASURY_FUNDS: u64 = 16;
+
+    /// ExecuteActionsInSequence Action.
+    pub const EXECUTE_ACTIONS_IN_SEQUENCE: u64 = 17;
 }
 
 impl governance::Mode {
@@ -238,6 +248,30 @@ impl governance::Mode {
                 )
             )),
 
+            Action::ApproveSnsTreasuryFunds(_) => Err(GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                format!(
+                    "ApproveSnsTreasuryFunds proposals are not allowed while \
+                        governance is in PreInitializationSwap mode: {:#?}",
+                    action
+                )
+            )),
+
+            // A sequence is only allowed if each of its actions is.
+            Action::ExecuteActionsInSequence(execute_actions_in_sequence) => {
+                execute_actions_in_sequence
+                    .actions
+                    .iter()
+                    .filter_map(|proposal| proposal.action.as_ref())
+                    .try_for_each(|action| {
+                        Self::proposal_action_is_allowed_in_pre_initialization_swap_or_err(
+                            action,
+                            disallowed_target_canister_ids,
+                            id_to_nervous_system_function,
+                        )
+                    })
+            }
+
             _ => Ok(()),
         }
     }
@@ -1108,6 +1142,34 @@ impl From<Action> for NervousSystemFunction {
                 ),
                 function_type: Some(FunctionType::NativeNervousSystemFunction(Empty {})),
             },
+            Action::ManageTreasuryTokens(_) => NervousSystemFunction {
+                id: native_action_ids::MANAGE_TREASURY_TOKENS,
+                name: "Manage treasury tokens".to_string(),
+                description: Some(
+                    "Proposal to change which ICRC-1 tokens are held in the SNS treasury."
+                        .to_string(),
+                ),
+                function_type: Some(FunctionType::NativeNervousSystemFunction(Empty {})),
+            },
+            Action::ApproveSnsTreasuryFunds(_) => NervousSystemFunction {
+                id: native_action_ids::APPROVE_SNS_TREASURY_FUNDS,
+                name: "Approve SNS treasury funds".to_string(),
+                description: Some(
+                    "Proposal to approve a spender to transfer funds from an SNS Governance \
+                     controlled treasury account"
+                        .to_string(),
+                ),
+                function_type: Some(FunctionType::NativeNervousSystemFunction(Empty {})),
+            },
+            Action::ExecuteActionsInSequence(_) => NervousSystemFunction {
+                id: native_action_ids::EXECUTE_ACTIONS_IN_SEQUENCE,
+                name: "Execute actions in sequence".to_string(),
+                description: Some(
+                    "Proposal to execute several actions, one after the other, as a single unit."
+                        .to_string(),
+                ),
+                function_type: Some(FunctionType::NativeNervousSystemFunction(Empty {})),
+            },
         }
     }
 }
@@ -1481,6 +1543,15 @@ impl Action {
             Action::ExecuteGenericNervousSystemFunction(action) => {
                 Action::ExecuteGenericNervousSystemFunction(action.strip_large_fields())
             }
+            Action::ExecuteActionsInSequence(action) => {
+                Action::ExecuteActionsInSequence(ExecuteActionsInSequence {
+                    actions: action
+                        .actions
+                        .iter()
+                        .map(|proposal| proposal.strip_large_fields())
+                        .collect(),
+                })
+            }
             action => action.clone(),
         }
     }
@@ -1532,9 +1603,16 @@ impl Action {
     fn proposal_criticality(&self) -> ProposalCriticality {
         use Action::*;
         match self {
-            DeregisterDappCanisters(_) | TransferSnsTreasuryFunds(_) | MintSnsTokens(_) => {
-                ProposalCriticality::Critical
-            }
+            // A sequence is critical if any action in it is.
+            ExecuteActionsInSequence(_) => match self.first_critical_sequenced_action() {
+                Some(action) => action.proposal_criticality(),
+                None => ProposalCriticality::Normal,
+            },
+
+            DeregisterDappCanisters(_)
+            | TransferSnsTreasuryFunds(_)
+            | MintSnsTokens(_)
+            | ApproveSnsTreasuryFunds(_) => ProposalCriticality::Critical,
 
             Unspecified(_)
             | ManageNervousSystemParameters(_)
@@ -1547,9 +1625,69 @@ impl Action {
             | ManageSnsMetadata(_)
             | ManageLedgerParameters(_)
             | RegisterDappCanisters(_)
-            | ManageDappCanisterSettings(_) => ProposalCriticality::Normal,
+            | ManageDappCanisterSettings(_)
+            | ManageTreasuryTokens(_) => ProposalCriticality::Normal,
         }
     }
+
+    /// When self is an ExecuteActionsInSequence, returns the first of its actions that is
+    /// critical, if any. Otherwise, returns None.
+    fn first_critical_sequenced_action(&self) -> Option<&Action> {
+        let Action::ExecuteActionsInSequence(execute_actions_in_sequence) = self else {
+            return None;
+        };
+        execute_actions_in_sequence.critical_actions().next()
+    }
+
+    /// Returns the actions that are performed when a proposal with this action is executed. For
+    /// ExecuteActionsInSequence, these are the actions in the sequence. Otherwise, this is just
+    /// self.
+    pub(crate) fn constituent_actions(&self) -> Vec<&Action> {
+        match self {
+            Action::ExecuteActionsInSequence(execute_actions_in_sequence) => {
+                execute_actions_in_sequence
+                    .actions
+                    .iter()
+                    .filter_map(|proposal| proposal.action.as_ref())
+                    .collect()
+            }
+            action => vec![action],
+        }
+    }
+
+    /// Returns the function ID that is used to determine how neurons follow each other when
+    /// voting on a proposal with this action.
+    ///
+    /// This is the same as `u64::from(self)`, except for an ExecuteActionsInSequence that
+    /// contains a critical action, which is voted on under its first critical action.
+    /// Otherwise, critical actions could avoid the stricter following rules that apply to them
+    /// by being wrapped in a sequence. Other sequences are voted on under the
+    /// ExecuteActionsInSequence function ID itself, which falls back to catch-all following.
+    pub(crate) fn following_function_id(&self) -> u64 {
+        self.first_critical_sequenced_action()
+            .map(u64::from)
+            .unwrap_or_else(|| u64::from(self))
+    }
+}
+
+impl ExecuteActionsInSequence {
+    /// Returns the actions in this sequence that are critical.
+    fn critical_actions(&self) -> impl Iterator<Item = &Action> {
+        self.actions
+            .iter()
+            .filter_map(|proposal| proposal.action.as_ref())
+            // Nested sequences are invalid. Skipping them here avoids unbounded recursion.
+            .filter(|action| !matches!(action, Action::ExecuteActionsInSequence(_)))
+            .filter(|action| action.proposal_criticality() == ProposalCriticality::Critical)
+    }
+
+    /// Returns the distinct function IDs of the critical actions in this sequence.
+    ///
+    /// The sequence is voted on under the function ID of its first critical action (see
+    /// `Action::following_function_id`), so a valid sequence contains at most one of them.
+    pub(crate) fn critical_function_ids(&self) -> BTreeSet<u64> {
+        self.critical_actions().map(u64::from).collect()
+    }
 }
 
 pub(crate) fn function_id_to_proposal_criticality(function_id: u64) -> ProposalCriticality {
@@ -1687,6 +1825,9 @@ impl From<&Action> for u64 {
             Action::ManageDappCanisterSettings(_) => {
                 native_action_ids::MANAGE_DAPP_CANISTER_SETTINGS
             }
+            Action::ManageTreasuryTokens(_) => native_action_ids::MANAGE_TREASURY_TOKENS,
+            Action::ApproveSnsTreasuryFunds(_) => native_action_ids::APPROVE_SNS_TREASURY_FUNDS,
+            Action::ExecuteActionsInSequence(_) => native_action_ids::EXECUTE_ACTIONS_IN_SEQUENCE,
         }
     }
 }
@@ -2172,6 +2313,24 @@ impl From<MintSnsTokens> for Action {
     }
 }
 
+impl From<ManageTreasuryTokens> for Action {
+    fn from(manage_treasury_tokens: ManageTreasuryTokens) -> Action {
+        Action::ManageTreasuryTokens(manage_treasury_tokens)
+    }
+}
+
+impl From<ApproveSnsTreasuryFunds> for Action {
+    fn from(approve_sns_treasury_funds: ApproveSnsTreasuryFunds) -> Action {
+        Action::ApproveSnsTreasuryFunds(approve_sns_treasury_funds)
+    }
+}
+
+impl From<ExecuteActionsInSequence> for Action {
+    fn from(execute_actions_in_sequence: ExecuteActionsInSequence) -> Action {
+        Action::ExecuteActionsInSequence(execute_actions_in_sequence)
+    }
+}
+
 pub mod test_helpers {
     use super::*;
     use ic_crypto_sha2::Sha256;
@@ -3375,6 +3534,91 @@ pub(crate) mod tests {
         );
     }
 
+    #[test]
+    fn test_execute_actions_in_sequence_criticality_and_following() {
+        let sequence = |actions: Vec<Action>| {
+            Action::ExecuteActionsInSequence(ExecuteActionsInSequence {
+                actions: actions
+                    .into_iter()
+                    .map(|action| Proposal {
+                        action: Some(action),
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+        };
+
+        // Only normal actions: voted on under the ExecuteActionsInSequence function ID.
+        let normal_sequence = sequence(vec![
+            Action::ManageSnsMetadata(Default::default()),
+            Action::Motion(Default::default()),
+        ]);
+        assert_eq!(
+            normal_sequence.proposal_criticality(),
+            ProposalCriticality::Normal
+        );
+        assert_eq!(
+            normal_sequence.following_function_id(),
+            native_action_ids::EXECUTE_ACTIONS_IN_SEQUENCE
+        );
+
+        // A critical action makes the whole sequence critical.
+        let critical_sequence = sequence(vec![
+            Action::Motion(Default::default()),
+            Action::TransferSnsTreasuryFunds(Default::default()),
+            Action::MintSnsTokens(Default::default()),
+        ]);
+        assert_eq!(
+            critical_sequence.proposal_criticality(),
+            ProposalCriticality::Critical
+        );
+        assert_eq!(
+            critical_sequence.following_function_id(),
+            native_action_ids::TRANSFER_SNS_TREASURY_FUNDS
+        );
+        assert_eq!(critical_sequence.constituent_actions().len(), 3);
+        let Action::ExecuteActionsInSequence(execute_critical_sequence) = &critical_sequence else {
+            unreachable!()
+        };
+        assert_eq!(
+            execute_critical_sequence.critical_function_ids(),
+            BTreeSet::from([
+                native_action_ids::TRANSFER_SNS_TREASURY_FUNDS,
+                native_action_ids::MINT_SNS_TOKENS,
+            ])
+        );
+
+        // Repeating the same critical action yields a single critical function ID.
+        let repeated_critical_sequence = ExecuteActionsInSequence {
+            actions: [
+                Action::TransferSnsTreasuryFunds(Default::default()),
+                Action::Motion(Default::default()),
+                Action::TransferSnsTreasuryFunds(Default::default()),
+            ]
+            .into_iter()
+            .map(|action| Proposal {
+                action: Some(action),
+                ..Default::default()
+            })
+            .collect(),
+        };
+        assert_eq!(
+            repeated_critical_sequence.critical_function_ids(),
+            BTreeSet::from([native_action_ids::TRANSFER_SNS_TREASURY_FUNDS])
+        );
+
+        // Degenerate case.
+        let empty_sequence = sequence(vec![]);
+        assert_eq!(
+            empty_sequence.proposal_criticality(),
+            ProposalCriticality::Normal
+        );
+        assert_eq!(
+            empty_sequence.following_function_id(),
+            native_action_ids::EXECUTE_ACTIONS_IN_SEQUENCE
+        );
+    }
+
     #[test]
     fn test_strip_large_fields() {
         let motion_proposal = ProposalData {
@@ -1466,6 +1466,7 @@ fn test_make_critical_proposal() {
         memo: None,
         to_principal: Some(PrincipalId::new_user_test_id(42)),
         to_subaccount: None,
+        ledger_canister_id: None,
     };
     let (_proposal_id, proposal_data) = canister_fixture
         .make_default_proposal(&neuron_id, proposal, user_principal)
@@ -3152,6 +3153,7 @@ fn test_transfer_treasury_funds_has_higher_voting_thresholds() {
         memo: None,
         to_principal: Some(user_principal),
         to_subaccount: None,
+        ledger_canister_id: None,
     };
 
     // Create the proposal with neuron_id so it doesn't instantly pass
@@ -9,9 +9,12 @@ use ic_nervous_system_common::{
 };
 use ic_nervous_system_runtime::{CdkRuntime, Runtime};
 use ic_nervous_system_string::clamp_debug_len;
-use ic_nns_constants::{CYCLES_MINTING_CANISTER_ID, LEDGER_CANISTER_ID as ICP_LEDGER_CANISTER_ID};
-use ic_sns_swap::pb::v1::{
-    GetDerivedStateRequest, GetDerivedStateResponse, GetInitRequest, GetInitResponse,
+use ic_nns_constants::{
+    CYCLES_MINTING_CANISTER_ID, EXCHANGE_RATE_CANISTER_ID,
+    LEDGER_CANISTER_ID as ICP_LEDGER_CANISTER_ID,
+};
+use ic_xrc_types::{
+    Asset, AssetClass, ExchangeRate, GetExchangeRateRequest, GetExchangeRateResult,
 };
 use icrc_ledger_types::icrc1::account::Account;
 use mockall::automock;
@@ -62,6 +65,34 @@ pub async fn try_get_sns_token_balance_valuation(
     })
 }
 
+/// Like `try_get_sns_token_balance_valuation`, but for an ICRC-1 token other than ICP and the
+/// SNS token. Such tokens are priced (with respect to ICP) by the exchange rate canister, which
+/// knows them by the symbol of the asset that they represent, `exchange_rate_asset_symbol` (e.g.
+/// "BTC" for ckBTC).
+pub async fn try_get_icrc1_token_balance_valuation(
+    account: Account,
+    ledger_canister_id: CanisterId,
+    exchange_rate_asset_symbol: String,
+) -> Result<Valuation, ValuationError> {
+    let timestamp = now();
+
+    try_get_balance_valuation_factors(
+        account,
+        &mut LedgerCanister::new(ledger_canister_id),
+        &mut IcpsPerIcrc1TokenClient {
+            symbol: exchange_rate_asset_symbol,
+        },
+        &mut new_standard_xdrs_per_icp_client::<CdkRuntime>(),
+    )
+    .await
+    .map(|valuation_factors| Valuation {
+        token: Token::Icrc1Token,
+        account,
+        timestamp,
+        valuation_factors,
+    })
+}
+
 fn now() -> SystemTime {
     SystemTime::UNIX_EPOCH + Duration::from_nanos(ic_cdk::api::time())
 }
@@ -72,6 +103,9 @@ pub enum Token {
 
     /// The native token of the SNS.
     SnsToken,
+
+    /// Any other ICRC-1 token held by the SNS (e.g. ckBTC).
+    Icrc1Token,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -337,6 +371,75 @@ impl<R: Runtime + Send + Sync> IcpsPerTokenClient for IcpsPerSnsTokenClient<R> {
     }
 }
 
+/// Asks the exchange rate canister how many ICP one unit of the asset `symbol` is worth.
+struct IcpsPerIcrc1TokenClient {
+    /// The symbol of the asset as listed by the exchange rate canister (e.g. "BTC").
+    symbol: String,
+}
+
+impl IcpsPerIcrc1TokenClient {
+    /// The exchange rate canister charges this many cycles per get_exchange_rate call.
+    const XRC_REQUEST_CYCLES_COST: u128 = 1_000_000_000;
+}
+
+#[async_trait]
+impl IcpsPerTokenClient for IcpsPerIcrc1TokenClient {
+    async fn get(&mut self) -> Result<Decimal, ValuationError> {
+        let request = GetExchangeRateRequest {
+            base_asset: Asset {
+                symbol: self.symbol.clone(),
+                class: AssetClass::Cryptocurrency,
+            },
+            quote_asset: Asset {
+                symbol: "ICP".to_string(),
+                class: AssetClass::Cryptocurrency,
+            },
+            timestamp: None,
+        };
+
+        let (result,): (GetExchangeRateResult,) = ic_cdk::api::call::call_with_payment128(
+            EXCHANGE_RATE_CANISTER_ID.get().0,
+            "get_exchange_rate",
+            (request,),
+            Self::XRC_REQUEST_CYCLES_COST,
+        )
+        .await
+        .map_err(|err| {
+            ValuationError::new_external(format!(
+                "Unable to determine ICPs per {}, because the exchange rate canister did not \
+                 reply to a get_exchange_rate call: {:?}",
+                self.symbol, err,
+            ))
+        })?;
+
+        let exchange_rate = result.map_err(|err| {
+            ValuationError::new_external(format!(
+                "The exchange rate canister was unable to determine ICPs per {}: {:?}",
+                self.symbol, err,
+            ))
+        })?;
+
+        icps_per_token_from_exchange_rate(&exchange_rate)
+    }
+}
+
+/// Interprets `exchange_rate.rate`, which is a fixed point number with
+/// `exchange_rate.metadata.decimals` decimal places.
+fn icps_per_token_from_exchange_rate(
+    exchange_rate: &ExchangeRate,
+) -> Result<Decimal, ValuationError> {
+    Decimal::try_from_i128_with_scale(
+        i128::from(exchange_rate.rate),
+        exchange_rate.metadata.decimals,
+    )
+    .map_err(|err| {
+        ValuationError::new_arithmetic(format!(
+            "Unable to interpret exchange rate {:?}: {}",
+            exchange_rate, err,
+        ))
+    })
+}
+
 // Here, "standard" just means that it is appropriate for production use.
 fn new_standard_xdrs_per_icp_client<MyRuntime: Runtime + Send + Sync>() -> impl XdrsPerIcpClient {
     struct CmcBased30DayMovingAverageXdrsPerIcpClient<MyRuntime: Runtime + Send + Sync> {
@@ -380,6 +483,34 @@ fn new_standard_xdrs_per_icp_client<MyRuntime: Runtime + Send + Sync>() -> impl
     }
 }
 
+// Swap API
+//
+// These are subsets of the types of the same name in ic_sns_swap::pb::v1 (the swap canister's
+// Candid interface), which contain only the fields used here. Candid ignores the fields of a
+// reply that are missing from the type that it is decoded into. Depending on ic_sns_swap itself is
+// not possible, because ic_sns_swap depends on ic_sns_governance, which depends on this crate.
+
+#[derive(CandidType, candid::Deserialize, Clone, Debug, Default)]
+struct GetDerivedStateRequest {}
+
+#[derive(CandidType, candid::Deserialize, Clone, Debug, Default)]
+struct GetDerivedStateResponse {
+    buyer_total_icp_e8s: Option<u64>,
+}
+
+#[derive(CandidType, candid::Deserialize, Clone, Debug, Default)]
+struct GetInitRequest {}
+
+#[derive(CandidType, candid::Deserialize, Clone, Debug, Default)]
+struct GetInitResponse {
+    init: Option<SwapInit>,
+}
+
+#[derive(CandidType, candid::Deserialize, Clone, Debug, Default)]
+struct SwapInit {
+    sns_token_e8s: Option<u64>,
+}
+
 // Generic Helpers (could be moved to more general place).
 
 /// Associates a request type with method_name and response type.
@@ -17,7 +17,13 @@ use cycles_minting_canister::IcpXdrConversionRate;
 use ic_base_types::PrincipalId;
 use ic_ledger_core::Tokens;
 use ic_nervous_system_common::ledger::MockICRC1Ledger;
-use ic_sns_swap::pb::v1::Init as SwapInit;
+// The real swap types (rather than the subsets in this crate) are used to simulate the swap
+// canister, to make sure that replies from the real thing can be decoded.
+use ic_sns_swap::pb::v1::{
+    GetDerivedStateRequest, GetDerivedStateResponse, GetInitRequest, GetInitResponse,
+    Init as SwapInit,
+};
+use ic_xrc_types::ExchangeRateMetadata;
 use lazy_static::lazy_static;
 use maplit::hashmap;
 use mockall::predicate;
@@ -298,3 +304,38 @@ async fn test_new_standard_xdrs_per_icp_client() {
 
     assert_eq!(observed_xdrs_per_icp, Decimal::try_from(3.21).unwrap(),);
 }
+
+#[test]
+fn test_icps_per_token_from_exchange_rate() {
+    let exchange_rate = |rate, decimals| ExchangeRate {
+        base_asset: Asset {
+            symbol: "BTC".to_string(),
+            class: AssetClass::Cryptocurrency,
+        },
+        quote_asset: Asset {
+            symbol: "ICP".to_string(),
+            class: AssetClass::Cryptocurrency,
+        },
+        timestamp: 1_700_000_000,
+        rate,
+        metadata: ExchangeRateMetadata {
+            decimals,
+            base_asset_num_queried_sources: 7,
+            base_asset_num_received_rates: 7,
+            quote_asset_num_queried_sources: 7,
+            quote_asset_num_received_rates: 7,
+            standard_deviation: 0,
+            forex_timestamp: None,
+        },
+    };
+
+    // 1 BTC = 3_210.5 ICP.
+    assert_eq!(
+        icps_per_token_from_exchange_rate(&exchange_rate(3_210_500_000_000, 9)),
+        Ok(Decimal::try_from(3_210.5).unwrap()),
+    );
+
+    // A scale that Decimal cannot represent.
+    let err = icps_per_token_from_exchange_rate(&exchange_rate(42, 100)).unwrap_err();
+    assert_eq!(err.species, ValuationErrorSpecies::Arithmetic, "{:?}", err);
+}
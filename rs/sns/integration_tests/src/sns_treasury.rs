@@ -155,6 +155,7 @@ fn sns_treasury_can_transfer_funds_via_proposals() {
                 memo: None,
                 to_principal: Some(user),
                 to_subaccount: None,
+                ledger_canister_id: None,
             })),
         },
     )
@@ -179,6 +180,7 @@ fn sns_treasury_can_transfer_funds_via_proposals() {
                 memo: None,
                 to_principal: Some(user),
                 to_subaccount: None,
+                ledger_canister_id: None,
             })),
         },
     )
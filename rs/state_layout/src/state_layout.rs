@@ -14,6 +14,7 @@ use ic_protobuf::{
     },
 };
 use ic_replicated_state::{
+    canister_snapshots::SnapshotId,
     canister_state::{
         execution_state::{NextScheduledMethod, WasmMetadata},
         system_state::{wasm_chunk_store::WasmChunkStoreMetadata, CanisterHistory, CyclesUseCase},
@@ -22,9 +23,9 @@ use ic_replicated_state::{
 };
 use ic_sys::{fs::sync_path, mmap::ScopedMmap};
 use ic_types::{
-    batch::TotalQueryStats, nominal_cycles::NominalCycles, AccumulatedPriority, CanisterId,
-    ComputeAllocation, Cycles, ExecutionRound, Height, MemoryAllocation, NumInstructions,
-    PrincipalId,
+    batch::TotalQueryStats, canister_log::CanisterLog, nominal_cycles::NominalCycles,
+    AccumulatedPriority, CanisterId, ComputeAllocation, Cycles, ExecutionRound, Height,
+    MemoryAllocation, NumInstructions, PrincipalId, Time,
 };
 use ic_utils::thread::parallel_map;
 use ic_wasm_types::{CanisterModule, WasmHash};
@@ -53,6 +54,8 @@ pub const SPLIT_MARKER_FILE: &str = "split_from.pbuf";
 pub const SUBNET_QUEUES_FILE: &str = "subnet_queues.pbuf";
 pub const SYSTEM_METADATA_FILE: &str = "system_metadata.pbuf";
 pub const STATS_FILE: &str = "stats.pbuf";
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+pub const SNAPSHOT_FILE: &str = "snapshot.pbuf";
 
 /// `ReadOnly` is the access policy used for reading checkpoints. We
 /// don't want to ever modify persisted states.
@@ -162,6 +165,24 @@ pub struct CanisterStateBits {
     pub wasm_chunk_store_metadata: WasmChunkStoreMetadata,
     pub total_query_stats: TotalQueryStats,
     pub log_visibility: LogVisibility,
+    pub snapshots_memory_usage: NumBytes,
+    pub next_snapshot_id: u64,
+    pub canister_log: CanisterLog,
+}
+
+/// This struct contains the bits of a `CanisterSnapshot` that are not
+/// persisted as separate files (Wasm module and memories).
+#[derive(Debug)]
+pub struct CanisterSnapshotBits {
+    pub snapshot_id: SnapshotId,
+    pub canister_id: CanisterId,
+    pub taken_at_timestamp: Time,
+    pub canister_version: u64,
+    pub binary_hash: WasmHash,
+    pub certified_data: Vec<u8>,
+    pub exported_globals: Vec<Global>,
+    pub wasm_memory_size: NumWasmPages,
+    pub stable_memory_size: NumWasmPages,
 }
 
 #[derive(Clone)]
@@ -233,6 +254,13 @@ struct CheckpointRefData {
 /// │      │       ├── stable_memory.bin
 /// │      │       ├── vmemory_0.bin
 /// │      │       └── wasm_chunk_store.bin
+/// │      ├── snapshots
+/// │      │   └── <hex(canister_id)>
+/// │      │       └── <hex(local_snapshot_id)>
+/// │      │           ├── snapshot.pbuf
+/// │      │           ├── software.wasm
+/// │      │           ├── stable_memory.bin
+/// │      │           └── vmemory_0.bin
 /// │      ├── ingress_history.pbuf
 /// │      ├── split_from.pbuf
 /// │      ├── subnet_queues.pbuf
@@ -401,6 +429,46 @@ impl TipHandler {
         }
         Ok(())
     }
+
+    /// Deletes snapshots from tip if they are not in ids.
+    pub fn filter_tip_snapshots(
+        &mut self,
+        height: Height,
+        ids: &BTreeSet<SnapshotId>,
+    ) -> Result<(), LayoutError> {
+        let tip = self.tip(height)?;
+        let snapshots_on_disk = tip.snapshot_ids()?;
+        for id in snapshots_on_disk {
+            if !ids.contains(&id) {
+                let snapshot_path = tip.snapshot(&id)?.raw_path();
+                std::fs::remove_dir_all(&snapshot_path).map_err(|err| LayoutError::IoError {
+                    path: snapshot_path,
+                    message: "Cannot remove snapshot.".to_string(),
+                    io_err: err,
+                })?;
+            }
+        }
+        // Remove the directories of canisters that no longer have any snapshots.
+        let snapshots_dir = tip.raw_path().join(SNAPSHOTS_DIR);
+        for canister_id in collect_subdirs(snapshots_dir.as_path(), parse_canister_id)? {
+            let canister_dir = snapshots_dir.join(hex::encode(canister_id.get_ref().as_slice()));
+            if dir_file_names(&canister_dir)
+                .map_err(|err| LayoutError::IoError {
+                    path: canister_dir.clone(),
+                    message: "Cannot list snapshots.".to_string(),
+                    io_err: err,
+                })?
+                .is_empty()
+            {
+                std::fs::remove_dir(&canister_dir).map_err(|err| LayoutError::IoError {
+                    path: canister_dir,
+                    message: "Cannot remove snapshot directory.".to_string(),
+                    io_err: err,
+                })?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl StateLayout {
@@ -1214,13 +1282,26 @@ fn parse_canister_id(hex: &str) -> Result<CanisterId, String> {
     ))
 }
 
+/// Helper for parsing hex representations of local snapshot IDs, used for the
+/// directory names under `snapshots/<hex(canister_id)>`.
+fn parse_local_snapshot_id(hex: &str) -> Result<u64, String> {
+    u64::from_str_radix(hex, 16).map_err(|err| {
+        format!(
+            "failed to convert directory name {} into a snapshot ID: {}",
+            hex, err
+        )
+    })
+}
+
 /// Parses the canister ID from a relative path, if it is the path of a canister
-/// state file (e.g. `canister_states/00000000000000010101/queues.pbuf`).
-/// Returns `None` if the path is not under `canister_states`; or if parsing
-/// fails.
+/// state file (e.g. `canister_states/00000000000000010101/queues.pbuf`) or of
+/// a canister snapshot file (e.g. `snapshots/00000000000000010101/...`).
+/// Returns `None` if the path is under neither `canister_states` nor
+/// `snapshots`; or if parsing fails.
 pub fn canister_id_from_path(path: &Path) -> Option<CanisterId> {
     let mut path = path.iter();
-    if path.next() == Some(OsStr::new(CANISTER_STATES_DIR)) {
+    let dir = path.next();
+    if dir == Some(OsStr::new(CANISTER_STATES_DIR)) || dir == Some(OsStr::new(SNAPSHOTS_DIR)) {
         if let Some(hex) = path.next() {
             return parse_canister_id(hex.to_str()?).ok();
         }
@@ -1357,6 +1438,37 @@ impl<Permissions: AccessPolicy> CheckpointLayout<Permissions> {
         )
     }
 
+    /// Returns the IDs of all snapshots persisted in this checkpoint.
+    pub fn snapshot_ids(&self) -> Result<Vec<SnapshotId>, LayoutError> {
+        let snapshots_dir = self.root.join(SNAPSHOTS_DIR);
+        if !snapshots_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut result = Vec::new();
+        for canister_id in collect_subdirs(snapshots_dir.as_path(), parse_canister_id)? {
+            let canister_dir = snapshots_dir.join(hex::encode(canister_id.get_ref().as_slice()));
+            for local_id in collect_subdirs(canister_dir.as_path(), parse_local_snapshot_id)? {
+                result.push(SnapshotId::new(canister_id, local_id));
+            }
+        }
+        result.sort();
+        Ok(result)
+    }
+
+    pub fn snapshot(
+        &self,
+        snapshot_id: &SnapshotId,
+    ) -> Result<SnapshotLayout<Permissions>, LayoutError> {
+        SnapshotLayout::new(
+            self.root
+                .join(SNAPSHOTS_DIR)
+                .join(hex::encode(
+                    snapshot_id.get_canister_id().get_ref().as_slice(),
+                ))
+                .join(format!("{:016x}", snapshot_id.get_local_snapshot_id())),
+        )
+    }
+
     pub fn height(&self) -> Height {
         self.height
     }
@@ -1476,6 +1588,92 @@ impl<Permissions: AccessPolicy> CanisterLayout<Permissions> {
     }
 }
 
+pub struct SnapshotLayout<Permissions: AccessPolicy> {
+    snapshot_root: PathBuf,
+    permissions_tag: PhantomData<Permissions>,
+}
+
+impl<Permissions: AccessPolicy> SnapshotLayout<Permissions> {
+    pub fn new(snapshot_root: PathBuf) -> Result<Self, LayoutError> {
+        Permissions::check_dir(&snapshot_root)?;
+        Ok(Self {
+            snapshot_root,
+            permissions_tag: PhantomData,
+        })
+    }
+
+    pub fn raw_path(&self) -> PathBuf {
+        self.snapshot_root.clone()
+    }
+
+    pub fn snapshot(
+        &self,
+    ) -> ProtoFileWith<pb_canister_state_bits::CanisterSnapshotBits, Permissions> {
+        self.snapshot_root.join(SNAPSHOT_FILE).into()
+    }
+
+    pub fn wasm(&self) -> WasmFile<Permissions> {
+        self.snapshot_root.join("software.wasm").into()
+    }
+
+    /// List all overlay files with a particular name ending. See
+    /// `CanisterLayout::overlays_impl()` for the naming scheme.
+    fn overlays_impl(&self, name_end: &str) -> Result<Vec<PathBuf>, LayoutError> {
+        let map_error = |err| LayoutError::IoError {
+            path: self.snapshot_root.clone(),
+            message: "Failed list overlays".to_string(),
+            io_err: err,
+        };
+
+        let files = std::fs::read_dir(&self.snapshot_root).map_err(map_error)?;
+        let mut result = Vec::default();
+        for file in files {
+            let path = file.map_err(map_error)?.path();
+            match path.to_str() {
+                Some(p) if p.ends_with(name_end) => {
+                    result.push(path);
+                }
+                _ => (),
+            }
+        }
+        result.sort();
+
+        Ok(result)
+    }
+
+    /// Base file for the snapshot of the wasm memory.
+    pub fn vmemory_0(&self) -> PathBuf {
+        self.snapshot_root.join("vmemory_0.bin")
+    }
+
+    /// List of existing overlay files for the snapshot of the wasm memory.
+    pub fn vmemory_0_overlays(&self) -> Result<Vec<PathBuf>, LayoutError> {
+        self.overlays_impl("_vmemory_0.overlay")
+    }
+
+    /// Name of a (potentially new) overlay file for the wasm memory written at `height`.
+    pub fn vmemory_0_overlay(&self, height: Height) -> PathBuf {
+        self.snapshot_root
+            .join(format!("{:016x}_vmemory_0.overlay", height.get()))
+    }
+
+    /// Base file for the snapshot of the stable memory.
+    pub fn stable_memory_blob(&self) -> PathBuf {
+        self.snapshot_root.join("stable_memory.bin")
+    }
+
+    /// List of existing overlay files for the snapshot of the stable memory.
+    pub fn stable_memory_overlays(&self) -> Result<Vec<PathBuf>, LayoutError> {
+        self.overlays_impl("_stable_memory.overlay")
+    }
+
+    /// Name of a (potentially new) overlay file for the stable memory written at `height`.
+    pub fn stable_memory_overlay(&self, height: Height) -> PathBuf {
+        self.snapshot_root
+            .join(format!("{:016x}_stable_memory.overlay", height.get()))
+    }
+}
+
 fn open_for_write(path: &Path) -> Result<std::fs::File, LayoutError> {
     OpenOptions::new()
         .write(true)
@@ -1768,6 +1966,15 @@ impl From<CanisterStateBits> for pb_canister_state_bits::CanisterStateBits {
             wasm_chunk_store_metadata: Some((&item.wasm_chunk_store_metadata).into()),
             total_query_stats: Some((&item.total_query_stats).into()),
             log_visibility: item.log_visibility.into(),
+            snapshots_memory_usage: item.snapshots_memory_usage.get(),
+            next_snapshot_id: item.next_snapshot_id,
+            canister_log_records: item
+                .canister_log
+                .records()
+                .iter()
+                .map(|record| record.into())
+                .collect(),
+            next_canister_log_record_idx: item.canister_log.next_idx(),
         }
     }
 }
@@ -1888,6 +2095,69 @@ impl TryFrom<pb_canister_state_bits::CanisterStateBits> for CanisterStateBits {
             )
             .unwrap_or_default(),
             log_visibility: LogVisibility::from(value.log_visibility),
+            snapshots_memory_usage: NumBytes::from(value.snapshots_memory_usage),
+            next_snapshot_id: value.next_snapshot_id,
+            canister_log: CanisterLog::new(
+                value.next_canister_log_record_idx,
+                value
+                    .canister_log_records
+                    .into_iter()
+                    .map(|record| record.into())
+                    .collect(),
+            ),
+        })
+    }
+}
+
+impl From<CanisterSnapshotBits> for pb_canister_state_bits::CanisterSnapshotBits {
+    fn from(item: CanisterSnapshotBits) -> Self {
+        Self {
+            snapshot_id: item.snapshot_id.get_local_snapshot_id(),
+            canister_id: Some((item.canister_id).into()),
+            taken_at_timestamp: item.taken_at_timestamp.as_nanos_since_unix_epoch(),
+            canister_version: item.canister_version,
+            binary_hash: item.binary_hash.to_vec(),
+            certified_data: item.certified_data,
+            exported_globals: item
+                .exported_globals
+                .iter()
+                .map(|global| global.into())
+                .collect(),
+            wasm_memory_size: item.wasm_memory_size.get() as u64,
+            stable_memory_size: item.stable_memory_size.get() as u64,
+        }
+    }
+}
+
+impl TryFrom<pb_canister_state_bits::CanisterSnapshotBits> for CanisterSnapshotBits {
+    type Error = ProxyDecodeError;
+
+    fn try_from(value: pb_canister_state_bits::CanisterSnapshotBits) -> Result<Self, Self::Error> {
+        let canister_id: CanisterId =
+            try_from_option_field(value.canister_id, "CanisterSnapshotBits::canister_id")?;
+        let binary_hash: [u8; 32] =
+            value
+                .binary_hash
+                .try_into()
+                .map_err(|e| ProxyDecodeError::ValueOutOfRange {
+                    typ: "BinaryHash",
+                    err: format!("Expected a 32-byte long module hash, got {:?}", e),
+                })?;
+        let mut exported_globals = Vec::with_capacity(value.exported_globals.len());
+        for global in value.exported_globals.into_iter() {
+            exported_globals.push(global.try_into()?);
+        }
+
+        Ok(Self {
+            snapshot_id: SnapshotId::new(canister_id, value.snapshot_id),
+            canister_id,
+            taken_at_timestamp: Time::from_nanos_since_unix_epoch(value.taken_at_timestamp),
+            canister_version: value.canister_version,
+            binary_hash: binary_hash.into(),
+            certified_data: value.certified_data,
+            exported_globals,
+            wasm_memory_size: NumWasmPages::from(value.wasm_memory_size as usize),
+            stable_memory_size: NumWasmPages::from(value.stable_memory_size as usize),
         })
     }
 }
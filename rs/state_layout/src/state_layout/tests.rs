@@ -53,6 +53,9 @@ fn default_canister_state_bits() -> CanisterStateBits {
         wasm_chunk_store_metadata: WasmChunkStoreMetadata::default(),
         total_query_stats: TotalQueryStats::default(),
         log_visibility: LogVisibility::default(),
+        snapshots_memory_usage: NumBytes::from(0),
+        next_snapshot_id: 0,
+        canister_log: CanisterLog::default(),
     }
 }
 
@@ -113,6 +116,24 @@ fn test_encode_decode_non_empty_controllers() {
     assert_eq!(canister_state_bits.controllers, expected_controllers);
 }
 
+#[test]
+fn test_encode_decode_canister_log() {
+    let mut canister_log = CanisterLog::default();
+    canister_log.add_record(42, b"first".to_vec());
+    canister_log.add_record(43, b"second".to_vec());
+
+    let canister_state_bits = CanisterStateBits {
+        canister_log: canister_log.clone(),
+        ..default_canister_state_bits()
+    };
+
+    let pb_bits = pb_canister_state_bits::CanisterStateBits::from(canister_state_bits);
+    let canister_state_bits = CanisterStateBits::try_from(pb_bits).unwrap();
+
+    assert_eq!(canister_state_bits.canister_log, canister_log);
+    assert_eq!(canister_state_bits.canister_log.next_idx(), 2);
+}
+
 #[test]
 fn test_encode_decode_empty_history() {
     let canister_history = CanisterHistory::default();
@@ -351,6 +372,58 @@ fn test_canister_id_from_path() {
         None,
         canister_id_from_path(Path::new("canister_states/not-a-canister-ID/queues.pbuf"))
     );
+    assert_eq!(
+        Some(CanisterId::from_u64(4)),
+        canister_id_from_path(Path::new(
+            "snapshots/00000000000000040101/0000000000000000/snapshot.pbuf"
+        ))
+    );
+}
+
+#[test]
+fn test_encode_decode_canister_snapshot_bits() {
+    let canister_id = canister_test_id(7);
+    let snapshot_bits = CanisterSnapshotBits {
+        snapshot_id: SnapshotId::new(canister_id, 3),
+        canister_id,
+        taken_at_timestamp: mock_time(),
+        canister_version: 5,
+        binary_hash: WasmHash::from(&CanisterModule::new(vec![1, 2, 3])),
+        certified_data: vec![4, 5, 6],
+        exported_globals: vec![Global::I32(1), Global::F64(2.0)],
+        wasm_memory_size: NumWasmPages::from(10),
+        stable_memory_size: NumWasmPages::from(20),
+    };
+
+    let pb_bits = pb_canister_state_bits::CanisterSnapshotBits::from(snapshot_bits);
+    let decoded = CanisterSnapshotBits::try_from(pb_bits).unwrap();
+
+    assert_eq!(decoded.snapshot_id, SnapshotId::new(canister_id, 3));
+    assert_eq!(decoded.canister_version, 5);
+    assert_eq!(decoded.certified_data, vec![4, 5, 6]);
+    assert_eq!(
+        decoded.exported_globals,
+        vec![Global::I32(1), Global::F64(2.0)]
+    );
+    assert_eq!(decoded.wasm_memory_size, NumWasmPages::from(10));
+    assert_eq!(decoded.stable_memory_size, NumWasmPages::from(20));
+}
+
+#[test]
+fn test_snapshot_ids_are_read_back() {
+    let tmp = tmpdir("checkpoint");
+    let checkpoint_layout: CheckpointLayout<RwPolicy<()>> =
+        CheckpointLayout::new_untracked(tmp.path().to_owned(), Height::new(0)).unwrap();
+    let snapshot_ids = vec![
+        SnapshotId::new(canister_test_id(1), 0),
+        SnapshotId::new(canister_test_id(1), 17),
+        SnapshotId::new(canister_test_id(2), 1),
+    ];
+    // Creating the layout of a snapshot creates its directory.
+    for snapshot_id in &snapshot_ids {
+        checkpoint_layout.snapshot(snapshot_id).unwrap();
+    }
+    assert_eq!(checkpoint_layout.snapshot_ids().unwrap(), snapshot_ids);
 }
 
 // A strategy to create a randomly sampled and strictly monotonic sequence of `Height`.
@@ -101,7 +101,7 @@ use ic_types::crypto::{
 use ic_types::malicious_flags::MaliciousFlags;
 use ic_types::messages::{
     CallbackId, Certificate, CertificateDelegation, RejectContext, Response,
-    EXPECTED_MESSAGE_ID_LENGTH,
+    EXPECTED_MESSAGE_ID_LENGTH, NO_DEADLINE,
 };
 use ic_types::signature::ThresholdSignature;
 use ic_types::time::GENESIS;
@@ -1031,6 +1031,7 @@ impl StateMachine {
                 originator_reply_callback: id,
                 refund: Cycles::zero(),
                 response_payload: MsgPayload::Data(reply.encode()),
+                deadline: NO_DEADLINE,
             });
         }
 
@@ -1569,6 +1570,7 @@ impl StateMachine {
                 originator_reply_callback: id,
                 refund: Cycles::zero(),
                 response_payload: MsgPayload::Data(reply.encode()),
+                deadline: NO_DEADLINE,
             });
         }
         self.execute_payload(payload);
@@ -1882,6 +1884,39 @@ impl StateMachine {
             .commit_and_certify(state, h.increment(), CertificationScope::Full);
     }
 
+    /// Imports a canister archive produced by `state-tool export_canister`
+    /// into the state machine, under the given canister ID.
+    ///
+    /// This allows reproducing issues locally using the Wasm module, memories
+    /// and state bits of a canister extracted from a production checkpoint.
+    /// The original archive is not modified.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if unpacking or loading the canister archive fails.
+    pub fn import_canister<P: AsRef<Path>>(&self, archive_path: P, canister_id: CanisterId) {
+        let archive_path = archive_path.as_ref();
+        let file = std::fs::File::open(archive_path).unwrap_or_else(|e| {
+            panic!(
+                "failed to open canister archive {}: {}",
+                archive_path.display(),
+                e
+            )
+        });
+        let canister_directory = tempfile::tempdir().expect("failed to create a temp directory");
+        tar::Archive::new(file)
+            .unpack(canister_directory.path())
+            .unwrap_or_else(|e| {
+                panic!(
+                    "failed to unpack canister archive {}: {}",
+                    archive_path.display(),
+                    e
+                )
+            });
+
+        self.import_canister_state(canister_directory.path(), canister_id);
+    }
+
     /// Replaces the canister state in this state machine with the canister
     /// state in given source replicated state.
     ///
@@ -2813,6 +2848,7 @@ impl PayloadBuilder {
             originator_reply_callback: id,
             refund: Cycles::zero(),
             response_payload: MsgPayload::Data(payload.encode()),
+            deadline: NO_DEADLINE,
         });
         self
     }
@@ -2829,6 +2865,7 @@ impl PayloadBuilder {
             originator_reply_callback: id,
             refund: Cycles::zero(),
             response_payload: MsgPayload::Reject(RejectContext::new(code, message)),
+            deadline: NO_DEADLINE,
         });
         self
     }
@@ -1,12 +1,17 @@
-use ic_config::{execution_environment::Config as HypervisorConfig, subnet_config::SubnetConfig};
+use ic_config::{
+    embedders::FeatureFlags, execution_environment::Config as HypervisorConfig,
+    flag_status::FlagStatus, subnet_config::SubnetConfig,
+};
 use ic_ic00_types::{EcdsaCurve, EcdsaKeyId};
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
 use ic_registry_routing_table::{CanisterIdRange, RoutingTable, CANISTER_IDS_PER_SUBNET};
 use ic_registry_subnet_type::SubnetType;
+use ic_replicated_state::CallOrigin;
 use ic_state_machine_tests::{StateMachine, StateMachineBuilder, StateMachineConfig};
 use ic_test_utilities::types::ids::{subnet_test_id, user_test_id};
 use ic_types::{
     ingress::{IngressStatus, WasmResult},
+    messages::NO_DEADLINE,
     CanisterId, Cycles, SubnetId,
 };
 use ic_universal_canister::{wasm, CallArgs, UNIVERSAL_CANISTER_WASM};
@@ -23,8 +28,27 @@ fn test_setup(
     routing_table: RoutingTable,
     registry_data_provider: Arc<ProtoRegistryDataProvider>,
 ) -> Arc<StateMachine> {
-    let config =
-        StateMachineConfig::new(SubnetConfig::new(subnet_type), HypervisorConfig::default());
+    test_setup_with_hypervisor_config(
+        subnets,
+        subnet_id,
+        subnet_type,
+        subnet_list,
+        routing_table,
+        registry_data_provider,
+        HypervisorConfig::default(),
+    )
+}
+
+fn test_setup_with_hypervisor_config(
+    subnets: Arc<RwLock<BTreeMap<SubnetId, Arc<StateMachine>>>>,
+    subnet_id: SubnetId,
+    subnet_type: SubnetType,
+    subnet_list: Vec<SubnetId>,
+    routing_table: RoutingTable,
+    registry_data_provider: Arc<ProtoRegistryDataProvider>,
+    hypervisor_config: HypervisorConfig,
+) -> Arc<StateMachine> {
+    let config = StateMachineConfig::new(SubnetConfig::new(subnet_type), hypervisor_config);
     StateMachineBuilder::new()
         .with_config(Some(config))
         .with_subnet_id(subnet_id)
@@ -386,3 +410,156 @@ fn counter_canister_call_test() {
         _ => panic!("unreachable"),
     };
 }
+
+/// A canister whose `call` method forwards its argument to the `update` method
+/// of `callee` as a best-effort call with a 60 second timeout, then replies.
+fn best_effort_caller_wat(callee: CanisterId) -> String {
+    let callee_bytes: String = callee
+        .get()
+        .as_slice()
+        .iter()
+        .map(|b| format!("\\{:02x}", b))
+        .collect();
+    format!(
+        r#"(module
+            (import "ic0" "call_new"
+                (func $call_new
+                    (param i32 i32)
+                    (param $method_name_src i32) (param $method_name_len i32)
+                    (param $reply_fun i32) (param $reply_env i32)
+                    (param $reject_fun i32) (param $reject_env i32)))
+            (import "ic0" "call_data_append" (func $call_data_append (param i32 i32)))
+            (import "ic0" "call_with_best_effort_response"
+                (func $call_with_best_effort_response (param i32)))
+            (import "ic0" "call_perform" (func $call_perform (result i32)))
+            (import "ic0" "msg_arg_data_size" (func $msg_arg_data_size (result i32)))
+            (import "ic0" "msg_arg_data_copy" (func $msg_arg_data_copy (param i32 i32 i32)))
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (func $call
+                (call $msg_arg_data_copy (i32.const 1000) (i32.const 0) (call $msg_arg_data_size))
+                (call $call_new
+                    (i32.const 100) (i32.const {callee_len})
+                    (i32.const 0) (i32.const 6)
+                    (i32.const 0) (i32.const 0)
+                    (i32.const 0) (i32.const 0))
+                (call $call_data_append (i32.const 1000) (call $msg_arg_data_size))
+                (call $call_with_best_effort_response (i32.const 60))
+                (drop (call $call_perform))
+                (call $msg_reply))
+            (export "canister_update call" (func $call))
+            (memory $memory 1)
+            (export "memory" (memory $memory))
+            (data (i32.const 0) "update")
+            (data (i32.const 100) "{callee_bytes}"))"#,
+        callee_len = callee.get().as_slice().len(),
+        callee_bytes = callee_bytes,
+    )
+}
+
+#[test]
+fn best_effort_call_deadline_survives_xnet() {
+    let user_id = user_test_id(1).get();
+
+    let subnet_id1 = subnet_test_id(1);
+    let subnet_id2 = subnet_test_id(2);
+    let range1 = CanisterIdRange {
+        start: CanisterId::from_u64(0),
+        end: CanisterId::from_u64(CANISTER_IDS_PER_SUBNET - 1),
+    };
+    let range2 = CanisterIdRange {
+        start: CanisterId::from_u64(CANISTER_IDS_PER_SUBNET),
+        end: CanisterId::from_u64(2 * CANISTER_IDS_PER_SUBNET - 1),
+    };
+    let mut routing_table = RoutingTable::new();
+    routing_table.insert(range1, subnet_id1).unwrap();
+    routing_table.insert(range2, subnet_id2).unwrap();
+    let subnet_list = vec![subnet_id1, subnet_id2];
+    let registry_data_provider = Arc::new(ProtoRegistryDataProvider::new());
+
+    let mut hypervisor_config = HypervisorConfig::default();
+    hypervisor_config.embedders_config.feature_flags = FeatureFlags {
+        best_effort_responses: FlagStatus::Enabled,
+        ..FeatureFlags::default()
+    };
+    let subnets = Arc::new(RwLock::new(BTreeMap::new()));
+    let env1 = test_setup_with_hypervisor_config(
+        subnets.clone(),
+        subnet_id1,
+        SubnetType::Application,
+        subnet_list.clone(),
+        routing_table.clone(),
+        registry_data_provider.clone(),
+        hypervisor_config,
+    );
+    let env2 = test_setup(
+        subnets.clone(),
+        subnet_id2,
+        SubnetType::Application,
+        subnet_list,
+        routing_table,
+        registry_data_provider.clone(),
+    );
+    env1.reload_registry();
+    env2.reload_registry();
+
+    let callee = env2
+        .install_canister_with_cycles(
+            UNIVERSAL_CANISTER_WASM.to_vec(),
+            vec![],
+            None,
+            INITIAL_CYCLES_BALANCE,
+        )
+        .unwrap();
+    let caller = env1
+        .install_canister_with_cycles(
+            wat::parse_str(best_effort_caller_wat(callee)).unwrap(),
+            vec![],
+            None,
+            INITIAL_CYCLES_BALANCE,
+        )
+        .unwrap();
+
+    // The callee makes a call back to the caller's subnet and does not reply,
+    // so that its call context stays open.
+    env1.submit_ingress_as(
+        user_id,
+        caller,
+        "call",
+        wasm().inter_update(caller, CallArgs::default()).build(),
+    )
+    .unwrap();
+    env1.execute_round();
+    env2.execute_round();
+
+    let caller_state = env1.get_latest_state();
+    let callbacks = caller_state
+        .canister_state(&caller)
+        .unwrap()
+        .system_state
+        .call_context_manager()
+        .unwrap()
+        .callbacks();
+    assert_eq!(callbacks.len(), 1);
+    let deadline = callbacks.values().next().unwrap().deadline;
+    assert_ne!(deadline, NO_DEADLINE);
+
+    let callee_state = env2.get_latest_state();
+    let call_origins: Vec<_> = callee_state
+        .canister_state(&callee)
+        .unwrap()
+        .system_state
+        .call_context_manager()
+        .unwrap()
+        .call_contexts()
+        .values()
+        .map(|call_context| call_context.call_origin().clone())
+        .collect();
+    assert_eq!(call_origins.len(), 1);
+    match &call_origins[0] {
+        CallOrigin::CanisterUpdate(sender, _, callee_deadline) => {
+            assert_eq!(*sender, caller);
+            assert_eq!(*callee_deadline, deadline);
+        }
+        origin => panic!("unexpected call origin {:?}", origin),
+    }
+}
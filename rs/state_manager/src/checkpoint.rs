@@ -9,11 +9,18 @@ use ic_logger::error;
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::page_map::PageAllocatorFileDescriptor;
 use ic_replicated_state::{
-    canister_state::execution_state::WasmBinary, page_map::PageMap, CanisterMetrics, CanisterState,
-    ExecutionState, ReplicatedState, SchedulerState, SystemState,
+    canister_snapshots::{
+        CanisterSnapshot, CanisterSnapshots, ExecutionStateSnapshot, PageMemory, SnapshotId,
+    },
+    canister_state::execution_state::WasmBinary,
+    page_map::PageMap,
+    CanisterMetrics, CanisterState, ExecutionState, ReplicatedState, SchedulerState, SystemState,
 };
 use ic_replicated_state::{CheckpointLoadingMetrics, Memory};
-use ic_state_layout::{CanisterLayout, CanisterStateBits, CheckpointLayout, ReadOnly, ReadPolicy};
+use ic_state_layout::{
+    CanisterLayout, CanisterSnapshotBits, CanisterStateBits, CheckpointLayout, ReadOnly,
+    ReadPolicy, SnapshotLayout,
+};
 use ic_types::batch::RawQueryStats;
 use ic_types::{CanisterTimer, Height, LongExecutionMode, Time};
 use ic_utils::thread::parallel_map;
@@ -77,6 +84,13 @@ pub(crate) fn make_checkpoint(
         })
         .unwrap();
 
+    tip_channel
+        .send(TipRequest::FilterTipSnapshots {
+            height,
+            ids: state.canister_snapshots.ids(),
+        })
+        .unwrap();
+
     let cp = {
         let _timer = metrics
             .make_checkpoint_step_duration
@@ -258,8 +272,32 @@ pub fn load_checkpoint<P: ReadPolicy + Send + Sync>(
         canister_states
     };
 
-    let state =
-        ReplicatedState::new_from_checkpoint(canister_states, metadata, subnet_queues, query_stats);
+    let canister_snapshots = {
+        let _timer = metrics
+            .load_checkpoint_step_duration
+            .with_label_values(&["canister_snapshots"])
+            .start_timer();
+
+        let mut snapshots = BTreeMap::new();
+        for snapshot_id in checkpoint_layout.snapshot_ids()? {
+            let snapshot = load_snapshot_from_checkpoint(
+                checkpoint_layout,
+                &snapshot_id,
+                Arc::clone(&fd_factory),
+            )?;
+            snapshots.insert(snapshot_id, Arc::new(snapshot));
+        }
+
+        CanisterSnapshots::new(snapshots)
+    };
+
+    let state = ReplicatedState::new_from_checkpoint(
+        canister_states,
+        metadata,
+        subnet_queues,
+        query_stats,
+        canister_snapshots,
+    );
 
     Ok(state)
 }
@@ -410,6 +448,9 @@ pub fn load_canister_state<P: ReadPolicy>(
         wasm_chunk_store_data,
         canister_state_bits.wasm_chunk_store_metadata,
         canister_state_bits.log_visibility,
+        canister_state_bits.snapshots_memory_usage,
+        canister_state_bits.next_snapshot_id,
+        canister_state_bits.canister_log,
     );
 
     let canister_state = CanisterState {
@@ -450,3 +491,68 @@ fn load_canister_state_from_checkpoint<P: ReadPolicy>(
         Arc::clone(&fd_factory),
     )
 }
+
+pub fn load_snapshot<P: ReadPolicy>(
+    snapshot_layout: &SnapshotLayout<P>,
+    snapshot_id: &SnapshotId,
+    height: Height,
+    fd_factory: Arc<dyn PageAllocatorFileDescriptor>,
+) -> Result<CanisterSnapshot, CheckpointError> {
+    let canister_snapshot_bits: CanisterSnapshotBits = CanisterSnapshotBits::try_from(
+        snapshot_layout.snapshot().deserialize()?,
+    )
+    .map_err(|err| CheckpointError::ProtoError {
+        path: snapshot_layout.raw_path(),
+        field: format!("snapshots[{}]::canister_snapshot_bits", snapshot_id),
+        proto_err: err.to_string(),
+    })?;
+
+    let wasm_memory = PageMemory {
+        page_map: PageMap::open(
+            &snapshot_layout.vmemory_0(),
+            &snapshot_layout.vmemory_0_overlays()?,
+            height,
+            Arc::clone(&fd_factory),
+        )?,
+        size: canister_snapshot_bits.wasm_memory_size,
+    };
+    let stable_memory = PageMemory {
+        page_map: PageMap::open(
+            &snapshot_layout.stable_memory_blob(),
+            &snapshot_layout.stable_memory_overlays()?,
+            height,
+            Arc::clone(&fd_factory),
+        )?,
+        size: canister_snapshot_bits.stable_memory_size,
+    };
+    let wasm_binary = snapshot_layout
+        .wasm()
+        .deserialize(Some(canister_snapshot_bits.binary_hash))?;
+
+    Ok(CanisterSnapshot::new(
+        canister_snapshot_bits.canister_id,
+        canister_snapshot_bits.taken_at_timestamp,
+        canister_snapshot_bits.canister_version,
+        canister_snapshot_bits.certified_data,
+        ExecutionStateSnapshot {
+            wasm_binary,
+            exported_globals: canister_snapshot_bits.exported_globals,
+            stable_memory,
+            wasm_memory,
+        },
+    ))
+}
+
+fn load_snapshot_from_checkpoint<P: ReadPolicy>(
+    checkpoint_layout: &CheckpointLayout<P>,
+    snapshot_id: &SnapshotId,
+    fd_factory: Arc<dyn PageAllocatorFileDescriptor>,
+) -> Result<CanisterSnapshot, CheckpointError> {
+    let snapshot_layout = checkpoint_layout.snapshot(snapshot_id)?;
+    load_snapshot::<P>(
+        &snapshot_layout,
+        snapshot_id,
+        checkpoint_layout.height(),
+        Arc::clone(&fd_factory),
+    )
+}
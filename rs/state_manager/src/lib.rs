@@ -43,6 +43,7 @@ use ic_protobuf::proxy::{ProtoProxy, ProxyDecodeError};
 use ic_protobuf::{messaging::xnet::v1, state::v1 as pb};
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::{
+    canister_snapshots::{SnapshotId, SnapshotOperation},
     canister_state::execution_state::SandboxMemory,
     page_map::{PersistenceError, StorageMetrics},
     PageIndex, PageMap, ReplicatedState,
@@ -1050,6 +1051,8 @@ pub enum PageMapType {
     WasmMemory(CanisterId),
     StableMemory(CanisterId),
     WasmChunkStore(CanisterId),
+    SnapshotWasmMemory(SnapshotId),
+    SnapshotStableMemory(SnapshotId),
 }
 
 impl PageMapType {
@@ -1063,6 +1066,10 @@ impl PageMapType {
                 result.push(Self::StableMemory(id.to_owned()));
             }
         }
+        for (id, _snapshot) in state.canister_snapshots.iter() {
+            result.push(Self::SnapshotWasmMemory(id.to_owned()));
+            result.push(Self::SnapshotStableMemory(id.to_owned()));
+        }
 
         result
     }
@@ -1076,6 +1083,8 @@ impl PageMapType {
             PageMapType::WasmMemory(id) => Ok(layout.canister(id)?.vmemory_0()),
             PageMapType::StableMemory(id) => Ok(layout.canister(id)?.stable_memory_blob()),
             PageMapType::WasmChunkStore(id) => Ok(layout.canister(id)?.wasm_chunk_store()),
+            PageMapType::SnapshotWasmMemory(id) => Ok(layout.snapshot(id)?.vmemory_0()),
+            PageMapType::SnapshotStableMemory(id) => Ok(layout.snapshot(id)?.stable_memory_blob()),
         }
     }
 
@@ -1094,6 +1103,12 @@ impl PageMapType {
             PageMapType::WasmChunkStore(id) => {
                 Ok(layout.canister(id)?.wasm_chunk_store_overlay(height))
             }
+            PageMapType::SnapshotWasmMemory(id) => {
+                Ok(layout.snapshot(id)?.vmemory_0_overlay(height))
+            }
+            PageMapType::SnapshotStableMemory(id) => {
+                Ok(layout.snapshot(id)?.stable_memory_overlay(height))
+            }
         }
     }
 
@@ -1109,6 +1124,8 @@ impl PageMapType {
             PageMapType::WasmMemory(id) => layout.canister(id)?.vmemory_0_overlays(),
             PageMapType::StableMemory(id) => layout.canister(id)?.stable_memory_overlays(),
             PageMapType::WasmChunkStore(id) => layout.canister(id)?.wasm_chunk_store_overlays(),
+            PageMapType::SnapshotWasmMemory(id) => layout.snapshot(id)?.vmemory_0_overlays(),
+            PageMapType::SnapshotStableMemory(id) => layout.snapshot(id)?.stable_memory_overlays(),
         }
     }
 
@@ -1128,6 +1145,14 @@ impl PageMapType {
             PageMapType::WasmChunkStore(id) => state
                 .canister_state(id)
                 .map(|can| can.system_state.wasm_chunk_store.page_map()),
+            PageMapType::SnapshotWasmMemory(id) => state
+                .canister_snapshots
+                .get(*id)
+                .map(|snap| &snap.execution_snapshot().wasm_memory.page_map),
+            PageMapType::SnapshotStableMemory(id) => state
+                .canister_snapshots
+                .get(*id)
+                .map(|snap| &snap.execution_snapshot().stable_memory.page_map),
         }
     }
 
@@ -1147,6 +1172,22 @@ impl PageMapType {
             PageMapType::WasmChunkStore(id) => state
                 .canister_state_mut(id)
                 .map(|can| can.system_state.wasm_chunk_store.page_map_mut()),
+            PageMapType::SnapshotWasmMemory(id) => {
+                state.canister_snapshots.get_mut(*id).map(|snap| {
+                    &mut Arc::make_mut(snap)
+                        .execution_snapshot_mut()
+                        .wasm_memory
+                        .page_map
+                })
+            }
+            PageMapType::SnapshotStableMemory(id) => {
+                state.canister_snapshots.get_mut(*id).map(|snap| {
+                    &mut Arc::make_mut(snap)
+                        .execution_snapshot_mut()
+                        .stable_memory
+                        .page_map
+                })
+            }
         }
     }
 }
@@ -1919,6 +1960,32 @@ impl StateManagerImpl {
     /// during execution from the last flush.
     fn flush_page_maps(&self, tip_state: &mut ReplicatedState, height: Height) {
         self.metrics.checkpoint_metrics.page_map_flushes.inc();
+
+        // Taking or loading a snapshot clones PageMaps, so the files backing the source
+        // must be copied to the destination before we persist any delta on top of them.
+        let snapshot_operations = tip_state.canister_snapshots.take_unflushed_changes();
+        let copied_page_maps: Vec<PageMapType> = snapshot_operations
+            .iter()
+            .flat_map(|op| match op {
+                SnapshotOperation::Backup(_, snapshot_id) => vec![
+                    PageMapType::SnapshotWasmMemory(*snapshot_id),
+                    PageMapType::SnapshotStableMemory(*snapshot_id),
+                ],
+                SnapshotOperation::Restore(canister_id, _) => vec![
+                    PageMapType::WasmMemory(*canister_id),
+                    PageMapType::StableMemory(*canister_id),
+                ],
+            })
+            .collect();
+        if !snapshot_operations.is_empty() {
+            self.tip_channel
+                .send(TipRequest::SnapshotOperations {
+                    height,
+                    operations: snapshot_operations,
+                })
+                .unwrap();
+        }
+
         let mut pagemaps = Vec::new();
         for entry in PageMapType::list_all(tip_state) {
             if let Some(page_map) = entry.get_mut(tip_state) {
@@ -1949,6 +2016,13 @@ impl StateManagerImpl {
                 page_map.strip_unflushed_delta();
             }
         }
+        // The files of PageMaps that were copied no longer correspond to the files at their
+        // base height, so they must not be used for incremental manifest computation.
+        for entry in copied_page_maps {
+            if let Some(page_map) = entry.get_mut(tip_state) {
+                page_map.base_height = None;
+            }
+        }
         if !pagemaps.is_empty() {
             self.tip_channel
                 .send(TipRequest::FlushPageMapDelta { height, pagemaps })
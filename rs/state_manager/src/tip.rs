@@ -19,12 +19,14 @@ use ic_replicated_state::page_map::{
 };
 #[allow(unused)]
 use ic_replicated_state::{
-    canister_state::execution_state::SandboxMemory, page_map::PAGE_SIZE, CanisterState,
-    NumWasmPages, PageMap, ReplicatedState,
+    canister_snapshots::{CanisterSnapshot, SnapshotId, SnapshotOperation},
+    canister_state::execution_state::SandboxMemory,
+    page_map::PAGE_SIZE,
+    CanisterState, NumWasmPages, PageMap, ReplicatedState,
 };
 use ic_state_layout::{
-    error::LayoutError, CanisterStateBits, CheckpointLayout, ExecutionStateBits, ReadOnly,
-    RwPolicy, StateLayout, TipHandler,
+    error::LayoutError, CanisterSnapshotBits, CanisterStateBits, CheckpointLayout,
+    ExecutionStateBits, ReadOnly, RwPolicy, StateLayout, TipHandler,
 };
 use ic_sys::fs::defrag_file_partially;
 use ic_types::{malicious_flags::MaliciousFlags, CanisterId, Height};
@@ -80,6 +82,20 @@ pub(crate) enum TipRequest {
         height: Height,
         ids: BTreeSet<CanisterId>,
     },
+    /// Filter snapshots in tip. Remove ones not present in the set.
+    /// State: !Empty
+    FilterTipSnapshots {
+        height: Height,
+        ids: BTreeSet<SnapshotId>,
+    },
+    /// Copy the PageMap files of canisters into snapshots (or vice versa) in the
+    /// order the operations were executed. Must precede the flush of the
+    /// corresponding PageMaps.
+    /// State: ReadyForPageDeltas(h) -> ReadyForPageDeltas(height), height >= h
+    SnapshotOperations {
+        height: Height,
+        operations: Vec<SnapshotOperation>,
+    },
     /// Flush PageMaps's unflushed delta on disc.
     /// State: ReadyForPageDeltas(h) -> ReadyForPageDeltas(height), height >= h
     FlushPageMapDelta {
@@ -210,6 +226,47 @@ pub(crate) fn spawn_tip_thread(
                                     )
                                 });
                         }
+                        TipRequest::FilterTipSnapshots { height, ids } => {
+                            debug_assert_ne!(tip_state, TipState::Empty);
+
+                            let _timer = request_timer(&metrics, "filter_tip_snapshots");
+                            tip_handler
+                                .filter_tip_snapshots(height, &ids)
+                                .unwrap_or_else(|err| {
+                                    fatal!(
+                                        log,
+                                        "Failed to filter tip snapshots for height @{}: {}",
+                                        height,
+                                        err
+                                    )
+                                });
+                        }
+                        TipRequest::SnapshotOperations { height, operations } => {
+                            let _timer = request_timer(&metrics, "snapshot_operations");
+                            #[cfg(debug_assertions)]
+                            match tip_state {
+                                TipState::ReadyForPageDeltas(h) => debug_assert!(height >= h),
+                                _ => panic!("Unexpected tip state: {:?}", tip_state),
+                            }
+                            tip_state = TipState::ReadyForPageDeltas(height);
+                            for operation in operations {
+                                apply_snapshot_operation(
+                                    &log,
+                                    &mut tip_handler,
+                                    height,
+                                    &operation,
+                                )
+                                .unwrap_or_else(|err| {
+                                    fatal!(
+                                        log,
+                                        "Failed to apply snapshot operation {:?} @{}: {}",
+                                        operation,
+                                        height,
+                                        err
+                                    )
+                                });
+                            }
+                        }
                         TipRequest::TipToCheckpoint { height, sender } => {
                             debug_assert_eq!(tip_state, TipState::Serialized(height));
                             debug_assert!(have_latest_manifest);
@@ -801,6 +858,153 @@ fn serialize_to_tip(
         result?;
     }
 
+    let results = parallel_map(
+        thread_pool,
+        state.canister_snapshots.iter(),
+        |(snapshot_id, snapshot)| {
+            serialize_snapshot_to_tip(log, snapshot_id, snapshot, tip, metrics, lsmt_storage)
+        },
+    );
+
+    for result in results.into_iter() {
+        result?;
+    }
+
+    Ok(())
+}
+
+fn serialize_snapshot_to_tip(
+    log: &ReplicaLogger,
+    snapshot_id: &SnapshotId,
+    canister_snapshot: &CanisterSnapshot,
+    tip: &CheckpointLayout<RwPolicy<TipHandler>>,
+    metrics: &StorageMetrics,
+    lsmt_storage: FlagStatus,
+) -> Result<(), CheckpointError> {
+    let snapshot_layout = tip.snapshot(snapshot_id)?;
+    let execution_snapshot = canister_snapshot.execution_snapshot();
+
+    // Snapshots are immutable, so the Wasm file only needs to be written once.
+    let wasm = snapshot_layout.wasm();
+    if !wasm.raw_path().exists() {
+        match execution_snapshot.wasm_binary.file() {
+            Some(path) => {
+                ic_state_layout::utils::do_copy(log, path, wasm.raw_path()).map_err(|io_err| {
+                    CheckpointError::IoError {
+                        path: path.to_path_buf(),
+                        message: "failed to copy Wasm file".to_string(),
+                        io_err: io_err.to_string(),
+                    }
+                })?;
+            }
+            None => {
+                wasm.serialize(&execution_snapshot.wasm_binary)?;
+            }
+        }
+    }
+
+    let memory_dst = PersistDestination::new(
+        snapshot_layout.vmemory_0(),
+        snapshot_layout.vmemory_0_overlay(tip.height()),
+        lsmt_storage,
+    );
+    let stable_dst = PersistDestination::new(
+        snapshot_layout.stable_memory_blob(),
+        snapshot_layout.stable_memory_overlay(tip.height()),
+        lsmt_storage,
+    );
+    execution_snapshot
+        .wasm_memory
+        .page_map
+        .persist_delta(memory_dst, metrics)?;
+    execution_snapshot
+        .stable_memory
+        .page_map
+        .persist_delta(stable_dst, metrics)?;
+
+    snapshot_layout.snapshot().serialize(
+        CanisterSnapshotBits {
+            snapshot_id: *snapshot_id,
+            canister_id: canister_snapshot.canister_id(),
+            taken_at_timestamp: *canister_snapshot.taken_at_timestamp(),
+            canister_version: canister_snapshot.canister_version(),
+            binary_hash: execution_snapshot.wasm_binary.module_hash().into(),
+            certified_data: canister_snapshot.certified_data().clone(),
+            exported_globals: execution_snapshot.exported_globals.clone(),
+            wasm_memory_size: execution_snapshot.wasm_memory.size,
+            stable_memory_size: execution_snapshot.stable_memory.size,
+        }
+        .into(),
+    )?;
+    Ok(())
+}
+
+/// Copies the files backing the memories of the source of `operation` over the
+/// files of its destination, replacing whatever the destination had on disk.
+fn apply_snapshot_operation(
+    log: &ReplicaLogger,
+    tip_handler: &mut TipHandler,
+    height: Height,
+    operation: &SnapshotOperation,
+) -> Result<(), LayoutError> {
+    let (sources, destinations) = match operation {
+        SnapshotOperation::Backup(canister_id, snapshot_id) => (
+            [
+                PageMapType::WasmMemory(*canister_id),
+                PageMapType::StableMemory(*canister_id),
+            ],
+            [
+                PageMapType::SnapshotWasmMemory(*snapshot_id),
+                PageMapType::SnapshotStableMemory(*snapshot_id),
+            ],
+        ),
+        SnapshotOperation::Restore(canister_id, snapshot_id) => (
+            [
+                PageMapType::SnapshotWasmMemory(*snapshot_id),
+                PageMapType::SnapshotStableMemory(*snapshot_id),
+            ],
+            [
+                PageMapType::WasmMemory(*canister_id),
+                PageMapType::StableMemory(*canister_id),
+            ],
+        ),
+    };
+    for (src, dst) in sources.iter().zip(destinations.iter()) {
+        let src_paths = page_map_paths(tip_handler, height, src)?;
+        let dst_paths = page_map_paths(tip_handler, height, dst)?;
+        delete_pagemap_files(log, &dst_paths.base_file_path, &dst_paths.existing_overlays);
+        let dst_dir = dst_paths
+            .base_file_path
+            .parent()
+            .expect("PageMap files always have a parent directory");
+        let files_to_copy = std::iter::once((
+            src_paths.base_file_path.clone(),
+            dst_paths.base_file_path.clone(),
+        ))
+        .chain(src_paths.existing_overlays.iter().map(|overlay| {
+            (
+                overlay.clone(),
+                dst_dir.join(
+                    overlay
+                        .file_name()
+                        .expect("Overlay files always have a file name"),
+                ),
+            )
+        }));
+        for (from, to) in files_to_copy {
+            // A memory that has never been flushed has no files yet.
+            if !from.exists() {
+                continue;
+            }
+            ic_state_layout::utils::do_copy(log, &from, &to).map_err(|io_err| {
+                LayoutError::IoError {
+                    path: from.clone(),
+                    message: format!("Failed to copy PageMap file to {}", to.display()),
+                    io_err,
+                }
+            })?;
+        }
+    }
     Ok(())
 }
 
@@ -967,6 +1171,9 @@ fn serialize_canister_to_tip(
                 .clone(),
             total_query_stats: canister_state.scheduler_state.total_query_stats.clone(),
             log_visibility: canister_state.system_state.log_visibility,
+            snapshots_memory_usage: canister_state.system_state.snapshots_memory_usage,
+            next_snapshot_id: canister_state.system_state.next_snapshot_id(),
+            canister_log: canister_state.system_state.canister_log.clone(),
         }
         .into(),
     )?;
@@ -3,7 +3,9 @@ pub mod cdiff;
 pub mod chash;
 pub mod convert_ids;
 pub mod decode;
+pub mod export_canister;
 pub mod import_state;
+pub mod inspect;
 pub mod list;
 pub mod manifest;
 pub mod split;
@@ -0,0 +1,264 @@
+//! Exports a single canister from a checkpoint into a self-contained archive.
+//!
+//! The archive is a tarball with the same file names as a canister directory
+//! of a checkpoint, so that it can be unpacked and loaded as a canister state
+//! (e.g. via `StateMachine::import_canister()`). It contains:
+//!
+//!  * `canister.pbuf`: the `CanisterStateBits` (including the certified data);
+//!  * `software.wasm`: the Wasm module;
+//!  * `vmemory_0.bin`: the Wasm heap pages, with all overlays applied;
+//!  * `stable_memory.bin`: the stable memory pages, with all overlays applied.
+//!
+//! Canister queues are not exported.
+
+use ic_replicated_state::page_map::{
+    PageIndex, PageMap, TestPageAllocatorFileDescriptorImpl, PAGE_SIZE,
+};
+use ic_state_layout::{CanisterLayout, CompleteCheckpointLayout, ReadOnly, CANISTER_FILE};
+use ic_types::{CanisterId, Height};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Name of the Wasm module within the archive.
+const WASM_FILE: &str = "software.wasm";
+/// Name of the Wasm heap dump within the archive.
+const VMEMORY_0_FILE: &str = "vmemory_0.bin";
+/// Name of the stable memory dump within the archive.
+const STABLE_MEMORY_FILE: &str = "stable_memory.bin";
+
+/// Reads the contents of a `PageMap` as a contiguous sequence of bytes,
+/// one page at a time.
+struct PageMapReader<'a> {
+    page_map: &'a PageMap,
+    len: usize,
+    offset: usize,
+}
+
+impl<'a> PageMapReader<'a> {
+    fn new(page_map: &'a PageMap) -> Self {
+        Self {
+            page_map,
+            len: page_map.num_host_pages() * PAGE_SIZE,
+            offset: 0,
+        }
+    }
+}
+
+impl Read for PageMapReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.offset >= self.len {
+            return Ok(0);
+        }
+        let page = self
+            .page_map
+            .get_page(PageIndex::new((self.offset / PAGE_SIZE) as u64));
+        let offset_in_page = self.offset % PAGE_SIZE;
+        let n = buf.len().min(PAGE_SIZE - offset_in_page);
+        buf[..n].copy_from_slice(&page[offset_in_page..offset_in_page + n]);
+        self.offset += n;
+        Ok(n)
+    }
+}
+
+/// Appends the file at `path` to the archive under the given `name`.
+fn append_file(archive: &mut tar::Builder<File>, path: &Path, name: &str) -> Result<(), String> {
+    archive
+        .append_path_with_name(path, name)
+        .map_err(|e| format!("failed to archive {}: {}", path.display(), e))
+}
+
+/// Appends the page map backed by `base_file` and `overlays` to the archive
+/// under the given `name`.
+fn append_page_map(
+    archive: &mut tar::Builder<File>,
+    base_file: &Path,
+    overlays: &[PathBuf],
+    name: &str,
+) -> Result<(), String> {
+    let page_map = PageMap::open(
+        base_file,
+        overlays,
+        Height::new(0),
+        Arc::new(TestPageAllocatorFileDescriptorImpl::new()),
+    )
+    .map_err(|e| format!("failed to open page map {}: {}", base_file.display(), e))?;
+    let reader = PageMapReader::new(&page_map);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(reader.len as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    archive
+        .append_data(&mut header, name, reader)
+        .map_err(|e| format!("failed to archive {}: {}", name, e))
+}
+
+/// Writes the Wasm module, memories and `CanisterStateBits` of the canister
+/// in the given canister layout into `archive`.
+fn export_canister_layout(
+    canister_layout: &CanisterLayout<ReadOnly>,
+    archive: &mut tar::Builder<File>,
+) -> Result<(), String> {
+    let canister_state_bits = canister_layout
+        .canister()
+        .deserialize()
+        .map_err(|e| format!("failed to read canister state bits: {}", e))?;
+    append_file(
+        archive,
+        canister_layout.canister().raw_path(),
+        CANISTER_FILE,
+    )?;
+
+    // Canisters without a Wasm module have no code and no memories.
+    if canister_state_bits.execution_state_bits.is_none() {
+        return Ok(());
+    }
+
+    append_file(archive, canister_layout.wasm().raw_path(), WASM_FILE)?;
+    append_page_map(
+        archive,
+        &canister_layout.vmemory_0(),
+        &canister_layout
+            .vmemory_0_overlays()
+            .map_err(|e| e.to_string())?,
+        VMEMORY_0_FILE,
+    )?;
+    append_page_map(
+        archive,
+        &canister_layout.stable_memory_blob(),
+        &canister_layout
+            .stable_memory_overlays()
+            .map_err(|e| e.to_string())?,
+        STABLE_MEMORY_FILE,
+    )
+}
+
+/// Exports the canister with the given ID from the checkpoint at `path` into
+/// an archive at `output`.
+pub fn do_export_canister(
+    path: PathBuf,
+    canister_id: CanisterId,
+    output: PathBuf,
+) -> Result<(), String> {
+    let cp_layout = CompleteCheckpointLayout::new_untracked(path.clone(), Height::new(0))
+        .map_err(|e| format!("failed to create checkpoint layout: {}", e))?;
+    let canister_layout = cp_layout
+        .canister(&canister_id)
+        .map_err(|e| format!("failed to create canister layout: {}", e))?;
+    if !canister_layout.raw_path().is_dir() {
+        return Err(format!(
+            "canister {} not found in checkpoint {}",
+            canister_id,
+            path.display()
+        ));
+    }
+
+    let file = File::create(&output)
+        .map_err(|e| format!("failed to create {}: {}", output.display(), e))?;
+    let mut archive = tar::Builder::new(file);
+    export_canister_layout(&canister_layout, &mut archive)?;
+    archive
+        .finish()
+        .map_err(|e| format!("failed to write {}: {}", output.display(), e))?;
+
+    println!(
+        "Exported canister {} from {} to {}",
+        canister_id,
+        path.display(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_state_machine_tests::{StateMachine, StateMachineBuilder};
+    use ic_types::ingress::WasmResult;
+    use ic_universal_canister::{wasm, UNIVERSAL_CANISTER_WASM};
+
+    #[test]
+    fn page_map_reader_reads_all_pages() {
+        let mut page_map = PageMap::new_for_testing();
+        page_map.update(&[
+            (PageIndex::new(0), &[1; PAGE_SIZE]),
+            (PageIndex::new(2), &[3; PAGE_SIZE]),
+        ]);
+
+        let mut bytes = vec![];
+        PageMapReader::new(&page_map)
+            .read_to_end(&mut bytes)
+            .unwrap();
+
+        let mut expected = vec![1; PAGE_SIZE];
+        expected.extend_from_slice(&[0; PAGE_SIZE]);
+        expected.extend_from_slice(&[3; PAGE_SIZE]);
+        assert_eq!(expected, bytes);
+    }
+
+    #[test]
+    fn exported_canister_can_be_imported_into_state_machine() {
+        let env = StateMachineBuilder::new()
+            .with_checkpoints_enabled(true)
+            .build();
+        let canister_id = env
+            .install_canister(UNIVERSAL_CANISTER_WASM.to_vec(), vec![], None)
+            .unwrap();
+        env.execute_ingress(
+            canister_id,
+            "update",
+            wasm()
+                .set_global_data(b"heap data")
+                .stable_grow(1)
+                .stable_write(42, b"stable data")
+                .reply()
+                .build(),
+        )
+        .unwrap();
+        env.tick();
+
+        let query_memories = |env: &StateMachine| {
+            let heap = env
+                .query(
+                    canister_id,
+                    "query",
+                    wasm().get_global_data().append_and_reply().build(),
+                )
+                .unwrap();
+            let stable = env
+                .query(
+                    canister_id,
+                    "query",
+                    wasm().stable_read(42, 11).append_and_reply().build(),
+                )
+                .unwrap();
+            (heap, stable)
+        };
+        let expected = query_memories(&env);
+        assert_eq!(
+            expected,
+            (
+                WasmResult::Reply(b"heap data".to_vec()),
+                WasmResult::Reply(b"stable data".to_vec())
+            )
+        );
+
+        let state_layout = env.state_manager.state_layout();
+        let height = *state_layout.checkpoint_heights().unwrap().last().unwrap();
+        let checkpoint_path = state_layout
+            .checkpoint(height)
+            .unwrap()
+            .raw_path()
+            .to_path_buf();
+        let output_dir = tempfile::tempdir().unwrap();
+        let archive_path = output_dir.path().join("canister.tar");
+        do_export_canister(checkpoint_path, canister_id, archive_path.clone()).unwrap();
+
+        let other_env = StateMachineBuilder::new().build();
+        other_env.import_canister(&archive_path, canister_id);
+        assert_eq!(query_memories(&other_env), expected);
+    }
+}
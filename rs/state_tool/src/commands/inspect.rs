@@ -0,0 +1,289 @@
+//! Loads a checkpoint and answers structured queries about it, printing the
+//! results as JSON.
+
+use clap::{Subcommand, ValueEnum};
+use ic_registry_subnet_type::SubnetType;
+use ic_replicated_state::{
+    page_map::TestPageAllocatorFileDescriptorImpl, CanisterQueues, CanisterState, ReplicatedState,
+};
+use ic_state_layout::CompleteCheckpointLayout;
+use ic_state_manager::{checkpoint::load_checkpoint, CheckpointMetrics};
+use ic_types::{ingress::IngressStatus, CountBytes, Height};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Queries supported by the `inspect` command.
+#[derive(Subcommand, Debug)]
+pub enum Query {
+    /// Lists all canisters with their status, cycles balance, controllers
+    /// and memory usage.
+    #[clap(name = "canisters")]
+    Canisters,
+
+    /// Lists the message counts and sizes of all canister queues, the subnet
+    /// queues and all streams.
+    #[clap(name = "queues")]
+    Queues,
+
+    /// Lists ingress history entries, optionally filtered by status.
+    #[clap(name = "ingress")]
+    Ingress {
+        /// Only list entries with the given status.
+        #[clap(long = "status", value_enum)]
+        status: Option<IngressStatusFilter>,
+    },
+}
+
+/// Ingress statuses that the `ingress` query can filter by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum IngressStatusFilter {
+    Received,
+    Processing,
+    Replied,
+    Rejected,
+    Done,
+    Unknown,
+}
+
+impl IngressStatusFilter {
+    /// Returns the status name, as reported by `IngressStatus::as_str()`.
+    fn as_str(&self) -> &'static str {
+        match self {
+            IngressStatusFilter::Received => "received",
+            IngressStatusFilter::Processing => "processing",
+            IngressStatusFilter::Replied => "replied",
+            IngressStatusFilter::Rejected => "rejected",
+            IngressStatusFilter::Done => "done",
+            IngressStatusFilter::Unknown => "unknown",
+        }
+    }
+}
+
+/// Summary of a single canister.
+#[derive(Serialize)]
+struct CanisterInfo {
+    canister_id: String,
+    status: String,
+    cycles_balance: u128,
+    controllers: Vec<String>,
+    memory_usage_bytes: u64,
+    message_memory_usage_bytes: u64,
+    module_hash: Option<String>,
+}
+
+impl From<&CanisterState> for CanisterInfo {
+    fn from(canister: &CanisterState) -> Self {
+        Self {
+            canister_id: canister.canister_id().to_string(),
+            status: canister.status().to_string(),
+            cycles_balance: canister.system_state.balance().get(),
+            controllers: canister
+                .system_state
+                .controllers
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            memory_usage_bytes: canister.memory_usage().get(),
+            message_memory_usage_bytes: canister.message_memory_usage().get(),
+            module_hash: canister.execution_state.as_ref().map(|execution_state| {
+                hex::encode(execution_state.wasm_binary.binary.module_hash())
+            }),
+        }
+    }
+}
+
+/// Message counts and sizes of a set of canister queues.
+#[derive(Serialize)]
+struct QueuesInfo {
+    ingress_messages: usize,
+    input_messages: usize,
+    output_messages: usize,
+    ingress_size_bytes: usize,
+    input_size_bytes: usize,
+    memory_usage_bytes: usize,
+}
+
+impl From<&CanisterQueues> for QueuesInfo {
+    fn from(queues: &CanisterQueues) -> Self {
+        Self {
+            ingress_messages: queues.ingress_queue_message_count(),
+            input_messages: queues.input_queues_message_count(),
+            output_messages: queues.output_queues_message_count(),
+            ingress_size_bytes: queues.ingress_queue_size_bytes(),
+            input_size_bytes: queues.input_queues_size_bytes(),
+            memory_usage_bytes: queues.memory_usage(),
+        }
+    }
+}
+
+/// Queues of a single canister.
+#[derive(Serialize)]
+struct CanisterQueuesInfo {
+    canister_id: String,
+    #[serde(flatten)]
+    queues: QueuesInfo,
+}
+
+/// Summary of an outgoing stream.
+#[derive(Serialize)]
+struct StreamInfo {
+    destination: String,
+    messages_begin: u64,
+    messages_end: u64,
+    signals_end: u64,
+    reject_signals: usize,
+    size_bytes: usize,
+}
+
+/// Queues and streams of the whole subnet.
+#[derive(Serialize)]
+struct StateQueuesInfo {
+    canisters: Vec<CanisterQueuesInfo>,
+    subnet_queues: QueuesInfo,
+    streams: Vec<StreamInfo>,
+}
+
+/// A single ingress history entry.
+#[derive(Serialize)]
+struct IngressInfo {
+    message_id: String,
+    status: &'static str,
+    receiver: Option<String>,
+    user_id: Option<String>,
+    time_nanos: Option<u64>,
+}
+
+fn canisters(state: &ReplicatedState) -> Vec<CanisterInfo> {
+    state.canisters_iter().map(CanisterInfo::from).collect()
+}
+
+fn queues(state: &ReplicatedState) -> StateQueuesInfo {
+    StateQueuesInfo {
+        canisters: state
+            .canisters_iter()
+            .map(|canister| CanisterQueuesInfo {
+                canister_id: canister.canister_id().to_string(),
+                queues: canister.system_state.queues().into(),
+            })
+            .collect(),
+        subnet_queues: state.subnet_queues().into(),
+        streams: state
+            .subnets_with_available_streams()
+            .into_iter()
+            .filter_map(|subnet_id| {
+                let stream = state.get_stream(&subnet_id)?;
+                Some(StreamInfo {
+                    destination: subnet_id.to_string(),
+                    messages_begin: stream.messages_begin().get(),
+                    messages_end: stream.messages_end().get(),
+                    signals_end: stream.signals_end().get(),
+                    reject_signals: stream.reject_signals().len(),
+                    size_bytes: stream.count_bytes(),
+                })
+            })
+            .collect(),
+    }
+}
+
+fn ingress(state: &ReplicatedState, status: Option<IngressStatusFilter>) -> Vec<IngressInfo> {
+    state
+        .get_ingress_history()
+        .statuses()
+        .filter(|(_, ingress_status)| {
+            status.map_or(true, |s| ingress_status.as_str() == s.as_str())
+        })
+        .map(|(message_id, ingress_status)| {
+            let time_nanos = match ingress_status {
+                IngressStatus::Known { time, .. } => Some(time.as_nanos_since_unix_epoch()),
+                IngressStatus::Unknown => None,
+            };
+            IngressInfo {
+                message_id: message_id.to_string(),
+                status: ingress_status.as_str(),
+                receiver: ingress_status.receiver().map(|id| id.to_string()),
+                user_id: ingress_status.user_id().map(|id| id.to_string()),
+                time_nanos,
+            }
+        })
+        .collect()
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("failed to serialize to JSON: {}", e))
+}
+
+/// Loads the checkpoint at `path` of a subnet of type `subnet_type` and prints
+/// the answer to `query` as JSON.
+pub fn do_inspect(path: PathBuf, subnet_type: SubnetType, query: Query) -> Result<(), String> {
+    let cp_layout = CompleteCheckpointLayout::new_untracked(path.clone(), Height::new(0))
+        .map_err(|e| format!("failed to create checkpoint layout: {}", e))?;
+
+    let dummy_metrics_registry = ic_metrics::MetricsRegistry::new();
+    let dummy_metrics = CheckpointMetrics::new(&dummy_metrics_registry, crate::commands::logger());
+
+    let state = load_checkpoint(
+        &cp_layout,
+        subnet_type,
+        &dummy_metrics,
+        None,
+        Arc::new(TestPageAllocatorFileDescriptorImpl::new()),
+    )
+    .map_err(|e| format!("failed to load checkpoint at {}: {}", path.display(), e))?;
+
+    let json = match query {
+        Query::Canisters => to_json(&canisters(&state))?,
+        Query::Queues => to_json(&queues(&state))?,
+        Query::Ingress { status } => to_json(&ingress(&state, status))?,
+    };
+    println!("{}", json);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_types::{
+        ingress::{IngressState, WasmResult},
+        messages::MessageId,
+        CanisterId, PrincipalId, SubnetId, Time, UserId,
+    };
+
+    #[test]
+    fn ingress_filters_by_status() {
+        let subnet_id = SubnetId::from(PrincipalId::new_subnet_test_id(1));
+        let mut state = ReplicatedState::new(subnet_id, SubnetType::Application);
+        let receiver = CanisterId::from_u64(1).get();
+        let user_id = UserId::from(PrincipalId::new_user_test_id(2));
+        for (i, ingress_state) in [
+            IngressState::Received,
+            IngressState::Completed(WasmResult::Reply(vec![])),
+            IngressState::Processing,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            state.set_ingress_status(
+                MessageId::from([i as u8; 32]),
+                IngressStatus::Known {
+                    receiver,
+                    user_id,
+                    time: Time::from_nanos_since_unix_epoch(7),
+                    state: ingress_state,
+                },
+                u64::MAX.into(),
+            );
+        }
+
+        assert_eq!(3, ingress(&state, None).len());
+
+        let replied = ingress(&state, Some(IngressStatusFilter::Replied));
+        assert_eq!(1, replied.len());
+        assert_eq!(MessageId::from([1; 32]).to_string(), replied[0].message_id);
+        assert_eq!(Some(receiver.to_string()), replied[0].receiver);
+        assert_eq!(Some(7), replied[0].time_nanos);
+
+        assert!(ingress(&state, Some(IngressStatusFilter::Unknown)).is_empty());
+    }
+}
@@ -2,13 +2,14 @@
 //!
 //! A command-line tool to manage Internet Computer replicated states (decode
 //! persisted state files, diff checkpoints, compute partial state hashes and
-//! checkpoint manifests, import state trees).
+//! checkpoint manifests, import state trees, export canisters, inspect
+//! checkpoints).
 
 use clap::Parser;
 use ic_registry_routing_table::CanisterIdRange;
 use ic_registry_subnet_type::SubnetType;
 use ic_state_tool::commands;
-use ic_types::{PrincipalId, Time};
+use ic_types::{CanisterId, PrincipalId, Time};
 use std::path::PathBuf;
 
 /// Supported `state_tool` commands and their arguments.
@@ -43,6 +44,21 @@ enum Opt {
         height: u64,
     },
 
+    /// Loads a checkpoint and prints the answer to a structured query as JSON.
+    #[clap(name = "inspect")]
+    Inspect {
+        /// Path to a checkpoint.
+        #[clap(long = "state")]
+        path: PathBuf,
+
+        /// Type of the subnet the checkpoint belongs to.
+        #[clap(long, default_value = "application")]
+        subnet_type: SubnetType,
+
+        #[clap(subcommand)]
+        query: commands::inspect::Query,
+    },
+
     /// Computes manifest of a checkpoint.
     #[clap(name = "manifest")]
     Manifest {
@@ -76,6 +92,23 @@ enum Opt {
         file: PathBuf,
     },
 
+    /// Exports a single canister from a checkpoint into a self-contained archive
+    /// (Wasm module, heap and stable memory, and canister state bits).
+    #[clap(name = "export_canister")]
+    ExportCanister {
+        /// Path to a checkpoint.
+        #[clap(long = "state")]
+        path: PathBuf,
+
+        /// ID of the canister to export.
+        #[clap(long = "canister_id")]
+        canister_id: PrincipalId,
+
+        /// Path to the archive to write.
+        #[clap(long = "output")]
+        output: PathBuf,
+    },
+
     /// Converts textual principal representation to hex.
     #[clap(name = "canister_id_to_hex")]
     CanisterIdToHex {
@@ -159,10 +192,24 @@ fn main() {
             config,
             height,
         } => commands::import_state::do_import(state, config, height),
+        Opt::Inspect {
+            path,
+            subnet_type,
+            query,
+        } => commands::inspect::do_inspect(path, subnet_type, query),
         Opt::Manifest { path } => commands::manifest::do_compute_manifest(path),
         Opt::VerifyManifest { file } => commands::verify_manifest::do_verify_manifest(&file),
         Opt::ListStates { config } => commands::list::do_list(config),
         Opt::Decode { file } => commands::decode::do_decode(file),
+        Opt::ExportCanister {
+            path,
+            canister_id,
+            output,
+        } => commands::export_canister::do_export_canister(
+            path,
+            CanisterId::unchecked_from_principal(canister_id),
+            output,
+        ),
         Opt::CanisterIdToHex { canister_id } => {
             commands::convert_ids::do_canister_id_to_hex(canister_id)
         }
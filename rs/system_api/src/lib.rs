@@ -8,6 +8,7 @@ use ic_base_types::PrincipalIdBlobParseError;
 use ic_config::flag_status::FlagStatus;
 use ic_cycles_account_manager::ResourceSaturation;
 use ic_error_types::RejectCode;
+use ic_ic00_types::{EcdsaCurve, EcdsaKeyId};
 use ic_interfaces::execution_environment::{
     ExecutionMode,
     HypervisorError::{self, *},
@@ -23,6 +24,7 @@ use ic_replicated_state::{
 };
 use ic_sys::PageBytes;
 use ic_types::{
+    canister_log::CanisterLog,
     ingress::WasmResult,
     messages::{CallContextId, RejectContext, Request, MAX_INTER_CANISTER_PAYLOAD_IN_BYTES},
     methods::{SystemMethod, WasmClosure},
@@ -43,6 +45,11 @@ pub const MULTIPLIER_MAX_SIZE_LOCAL_SUBNET: u64 = 5;
 const MAX_NON_REPLICATED_QUERY_REPLY_SIZE: NumBytes = NumBytes::new(3 << 20);
 const CERTIFIED_DATA_MAX_LENGTH: u32 = 32;
 
+/// Return codes of `ic0.cost_sign_with_ecdsa`.
+pub const COST_SIGN_WITH_ECDSA_SUCCESS: u32 = 0;
+pub const COST_SIGN_WITH_ECDSA_INVALID_CURVE: u32 = 1;
+pub const COST_SIGN_WITH_ECDSA_UNKNOWN_KEY: u32 = 2;
+
 // Enables tracing of system calls for local debugging.
 const TRACE_SYSCALLS: bool = false;
 
@@ -841,6 +848,10 @@ pub struct SystemApiImpl {
 
     /// How many times each tracked System API call was invoked.
     call_counters: SystemApiCallCounters,
+
+    /// Log records produced by the canister during the current message
+    /// execution. They are appended to the canister's log after execution.
+    canister_log: CanisterLog,
 }
 
 impl SystemApiImpl {
@@ -883,6 +894,7 @@ impl SystemApiImpl {
             current_slice_instruction_limit: i64::try_from(slice_limit).unwrap_or(i64::MAX),
             instructions_executed_before_current_slice: 0,
             call_counters: SystemApiCallCounters::default(),
+            canister_log: CanisterLog::default(),
         }
     }
 
@@ -1334,6 +1346,40 @@ impl SystemApiImpl {
     pub fn call_counters(&self) -> SystemApiCallCounters {
         self.call_counters.clone()
     }
+
+    /// Appends the given message to the canister log of the current
+    /// execution. Invalid memory ranges are recorded as a placeholder message
+    /// instead of failing, because logging must never trap.
+    pub fn save_log_message(&mut self, src: u32, size: u32, heap: &[u8]) {
+        let content = match valid_subslice("save_log_message", src, size, heap) {
+            Ok(bytes) => bytes.to_vec(),
+            Err(_) => b"(debug message out of memory bounds)".to_vec(),
+        };
+        self.add_log_record(content);
+    }
+
+    fn add_log_record(&mut self, content: Vec<u8>) {
+        let time = match &self.api_type {
+            ApiType::Start { time }
+            | ApiType::Init { time, .. }
+            | ApiType::SystemTask { time, .. }
+            | ApiType::Update { time, .. }
+            | ApiType::Cleanup { time, .. }
+            | ApiType::NonReplicatedQuery { time, .. }
+            | ApiType::ReplicatedQuery { time, .. }
+            | ApiType::PreUpgrade { time, .. }
+            | ApiType::ReplyCallback { time, .. }
+            | ApiType::RejectCallback { time, .. }
+            | ApiType::InspectMessage { time, .. } => *time,
+        };
+        self.canister_log
+            .add_record(time.as_nanos_since_unix_epoch(), content);
+    }
+
+    /// Returns the log records produced during the current execution.
+    pub fn take_canister_log(&mut self) -> CanisterLog {
+        std::mem::take(&mut self.canister_log)
+    }
 }
 
 impl SystemApi for SystemApiImpl {
@@ -2008,6 +2054,50 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
+    fn ic0_call_with_best_effort_response(&mut self, timeout_seconds: u32) -> HypervisorResult<()> {
+        let result = match &mut self.api_type {
+            ApiType::Start { .. }
+            | ApiType::Init { .. }
+            | ApiType::ReplicatedQuery { .. }
+            | ApiType::NonReplicatedQuery {
+                query_kind: NonReplicatedQueryKind::Pure,
+                ..
+            }
+            | ApiType::Cleanup { .. }
+            | ApiType::PreUpgrade { .. }
+            | ApiType::InspectMessage { .. } => {
+                Err(self.error_for("ic0_call_with_best_effort_response"))
+            }
+            ApiType::Update {
+                outgoing_request, ..
+            }
+            | ApiType::NonReplicatedQuery {
+                query_kind:
+                    NonReplicatedQueryKind::Stateful {
+                        outgoing_request, ..
+                    },
+                ..
+            }
+            | ApiType::SystemTask {
+                outgoing_request, ..
+            }
+            | ApiType::ReplyCallback {
+                outgoing_request, ..
+            }
+            | ApiType::RejectCallback {
+                outgoing_request, ..
+            } => match outgoing_request {
+                None => Err(HypervisorError::ContractViolation(
+                    "ic0.call_with_best_effort_response called when no call is under construction."
+                        .to_string(),
+                )),
+                Some(request) => request.set_timeout(timeout_seconds),
+            },
+        };
+        trace_syscall!(self, CallWithBestEffortResponse, result, timeout_seconds);
+        result
+    }
+
     fn ic0_call_cycles_add(&mut self, amount: u64) -> HypervisorResult<()> {
         let result = self.ic0_call_cycles_add_helper("ic0_call_cycles_add", Cycles::from(amount));
         trace_syscall!(self, CallCyclesAdd, result, amount);
@@ -2043,26 +2133,31 @@ impl SystemApi for SystemApiImpl {
             | ApiType::PreUpgrade { .. }
             | ApiType::InspectMessage { .. } => Err(self.error_for("ic0_call_perform")),
             ApiType::Update {
+                time,
                 call_context_id,
                 outgoing_request,
                 ..
             }
             | ApiType::SystemTask {
+                time,
                 call_context_id,
                 outgoing_request,
                 ..
             }
             | ApiType::ReplyCallback {
+                time,
                 call_context_id,
                 outgoing_request,
                 ..
             }
             | ApiType::RejectCallback {
+                time,
                 call_context_id,
                 outgoing_request,
                 ..
             }
             | ApiType::NonReplicatedQuery {
+                time,
                 query_kind:
                     NonReplicatedQueryKind::Stateful {
                         call_context_id,
@@ -2080,6 +2175,7 @@ impl SystemApi for SystemApiImpl {
                     req_in_prep,
                     *call_context_id,
                     &mut self.sandbox_safe_system_state,
+                    *time,
                     &self.log,
                 )?;
 
@@ -2826,9 +2922,10 @@ impl SystemApi for SystemApiImpl {
         result
     }
 
-    fn ic0_debug_print(&self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()> {
+    fn ic0_debug_print(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()> {
         const MAX_DEBUG_MESSAGE_SIZE: u32 = 32 * 1024;
         let size = size.min(MAX_DEBUG_MESSAGE_SIZE);
+        self.save_log_message(src, size, heap);
         let msg = match valid_subslice("ic0.debug_print", src, size, heap) {
             Ok(bytes) => String::from_utf8_lossy(bytes).to_string(),
             Err(_) => {
@@ -2858,13 +2955,14 @@ impl SystemApi for SystemApiImpl {
         Ok(())
     }
 
-    fn ic0_trap(&self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()> {
+    fn ic0_trap(&mut self, src: u32, size: u32, heap: &[u8]) -> HypervisorResult<()> {
         const MAX_ERROR_MESSAGE_SIZE: u32 = 16 * 1024;
         let size = size.min(MAX_ERROR_MESSAGE_SIZE);
         let result = {
             let msg = valid_subslice("trap", src, size, heap)
                 .map(|bytes| String::from_utf8_lossy(bytes).to_string())
                 .unwrap_or_else(|_| "(trap message out of memory bounds)".to_string());
+            self.add_log_record(format!("[TRAP]: {}", msg).into_bytes());
             CalledTrap(msg)
         };
         trace_syscall!(self, Trap, src, size, summarize(heap, src, size));
@@ -2964,6 +3062,80 @@ impl SystemApi for SystemApiImpl {
         trace_syscall!(self, CyclesBurn128, result, amount);
         result
     }
+
+    fn ic0_cost_call(
+        &self,
+        method_name_size: u64,
+        payload_size: u64,
+        dst: u32,
+        heap: &mut [u8],
+    ) -> HypervisorResult<()> {
+        let size = NumBytes::from(method_name_size.saturating_add(payload_size));
+        let cost = self.sandbox_safe_system_state.call_cost(size);
+        let result = copy_cycles_to_heap(cost, dst, heap, "ic0_cost_call");
+        trace_syscall!(self, CostCall, result, method_name_size, payload_size, cost);
+        result
+    }
+
+    fn ic0_cost_create_canister(&self, dst: u32, heap: &mut [u8]) -> HypervisorResult<()> {
+        let cost = self.sandbox_safe_system_state.create_canister_cost();
+        let result = copy_cycles_to_heap(cost, dst, heap, "ic0_cost_create_canister");
+        trace_syscall!(self, CostCreateCanister, result, cost);
+        result
+    }
+
+    fn ic0_cost_http_request(
+        &self,
+        request_size: u64,
+        max_res_bytes: u64,
+        dst: u32,
+        heap: &mut [u8],
+    ) -> HypervisorResult<()> {
+        let cost = self.sandbox_safe_system_state.http_request_cost(
+            NumBytes::from(request_size),
+            Some(NumBytes::from(max_res_bytes)),
+        );
+        let result = copy_cycles_to_heap(cost, dst, heap, "ic0_cost_http_request");
+        trace_syscall!(
+            self,
+            CostHttpRequest,
+            result,
+            request_size,
+            max_res_bytes,
+            cost
+        );
+        result
+    }
+
+    fn ic0_cost_sign_with_ecdsa(
+        &self,
+        src: u32,
+        size: u32,
+        curve: u32,
+        dst: u32,
+        heap: &mut [u8],
+    ) -> HypervisorResult<u32> {
+        let curve = match curve {
+            0 => EcdsaCurve::Secp256k1,
+            _ => {
+                let result = Ok(COST_SIGN_WITH_ECDSA_INVALID_CURVE);
+                trace_syscall!(self, CostSignWithEcdsa, result, src, size, curve, dst);
+                return result;
+            }
+        };
+        let name = valid_subslice("ic0.cost_sign_with_ecdsa", src, size, heap)?;
+        let name = String::from_utf8(name.to_vec()).map_err(|_| {
+            ContractViolation("ic0.cost_sign_with_ecdsa: key name is not valid UTF-8".to_string())
+        })?;
+        let key_id = EcdsaKeyId { curve, name };
+        let result = match self.sandbox_safe_system_state.sign_with_ecdsa_cost(&key_id) {
+            Some(cost) => copy_cycles_to_heap(cost, dst, heap, "ic0_cost_sign_with_ecdsa")
+                .map(|()| COST_SIGN_WITH_ECDSA_SUCCESS),
+            None => Ok(COST_SIGN_WITH_ECDSA_UNKNOWN_KEY),
+        };
+        trace_syscall!(self, CostSignWithEcdsa, result, src, size, key_id, dst);
+        result
+    }
 }
 
 /// The default implementation of the `OutOfInstructionHandler` trait.
@@ -2,13 +2,18 @@ use crate::{sandbox_safe_system_state::SandboxSafeSystemState, valid_subslice};
 use ic_interfaces::execution_environment::{HypervisorError, HypervisorResult};
 use ic_logger::ReplicaLogger;
 use ic_types::{
-    messages::{CallContextId, Request},
+    messages::{CallContextId, Request, NO_DEADLINE},
     methods::{Callback, WasmClosure},
-    CanisterId, Cycles, NumBytes, PrincipalId,
+    CanisterId, CoarseTime, Cycles, NumBytes, PrincipalId, Time,
 };
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 
+/// The maximum timeout (in seconds) that a canister may specify for a
+/// best-effort call via `ic0.call_with_best_effort_response`. Larger values
+/// are silently bounded to this limit.
+pub const MAX_CALL_TIMEOUT_SECONDS: u32 = 300;
+
 /// Represents an under construction `Request`.
 ///
 /// The main differences from a `Request` are:
@@ -47,6 +52,9 @@ pub struct RequestInPrep {
     /// them up creating tricky bugs. Storing this an integer means that the two
     /// limits are stored as different types and are more difficult to mix up.
     multiplier_max_size_local_subnet: u64,
+    /// The timeout (in seconds) set via `ic0.call_with_best_effort_response`.
+    /// `None` for guaranteed response calls.
+    timeout_seconds: Option<u32>,
 }
 
 impl RequestInPrep {
@@ -110,6 +118,7 @@ impl RequestInPrep {
             method_payload: Vec::new(),
             max_size_remote_subnet,
             multiplier_max_size_local_subnet,
+            timeout_seconds: None,
         })
     }
 
@@ -125,6 +134,18 @@ impl RequestInPrep {
         }
     }
 
+    pub(crate) fn set_timeout(&mut self, timeout_seconds: u32) -> HypervisorResult<()> {
+        if self.timeout_seconds.is_some() {
+            Err(HypervisorError::ContractViolation(
+                "ic0.call_with_best_effort_response can be called at most once between `ic0.call_new` and `ic0.call_perform`"
+                    .to_string(),
+            ))
+        } else {
+            self.timeout_seconds = Some(timeout_seconds.min(MAX_CALL_TIMEOUT_SECONDS));
+            Ok(())
+        }
+    }
+
     pub(crate) fn take_cycles(self) -> Cycles {
         self.cycles
     }
@@ -178,9 +199,11 @@ pub(crate) fn into_request(
         method_payload,
         max_size_remote_subnet,
         multiplier_max_size_local_subnet,
+        timeout_seconds,
     }: RequestInPrep,
     call_context_id: CallContextId,
     sandbox_safe_system_state: &mut SandboxSafeSystemState,
+    time: Time,
     _logger: &ReplicaLogger,
 ) -> HypervisorResult<RequestWithPrepayment> {
     let destination_canister = CanisterId::unchecked_from_principal(callee);
@@ -203,6 +226,12 @@ pub(crate) fn into_request(
     let prepayment_for_response_transmission =
         sandbox_safe_system_state.prepayment_for_response_transmission();
 
+    // Best-effort calls expire `timeout_seconds` after the current time.
+    let deadline = match timeout_seconds {
+        Some(timeout_seconds) => CoarseTime::floor(time).saturating_add_secs(timeout_seconds),
+        None => NO_DEADLINE,
+    };
+
     let callback_id = sandbox_safe_system_state.register_callback(Callback::new(
         call_context_id,
         sender,
@@ -213,6 +242,7 @@ pub(crate) fn into_request(
         on_reply,
         on_reject,
         on_cleanup,
+        deadline,
     ))?;
 
     let req = Request {
@@ -223,6 +253,7 @@ pub(crate) fn into_request(
         sender_reply_callback: callback_id,
         payment: cycles,
         metadata: Some(sandbox_safe_system_state.request_metadata.clone()),
+        deadline,
     };
     // We cannot call `Request::payload_size_bytes()` before constructing the
     // request, so ensure our separate calculation matches the actual size.
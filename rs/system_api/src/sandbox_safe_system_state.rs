@@ -8,7 +8,8 @@ use ic_cycles_account_manager::{
 };
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
-    CreateCanisterArgs, InstallChunkedCodeArgs, InstallCodeArgsV2, Method as Ic00Method, Payload,
+    CreateCanisterArgs, EcdsaKeyId, InstallChunkedCodeArgs, InstallCodeArgsV2,
+    LoadCanisterSnapshotArgs, Method as Ic00Method, Payload,
     ProvisionalCreateCanisterWithCyclesArgs, UninstallCodeArgs, UpdateSettingsArgs, IC_00,
 };
 use ic_interfaces::execution_environment::{HypervisorError, HypervisorResult};
@@ -223,6 +224,8 @@ impl SystemStateChanges {
                 ProvisionalCreateCanisterWithCyclesArgs::decode(payload)
                     .map(|record| record.get_sender_canister_version())
             }
+            Ok(Ic00Method::LoadCanisterSnapshot) => LoadCanisterSnapshotArgs::decode(payload)
+                .map(|record| record.get_sender_canister_version()),
             Ok(Ic00Method::SignWithECDSA)
             | Ok(Ic00Method::CanisterStatus)
             | Ok(Ic00Method::CanisterInfo)
@@ -250,8 +253,7 @@ impl SystemStateChanges {
             | Ok(Ic00Method::ClearChunkStore)
             | Ok(Ic00Method::TakeCanisterSnapshot)
             | Ok(Ic00Method::ListCanisterSnapshots)
-            | Ok(Ic00Method::DeleteCanisterSnapshot)
-            | Ok(Ic00Method::LoadCanisterSnapshot) => Ok(None),
+            | Ok(Ic00Method::DeleteCanisterSnapshot) => Ok(None),
             Err(_) => Err(UserError::new(
                 ErrorCode::CanisterMethodNotFound,
                 format!("Management canister has no method '{}'", msg.method_name),
@@ -310,7 +312,7 @@ impl SystemStateChanges {
                 })?;
                 if (*amount_taken).get() > LOG_CANISTER_OPERATION_CYCLES_THRESHOLD {
                     match call_context.call_origin() {
-                        CallOrigin::CanisterUpdate(origin_canister_id, _)
+                        CallOrigin::CanisterUpdate(origin_canister_id, _, _)
                         | CallOrigin::CanisterQuery(origin_canister_id, _) => info!(
                             logger,
                             "Canister {} accepted {} cycles from canister {}.",
@@ -538,6 +540,10 @@ pub struct SandboxSafeSystemState {
     pub(super) status: CanisterStatusView,
     pub(super) subnet_type: SubnetType,
     pub(super) subnet_size: usize,
+    nns_subnet_id: SubnetId,
+    /// The size of the subnet that signs with each ECDSA key, used to price
+    /// `sign_with_ecdsa` the same way as the signing subnet does.
+    ecdsa_signing_subnet_sizes: BTreeMap<EcdsaKeyId, usize>,
     dirty_page_overhead: NumInstructions,
     freeze_threshold: NumSeconds,
     memory_allocation: MemoryAllocation,
@@ -580,6 +586,8 @@ impl SandboxSafeSystemState {
         ic00_available_request_slots: usize,
         ic00_aliases: BTreeSet<CanisterId>,
         subnet_size: usize,
+        nns_subnet_id: SubnetId,
+        ecdsa_signing_subnet_sizes: BTreeMap<EcdsaKeyId, usize>,
         dirty_page_overhead: NumInstructions,
         global_timer: CanisterTimer,
         canister_version: u64,
@@ -591,6 +599,8 @@ impl SandboxSafeSystemState {
             status,
             subnet_type: cycles_account_manager.subnet_type(),
             subnet_size,
+            nns_subnet_id,
+            ecdsa_signing_subnet_sizes,
             dirty_page_overhead,
             freeze_threshold,
             memory_allocation,
@@ -658,6 +668,16 @@ impl SandboxSafeSystemState {
         let subnet_size = network_topology
             .get_subnet_size(&cycles_account_manager.get_subnet_id())
             .unwrap_or(SMALL_APP_SUBNET_MAX_SIZE);
+        // Signing requests are routed to the first subnet holding the key, see
+        // `routing::route_ecdsa_message`.
+        let ecdsa_signing_subnet_sizes = network_topology
+            .ecdsa_signing_subnets
+            .iter()
+            .filter_map(|(key_id, subnets)| {
+                let subnet_size = network_topology.get_subnet_size(subnets.first()?)?;
+                Some((key_id.clone(), subnet_size))
+            })
+            .collect();
 
         Self::new_internal(
             system_state.canister_id,
@@ -677,6 +697,8 @@ impl SandboxSafeSystemState {
             ic00_available_request_slots,
             ic00_aliases,
             subnet_size,
+            network_topology.nns_subnet_id,
+            ecdsa_signing_subnet_sizes,
             dirty_page_overhead,
             system_state.global_timer,
             system_state.canister_version,
@@ -882,6 +904,47 @@ impl SandboxSafeSystemState {
             .prepayment_for_response_transmission(self.subnet_size)
     }
 
+    /// The amount of cycles that `call_perform` withdraws for a call with the
+    /// given payload size (including the method name).
+    pub(super) fn call_cost(&self, payload_size: NumBytes) -> Cycles {
+        self.cycles_account_manager
+            .xnet_call_total_fee(payload_size, self.subnet_size)
+            + self.prepayment_for_response_transmission()
+            + self.prepayment_for_response_execution()
+    }
+
+    pub(super) fn create_canister_cost(&self) -> Cycles {
+        self.cycles_account_manager
+            .canister_creation_fee(self.subnet_size)
+    }
+
+    pub(super) fn http_request_cost(
+        &self,
+        request_size: NumBytes,
+        max_response_bytes: Option<NumBytes>,
+    ) -> Cycles {
+        self.cycles_account_manager.http_request_fee(
+            request_size,
+            max_response_bytes,
+            self.subnet_size,
+        )
+    }
+
+    /// Returns the fee for signing with the given key, computed for the size
+    /// of the subnet that signs with it, or `None` if no subnet holds the key.
+    pub(super) fn sign_with_ecdsa_cost(&self, key_id: &EcdsaKeyId) -> Option<Cycles> {
+        let signing_subnet_size = *self.ecdsa_signing_subnet_sizes.get(key_id)?;
+        // Signing requests from the NNS subnet are not charged for, see
+        // `ExecutionEnvironment::sign_with_ecdsa`.
+        if self.cycles_account_manager.get_subnet_id() == self.nns_subnet_id {
+            return Some(Cycles::zero());
+        }
+        Some(
+            self.cycles_account_manager
+                .ecdsa_signature_fee(signing_subnet_size),
+        )
+    }
+
     pub(super) fn withdraw_cycles_for_transfer(
         &mut self,
         canister_current_memory_usage: NumBytes,
@@ -22,7 +22,7 @@ use ic_test_utilities::{
 };
 use ic_test_utilities_time::mock_time;
 use ic_types::{
-    messages::{CallContextId, CallbackId, RejectContext, RequestMetadata},
+    messages::{CallContextId, CallbackId, RejectContext, RequestMetadata, NO_DEADLINE},
     methods::SystemMethod,
     ComputeAllocation, Cycles, MemoryAllocation, NumInstructions, PrincipalId, Time,
 };
@@ -128,10 +128,26 @@ pub fn get_system_api(
     system_state: &SystemState,
     cycles_account_manager: CyclesAccountManager,
 ) -> SystemApiImpl {
-    let sandbox_safe_system_state = SandboxSafeSystemState::new(
+    get_system_api_with_network_topology(
+        api_type,
         system_state,
         cycles_account_manager,
         &NetworkTopology::default(),
+    )
+}
+
+// Not used in all test crates
+#[allow(dead_code)]
+pub fn get_system_api_with_network_topology(
+    api_type: ApiType,
+    system_state: &SystemState,
+    cycles_account_manager: CyclesAccountManager,
+    network_topology: &NetworkTopology,
+) -> SystemApiImpl {
+    let sandbox_safe_system_state = SandboxSafeSystemState::new(
+        system_state,
+        cycles_account_manager,
+        network_topology,
         SchedulerConfig::application_subnet().dirty_page_overhead,
         execution_parameters().compute_allocation,
         RequestMetadata::new(0, mock_time()),
@@ -163,7 +179,7 @@ pub fn get_system_state() -> SystemState {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(50),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
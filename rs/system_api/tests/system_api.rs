@@ -1,9 +1,10 @@
-use ic_base_types::{NumSeconds, PrincipalIdBlobParseError};
+use ic_base_types::{NumSeconds, PrincipalIdBlobParseError, SubnetId};
 use ic_config::{
     embedders::Config as EmbeddersConfig, flag_status::FlagStatus, subnet_config::SchedulerConfig,
 };
 use ic_constants::SMALL_APP_SUBNET_MAX_SIZE;
 use ic_error_types::RejectCode;
+use ic_ic00_types::{EcdsaCurve, EcdsaKeyId};
 use ic_interfaces::execution_environment::{
     CanisterOutOfCyclesError, HypervisorError, HypervisorResult, PerformanceCounterType,
     SubnetAvailableMemory, SystemApi, TrapCode,
@@ -11,30 +12,34 @@ use ic_interfaces::execution_environment::{
 use ic_logger::replica_logger::no_op_logger;
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::{
-    testing::CanisterQueuesTesting, CallOrigin, Memory, NetworkTopology, SystemState,
+    testing::CanisterQueuesTesting, CallOrigin, Memory, NetworkTopology, SubnetTopology,
+    SystemState,
 };
 use ic_system_api::{
     sandbox_safe_system_state::SandboxSafeSystemState, ApiType, DefaultOutOfInstructionsHandler,
-    NonReplicatedQueryKind, SystemApiImpl,
+    NonReplicatedQueryKind, SystemApiImpl, COST_SIGN_WITH_ECDSA_INVALID_CURVE,
+    COST_SIGN_WITH_ECDSA_SUCCESS, COST_SIGN_WITH_ECDSA_UNKNOWN_KEY,
 };
 use ic_test_utilities::{
     cycles_account_manager::CyclesAccountManagerBuilder,
     state::SystemStateBuilder,
     types::{
-        ids::{call_context_test_id, canister_test_id, subnet_test_id, user_test_id},
+        ids::{call_context_test_id, canister_test_id, node_test_id, subnet_test_id, user_test_id},
         messages::RequestBuilder,
     },
 };
 use ic_test_utilities_time::mock_time;
 use ic_types::{
     messages::{
-        CallContextId, CallbackId, RejectContext, RequestMetadata, MAX_RESPONSE_COUNT_BYTES,
+        CallContextId, CallbackId, RejectContext, RequestMetadata, RequestOrResponse,
+        MAX_RESPONSE_COUNT_BYTES, NO_DEADLINE,
     },
     methods::{Callback, WasmClosure},
-    time, CanisterTimer, CountBytes, Cycles, NumInstructions, PrincipalId, Time,
+    time, CanisterTimer, CoarseTime, CountBytes, Cycles, NumBytes, NumInstructions, PrincipalId,
+    Time,
 };
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     convert::From,
     panic::{catch_unwind, UnwindSafe},
     rc::Rc,
@@ -158,6 +163,7 @@ fn test_canister_init_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
@@ -184,6 +190,10 @@ fn test_canister_init_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -216,6 +226,7 @@ fn test_canister_update_support() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
@@ -242,6 +253,10 @@ fn test_canister_update_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -274,6 +289,7 @@ fn test_canister_replicated_query_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
@@ -300,6 +316,10 @@ fn test_canister_replicated_query_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_not_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -332,6 +352,7 @@ fn test_canister_pure_query_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
@@ -358,6 +379,10 @@ fn test_canister_pure_query_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_not_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -400,6 +425,7 @@ fn test_canister_stateful_query_support() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
@@ -426,6 +452,10 @@ fn test_canister_stateful_query_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_not_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -458,6 +488,7 @@ fn test_reply_api_support_on_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
@@ -484,6 +515,10 @@ fn test_reply_api_support_on_nns() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -516,6 +551,7 @@ fn test_reply_api_support_non_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
@@ -542,6 +578,10 @@ fn test_reply_api_support_non_nns() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -575,6 +615,7 @@ fn test_reject_api_support_on_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
@@ -601,6 +642,10 @@ fn test_reject_api_support_on_nns() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -634,6 +679,7 @@ fn test_reject_api_support_non_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
@@ -660,6 +706,10 @@ fn test_reject_api_support_non_nns() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -692,6 +742,7 @@ fn test_pre_upgrade_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
@@ -718,6 +769,10 @@ fn test_pre_upgrade_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -750,6 +805,7 @@ fn test_start_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
@@ -776,6 +832,10 @@ fn test_start_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_not_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -812,6 +872,7 @@ fn test_cleanup_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
@@ -838,6 +899,10 @@ fn test_cleanup_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -875,6 +940,7 @@ fn test_inspect_message_support() {
     assert_api_not_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_not_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_not_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_not_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_not_supported(api.ic0_call_cycles_add(0));
     assert_api_not_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_not_supported(api.ic0_call_perform());
@@ -901,6 +967,10 @@ fn test_inspect_message_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_not_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -934,6 +1004,7 @@ fn test_canister_system_task_support() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
@@ -960,6 +1031,10 @@ fn test_canister_system_task_support() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -992,6 +1067,7 @@ fn test_canister_system_task_support_nns() {
     assert_api_supported(api.ic0_call_new(0, 0, 0, 0, 0, 0, 0, 0, &[]));
     assert_api_supported(api.ic0_call_data_append(0, 0, &[]));
     assert_api_supported(api.ic0_call_on_cleanup(0, 0));
+    assert_api_supported(api.ic0_call_with_best_effort_response(0));
     assert_api_supported(api.ic0_call_cycles_add(0));
     assert_api_supported(api.ic0_call_cycles_add128(Cycles::new(0)));
     assert_api_supported(api.ic0_call_perform());
@@ -1018,6 +1094,10 @@ fn test_canister_system_task_support_nns() {
     assert_api_supported(api.ic0_is_controller(0, 0, &[]));
     assert_api_supported(api.ic0_in_replicated_execution());
     assert_api_supported(api.ic0_cycles_burn128(Cycles::zero(), 0, &mut []));
+    assert_api_supported(api.ic0_cost_call(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_create_canister(0, &mut []));
+    assert_api_supported(api.ic0_cost_http_request(0, 0, 0, &mut []));
+    assert_api_supported(api.ic0_cost_sign_with_ecdsa(0, 0, 0, 0, &mut []));
     check_stable_apis_support(api);
 }
 
@@ -1166,7 +1246,7 @@ fn test_canister_balance() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(50),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1195,7 +1275,7 @@ fn test_canister_cycle_balance() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(50),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1230,7 +1310,7 @@ fn test_msg_cycles_available_traps() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             available_cycles,
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1392,7 +1472,7 @@ fn msg_cycles_accept_all_cycles_in_call_context() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::from(amount),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1416,7 +1496,7 @@ fn msg_cycles_accept_all_cycles_in_call_context_when_more_asked() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(40),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1449,7 +1529,7 @@ fn call_perform_not_enough_cycles_does_not_trap() {
         .call_context_manager_mut()
         .unwrap()
         .new_call_context(
-            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5)),
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
             Cycles::new(40),
             Time::from_nanos_since_unix_epoch(0),
             RequestMetadata::new(0, mock_time()),
@@ -1488,6 +1568,65 @@ fn call_perform_not_enough_cycles_does_not_trap() {
     assert_eq!(call_context_manager.callbacks().len(), 0);
 }
 
+#[test]
+fn call_with_best_effort_response_sets_deadline() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new().build();
+    let mut system_state = SystemStateBuilder::new()
+        .initial_cycles(INITIAL_CYCLES)
+        .build();
+    system_state
+        .call_context_manager_mut()
+        .unwrap()
+        .new_call_context(
+            CallOrigin::CanisterUpdate(canister_test_id(33), CallbackId::from(5), NO_DEADLINE),
+            Cycles::zero(),
+            Time::from_nanos_since_unix_epoch(0),
+            RequestMetadata::new(0, mock_time()),
+        );
+    let time = Time::from_secs_since_unix_epoch(1_000).unwrap();
+    let mut api = get_system_api(
+        ApiType::update(
+            time,
+            vec![],
+            Cycles::zero(),
+            user_test_id(1).get(),
+            CallContextId::from(1),
+        ),
+        &system_state,
+        cycles_account_manager,
+    );
+    api.ic0_call_new(0, 10, 0, 10, 0, 0, 0, 0, &[0; 1024])
+        .unwrap();
+    api.ic0_call_with_best_effort_response(10).unwrap();
+    // Setting the timeout a second time traps.
+    assert!(api.ic0_call_with_best_effort_response(20).is_err());
+    assert_eq!(api.ic0_call_perform(), Ok(0));
+
+    let system_state_changes = api.into_system_state_changes();
+    system_state_changes
+        .apply_changes(
+            time,
+            &mut system_state,
+            &default_network_topology(),
+            subnet_test_id(1),
+            &no_op_logger(),
+        )
+        .unwrap();
+
+    let expected_deadline = CoarseTime::from_secs_since_unix_epoch(1_010);
+    let call_context_manager = system_state.call_context_manager().unwrap();
+    assert_eq!(call_context_manager.callbacks().len(), 1);
+    let callback = call_context_manager.callbacks().values().next().unwrap();
+    assert_eq!(callback.deadline, expected_deadline);
+
+    let canister_id = system_state.canister_id;
+    let (_, msg) = system_state.output_into_iter(canister_id).next().unwrap();
+    match msg {
+        RequestOrResponse::Request(req) => assert_eq!(req.deadline, expected_deadline),
+        RequestOrResponse::Response(_) => panic!("Expected a request, got a response"),
+    }
+}
+
 #[test]
 fn update_available_memory_updates_subnet_available_memory() {
     let wasm_page_size = 64 << 10;
@@ -1572,6 +1711,7 @@ fn push_output_request_respects_memory_limits() {
             WasmClosure::new(0, 0),
             WasmClosure::new(0, 0),
             None,
+            NO_DEADLINE,
         ))
         .unwrap();
     let mut api = SystemApiImpl::new(
@@ -1678,6 +1818,7 @@ fn push_output_request_oversized_request_memory_limits() {
             WasmClosure::new(0, 0),
             WasmClosure::new(0, 0),
             None,
+            NO_DEADLINE,
         ))
         .unwrap();
     let mut api = SystemApiImpl::new(
@@ -1862,3 +2003,153 @@ fn test_ic0_cycles_burn() {
     // There are no more cycles that can be burned.
     assert_eq!(Cycles::new(0), Cycles::from(&heap));
 }
+
+#[test]
+fn test_ic0_cost_apis() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new().build();
+    let api = get_system_api(
+        ApiTypeBuilder::build_update_api(),
+        &get_system_state(),
+        cycles_account_manager,
+    );
+    let subnet_size = SMALL_APP_SUBNET_MAX_SIZE;
+
+    let mut heap = vec![0; 16];
+    api.ic0_cost_call(10, 100, 0, &mut heap).unwrap();
+    assert_eq!(
+        Cycles::from(&heap),
+        cycles_account_manager.xnet_call_total_fee(NumBytes::new(110), subnet_size)
+            + cycles_account_manager.prepayment_for_response_transmission(subnet_size)
+            + cycles_account_manager.prepayment_for_response_execution(subnet_size)
+    );
+
+    api.ic0_cost_create_canister(0, &mut heap).unwrap();
+    assert_eq!(
+        Cycles::from(&heap),
+        cycles_account_manager.canister_creation_fee(subnet_size)
+    );
+
+    api.ic0_cost_http_request(100, 2_000, 0, &mut heap).unwrap();
+    assert_eq!(
+        Cycles::from(&heap),
+        cycles_account_manager.http_request_fee(
+            NumBytes::new(100),
+            Some(NumBytes::new(2_000)),
+            subnet_size
+        )
+    );
+
+    // The result has to fit into the heap.
+    assert!(api.ic0_cost_create_canister(1, &mut heap).is_err());
+}
+
+const ECDSA_KEY_NAME: &str = "secp256k1";
+const ECDSA_SIGNING_SUBNET_SIZE: usize = 34;
+
+/// Returns a topology in which a subnet of `ECDSA_SIGNING_SUBNET_SIZE` nodes
+/// signs with the `ECDSA_KEY_NAME` key.
+fn network_topology_with_ecdsa_key(nns_subnet_id: SubnetId) -> NetworkTopology {
+    let signing_subnet_id = subnet_test_id(42);
+    let key_id = EcdsaKeyId {
+        curve: EcdsaCurve::Secp256k1,
+        name: ECDSA_KEY_NAME.to_string(),
+    };
+    NetworkTopology {
+        nns_subnet_id,
+        subnets: BTreeMap::from([(
+            signing_subnet_id,
+            SubnetTopology {
+                nodes: (0..ECDSA_SIGNING_SUBNET_SIZE as u64)
+                    .map(node_test_id)
+                    .collect(),
+                ecdsa_keys_held: BTreeSet::from([key_id.clone()]),
+                ..SubnetTopology::default()
+            },
+        )]),
+        ecdsa_signing_subnets: BTreeMap::from([(key_id, vec![signing_subnet_id])]),
+        ..NetworkTopology::default()
+    }
+}
+
+/// Returns a heap holding the 16 byte cost followed by the key name.
+fn heap_with_ecdsa_key_name() -> Vec<u8> {
+    let mut heap = vec![0xff; 16];
+    heap.extend_from_slice(ECDSA_KEY_NAME.as_bytes());
+    heap
+}
+
+#[test]
+fn test_ic0_cost_sign_with_ecdsa_uses_the_signing_subnet_size() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new().build();
+    let network_topology = network_topology_with_ecdsa_key(subnet_test_id(1));
+    let api = get_system_api_with_network_topology(
+        ApiTypeBuilder::build_update_api(),
+        &get_system_state(),
+        cycles_account_manager,
+        &network_topology,
+    );
+
+    let mut heap = heap_with_ecdsa_key_name();
+    let size = ECDSA_KEY_NAME.len() as u32;
+    assert_eq!(
+        api.ic0_cost_sign_with_ecdsa(16, size, 0, 0, &mut heap),
+        Ok(COST_SIGN_WITH_ECDSA_SUCCESS)
+    );
+    // The signature is paid on the signing subnet, whose size differs from the
+    // size of the subnet the canister runs on.
+    assert_ne!(ECDSA_SIGNING_SUBNET_SIZE, SMALL_APP_SUBNET_MAX_SIZE);
+    assert_eq!(
+        Cycles::from(&heap[..16].to_vec()),
+        cycles_account_manager.ecdsa_signature_fee(ECDSA_SIGNING_SUBNET_SIZE)
+    );
+
+    // The result has to fit into the heap.
+    assert!(api
+        .ic0_cost_sign_with_ecdsa(16, size, 0, heap.len() as u32, &mut heap)
+        .is_err());
+}
+
+#[test]
+fn test_ic0_cost_sign_with_ecdsa_rejects_unknown_keys_and_curves() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new().build();
+    let network_topology = network_topology_with_ecdsa_key(subnet_test_id(1));
+    let api = get_system_api_with_network_topology(
+        ApiTypeBuilder::build_update_api(),
+        &get_system_state(),
+        cycles_account_manager,
+        &network_topology,
+    );
+
+    let mut heap = heap_with_ecdsa_key_name();
+    let size = ECDSA_KEY_NAME.len() as u32;
+    assert_eq!(
+        api.ic0_cost_sign_with_ecdsa(16, size, 1, 0, &mut heap),
+        Ok(COST_SIGN_WITH_ECDSA_INVALID_CURVE)
+    );
+    assert_eq!(
+        api.ic0_cost_sign_with_ecdsa(16, size - 1, 0, 0, &mut heap),
+        Ok(COST_SIGN_WITH_ECDSA_UNKNOWN_KEY)
+    );
+    // Nothing is copied when the key cannot be priced.
+    assert_eq!(&heap[..16], &[0xff; 16]);
+}
+
+#[test]
+fn test_ic0_cost_sign_with_ecdsa_is_free_on_nns_subnet() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new().build();
+    let network_topology = network_topology_with_ecdsa_key(cycles_account_manager.get_subnet_id());
+    let api = get_system_api_with_network_topology(
+        ApiTypeBuilder::build_update_api(),
+        &get_system_state(),
+        cycles_account_manager,
+        &network_topology,
+    );
+
+    let mut heap = heap_with_ecdsa_key_name();
+    let size = ECDSA_KEY_NAME.len() as u32;
+    assert_eq!(
+        api.ic0_cost_sign_with_ecdsa(16, size, 0, 0, &mut heap),
+        Ok(COST_SIGN_WITH_ECDSA_SUCCESS)
+    );
+    assert_eq!(Cycles::from(&heap[..16].to_vec()), Cycles::zero());
+}
@@ -295,13 +295,15 @@ pub fn test(env: TestEnv) {
         use LookupStatus::Found;
         let hash_tree: MixedHashTree = serde_cbor::from_slice(&data_certificate.hash_tree).unwrap();
 
+        let mut last_block_index_encoded = vec![];
+        leb128::write::unsigned(&mut last_block_index_encoded, 1).unwrap();
         assert_eq!(
             hash_tree.lookup(&[b"last_block_index"]),
-            Found(&mleaf((1_u64).to_be_bytes()))
+            Found(&mleaf(last_block_index_encoded))
         );
 
         assert_eq!(
-            hash_tree.lookup(&[b"tip_hash"]),
+            hash_tree.lookup(&[b"last_block_hash"]),
             Found(&mleaf(archived_blocks.blocks[1].hash()))
         );
 
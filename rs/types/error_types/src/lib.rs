@@ -23,6 +23,7 @@ pub enum RejectCode {
     DestinationInvalid = 3,
     CanisterReject = 4,
     CanisterError = 5,
+    SysUnknown = 6,
 }
 
 impl ToString for RejectCode {
@@ -39,6 +40,7 @@ impl RejectCode {
             RejectCode::DestinationInvalid => "DESTINATION_INVALID",
             RejectCode::CanisterReject => "CANISTER_REJECT",
             RejectCode::CanisterError => "CANISTER_ERROR",
+            RejectCode::SysUnknown => "SYS_UNKNOWN",
         }
     }
 }
@@ -52,6 +54,7 @@ impl TryFrom<u64> for RejectCode {
             3 => Ok(RejectCode::DestinationInvalid),
             4 => Ok(RejectCode::CanisterReject),
             5 => Ok(RejectCode::CanisterError),
+            6 => Ok(RejectCode::SysUnknown),
             _ => Err(TryFromError::ValueOutOfRange(code)),
         }
     }
@@ -396,4 +399,15 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn can_decode_reject_code_from_u64() {
+        for code in RejectCode::iter() {
+            let int_code = code as u64;
+            match RejectCode::try_from(int_code) {
+                Ok(decoded_code) => assert_eq!(code, decoded_code),
+                Err(err) => panic!("Could not decode {} to a RejectCode: {:?}.", int_code, err),
+            }
+        }
+    }
 }
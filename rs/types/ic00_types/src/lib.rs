@@ -269,6 +269,34 @@ impl CanisterControllersChangeRecord {
     }
 }
 
+/// `CandidType` for `CanisterLoadSnapshotRecord`
+/// ```text
+/// record {
+///   canister_version : nat64;
+///   snapshot_id : blob;
+///   taken_at_timestamp : nat64;
+/// }
+/// ```
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CanisterLoadSnapshotRecord {
+    canister_version: u64,
+    #[serde(with = "serde_bytes")]
+    snapshot_id: Vec<u8>,
+    taken_at_timestamp: u64,
+}
+
+impl CanisterLoadSnapshotRecord {
+    pub fn canister_version(&self) -> u64 {
+        self.canister_version
+    }
+    pub fn snapshot_id(&self) -> &[u8] {
+        &self.snapshot_id
+    }
+    pub fn taken_at_timestamp(&self) -> u64 {
+        self.taken_at_timestamp
+    }
+}
+
 /// `CandidType` for `CanisterChangeDetails`
 /// ```text
 /// variant {
@@ -283,6 +311,11 @@ impl CanisterControllersChangeRecord {
 ///   controllers_change : record {
 ///     controllers : vec principal;
 ///   };
+///   load_snapshot : record {
+///     canister_version : nat64;
+///     snapshot_id : blob;
+///     taken_at_timestamp : nat64;
+///   };
 /// }
 /// ```
 #[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -295,6 +328,8 @@ pub enum CanisterChangeDetails {
     CanisterCodeDeployment(CanisterCodeDeploymentRecord),
     #[serde(rename = "controllers_change")]
     CanisterControllersChange(CanisterControllersChangeRecord),
+    #[serde(rename = "load_snapshot")]
+    CanisterLoadSnapshot(CanisterLoadSnapshotRecord),
 }
 
 impl CanisterChangeDetails {
@@ -317,9 +352,21 @@ impl CanisterChangeDetails {
             controllers,
         })
     }
+
+    pub fn load_snapshot(
+        canister_version: u64,
+        snapshot_id: Vec<u8>,
+        taken_at_timestamp: u64,
+    ) -> CanisterChangeDetails {
+        CanisterChangeDetails::CanisterLoadSnapshot(CanisterLoadSnapshotRecord {
+            canister_version,
+            snapshot_id,
+            taken_at_timestamp,
+        })
+    }
 }
 
-/// Every canister change (canister creation, code uninstallation, code deployment, or controllers change) consists of
+/// Every canister change (canister creation, code uninstallation, code deployment, controllers change, or snapshot load) consists of
 ///
 /// 1. the system timestamp (in nanoseconds since Unix Epoch) at which the change was performed,
 /// 2. the canister version after performing the change,
@@ -335,6 +382,9 @@ impl CanisterChangeDetails {
 ///
 /// Controllers changes are described by the full new set of the canister controllers after the change.
 ///
+/// Snapshot loads are described by the ID of the loaded snapshot, the canister version at which
+/// it was taken, and the time at which it was taken.
+///
 /// `CandidType` for `CanisterChange`
 /// ```text
 /// record {
@@ -369,8 +419,8 @@ impl CanisterChange {
 
     /// Returns the number of bytes to represent a canister change in memory.
     /// The vector of controllers in `CanisterCreation` and `CanisterControllersChange`
-    /// is counted separately because the controllers are stored on heap
-    /// and thus not accounted for in `size_of::<CanisterChange>()`.
+    /// and the snapshot ID in `CanisterLoadSnapshot` are counted separately because
+    /// they are stored on heap and thus not accounted for in `size_of::<CanisterChange>()`.
     pub fn count_bytes(&self) -> NumBytes {
         let controllers_memory_size = match &self.details {
             CanisterChangeDetails::CanisterCreation(canister_creation) => {
@@ -379,6 +429,9 @@ impl CanisterChange {
             CanisterChangeDetails::CanisterControllersChange(canister_controllers_change) => {
                 std::mem::size_of_val(canister_controllers_change.controllers())
             }
+            CanisterChangeDetails::CanisterLoadSnapshot(canister_load_snapshot) => {
+                std::mem::size_of_val(canister_load_snapshot.snapshot_id())
+            }
             CanisterChangeDetails::CanisterCodeDeployment(_)
             | CanisterChangeDetails::CanisterCodeUninstall => 0,
         };
@@ -560,6 +613,15 @@ impl From<&CanisterChangeDetails> for pb_canister_state_bits::canister_change::C
                     },
                 )
             }
+            CanisterChangeDetails::CanisterLoadSnapshot(canister_load_snapshot) => {
+                pb_canister_state_bits::canister_change::ChangeDetails::CanisterLoadSnapshot(
+                    pb_canister_state_bits::CanisterLoadSnapshot {
+                        canister_version: canister_load_snapshot.canister_version,
+                        snapshot_id: canister_load_snapshot.snapshot_id.clone(),
+                        taken_at_timestamp: canister_load_snapshot.taken_at_timestamp,
+                    },
+                )
+            }
         }
     }
 }
@@ -603,6 +665,13 @@ impl TryFrom<pb_canister_state_bits::canister_change::ChangeDetails> for Caniste
                     .map(TryInto::try_into)
                     .collect::<Result<Vec<PrincipalId>, _>>()?,
             )),
+            pb_canister_state_bits::canister_change::ChangeDetails::CanisterLoadSnapshot(
+                canister_load_snapshot,
+            ) => Ok(CanisterChangeDetails::load_snapshot(
+                canister_load_snapshot.canister_version,
+                canister_load_snapshot.snapshot_id,
+                canister_load_snapshot.taken_at_timestamp,
+            )),
         }
     }
 }
@@ -2323,7 +2392,7 @@ impl FetchCanisterLogsRequest {
 ///     content: blob;
 /// }
 /// ```
-#[derive(Default, Clone, CandidType, Deserialize, Debug, PartialEq)]
+#[derive(Default, Clone, CandidType, Deserialize, Debug, PartialEq, Eq, Serialize)]
 pub struct CanisterLogRecord {
     pub idx: u64,
     pub timestamp_nanos: u64,
@@ -2333,6 +2402,26 @@ pub struct CanisterLogRecord {
 
 impl Payload<'_> for CanisterLogRecord {}
 
+impl From<&CanisterLogRecord> for pb_canister_state_bits::CanisterLogRecord {
+    fn from(item: &CanisterLogRecord) -> Self {
+        Self {
+            idx: item.idx,
+            timestamp_nanos: item.timestamp_nanos,
+            content: item.content.clone(),
+        }
+    }
+}
+
+impl From<pb_canister_state_bits::CanisterLogRecord> for CanisterLogRecord {
+    fn from(item: pb_canister_state_bits::CanisterLogRecord) -> Self {
+        Self {
+            idx: item.idx,
+            timestamp_nanos: item.timestamp_nanos,
+            content: item.content,
+        }
+    }
+}
+
 /// `CandidType` for `FetchCanisterLogsResponse`
 /// ```text
 /// record {
@@ -2497,3 +2586,164 @@ impl StoredChunksArgs {
 pub struct StoredChunksReply(pub Vec<serde_bytes::ByteBuf>);
 
 impl Payload<'_> for StoredChunksReply {}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     replace_snapshot: opt blob;
+/// })`
+#[derive(Default, Clone, CandidType, Deserialize, Debug)]
+pub struct TakeCanisterSnapshotArgs {
+    canister_id: PrincipalId,
+    replace_snapshot: Option<serde_bytes::ByteBuf>,
+}
+
+impl Payload<'_> for TakeCanisterSnapshotArgs {}
+
+impl TakeCanisterSnapshotArgs {
+    pub fn new(canister_id: CanisterId, replace_snapshot: Option<Vec<u8>>) -> Self {
+        Self {
+            canister_id: canister_id.get(),
+            replace_snapshot: replace_snapshot.map(serde_bytes::ByteBuf::from),
+        }
+    }
+
+    pub fn get_canister_id(&self) -> CanisterId {
+        CanisterId::unchecked_from_principal(self.canister_id)
+    }
+
+    pub fn replace_snapshot(&self) -> Option<&[u8]> {
+        self.replace_snapshot.as_deref()
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     snapshot_id: blob;
+///     sender_canister_version: opt nat64;
+/// })`
+#[derive(Default, Clone, CandidType, Deserialize, Debug)]
+pub struct LoadCanisterSnapshotArgs {
+    canister_id: PrincipalId,
+    #[serde(with = "serde_bytes")]
+    snapshot_id: Vec<u8>,
+    sender_canister_version: Option<u64>,
+}
+
+impl Payload<'_> for LoadCanisterSnapshotArgs {}
+
+impl LoadCanisterSnapshotArgs {
+    pub fn new(
+        canister_id: CanisterId,
+        snapshot_id: Vec<u8>,
+        sender_canister_version: Option<u64>,
+    ) -> Self {
+        Self {
+            canister_id: canister_id.get(),
+            snapshot_id,
+            sender_canister_version,
+        }
+    }
+
+    pub fn get_canister_id(&self) -> CanisterId {
+        CanisterId::unchecked_from_principal(self.canister_id)
+    }
+
+    pub fn snapshot_id(&self) -> &[u8] {
+        &self.snapshot_id
+    }
+
+    pub fn get_sender_canister_version(&self) -> Option<u64> {
+        self.sender_canister_version
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+/// })`
+#[derive(Default, Clone, CandidType, Deserialize, Debug)]
+pub struct ListCanisterSnapshotArgs {
+    canister_id: PrincipalId,
+}
+
+impl Payload<'_> for ListCanisterSnapshotArgs {}
+
+impl ListCanisterSnapshotArgs {
+    pub fn new(canister_id: CanisterId) -> Self {
+        Self {
+            canister_id: canister_id.get(),
+        }
+    }
+
+    pub fn get_canister_id(&self) -> CanisterId {
+        CanisterId::unchecked_from_principal(self.canister_id)
+    }
+}
+
+/// Struct used for encoding/decoding
+/// `(record {
+///     canister_id: principal;
+///     snapshot_id: blob;
+/// })`
+#[derive(Default, Clone, CandidType, Deserialize, Debug)]
+pub struct DeleteCanisterSnapshotArgs {
+    canister_id: PrincipalId,
+    #[serde(with = "serde_bytes")]
+    snapshot_id: Vec<u8>,
+}
+
+impl Payload<'_> for DeleteCanisterSnapshotArgs {}
+
+impl DeleteCanisterSnapshotArgs {
+    pub fn new(canister_id: CanisterId, snapshot_id: Vec<u8>) -> Self {
+        Self {
+            canister_id: canister_id.get(),
+            snapshot_id,
+        }
+    }
+
+    pub fn get_canister_id(&self) -> CanisterId {
+        CanisterId::unchecked_from_principal(self.canister_id)
+    }
+
+    pub fn snapshot_id(&self) -> &[u8] {
+        &self.snapshot_id
+    }
+}
+
+/// Struct to be returned when taking a canister snapshot and, as elements of
+/// a vector, when listing the snapshots of a canister.
+/// `(record {
+///     id: blob;
+///     taken_at_timestamp: nat64;
+///     total_size: nat64;
+/// })`
+#[derive(Clone, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub struct CanisterSnapshotResponse {
+    #[serde(with = "serde_bytes")]
+    pub id: Vec<u8>,
+    pub taken_at_timestamp: u64,
+    pub total_size: u64,
+}
+
+impl Payload<'_> for CanisterSnapshotResponse {}
+
+impl CanisterSnapshotResponse {
+    pub fn new(id: Vec<u8>, taken_at_timestamp: u64, total_size: u64) -> Self {
+        Self {
+            id,
+            taken_at_timestamp,
+            total_size,
+        }
+    }
+
+    pub fn snapshot_id(&self) -> &[u8] {
+        &self.id
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.total_size
+    }
+}
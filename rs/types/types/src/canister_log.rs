@@ -0,0 +1,192 @@
+//! A bounded buffer of log records produced by a canister.
+use ic_ic00_types::CanisterLogRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// The maximum allowed size of a canister log buffer.
+pub const MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE: usize = 4 * 1024;
+
+/// The size accounted for each record in addition to its content, i.e. the
+/// record index and the timestamp.
+const RECORD_OVERHEAD_SIZE: usize = 2 * std::mem::size_of::<u64>();
+
+fn record_size(record: &CanisterLogRecord) -> usize {
+    RECORD_OVERHEAD_SIZE + record.content.len()
+}
+
+/// Holds the log records of a canister in a ring buffer.
+///
+/// Once the total size of the records exceeds
+/// `MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE`, the oldest records are dropped.
+/// Record indices are assigned monotonically and never reused, so callers can
+/// detect dropped records by looking for gaps.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanisterLog {
+    next_idx: u64,
+    records: VecDeque<CanisterLogRecord>,
+    size: usize,
+}
+
+impl CanisterLog {
+    /// Creates a log from records loaded from a checkpoint.
+    pub fn new(next_idx: u64, records: Vec<CanisterLogRecord>) -> Self {
+        let mut log = Self {
+            next_idx,
+            records: VecDeque::new(),
+            size: 0,
+        };
+        for record in records {
+            log.push_record(record);
+        }
+        log
+    }
+
+    /// Returns the index that will be assigned to the next record.
+    pub fn next_idx(&self) -> u64 {
+        self.next_idx
+    }
+
+    /// Returns the records currently held in the buffer, oldest first.
+    pub fn records(&self) -> &VecDeque<CanisterLogRecord> {
+        &self.records
+    }
+
+    /// Returns the total size of the records held in the buffer.
+    pub fn used_space(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Appends a new record with the next available index.
+    pub fn add_record(&mut self, timestamp_nanos: u64, content: Vec<u8>) {
+        let record = CanisterLogRecord {
+            idx: self.next_idx,
+            timestamp_nanos,
+            content,
+        };
+        self.next_idx += 1;
+        self.push_record(record);
+    }
+
+    /// Moves all records of `delta_log` into this log, re-assigning their
+    /// indices so that they continue the sequence of this log.
+    ///
+    /// Used to merge the records produced during a single message execution,
+    /// which start from an unrelated index, into the canister's log.
+    pub fn append_delta_log(&mut self, delta_log: &mut CanisterLog) {
+        for record in std::mem::take(&mut delta_log.records) {
+            self.add_record(record.timestamp_nanos, record.content);
+        }
+        delta_log.size = 0;
+    }
+
+    /// Removes all records while keeping the index sequence.
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.size = 0;
+    }
+
+    fn push_record(&mut self, mut record: CanisterLogRecord) {
+        // A single record must always fit into the buffer.
+        let max_content_size = MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE - RECORD_OVERHEAD_SIZE;
+        record.content.truncate(max_content_size);
+        self.next_idx = self.next_idx.max(record.idx + 1);
+        self.size += record_size(&record);
+        self.records.push_back(record);
+        while self.size > MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE {
+            match self.records.pop_front() {
+                Some(oldest) => self.size -= record_size(&oldest),
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_get_consecutive_indices() {
+        let mut log = CanisterLog::default();
+        log.add_record(100, b"a".to_vec());
+        log.add_record(200, b"b".to_vec());
+
+        let records: Vec<_> = log.records().iter().cloned().collect();
+        assert_eq!(
+            records,
+            vec![
+                CanisterLogRecord {
+                    idx: 0,
+                    timestamp_nanos: 100,
+                    content: b"a".to_vec(),
+                },
+                CanisterLogRecord {
+                    idx: 1,
+                    timestamp_nanos: 200,
+                    content: b"b".to_vec(),
+                },
+            ]
+        );
+        assert_eq!(log.next_idx(), 2);
+        assert_eq!(log.used_space(), 2 * (RECORD_OVERHEAD_SIZE + 1));
+    }
+
+    #[test]
+    fn oldest_records_are_dropped_when_buffer_is_full() {
+        let mut log = CanisterLog::default();
+        let content = vec![0; 1000];
+        for i in 0..10 {
+            log.add_record(i, content.clone());
+        }
+
+        assert!(log.used_space() <= MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE);
+        assert_eq!(log.next_idx(), 10);
+        // Only the most recent records are kept.
+        let indices: Vec<_> = log.records().iter().map(|r| r.idx).collect();
+        assert_eq!(indices, vec![6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn oversized_record_is_truncated() {
+        let mut log = CanisterLog::default();
+        log.add_record(0, vec![0; 2 * MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE]);
+
+        assert_eq!(log.records().len(), 1);
+        assert_eq!(log.used_space(), MAX_ALLOWED_CANISTER_LOG_BUFFER_SIZE);
+    }
+
+    #[test]
+    fn append_delta_log_continues_index_sequence() {
+        let mut log = CanisterLog::default();
+        log.add_record(0, b"first".to_vec());
+
+        let mut delta = CanisterLog::default();
+        delta.add_record(1, b"second".to_vec());
+        delta.add_record(2, b"third".to_vec());
+        log.append_delta_log(&mut delta);
+
+        assert!(delta.is_empty());
+        assert_eq!(delta.used_space(), 0);
+        let indices: Vec<_> = log.records().iter().map(|r| r.idx).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        assert_eq!(log.next_idx(), 3);
+    }
+
+    #[test]
+    fn new_restores_next_idx() {
+        let log = CanisterLog::new(
+            7,
+            vec![CanisterLogRecord {
+                idx: 5,
+                timestamp_nanos: 0,
+                content: vec![],
+            }],
+        );
+        assert_eq!(log.next_idx(), 7);
+        assert_eq!(log.records().len(), 1);
+    }
+}
@@ -67,6 +67,7 @@ pub mod artifact;
 pub mod artifact_kind;
 pub mod batch;
 pub mod canister_http;
+pub mod canister_log;
 pub mod consensus;
 pub mod crypto;
 pub mod filetree_sync;
@@ -92,7 +93,7 @@ pub mod xnet;
 pub mod exhaustive;
 
 pub use crate::replica_version::ReplicaVersion;
-pub use crate::time::Time;
+pub use crate::time::{CoarseTime, Time};
 pub use funds::*;
 pub use ic_base_types::{
     subnet_id_into_protobuf, subnet_id_try_from_protobuf, CanisterId, CanisterIdBlobParseError,
@@ -30,7 +30,7 @@ pub use ingress_messages::{
 };
 pub use inter_canister::{
     CallContextId, CallbackId, Payload, RejectContext, Request, RequestMetadata, RequestOrResponse,
-    Response, MAX_REJECT_MESSAGE_LEN_BYTES,
+    Response, MAX_REJECT_MESSAGE_LEN_BYTES, NO_DEADLINE,
 };
 pub use message_id::{MessageId, MessageIdError, EXPECTED_MESSAGE_ID_LENGTH};
 use phantom_newtype::Id;
@@ -704,6 +704,7 @@ mod tests {
                 method_name: "method".into(),
                 method_payload: vec![0_u8, 1_u8, 2_u8, 3_u8, 4_u8, 5_u8],
                 metadata,
+                deadline: NO_DEADLINE,
             };
             let bytes = bincode::serialize(&request).unwrap();
             let request1 = bincode::deserialize::<Request>(&bytes);
@@ -719,6 +720,7 @@ mod tests {
             originator_reply_callback: CallbackId::from(100),
             refund: Cycles::from(100_000_000_u128),
             response_payload: Payload::Data(vec![0_u8, 1_u8, 2_u8, 3_u8, 4_u8, 5_u8]),
+            deadline: NO_DEADLINE,
         };
         let bytes = bincode::serialize(&response).unwrap();
         let response1 = bincode::deserialize::<Response>(&bytes);
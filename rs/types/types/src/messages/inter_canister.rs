@@ -1,10 +1,13 @@
-use crate::{ingress::WasmResult, CanisterId, CountBytes, Cycles, Funds, NumBytes, Time};
+use crate::{
+    ingress::WasmResult, CanisterId, CoarseTime, CountBytes, Cycles, Funds, NumBytes, Time,
+};
 use ic_error_types::{RejectCode, TryFromError, UserError};
 #[cfg(test)]
 use ic_exhaustive_derive::ExhaustiveSet;
 use ic_ic00_types::{
-    CanisterIdRecord, CanisterInfoRequest, ClearChunkStoreArgs, InstallChunkedCodeArgs,
-    InstallCodeArgsV2, Method, Payload as _, ProvisionalTopUpCanisterArgs, StoredChunksArgs,
+    CanisterIdRecord, CanisterInfoRequest, ClearChunkStoreArgs, DeleteCanisterSnapshotArgs,
+    InstallChunkedCodeArgs, InstallCodeArgsV2, ListCanisterSnapshotArgs, LoadCanisterSnapshotArgs,
+    Method, Payload as _, ProvisionalTopUpCanisterArgs, StoredChunksArgs, TakeCanisterSnapshotArgs,
     UpdateSettingsArgs, UploadChunkArgs,
 };
 use ic_protobuf::{
@@ -33,6 +36,9 @@ impl CountBytes for CallbackId {
     }
 }
 
+/// The deadline of guaranteed response calls.
+pub const NO_DEADLINE: CoarseTime = CoarseTime::from_secs_since_unix_epoch(0);
+
 pub enum CallContextIdTag {}
 /// Identifies an incoming call.
 pub type CallContextId = Id<CallContextIdTag, u64>;
@@ -85,9 +91,17 @@ pub struct Request {
     #[serde(with = "serde_bytes")]
     pub method_payload: Vec<u8>,
     pub metadata: Option<RequestMetadata>,
+    /// If non-zero, this is a best-effort call: its response may be dropped
+    /// and the caller gets a synthetic reject once the deadline expires.
+    pub deadline: CoarseTime,
 }
 
 impl Request {
+    /// Returns `true` if this is a best-effort call.
+    pub fn is_best_effort(&self) -> bool {
+        self.deadline != NO_DEADLINE
+    }
+
     /// Returns the sender of this `Request`.
     pub fn sender(&self) -> CanisterId {
         self.sender
@@ -162,11 +176,31 @@ impl Request {
                 Ok(record) => Some(record.get_canister_id()),
                 Err(_) => None,
             },
-            Ok(Method::DeleteChunks)
-            | Ok(Method::TakeCanisterSnapshot)
-            | Ok(Method::LoadCanisterSnapshot)
-            | Ok(Method::ListCanisterSnapshots)
-            | Ok(Method::DeleteCanisterSnapshot) => None,
+            Ok(Method::TakeCanisterSnapshot) => {
+                match TakeCanisterSnapshotArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::LoadCanisterSnapshot) => {
+                match LoadCanisterSnapshotArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::ListCanisterSnapshots) => {
+                match ListCanisterSnapshotArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::DeleteCanisterSnapshot) => {
+                match DeleteCanisterSnapshotArgs::decode(&self.method_payload) {
+                    Ok(record) => Some(record.get_canister_id()),
+                    Err(_) => None,
+                }
+            }
+            Ok(Method::DeleteChunks) => None,
             Ok(Method::CreateCanister)
             | Ok(Method::SetupInitialDKG)
             | Ok(Method::HttpRequest)
@@ -213,7 +247,8 @@ impl std::fmt::Debug for Request {
             "method_payload: [{}], ",
             truncate_and_format(&self.method_payload, 1024)
         )?;
-        write!(f, "metadata: {:?} }}", self.metadata)?;
+        write!(f, "metadata: {:?}, ", self.metadata)?;
+        write!(f, "deadline: {:?} }}", self.deadline)?;
         Ok(())
     }
 }
@@ -363,9 +398,17 @@ pub struct Response {
     pub originator_reply_callback: CallbackId,
     pub refund: Cycles,
     pub response_payload: Payload,
+    /// The deadline of the `Request` this is a response to; `NO_DEADLINE`
+    /// for guaranteed response calls.
+    pub deadline: CoarseTime,
 }
 
 impl Response {
+    /// Returns `true` if this is the response to a best-effort call.
+    pub fn is_best_effort(&self) -> bool {
+        self.deadline != NO_DEADLINE
+    }
+
     /// Returns the size in bytes of this `Response`'s payload.
     pub fn payload_size_bytes(&self) -> NumBytes {
         self.response_payload.size_bytes()
@@ -487,6 +530,7 @@ impl From<&Request> for pb_queues::Request {
             method_payload: req.method_payload.clone(),
             cycles_payment: Some((req.payment).into()),
             metadata: req.metadata.as_ref().map(From::from),
+            deadline_seconds: req.deadline.as_secs_since_unix_epoch(),
         }
     }
 }
@@ -522,6 +566,7 @@ impl TryFrom<pb_queues::Request> for Request {
             method_name: req.method_name,
             method_payload: req.method_payload,
             metadata: req.metadata.map(From::from),
+            deadline: CoarseTime::from_secs_since_unix_epoch(req.deadline_seconds),
         })
     }
 }
@@ -564,6 +609,7 @@ impl From<&Response> for pb_queues::Response {
             refund: Some((&Funds::new(rep.refund)).into()),
             response_payload: Some(p),
             cycles_refund: Some((rep.refund).into()),
+            deadline_seconds: rep.deadline.as_secs_since_unix_epoch(),
         }
     }
 }
@@ -594,6 +640,7 @@ impl TryFrom<pb_queues::Response> for Response {
             originator_reply_callback: rep.originator_reply_callback.into(),
             refund,
             response_payload,
+            deadline: CoarseTime::from_secs_since_unix_epoch(rep.deadline_seconds),
         })
     }
 }
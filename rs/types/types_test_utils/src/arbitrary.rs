@@ -8,6 +8,7 @@ use ic_types::{
     crypto::{AlgorithmId, KeyPurpose, UserPublicKey},
     messages::{
         CallbackId, Payload, RejectContext, Request, RequestMetadata, RequestOrResponse, Response,
+        NO_DEADLINE,
     },
     time::UNIX_EPOCH,
     xnet::StreamIndex,
@@ -135,6 +136,7 @@ prop_compose! {
             method_name,
             method_payload,
             metadata,
+            deadline: NO_DEADLINE,
         }
     }
 }
@@ -158,7 +160,7 @@ prop_compose! {
                 let req: CanonicalRequestV13 = (&request, certification_version).into();
                 req.try_into().unwrap()
             }
-            V14 | V15 => {
+            V14 | V15 | V16 => {
                 let req: CanonicalRequestV14 = (&request, certification_version).into();
                 req.try_into().unwrap()
             }
@@ -205,7 +207,8 @@ prop_compose! {
             respondent,
             originator_reply_callback: CallbackId::from(callback),
             refund: Cycles::from(cycles_refund),
-            response_payload
+            response_payload,
+            deadline: NO_DEADLINE,
         }
     }
 }